@@ -70,6 +70,15 @@ enum Command {
     InitRoles(Box<InitializeRoles>),
     /// Initialize callback authority.
     InitCallbackAuthority,
+    /// Add a program to the callback program allowlist.
+    RegisterCallbackProgram(CallbackProgramArgs),
+    /// Update the allowed action kinds and the compute unit limit hint of a callback program.
+    UpdateCallbackProgram(CallbackProgramArgs),
+    /// Remove a program from the callback program allowlist.
+    UnregisterCallbackProgram {
+        /// The callback program ID.
+        program_id: Pubkey,
+    },
     /// Update last restarted slot.
     UpdateLastRestartedSlot,
 }
@@ -259,6 +268,29 @@ impl super::Command for Admin {
             Command::InitCallbackAuthority => client
                 .initialize_callback_authority()
                 .into_bundle_with_options(options)?,
+            Command::RegisterCallbackProgram(args) => {
+                let (rpc, callback_program) = client
+                    .register_callback_program(
+                        store,
+                        &args.program_id,
+                        args.action_kinds(),
+                        args.compute_unit_limit,
+                    )
+                    .swap_output(());
+                println!("{callback_program}");
+                rpc.into_bundle_with_options(options)?
+            }
+            Command::UpdateCallbackProgram(args) => client
+                .update_callback_program(
+                    store,
+                    &args.program_id,
+                    args.action_kinds(),
+                    args.compute_unit_limit,
+                )
+                .into_bundle_with_options(options)?,
+            Command::UnregisterCallbackProgram { program_id } => client
+                .unregister_callback_program(store, program_id)
+                .into_bundle_with_options(options)?,
             Command::UpdateLastRestartedSlot => client
                 .update_last_restarted_slot(store)
                 .into_bundle_with_options(options)?,
@@ -269,6 +301,38 @@ impl super::Command for Admin {
     }
 }
 
+#[derive(Debug, clap::Args)]
+struct CallbackProgramArgs {
+    /// The callback program ID.
+    program_id: Pubkey,
+    /// The action kinds the program is allowed to receive callbacks for.
+    #[arg(long, value_delimiter = ',', required = true)]
+    action_kinds: Vec<CallbackActionKind>,
+    /// The hint for the compute unit limit required by the callback.
+    #[arg(long, default_value_t = 0)]
+    compute_unit_limit: u32,
+}
+
+impl CallbackProgramArgs {
+    fn action_kinds(&self) -> u8 {
+        self.action_kinds
+            .iter()
+            .fold(0, |bitmap, kind| bitmap | (1 << (*kind as u8)))
+    }
+}
+
+/// Action kinds, in the same order as the `ActionKind` of the callback interface.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CallbackActionKind {
+    Deposit,
+    Withdrawal,
+    Shift,
+    Order,
+    GlvDeposit,
+    GlvWithdrawal,
+    GlvShift,
+}
+
 #[derive(Debug, clap::Args)]
 struct InitializeRoles {
     #[arg(long)]
@@ -2,10 +2,19 @@ use std::time::Duration;
 
 use eyre::OptionExt;
 use gmsol_sdk::{
-    ops::competition::{CompetitionOps, CompetitionParams},
-    programs::anchor_lang::prelude::Pubkey,
+    constants::MARKET_DECIMALS,
+    ops::competition::{participant_score, CompetitionOps, CompetitionParams},
+    programs::{
+        anchor_lang::prelude::Pubkey,
+        gmsol_competition::{
+            accounts::{Competition as CompetitionAccount, PrizePool},
+            constants::ROI_DECIMALS,
+            types::ScoringMode,
+        },
+    },
     utils::Value,
 };
+use rust_decimal::Decimal;
 use time::OffsetDateTime;
 
 /// Competition management commands.
@@ -33,6 +42,8 @@ enum Command {
         only_count_increase: bool,
         #[arg(long, value_parser = humantime::parse_duration)]
         volume_merge_window: Duration,
+        #[arg(long, value_enum, default_value_t = Scoring::Volume)]
+        scoring_mode: Scoring,
     },
     /// Fetch a competition.
     Get { address: Pubkey },
@@ -41,9 +52,94 @@ enum Command {
         #[arg(long)]
         competition: Pubkey,
     },
+    /// List the standings of a competition.
+    Standings {
+        competition: Pubkey,
+        /// Rank the participants by their current volume instead of
+        /// reading the final standings.
+        #[arg(long)]
+        live: bool,
+    },
+    /// Rank the participants into the final standings after the competition ends
+    /// and finalize the standings.
+    Rank {
+        competition: Pubkey,
+        #[arg(long)]
+        chunk_size: Option<usize>,
+    },
+    /// Migrate a competition and its participants from the legacy account layouts.
+    Migrate { competition: Pubkey },
+    /// Initialize the prize pool of a competition.
+    InitPrizePool {
+        competition: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        token_program_id: Option<Pubkey>,
+        /// Share of the prize pool (in basis points) for each rank, starting from the first place.
+        #[arg(long, value_delimiter = ',', required = true)]
+        schedule: Vec<u16>,
+    },
+    /// Fund the prize pool of a competition.
+    FundPrizePool {
+        competition: Pubkey,
+        /// Amount in the smallest unit of the prize token.
+        amount: u64,
+        #[arg(long)]
+        source: Option<Pubkey>,
+        #[arg(long)]
+        token_program_id: Option<Pubkey>,
+    },
+    /// Pay the prizes to the ranked participants.
+    Distribute {
+        competition: Pubkey,
+        #[arg(long)]
+        token_program_id: Option<Pubkey>,
+        #[arg(long)]
+        chunk_size: Option<usize>,
+    },
+    /// Reclaim the remaining prizes after the distribution is completed.
+    ReclaimPrizes {
+        competition: Pubkey,
+        #[arg(long)]
+        receiver: Option<Pubkey>,
+        #[arg(long)]
+        token_program_id: Option<Pubkey>,
+    },
+}
+
+/// Scoring mode.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Scoring {
+    /// Rank by traded volume.
+    Volume,
+    /// Rank by realized PnL net of fees.
+    Pnl,
+    /// Rank by realized PnL net of fees over the collateral value put in.
+    Roi,
+}
+
+impl From<Scoring> for ScoringMode {
+    fn from(scoring: Scoring) -> Self {
+        match scoring {
+            Scoring::Volume => Self::Volume,
+            Scoring::Pnl => Self::Pnl,
+            Scoring::Roi => Self::Roi,
+        }
+    }
+}
+
+fn format_score(score: i128, mode: &ScoringMode) -> String {
+    let scale = match mode {
+        ScoringMode::Volume | ScoringMode::Pnl => MARKET_DECIMALS,
+        ScoringMode::Roi => ROI_DECIMALS,
+    };
+    Decimal::try_from_i128_with_scale(score, scale.into())
+        .map(|score| score.normalize().to_string())
+        .unwrap_or_else(|_| score.to_string())
 }
 
-fn parse_datetime(s: &str) -> Result<OffsetDateTime, time::error::Parse> {
+pub(crate) fn parse_datetime(s: &str) -> Result<OffsetDateTime, time::error::Parse> {
     use time::format_description::well_known::Rfc3339;
 
     OffsetDateTime::parse(s, &Rfc3339)
@@ -66,6 +162,7 @@ impl super::Command for Competition {
                 extension_cap,
                 only_count_increase,
                 volume_merge_window,
+                scoring_mode,
             } => {
                 let (tx, competition) = client
                     .initialize_competition(
@@ -77,6 +174,7 @@ impl super::Command for Competition {
                             .extension_cap(extension_cap.as_secs().try_into()?)
                             .only_count_increase(*only_count_increase)
                             .volume_merge_window(volume_merge_window.as_secs().try_into()?)
+                            .scoring_mode((*scoring_mode).into())
                             .build(),
                     )
                     .swap_output(());
@@ -97,9 +195,133 @@ impl super::Command for Competition {
             Command::CloseParticipant { competition } => client
                 .close_participant(competition)
                 .into_bundle_with_options(options)?,
+            Command::Standings { competition, live } => {
+                let account = client
+                    .account::<CompetitionAccount>(competition)
+                    .await?
+                    .ok_or_eyre("competition not found")?;
+                let mode = account.scoring_mode;
+                let standings = if *live {
+                    client
+                        .competition_participants(competition)
+                        .await?
+                        .into_iter()
+                        .map(|(_, participant)| {
+                            let score = participant_score(&participant, &mode);
+                            (participant.trader, participant.volume, score)
+                        })
+                        .collect::<Vec<_>>()
+                } else {
+                    if account.ranked_count < account.participant_count {
+                        println!(
+                            "Final standings are incomplete: {}/{} ranked",
+                            account.ranked_count, account.participant_count
+                        );
+                    }
+                    client
+                        .competition_standings(competition)
+                        .await?
+                        .into_iter()
+                        .map(|entry| (entry.address, entry.volume, entry.score))
+                        .collect()
+                };
+                for (idx, (trader, volume, score)) in standings.iter().enumerate() {
+                    println!(
+                        "{:>4} {trader} volume={} score={}",
+                        idx + 1,
+                        Value::from_u128(*volume),
+                        format_score(*score, &mode)
+                    );
+                }
+                return Ok(());
+            }
+            Command::Rank {
+                competition,
+                chunk_size,
+            } => client.rank_competition(competition, *chunk_size).await?,
+            Command::Migrate { competition } => {
+                client.migrate_competition_accounts(competition).await?
+            }
+            Command::InitPrizePool {
+                competition,
+                mint,
+                token_program_id,
+                schedule,
+            } => {
+                let (tx, prize_pool) = client
+                    .initialize_prize_pool(
+                        competition,
+                        mint,
+                        token_program_id.as_ref(),
+                        schedule.clone(),
+                    )
+                    .swap_output(());
+                println!("{prize_pool}");
+                tx.into_bundle_with_options(options)?
+            }
+            Command::FundPrizePool {
+                competition,
+                amount,
+                source,
+                token_program_id,
+            } => {
+                let prize_pool = fetch_prize_pool(client, competition).await?;
+                client
+                    .fund_prize_pool(
+                        competition,
+                        &prize_pool.mint,
+                        token_program_id.as_ref(),
+                        source.as_ref(),
+                        *amount,
+                    )
+                    .into_bundle_with_options(options)?
+            }
+            Command::Distribute {
+                competition,
+                token_program_id,
+                chunk_size,
+            } => {
+                client
+                    .distribute_competition_prizes(
+                        competition,
+                        token_program_id.as_ref(),
+                        *chunk_size,
+                    )
+                    .await?
+            }
+            Command::ReclaimPrizes {
+                competition,
+                receiver,
+                token_program_id,
+            } => {
+                let prize_pool = fetch_prize_pool(client, competition).await?;
+                client
+                    .reclaim_prizes(
+                        competition,
+                        &prize_pool.mint,
+                        token_program_id.as_ref(),
+                        receiver.as_ref(),
+                    )
+                    .into_bundle_with_options(options)?
+            }
         };
 
         client.send_or_serialize(bundle).await?;
         Ok(())
     }
 }
+
+async fn fetch_prize_pool(
+    client: &super::CommandClient,
+    competition: &Pubkey,
+) -> eyre::Result<PrizePool> {
+    let address = gmsol_sdk::pda::find_prize_pool_address(
+        competition,
+        &gmsol_sdk::programs::gmsol_competition::ID,
+    )
+    .0;
+    client
+        .account::<PrizePool>(&address)
+        .await?
+        .ok_or_eyre("prize pool not found")
+}
@@ -4,25 +4,31 @@ pub(crate) mod executor;
 use std::{
     collections::{HashMap, HashSet},
     ops::Deref,
+    time::Duration,
 };
 
 use clap::ArgGroup;
 use eyre::OptionExt;
 use gmsol_sdk::{
     builders::{token::WrapNative, NonceBytes},
+    client::token_map::TokenMap,
     constants::MARKET_DECIMALS,
-    core::{market::MarketMeta, order::OrderKind},
+    core::{
+        market::MarketMeta,
+        order::{OrderKind, TrailingDistance},
+    },
     decode::gmsol::programs::GMSOLAccountData,
     model::PositionStateExt,
     ops::{
         exchange::{deposit, glv_deposit, glv_shift, glv_withdrawal, shift, withdrawal},
         AddressLookupTableOps, ExchangeOps,
     },
+    position::status::PositionStatus,
     programs::{
         anchor_lang::prelude::Pubkey,
         gmsol_store::{
             accounts::Market,
-            types::{DecreasePositionSwapType, UpdateOrderParams},
+            types::{DecreasePositionSwapType, UpdateOrderOptions, UpdateOrderParams},
         },
     },
     serde::{serde_market::SerdeMarket, serde_position::SerdePosition, StringPubkey},
@@ -37,7 +43,9 @@ use gmsol_sdk::{
 use indexmap::IndexMap;
 
 use crate::{
-    commands::utils::{get_token_amount_with_token_map, token_amount, unit_price},
+    commands::utils::{
+        get_token_amount_with_token_map, price_from_unit_price, token_amount, unit_price,
+    },
     config::DisplayOptions,
 };
 
@@ -55,6 +63,9 @@ pub struct Exchange {
     /// Skips wrapping the native token when enabled.
     #[arg(long)]
     skip_native_wrap: bool,
+    /// Create, update or close orders as a delegate of the given owner.
+    #[arg(long)]
+    delegate_of: Option<Pubkey>,
     /// Commands.
     #[command(subcommand)]
     command: Command,
@@ -255,6 +266,9 @@ enum Command {
         /// Provide this to participate in a competition.
         #[arg(long)]
         competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
         /// Provide to only prepare position for the order.
         #[arg(long)]
         prepare_position_only: bool,
@@ -301,6 +315,9 @@ enum Command {
         /// Provide this to participate in a competition.
         #[arg(long)]
         competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
         #[command(flatten)]
         should_keep_position: ShouldKeepPosition,
     },
@@ -338,8 +355,13 @@ enum Command {
         /// Provide this to participate in a competition.
         #[arg(long)]
         competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
         #[command(flatten)]
         should_keep_position: ShouldKeepPosition,
+        #[command(flatten)]
+        twap: Twap,
     },
     /// Create a limit decrese order.
     LimitDecrease {
@@ -378,6 +400,9 @@ enum Command {
         /// Provide this to participate in a competition.
         #[arg(long)]
         competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
         /// Valid from this timestamp.
         #[arg(long)]
         valid_from_ts: Option<humantime::Timestamp>,
@@ -420,17 +445,108 @@ enum Command {
         /// Provide this to participate in a competition.
         #[arg(long)]
         competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
         /// Valid from this timestamp.
         #[arg(long)]
         valid_from_ts: Option<humantime::Timestamp>,
         #[command(flatten)]
         should_keep_position: ShouldKeepPosition,
     },
-    /// Update a limit or stop-loss order.
+    /// Create a trailing-stop decrease order.
+    #[command(group(ArgGroup::new("trailing").required(true)))]
+    TrailingStop {
+        /// The address of the market token of the position's market.
+        market_token: Pubkey,
+        /// Whether the collateral is long token.
+        #[arg(long)]
+        collateral_side: Side,
+        /// Collateral withdrawal amount.
+        #[arg(long, short = 'a', default_value_t = Amount::ZERO)]
+        collateral_withdrawal_amount: Amount,
+        /// Position side.
+        #[arg(long)]
+        side: Side,
+        /// Initial best price.
+        #[arg(long)]
+        price: Value,
+        /// Trailing distance in price.
+        #[arg(long, group = "trailing")]
+        trailing_distance: Option<Value>,
+        /// Trailing distance as a factor of the best price (e.g. `0.05` for 5%).
+        #[arg(long, group = "trailing")]
+        trailing_factor: Option<Value>,
+        /// Acceptable price.
+        #[arg(long)]
+        acceptable_price: Option<Value>,
+        #[arg(long, default_value_t = Value::ZERO)]
+        size: Value,
+        /// Final output token.
+        #[arg(long, short = 'c')]
+        final_output_token: Option<Pubkey>,
+        /// Min output value.
+        #[arg(long)]
+        min_output: Option<Value>,
+        /// Swap paths for output token (collateral token).
+        #[arg(long, short, action = clap::ArgAction::Append)]
+        swap: Vec<Pubkey>,
+        /// Provide this to participate in a competition.
+        #[arg(long)]
+        competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
+        /// Valid from this timestamp.
+        #[arg(long)]
+        valid_from_ts: Option<humantime::Timestamp>,
+        #[command(flatten)]
+        should_keep_position: ShouldKeepPosition,
+    },
+    /// Create a linked take-profit / stop-loss bracket for a position.
+    /// Executing one of the orders cancels the other.
+    Bracket {
+        /// The address of the market token of the position's market.
+        market_token: Pubkey,
+        /// Whether the collateral is long token.
+        #[arg(long)]
+        collateral_side: Side,
+        /// Position side.
+        #[arg(long)]
+        side: Side,
+        /// Take-profit trigger price.
+        #[arg(long)]
+        take_profit: Value,
+        /// Stop-loss trigger price.
+        #[arg(long)]
+        stop_loss: Value,
+        /// Size to decrease. Defaults to the full position.
+        #[arg(long, default_value_t = Value::ZERO)]
+        size: Value,
+        /// Final output token.
+        #[arg(long, short = 'c')]
+        final_output_token: Option<Pubkey>,
+        /// Swap paths for output token (collateral token).
+        #[arg(long, short, action = clap::ArgAction::Append)]
+        swap: Vec<Pubkey>,
+        /// Provide this to participate in a competition.
+        #[arg(long)]
+        competition: Option<Pubkey>,
+        /// Provide this to participate in a GT incentive campaign.
+        #[arg(long, conflicts_with = "competition")]
+        gt_campaign: Option<Pubkey>,
+    },
+    /// Link orders, so that executing one of them cancels the others.
+    LinkOrders {
+        /// The orders to link.
+        #[arg(required = true, num_args = 2..)]
+        orders: Vec<Pubkey>,
+    },
+    /// Update a limit, stop-loss or trailing-stop order.
     UpdateOrder {
         /// The address of the swap order to update.
         address: Pubkey,
-        /// New Tigger price.
+        /// New Tigger price, or the new best price for trailing-stop orders.
         #[arg(long)]
         price: Option<Value>,
         /// Acceptable price.
@@ -445,6 +561,12 @@ enum Command {
         /// Valid from this timestamp.
         #[arg(long)]
         valid_from_ts: Option<humantime::Timestamp>,
+        /// New trailing distance in price (trailing-stop orders only).
+        #[arg(long, conflicts_with = "trailing_factor")]
+        trailing_distance: Option<Value>,
+        /// New trailing distance as a factor of the best price (trailing-stop orders only).
+        #[arg(long)]
+        trailing_factor: Option<Value>,
         #[command(flatten)]
         should_keep_position: ShouldKeepPosition,
     },
@@ -471,6 +593,8 @@ enum Command {
         /// Min output amount.
         #[arg(long)]
         min_output_amount: Option<Amount>,
+        #[command(flatten)]
+        twap: Twap,
     },
     /// Create a limit swap order.
     LimitSwap {
@@ -547,6 +671,14 @@ enum Command {
         #[arg(long, short)]
         side: Option<Side>,
     },
+    /// Update the best price of a trailing-stop order.
+    /// Requires appropriate permissions.
+    #[cfg(feature = "execute")]
+    UpdateTrailingStop {
+        #[command(flatten)]
+        args: executor::ExecutorArgs,
+        order: Pubkey,
+    },
     /// Update the closed state for the given market.
     /// Requires appropriate permissions.
     #[cfg(feature = "execute")]
@@ -555,6 +687,15 @@ enum Command {
         args: executor::ExecutorArgs,
         market_token: Pubkey,
     },
+    /// Update the price circuit breakers of the given tokens.
+    /// Requires appropriate permissions.
+    #[cfg(feature = "execute")]
+    UpdatePriceCircuitBreakers {
+        #[command(flatten)]
+        args: executor::ExecutorArgs,
+        #[arg(required = true)]
+        tokens: Vec<Pubkey>,
+    },
     /// Close a profitable position when ADL is enabled.
     #[cfg(feature = "execute")]
     Adl {
@@ -748,6 +889,25 @@ impl ShouldKeepPosition {
     }
 }
 
+#[derive(Debug, clap::Args, Default)]
+pub(crate) struct Twap {
+    /// Execute the order in the given number of TWAP slices.
+    #[arg(long, requires = "twap_interval", value_parser = clap::value_parser!(u8).range(2..=64))]
+    twap_slices: Option<u8>,
+    /// The minimum interval between two TWAP slices.
+    #[arg(long, requires = "twap_slices", value_parser = humantime::parse_duration)]
+    twap_interval: Option<Duration>,
+}
+
+impl Twap {
+    pub(crate) fn params(&self) -> eyre::Result<Option<(u8, u32)>> {
+        match (self.twap_slices, self.twap_interval) {
+            (Some(slices), Some(interval)) => Ok(Some((slices, interval.as_secs().try_into()?))),
+            _ => Ok(None),
+        }
+    }
+}
+
 impl super::Command for Exchange {
     fn is_client_required(&self) -> bool {
         true
@@ -755,6 +915,7 @@ impl super::Command for Exchange {
 
     async fn execute(&self, ctx: super::Context<'_>) -> eyre::Result<()> {
         let nonce = self.nonce.map(|nonce| nonce.to_bytes());
+        let delegate_of = self.delegate_of;
         let store = ctx.store();
         let client = ctx.client()?;
         let mut token_map = match &self.command {
@@ -776,8 +937,11 @@ impl super::Command for Exchange {
             Command::UpdateOrder {
                 price,
                 acceptable_price,
+                trailing_distance,
                 ..
-            } if price.is_none() && acceptable_price.is_none() => None,
+            } if price.is_none() && acceptable_price.is_none() && trailing_distance.is_none() => {
+                None
+            }
             Command::UpdateSwap { price, .. } if price.is_none() => None,
             Command::Glv { command, .. } => match command {
                 GlvCommand::CloseDeposit { .. }
@@ -803,7 +967,9 @@ impl super::Command for Exchange {
             _ => Some(client.authorized_token_map(store).await?),
         };
         let options = ctx.bundle_options();
-        let mut collector = (!self.skip_native_wrap).then(NativeCollector::default);
+        // Native tokens can only be wrapped into the payer's own account.
+        let mut collector =
+            (!self.skip_native_wrap && delegate_of.is_none()).then(NativeCollector::default);
         let owner = &client.payer();
         let output = ctx.config().output();
         let bundle = match &self.command {
@@ -852,6 +1018,7 @@ impl super::Command for Exchange {
                                 ("is_adl_enabled_for_long", "Is ADL Enabled (Long)"),
                                 ("is_adl_enabled_for_short", "Is ADL Enabled (Short)"),
                                 ("is_gt_minting_enabled", "Is GT Minting Enabled"),
+                                ("trading_mode", "Trading Mode"),
                                 ("state.long_token_balance", "◎ Long Token"),
                                 ("state.short_token_balance", "◎ Short Token"),
                                 ("state.funding_factor_per_second", "Funding Factor"),
@@ -956,31 +1123,74 @@ impl super::Command for Exchange {
                         .filter(|p| *include_empty || p.state.size_in_usd != 0)
                         .map(|p| p.market_token)
                         .collect::<HashSet<_>>();
-                    let mut market_metas = HashMap::<_, _>::default();
+                    let mut markets = HashMap::<_, _>::default();
                     for market_token in market_tokens {
                         let market = client
                             .market(&client.find_market_address(store, &market_token))
                             .await?;
-                        market_metas.insert(market_token, MarketMeta::from(market.meta));
+                        markets.insert(market_token, market);
                     }
+                    #[cfg(feature = "pyth")]
+                    let prices = {
+                        let hermes = gmsol_sdk::client::pyth::Hermes::default();
+                        let mut prices = HashMap::<_, _>::default();
+                        for (market_token, market) in markets.iter() {
+                            match hermes.unit_prices_for_market(token_map, &**market).await {
+                                Ok(market_prices) => {
+                                    prices.insert(*market_token, market_prices);
+                                }
+                                Err(err) => {
+                                    tracing::warn!(%market_token, %err, "failed to fetch prices");
+                                }
+                            }
+                        }
+                        prices
+                    };
                     let mut positions = positions
                         .iter()
                         .filter(|(_, p)| *include_empty || p.state.size_in_usd != 0)
                         .map(|(k, p)| {
+                            let market = markets.get(&p.market_token).unwrap();
+                            let position = SerdePosition::from_position(
+                                p,
+                                &MarketMeta::from(market.meta),
+                                token_map,
+                            )?;
+                            #[cfg(feature = "pyth")]
+                            let status = prices
+                                .get(&p.market_token)
+                                .filter(|_| p.state.size_in_usd != 0)
+                                .map(|prices| -> eyre::Result<_> {
+                                    use gmsol_sdk::{
+                                        model::{MarketModel, PositionModel},
+                                        position::PositionCalculations,
+                                    };
+
+                                    // The market token supply is irrelevant to the position status.
+                                    let market = MarketModel::from_parts(market.clone(), 1);
+                                    let position =
+                                        PositionModel::new(market, std::sync::Arc::new(*p))?;
+                                    Ok(position.status(prices)?)
+                                })
+                                .transpose()?;
+                            #[cfg(not(feature = "pyth"))]
+                            let status = None;
                             Ok((
                                 *k,
-                                SerdePosition::from_position(
-                                    p,
-                                    market_metas.get(&p.market_token).unwrap(),
-                                    token_map,
-                                )?,
+                                PositionWithStatus::new(position, status, market, token_map)?,
                             ))
                         })
                         .collect::<eyre::Result<IndexMap<_, _>>>()?;
                     positions.sort_by(|_, a, _, b| {
-                        a.state.size_in_usd.cmp(&b.state.size_in_usd).reverse()
+                        a.position
+                            .state
+                            .size_in_usd
+                            .cmp(&b.position.state.size_in_usd)
+                            .reverse()
+                    });
+                    positions.sort_by(|_, a, _, b| {
+                        a.position.market_token.cmp(&b.position.market_token)
                     });
-                    positions.sort_by(|_, a, _, b| a.market_token.cmp(&b.market_token));
                     let output = output.display_keyed_accounts(
                         positions,
                         DisplayOptions::table_projection([
@@ -990,6 +1200,8 @@ impl super::Command for Exchange {
                             ("is_collateral_long_token", "Is Collateral Long"),
                             ("state.collateral_amount", "Collateral Amount"),
                             ("state.size_in_usd", "Size($)"),
+                            ("liquidation_price", "Liq. Price($)"),
+                            ("margin_health", "Margin Health"),
                             ("state.trade_id", "Last Trade ID"),
                         ])
                         .set_empty_message("No Positions"),
@@ -1229,6 +1441,7 @@ impl super::Command for Exchange {
             } => client
                 .close_order(order)?
                 .skip_callback(*skip_callabck)
+                .by_delegate(delegate_of.is_some())
                 .build()
                 .await?
                 .into_bundle_with_options(options)?,
@@ -1243,6 +1456,7 @@ impl super::Command for Exchange {
                 swap,
                 wait,
                 competition,
+                gt_campaign,
                 min_collateral_amount,
                 acceptable_price,
                 prepare_position_only,
@@ -1280,6 +1494,9 @@ impl super::Command for Exchange {
                 if let Some(nonce) = nonce {
                     builder.nonce(nonce);
                 }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
                 if let Some(token) = initial_collateral_token {
                     builder
                         .initial_collateral_token(token, initial_collateral_token_account.as_ref());
@@ -1299,6 +1516,9 @@ impl super::Command for Exchange {
                 if let Some(competition) = competition {
                     builder.competition(competition);
                 }
+                if let Some(campaign) = gt_campaign {
+                    builder.gt_incentive_campaign(campaign);
+                }
 
                 if *prepare_position_only {
                     let (rpc, position) = builder.build_prepare_position().await?.swap_output(());
@@ -1355,6 +1575,7 @@ impl super::Command for Exchange {
                 swap,
                 wait,
                 competition,
+                gt_campaign,
                 min_collateral_amount,
                 acceptable_price,
                 should_keep_position,
@@ -1393,6 +1614,9 @@ impl super::Command for Exchange {
                 if let Some(nonce) = nonce {
                     builder.nonce(nonce);
                 }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
                 if let Some(token) = initial_collateral_token {
                     builder
                         .initial_collateral_token(token, initial_collateral_token_account.as_ref());
@@ -1401,6 +1625,9 @@ impl super::Command for Exchange {
                 if let Some(competition) = competition {
                     builder.competition(competition);
                 }
+                if let Some(campaign) = gt_campaign {
+                    builder.gt_incentive_campaign(campaign);
+                }
 
                 if let Some(amount) = min_collateral_amount {
                     builder.min_output_amount(
@@ -1456,9 +1683,11 @@ impl super::Command for Exchange {
                 swap,
                 wait,
                 competition,
+                gt_campaign,
                 min_output,
                 acceptable_price,
                 should_keep_position,
+                twap,
             } => {
                 let is_collateral_token_long = collateral_side.is_long();
                 let market = if token_map.is_some() {
@@ -1490,6 +1719,9 @@ impl super::Command for Exchange {
                 if let Some(nonce) = nonce {
                     builder.nonce(nonce);
                 }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
                 if let Some(token) = final_output_token {
                     builder.final_output_token(token);
                 }
@@ -1498,6 +1730,9 @@ impl super::Command for Exchange {
                 if let Some(competition) = competition {
                     builder.competition(competition);
                 }
+                if let Some(campaign) = gt_campaign {
+                    builder.gt_incentive_campaign(campaign);
+                }
 
                 if let Some(value) = min_output {
                     builder.min_output_amount(value.to_u128()?);
@@ -1509,6 +1744,9 @@ impl super::Command for Exchange {
                         market.as_ref().expect("must exist"),
                     )?);
                 }
+                if let Some((slices, interval)) = twap.params()? {
+                    builder.twap(slices, interval);
+                }
 
                 let (rpc, order) = builder
                     .decrease_position_swap_type(Some(
@@ -1551,6 +1789,7 @@ impl super::Command for Exchange {
                 swap,
                 wait,
                 competition,
+                gt_campaign,
                 min_output,
                 acceptable_price,
                 valid_from_ts,
@@ -1567,6 +1806,7 @@ impl super::Command for Exchange {
                 swap,
                 wait,
                 competition,
+                gt_campaign,
                 min_output,
                 acceptable_price,
                 valid_from_ts,
@@ -1608,12 +1848,18 @@ impl super::Command for Exchange {
                 if let Some(nonce) = nonce {
                     builder.nonce(nonce);
                 }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
                 if let Some(token) = final_output_token {
                     builder.final_output_token(token);
                 }
                 if let Some(competition) = competition {
                     builder.competition(competition);
                 }
+                if let Some(campaign) = gt_campaign {
+                    builder.gt_incentive_campaign(campaign);
+                }
                 if let Some(value) = min_output {
                     builder.min_output_amount(value.to_u128()?);
                 }
@@ -1654,6 +1900,170 @@ impl super::Command for Exchange {
 
                 bundle
             }
+            Command::TrailingStop {
+                market_token,
+                collateral_side,
+                collateral_withdrawal_amount,
+                side,
+                price,
+                trailing_distance,
+                trailing_factor,
+                acceptable_price,
+                size,
+                final_output_token,
+                min_output,
+                swap,
+                competition,
+                gt_campaign,
+                valid_from_ts,
+                should_keep_position,
+            } => {
+                let market_address = client.find_market_address(store, market_token);
+                let market = client.market(&market_address).await?;
+                let token_map = token_map.as_ref().expect("must exist");
+                let price = unit_price(price, token_map, &market)?;
+                let distance = match (trailing_distance, trailing_factor) {
+                    (Some(distance), _) => {
+                        TrailingDistance::Absolute(unit_price(distance, token_map, &market)?)
+                    }
+                    (None, Some(factor)) => TrailingDistance::Factor(factor.to_u128()?),
+                    (None, None) => unreachable!("guaranteed by the arg group"),
+                };
+                let is_collateral_token_long = collateral_side.is_long();
+                let collateral_withdrawal_amount = token_amount(
+                    collateral_withdrawal_amount,
+                    final_output_token.as_ref(),
+                    token_map,
+                    &market,
+                    is_collateral_token_long,
+                )?;
+                let mut builder = client.trailing_stop(
+                    store,
+                    market_token,
+                    side.is_long(),
+                    size.to_u128()?,
+                    price,
+                    distance,
+                    is_collateral_token_long,
+                    collateral_withdrawal_amount,
+                );
+                if let Some(nonce) = nonce {
+                    builder.nonce(nonce);
+                }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
+                if let Some(token) = final_output_token {
+                    builder.final_output_token(token);
+                }
+                if let Some(competition) = competition {
+                    builder.competition(competition);
+                }
+                if let Some(campaign) = gt_campaign {
+                    builder.gt_incentive_campaign(campaign);
+                }
+                if let Some(value) = min_output {
+                    builder.min_output_amount(value.to_u128()?);
+                }
+                if let Some(price) = acceptable_price {
+                    builder.acceptable_price(unit_price(price, token_map, &market)?);
+                }
+                if let Some(ts) = valid_from_ts {
+                    builder.valid_from_ts(to_unix_timestamp(ts)?);
+                }
+
+                let (rpc, order) = builder
+                    .swap_path(swap.clone())
+                    .decrease_position_swap_type(Some(
+                        DecreasePositionSwapType::PnlTokenToCollateralToken,
+                    ))
+                    .build_with_address()
+                    .await?;
+                println!("Order: {order}");
+
+                let mut bundle = rpc.into_bundle_with_options(options)?;
+
+                if let Some(keep) = should_keep_position.should_keep_position() {
+                    let txn = client.set_should_keep_position_account(store, &order, keep)?;
+                    bundle.push(txn)?;
+                }
+
+                bundle
+            }
+            Command::Bracket {
+                market_token,
+                collateral_side,
+                side,
+                take_profit,
+                stop_loss,
+                size,
+                final_output_token,
+                swap,
+                competition,
+                gt_campaign,
+            } => {
+                if delegate_of.is_some() {
+                    eyre::bail!("linking orders is not supported for delegates");
+                }
+                let market_address = client.find_market_address(store, market_token);
+                let market = client.market(&market_address).await?;
+                let token_map = token_map.as_ref().expect("must exist");
+                let take_profit = unit_price(take_profit, token_map, &market)?;
+                let stop_loss = unit_price(stop_loss, token_map, &market)?;
+                let is_collateral_token_long = collateral_side.is_long();
+                let size = size.to_u128()?;
+
+                let mut take_profit_builder = client.limit_decrease(
+                    store,
+                    market_token,
+                    side.is_long(),
+                    size,
+                    take_profit,
+                    is_collateral_token_long,
+                    0,
+                );
+                let mut stop_loss_builder = client.stop_loss(
+                    store,
+                    market_token,
+                    side.is_long(),
+                    size,
+                    stop_loss,
+                    is_collateral_token_long,
+                    0,
+                );
+
+                let mut bundle = client.bundle_with_options(options);
+                let mut orders = Vec::with_capacity(2);
+                for builder in [&mut take_profit_builder, &mut stop_loss_builder] {
+                    if let Some(token) = final_output_token {
+                        builder.final_output_token(token);
+                    }
+                    if let Some(competition) = competition {
+                        builder.competition(competition);
+                    }
+                    if let Some(campaign) = gt_campaign {
+                        builder.gt_incentive_campaign(campaign);
+                    }
+                    let (rpc, order) = builder
+                        .swap_path(swap.clone())
+                        .decrease_position_swap_type(Some(
+                            DecreasePositionSwapType::PnlTokenToCollateralToken,
+                        ))
+                        .build_with_address()
+                        .await?;
+                    bundle.push(rpc)?;
+                    orders.push(order);
+                }
+                bundle.push(client.link_orders(store, &orders)?)?;
+
+                println!("Take-profit order: {}", orders[0]);
+                println!("Stop-loss order: {}", orders[1]);
+
+                bundle
+            }
+            Command::LinkOrders { orders } => client
+                .link_orders(store, orders)?
+                .into_bundle_with_options(options)?,
             Command::UpdateOrder {
                 address,
                 price,
@@ -1661,6 +2071,8 @@ impl super::Command for Exchange {
                 min_output,
                 size,
                 valid_from_ts,
+                trailing_distance,
+                trailing_factor,
                 should_keep_position,
             } => {
                 let order = client.order(address).await?;
@@ -1672,7 +2084,9 @@ impl super::Command for Exchange {
                     None
                 };
                 let min_output = match kind {
-                    OrderKind::LimitDecrease | OrderKind::StopLossDecrease => min_output
+                    OrderKind::LimitDecrease
+                    | OrderKind::StopLossDecrease
+                    | OrderKind::TrailingStopDecrease => min_output
                         .as_ref()
                         .map(|value| value.to_u128(MARKET_DECIMALS))
                         .transpose()?,
@@ -1702,6 +2116,21 @@ impl super::Command for Exchange {
                         eyre::bail!("{:?} is not updatable", kind);
                     }
                 };
+                let price = price
+                    .as_ref()
+                    .map(|price| {
+                        unit_price(
+                            price,
+                            token_map.as_ref().expect("must exist"),
+                            market.as_ref().expect("must exist"),
+                        )
+                    })
+                    .transpose()?;
+                let (trigger_price, best_price) = if kind.is_trailing_stop() {
+                    (None, price)
+                } else {
+                    (price, None)
+                };
                 let params = UpdateOrderParams {
                     size_delta_value: size.as_ref().map(|s| s.to_u128()).transpose()?,
                     acceptable_price: acceptable_price
@@ -1714,26 +2143,54 @@ impl super::Command for Exchange {
                             )
                         })
                         .transpose()?,
-                    trigger_price: price
-                        .as_ref()
-                        .map(|price| {
-                            unit_price(
-                                price,
-                                token_map.as_ref().expect("must exist"),
-                                market.as_ref().expect("must exist"),
-                            )
-                        })
-                        .transpose()?,
+                    trigger_price,
                     min_output,
                     valid_from_ts: valid_from_ts.as_ref().map(to_unix_timestamp).transpose()?,
                 };
+                let update_options = UpdateOrderOptions {
+                    trailing_distance: match (trailing_distance, trailing_factor) {
+                        (Some(distance), _) => Some(TrailingDistance::Absolute(unit_price(
+                            distance,
+                            token_map.as_ref().expect("must exist"),
+                            market.as_ref().expect("must exist"),
+                        )?)),
+                        (None, Some(factor)) => Some(TrailingDistance::Factor(factor.to_u128()?)),
+                        (None, None) => None,
+                    }
+                    .map(Into::into),
+                    best_price,
+                };
 
                 let mut bundle = client.bundle_with_options(options);
 
-                if !params.is_empty() {
-                    let update = client
-                        .update_order(store, &order.market_token, address, params, None)
-                        .await?;
+                if !(params.is_empty() && update_options.is_empty()) {
+                    let update = match delegate_of {
+                        Some(owner) => {
+                            client
+                                .update_order_by_delegate(
+                                    store,
+                                    &owner,
+                                    &order.market_token,
+                                    address,
+                                    params,
+                                    update_options,
+                                    None,
+                                )
+                                .await?
+                        }
+                        None => {
+                            client
+                                .update_order_with_options(
+                                    store,
+                                    &order.market_token,
+                                    address,
+                                    params,
+                                    update_options,
+                                    None,
+                                )
+                                .await?
+                        }
+                    };
 
                     bundle.push(update)?;
                 }
@@ -1759,6 +2216,7 @@ impl super::Command for Exchange {
                 initial_swap_in_token_amount,
                 swap,
                 min_output_amount,
+                twap,
             } => {
                 let token_map = token_map.as_ref().expect("must exist");
                 let initial_swap_in_token_amount = get_token_amount_with_token_map(
@@ -1786,6 +2244,9 @@ impl super::Command for Exchange {
                 if let Some(nonce) = nonce {
                     builder.nonce(nonce);
                 }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
                 if let Some(account) = initial_swap_in_token_account {
                     builder.initial_collateral_token(initial_swap_in_token, Some(account));
                 }
@@ -1798,6 +2259,9 @@ impl super::Command for Exchange {
                             .into(),
                     );
                 }
+                if let Some((slices, interval)) = twap.params()? {
+                    builder.twap(slices, interval);
+                }
 
                 let (rpc, order) = builder.build_with_address().await?;
 
@@ -1863,6 +2327,9 @@ impl super::Command for Exchange {
                 if let Some(nonce) = nonce {
                     builder.nonce(nonce);
                 }
+                if let Some(owner) = delegate_of {
+                    builder.delegate_of(owner);
+                }
                 if let Some(account) = initial_swap_in_token_account {
                     builder.initial_collateral_token(initial_swap_in_token, Some(account));
                 }
@@ -1931,10 +2398,30 @@ impl super::Command for Exchange {
                     valid_from_ts: valid_from_ts.as_ref().map(to_unix_timestamp).transpose()?,
                 };
 
-                client
-                    .update_order(store, &order.market_token, address, params, None)
-                    .await?
-                    .into_bundle_with_options(options)?
+                let update = match delegate_of {
+                    Some(owner) => {
+                        client
+                            .update_order_by_delegate(
+                                store,
+                                &owner,
+                                &order.market_token,
+                                address,
+                                params,
+                                UpdateOrderOptions {
+                                    trailing_distance: None,
+                                    best_price: None,
+                                },
+                                None,
+                            )
+                            .await?
+                    }
+                    None => {
+                        client
+                            .update_order(store, &order.market_token, address, params, None)
+                            .await?
+                    }
+                };
+                update.into_bundle_with_options(options)?
             }
             Command::CancelOrderIfNoPosition { order, keep } => {
                 let cancel = client
@@ -2296,6 +2783,17 @@ impl super::Command for Exchange {
                 return Ok(());
             }
             #[cfg(feature = "execute")]
+            Command::UpdateTrailingStop { args, order } => {
+                ctx.require_not_serialize_only_mode()?;
+                ctx.require_not_ix_buffer_mode()?;
+
+                let executor = args.build(client).await?;
+                let oracle = ctx.config().oracle()?;
+                let builder = client.update_trailing_stop_order(store, oracle, order);
+                executor.execute(builder, options).await?;
+                return Ok(());
+            }
+            #[cfg(feature = "execute")]
             Command::UpdateClosedState { args, market_token } => {
                 ctx.require_not_serialize_only_mode()?;
                 ctx.require_not_ix_buffer_mode()?;
@@ -2307,6 +2805,19 @@ impl super::Command for Exchange {
                 return Ok(());
             }
             #[cfg(feature = "execute")]
+            Command::UpdatePriceCircuitBreakers { args, tokens } => {
+                use gmsol_sdk::ops::OracleOps;
+
+                ctx.require_not_serialize_only_mode()?;
+                ctx.require_not_ix_buffer_mode()?;
+
+                let executor = args.build(client).await?;
+                let oracle = ctx.config().oracle()?;
+                let builder = client.update_price_circuit_breakers(store, oracle, tokens.clone());
+                executor.execute(builder, options).await?;
+                return Ok(());
+            }
+            #[cfg(feature = "execute")]
             Command::Adl {
                 args,
                 position,
@@ -2536,6 +3047,40 @@ impl NativeCollector {
     }
 }
 
+/// A position with its status calculated at the latest prices.
+#[derive(serde::Serialize)]
+struct PositionWithStatus {
+    #[serde(flatten)]
+    position: SerdePosition,
+    liquidation_price: Option<Value>,
+    margin_health: Option<Value>,
+}
+
+impl PositionWithStatus {
+    fn new(
+        position: SerdePosition,
+        status: Option<PositionStatus>,
+        market: &Market,
+        token_map: &TokenMap,
+    ) -> eyre::Result<Self> {
+        let (liquidation_price, margin_health) = match status {
+            Some(status) => (
+                status
+                    .liquidation_price
+                    .map(|price| price_from_unit_price(price, token_map, market))
+                    .transpose()?,
+                status.margin_health.map(Value::from_u128),
+            ),
+            None => (None, None),
+        };
+        Ok(Self {
+            position,
+            liquidation_price,
+            margin_health,
+        })
+    }
+}
+
 fn to_unix_timestamp(ts: &humantime::Timestamp) -> eyre::Result<i64> {
     use std::time::SystemTime;
 
@@ -2551,6 +3096,7 @@ pub(crate) fn display_options_for_markets() -> DisplayOptions {
         ("meta.market_token", "Market Token"),
         ("enabled", "Is Enabled"),
         ("is_closed", "Is Closed"),
+        ("trading_mode", "Trading Mode"),
         ("state.long_token_balance", "◎ Long Token"),
         ("state.short_token_balance", "◎ Short Token"),
         ("pools.claimable_fee.long_amount", "◎ Claimable Long Token"),
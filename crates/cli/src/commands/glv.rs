@@ -79,6 +79,15 @@ enum Command {
         #[clap(required = true)]
         market_tokens: Vec<Pubkey>,
     },
+    /// Plan the GLV shifts to rebalance the GLV towards the given targets,
+    /// and create them unless `--dry-run` is specified.
+    #[cfg(feature = "pyth")]
+    Rebalance {
+        #[command(flatten)]
+        glv_token: GlvToken,
+        #[command(flatten)]
+        args: RebalanceArgs,
+    },
 }
 
 impl super::Command for Glv {
@@ -278,6 +287,41 @@ impl super::Command for Glv {
 
                 bundle
             }
+            #[cfg(feature = "pyth")]
+            Command::Rebalance {
+                glv_token: selected,
+                args,
+            } => {
+                use gmsol_sdk::ops::{exchange::glv_shift, ExchangeOps};
+
+                let glv_token = selected.address(client, store);
+                let plan = args.plan(client, store, &glv_token).await?;
+                display_rebalance_plan(&ctx, &plan)?;
+
+                if args.dry_run || plan.steps.is_empty() {
+                    return Ok(());
+                }
+
+                let mut bundle = client.bundle_with_options(options);
+                for step in plan.steps.iter() {
+                    let mut builder = client.create_glv_shift(
+                        store,
+                        &glv_token,
+                        &step.from_market_token,
+                        &step.to_market_token,
+                        step.from_market_token_amount,
+                    );
+                    builder
+                        .execution_fee(
+                            args.extra_execution_fee.to_u64()? + glv_shift::MIN_EXECUTION_LAMPORTS,
+                        )
+                        .min_to_market_token_amount(step.min_to_market_token_amount);
+                    let (rpc, shift) = builder.build_with_address()?;
+                    println!("GLV shift: {shift}");
+                    bundle.push(rpc)?;
+                }
+                bundle
+            }
         };
 
         client.send_or_serialize(bundle).await?;
@@ -422,6 +466,259 @@ struct UpdateGlv {
     market: IndexMap<StringPubkey, MarketConfigWithFlag>,
 }
 
+#[cfg(feature = "pyth")]
+#[derive(Debug, clap::Args)]
+struct RebalanceArgs {
+    /// Path to the rebalancing targets file (TOML).
+    ///
+    /// Each market is given either a `weight` or a `max_exposure` as a share of
+    /// the GLV value. For example: `[targets.<market_token>]` with `weight = "0.6"`.
+    #[arg(long, short)]
+    file: PathBuf,
+    /// Slippage applied to the simulated output of each shift.
+    #[arg(long, default_value = "0.005")]
+    slippage: Value,
+    /// Max number of shifts to create.
+    #[arg(long, default_value_t = 10)]
+    max_shifts: usize,
+    /// Differences below this value are left as is.
+    #[arg(long, default_value_t = Value::ZERO)]
+    min_shift_value: Value,
+    /// Extra execution fee allowed to use for each shift.
+    #[arg(long, short, default_value_t = gmsol_sdk::utils::Lamport::ZERO)]
+    extra_execution_fee: gmsol_sdk::utils::Lamport,
+    /// Only display the plan without creating the shifts.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[cfg(feature = "pyth")]
+impl RebalanceArgs {
+    async fn plan(
+        &self,
+        client: &crate::CommandClient,
+        store: &Pubkey,
+        glv_token: &Pubkey,
+    ) -> eyre::Result<gmsol_sdk::glv::rebalance::RebalancePlan> {
+        use gmsol_sdk::glv::rebalance::{GlvRebalancePlanner, RebalanceOptions};
+
+        let targets = toml_from_file::<RebalanceTargets>(&self.file)?
+            .targets
+            .into_iter()
+            .map(|(market_token, target)| Ok((*market_token, target.try_into()?)))
+            .collect::<eyre::Result<BTreeMap<_, _>>>()?;
+        let simulator = load_simulator(client, store, glv_token).await?;
+        let plan = GlvRebalancePlanner::builder()
+            .simulator(&simulator)
+            .glv_token(glv_token)
+            .targets(&targets)
+            .options(RebalanceOptions {
+                slippage: self.slippage.to_u128()?,
+                max_shifts: self.max_shifts,
+                min_shift_value: self.min_shift_value.to_u128()?,
+                simulation: Default::default(),
+            })
+            .build()
+            .plan()?;
+        Ok(plan)
+    }
+}
+
+/// Load the GLV and its markets into a simulator, with the latest prices from Pyth.
+#[cfg(feature = "pyth")]
+async fn load_simulator(
+    client: &crate::CommandClient,
+    store: &Pubkey,
+    glv_token: &Pubkey,
+) -> eyre::Result<gmsol_sdk::simulation::Simulator> {
+    use std::{collections::HashMap, sync::Arc};
+
+    use anchor_spl::token_interface::Mint;
+    use gmsol_sdk::{
+        client::pyth::Hermes,
+        glv::GlvModel,
+        model::{MarketModel, VirtualInventoryModel},
+        programs::gmsol_store::accounts::VirtualInventory,
+        simulation::{Simulator, TokenState},
+    };
+
+    let glv = client
+        .account::<ZeroCopy<gmsol_sdk::programs::gmsol_store::accounts::Glv>>(
+            &client.find_glv_address(glv_token),
+        )
+        .await?
+        .ok_or_eyre("GLV not found")?
+        .0;
+    let supply = client
+        .account::<Mint>(glv_token)
+        .await?
+        .ok_or_eyre("GLV token not found")?
+        .supply;
+
+    let token_map = client.authorized_token_map(store).await?;
+    let hermes = Hermes::default();
+    let mut tokens = HashMap::default();
+    let mut markets = HashMap::default();
+    let mut vis = BTreeMap::default();
+    for market_token in glv.market_tokens() {
+        let market = client
+            .market(&client.find_market_address(store, &market_token))
+            .await?;
+        let market_token_supply = client
+            .account::<Mint>(&market_token)
+            .await?
+            .ok_or_eyre("market token not found")?
+            .supply;
+        let prices = hermes.unit_prices_for_market(&token_map, &*market).await?;
+        let meta = &market.meta;
+        for (token, price) in [
+            (meta.index_token_mint, prices.index_token_price),
+            (meta.long_token_mint, prices.long_token_price),
+            (meta.short_token_mint, prices.short_token_price),
+        ] {
+            tokens.insert(token, TokenState::from_price(Some(Arc::new(price))));
+        }
+        for address in [
+            market.virtual_inventory_for_swaps,
+            market.virtual_inventory_for_positions,
+        ] {
+            if address == Pubkey::default() || vis.contains_key(&address) {
+                continue;
+            }
+            let vi = client
+                .account::<ZeroCopy<VirtualInventory>>(&address)
+                .await?
+                .ok_or_eyre("virtual inventory not found")?;
+            vis.insert(address, VirtualInventoryModel::from_parts(Arc::new(vi.0)));
+        }
+        markets.insert(
+            market_token,
+            MarketModel::from_parts(market, market_token_supply),
+        );
+    }
+
+    let glvs = HashMap::from([(*glv_token, GlvModel::new(Arc::new(glv), supply))]);
+    Ok(Simulator::from_parts(tokens, markets, glvs, vis))
+}
+
+#[cfg(feature = "pyth")]
+fn display_rebalance_plan(
+    ctx: &super::Context<'_>,
+    plan: &gmsol_sdk::glv::rebalance::RebalancePlan,
+) -> eyre::Result<()> {
+    use gmsol_sdk::glv::rebalance::RebalanceTarget;
+
+    let output = ctx.config().output();
+
+    let markets = plan.markets.iter().map(|market| {
+        let (weight, max_exposure) = match market.target {
+            Some(RebalanceTarget::Weight(factor)) => (Some(Value::from_u128(factor)), None),
+            Some(RebalanceTarget::MaxExposure(factor)) => (None, Some(Value::from_u128(factor))),
+            None => (None, None),
+        };
+        serde_json::json!({
+            "market_token": market.market_token,
+            "target_weight": weight,
+            "max_exposure": max_exposure,
+            "target_value": Value::from_u128(market.target_value),
+            "value_before": Value::from_u128(market.value_before),
+            "weight_before": Value::from_u128(market.weight_before),
+            "value_after": Value::from_u128(market.value_after),
+            "weight_after": Value::from_u128(market.weight_after),
+        })
+    });
+    println!(
+        "{}",
+        output.display_many(
+            markets,
+            DisplayOptions::table_projection([
+                ("market_token", "Market Token"),
+                ("target_weight", "Target Weight"),
+                ("max_exposure", "Max Exposure"),
+                ("target_value", "$ Target"),
+                ("value_before", "$ Before"),
+                ("weight_before", "Weight Before"),
+                ("value_after", "$ After"),
+                ("weight_after", "Weight After"),
+            ]),
+        )?
+    );
+
+    let steps = plan.steps.iter().enumerate().map(|(idx, step)| {
+        serde_json::json!({
+            "step": idx,
+            "from_market_token": step.from_market_token,
+            "to_market_token": step.to_market_token,
+            "from_market_token_amount": GmAmount::from_u64(step.from_market_token_amount),
+            "to_market_token_amount": GmAmount::from_u64(step.to_market_token_amount),
+            "min_to_market_token_amount": GmAmount::from_u64(step.min_to_market_token_amount),
+            "from_value": Value::from_u128(step.from_value),
+            "to_value": Value::from_u128(step.to_value),
+            "price_impact_factor": Value::from_u128(step.price_impact_factor),
+            "executable_after": step.executable_after,
+        })
+    });
+    println!(
+        "{}",
+        output.display_many(
+            steps,
+            DisplayOptions::table_projection([
+                ("step", "Step"),
+                ("from_market_token", "From"),
+                ("to_market_token", "To"),
+                ("from_market_token_amount", "Amount"),
+                ("to_market_token_amount", "Est. Output"),
+                ("min_to_market_token_amount", "Min Output"),
+                ("from_value", "$ From"),
+                ("to_value", "$ To"),
+                ("price_impact_factor", "Price Impact"),
+                ("executable_after", "Executable After"),
+            ])
+            .set_empty_message("No shifts are needed."),
+        )?
+    );
+
+    println!(
+        "GLV value: {} -> {}",
+        Value::from_u128(plan.total_value_before),
+        Value::from_u128(plan.total_value_after),
+    );
+    if plan.unresolved_value != 0 {
+        println!(
+            "Unresolved value above the targets: {}",
+            Value::from_u128(plan.unresolved_value)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "pyth")]
+#[derive(Debug, serde::Deserialize)]
+struct RebalanceTargets {
+    targets: IndexMap<StringPubkey, RebalanceTargetConfig>,
+}
+
+#[cfg(feature = "pyth")]
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RebalanceTargetConfig {
+    Weight(Value),
+    MaxExposure(Value),
+}
+
+#[cfg(feature = "pyth")]
+impl TryFrom<RebalanceTargetConfig> for gmsol_sdk::glv::rebalance::RebalanceTarget {
+    type Error = gmsol_sdk::Error;
+
+    fn try_from(config: RebalanceTargetConfig) -> Result<Self, Self::Error> {
+        Ok(match config {
+            RebalanceTargetConfig::Weight(factor) => Self::Weight(factor.to_u128()?),
+            RebalanceTargetConfig::MaxExposure(factor) => Self::MaxExposure(factor.to_u128()?),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,4 +735,34 @@ mod tests {
         let expected = 1_200_000_000u64;
         assert_eq!(params.min_tokens_for_first_deposit, Some(expected));
     }
+
+    #[cfg(feature = "pyth")]
+    #[test]
+    fn test_rebalance_targets_parsing() {
+        use gmsol_sdk::{constants::MARKET_USD_UNIT, glv::rebalance::RebalanceTarget};
+
+        let toml_content = r#"
+            [targets.BwN2FWixP5JyKjJNyD1YcRKN1XhgvFtnzrPrkfyb4DkW]
+            weight = "0.6"
+
+            [targets.2dVHXNgzC7vvcsDi89S6crSkX3Y6HzPgZfmWqttEzvmo]
+            max_exposure = "0.1"
+        "#;
+
+        let targets: RebalanceTargets = toml::from_str(toml_content).expect("Failed to parse TOML");
+        let targets = targets
+            .targets
+            .into_values()
+            .map(RebalanceTarget::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to convert");
+
+        assert_eq!(
+            targets,
+            [
+                RebalanceTarget::Weight(MARKET_USD_UNIT * 6 / 10),
+                RebalanceTarget::MaxExposure(MARKET_USD_UNIT / 10),
+            ]
+        );
+    }
 }
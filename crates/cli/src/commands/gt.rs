@@ -4,10 +4,12 @@ use gmsol_sdk::{
     core::{gt::GtBankFlags, pubkey::optional_address, token_config::TokenMapAccess},
     ops::{
         gt::{current_time_window_index, GtOps},
+        gt_incentive::{GtIncentiveCampaignParams, GtIncentiveOps},
         treasury::TreasuryOps,
     },
     programs::{
         anchor_lang::prelude::Pubkey,
+        gmsol_gt_incentive::accounts::{Campaign, Participant},
         gmsol_store::accounts::GtExchangeVault,
         gmsol_treasury::accounts::{Config, GtBank},
     },
@@ -16,6 +18,9 @@ use gmsol_sdk::{
     utils::{unsigned_amount_to_decimal, zero_copy::ZeroCopy, Amount, Value},
 };
 use std::{num::NonZeroU32, ops::Deref};
+use time::OffsetDateTime;
+
+use super::competition::parse_datetime;
 
 /// GT-related commands.
 #[derive(Debug, clap::Args)]
@@ -97,6 +102,37 @@ enum Command {
         #[arg(long)]
         owner: Pubkey,
     },
+    /// Initialize the GT incentive config for the store. Only the store admin can initialize.
+    InitIncentive {
+        /// The maximum total GT rewards that can be funded to the campaigns.
+        #[arg(long)]
+        reward_budget: Amount,
+    },
+    /// Set the GT reward budget of the GT incentive config. Only the store admin can set.
+    SetIncentiveBudget { reward_budget: Amount },
+    /// Create a GT incentive campaign.
+    CreateCampaign {
+        #[arg(long, value_parser = parse_datetime)]
+        start: OffsetDateTime,
+        #[arg(long, value_parser = parse_datetime)]
+        end: OffsetDateTime,
+        /// Whether to only count volume from position increases.
+        #[arg(long)]
+        only_count_increase: bool,
+    },
+    /// Fund a GT incentive campaign with GT rewards.
+    FundCampaign { campaign: Pubkey, amount: Amount },
+    /// Fetch a GT incentive campaign.
+    Campaign {
+        address: Pubkey,
+        /// Show the participant of the given trader.
+        #[arg(long)]
+        trader: Option<Pubkey>,
+    },
+    /// Claim GT rewards from a campaign.
+    ClaimReward { campaign: Pubkey },
+    /// Close the participant account associated to the given campaign.
+    CloseParticipant { campaign: Pubkey },
 }
 
 impl super::Command for Gt {
@@ -358,6 +394,87 @@ impl super::Command for Gt {
                 }
                 client.mint_gt_reward(store, owner, amount.to_u64(decimals)?)?
             }
+            Command::InitIncentive { reward_budget } => {
+                let decimals = client.store(store).await?.gt.decimals;
+                let (txn, config) = client
+                    .initialize_gt_incentive(store, reward_budget.to_u64(decimals)?)
+                    .swap_output(());
+                println!("{config}");
+                txn
+            }
+            Command::SetIncentiveBudget { reward_budget } => {
+                let decimals = client.store(store).await?.gt.decimals;
+                client.set_gt_incentive_reward_budget(store, reward_budget.to_u64(decimals)?)
+            }
+            Command::CreateCampaign {
+                start,
+                end,
+                only_count_increase,
+            } => {
+                let (txn, campaign) = client
+                    .initialize_gt_incentive_campaign(
+                        store,
+                        &GtIncentiveCampaignParams::builder()
+                            .start_time(start.unix_timestamp())
+                            .end_time(end.unix_timestamp())
+                            .only_count_increase(*only_count_increase)
+                            .build(),
+                    )
+                    .swap_output(());
+                println!("{campaign}");
+                txn
+            }
+            Command::FundCampaign { campaign, amount } => {
+                let decimals = client.store(store).await?.gt.decimals;
+                if amount.is_zero() {
+                    eyre::bail!("the fund amount cannot be zero");
+                }
+                client.fund_gt_incentive_campaign(store, campaign, amount.to_u64(decimals)?)
+            }
+            Command::Campaign { address, trader } => {
+                let decimals = client.store(store).await?.gt.decimals;
+                let campaign = client
+                    .account::<Campaign>(address)
+                    .await?
+                    .ok_or_eyre("campaign not found")?;
+                println!("Start Time: {}", campaign.start_time);
+                println!("End Time: {}", campaign.end_time);
+                println!("Only Count Increase: {}", campaign.only_count_increase);
+                println!(
+                    "Total Rewards: {}",
+                    Amount::from_u64(campaign.total_rewards, decimals)
+                );
+                println!(
+                    "Claimed Rewards: {}",
+                    Amount::from_u64(campaign.claimed_rewards, decimals)
+                );
+                println!("Total Volume: {}", Value::from_u128(campaign.total_volume));
+                if let Some(trader) = trader {
+                    let participant = gmsol_sdk::pda::find_gt_incentive_participant_address(
+                        address,
+                        trader,
+                        &gmsol_sdk::programs::gmsol_gt_incentive::ID,
+                    )
+                    .0;
+                    let participant = client
+                        .account::<Participant>(&participant)
+                        .await?
+                        .ok_or_eyre("participant not found")?;
+                    println!("[Participant]");
+                    println!("Volume: {}", Value::from_u128(participant.volume));
+                    println!(
+                        "Claimed Rewards: {}",
+                        Amount::from_u64(participant.claimed_rewards, decimals)
+                    );
+                }
+                return Ok(());
+            }
+            Command::ClaimReward { campaign } => {
+                client.claim_gt_incentive_reward(store, campaign)?
+            }
+            Command::CloseParticipant { campaign } => {
+                client.close_gt_incentive_participant(campaign)
+            }
         };
 
         let bundle = txn.into_bundle_with_options(options)?;
@@ -189,7 +189,9 @@ impl super::Command for Inspect {
                     let account = CompetitionAccount::try_from_bytes(&account.data)?;
                     match account {
                         CompetitionAccount::Competition(a) => println!("{a:#?}"),
+                        CompetitionAccount::LeaderboardPage(a) => println!("{a:#?}"),
                         CompetitionAccount::Participant(a) => println!("{a:#?}"),
+                        CompetitionAccount::PrizePool(a) => println!("{a:#?}"),
                         CompetitionAccount::TradeData(a) => println!("{a:#?}"),
                     }
                 }
@@ -221,7 +223,7 @@ impl super::Command for Inspect {
             Command::Tld { raw, addresses } => {
                 use gmsol_sdk::{
                     core::instruction::{InstructionAccess, InstructionFlag},
-                    programs::gmsol_timelock::accounts::TimelockConfig,
+                    programs::gmsol_timelock::accounts::{Executor, TimelockConfig},
                     solana_utils::{
                         solana_sdk::message::{Message, VersionedMessage},
                         utils::inspect_transaction,
@@ -230,13 +232,11 @@ impl super::Command for Inspect {
                 };
 
                 let config = client.find_timelock_config_address(store);
-                let delay = client
+                let config = client
                     .account::<ZeroCopy<TimelockConfig>>(&config)
                     .await?
                     .ok_or(gmsol_sdk::Error::NotFound)?
-                    .0
-                    .delay;
-                let delay = time::Duration::seconds(delay as i64);
+                    .0;
 
                 let mut instructions = Vec::with_capacity(addresses.len());
 
@@ -247,6 +247,13 @@ impl super::Command for Inspect {
                         .ok_or(gmsol_sdk::Error::NotFound)?;
 
                     let status = if buffer.header.flags.get_flag(InstructionFlag::Approved) {
+                        let executor = client
+                            .account::<ZeroCopy<Executor>>(&buffer.header.executor)
+                            .await?
+                            .ok_or(gmsol_sdk::Error::NotFound)?
+                            .0;
+                        let delay =
+                            time::Duration::seconds(buffer.delay(&config, &executor).into());
                         let approved_at =
                             time::OffsetDateTime::from_unix_timestamp(buffer.header.approved_at)
                                 .map_err(gmsol_sdk::Error::custom)?;
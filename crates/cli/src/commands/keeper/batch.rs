@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use gmsol_sdk::{
+    client::{
+        feeds_parser::FeedAddressMap,
+        ops::exchange::{
+            deposit::ExecuteDepositBuilder,
+            glv_deposit::ExecuteGlvDepositBuilder,
+            glv_shift::ExecuteGlvShiftBuilder,
+            glv_withdrawal::ExecuteGlvWithdrawalBuilder,
+            order::{
+                ExecuteOrderBuilder, PositionCutBuilder, UpdateAdlBuilder,
+                UpdateTrailingStopBuilder,
+            },
+            shift::ExecuteShiftBuilder,
+            withdrawal::ExecuteWithdrawalBuilder,
+        },
+        pull_oracle::{FeedIds, PullOraclePriceConsumer},
+    },
+    core::{
+        oracle::PriceProviderKind,
+        token_config::{TokenRecord, TokensWithFeed},
+    },
+    programs::anchor_lang::prelude::Pubkey,
+    solana_utils::{
+        bundle_builder::{BundleBuilder, BundleOptions},
+        make_bundle_builder::{MakeBundleBuilder, SetExecutionFee},
+        signer::LocalSignerRef,
+    },
+};
+
+/// A builder for a keeper task.
+pub(super) enum TaskBuilder<'a> {
+    Deposit(ExecuteDepositBuilder<'a, LocalSignerRef>),
+    Withdrawal(ExecuteWithdrawalBuilder<'a, LocalSignerRef>),
+    Shift(ExecuteShiftBuilder<'a, LocalSignerRef>),
+    Order(ExecuteOrderBuilder<'a, LocalSignerRef>),
+    GlvDeposit(ExecuteGlvDepositBuilder<'a, LocalSignerRef>),
+    GlvWithdrawal(ExecuteGlvWithdrawalBuilder<'a, LocalSignerRef>),
+    GlvShift(ExecuteGlvShiftBuilder<'a, LocalSignerRef>),
+    PositionCut(PositionCutBuilder<'a, LocalSignerRef>),
+    UpdateAdl(UpdateAdlBuilder<'a, LocalSignerRef>),
+    UpdateTrailingStop(UpdateTrailingStopBuilder<'a, LocalSignerRef>),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $builder:ident => $expr:expr) => {
+        match $self {
+            Self::Deposit($builder) => $expr,
+            Self::Withdrawal($builder) => $expr,
+            Self::Shift($builder) => $expr,
+            Self::Order($builder) => $expr,
+            Self::GlvDeposit($builder) => $expr,
+            Self::GlvWithdrawal($builder) => $expr,
+            Self::GlvShift($builder) => $expr,
+            Self::PositionCut($builder) => $expr,
+            Self::UpdateAdl($builder) => $expr,
+            Self::UpdateTrailingStop($builder) => $expr,
+        }
+    };
+}
+
+impl<'a> MakeBundleBuilder<'a, LocalSignerRef> for TaskBuilder<'a> {
+    async fn build_with_options(
+        &mut self,
+        options: BundleOptions,
+    ) -> gmsol_sdk::solana_utils::Result<BundleBuilder<'a, LocalSignerRef>> {
+        dispatch!(self, builder => builder.build_with_options(options).await)
+    }
+}
+
+impl PullOraclePriceConsumer for TaskBuilder<'_> {
+    async fn feed_ids(&mut self) -> gmsol_sdk::Result<FeedIds> {
+        dispatch!(self, builder => builder.feed_ids().await)
+    }
+
+    fn process_feeds(
+        &mut self,
+        provider: PriceProviderKind,
+        map: FeedAddressMap,
+    ) -> gmsol_sdk::Result<()> {
+        dispatch!(self, builder => builder.process_feeds(provider, map))
+    }
+}
+
+impl SetExecutionFee for TaskBuilder<'_> {
+    fn is_execution_fee_estimation_required(&self) -> bool {
+        dispatch!(self, builder => builder.is_execution_fee_estimation_required())
+    }
+
+    fn set_execution_fee(&mut self, lamports: u64) -> &mut Self {
+        dispatch!(self, builder => {
+            builder.set_execution_fee(lamports);
+        });
+        self
+    }
+}
+
+/// A batch of tasks sharing the same price updates.
+///
+/// The feeds required by the tasks are merged so that the prices of each token
+/// are only posted once, and the instructions of the tasks are appended in order.
+pub(super) struct Batch<'a> {
+    store: Pubkey,
+    builders: Vec<TaskBuilder<'a>>,
+}
+
+impl<'a> Batch<'a> {
+    pub(super) fn new(store: Pubkey, builders: Vec<TaskBuilder<'a>>) -> Self {
+        Self { store, builders }
+    }
+}
+
+impl<'a> MakeBundleBuilder<'a, LocalSignerRef> for Batch<'a> {
+    async fn build_with_options(
+        &mut self,
+        options: BundleOptions,
+    ) -> gmsol_sdk::solana_utils::Result<BundleBuilder<'a, LocalSignerRef>> {
+        let mut builders = self.builders.iter_mut();
+        let first = builders
+            .next()
+            .ok_or_else(|| gmsol_sdk::solana_utils::Error::custom("empty batch"))?;
+        let mut bundle = first.build_with_options(options.clone()).await?;
+        for builder in builders {
+            bundle.append(builder.build_with_options(options.clone()).await?, false)?;
+        }
+        Ok(bundle)
+    }
+}
+
+impl PullOraclePriceConsumer for Batch<'_> {
+    async fn feed_ids(&mut self) -> gmsol_sdk::Result<FeedIds> {
+        let mut records = BTreeMap::default();
+        for builder in self.builders.iter_mut() {
+            let feed_ids = builder.feed_ids().await?;
+            let mut tokens = feed_ids.tokens.iter().zip(feed_ids.feeds.iter());
+            for (provider, num) in feed_ids.providers.iter().zip(feed_ids.nums.iter()) {
+                let provider =
+                    PriceProviderKind::try_from(*provider).map_err(gmsol_sdk::Error::custom)?;
+                for (token, feed) in tokens.by_ref().take(usize::from(*num)) {
+                    records
+                        .entry((*token, u8::from(provider)))
+                        .or_insert_with(|| TokenRecord::new(*token, *feed, provider));
+                }
+            }
+        }
+        let tokens_with_feed = TokensWithFeed::try_from_records(records.into_values().collect())
+            .map_err(gmsol_sdk::Error::custom)?;
+        Ok(FeedIds::new(self.store, tokens_with_feed))
+    }
+
+    fn process_feeds(
+        &mut self,
+        provider: PriceProviderKind,
+        map: FeedAddressMap,
+    ) -> gmsol_sdk::Result<()> {
+        for builder in self.builders.iter_mut() {
+            builder.process_feeds(provider, map.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl SetExecutionFee for Batch<'_> {
+    fn is_execution_fee_estimation_required(&self) -> bool {
+        self.builders
+            .iter()
+            .any(|builder| builder.is_execution_fee_estimation_required())
+    }
+
+    /// The estimated execution fee is for the whole batch, so it is split evenly
+    /// across the tasks that require it.
+    fn set_execution_fee(&mut self, lamports: u64) -> &mut Self {
+        let count = self
+            .builders
+            .iter()
+            .filter(|builder| builder.is_execution_fee_estimation_required())
+            .count();
+        if count != 0 {
+            let lamports = lamports.div_ceil(count as u64);
+            for builder in self
+                .builders
+                .iter_mut()
+                .filter(|builder| builder.is_execution_fee_estimation_required())
+            {
+                builder.set_execution_fee(lamports);
+            }
+        }
+        self
+    }
+}
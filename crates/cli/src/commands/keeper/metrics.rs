@@ -0,0 +1,142 @@
+use std::{
+    fmt::Write,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// Keeper metrics.
+#[derive(Debug, Default)]
+pub(super) struct Metrics {
+    pub(super) ticks: AtomicU64,
+    pub(super) pending: AtomicU64,
+    pub(super) executed: AtomicU64,
+    pub(super) failed: AtomicU64,
+    pub(super) dropped: AtomicU64,
+    pub(super) closed: AtomicU64,
+    pub(super) liquidated: AtomicU64,
+    pub(super) auto_deleveraged: AtomicU64,
+    pub(super) trailing_stops_updated: AtomicU64,
+    pub(super) last_tick_timestamp: AtomicU64,
+}
+
+impl Metrics {
+    pub(super) fn add(counter: &AtomicU64, value: u64) {
+        counter.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub(super) fn set(gauge: &AtomicU64, value: u64) {
+        gauge.store(value, Ordering::Relaxed);
+    }
+
+    /// Render in Prometheus text exposition format.
+    fn render(&self) -> String {
+        let metrics = [
+            (
+                "gmsol_keeper_ticks_total",
+                "counter",
+                "Number of keeper iterations.",
+                &self.ticks,
+            ),
+            (
+                "gmsol_keeper_pending_actions",
+                "gauge",
+                "Number of pending actions found in the last iteration.",
+                &self.pending,
+            ),
+            (
+                "gmsol_keeper_executed_total",
+                "counter",
+                "Number of actions executed.",
+                &self.executed,
+            ),
+            (
+                "gmsol_keeper_failed_total",
+                "counter",
+                "Number of failed execution attempts.",
+                &self.failed,
+            ),
+            (
+                "gmsol_keeper_dropped_total",
+                "counter",
+                "Number of actions given up after too many retries.",
+                &self.dropped,
+            ),
+            (
+                "gmsol_keeper_closed_total",
+                "counter",
+                "Number of finished actions closed.",
+                &self.closed,
+            ),
+            (
+                "gmsol_keeper_liquidated_total",
+                "counter",
+                "Number of positions liquidated.",
+                &self.liquidated,
+            ),
+            (
+                "gmsol_keeper_auto_deleveraged_total",
+                "counter",
+                "Number of positions auto-deleveraged.",
+                &self.auto_deleveraged,
+            ),
+            (
+                "gmsol_keeper_trailing_stops_updated_total",
+                "counter",
+                "Number of trailing-stop orders whose best prices were updated.",
+                &self.trailing_stops_updated,
+            ),
+            (
+                "gmsol_keeper_last_tick_timestamp_seconds",
+                "gauge",
+                "Unix timestamp of the last iteration.",
+                &self.last_tick_timestamp,
+            ),
+        ];
+        let mut output = String::new();
+        for (name, kind, help, value) in metrics {
+            _ = writeln!(output, "# HELP {name} {help}");
+            _ = writeln!(output, "# TYPE {name} {kind}");
+            _ = writeln!(output, "{name} {}", value.load(Ordering::Relaxed));
+        }
+        output
+    }
+
+    /// Serve the metrics over HTTP at the given address.
+    ///
+    /// Every request is answered with the metrics, regardless of its path.
+    pub(super) async fn serve(self: Arc<Self>, addr: SocketAddr) -> eyre::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        tracing::info!(%addr, "serving metrics");
+        loop {
+            let (mut stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!(%err, "failed to accept metrics connection");
+                    continue;
+                }
+            };
+            let metrics = self.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                // The request is not inspected, but it must be read before responding.
+                _ = stream.read(&mut buf).await;
+                let body = metrics.render();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                if let Err(err) = stream.write_all(response.as_bytes()).await {
+                    tracing::debug!(%peer, %err, "failed to write metrics");
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,937 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::{Stream, StreamExt};
+use gmsol_sdk::{
+    client::pyth::Hermes,
+    core::action::ActionState,
+    model::price::{Price, Prices},
+    ops::ExchangeOps,
+    position::scanner::{PositionScanner, ScanOptions},
+    programs::{anchor_lang::prelude::Pubkey, gmsol_store::types::ActionHeader},
+    serde::StringPubkey,
+    solana_utils::{
+        bundle_builder::BundleOptions, signer::LocalSignerRef,
+        solana_sdk::address_lookup_table::AddressLookupTableAccount,
+    },
+    utils::Value,
+};
+
+use crate::{
+    commands::{
+        exchange::executor::{Executor, ExecutorArgs},
+        utils::toml_from_file,
+    },
+    config::DisplayOptions,
+    CommandClient,
+};
+
+use self::{
+    batch::{Batch, TaskBuilder},
+    metrics::Metrics,
+    retry::{Failure, Retries},
+    trigger::{OrderTrigger, Readiness},
+};
+
+mod batch;
+mod metrics;
+mod retry;
+mod trigger;
+
+/// Keeper commands.
+#[derive(Debug, clap::Args)]
+pub struct Keeper {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Run the keeper until interrupted.
+    ///
+    /// Pending actions are executed in batches sharing the same price updates,
+    /// and finished actions are closed.
+    Run(Box<RunArgs>),
+    /// Scan the positions for liquidations and ADL candidates, ranked by urgency.
+    Scan {
+        /// Market tokens of the markets to scan. All markets are scanned if not provided.
+        #[arg(long = "market-token")]
+        market_tokens: Vec<Pubkey>,
+        /// Path to a TOML file of unit prices keyed by token mint, used instead of
+        /// fetching the latest prices.
+        ///
+        /// For example: `[prices.<mint>]` with `min = "<price>"` and `max = "<price>"`.
+        #[arg(long)]
+        prices: Option<PathBuf>,
+        /// Whether to skip looking for liquidatable positions.
+        #[arg(long)]
+        skip_liquidation: bool,
+        /// Whether to skip looking for ADL candidates.
+        #[arg(long)]
+        skip_adl: bool,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+struct RunArgs {
+    #[command(flatten)]
+    executor: ExecutorArgs,
+    /// Interval between two iterations if no store event is received.
+    #[arg(long, default_value = "5s", value_parser = humantime::parse_duration)]
+    interval: Duration,
+    /// Max number of actions to execute in a batch.
+    #[arg(long, default_value_t = 4)]
+    batch_size: usize,
+    /// Max number of retries before giving up on an action.
+    #[arg(long, default_value_t = 5)]
+    max_retries: u32,
+    /// Delay before retrying a failed action, doubled after each failure.
+    #[arg(long, default_value = "2s", value_parser = humantime::parse_duration)]
+    backoff: Duration,
+    /// Whether to execute limit and trigger orders as well.
+    ///
+    /// Only market orders are executed by default. Limit and trigger orders are
+    /// only executed once triggered at the latest prices, and the best prices of
+    /// trailing-stop orders are updated when they can be moved.
+    #[arg(long)]
+    limit_orders: bool,
+    /// Whether to disable waking up on new store events, polling only.
+    #[arg(long)]
+    disable_subscription: bool,
+    /// Whether to liquidate liquidatable positions.
+    #[arg(long)]
+    liquidate: bool,
+    /// Whether to update the ADL states and auto-deleverage positions when required.
+    #[arg(long)]
+    adl: bool,
+    /// Address to serve the metrics at, in Prometheus text format.
+    #[arg(long)]
+    metrics_addr: Option<SocketAddr>,
+}
+
+impl super::Command for Keeper {
+    fn is_client_required(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, ctx: super::Context<'_>) -> eyre::Result<()> {
+        match &self.command {
+            Command::Run(args) => args.run(ctx).await,
+            Command::Scan {
+                market_tokens,
+                prices,
+                skip_liquidation,
+                skip_adl,
+            } => {
+                let client = ctx.client()?;
+                let prices = prices
+                    .as_ref()
+                    .map(|path| {
+                        toml_from_file::<PricesFile>(path)?
+                            .prices
+                            .into_iter()
+                            .map(|(token, price)| {
+                                Ok((
+                                    *token,
+                                    Price {
+                                        min: price.min.to_u128()?,
+                                        max: price.max.to_u128()?,
+                                    },
+                                ))
+                            })
+                            .collect::<eyre::Result<HashMap<_, _>>>()
+                    })
+                    .transpose()?;
+                let market_tokens = (!market_tokens.is_empty()).then_some(market_tokens.as_slice());
+                let scanner =
+                    load_scanner(client, ctx.store(), market_tokens, prices.as_ref()).await?;
+                let report = scanner.scan(&ScanOptions {
+                    liquidation: !skip_liquidation,
+                    adl: !skip_adl,
+                })?;
+                display_report(&ctx, &report)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Unit prices keyed by token mint.
+#[derive(Debug, serde::Deserialize)]
+struct PricesFile {
+    prices: HashMap<StringPubkey, Price<Value>>,
+}
+
+fn display_report(
+    ctx: &super::Context<'_>,
+    report: &gmsol_sdk::position::scanner::ScanReport,
+) -> eyre::Result<()> {
+    let output = ctx.config().output();
+
+    let liquidations = report.liquidations.iter().map(|candidate| {
+        serde_json::json!({
+            "position": candidate.position,
+            "owner": candidate.owner,
+            "market_token": candidate.market_token,
+            "is_long": candidate.is_long,
+            "size_in_usd": Value::from_u128(candidate.size_in_usd),
+            "margin_health": candidate.margin_health.map(Value::from_u128),
+            "reason": candidate.reason,
+        })
+    });
+    println!(
+        "{}",
+        output.display_many(
+            liquidations,
+            DisplayOptions::table_projection([
+                ("position", "Liquidatable Position"),
+                ("owner", "Owner"),
+                ("market_token", "Market Token"),
+                ("is_long", "Is Long"),
+                ("size_in_usd", "$ Size"),
+                ("margin_health", "Margin Health"),
+                ("reason", "Reason"),
+            ])
+            .set_empty_message("No liquidatable positions."),
+        )?
+    );
+
+    let requirements = report.adl_requirements.iter().map(|requirement| {
+        serde_json::json!({
+            "market_token": requirement.market_token,
+            "is_long": requirement.is_long,
+            "pnl_factor": Value::from_i128(requirement.pnl_factor),
+            "max_pnl_factor": Value::from_u128(requirement.max_pnl_factor),
+            "min_pnl_factor_after_adl": Value::from_u128(requirement.min_pnl_factor_after_adl),
+            "pnl_to_realize": Value::from_u128(requirement.pnl_to_realize),
+        })
+    });
+    println!(
+        "{}",
+        output.display_many(
+            requirements,
+            DisplayOptions::table_projection([
+                ("market_token", "ADL Required Market"),
+                ("is_long", "Is Long"),
+                ("pnl_factor", "PnL Factor"),
+                ("max_pnl_factor", "Max PnL Factor"),
+                ("min_pnl_factor_after_adl", "Min PnL Factor After ADL"),
+                ("pnl_to_realize", "$ PnL To Realize"),
+            ])
+            .set_empty_message("No ADL required."),
+        )?
+    );
+
+    let candidates = report.adl_candidates.iter().map(|candidate| {
+        serde_json::json!({
+            "position": candidate.position,
+            "owner": candidate.owner,
+            "market_token": candidate.market_token,
+            "is_long": candidate.is_long,
+            "size_in_usd": Value::from_u128(candidate.size_in_usd),
+            "pnl": Value::from_i128(candidate.pnl),
+            "pnl_factor": Value::from_i128(candidate.pnl_factor),
+            "size_delta_usd": Value::from_u128(candidate.size_delta_usd),
+        })
+    });
+    println!(
+        "{}",
+        output.display_many(
+            candidates,
+            DisplayOptions::table_projection([
+                ("position", "ADL Candidate"),
+                ("owner", "Owner"),
+                ("market_token", "Market Token"),
+                ("is_long", "Is Long"),
+                ("size_in_usd", "$ Size"),
+                ("pnl", "$ PnL"),
+                ("pnl_factor", "PnL Factor"),
+                ("size_delta_usd", "$ Size To Decrease"),
+            ])
+            .set_empty_message("No ADL candidates."),
+        )?
+    );
+
+    for skipped in report.skipped.iter() {
+        tracing::warn!(position = %skipped.position, reason = %skipped.reason, "skipped position");
+    }
+
+    Ok(())
+}
+
+/// Load the positions of the given markets with their prices.
+///
+/// The prices are looked up by token mint in `prices` if provided, or fetched from
+/// Pyth otherwise. Markets without prices are left to be reported as skipped.
+async fn load_scanner(
+    client: &CommandClient,
+    store: &Pubkey,
+    market_tokens: Option<&[Pubkey]>,
+    prices: Option<&HashMap<Pubkey, Price<u128>>>,
+) -> gmsol_sdk::Result<PositionScanner> {
+    let mut scanner = PositionScanner::fetch(client, store, market_tokens).await?;
+    let markets = scanner.markets().cloned().collect::<Vec<_>>();
+    match prices {
+        Some(prices) => {
+            for market in markets {
+                let meta = &market.meta;
+                let get = |token: &Pubkey| prices.get(token).copied();
+                match (
+                    get(&meta.index_token_mint),
+                    get(&meta.long_token_mint),
+                    get(&meta.short_token_mint),
+                ) {
+                    (Some(index_token_price), Some(long_token_price), Some(short_token_price)) => {
+                        scanner.update_prices(
+                            &meta.market_token_mint,
+                            Prices {
+                                index_token_price,
+                                long_token_price,
+                                short_token_price,
+                            },
+                        )?;
+                    }
+                    _ => {
+                        tracing::warn!(market_token = %meta.market_token_mint, "missing prices");
+                    }
+                }
+            }
+        }
+        None => {
+            let token_map = client.authorized_token_map(store).await?;
+            let hermes = Hermes::default();
+            for market in markets {
+                let market_token = market.meta.market_token_mint;
+                match hermes.unit_prices_for_market(&token_map, &*market).await {
+                    Ok(prices) => {
+                        scanner.update_prices(&market_token, prices)?;
+                    }
+                    Err(err) => {
+                        tracing::warn!(%market_token, %err, "failed to fetch prices");
+                    }
+                }
+            }
+        }
+    }
+    Ok(scanner)
+}
+
+impl RunArgs {
+    async fn run(&self, ctx: super::Context<'_>) -> eyre::Result<()> {
+        ctx.require_not_serialize_only_mode()?;
+        ctx.require_not_ix_buffer_mode()?;
+
+        let client = ctx.client()?;
+        let mut alts = Vec::default();
+        for alt in ctx.config().alts() {
+            alts.push(client.alt(alt).await?.ok_or(gmsol_sdk::Error::NotFound)?);
+        }
+
+        let metrics = Arc::new(Metrics::default());
+        if let Some(addr) = self.metrics_addr {
+            let metrics = metrics.clone();
+            tokio::spawn(async move {
+                if let Err(err) = metrics.serve(addr).await {
+                    tracing::error!(%err, "metrics server stopped");
+                }
+            });
+        }
+
+        let mut keeper = KeeperState {
+            client,
+            store: *ctx.store(),
+            oracle: *ctx.config().oracle()?,
+            executor: self.executor.build(client).await?,
+            options: ctx.bundle_options(),
+            args: self,
+            alts,
+            retries: Retries::new(self.max_retries, self.backoff),
+            metrics,
+        };
+
+        let mut events = if self.disable_subscription {
+            None
+        } else {
+            client
+                .subscribe_store_cpi_events(None)
+                .await
+                .inspect_err(
+                    |err| tracing::warn!(%err, "failed to subscribe to store events, polling only"),
+                )
+                .ok()
+                .map(Box::pin)
+        };
+
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
+        loop {
+            keeper.tick().await;
+            tokio::select! {
+                _ = &mut shutdown => {
+                    tracing::info!("shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.interval) => {}
+                _ = next_event(&mut events) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Wait for the next batch of store events, or forever if not subscribed.
+async fn next_event<S, T>(events: &mut Option<S>)
+where
+    S: Stream<Item = gmsol_sdk::Result<T>> + Unpin,
+{
+    let Some(stream) = events.as_mut() else {
+        return std::future::pending().await;
+    };
+    match stream.next().await {
+        Some(Ok(_)) => {}
+        Some(Err(err)) => {
+            tracing::warn!(%err, "store event subscription error");
+        }
+        None => {
+            tracing::warn!("store event subscription ended, polling only");
+            *events = None;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TaskKind {
+    Deposit,
+    Withdrawal,
+    Shift,
+    Order {
+        is_limit: bool,
+        trigger: Option<OrderTrigger>,
+    },
+    UpdateTrailingStop,
+    GlvDeposit,
+    GlvWithdrawal,
+    GlvShift,
+    Liquidate,
+    AutoDeleverage {
+        size_delta_usd: u128,
+    },
+    UpdateAdl {
+        for_long: bool,
+        for_short: bool,
+    },
+}
+
+/// A keeper task.
+///
+/// The address is the action for action tasks, the order for trailing-stop updates,
+/// the position for liquidation and ADL, and the market token for ADL state updates.
+#[derive(Debug, Clone, Copy)]
+struct Task {
+    address: Pubkey,
+    kind: TaskKind,
+}
+
+struct KeeperState<'a> {
+    client: &'a CommandClient,
+    store: Pubkey,
+    oracle: Pubkey,
+    executor: Executor<'a>,
+    options: BundleOptions,
+    args: &'a RunArgs,
+    alts: Vec<AddressLookupTableAccount>,
+    retries: Retries,
+    metrics: Arc<Metrics>,
+}
+
+impl<'a> KeeperState<'a> {
+    async fn tick(&mut self) {
+        Metrics::add(&self.metrics.ticks, 1);
+        Metrics::set(
+            &self.metrics.last_tick_timestamp,
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        );
+
+        let (pending, finished) = self.discover().await.unwrap_or_else(|err| {
+            tracing::error!(%err, "failed to discover actions");
+            Default::default()
+        });
+        Metrics::set(&self.metrics.pending, pending.len() as u64);
+        self.close(&finished).await;
+
+        let (adl_updates, cuts) = if self.args.liquidate || self.args.adl {
+            self.scan_positions().await.unwrap_or_else(|err| {
+                tracing::error!(%err, "failed to scan positions");
+                Default::default()
+            })
+        } else {
+            Default::default()
+        };
+
+        self.retries.retain(|address| {
+            pending
+                .iter()
+                .chain(adl_updates.iter())
+                .chain(cuts.iter())
+                .any(|task| task.address == *address)
+        });
+
+        let pending = self.check_readiness(pending).await;
+        self.run_tasks(&pending).await;
+        // ADL states must be updated before the positions can be auto-deleveraged.
+        self.run_tasks(&adl_updates).await;
+        self.run_tasks(&cuts).await;
+    }
+
+    /// Returns the pending actions and the finished actions.
+    async fn discover(&self) -> gmsol_sdk::Result<(Vec<Task>, Vec<(Task, ActionState)>)> {
+        let client = self.client;
+        let store = &self.store;
+
+        let mut pending = Vec::default();
+        let mut finished = Vec::default();
+        let mut classify =
+            |address: Pubkey, header: &ActionHeader, kind: TaskKind| -> gmsol_sdk::Result<()> {
+                let task = Task { address, kind };
+                match header.action_state()? {
+                    ActionState::Pending => pending.push(task),
+                    state => finished.push((task, state)),
+                }
+                Ok(())
+            };
+
+        for (address, action) in client.deposits(store, None, None).await? {
+            classify(address, &action.header, TaskKind::Deposit)?;
+        }
+        for (address, action) in client.withdrawals(store, None, None).await? {
+            classify(address, &action.header, TaskKind::Withdrawal)?;
+        }
+        for (address, action) in client.shifts(store, None, None).await? {
+            classify(address, &action.header, TaskKind::Shift)?;
+        }
+        for (address, action) in client.glv_deposits(store, None, None).await? {
+            classify(address, &action.header, TaskKind::GlvDeposit)?;
+        }
+        for (address, action) in client.glv_withdrawals(store, None, None).await? {
+            classify(address, &action.header, TaskKind::GlvWithdrawal)?;
+        }
+        for (address, action) in client.glv_shifts(store, None, None).await? {
+            classify(address, &action.header, TaskKind::GlvShift)?;
+        }
+        for (address, order) in client.orders(store, None, None).await? {
+            let is_limit = !order.params.kind()?.is_market();
+            if is_limit
+                && !self.args.limit_orders
+                && matches!(order.header.action_state()?, ActionState::Pending)
+            {
+                continue;
+            }
+            let trigger = OrderTrigger::new(order.header.market, &order.params)?;
+            classify(
+                address,
+                &order.header,
+                TaskKind::Order { is_limit, trigger },
+            )?;
+        }
+
+        Ok((pending, finished))
+    }
+
+    async fn close(&self, finished: &[(Task, ActionState)]) {
+        if finished.is_empty() {
+            return;
+        }
+        let mut bundle = self.client.bundle_with_options(self.options.clone());
+        let mut count = 0;
+        for (task, state) in finished {
+            match self.close_txn(task, state).await {
+                Ok(Some(txn)) => {
+                    if let Err((_, err)) = bundle.try_push(txn) {
+                        tracing::warn!(address = %task.address, %err, "failed to push close instruction");
+                    } else {
+                        count += 1;
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::warn!(address = %task.address, %err, "failed to build close instruction");
+                }
+            }
+        }
+        if count == 0 {
+            return;
+        }
+        match self.client.send_or_serialize(bundle).await {
+            Ok(()) => {
+                tracing::info!(count, "closed finished actions");
+                Metrics::add(&self.metrics.closed, count);
+            }
+            Err(err) => {
+                tracing::warn!(%err, "failed to close finished actions");
+            }
+        }
+    }
+
+    async fn close_txn(
+        &self,
+        task: &Task,
+        state: &ActionState,
+    ) -> gmsol_sdk::Result<
+        Option<
+            gmsol_sdk::solana_utils::transaction_builder::TransactionBuilder<'a, LocalSignerRef>,
+        >,
+    > {
+        let client = self.client;
+        let store = &self.store;
+        let address = &task.address;
+        let reason = match state {
+            ActionState::Completed => "executed",
+            _ => "cancelled",
+        };
+        let txn = match task.kind {
+            TaskKind::Deposit => {
+                client
+                    .close_deposit(store, address)
+                    .reason(reason)
+                    .build()
+                    .await?
+            }
+            TaskKind::Withdrawal => {
+                client
+                    .close_withdrawal(store, address)
+                    .reason(reason)
+                    .build()
+                    .await?
+            }
+            TaskKind::Shift => client.close_shift(address).reason(reason).build().await?,
+            TaskKind::Order { .. } => client.close_order(address)?.reason(reason).build().await?,
+            TaskKind::GlvDeposit => {
+                client
+                    .close_glv_deposit(address)
+                    .reason(reason)
+                    .build()
+                    .await?
+            }
+            TaskKind::GlvWithdrawal => {
+                client
+                    .close_glv_withdrawal(address)
+                    .reason(reason)
+                    .build()
+                    .await?
+            }
+            TaskKind::GlvShift => {
+                client
+                    .close_glv_shift(address)
+                    .reason(reason)
+                    .build()
+                    .await?
+            }
+            TaskKind::UpdateTrailingStop
+            | TaskKind::Liquidate
+            | TaskKind::AutoDeleverage { .. }
+            | TaskKind::UpdateAdl { .. } => return Ok(None),
+        };
+        Ok(Some(txn))
+    }
+
+    /// Returns the ADL state updates and the positions to liquidate or auto-deleverage.
+    async fn scan_positions(&self) -> gmsol_sdk::Result<(Vec<Task>, Vec<Task>)> {
+        let scanner = load_scanner(self.client, &self.store, None, None).await?;
+        let report = scanner.scan(&ScanOptions {
+            liquidation: self.args.liquidate,
+            adl: self.args.adl,
+        })?;
+
+        for skipped in report.skipped.iter() {
+            tracing::debug!(position = %skipped.position, reason = %skipped.reason, "skipped position");
+        }
+
+        let mut cuts = Vec::default();
+        for candidate in report.liquidations {
+            tracing::info!(position = %candidate.position, reason = %candidate.reason, "found liquidatable position");
+            cuts.push(Task {
+                address: *candidate.position,
+                kind: TaskKind::Liquidate,
+            });
+        }
+
+        let mut adl_updates = BTreeMap::<Pubkey, (bool, bool)>::default();
+        for requirement in report.adl_requirements {
+            tracing::info!(market_token = %requirement.market_token, is_long = requirement.is_long, "ADL is required");
+            let sides = adl_updates.entry(*requirement.market_token).or_default();
+            if requirement.is_long {
+                sides.0 = true;
+            } else {
+                sides.1 = true;
+            }
+        }
+        for candidate in report.adl_candidates {
+            cuts.push(Task {
+                address: *candidate.position,
+                kind: TaskKind::AutoDeleverage {
+                    size_delta_usd: candidate.size_delta_usd,
+                },
+            });
+        }
+        let adl_updates = adl_updates
+            .into_iter()
+            .map(|(address, (for_long, for_short))| Task {
+                address,
+                kind: TaskKind::UpdateAdl {
+                    for_long,
+                    for_short,
+                },
+            })
+            .collect();
+
+        Ok((adl_updates, cuts))
+    }
+
+    /// Returns the tasks that are ready to run.
+    ///
+    /// Orders waiting for their trigger prices or valid-from times are skipped instead of
+    /// failing and counting against their retries, and their retry states are reset so that
+    /// they are executed again once ready. Trailing-stop orders whose best prices can be moved
+    /// are updated instead of executed.
+    async fn check_readiness(&mut self, tasks: Vec<Task>) -> Vec<Task> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+        let prices = self.fetch_trigger_prices(&tasks).await;
+
+        let mut ready = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let TaskKind::Order {
+                trigger: Some(trigger),
+                ..
+            } = task.kind
+            else {
+                ready.push(task);
+                continue;
+            };
+            let index_price = prices
+                .get(&trigger.market)
+                .map(|prices| &prices.index_token_price);
+            match trigger.readiness(now, index_price) {
+                Readiness::Ready => ready.push(task),
+                Readiness::UpdateTrailingStop => ready.push(Task {
+                    address: task.address,
+                    kind: TaskKind::UpdateTrailingStop,
+                }),
+                Readiness::NotReady => {
+                    tracing::debug!(address = %task.address, "order is not ready");
+                    self.retries.reset(&task.address);
+                }
+            }
+        }
+        ready
+    }
+
+    /// Fetch the latest prices of the markets of the orders whose readiness depends on them.
+    async fn fetch_trigger_prices(&self, tasks: &[Task]) -> HashMap<Pubkey, Prices<u128>> {
+        let markets = tasks
+            .iter()
+            .filter_map(|task| match task.kind {
+                TaskKind::Order {
+                    trigger: Some(trigger),
+                    ..
+                } if trigger.requires_price() => Some(trigger.market),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        let mut prices = HashMap::default();
+        if markets.is_empty() {
+            return prices;
+        }
+        let token_map = match self.client.authorized_token_map(&self.store).await {
+            Ok(token_map) => token_map,
+            Err(err) => {
+                tracing::warn!(%err, "failed to fetch token map");
+                return prices;
+            }
+        };
+        let hermes = Hermes::default();
+        for market in markets {
+            let fetched = async {
+                let market = self.client.market(&market).await?;
+                hermes.unit_prices_for_market(&token_map, &*market).await
+            }
+            .await;
+            match fetched {
+                Ok(fetched) => {
+                    prices.insert(market, fetched);
+                }
+                Err(err) => {
+                    tracing::warn!(%market, %err, "failed to fetch prices for orders");
+                }
+            }
+        }
+        prices
+    }
+
+    async fn run_tasks(&mut self, tasks: &[Task]) {
+        let now = Instant::now();
+        let ready = tasks
+            .iter()
+            .filter(|task| self.retries.is_ready(&task.address, now))
+            .copied()
+            .collect::<Vec<_>>();
+
+        for batch in ready.chunks(self.args.batch_size.max(1)) {
+            if batch.len() > 1 {
+                match self.execute(batch).await {
+                    Ok(()) => {
+                        for task in batch {
+                            self.on_success(task);
+                        }
+                        continue;
+                    }
+                    Err(err) => {
+                        tracing::warn!(%err, len = batch.len(), "failed to execute batch, retrying one by one");
+                    }
+                }
+            }
+            for task in batch {
+                match self.execute(std::slice::from_ref(task)).await {
+                    Ok(()) => self.on_success(task),
+                    Err(err) => self.on_failure(task, err),
+                }
+            }
+        }
+    }
+
+    async fn execute(&self, tasks: &[Task]) -> gmsol_sdk::Result<()> {
+        let builders = tasks
+            .iter()
+            .map(|task| self.task_builder(task))
+            .collect::<gmsol_sdk::Result<Vec<_>>>()?;
+        self.executor
+            .execute(Batch::new(self.store, builders), self.options.clone())
+            .await
+    }
+
+    fn task_builder(&self, task: &Task) -> gmsol_sdk::Result<TaskBuilder<'a>> {
+        let client = self.client;
+        let store = &self.store;
+        let oracle = &self.oracle;
+        let address = &task.address;
+        let builder = match task.kind {
+            TaskKind::Deposit => {
+                let mut builder = client.execute_deposit(store, oracle, address, true);
+                builder.close(true);
+                TaskBuilder::Deposit(builder)
+            }
+            TaskKind::Withdrawal => {
+                let mut builder = client.execute_withdrawal(store, oracle, address, true);
+                builder.close(true);
+                TaskBuilder::Withdrawal(builder)
+            }
+            TaskKind::Shift => {
+                let mut builder = client.execute_shift(oracle, address, true);
+                builder.close(true);
+                TaskBuilder::Shift(builder)
+            }
+            TaskKind::Order { is_limit, .. } => {
+                // Limit orders must stay pending if they cannot be executed yet.
+                let mut builder = client.execute_order(store, oracle, address, !is_limit)?;
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                builder.close(true);
+                TaskBuilder::Order(builder)
+            }
+            TaskKind::UpdateTrailingStop => {
+                let mut builder = client.update_trailing_stop_order(store, oracle, address);
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::UpdateTrailingStop(builder)
+            }
+            TaskKind::GlvDeposit => {
+                let mut builder = client.execute_glv_deposit(oracle, address, true);
+                builder.close(true);
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::GlvDeposit(builder)
+            }
+            TaskKind::GlvWithdrawal => {
+                let mut builder = client.execute_glv_withdrawal(oracle, address, true);
+                builder.close(true);
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::GlvWithdrawal(builder)
+            }
+            TaskKind::GlvShift => {
+                let mut builder = client.execute_glv_shift(oracle, address, true);
+                builder.close(true);
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::GlvShift(builder)
+            }
+            TaskKind::Liquidate => {
+                let mut builder = client.liquidate(oracle, address)?;
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::PositionCut(builder)
+            }
+            TaskKind::AutoDeleverage { size_delta_usd } => {
+                let mut builder = client.auto_deleverage(oracle, address, size_delta_usd)?;
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::PositionCut(builder)
+            }
+            TaskKind::UpdateAdl {
+                for_long,
+                for_short,
+            } => {
+                let mut builder = client.update_adl(store, oracle, address, for_long, for_short)?;
+                for alt in self.alts.iter() {
+                    builder.add_alt(alt.clone());
+                }
+                TaskBuilder::UpdateAdl(builder)
+            }
+        };
+        Ok(builder)
+    }
+
+    fn on_success(&mut self, task: &Task) {
+        tracing::info!(address = %task.address, kind = ?task.kind, "executed");
+        self.retries.reset(&task.address);
+        let counter = match task.kind {
+            TaskKind::Liquidate => &self.metrics.liquidated,
+            TaskKind::AutoDeleverage { .. } => &self.metrics.auto_deleveraged,
+            TaskKind::UpdateTrailingStop => &self.metrics.trailing_stops_updated,
+            _ => &self.metrics.executed,
+        };
+        Metrics::add(counter, 1);
+    }
+
+    fn on_failure(&mut self, task: &Task, err: gmsol_sdk::Error) {
+        Metrics::add(&self.metrics.failed, 1);
+        match self.retries.on_failure(&task.address, Instant::now()) {
+            Failure::Retry { attempts, backoff } => {
+                tracing::warn!(address = %task.address, kind = ?task.kind, %err, attempts, ?backoff, "failed to execute, will retry");
+            }
+            Failure::GiveUp => {
+                tracing::error!(address = %task.address, kind = ?task.kind, %err, "failed to execute, giving up");
+                Metrics::add(&self.metrics.dropped, 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use gmsol_sdk::programs::anchor_lang::prelude::Pubkey;
+
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// Retry states of the keeper tasks, keyed by address.
+#[derive(Debug)]
+pub(super) struct Retries {
+    max_retries: u32,
+    backoff: Duration,
+    states: HashMap<Pubkey, Retry>,
+}
+
+#[derive(Debug)]
+struct Retry {
+    attempts: u32,
+    next_attempt: Instant,
+}
+
+/// The outcome of a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Failure {
+    /// The task will be retried after the backoff.
+    Retry { attempts: u32, backoff: Duration },
+    /// The task has failed too many times and will not be retried.
+    GiveUp,
+}
+
+impl Retries {
+    pub(super) fn new(max_retries: u32, backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            backoff,
+            states: Default::default(),
+        }
+    }
+
+    /// Returns whether the task at the given address can be run at `now`.
+    pub(super) fn is_ready(&self, address: &Pubkey, now: Instant) -> bool {
+        self.states
+            .get(address)
+            .map(|retry| retry.attempts <= self.max_retries && retry.next_attempt <= now)
+            .unwrap_or(true)
+    }
+
+    /// Record a failed attempt of the task at the given address.
+    ///
+    /// The backoff is doubled after each failure.
+    pub(super) fn on_failure(&mut self, address: &Pubkey, now: Instant) -> Failure {
+        let retry = self.states.entry(*address).or_insert(Retry {
+            attempts: 0,
+            next_attempt: now,
+        });
+        retry.attempts += 1;
+        if retry.attempts > self.max_retries {
+            return Failure::GiveUp;
+        }
+        let backoff = self
+            .backoff
+            .saturating_mul(1 << (retry.attempts - 1).min(16))
+            .min(MAX_BACKOFF);
+        retry.next_attempt = now + backoff;
+        Failure::Retry {
+            attempts: retry.attempts,
+            backoff,
+        }
+    }
+
+    /// Forget the retry state of the task at the given address.
+    pub(super) fn reset(&mut self, address: &Pubkey) {
+        self.states.remove(address);
+    }
+
+    /// Only keep the retry states of the tasks at the addresses satisfying `f`.
+    pub(super) fn retain(&mut self, mut f: impl FnMut(&Pubkey) -> bool) {
+        self.states.retain(|address, _| f(address));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_and_give_up() {
+        let backoff = Duration::from_secs(2);
+        let mut retries = Retries::new(3, backoff);
+        let address = Pubkey::new_unique();
+        let now = Instant::now();
+
+        assert!(retries.is_ready(&address, now));
+        assert_eq!(
+            retries.on_failure(&address, now),
+            Failure::Retry {
+                attempts: 1,
+                backoff
+            }
+        );
+        assert!(!retries.is_ready(&address, now));
+        assert!(retries.is_ready(&address, now + backoff));
+
+        assert_eq!(
+            retries.on_failure(&address, now),
+            Failure::Retry {
+                attempts: 2,
+                backoff: backoff * 2
+            }
+        );
+        assert_eq!(
+            retries.on_failure(&address, now),
+            Failure::Retry {
+                attempts: 3,
+                backoff: backoff * 4
+            }
+        );
+        assert_eq!(retries.on_failure(&address, now), Failure::GiveUp);
+        assert!(!retries.is_ready(&address, now + MAX_BACKOFF));
+
+        // A reset task is run again.
+        retries.reset(&address);
+        assert!(retries.is_ready(&address, now));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut retries = Retries::new(u32::MAX, Duration::from_secs(2));
+        let address = Pubkey::new_unique();
+        let now = Instant::now();
+        let mut last = Failure::GiveUp;
+        for _ in 0..40 {
+            last = retries.on_failure(&address, now);
+        }
+        assert_eq!(
+            last,
+            Failure::Retry {
+                attempts: 40,
+                backoff: MAX_BACKOFF
+            }
+        );
+    }
+
+    #[test]
+    fn retain() {
+        let mut retries = Retries::new(0, Duration::from_secs(2));
+        let kept = Pubkey::new_unique();
+        let removed = Pubkey::new_unique();
+        let now = Instant::now();
+        retries.on_failure(&kept, now);
+        retries.on_failure(&removed, now);
+
+        retries.retain(|address| *address == kept);
+        assert!(!retries.is_ready(&kept, now));
+        assert!(retries.is_ready(&removed, now));
+    }
+}
@@ -0,0 +1,248 @@
+use gmsol_sdk::{
+    core::order::OrderKind,
+    model::price::Price,
+    programs::{anchor_lang::prelude::Pubkey, gmsol_store::types::OrderActionParams},
+};
+
+/// The conditions to be met before an order can be executed.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct OrderTrigger {
+    /// The market of the order.
+    pub(super) market: Pubkey,
+    kind: OrderKind,
+    is_long: bool,
+    trigger_price: u128,
+    best_price: u128,
+    valid_from_ts: i64,
+}
+
+/// The readiness of an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Readiness {
+    /// The order can be executed.
+    Ready,
+    /// The best price of the trailing-stop order can be moved.
+    UpdateTrailingStop,
+    /// The order cannot be executed yet.
+    NotReady,
+}
+
+impl OrderTrigger {
+    /// Create from the params of an order.
+    ///
+    /// Returns `None` if the order can be executed at any time.
+    pub(super) fn new(
+        market: Pubkey,
+        params: &OrderActionParams,
+    ) -> gmsol_sdk::Result<Option<Self>> {
+        let kind = params.kind()?;
+        if kind.is_market() && params.twap().is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            market,
+            kind,
+            is_long: params.side()?.is_long(),
+            trigger_price: params.trigger_price,
+            best_price: params.best_price,
+            valid_from_ts: params.valid_from_ts,
+        }))
+    }
+
+    /// Returns whether the index price is required to check the readiness.
+    pub(super) fn requires_price(&self) -> bool {
+        matches!(
+            self.kind,
+            OrderKind::LimitIncrease
+                | OrderKind::LimitDecrease
+                | OrderKind::StopLossDecrease
+                | OrderKind::TrailingStopDecrease
+        )
+    }
+
+    /// Check the readiness of the order at the given time and index price.
+    ///
+    /// Orders requiring the index price are not ready if it is not provided.
+    pub(super) fn readiness(&self, now: i64, index_price: Option<&Price<u128>>) -> Readiness {
+        if now < self.valid_from_ts {
+            return Readiness::NotReady;
+        }
+        if !self.requires_price() {
+            return Readiness::Ready;
+        }
+        let Some(index_price) = index_price else {
+            return Readiness::NotReady;
+        };
+        if self.is_best_price_improved(index_price) {
+            // The trigger price moves away from the index price with the best price.
+            return Readiness::UpdateTrailingStop;
+        }
+        if self.is_triggered(index_price) {
+            Readiness::Ready
+        } else {
+            Readiness::NotReady
+        }
+    }
+
+    /// Returns whether the best price of a trailing-stop order would be moved
+    /// by the given index price.
+    fn is_best_price_improved(&self, index_price: &Price<u128>) -> bool {
+        if !self.kind.is_trailing_stop() {
+            return false;
+        }
+        let price = *index_price.pick_price(!self.is_long);
+        if self.is_long {
+            price > self.best_price
+        } else {
+            price < self.best_price
+        }
+    }
+
+    /// Returns whether the trigger price is met at the given index price.
+    ///
+    /// This mirrors the trigger price validation performed at execution.
+    fn is_triggered(&self, index_price: &Price<u128>) -> bool {
+        let trigger_price = self.trigger_price;
+        match (self.kind, self.is_long) {
+            (OrderKind::LimitIncrease, true) | (OrderKind::LimitDecrease, false) => {
+                trigger_price >= *index_price.pick_price(true)
+            }
+            (OrderKind::LimitIncrease, false) | (OrderKind::LimitDecrease, true) => {
+                *index_price.pick_price(false) >= trigger_price
+            }
+            (OrderKind::StopLossDecrease | OrderKind::TrailingStopDecrease, true) => {
+                trigger_price >= *index_price.pick_price(false)
+            }
+            (OrderKind::StopLossDecrease | OrderKind::TrailingStopDecrease, false) => {
+                *index_price.pick_price(true) >= trigger_price
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(kind: OrderKind, is_long: bool, trigger_price: u128) -> OrderTrigger {
+        OrderTrigger {
+            market: Pubkey::default(),
+            kind,
+            is_long,
+            trigger_price,
+            best_price: 0,
+            valid_from_ts: 0,
+        }
+    }
+
+    fn price(min: u128, max: u128) -> Price<u128> {
+        Price { min, max }
+    }
+
+    #[test]
+    fn limit_orders() {
+        let long_increase = trigger(OrderKind::LimitIncrease, true, 100);
+        assert_eq!(
+            long_increase.readiness(0, Some(&price(99, 100))),
+            Readiness::Ready
+        );
+        assert_eq!(
+            long_increase.readiness(0, Some(&price(100, 101))),
+            Readiness::NotReady
+        );
+
+        let short_increase = trigger(OrderKind::LimitIncrease, false, 100);
+        assert_eq!(
+            short_increase.readiness(0, Some(&price(100, 101))),
+            Readiness::Ready
+        );
+        assert_eq!(
+            short_increase.readiness(0, Some(&price(99, 100))),
+            Readiness::NotReady
+        );
+
+        let long_decrease = trigger(OrderKind::LimitDecrease, true, 100);
+        assert_eq!(
+            long_decrease.readiness(0, Some(&price(100, 101))),
+            Readiness::Ready
+        );
+        assert_eq!(
+            long_decrease.readiness(0, Some(&price(99, 100))),
+            Readiness::NotReady
+        );
+
+        let short_decrease = trigger(OrderKind::LimitDecrease, false, 100);
+        assert_eq!(
+            short_decrease.readiness(0, Some(&price(99, 100))),
+            Readiness::Ready
+        );
+        assert_eq!(
+            short_decrease.readiness(0, Some(&price(100, 101))),
+            Readiness::NotReady
+        );
+    }
+
+    #[test]
+    fn stop_loss_orders() {
+        let long = trigger(OrderKind::StopLossDecrease, true, 100);
+        assert_eq!(long.readiness(0, Some(&price(100, 101))), Readiness::Ready);
+        assert_eq!(
+            long.readiness(0, Some(&price(101, 102))),
+            Readiness::NotReady
+        );
+
+        let short = trigger(OrderKind::StopLossDecrease, false, 100);
+        assert_eq!(short.readiness(0, Some(&price(99, 100))), Readiness::Ready);
+        assert_eq!(
+            short.readiness(0, Some(&price(98, 99))),
+            Readiness::NotReady
+        );
+
+        // Not ready without the index price.
+        assert_eq!(long.readiness(0, None), Readiness::NotReady);
+    }
+
+    #[test]
+    fn trailing_stop_orders() {
+        let mut long = trigger(OrderKind::TrailingStopDecrease, true, 90);
+        long.best_price = 100;
+        assert_eq!(
+            long.readiness(0, Some(&price(101, 102))),
+            Readiness::UpdateTrailingStop
+        );
+        assert_eq!(long.readiness(0, Some(&price(95, 96))), Readiness::NotReady);
+        assert_eq!(long.readiness(0, Some(&price(90, 91))), Readiness::Ready);
+
+        let mut short = trigger(OrderKind::TrailingStopDecrease, false, 110);
+        short.best_price = 100;
+        assert_eq!(
+            short.readiness(0, Some(&price(98, 99))),
+            Readiness::UpdateTrailingStop
+        );
+        assert_eq!(
+            short.readiness(0, Some(&price(104, 105))),
+            Readiness::NotReady
+        );
+        assert_eq!(short.readiness(0, Some(&price(109, 110))), Readiness::Ready);
+    }
+
+    #[test]
+    fn valid_from_ts() {
+        let mut twap = trigger(OrderKind::MarketIncrease, true, 0);
+        twap.valid_from_ts = 100;
+        assert_eq!(twap.readiness(99, None), Readiness::NotReady);
+        assert_eq!(twap.readiness(100, None), Readiness::Ready);
+
+        let mut limit = trigger(OrderKind::LimitIncrease, true, 100);
+        limit.valid_from_ts = 100;
+        assert_eq!(
+            limit.readiness(99, Some(&price(99, 100))),
+            Readiness::NotReady
+        );
+        assert_eq!(
+            limit.readiness(100, Some(&price(99, 100))),
+            Readiness::Ready
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, num::NonZeroUsize, path::PathBuf};
+use std::{collections::BTreeMap, num::NonZeroUsize, path::PathBuf, time::SystemTime};
 
 use anchor_spl::associated_token::get_associated_token_address;
 use clap::ValueEnum;
@@ -8,8 +8,8 @@ use gmsol_sdk::{
     client::{StoreFilter, DISC_OFFSET},
     core::{
         config::FactorKey,
-        market::{MarketConfigFlag, VirtualInventoryFlag},
-        oracle::PriceProviderKind,
+        market::{MarketConfigFlag, MarketConfigKey, MarketTradingMode, VirtualInventoryFlag},
+        oracle::{CompositePriceMode, PriceProviderKind},
         price::market_status::MarketStatusFlag,
         token_config::{
             TokenMapAccess, UpdateTokenConfigParams, DEFAULT_HEARTBEAT_DURATION, DEFAULT_PRECISION,
@@ -17,8 +17,8 @@ use gmsol_sdk::{
         },
     },
     ops::{
-        token_config::UpdateFeedConfig, ConfigOps, GtOps, MarketOps, OracleOps, StoreOps,
-        TokenAccountOps, TokenConfigOps, VirtualInventoryOps,
+        token_config::UpdateFeedConfig, ConfigOps, GtOps, MarketOps, OracleOps, RiskOracleOps,
+        StoreOps, TokenAccountOps, TokenConfigOps, VirtualInventoryOps,
     },
     pda::find_virtual_inventory_for_swaps_address,
     programs::{
@@ -27,7 +27,9 @@ use gmsol_sdk::{
         gmsol_store::accounts::{MarketConfigBuffer, VirtualInventory},
     },
     serde::{
-        serde_market::{SerdeMarket, SerdeMarketConfig, SerdeMarketConfigBuffer},
+        serde_market::{
+            SerdeMarket, SerdeMarketConfig, SerdeMarketConfigBuffer, SerdeMarketConfigSchedule,
+        },
         serde_token_map::SerdeTokenConfig,
         StringPubkey,
     },
@@ -157,6 +159,40 @@ enum Command {
         token: Pubkey,
         provider: PriceProviderKind,
     },
+    /// Set composite price config of token.
+    SetCompositePrice {
+        #[arg(long)]
+        token_map: Option<Pubkey>,
+        token: Pubkey,
+        /// Composite price mode. Use `disabled` to clear the composite price config.
+        mode: CompositePriceMode,
+        /// Price sources, which must include the expected provider.
+        #[arg(long = "source")]
+        sources: Vec<PriceProviderKind>,
+        /// The max allowed disagreement between the price sources and the aggregated price.
+        #[arg(long)]
+        max_disagreement_factor: Option<Value>,
+    },
+    /// Set the price circuit breaker config of the given token.
+    SetPriceCircuitBreaker {
+        #[arg(long)]
+        token_map: Option<Pubkey>,
+        token: Pubkey,
+        /// The max allowed price move within a window.
+        /// The circuit breaker is disabled if not provided.
+        #[arg(long, requires = "window")]
+        max_move_factor: Option<Value>,
+        /// The length of the window.
+        #[arg(long)]
+        window: Option<humantime::Duration>,
+    },
+    /// Reset the price circuit breakers of the given tokens.
+    ResetPriceCircuitBreaker {
+        #[arg(long)]
+        token_map: Option<Pubkey>,
+        #[arg(required = true)]
+        tokens: Vec<Pubkey>,
+    },
     /// Create a `MarketConfigBuffer` account.
     CreateBuffer {
         #[command(flatten)]
@@ -280,6 +316,68 @@ enum Command {
         /// Whether to keep the used market config buffer accounts.
         #[arg(long)]
         keep_buffers: bool,
+        /// Ramp the factors linearly to the new values over this duration
+        /// instead of updating them at once.
+        #[arg(long)]
+        ramp: Option<humantime::Duration>,
+        /// The time to start the ramp at.
+        /// The ramp starts once the instructions are executed if not provided.
+        #[arg(long, requires = "ramp")]
+        ramp_start: Option<humantime::Timestamp>,
+    },
+    /// Display the scheduled config updates of markets.
+    ConfigSchedules {
+        /// The market tokens of the markets to display. Display all markets if not provided.
+        market_tokens: Vec<Pubkey>,
+    },
+    /// Cancel the scheduled updates of market config factors,
+    /// keeping their current effective values.
+    CancelConfigSchedules {
+        /// The market token of the market to update.
+        market_token: Pubkey,
+        /// The config keys of the scheduled updates to cancel.
+        #[arg(long = "key", required = true, num_args = 1..)]
+        keys: Vec<MarketConfigKey>,
+    },
+    /// Display the risk oracle config.
+    RiskOracle,
+    /// Initialize the risk oracle.
+    InitRiskOracle {
+        /// The keccak hash of the compressed secp256k1 public key of the risk oracle signer.
+        signer: Pubkey,
+        /// The max age of recommendations. Unlimited if not provided.
+        #[arg(long)]
+        max_age: Option<humantime::Duration>,
+    },
+    /// Update the signer and the max age of recommendations of the risk oracle.
+    UpdateRiskOracle {
+        /// The keccak hash of the compressed secp256k1 public key of the risk oracle signer.
+        signer: Pubkey,
+        /// The max age of recommendations. Unlimited if not provided.
+        #[arg(long)]
+        max_age: Option<humantime::Duration>,
+    },
+    /// Set the bounded range of a market config key that the risk oracle is allowed to update.
+    SetRiskOracleBound {
+        #[arg(long)]
+        key: MarketConfigKey,
+        #[arg(long)]
+        min: Value,
+        #[arg(long)]
+        max: Value,
+    },
+    /// Remove the bounded ranges of market config keys from the risk oracle.
+    RemoveRiskOracleBounds {
+        #[arg(long = "key", required = true, num_args = 1..)]
+        keys: Vec<MarketConfigKey>,
+    },
+    /// Apply the latest recommendations of Chaos Labs' Risk Oracle to the market on-chain.
+    ApplyRiskOracleRecommendations {
+        /// The market token of the market to update.
+        market_token: Pubkey,
+        /// Comma-separated Chaos parameter types to request (optional).
+        #[arg(long, value_delimiter = ',')]
+        types: Option<Vec<String>>,
     },
     /// Toggle GT minting.
     ToggleGtMinting {
@@ -288,6 +386,14 @@ enum Command {
         #[command(flatten)]
         toggle: ToggleValue,
     },
+    /// Set the trading mode of markets.
+    SetTradingMode {
+        #[arg(required = true, num_args = 1..)]
+        market_tokens: Vec<Pubkey>,
+        /// The trading mode.
+        #[arg(long, value_enum)]
+        mode: MarketTradingMode,
+    },
     /// Initialize GT.
     InitGt {
         #[arg(long, short, default_value_t = 7)]
@@ -450,6 +556,15 @@ impl super::Command for Market {
                                     "feeds.switchboard.timestamp_adjustment",
                                     "Switchboard TS Adj",
                                 ),
+                                ("composite_price.mode", "Composite Price"),
+                                ("composite_price.sources", "Price Sources"),
+                                (
+                                    "composite_price.max_disagreement_factor",
+                                    "Max Disagreement",
+                                ),
+                                ("price_circuit_breaker.max_move_factor", "Max Price Move"),
+                                ("price_circuit_breaker.window", "Price Move Window"),
+                                ("price_circuit_breaker.is_tripped", "Price Move Tripped"),
                             ])
                         )?
                     );
@@ -655,6 +770,58 @@ impl super::Command for Market {
                     .set_expected_provider(store, &token_map_address, token, *provider)
                     .into_bundle_with_options(options)?
             }
+            Command::SetCompositePrice {
+                token_map,
+                token,
+                mode,
+                sources,
+                max_disagreement_factor,
+            } => {
+                let token_map_address = token_map_address(client, token_map.as_ref()).await?;
+                client
+                    .set_composite_price(
+                        store,
+                        &token_map_address,
+                        token,
+                        *mode,
+                        sources,
+                        max_disagreement_factor.as_ref(),
+                    )?
+                    .into_bundle_with_options(options)?
+            }
+            Command::SetPriceCircuitBreaker {
+                token_map,
+                token,
+                max_move_factor,
+                window,
+            } => {
+                let token_map_address = token_map_address(client, token_map.as_ref()).await?;
+                let window = window
+                    .map(|window| window.as_secs().try_into())
+                    .transpose()?
+                    .unwrap_or_default();
+                client
+                    .set_price_circuit_breaker(
+                        store,
+                        &token_map_address,
+                        token,
+                        max_move_factor.as_ref(),
+                        window,
+                    )?
+                    .into_bundle_with_options(options)?
+            }
+            Command::ResetPriceCircuitBreaker { token_map, tokens } => {
+                let token_map_address = token_map_address(client, token_map.as_ref()).await?;
+                let mut bundle = client.bundle_with_options(options);
+                for token in tokens {
+                    bundle.push(client.reset_price_circuit_breaker(
+                        store,
+                        &token_map_address,
+                        token,
+                    ))?;
+                }
+                bundle
+            }
             Command::CreateBuffer {
                 keypair,
                 expire_after,
@@ -710,17 +877,7 @@ impl super::Command for Market {
                         let base_url = ctx.config().chaos_base_url();
                         let api_key = ctx.config().chaos_api_key();
                         let chaos = ChaosClient::try_new(&base_url, api_key)?;
-                        let update_types: Vec<String> = if let Some(t) = types {
-                            t.clone()
-                        } else {
-                            vec![
-                                "oiCaps/maxOpenInterestForLongs/v1".to_string(),
-                                "oiCaps/maxOpenInterestForShorts/v1".to_string(),
-                                "priceImpact/negativePositionImpactFactor/v1".to_string(),
-                                "priceImpact/positionImpactExponentFactor/v1".to_string(),
-                                "priceImpact/positivePositionImpactFactor/v1".to_string(),
-                            ]
-                        };
+                        let update_types = types.clone().unwrap_or_else(default_chaos_update_types);
                         let update_types_ref: Vec<&str> =
                             update_types.iter().map(|s| s.as_str()).collect();
                         let recs: Vec<EncodedRecommendation> = chaos
@@ -777,15 +934,10 @@ impl super::Command for Market {
                                         .ok_or_eyre(format!("missing decimals for {k}"))?;
                                     let dec_target = md.market_config_decimals(key_enum)?;
 
-                                    let amount = Amount::from_u128((*v_api).into(), dec_api)?;
-                                    let v_scaled = match key_enum {
-                                        gmsol_sdk::core::market::MarketConfigKey::PositionImpactExponent
-                                        | gmsol_sdk::core::market::MarketConfigKey::SwapImpactExponent => {
-                                            let rounded = Amount(amount.0.round());
-                                            rounded.to_u128(dec_target)?
-                                        }
-                                        _ => amount.to_u128(dec_target)?,
-                                    };
+                                    let v_scaled = gmsol_sdk::core::risk_oracle::scale_value(
+                                        key_enum, *v_api, dec_api, dec_target,
+                                    )
+                                    .ok_or_eyre(format!("value overflow for {k}"))?;
                                     let key_str = key_enum.to_string();
                                     entries.push((key_str, v_scaled));
                                 }
@@ -906,12 +1058,207 @@ impl super::Command for Market {
                 path,
                 receiver,
                 keep_buffers,
+                ramp,
+                ramp_start,
             } => {
                 let configs: MarketConfigs = toml_from_file(path)?;
+                let ramp = ramp
+                    .as_ref()
+                    .map(|duration| {
+                        // A start time in the past starts the ramp once executed.
+                        let start_at = match ramp_start {
+                            Some(start) => start
+                                .duration_since(SystemTime::UNIX_EPOCH)?
+                                .as_secs()
+                                .try_into()?,
+                            None => 0,
+                        };
+                        Ok::<_, eyre::Report>((start_at, duration.as_secs().try_into()?))
+                    })
+                    .transpose()?;
                 configs
-                    .update_market_configs(client, receiver.as_ref(), !*keep_buffers, options)
+                    .update_market_configs(client, receiver.as_ref(), !*keep_buffers, ramp, options)
                     .await?
             }
+            Command::ConfigSchedules { market_tokens } => {
+                let token_map = client.authorized_token_map(store).await?;
+                let markets = if market_tokens.is_empty() {
+                    client
+                        .markets(store)
+                        .await?
+                        .into_values()
+                        .collect::<Vec<_>>()
+                } else {
+                    let mut markets = Vec::with_capacity(market_tokens.len());
+                    for market_token in market_tokens {
+                        markets.push(client.market_by_token(store, market_token).await?);
+                    }
+                    markets
+                };
+                let now = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)?
+                    .as_secs()
+                    .try_into()?;
+                let mut rows = Vec::default();
+                for market in markets {
+                    let decimals = MarketDecimals::new(&market.meta.into(), &token_map)?;
+                    for schedule in market.state.config_schedules.iter() {
+                        let key = schedule.key()?;
+                        let current = Amount::from_u128(
+                            schedule.value_at(now),
+                            decimals.market_config_decimals(key)?,
+                        )?;
+                        let schedule = SerdeMarketConfigSchedule::from_market_config_schedule(
+                            schedule, decimals,
+                        )?;
+                        rows.push(serde_json::json!({
+                            "market_token": StringPubkey(market.meta.market_token_mint),
+                            "name": market.name()?,
+                            "current": current,
+                            "schedule": schedule,
+                        }));
+                    }
+                }
+                println!(
+                    "{}",
+                    output.display_many(
+                        rows,
+                        DisplayOptions::table_projection([
+                            ("name", "Market"),
+                            ("market_token", "Market Token"),
+                            ("schedule.key", "Key"),
+                            ("current", "Current"),
+                            ("schedule.from", "From"),
+                            ("schedule.target", "Target"),
+                            ("schedule.start_at", "Start At"),
+                            ("schedule.end_at", "End At"),
+                        ])
+                        .set_empty_message("No scheduled config updates."),
+                    )?
+                );
+                return Ok(());
+            }
+            Command::RiskOracle => {
+                let risk_oracle = client.risk_oracle(store).await?;
+                println!("Signer: {}", risk_oracle.signer);
+                println!(
+                    "Max Age: {}",
+                    match risk_oracle.max_age {
+                        0 => "unlimited".to_string(),
+                        secs =>
+                            humantime::format_duration(std::time::Duration::from_secs(secs.into()))
+                                .to_string(),
+                    }
+                );
+                let rows = risk_oracle
+                    .bounds()
+                    .map(|bound| {
+                        Ok(serde_json::json!({
+                            "key": bound.key().ok_or_eyre("invalid key")?.to_string(),
+                            "min": Value::from_u128(bound.min),
+                            "max": Value::from_u128(bound.max),
+                        }))
+                    })
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                println!(
+                    "{}",
+                    output.display_many(
+                        rows,
+                        DisplayOptions::table_projection([
+                            ("key", "Key"),
+                            ("min", "Min"),
+                            ("max", "Max"),
+                        ])
+                        .set_empty_message("No bounds."),
+                    )?
+                );
+                return Ok(());
+            }
+            Command::InitRiskOracle { signer, max_age } => {
+                let max_age = max_age
+                    .map(|max_age| max_age.as_secs().try_into())
+                    .transpose()?
+                    .unwrap_or_default();
+                let (rpc, risk_oracle) = client
+                    .initialize_risk_oracle(store, signer, max_age)
+                    .swap_output(());
+                println!("{risk_oracle}");
+                rpc.into_bundle_with_options(options)?
+            }
+            Command::UpdateRiskOracle { signer, max_age } => {
+                let max_age = max_age
+                    .map(|max_age| max_age.as_secs().try_into())
+                    .transpose()?
+                    .unwrap_or_default();
+                client
+                    .update_risk_oracle(store, signer, max_age)
+                    .into_bundle_with_options(options)?
+            }
+            Command::SetRiskOracleBound { key, min, max } => client
+                .set_risk_oracle_bound(store, *key, min.to_u128()?, max.to_u128()?)
+                .into_bundle_with_options(options)?,
+            Command::RemoveRiskOracleBounds { keys } => {
+                let mut bundle = client.bundle_with_options(options);
+                for key in keys {
+                    bundle.push(client.remove_risk_oracle_bound(store, *key))?;
+                }
+                bundle
+            }
+            Command::ApplyRiskOracleRecommendations {
+                market_token,
+                types,
+            } => {
+                #[cfg(not(feature = "chaoslabs-risk-oracle"))]
+                {
+                    let _ = (market_token, types);
+                    eyre::bail!("chaoslabs-risk-oracle feature is not enabled for CLI");
+                }
+                #[cfg(feature = "chaoslabs-risk-oracle")]
+                {
+                    let base_url = ctx.config().chaos_base_url();
+                    let api_key = ctx.config().chaos_api_key();
+                    let chaos = ChaosClient::try_new(&base_url, api_key)?;
+                    let update_types = types.clone().unwrap_or_else(default_chaos_update_types);
+                    let update_types_ref: Vec<&str> =
+                        update_types.iter().map(|s| s.as_str()).collect();
+                    let recs: Vec<EncodedRecommendation> = chaos
+                        .fetch_latest_recommendations("gmx_solana", &update_types_ref)
+                        .await?;
+
+                    let mut bundle = client.bundle_with_options(options);
+                    for rec in &recs {
+                        if rec.market_pubkey()? != *market_token {
+                            continue;
+                        }
+                        if gmsol_sdk::core::risk_oracle::find_parameter(&rec.parameter_name)
+                            .is_none()
+                        {
+                            tracing::warn!(
+                                parameter = %rec.parameter_name,
+                                "skipped unsupported recommendation"
+                            );
+                            continue;
+                        }
+                        tracing::info!(%market_token, "applying recommendation: {rec:#?}");
+                        bundle.push(client.apply_risk_oracle_recommendation(
+                            store,
+                            market_token,
+                            rec.to_recommendation()?,
+                        ))?;
+                    }
+                    if bundle.is_empty() {
+                        eyre::bail!("no chaos recommendations for the specified market_token");
+                    }
+                    bundle
+                }
+            }
+            Command::CancelConfigSchedules { market_token, keys } => {
+                let mut bundle = client.bundle_with_options(options);
+                for key in keys {
+                    bundle.push(client.cancel_market_config_schedule(store, market_token, *key))?;
+                }
+                bundle
+            }
             Command::ToggleGtMinting {
                 market_tokens,
                 toggle,
@@ -923,6 +1270,17 @@ impl super::Command for Market {
                 }
                 bundle
             }
+            Command::SetTradingMode {
+                market_tokens,
+                mode,
+            } => {
+                let mut bundle = client.bundle_with_options(options);
+                for market_token in market_tokens {
+                    let rpc = client.set_market_trading_mode(store, market_token, *mode);
+                    bundle.push(rpc)?;
+                }
+                bundle
+            }
             Command::SetConfigUpdatable {
                 flags,
                 factors,
@@ -1359,6 +1717,7 @@ impl MarketConfigs {
         client: &'a CommandClient,
         receiver: Option<&Pubkey>,
         close_buffers: bool,
+        ramp: Option<(i64, u32)>,
         options: BundleOptions,
     ) -> eyre::Result<BundleBuilder<'a, LocalSignerRef>> {
         let store = &client.store;
@@ -1387,25 +1746,54 @@ impl MarketConfigs {
                     )
                     .into());
                 }
-                tracing::info!("A buffer account is provided, it will be used first to update the market config. Add instruction to update `{market_token}` with it");
-                bundle.push(client.update_market_config_with_buffer(
-                    store,
-                    market_token,
-                    buffer,
-                ))?;
+                match ramp {
+                    Some((start_at, duration)) => {
+                        tracing::info!("A buffer account is provided, it will be used first to schedule the market config updates. Add instruction to schedule updates of `{market_token}` with it");
+                        bundle.push(client.schedule_market_config_update_with_buffer(
+                            store,
+                            market_token,
+                            buffer,
+                            start_at,
+                            duration,
+                        ))?;
+                    }
+                    None => {
+                        tracing::info!("A buffer account is provided, it will be used first to update the market config. Add instruction to update `{market_token}` with it");
+                        bundle.push(client.update_market_config_with_buffer(
+                            store,
+                            market_token,
+                            buffer,
+                        ))?;
+                    }
+                }
                 if close_buffers {
                     buffers_to_close.insert(**buffer);
                 }
             }
             for (key, value) in &config.config.0 {
                 let value = value.to_u128(decimals.market_config_decimals(*key)?)?;
-                tracing::info!(%market_token, "Add instruction to update `{key}` to `{value}`");
-                bundle.push(client.update_market_config_by_key(
-                    store,
-                    market_token,
-                    *key,
-                    &value,
-                )?)?;
+                match ramp {
+                    Some((start_at, duration)) => {
+                        tracing::info!(%market_token, "Add instruction to ramp `{key}` to `{value}` over {duration}s");
+                        bundle.push(client.schedule_market_config_update(
+                            store,
+                            market_token,
+                            *key,
+                            &value,
+                            start_at,
+                            duration,
+                        ))?;
+                    }
+                    None => {
+                        tracing::info!(%market_token, "Add instruction to update `{key}` to `{value}`");
+                        bundle.push(client.update_market_config_by_key(
+                            store,
+                            market_token,
+                            *key,
+                            &value,
+                        )?)?;
+                    }
+                }
             }
             if let Some(enable) = config.enable {
                 tracing::info!(%market_token,
@@ -1724,3 +2112,11 @@ impl SerdeVirtualInventory {
         })
     }
 }
+
+#[cfg(feature = "chaoslabs-risk-oracle")]
+fn default_chaos_update_types() -> Vec<String> {
+    gmsol_sdk::core::risk_oracle::RISK_ORACLE_PARAMETERS
+        .iter()
+        .flat_map(|parameter| parameter.keys.iter().map(|(key, _)| key.to_string()))
+        .collect()
+}
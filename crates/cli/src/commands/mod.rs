@@ -66,6 +66,9 @@ mod user;
 #[cfg(feature = "nightly-cli-market-graph")]
 mod graph;
 
+#[cfg(feature = "execute")]
+mod keeper;
+
 /// Utils for command implementations.
 pub mod utils;
 
@@ -103,6 +106,9 @@ pub enum Commands {
     Lp(Lp),
     /// Inspect protocol data.
     Inspect(Inspect),
+    /// Keeper commands.
+    #[cfg(feature = "execute")]
+    Keeper(keeper::Keeper),
     #[cfg(feature = "nightly-cli-market-graph")]
     Graph(graph::Graph),
     /// Miscellaneous useful commands.
@@ -42,6 +42,31 @@ enum Command {
         #[arg(value_parser = humantime::parse_duration)]
         delta: Duration,
     },
+    /// Set the delay override for the instructions of a program.
+    SetDelayOverride {
+        /// Program ID.
+        program_id: Pubkey,
+        /// Delay.
+        #[arg(value_parser = humantime::parse_duration)]
+        delay: Duration,
+        /// Only override the delay of the instruction with the given name.
+        #[arg(long)]
+        instruction: Option<String>,
+    },
+    /// Remove the delay override.
+    RemoveDelayOverride {
+        /// Program ID.
+        program_id: Pubkey,
+        /// Name of the instruction of the override.
+        #[arg(long)]
+        instruction: Option<String>,
+    },
+    /// Set the min delay of an executor.
+    SetMinDelay {
+        role: String,
+        #[arg(value_parser = humantime::parse_duration)]
+        min_delay: Duration,
+    },
     /// Init executor.
     InitExecutor { role: String },
     /// Approve a timelocked instruction.
@@ -88,9 +113,29 @@ enum Command {
         #[cfg(feature = "squads")]
         #[arg(long, group = "buffer-signers")]
         use_squads: bool,
+        /// Group the instruction buffers into a proposal executed atomically.
+        #[arg(long)]
+        proposal: bool,
+        /// The proposal that must be executed before the created proposal.
+        #[arg(long, requires = "proposal")]
+        depends_on: Option<Pubkey>,
         /// Base58-encoded transaction.
         transaction: String,
     },
+    /// Get proposal.
+    Proposal { address: Pubkey },
+    /// Approve timelocked proposals.
+    ApproveProposal {
+        proposals: Vec<Pubkey>,
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Cancel timelocked proposals.
+    CancelProposal { proposals: Vec<Pubkey> },
+    /// Execute timelocked proposals.
+    ExecuteProposal { proposals: Vec<Pubkey> },
+    /// Close executed proposals.
+    CloseProposal { proposals: Vec<Pubkey> },
 }
 
 impl super::Command for Timelock {
@@ -113,6 +158,17 @@ impl super::Command for Timelock {
                         let config = config.0;
                         println!("Address: {config_address}");
                         println!("Delay: {}s", config.delay);
+                        for o in config.delay_overrides() {
+                            match o.discriminator() {
+                                Some(discriminator) => println!(
+                                    "Override: {} ({}): {}s",
+                                    o.program_id,
+                                    hex::encode(discriminator),
+                                    o.delay
+                                ),
+                                None => println!("Override: {}: {}s", o.program_id, o.delay),
+                            }
+                        }
                     }
                     None => {
                         println!("Not initialized");
@@ -123,8 +179,15 @@ impl super::Command for Timelock {
             Command::Executor { role } => {
                 let executor = get_and_validate_executor_address(client, store, role).await?;
                 let wallet = client.find_executor_wallet_address(&executor);
+                let min_delay = client
+                    .account::<ZeroCopy<Executor>>(&executor)
+                    .await?
+                    .ok_or(gmsol_sdk::Error::NotFound)?
+                    .0
+                    .min_delay;
                 println!("Executor: {executor}");
                 println!("Wallet: {wallet}");
+                println!("Min Delay: {min_delay}s");
                 return Ok(());
             }
             Command::ExecutorWallet { role } => {
@@ -143,6 +206,31 @@ impl super::Command for Timelock {
             Command::IncreaseDelay { delta } => client
                 .increase_timelock_delay(store, delta.as_secs().try_into()?)
                 .into_bundle_with_options(options)?,
+            Command::SetDelayOverride {
+                program_id,
+                delay,
+                instruction,
+            } => client
+                .set_timelock_delay_override(
+                    store,
+                    program_id,
+                    instruction.as_deref().map(instruction_discriminator),
+                    delay.as_secs().try_into()?,
+                )
+                .into_bundle_with_options(options)?,
+            Command::RemoveDelayOverride {
+                program_id,
+                instruction,
+            } => client
+                .remove_timelock_delay_override(
+                    store,
+                    program_id,
+                    instruction.as_deref().map(instruction_discriminator),
+                )
+                .into_bundle_with_options(options)?,
+            Command::SetMinDelay { role, min_delay } => client
+                .set_executor_min_delay(store, role, min_delay.as_secs().try_into()?)?
+                .into_bundle_with_options(options)?,
             Command::InitExecutor { role } => {
                 let (rpc, executor) = client.initialize_executor(store, role)?.swap_output(());
                 println!("{executor}");
@@ -200,11 +288,70 @@ impl super::Command for Timelock {
                     )
                     .into_bundle_with_options(options)?
             }
+            Command::Proposal { address } => {
+                let proposal = client
+                    .timelock_proposal(address)
+                    .await?
+                    .ok_or(gmsol_sdk::Error::NotFound)?;
+                let status = if proposal.is_executed() {
+                    "executed"
+                } else if proposal.is_approved() {
+                    "approved"
+                } else {
+                    "not approved"
+                };
+                println!("Executor: {}", proposal.executor);
+                println!("Status: {status}");
+                if let Some(depends_on) = proposal.depends_on() {
+                    println!("Depends on: {depends_on}");
+                }
+                println!("Pending dependents: {}", proposal.num_dependents);
+                for (idx, buffer) in proposal.instructions().iter().enumerate() {
+                    println!("ix[{idx}]: {buffer}");
+                }
+                return Ok(());
+            }
+            Command::ApproveProposal { proposals, role } => {
+                let mut txns = client.bundle_with_options(options);
+                for proposal in proposals {
+                    let rpc = client
+                        .approve_timelocked_proposal(store, proposal, role.as_deref())
+                        .await?;
+                    txns.push(rpc)?;
+                }
+                txns
+            }
+            Command::CancelProposal { proposals } => {
+                let mut txns = client.bundle_with_options(options);
+                for proposal in proposals {
+                    let rpc = client.cancel_timelocked_proposal(store, proposal).await?;
+                    txns.push(rpc)?;
+                }
+                txns
+            }
+            Command::ExecuteProposal { proposals } => {
+                let mut txns = client.bundle_with_options(options);
+                for proposal in proposals {
+                    let rpc = client.execute_timelocked_proposal(store, proposal).await?;
+                    txns.push(rpc)?;
+                }
+                txns
+            }
+            Command::CloseProposal { proposals } => {
+                let mut txns = client.bundle_with_options(options);
+                for proposal in proposals {
+                    let rpc = client.close_timelocked_proposal(store, proposal).await?;
+                    txns.push(rpc)?;
+                }
+                txns
+            }
             Command::CreateIxBuffer {
                 role,
                 buffers,
                 #[cfg(feature = "squads")]
                 use_squads,
+                proposal,
+                depends_on,
                 transaction,
             } => {
                 let transaction = decode_base64(transaction)?;
@@ -231,7 +378,8 @@ impl super::Command for Timelock {
 
                     if *use_squads {
                         let len = ixs.len();
-                        let steps = len + 1;
+                        let steps = len + 1 + usize::from(*proposal);
+                        let mut created = Vec::with_capacity(len);
                         let (multisig, vault_index) =
                             client.squads_ctx().ok_or_eyre("must use with `--squads`")?;
                         let host_client = client.host_client();
@@ -254,6 +402,7 @@ impl super::Command for Timelock {
                                             .swap_output(())
                                             .0;
                                         println!("ix[{idx}]: {buffer}");
+                                        created.push(buffer);
                                         message = rpc.message_with_blockhash_and_options(
                                             Default::default(),
                                             true,
@@ -292,9 +441,68 @@ impl super::Command for Timelock {
 
                             bundle.push(rpc)?;
                         }
+                        if *proposal {
+                            let idx = len;
+                            let mut message = Default::default();
+                            let (rpc, transaction) = host_client
+                                .squads_create_vault_transaction(
+                                    &multisig,
+                                    vault_index,
+                                    |ephemeral_signers| {
+                                        let proposal = ephemeral_signers[0];
+                                        let rpc = client
+                                            .create_timelocked_proposal(
+                                                store,
+                                                role,
+                                                NullSigner::new(&proposal),
+                                                created,
+                                                depends_on.as_ref(),
+                                            )?
+                                            .swap_output(())
+                                            .0;
+                                        println!("Proposal: {proposal}");
+                                        message = rpc.message_with_blockhash_and_options(
+                                            Default::default(),
+                                            true,
+                                            None,
+                                            None,
+                                        )?;
+                                        Ok(message.clone())
+                                    },
+                                    VaultTransactionOptions {
+                                        ephemeral_signers: 1,
+                                        ..Default::default()
+                                    },
+                                    Some(idx as u64),
+                                )
+                                .await?
+                                .swap_output(());
+                            println!("Adding a vault transaction {idx}: id = {transaction}");
+                            println!(
+                                "Inspector URL for transaction {idx}: {}",
+                                inspect_transaction(&message, Some(client.cluster()), false),
+                            );
+
+                            let confirmation = dialoguer::Confirm::new()
+                                .with_prompt(format!(
+                                    "[{}/{steps}] Confirm to add vault transaction {idx} ?",
+                                    idx + 1
+                                ))
+                                .default(false)
+                                .interact()
+                                .map_err(gmsol_sdk::Error::custom)?;
+
+                            if !confirmation {
+                                tracing::info!("Cancelled");
+                                return Ok(());
+                            }
+
+                            bundle.push(rpc)?;
+                        }
+                        let txns = len + usize::from(*proposal);
                         let confirmation = dialoguer::Confirm::new()
                             .with_prompt(format!(
-                                "[{steps}/{steps}] Confirm creation of {len} vault transactions?"
+                                "[{steps}/{steps}] Confirm creation of {txns} vault transactions?"
                             ))
                             .default(false)
                             .interact()
@@ -313,6 +521,7 @@ impl super::Command for Timelock {
 
                 let mut bundle = client.bundle_with_options(options);
                 let mut buffers = buffers.iter();
+                let mut created = Vec::with_capacity(ixs.len());
                 for (idx, ix) in ixs.into_iter().enumerate() {
                     let buffer = match buffers.next() {
                         Some(buffer) => {
@@ -324,6 +533,20 @@ impl super::Command for Timelock {
                         .create_timelocked_instruction(store, role, buffer, ix)?
                         .swap_output(());
                     println!("ix[{idx}]: {buffer}");
+                    created.push(buffer);
+                    bundle.push(rpc)?;
+                }
+                if *proposal {
+                    let (rpc, proposal) = client
+                        .create_timelocked_proposal(
+                            store,
+                            role,
+                            Keypair::new(),
+                            created,
+                            depends_on.as_ref(),
+                        )?
+                        .swap_output(());
+                    println!("Proposal: {proposal}");
                     bundle.push(rpc)?;
                 }
                 bundle
@@ -334,6 +557,15 @@ impl super::Command for Timelock {
     }
 }
 
+/// Returns the Anchor discriminator of the instruction with the given name.
+fn instruction_discriminator(name: &str) -> [u8; 8] {
+    use gmsol_sdk::programs::anchor_lang::solana_program::hash::hash;
+
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
 async fn get_and_validate_executor_address<C: Deref<Target = impl Signer> + Clone>(
     client: &gmsol_sdk::Client<C>,
     store: &Pubkey,
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::commands::utils::token_amount;
+use crate::{commands::utils::token_amount, config::DisplayOptions};
 use anchor_spl::{
     associated_token::{
         get_associated_token_address, get_associated_token_address_with_program_id,
@@ -9,7 +9,7 @@ use anchor_spl::{
 };
 use eyre::OptionExt;
 use gmsol_sdk::{
-    client::ops::treasury::CreateTreasurySwapOptions,
+    client::ops::treasury::{BuybackPlanParams, CreateTreasurySwapOptions},
     core::{
         market::MarketFlag,
         token_config::{TokenFlag, TokenMapAccess},
@@ -169,6 +169,43 @@ enum Command {
     },
     /// Cancel Swap.
     CancelSwap { order: Pubkey },
+    /// Create a buyback plan.
+    CreateBuybackPlan {
+        /// The market token of the swap orders.
+        market_token: Pubkey,
+        #[arg(long, short = 'i')]
+        swap_in: Pubkey,
+        #[arg(long, short = 'o')]
+        swap_out: Pubkey,
+        /// Total amount of the swap in token to spend.
+        #[arg(long)]
+        budget: Amount,
+        /// Max amount of the swap in token to spend in a single swap.
+        #[arg(long)]
+        slice: Amount,
+        /// Min interval between two swaps.
+        #[arg(long)]
+        interval: humantime::Duration,
+        /// Max slippage from the oracle price.
+        #[arg(long)]
+        max_slippage: Value,
+        /// Extra swap paths.
+        #[arg(long, short = 's', action = clap::ArgAction::Append)]
+        extra_swap_path: Vec<Pubkey>,
+    },
+    /// Show the progress of buyback plans.
+    BuybackPlans,
+    /// Close a buyback plan.
+    CloseBuybackPlan { buyback_plan: Pubkey },
+    /// Settle the last slice of a buyback plan.
+    SettleBuybackPlan { buyback_plan: Pubkey },
+    /// Execute the next slice of a buyback plan.
+    #[cfg(feature = "execute")]
+    ExecuteBuybackPlan {
+        buyback_plan: Pubkey,
+        #[command(flatten)]
+        args: executor::ExecutorArgs,
+    },
     /// Get Receiver Address.
     Receiver,
     /// Withdraw from the treasury vault.
@@ -750,6 +787,109 @@ impl super::Command for Treasury {
                 .cancel_treasury_swap(store, order, None)
                 .await?
                 .into_bundle_with_options(options)?,
+            Command::CreateBuybackPlan {
+                market_token,
+                swap_in,
+                swap_out,
+                budget,
+                slice,
+                interval,
+                max_slippage,
+                extra_swap_path,
+            } => {
+                let decimals = token_map
+                    .get(swap_in)
+                    .ok_or_eyre("token config not found")?
+                    .token_decimals;
+                let params = BuybackPlanParams {
+                    budget: budget.to_u64(decimals)?,
+                    slice: slice.to_u64(decimals)?,
+                    interval: interval.as_secs().try_into()?,
+                    max_slippage: max_slippage.to_u128()?,
+                    swap_path: extra_swap_path.clone(),
+                };
+                let (rpc, buyback_plan) = client
+                    .create_buyback_plan(store, market_token, swap_in, swap_out, params, None)
+                    .await?
+                    .swap_output(());
+                println!("{buyback_plan}");
+                rpc.into_bundle_with_options(options)?
+            }
+            Command::BuybackPlans => {
+                let now = time::OffsetDateTime::now_utc();
+                let plans = client.buyback_plans(store).await?;
+                let output = ctx.config().output();
+                let plans = plans
+                    .iter()
+                    .map(|(address, plan)| {
+                        let decimals = token_map
+                            .get(&plan.swap_in_token)
+                            .ok_or_eyre("token config not found")?
+                            .token_decimals;
+                        let amount = |amount: u64| Amount::from_u64(amount, decimals);
+                        let last_slice_at = plan
+                            .last_slice_at()
+                            .map(time::OffsetDateTime::from_unix_timestamp)
+                            .transpose()?;
+                        let next_slice = match plan.next_slice_at() {
+                            None => "completed".to_string(),
+                            Some(ts) => {
+                                let delta = time::OffsetDateTime::from_unix_timestamp(ts)? - now;
+                                if delta.is_positive() {
+                                    format!("in {delta}")
+                                } else {
+                                    "ready".to_string()
+                                }
+                            }
+                        };
+                        Ok(serde_json::json!({
+                            "address": address.to_string(),
+                            "swap_in_token": plan.swap_in_token.to_string(),
+                            "swap_out_token": plan.swap_out_token.to_string(),
+                            "spent": amount(plan.spent),
+                            "pending": amount(plan.pending),
+                            "budget": amount(plan.budget),
+                            "slice": amount(plan.slice),
+                            "interval_secs": plan.interval,
+                            "max_slippage": Value::from_u128(plan.max_slippage),
+                            "num_slices": plan.num_slices,
+                            "last_slice_at": last_slice_at.map(|ts| ts.to_string()),
+                            "last_order": plan.last_order().map(|order| order.to_string()),
+                            "next_slice": next_slice,
+                        }))
+                    })
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                println!(
+                    "{}",
+                    output.display_many(
+                        plans,
+                        DisplayOptions::table_projection([
+                            ("address", "Plan"),
+                            ("swap_in_token", "Swap In"),
+                            ("swap_out_token", "Swap Out"),
+                            ("spent", "Spent"),
+                            ("pending", "Pending"),
+                            ("budget", "Budget"),
+                            ("slice", "Slice"),
+                            ("interval_secs", "Interval (s)"),
+                            ("max_slippage", "Max Slippage"),
+                            ("num_slices", "Slices"),
+                            ("last_slice_at", "Last Slice"),
+                            ("last_order", "Last Order"),
+                            ("next_slice", "Next Slice"),
+                        ])
+                        .set_empty_message("No buyback plans."),
+                    )?
+                );
+                return Ok(());
+            }
+            Command::CloseBuybackPlan { buyback_plan } => client
+                .close_buyback_plan(store, buyback_plan)
+                .into_bundle_with_options(options)?,
+            Command::SettleBuybackPlan { buyback_plan } => client
+                .settle_buyback_plan(buyback_plan)
+                .await?
+                .into_bundle_with_options(options)?,
             Command::Withdraw {
                 token,
                 token_program_id,
@@ -844,6 +984,14 @@ impl super::Command for Treasury {
                 bundle
             }
             #[cfg(feature = "execute")]
+            Command::ExecuteBuybackPlan { buyback_plan, args } => {
+                let oracle = ctx.config().oracle()?;
+                let builder = client.execute_buyback_plan(store, buyback_plan, oracle);
+                let executor = args.build(client).await?;
+                executor.execute(builder, options).await?;
+                return Ok(());
+            }
+            #[cfg(feature = "execute")]
             Command::ConfirmGtBuyback {
                 gt_exchange_vault,
                 args,
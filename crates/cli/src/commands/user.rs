@@ -1,6 +1,19 @@
+use std::{
+    collections::BTreeSet,
+    ops::Bound,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use futures_util::StreamExt;
 use gmsol_sdk::{
-    ops::user::UserOps, programs::anchor_lang::prelude::Pubkey,
-    programs::gmsol_store::accounts::ReferralCodeV2,
+    client::indexer::EventStore,
+    decode::gmsol::programs::GMSOLCPIEvent,
+    ops::user::UserOps,
+    position::statement::Statement,
+    programs::anchor_lang::prelude::Pubkey,
+    programs::gmsol_store::{accounts::ReferralCodeV2, events::TradeEvent, types::DelegationScope},
+    utils::Value,
 };
 
 /// User account commands.
@@ -24,6 +37,93 @@ enum Command {
     AcceptReferralCode { code: String },
     /// Set Referrer.
     SetReferrer { code: String },
+    /// Authorize a delegate to trade on behalf of the payer.
+    CreateDelegation {
+        delegate: Pubkey,
+        #[command(flatten)]
+        scope: DelegationScopeArgs,
+    },
+    /// Update the scope of a delegation.
+    UpdateDelegation {
+        delegate: Pubkey,
+        #[command(flatten)]
+        scope: DelegationScopeArgs,
+    },
+    /// Revoke a delegation.
+    RevokeDelegation { delegate: Pubkey },
+    /// Export the realized PnL and fee statement of a user.
+    Statement {
+        /// Owner of the positions. Defaults to the payer.
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        /// Only include trades executed at or after this time.
+        #[arg(long)]
+        since: Option<humantime::Timestamp>,
+        /// Only include trades executed before this time.
+        #[arg(long)]
+        until: Option<humantime::Timestamp>,
+        /// Path to the local event database built by the indexer.
+        /// Events are fetched from the RPC if not provided.
+        #[arg(long)]
+        db: Option<PathBuf>,
+        /// Format of the statement.
+        #[arg(long, value_enum, default_value_t)]
+        format: StatementFormat,
+        /// Ledger to export when the format is `csv`.
+        #[arg(long, value_enum, default_value_t)]
+        ledger: StatementLedger,
+        /// Write the statement to the given file instead of stdout.
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum StatementFormat {
+    /// JSON, including all the ledgers.
+    #[default]
+    Json,
+    /// CSV, including only the selected ledger.
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum StatementLedger {
+    /// Per-trade ledger.
+    Trades,
+    /// Per-position ledger.
+    #[default]
+    Positions,
+    /// Per-market ledger.
+    Markets,
+}
+
+#[derive(Debug, clap::Args)]
+struct DelegationScopeArgs {
+    /// Markets (by market token) the delegate is allowed to trade.
+    /// All markets are allowed if not provided.
+    #[arg(long = "market")]
+    markets: Vec<Pubkey>,
+    /// Only allow the delegate to decrease positions.
+    #[arg(long)]
+    decrease_only: bool,
+    /// Max size delta value of the orders created or updated by the delegate.
+    #[arg(long)]
+    max_size: Option<Value>,
+    /// Expiration time of the delegation.
+    #[arg(long)]
+    expires_at: Option<humantime::Timestamp>,
+}
+
+impl DelegationScopeArgs {
+    fn to_scope(&self) -> eyre::Result<DelegationScope> {
+        Ok(DelegationScope {
+            markets: self.markets.clone(),
+            decrease_only: self.decrease_only,
+            max_size_delta_value: self.max_size.map(|size| size.to_u128()).transpose()?,
+            expires_at: self.expires_at.as_ref().map(unix_timestamp).transpose()?,
+        })
+    }
 }
 
 impl super::Command for User {
@@ -57,6 +157,88 @@ impl super::Command for User {
                     .set_referrer(store, ReferralCodeV2::decode(code)?, None)
                     .await?
             }
+            Command::CreateDelegation { delegate, scope } => {
+                let (rpc, delegation) = client
+                    .create_user_delegation(store, delegate, scope.to_scope()?)?
+                    .swap_output(());
+                println!("{delegation}");
+                rpc
+            }
+            Command::UpdateDelegation { delegate, scope } => {
+                client.update_user_delegation(store, delegate, scope.to_scope()?)?
+            }
+            Command::RevokeDelegation { delegate } => {
+                client.revoke_user_delegation(store, delegate)?
+            }
+            Command::Statement {
+                owner,
+                since,
+                until,
+                db,
+                format,
+                ledger,
+                file,
+            } => {
+                let owner = owner.unwrap_or(client.payer());
+                let since = since.as_ref().map(unix_timestamp).transpose()?;
+                let until = until.as_ref().map(unix_timestamp).transpose()?;
+                let range = (
+                    since.map_or(Bound::Unbounded, Bound::Included),
+                    until.map_or(Bound::Unbounded, Bound::Excluded),
+                );
+
+                let trades = match db {
+                    Some(path) => trades_from_db(path, &owner, range)?,
+                    None => {
+                        let mut trades = vec![];
+                        let stream = client.historical_store_cpi_events(&owner, None).await?;
+                        futures_util::pin_mut!(stream);
+                        // Transactions are returned from newest to oldest.
+                        'outer: while let Some(events) = stream.next().await {
+                            for event in events?.into_value() {
+                                if let GMSOLCPIEvent::TradeEvent(event) = event {
+                                    if since.is_some_and(|since| event.ts < since) {
+                                        break 'outer;
+                                    }
+                                    trades.push(event);
+                                }
+                            }
+                        }
+                        trades
+                    }
+                };
+                let trades = trades
+                    .into_iter()
+                    .filter(|event| event.store == *store)
+                    .collect::<Vec<_>>();
+
+                let token_map = client.authorized_token_map(store).await?;
+                let mut builder = Statement::builder().owner(&owner).time_range(range);
+                let market_tokens = trades
+                    .iter()
+                    .map(|event| event.market_token)
+                    .collect::<BTreeSet<_>>();
+                for market_token in market_tokens {
+                    let market = client.market_by_token(store, &market_token).await?;
+                    builder.insert_market(&market.meta.into(), &token_map)?;
+                }
+                builder.add_trades(&trades)?;
+                let statement = builder.build()?;
+
+                let output = match format {
+                    StatementFormat::Json => serde_json::to_string_pretty(&statement)?,
+                    StatementFormat::Csv => match ledger {
+                        StatementLedger::Trades => to_csv(&statement.trades)?,
+                        StatementLedger::Positions => to_csv(&statement.positions)?,
+                        StatementLedger::Markets => to_csv(&statement.markets)?,
+                    },
+                };
+                match file {
+                    Some(path) => std::fs::write(path, output)?,
+                    None => println!("{output}"),
+                }
+                return Ok(());
+            }
         };
 
         let bundle = txn.into_bundle_with_options(options)?;
@@ -64,3 +246,35 @@ impl super::Command for User {
         Ok(())
     }
 }
+
+fn unix_timestamp(ts: &humantime::Timestamp) -> eyre::Result<i64> {
+    Ok(ts
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs()
+        .try_into()?)
+}
+
+fn trades_from_db(
+    path: &Path,
+    owner: &Pubkey,
+    range: (Bound<i64>, Bound<i64>),
+) -> eyre::Result<Vec<TradeEvent>> {
+    let store = EventStore::open(path)?;
+    let trades = store
+        .events_by_owner(owner, range)?
+        .into_iter()
+        .filter_map(|indexed| match indexed.event {
+            GMSOLCPIEvent::TradeEvent(event) => Some(event),
+            _ => None,
+        })
+        .collect();
+    Ok(trades)
+}
+
+fn to_csv(records: &[impl serde::Serialize]) -> eyre::Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for record in records {
+        writer.serialize(record)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
@@ -53,6 +53,18 @@ pub(crate) fn unit_price(
     Ok(price.to_u128()?)
 }
 
+pub(crate) fn price_from_unit_price(
+    unit_price: u128,
+    token_map: &TokenMap,
+    market: &Market,
+) -> eyre::Result<Value> {
+    let decimals = MarketDecimals::new(&market.meta.into(), token_map)?;
+    let mut price = Value::from_u128(unit_price);
+    price.0 *= Decimal::TEN.powu(decimals.index_token_decimals.into());
+
+    Ok(price)
+}
+
 /// Price to min output amount.
 pub(crate) fn price_to_min_output_amount(
     token_map: &TokenMap,
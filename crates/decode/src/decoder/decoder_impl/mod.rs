@@ -3,7 +3,7 @@
 pub mod solana_decoder;
 
 #[cfg(feature = "solana-decoder")]
-pub use solana_decoder::{CPIEventFilter, CPIEvents, TransactionDecoder};
+pub use solana_decoder::{CPIEvent, CPIEventFilter, CPIEvents, TransactionDecoder};
 
 use crate::{AccountAccess, DecodeError, Visitor};
 
@@ -221,6 +221,16 @@ impl CPIEvent {
     pub fn new(program_id: Pubkey, data: Vec<u8>) -> Self {
         Self { program_id, data }
     }
+
+    /// Get the program ID of the event.
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Get the raw data of the event.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
 }
 
 impl Decoder for &CPIEvent {
@@ -459,15 +459,15 @@ pub trait PositionExt<const DECIMALS: u8>: Position<DECIMALS> {
         Ok(())
     }
 
-    /// Check if the position is liquidatable.
+    /// Calculate the remaining collateral value if the position were fully closed at the given prices.
     ///
-    /// Return [`LiquidatableReason`] if it is liquidatable, `None` otherwise.
-    fn check_liquidatable(
+    /// The pending PnL, the (capped) negative price impact on close and the total cost of the
+    /// position fees, including the pending borrowing and funding fees, are all taken into account.
+    /// Liquidation fees are not included.
+    fn remaining_collateral_value_on_close(
         &self,
         prices: &Prices<Self::Num>,
-        should_validate_min_collateral_usd: bool,
-        for_liquidation: bool,
-    ) -> crate::Result<Option<LiquidatableReason>> {
+    ) -> crate::Result<Self::Signed> {
         use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
 
         let size_in_usd = self.size_in_usd();
@@ -509,7 +509,7 @@ pub trait PositionExt<const DECIMALS: u8>: Position<DECIMALS> {
                 "overflow calculating collateral cost value",
             ))?;
 
-        let remaining_collateral_value = collateral_value
+        collateral_value
             .to_signed()?
             .checked_add(&pnl)
             .and_then(|v| {
@@ -518,7 +518,167 @@ pub trait PositionExt<const DECIMALS: u8>: Position<DECIMALS> {
             })
             .ok_or(crate::Error::Computation(
                 "calculating remaining collateral value",
-            ))?;
+            ))
+    }
+
+    /// Get the min collateral value required to keep the position from being liquidated.
+    ///
+    /// It is the larger of `size_in_usd * min_collateral_factor_for_liquidation`
+    /// and the `min_collateral_value` of the market.
+    fn min_collateral_value_for_liquidation(&self) -> crate::Result<Self::Num> {
+        let params = self.market().position_params()?;
+        let min_collateral_usd_for_leverage = crate::utils::apply_factor(
+            self.size_in_usd(),
+            params.min_collateral_factor_for_liquidation(),
+        )
+        .ok_or(crate::Error::Computation(
+            "calculating min collateral usd for leverage",
+        ))?;
+        Ok(min_collateral_usd_for_leverage.max(params.min_collateral_value().clone()))
+    }
+
+    /// Calculate the margin health of the position at the given prices.
+    ///
+    /// The margin health is the ratio (as a factor) of the remaining collateral value on close
+    /// to [the min collateral value for liquidation](PositionExt::min_collateral_value_for_liquidation).
+    /// The position is liquidatable once it drops below one.
+    ///
+    /// Returns `None` if the min collateral value for liquidation is zero.
+    fn margin_health(&self, prices: &Prices<Self::Num>) -> crate::Result<Option<Self::Num>> {
+        let min_collateral_value = self.min_collateral_value_for_liquidation()?;
+        if min_collateral_value.is_zero() {
+            return Ok(None);
+        }
+        let remaining_collateral_value = self.remaining_collateral_value_on_close(prices)?;
+        if !remaining_collateral_value.is_positive() {
+            return Ok(Some(Zero::zero()));
+        }
+        let health = crate::utils::div_to_factor(
+            &remaining_collateral_value.unsigned_abs(),
+            &min_collateral_value,
+            false,
+        )
+        .ok_or(crate::Error::Computation("calculating margin health"))?;
+        Ok(Some(health))
+    }
+
+    /// Solve for the index token price at which the position becomes liquidatable.
+    ///
+    /// For a long position, it is the highest price at which the position is liquidatable;
+    /// for a short position, the lowest one. The pending borrowing and funding fees and the
+    /// price impact on close are taken into account, see [`PositionExt::check_liquidatable`].
+    ///
+    /// All other prices are held fixed, except that the collateral token price moves together
+    /// with the index token price if `is_collateral_index_token` is `true`.
+    ///
+    /// Returns `None` if no such price can be found, e.g., a long position collateralized by
+    /// the index token whose collateral covers the losses at any positive price.
+    fn liquidation_price(
+        &self,
+        prices: &Prices<Self::Num>,
+        is_collateral_index_token: bool,
+    ) -> crate::Result<Option<Self::Num>> {
+        use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive};
+
+        if self.size_in_tokens().is_zero() {
+            return Ok(None);
+        }
+
+        let is_long = self.is_long();
+        let is_collateral_token_long = self.is_collateral_token_long();
+        let is_liquidatable_at = |price: &Self::Num| -> crate::Result<bool> {
+            let mut prices = prices.clone();
+            let price = Price {
+                min: price.clone(),
+                max: price.clone(),
+            };
+            if is_collateral_index_token {
+                if is_collateral_token_long {
+                    prices.long_token_price = price.clone();
+                } else {
+                    prices.short_token_price = price.clone();
+                }
+            }
+            prices.index_token_price = price;
+            Ok(self.check_liquidatable(&prices, true, true)?.is_some())
+        };
+
+        let one = Self::Num::one();
+        let two = Self::Num::from_u8(2).ok_or(crate::Error::Computation("converting two"))?;
+
+        let current = prices
+            .index_token_price
+            .pick_price_for_pnl(is_long, false)
+            .clone()
+            .max(one.clone());
+        let is_liquidatable = is_liquidatable_at(&current)?;
+
+        // Search for a price on the other side of the liquidation price. A long position
+        // becomes liquidatable as the price goes down and a short one as the price goes up.
+        let search_up = is_long == is_liquidatable;
+        let mut other = current.clone();
+        loop {
+            let next = if search_up {
+                other.checked_mul(&two)
+            } else if other > one {
+                other.checked_div(&two)
+            } else {
+                None
+            };
+            let Some(next) = next else {
+                return Ok(None);
+            };
+            other = next;
+            if is_liquidatable_at(&other)? != is_liquidatable {
+                break;
+            }
+        }
+
+        let (mut safe, mut unsafe_) = if is_liquidatable {
+            (other, current)
+        } else {
+            (current, other)
+        };
+
+        // Bisect until the two prices are adjacent.
+        loop {
+            let (low, high) = if safe < unsafe_ {
+                (&safe, &unsafe_)
+            } else {
+                (&unsafe_, &safe)
+            };
+            let diff = high
+                .checked_sub(low)
+                .ok_or(crate::Error::Computation("calculating price diff"))?;
+            if diff <= one {
+                break;
+            }
+            let mid = diff
+                .checked_div(&two)
+                .and_then(|half| low.checked_add(&half))
+                .ok_or(crate::Error::Computation("calculating mid price"))?;
+            if is_liquidatable_at(&mid)? {
+                unsafe_ = mid;
+            } else {
+                safe = mid;
+            }
+        }
+
+        Ok(Some(unsafe_))
+    }
+
+    /// Check if the position is liquidatable.
+    ///
+    /// Return [`LiquidatableReason`] if it is liquidatable, `None` otherwise.
+    fn check_liquidatable(
+        &self,
+        prices: &Prices<Self::Num>,
+        should_validate_min_collateral_usd: bool,
+        for_liquidation: bool,
+    ) -> crate::Result<Option<LiquidatableReason>> {
+        let size_in_usd = self.size_in_usd();
+
+        let remaining_collateral_value = self.remaining_collateral_value_on_close(prices)?;
 
         let params = self.market().position_params()?;
 
@@ -1040,3 +1200,74 @@ pub enum InsolventCloseStep {
     /// Price impact diff.
     Diff,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        market::LiquidityMarketMutExt,
+        test::{TestMarket, TestPosition},
+        MarketAction,
+    };
+
+    use super::*;
+
+    #[test]
+    fn liquidation_price() -> crate::Result<()> {
+        let prices = Prices::new_for_test(120, 120, 1);
+        for (is_long, is_collateral_token_long) in [(true, false), (false, false), (true, true)] {
+            let mut market = TestMarket::<u64, 9>::default();
+            market.deposit(1_000_000_000, 0, prices)?.execute()?;
+            market.deposit(0, 120_000_000_000, prices)?.execute()?;
+            let mut position = if is_long {
+                TestPosition::long(is_collateral_token_long)
+            } else {
+                TestPosition::short(is_collateral_token_long)
+            };
+            let collateral_amount = if is_collateral_token_long {
+                100_000_000
+            } else {
+                10_000_000_000
+            };
+            let report = position
+                .ops(&mut market)
+                .increase(prices, collateral_amount, 80_000_000_000, None)?
+                .execute()?;
+            assert_eq!(report.claimable_funding_amounts(), (&0, &0));
+
+            let ops = position.ops(&mut market);
+            let prices_at = |price: u64| {
+                if is_collateral_token_long {
+                    Prices::new_for_test(price, price, 1)
+                } else {
+                    Prices::new_for_test(price, 120, 1)
+                }
+            };
+            let liquidation_price = ops
+                .liquidation_price(&prices, is_collateral_token_long)?
+                .expect("must have a liquidation price");
+            let safe_price = if is_long {
+                assert!(liquidation_price < 120);
+                liquidation_price + 1
+            } else {
+                assert!(liquidation_price > 120);
+                liquidation_price - 1
+            };
+            assert!(ops
+                .check_liquidatable(&prices_at(liquidation_price), true, true)?
+                .is_some());
+            assert!(ops
+                .check_liquidatable(&prices_at(safe_price), true, true)?
+                .is_none());
+
+            let health = ops
+                .margin_health(&prices)?
+                .expect("must have margin health");
+            assert!(health > 1_000_000_000);
+            let health = ops
+                .margin_health(&prices_at(liquidation_price))?
+                .expect("must have margin health");
+            assert!(health < 1_000_000_000);
+        }
+        Ok(())
+    }
+}
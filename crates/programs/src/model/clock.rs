@@ -1,17 +1,24 @@
 use time::OffsetDateTime;
 
-fn now() -> i64 {
+pub(super) fn now() -> i64 {
     OffsetDateTime::now_utc().unix_timestamp()
 }
 
 pub(super) struct AsClock<'a> {
     last: &'a i64,
+    current: Option<i64>,
 }
 
 impl AsClock<'_> {
+    /// Use the given timestamp as the current time instead of the system time.
+    pub(super) fn at(mut self, current: Option<i64>) -> Self {
+        self.current = current;
+        self
+    }
+
     /// Passed in seconds.
     pub(super) fn passed_in_seconds(&mut self) -> gmsol_model::Result<u64> {
-        let current = now();
+        let current = self.current.unwrap_or_else(now);
         let duration = current.saturating_sub(*self.last);
         if duration > 0 {
             Ok(duration as u64)
@@ -23,19 +30,29 @@ impl AsClock<'_> {
 
 impl<'a> From<&'a i64> for AsClock<'a> {
     fn from(last: &'a i64) -> Self {
-        Self { last }
+        Self {
+            last,
+            current: None,
+        }
     }
 }
 
 /// Clock-related operations.
 pub(super) struct AsClockMut<'a> {
     last: &'a mut i64,
+    current: Option<i64>,
 }
 
 impl AsClockMut<'_> {
+    /// Use the given timestamp as the current time instead of the system time.
+    pub(super) fn at(mut self, current: Option<i64>) -> Self {
+        self.current = current;
+        self
+    }
+
     /// Just passed in seconds.
     pub(super) fn just_passed_in_seconds(&mut self) -> gmsol_model::Result<u64> {
-        let current = now();
+        let current = self.current.unwrap_or_else(now);
         let duration = current.saturating_sub(*self.last);
         if duration > 0 {
             *self.last = current;
@@ -48,6 +65,9 @@ impl AsClockMut<'_> {
 
 impl<'a> From<&'a mut i64> for AsClockMut<'a> {
     fn from(last: &'a mut i64) -> Self {
-        Self { last }
+        Self {
+            last,
+            current: None,
+        }
     }
 }
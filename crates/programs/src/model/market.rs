@@ -171,6 +171,8 @@ enum MarketFlag {
     AutoDeleveragingEnabledForShort,
     GTEnabled,
     Closed,
+    ReduceOnly,
+    CloseOnly,
 }
 
 type MarketFlags = Bitmap<{ constants::NUM_MARKET_FLAGS }>;
@@ -227,6 +229,7 @@ pub struct MarketModel {
     vi_for_positions: Option<VirtualInventoryModel>,
     disable_vis: bool,
     order_fee_discount_factor: u128,
+    clock_override: Option<i64>,
 }
 
 impl Deref for MarketModel {
@@ -239,8 +242,10 @@ impl Deref for MarketModel {
 
 impl MarketModel {
     /// Create from parts.
+    ///
+    /// Scheduled config updates are applied at the current time.
     pub fn from_parts(market: Arc<Market>, supply: u64) -> Self {
-        Self {
+        let mut model = Self {
             market,
             supply,
             swap_pricing: Default::default(),
@@ -248,7 +253,10 @@ impl MarketModel {
             vi_for_positions: None,
             disable_vis: false,
             order_fee_discount_factor: 0,
-        }
+            clock_override: None,
+        };
+        model.apply_config_schedules();
+        model
     }
 
     /// Get whether it is a pure market.
@@ -578,9 +586,59 @@ impl MarketModel {
         }
     }
 
+    /// Returns the timestamp used as the current time by the clocks, if overridden.
+    pub fn clock_override(&self) -> Option<i64> {
+        self.clock_override
+    }
+
+    /// Returns the current time of the clocks.
+    ///
+    /// Returns the clock override if set, otherwise the system time.
+    pub fn current_timestamp(&self) -> i64 {
+        self.clock_override.unwrap_or_else(super::clock::now)
+    }
+
+    /// Override the current time used by the clocks with the given timestamp.
+    ///
+    /// The system time is used if it is set to `None`. Scheduled config updates
+    /// are applied at the new time.
+    pub fn set_clock_override(&mut self, timestamp: Option<i64>) {
+        self.clock_override = timestamp;
+        self.apply_config_schedules();
+    }
+
+    /// Write the effective values of the scheduled config updates at the current time
+    /// into the config, as the store program does when the market is loaded for execution.
+    ///
+    /// Unlike the program, ended schedules are kept so that they can be applied at any time.
+    pub fn apply_config_schedules(&mut self) {
+        let now = self.current_timestamp();
+        let updates = self
+            .state
+            .config_schedules
+            .iter()
+            .filter_map(|schedule| {
+                let key = schedule.key().ok()?;
+                let value = schedule.value_at(now);
+                (self.config.get(key) != Some(&value)).then_some((key, value))
+            })
+            .collect::<Vec<_>>();
+        if updates.is_empty() {
+            return;
+        }
+        let config = &mut self.make_market_mut().config;
+        for (key, value) in updates {
+            if let Some(factor) = config.get_mut(key) {
+                *factor = value;
+            }
+        }
+    }
+
     /// Returns the time in seconds since last funding fee state update.
     pub fn passed_in_seconds_for_funding(&self) -> gmsol_model::Result<u64> {
-        AsClock::from(&self.state.clocks.funding).passed_in_seconds()
+        AsClock::from(&self.state.clocks.funding)
+            .at(self.clock_override)
+            .passed_in_seconds()
     }
 
     /// Convert into an empty position model.
@@ -872,7 +930,9 @@ impl gmsol_model::PositionImpactMarket<{ constants::MARKET_DECIMALS }> for Marke
     }
 
     fn passed_in_seconds_for_position_impact_distribution(&self) -> gmsol_model::Result<u64> {
-        AsClock::from(&self.state.clocks.price_impact_distribution).passed_in_seconds()
+        AsClock::from(&self.state.clocks.price_impact_distribution)
+            .at(self.clock_override)
+            .passed_in_seconds()
     }
 }
 
@@ -900,7 +960,9 @@ impl gmsol_model::BorrowingFeeMarket<{ constants::MARKET_DECIMALS }> for MarketM
     }
 
     fn passed_in_seconds_for_borrowing(&self) -> gmsol_model::Result<u64> {
-        AsClock::from(&self.state.clocks.borrowing).passed_in_seconds()
+        AsClock::from(&self.state.clocks.borrowing)
+            .at(self.clock_override)
+            .passed_in_seconds()
     }
 
     fn borrowing_fee_kink_model_params(
@@ -1102,6 +1164,7 @@ impl gmsol_model::PositionImpactMarketMut<{ constants::MARKET_DECIMALS }> for Ma
     fn just_passed_in_seconds_for_position_impact_distribution(
         &mut self,
     ) -> gmsol_model::Result<u64> {
+        let current = self.clock_override;
         AsClockMut::from(
             &mut self
                 .make_market_mut()
@@ -1109,13 +1172,31 @@ impl gmsol_model::PositionImpactMarketMut<{ constants::MARKET_DECIMALS }> for Ma
                 .clocks
                 .price_impact_distribution,
         )
+        .at(current)
         .just_passed_in_seconds()
     }
 }
 
+impl gmsol_model::BorrowingFeeMarketMut<{ constants::MARKET_DECIMALS }> for MarketModel {
+    fn just_passed_in_seconds_for_borrowing(&mut self) -> gmsol_model::Result<u64> {
+        let current = self.clock_override;
+        AsClockMut::from(&mut self.make_market_mut().state.clocks.borrowing)
+            .at(current)
+            .just_passed_in_seconds()
+    }
+
+    fn borrowing_factor_pool_mut(&mut self) -> gmsol_model::Result<&mut Self::Pool> {
+        self.make_market_mut()
+            .try_pool_mut(PoolKind::BorrowingFactor)
+    }
+}
+
 impl gmsol_model::PerpMarketMut<{ constants::MARKET_DECIMALS }> for MarketModel {
     fn just_passed_in_seconds_for_funding(&mut self) -> gmsol_model::Result<u64> {
-        AsClockMut::from(&mut self.make_market_mut().state.clocks.funding).just_passed_in_seconds()
+        let current = self.clock_override;
+        AsClockMut::from(&mut self.make_market_mut().state.clocks.funding)
+            .at(current)
+            .just_passed_in_seconds()
     }
 
     fn funding_factor_per_second_mut(&mut self) -> &mut Self::Signed {
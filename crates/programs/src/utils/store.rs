@@ -3,8 +3,13 @@ use std::num::NonZeroU64;
 use bytemuck::Zeroable;
 
 use crate::gmsol_store::{
-    accounts::{Glv, GtExchange, Market, Position, ReferralCodeV2, Store, VirtualInventory},
-    types::{ActionHeader, EventPositionState, Pool, PositionState, UpdateOrderParams},
+    accounts::{
+        Glv, GtExchange, Market, Position, ReferralCodeV2, Store, UserDelegation, VirtualInventory,
+    },
+    types::{
+        ActionHeader, CreateOrderOptions, EventPositionState, Pool, PositionState,
+        UpdateOrderOptions, UpdateOrderParams,
+    },
 };
 
 /// Referral Code Bytes.
@@ -59,6 +64,12 @@ impl Default for GtExchange {
     }
 }
 
+impl Default for UserDelegation {
+    fn default() -> Self {
+        Zeroable::zeroed()
+    }
+}
+
 impl Store {
     /// Get claimable time window size.
     pub fn claimable_time_window(&self) -> crate::Result<NonZeroU64> {
@@ -198,6 +209,20 @@ impl UpdateOrderParams {
     }
 }
 
+impl CreateOrderOptions {
+    /// Is empty.
+    pub fn is_empty(&self) -> bool {
+        self.trailing_distance.is_none() && self.twap.is_none()
+    }
+}
+
+impl UpdateOrderOptions {
+    /// Is empty.
+    pub fn is_empty(&self) -> bool {
+        self.trailing_distance.is_none() && self.best_price.is_none()
+    }
+}
+
 #[cfg(feature = "gmsol-model")]
 mod model {
     use gmsol_model::ClockKind;
@@ -229,9 +254,9 @@ mod utils {
         glv::{GlvMarketFlag, MAX_GLV_MARKET_FLAGS},
         impl_fixed_map, impl_flags,
         market::{
-            self, HasMarketMeta, MarketConfigFactor, MarketConfigFlag, MarketConfigKey, MarketFlag,
-            VirtualInventoryFlag, MAX_MARKET_CONFIG_FACTORS, MAX_MARKET_CONFIG_FLAGS,
-            MAX_MARKET_FLAGS, MAX_VIRTUAL_INVENTORY_FLAGS,
+            self, ramp_config_factor, HasMarketMeta, MarketConfigFactor, MarketConfigFlag,
+            MarketConfigKey, MarketFlag, VirtualInventoryFlag, MAX_MARKET_CONFIG_FACTORS,
+            MAX_MARKET_CONFIG_FLAGS, MAX_MARKET_FLAGS, MAX_VIRTUAL_INVENTORY_FLAGS,
         },
         order::{self, OrderFlag, PositionKind, TradeFlag, TradeFlagContainer, MAX_ORDER_FLAGS},
         pubkey::{self, optional_address},
@@ -240,14 +265,15 @@ mod utils {
     };
 
     use crate::gmsol_store::{
-        accounts::{Glv, Market, Position},
+        accounts::{Glv, Market, Position, RiskOracle},
         events::TradeEvent,
         types::{
             ActionFlagContainer, ActionHeader, GlvMarketConfig, GlvMarketFlagContainer, GlvMarkets,
             GlvMarketsEntry, MarketConfig, MarketConfigFactorContainer, MarketConfigFlagContainer,
-            MarketFlagContainer, MarketMeta, Members, MembersEntry, OrderActionParams,
-            OrderFlagContainer, OrderKind, RoleMap, RoleMapEntry, RoleMetadata, RoleStore,
-            SwapActionParams, TokenAndAccount, Tokens, TokensEntry, UpdateTokenConfigParams,
+            MarketConfigSchedule, MarketConfigSchedules, MarketFlagContainer, MarketMeta, Members,
+            MembersEntry, OrderActionParams, OrderFlagContainer, OrderKind, RiskOracleBound,
+            RoleMap, RoleMapEntry, RoleMetadata, RoleStore, SwapActionParams, TokenAndAccount,
+            Tokens, TokensEntry, TrailingDistance, TwapParams, UpdateTokenConfigParams,
             VirtualInventoryFlagContainer,
         },
     };
@@ -323,6 +349,17 @@ mod utils {
         pub fn name(&self) -> crate::Result<&str> {
             bytes_to_fixed_str(&self.name).map_err(crate::Error::custom)
         }
+
+        /// Get trading mode.
+        pub fn trading_mode(&self) -> market::MarketTradingMode {
+            if self.flags.get_flag(MarketFlag::CloseOnly) {
+                market::MarketTradingMode::CloseOnly
+            } else if self.flags.get_flag(MarketFlag::ReduceOnly) {
+                market::MarketTradingMode::ReduceOnly
+            } else {
+                market::MarketTradingMode::Normal
+            }
+        }
     }
 
     impl HasMarketMeta for Market {
@@ -485,6 +522,240 @@ mod utils {
             };
             Some(value)
         }
+
+        /// Get config mutably by [`MarketConfigKey`].
+        pub fn get_mut(&mut self, key: MarketConfigKey) -> Option<&mut u128> {
+            let value = match key {
+                MarketConfigKey::SwapImpactExponent => &mut self.swap_impact_exponent,
+                MarketConfigKey::SwapImpactPositiveFactor => &mut self.swap_impact_positive_factor,
+                MarketConfigKey::SwapImpactNegativeFactor => &mut self.swap_impact_negative_factor,
+                MarketConfigKey::SwapFeeReceiverFactor => &mut self.swap_fee_receiver_factor,
+                MarketConfigKey::SwapFeeFactorForPositiveImpact => {
+                    &mut self.swap_fee_factor_for_positive_impact
+                }
+                MarketConfigKey::SwapFeeFactorForNegativeImpact => {
+                    &mut self.swap_fee_factor_for_negative_impact
+                }
+                MarketConfigKey::MinPositionSizeUsd => &mut self.min_position_size_usd,
+                MarketConfigKey::MinCollateralValue => &mut self.min_collateral_value,
+                MarketConfigKey::MinCollateralFactor => &mut self.min_collateral_factor,
+                MarketConfigKey::MinCollateralFactorForOpenInterestMultiplierForLong => {
+                    &mut self.min_collateral_factor_for_open_interest_multiplier_for_long
+                }
+                MarketConfigKey::MinCollateralFactorForOpenInterestMultiplierForShort => {
+                    &mut self.min_collateral_factor_for_open_interest_multiplier_for_short
+                }
+                MarketConfigKey::MaxPositivePositionImpactFactor => {
+                    &mut self.max_positive_position_impact_factor
+                }
+                MarketConfigKey::MaxNegativePositionImpactFactor => {
+                    &mut self.max_negative_position_impact_factor
+                }
+                MarketConfigKey::MaxPositionImpactFactorForLiquidations => {
+                    &mut self.max_position_impact_factor_for_liquidations
+                }
+                MarketConfigKey::PositionImpactExponent => &mut self.position_impact_exponent,
+                MarketConfigKey::PositionImpactPositiveFactor => {
+                    &mut self.position_impact_positive_factor
+                }
+                MarketConfigKey::PositionImpactNegativeFactor => {
+                    &mut self.position_impact_negative_factor
+                }
+                MarketConfigKey::OrderFeeReceiverFactor => &mut self.order_fee_receiver_factor,
+                MarketConfigKey::OrderFeeFactorForPositiveImpact => {
+                    &mut self.order_fee_factor_for_positive_impact
+                }
+                MarketConfigKey::OrderFeeFactorForNegativeImpact => {
+                    &mut self.order_fee_factor_for_negative_impact
+                }
+                MarketConfigKey::LiquidationFeeReceiverFactor => {
+                    &mut self.liquidation_fee_receiver_factor
+                }
+                MarketConfigKey::LiquidationFeeFactor => &mut self.liquidation_fee_factor,
+                MarketConfigKey::PositionImpactDistributeFactor => {
+                    &mut self.position_impact_distribute_factor
+                }
+                MarketConfigKey::MinPositionImpactPoolAmount => {
+                    &mut self.min_position_impact_pool_amount
+                }
+                MarketConfigKey::BorrowingFeeReceiverFactor => {
+                    &mut self.borrowing_fee_receiver_factor
+                }
+                MarketConfigKey::BorrowingFeeFactorForLong => {
+                    &mut self.borrowing_fee_factor_for_long
+                }
+                MarketConfigKey::BorrowingFeeFactorForShort => {
+                    &mut self.borrowing_fee_factor_for_short
+                }
+                MarketConfigKey::BorrowingFeeExponentForLong => {
+                    &mut self.borrowing_fee_exponent_for_long
+                }
+                MarketConfigKey::BorrowingFeeExponentForShort => {
+                    &mut self.borrowing_fee_exponent_for_short
+                }
+                MarketConfigKey::BorrowingFeeOptimalUsageFactorForLong => {
+                    &mut self.borrowing_fee_optimal_usage_factor_for_long
+                }
+                MarketConfigKey::BorrowingFeeOptimalUsageFactorForShort => {
+                    &mut self.borrowing_fee_optimal_usage_factor_for_short
+                }
+                MarketConfigKey::BorrowingFeeBaseFactorForLong => {
+                    &mut self.borrowing_fee_base_factor_for_long
+                }
+                MarketConfigKey::BorrowingFeeBaseFactorForShort => {
+                    &mut self.borrowing_fee_base_factor_for_short
+                }
+                MarketConfigKey::BorrowingFeeAboveOptimalUsageFactorForLong => {
+                    &mut self.borrowing_fee_above_optimal_usage_factor_for_long
+                }
+                MarketConfigKey::BorrowingFeeAboveOptimalUsageFactorForShort => {
+                    &mut self.borrowing_fee_above_optimal_usage_factor_for_short
+                }
+                MarketConfigKey::FundingFeeExponent => &mut self.funding_fee_exponent,
+                MarketConfigKey::FundingFeeFactor => &mut self.funding_fee_factor,
+                MarketConfigKey::FundingFeeMaxFactorPerSecond => {
+                    &mut self.funding_fee_max_factor_per_second
+                }
+                MarketConfigKey::FundingFeeMinFactorPerSecond => {
+                    &mut self.funding_fee_min_factor_per_second
+                }
+                MarketConfigKey::FundingFeeIncreaseFactorPerSecond => {
+                    &mut self.funding_fee_increase_factor_per_second
+                }
+                MarketConfigKey::FundingFeeDecreaseFactorPerSecond => {
+                    &mut self.funding_fee_decrease_factor_per_second
+                }
+                MarketConfigKey::FundingFeeThresholdForStableFunding => {
+                    &mut self.funding_fee_threshold_for_stable_funding
+                }
+                MarketConfigKey::FundingFeeThresholdForDecreaseFunding => {
+                    &mut self.funding_fee_threshold_for_decrease_funding
+                }
+                MarketConfigKey::ReserveFactor => &mut self.reserve_factor,
+                MarketConfigKey::OpenInterestReserveFactor => {
+                    &mut self.open_interest_reserve_factor
+                }
+                MarketConfigKey::MaxPnlFactorForLongDeposit => {
+                    &mut self.max_pnl_factor_for_long_deposit
+                }
+                MarketConfigKey::MaxPnlFactorForShortDeposit => {
+                    &mut self.max_pnl_factor_for_short_deposit
+                }
+                MarketConfigKey::MaxPnlFactorForLongWithdrawal => {
+                    &mut self.max_pnl_factor_for_long_withdrawal
+                }
+                MarketConfigKey::MaxPnlFactorForShortWithdrawal => {
+                    &mut self.max_pnl_factor_for_short_withdrawal
+                }
+                MarketConfigKey::MaxPnlFactorForLongTrader => {
+                    &mut self.max_pnl_factor_for_long_trader
+                }
+                MarketConfigKey::MaxPnlFactorForShortTrader => {
+                    &mut self.max_pnl_factor_for_short_trader
+                }
+                MarketConfigKey::MaxPnlFactorForLongAdl => &mut self.max_pnl_factor_for_long_adl,
+                MarketConfigKey::MaxPnlFactorForShortAdl => &mut self.max_pnl_factor_for_short_adl,
+                MarketConfigKey::MinPnlFactorAfterLongAdl => {
+                    &mut self.min_pnl_factor_after_long_adl
+                }
+                MarketConfigKey::MinPnlFactorAfterShortAdl => {
+                    &mut self.min_pnl_factor_after_short_adl
+                }
+                MarketConfigKey::MaxPoolAmountForLongToken => {
+                    &mut self.max_pool_amount_for_long_token
+                }
+                MarketConfigKey::MaxPoolAmountForShortToken => {
+                    &mut self.max_pool_amount_for_short_token
+                }
+                MarketConfigKey::MaxPoolValueForDepositForLongToken => {
+                    &mut self.max_pool_value_for_deposit_for_long_token
+                }
+                MarketConfigKey::MaxPoolValueForDepositForShortToken => {
+                    &mut self.max_pool_value_for_deposit_for_short_token
+                }
+                MarketConfigKey::MaxOpenInterestForLong => &mut self.max_open_interest_for_long,
+                MarketConfigKey::MaxOpenInterestForShort => &mut self.max_open_interest_for_short,
+                MarketConfigKey::MinTokensForFirstDeposit => &mut self.min_tokens_for_first_deposit,
+                MarketConfigKey::MinCollateralFactorForLiquidation => {
+                    &mut self.min_collateral_factor_for_liquidation
+                }
+                MarketConfigKey::MarketClosedMinCollateralFactorForLiquidation => {
+                    &mut self.market_closed_min_collateral_factor_for_liquidation
+                }
+                MarketConfigKey::MarketClosedBorrowingFeeBaseFactor => {
+                    &mut self.market_closed_borrowing_fee_base_factor
+                }
+                MarketConfigKey::MarketClosedBorrowingFeeAboveOptimalUsageFactor => {
+                    &mut self.market_closed_borrowing_fee_above_optimal_usage_factor
+                }
+                _ => return None,
+            };
+            Some(value)
+        }
+    }
+
+    impl MarketConfigSchedule {
+        /// Get key.
+        pub fn key(&self) -> crate::Result<MarketConfigKey> {
+            self.key.try_into().map_err(crate::Error::custom)
+        }
+
+        /// Returns whether the schedule is active.
+        pub fn is_active(&self) -> bool {
+            self.is_active != 0
+        }
+
+        /// Returns the end time of the ramp.
+        pub fn end_at(&self) -> i64 {
+            self.start_at.saturating_add(i64::from(self.duration))
+        }
+
+        /// Returns the effective value at the given time.
+        pub fn value_at(&self, now: i64) -> u128 {
+            ramp_config_factor(self.from, self.target, self.start_at, self.duration, now)
+        }
+    }
+
+    impl MarketConfigSchedules {
+        /// Get the active schedule of the given key.
+        pub fn get(&self, key: MarketConfigKey) -> Option<&MarketConfigSchedule> {
+            let key = u16::from(key);
+            self.iter().find(|schedule| schedule.key == key)
+        }
+
+        /// Create an iterator of the active schedules.
+        pub fn iter(&self) -> impl Iterator<Item = &MarketConfigSchedule> {
+            self.schedules
+                .iter()
+                .filter(|schedule| schedule.is_active())
+        }
+    }
+
+    impl RiskOracleBound {
+        /// Returns whether the bound is active.
+        pub fn is_active(&self) -> bool {
+            self.is_active != 0
+        }
+
+        /// Get the key.
+        pub fn key(&self) -> Option<MarketConfigKey> {
+            if !self.is_active() {
+                return None;
+            }
+            MarketConfigKey::try_from(self.key).ok()
+        }
+    }
+
+    impl RiskOracle {
+        /// Get the bound of the given key.
+        pub fn bound(&self, key: MarketConfigKey) -> Option<&RiskOracleBound> {
+            self.bounds().find(|bound| bound.key == u16::from(key))
+        }
+
+        /// Create an iterator over the active bounds.
+        pub fn bounds(&self) -> impl Iterator<Item = &RiskOracleBound> {
+            self.bounds.iter().filter(|bound| bound.is_active())
+        }
     }
 
     impl TokenAndAccount {
@@ -518,6 +789,7 @@ mod utils {
                 OrderKind::LimitIncrease => Self::LimitIncrease,
                 OrderKind::LimitDecrease => Self::LimitDecrease,
                 OrderKind::StopLossDecrease => Self::StopLossDecrease,
+                OrderKind::TrailingStopDecrease => Self::TrailingStopDecrease,
             }
         }
     }
@@ -536,6 +808,7 @@ mod utils {
                 order::OrderKind::LimitIncrease => Ok(Self::LimitIncrease),
                 order::OrderKind::LimitDecrease => Ok(Self::LimitDecrease),
                 order::OrderKind::StopLossDecrease => Ok(Self::StopLossDecrease),
+                order::OrderKind::TrailingStopDecrease => Ok(Self::TrailingStopDecrease),
                 kind => Err(crate::Error::custom(format!(
                     "unsupported order kind: {kind}"
                 ))),
@@ -543,7 +816,72 @@ mod utils {
         }
     }
 
+    impl From<order::TrailingDistance> for TrailingDistance {
+        fn from(value: order::TrailingDistance) -> Self {
+            match value {
+                order::TrailingDistance::Absolute(value) => Self::Absolute(value),
+                order::TrailingDistance::Factor(value) => Self::Factor(value),
+            }
+        }
+    }
+
+    impl From<TrailingDistance> for order::TrailingDistance {
+        fn from(value: TrailingDistance) -> Self {
+            match value {
+                TrailingDistance::Absolute(value) => Self::Absolute(value),
+                TrailingDistance::Factor(value) => Self::Factor(value),
+            }
+        }
+    }
+
+    impl From<order::TwapParams> for TwapParams {
+        fn from(value: order::TwapParams) -> Self {
+            Self {
+                slices: value.slices,
+                interval: value.interval,
+            }
+        }
+    }
+
+    impl From<TwapParams> for order::TwapParams {
+        fn from(value: TwapParams) -> Self {
+            Self {
+                slices: value.slices,
+                interval: value.interval,
+            }
+        }
+    }
+
     impl OrderActionParams {
+        /// Get trailing distance.
+        ///
+        /// Returns `None` if the order is not a trailing-stop order.
+        pub fn trailing_distance(&self) -> Option<order::TrailingDistance> {
+            if self.trailing_distance == 0 {
+                return None;
+            }
+            if self.flags.get_flag(OrderFlag::IsTrailingDistanceFactor) {
+                Some(order::TrailingDistance::Factor(self.trailing_distance))
+            } else {
+                Some(order::TrailingDistance::Absolute(self.trailing_distance))
+            }
+        }
+
+        /// Get TWAP params.
+        ///
+        /// Returns `None` if the order is not a TWAP order.
+        pub fn twap(&self) -> Option<order::TwapParams> {
+            (self.twap_slices != 0).then_some(order::TwapParams {
+                slices: self.twap_slices,
+                interval: self.twap_interval,
+            })
+        }
+
+        /// Get the number of TWAP slices left to execute.
+        pub fn twap_remaining_slices(&self) -> u8 {
+            self.twap_slices.saturating_sub(self.twap_executed_slices)
+        }
+
         /// Get order side.
         pub fn side(&self) -> crate::Result<order::OrderSide> {
             self.side.try_into().map_err(crate::Error::custom)
@@ -1,12 +1,17 @@
 #[cfg(feature = "gmsol-utils")]
 mod utils {
+    use anchor_lang::prelude::Pubkey;
     use gmsol_utils::{
         fixed_str::bytes_to_fixed_str,
         impl_flags,
-        instruction::{InstructionFlag, MAX_IX_FLAGS},
+        instruction::{InstructionFlag, ProposalFlag, MAX_IX_FLAGS, MAX_PROPOSAL_FLAGS},
+        pubkey::optional_address,
     };
 
-    use crate::gmsol_timelock::{accounts::Executor, types::InstructionFlagContainer};
+    use crate::gmsol_timelock::{
+        accounts::{Executor, Proposal, TimelockConfig},
+        types::{DelayOverride, InstructionFlagContainer, ProposalFlagContainer},
+    };
 
     impl Executor {
         /// Get role name.
@@ -15,5 +20,71 @@ mod utils {
         }
     }
 
+    impl TimelockConfig {
+        /// Get the active delay overrides.
+        pub fn delay_overrides(&self) -> impl Iterator<Item = &DelayOverride> {
+            self.delay_overrides.iter().filter(|o| o.is_active())
+        }
+
+        /// Get the delay of the instruction with the given `program_id` and `data`,
+        /// executed by the given `executor`.
+        pub fn delay_for(&self, executor: &Executor, program_id: &Pubkey, data: &[u8]) -> u32 {
+            let discriminator = data.get(..8);
+            let mut delay = None;
+            for o in self
+                .delay_overrides()
+                .filter(|o| o.program_id == *program_id)
+            {
+                match o.discriminator() {
+                    Some(expected) if discriminator == Some(expected.as_slice()) => {
+                        delay = Some(o.delay);
+                        break;
+                    }
+                    None => delay = Some(o.delay),
+                    _ => {}
+                }
+            }
+            delay.unwrap_or(self.delay).max(executor.min_delay)
+        }
+    }
+
+    impl DelayOverride {
+        /// Returns whether the override is active.
+        pub fn is_active(&self) -> bool {
+            self.is_active != 0
+        }
+
+        /// Get the discriminator of the instruction to override.
+        ///
+        /// Returns `None` if the override applies to the whole program.
+        pub fn discriminator(&self) -> Option<&[u8; 8]> {
+            (self.has_discriminator != 0).then_some(&self.discriminator)
+        }
+    }
+
+    impl Proposal {
+        /// Returns whether the proposal is approved.
+        pub fn is_approved(&self) -> bool {
+            self.flags.get_flag(ProposalFlag::Approved)
+        }
+
+        /// Returns whether the proposal is executed.
+        pub fn is_executed(&self) -> bool {
+            self.flags.get_flag(ProposalFlag::Executed)
+        }
+
+        /// Get the proposal that must be executed before this one.
+        pub fn depends_on(&self) -> Option<&Pubkey> {
+            optional_address(&self.depends_on)
+        }
+
+        /// Get the instruction buffers, in execution order.
+        pub fn instructions(&self) -> &[Pubkey] {
+            &self.instructions[..usize::from(self.num_instructions)]
+        }
+    }
+
     impl_flags!(InstructionFlag, MAX_IX_FLAGS, u8);
+
+    impl_flags!(ProposalFlag, MAX_PROPOSAL_FLAGS, u8);
 }
@@ -1,6 +1,9 @@
 use bytemuck::Zeroable;
 
-use crate::gmsol_treasury::types::{TokenBalance, TokenBalancesEntry, TokenConfig, TokenMapEntry};
+use crate::gmsol_treasury::{
+    accounts::BuybackPlan,
+    types::{TokenBalance, TokenBalancesEntry, TokenConfig, TokenMapEntry},
+};
 
 impl Default for TokenBalance {
     fn default() -> Self {
@@ -26,10 +29,16 @@ impl Default for TokenMapEntry {
     }
 }
 
+impl Default for BuybackPlan {
+    fn default() -> Self {
+        Zeroable::zeroed()
+    }
+}
+
 #[cfg(feature = "gmsol-utils")]
 mod utils {
     use crate::gmsol_treasury::{
-        accounts::{GtBank, TreasuryVaultConfig},
+        accounts::{BuybackPlan, GtBank, TreasuryVaultConfig},
         types::{
             GtBankFlagsContainer, TokenBalance, TokenBalances, TokenBalancesEntry, TokenConfig,
             TokenFlagContainer, TokenMap, TokenMapEntry,
@@ -39,7 +48,7 @@ mod utils {
     use gmsol_utils::{
         gt::{GtBankFlags, MAX_GT_BANK_FLAGS},
         impl_fixed_map, impl_flags,
-        pubkey::to_bytes,
+        pubkey::{optional_address, to_bytes},
         token_config::{
             TokenFlag, TokenMapAccess, TokenRecord, TokensWithFeed, MAX_TREASURY_TOKEN_FLAGS,
         },
@@ -78,15 +87,78 @@ mod utils {
                 .tokens()
                 .chain(treasury_vault_config.tokens())
                 .collect::<BTreeSet<_>>();
-            let records = tokens
-                .iter()
-                .map(|token| {
-                    let config = map
-                        .get(token)
-                        .ok_or_else(|| crate::Error::custom("unknown token"))?;
-                    TokenRecord::from_config(*token, config).map_err(crate::Error::custom)
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+            let mut records = Vec::with_capacity(tokens.len());
+            for token in tokens.iter() {
+                let config = map
+                    .get(token)
+                    .ok_or_else(|| crate::Error::custom("unknown token"))?;
+                records.extend(
+                    TokenRecord::from_price_sources(*token, config)
+                        .map_err(crate::Error::custom)?,
+                );
+            }
+
+            TokensWithFeed::try_from_records(records).map_err(crate::Error::custom)
+        }
+    }
+
+    impl BuybackPlan {
+        /// Get the remaining budget.
+        pub fn remaining(&self) -> u64 {
+            self.budget
+                .saturating_sub(self.spent)
+                .saturating_sub(self.pending)
+        }
+
+        /// Returns whether the budget has been used up.
+        pub fn is_completed(&self) -> bool {
+            self.remaining() == 0 && self.pending == 0
+        }
+
+        /// Get the market addresses of the swap path.
+        pub fn swap_path(&self) -> &[Pubkey] {
+            &self.swap_path[..usize::from(self.swap_path_len)]
+        }
+
+        /// Get the timestamp of the last slice.
+        pub fn last_slice_at(&self) -> Option<i64> {
+            (self.num_slices != 0).then_some(self.last_slice_at)
+        }
+
+        /// Get the earliest timestamp at which the next slice can be executed.
+        ///
+        /// Returns `None` if the budget has been used up.
+        pub fn next_slice_at(&self) -> Option<i64> {
+            if self.is_completed() {
+                return None;
+            }
+            Some(
+                self.last_slice_at()
+                    .map(|ts| ts.saturating_add(i64::from(self.interval)))
+                    .unwrap_or_default(),
+            )
+        }
+
+        /// Get the swap order created by the last slice.
+        pub fn last_order(&self) -> Option<&Pubkey> {
+            optional_address(&self.last_order)
+        }
+
+        /// Create tokens with feed.
+        pub fn to_feeds(&self, map: &impl TokenMapAccess) -> crate::Result<TokensWithFeed> {
+            use std::collections::BTreeSet;
+
+            let tokens = BTreeSet::from([self.swap_in_token, self.swap_out_token]);
+            let mut records = Vec::with_capacity(tokens.len());
+            for token in tokens.iter() {
+                let config = map
+                    .get(token)
+                    .ok_or_else(|| crate::Error::custom("unknown token"))?;
+                records.extend(
+                    TokenRecord::from_price_sources(*token, config)
+                        .map_err(crate::Error::custom)?,
+                );
+            }
 
             TokensWithFeed::try_from_records(records).map_err(crate::Error::custom)
         }
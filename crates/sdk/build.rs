@@ -9,6 +9,8 @@ fn main() {
         timelock: { feature = "timelock" },
         competition: { feature = "competition" },
         liquidity_provider: { feature = "liquidity-provider" },
+        gt_incentive: { feature = "gt-incentive" },
         simulation: { feature = "simulation" },
+        backtest: { feature = "backtest" },
     }
 }
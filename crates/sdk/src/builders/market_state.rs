@@ -74,10 +74,10 @@ impl IntoAtomicGroup for UpdateClosedState {
     fn into_atomic_group(self, hint: &Self::Hint) -> gmsol_solana_utils::Result<AtomicGroup> {
         let authority = &self.payer.0;
         let market = self.store_program.find_market_address(&self.market_token);
+        // The feeds must be sorted by tokens to match the ordered tokens of the market.
         let feeds = self
             .feeds_parser
-            .parse(&hint.to_tokens_with_feeds()?)
-            .collect::<Result<Vec<_>, _>>()
+            .parse_and_sort_by_tokens(&hint.to_tokens_with_feeds()?)
             .map_err(gmsol_solana_utils::Error::custom)?;
         let update = self
             .store_program
@@ -268,10 +268,10 @@ impl IntoAtomicGroup for UpdateFeesState {
     fn into_atomic_group(self, hint: &Self::Hint) -> gmsol_solana_utils::Result<AtomicGroup> {
         let authority = &self.payer.0;
         let market = self.store_program.find_market_address(&self.market_token);
+        // The feeds must be sorted by tokens to match the ordered tokens of the market.
         let feeds = self
             .feeds_parser
-            .parse(&hint.to_tokens_with_feeds()?)
-            .collect::<Result<Vec<_>, _>>()
+            .parse_and_sort_by_tokens(&hint.to_tokens_with_feeds()?)
             .map_err(gmsol_solana_utils::Error::custom)?;
         let virtual_inventories = hint
             .virtual_inventories
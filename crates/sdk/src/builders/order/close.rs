@@ -170,6 +170,7 @@ impl IntoAtomicGroup for CloseOrder {
                     callback_program,
                     callback_shared_data_account,
                     callback_partitioned_data_account,
+                    delegation: None,
                 },
                 true,
             )
@@ -1,5 +1,6 @@
 use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
 use gmsol_programs::gmsol_store::client::args;
+use gmsol_programs::gmsol_store::types::CreateOrderOptions;
 use gmsol_programs::gmsol_store::types::CreateOrderParams as StoreCreateOrderParams;
 use gmsol_programs::gmsol_store::{client::accounts, types::OrderKind};
 use gmsol_solana_utils::client_traits::FromRpcClientWith;
@@ -39,6 +40,8 @@ pub enum CreateOrderKind {
     LimitDecrease,
     /// Stop-loss Decrease.
     StopLossDecrease,
+    /// Trailing-stop Decrease.
+    TrailingStopDecrease,
 }
 
 impl From<CreateOrderKind> for OrderKind {
@@ -51,6 +54,7 @@ impl From<CreateOrderKind> for OrderKind {
             CreateOrderKind::LimitIncrease => Self::LimitIncrease,
             CreateOrderKind::LimitDecrease => Self::LimitDecrease,
             CreateOrderKind::StopLossDecrease => Self::StopLossDecrease,
+            CreateOrderKind::TrailingStopDecrease => Self::TrailingStopDecrease,
         }
     }
 }
@@ -70,9 +74,17 @@ impl CreateOrderKind {
     pub fn is_decrease(&self) -> bool {
         matches!(
             self,
-            Self::MarketDecrease | Self::LimitDecrease | Self::StopLossDecrease
+            Self::MarketDecrease
+                | Self::LimitDecrease
+                | Self::StopLossDecrease
+                | Self::TrailingStopDecrease
         )
     }
+
+    /// Returns whether the order kind is "trailing-stop".
+    pub fn is_trailing_stop(&self) -> bool {
+        matches!(self, Self::TrailingStopDecrease)
+    }
 }
 
 /// Swap type for decreasing position.
@@ -113,6 +125,107 @@ impl From<DecreasePositionSwapType>
     }
 }
 
+/// Trailing distance of a trailing-stop order.
+#[cfg_attr(js, derive(tsify_next::Tsify))]
+#[cfg_attr(js, tsify(from_wasm_abi))]
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(serde, serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy)]
+pub enum TrailingDistance {
+    /// A fixed distance from the best price (in unit price).
+    Absolute(u128),
+    /// A distance expressed as a factor of the best price.
+    Factor(u128),
+}
+
+impl TrailingDistance {
+    /// Returns the trigger price trailing the given best price by this distance.
+    ///
+    /// Returns `None` if overflow or the trigger price would fall to zero.
+    pub fn trigger_price(&self, best_price: u128, is_long: bool) -> Option<u128> {
+        use gmsol_model::utils::apply_factor;
+        use gmsol_programs::constants::MARKET_DECIMALS;
+
+        let delta = match self {
+            Self::Absolute(value) => *value,
+            Self::Factor(factor) => apply_factor::<_, MARKET_DECIMALS>(&best_price, factor)?,
+        };
+        if is_long {
+            best_price.checked_sub(delta).filter(|price| *price != 0)
+        } else {
+            best_price.checked_add(delta)
+        }
+    }
+}
+
+impl From<TrailingDistance> for gmsol_programs::gmsol_store::types::TrailingDistance {
+    fn from(distance: TrailingDistance) -> Self {
+        match distance {
+            TrailingDistance::Absolute(value) => Self::Absolute(value),
+            TrailingDistance::Factor(value) => Self::Factor(value),
+        }
+    }
+}
+
+impl From<TrailingDistance> for gmsol_utils::order::TrailingDistance {
+    fn from(distance: TrailingDistance) -> Self {
+        match distance {
+            TrailingDistance::Absolute(value) => Self::Absolute(value),
+            TrailingDistance::Factor(value) => Self::Factor(value),
+        }
+    }
+}
+
+impl From<gmsol_utils::order::TrailingDistance> for TrailingDistance {
+    fn from(distance: gmsol_utils::order::TrailingDistance) -> Self {
+        match distance {
+            gmsol_utils::order::TrailingDistance::Absolute(value) => Self::Absolute(value),
+            gmsol_utils::order::TrailingDistance::Factor(value) => Self::Factor(value),
+        }
+    }
+}
+
+/// TWAP (time-weighted average price) execution params.
+///
+/// Only market swap and market decrease orders can be executed in TWAP slices.
+#[cfg_attr(js, derive(tsify_next::Tsify))]
+#[cfg_attr(js, tsify(from_wasm_abi))]
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct TwapParams {
+    /// The total number of slices.
+    pub slices: u8,
+    /// The minimum interval between two slices in seconds.
+    pub interval: u32,
+}
+
+impl From<TwapParams> for gmsol_programs::gmsol_store::types::TwapParams {
+    fn from(params: TwapParams) -> Self {
+        Self {
+            slices: params.slices,
+            interval: params.interval,
+        }
+    }
+}
+
+impl From<TwapParams> for gmsol_utils::order::TwapParams {
+    fn from(params: TwapParams) -> Self {
+        Self {
+            slices: params.slices,
+            interval: params.interval,
+        }
+    }
+}
+
+impl From<gmsol_utils::order::TwapParams> for TwapParams {
+    fn from(params: gmsol_utils::order::TwapParams) -> Self {
+        Self {
+            slices: params.slices,
+            interval: params.interval,
+        }
+    }
+}
+
 /// Parameters for creating an order.
 #[cfg_attr(js, derive(tsify_next::Tsify))]
 #[cfg_attr(js, tsify(from_wasm_abi))]
@@ -156,6 +269,32 @@ pub struct CreateOrderParams {
     #[cfg_attr(serde, serde(default))]
     #[builder(default, setter(strip_option))]
     pub valid_from_ts: Option<i64>,
+    /// Trailing distance (trailing-stop orders only).
+    ///
+    /// For trailing-stop orders, the `trigger_price` is used as the initial best price.
+    #[cfg_attr(serde, serde(default))]
+    #[builder(default, setter(strip_option))]
+    pub trailing_distance: Option<TrailingDistance>,
+    /// TWAP params (market swap and market decrease orders only).
+    ///
+    /// The execution fee is charged once per slice.
+    #[cfg_attr(serde, serde(default))]
+    #[builder(default, setter(strip_option))]
+    pub twap: Option<TwapParams>,
+}
+
+impl CreateOrderParams {
+    /// Returns the effective trigger price.
+    ///
+    /// For trailing-stop orders, `trigger_price` is the initial best price, so the
+    /// effective trigger price is derived from it with the trailing distance.
+    pub fn effective_trigger_price(&self) -> Option<u128> {
+        let trigger_price = self.trigger_price?;
+        match &self.trailing_distance {
+            Some(distance) => distance.trigger_price(trigger_price, self.is_long),
+            None => Some(trigger_price),
+        }
+    }
 }
 
 /// Builder for the `create_order` instruction.
@@ -318,7 +457,8 @@ impl IntoAtomicGroup for CreateOrder {
             ),
             CreateOrderKind::MarketDecrease
             | CreateOrderKind::LimitDecrease
-            | CreateOrderKind::StopLossDecrease => (
+            | CreateOrderKind::StopLossDecrease
+            | CreateOrderKind::TrailingStopDecrease => (
                 None,
                 Some(
                     self.receive_token
@@ -393,6 +533,10 @@ impl IntoAtomicGroup for CreateOrder {
             should_unwrap_native_token: self.unwrap_native_on_receive,
             valid_from_ts: self.params.valid_from_ts,
         };
+        let options = CreateOrderOptions {
+            trailing_distance: self.params.trailing_distance.map(Into::into),
+            twap: self.params.twap.map(Into::into),
+        };
 
         if is_position_order && self.skip_position_creation && self.force_position_creation {
             return Err(crate::SolanaUtilsError::custom(
@@ -425,13 +569,22 @@ impl IntoAtomicGroup for CreateOrder {
             callback_partitioned_data_account,
         } = self.program.get_callback_params(self.callback.as_ref());
 
-        let create = self
-            .program
-            .anchor_instruction(args::CreateOrderV2 {
+        // Only use the new instruction when the options are required.
+        let create = if options.is_empty() {
+            self.program.anchor_instruction(args::CreateOrderV2 {
                 nonce: nonce.to_bytes(),
                 params,
                 callback_version,
             })
+        } else {
+            self.program.anchor_instruction(args::CreateOrderV3 {
+                nonce: nonce.to_bytes(),
+                params,
+                callback_version,
+                options,
+            })
+        };
+        let create = create
             .anchor_accounts(
                 accounts::CreateOrderV2 {
                     owner,
@@ -459,6 +612,8 @@ impl IntoAtomicGroup for CreateOrder {
                     callback_program,
                     callback_shared_data_account,
                     callback_partitioned_data_account,
+                    callback_program_config: callback_program
+                        .map(|program_id| self.program.find_callback_program_address(&program_id)),
                 },
                 true,
             )
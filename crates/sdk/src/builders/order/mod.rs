@@ -17,7 +17,8 @@ pub use self::{
     close::{CloseOrder, CloseOrderHint},
     create::{
         CreateOrder, CreateOrderHint, CreateOrderKind, CreateOrderParams, DecreasePositionSwapType,
+        TrailingDistance, TwapParams,
     },
     position::PreparePosition,
-    update::{UpdateOrder, UpdateOrderHint, UpdateOrderParams},
+    update::{LinkOrders, UpdateOrder, UpdateOrderHint, UpdateOrderParams},
 };
@@ -3,6 +3,7 @@ use gmsol_programs::gmsol_store::{
     types,
 };
 use gmsol_solana_utils::{AtomicGroup, IntoAtomicGroup, ProgramExt};
+use solana_sdk::instruction::AccountMeta;
 use typed_builder::TypedBuilder;
 
 use crate::{
@@ -13,6 +14,8 @@ use crate::{
     serde::StringPubkey,
 };
 
+use super::TrailingDistance;
+
 /// Builder for the `update_order` instruction.
 #[cfg_attr(js, derive(tsify_next::Tsify))]
 #[cfg_attr(js, tsify(from_wasm_abi))]
@@ -59,6 +62,14 @@ pub struct UpdateOrderParams {
     #[cfg_attr(serde, serde(default))]
     #[builder(default, setter(strip_option))]
     pub valid_from_ts: Option<i64>,
+    /// Trailing distance (trailing-stop orders only).
+    #[cfg_attr(serde, serde(default))]
+    #[builder(default, setter(strip_option))]
+    pub trailing_distance: Option<TrailingDistance>,
+    /// Reset the best price (trailing-stop orders only).
+    #[cfg_attr(serde, serde(default))]
+    #[builder(default, setter(strip_option))]
+    pub best_price: Option<u128>,
 }
 
 impl From<UpdateOrderParams> for types::UpdateOrderParams {
@@ -73,6 +84,16 @@ impl From<UpdateOrderParams> for types::UpdateOrderParams {
     }
 }
 
+impl UpdateOrderParams {
+    /// Get the update options.
+    pub fn options(&self) -> types::UpdateOrderOptions {
+        types::UpdateOrderOptions {
+            trailing_distance: self.trailing_distance.map(Into::into),
+            best_price: self.best_price,
+        }
+    }
+}
+
 /// Hint for [`UpdateOrder`].
 #[cfg_attr(js, derive(tsify_next::Tsify))]
 #[cfg_attr(js, tsify(from_wasm_abi))]
@@ -100,11 +121,17 @@ impl IntoAtomicGroup for UpdateOrder {
             ..
         } = self.program.get_callback_params(hint.callback.as_ref());
 
-        let update = self
-            .program
-            .anchor_instruction(args::UpdateOrderV2 {
-                params: self.params.into(),
-            })
+        let options = self.params.options();
+        let params = self.params.into();
+        // Only use the new instruction when the options are required.
+        let update = if options.is_empty() {
+            self.program
+                .anchor_instruction(args::UpdateOrderV2 { params })
+        } else {
+            self.program
+                .anchor_instruction(args::UpdateOrderV3 { params, options })
+        };
+        let update = update
             .anchor_accounts(
                 accounts::UpdateOrderV2 {
                     owner: payer,
@@ -166,6 +193,51 @@ impl IntoAtomicGroup for SetShouldKeepPositionAccount {
     }
 }
 
+/// Builder for the `link_orders` instruction.
+#[cfg_attr(js, derive(tsify_next::Tsify))]
+#[cfg_attr(js, tsify(from_wasm_abi))]
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct LinkOrders {
+    /// Program.
+    #[cfg_attr(serde, serde(default))]
+    #[builder(default)]
+    pub program: StoreProgram,
+    /// Payer (a.k.a. owner).
+    #[builder(setter(into))]
+    pub payer: StringPubkey,
+    /// Orders to link.
+    #[builder(setter(into))]
+    pub orders: Vec<StringPubkey>,
+}
+
+impl IntoAtomicGroup for LinkOrders {
+    type Hint = ();
+
+    fn into_atomic_group(self, _hint: &Self::Hint) -> gmsol_solana_utils::Result<AtomicGroup> {
+        let owner = self.payer.0;
+        let orders = self
+            .orders
+            .iter()
+            .map(|order| AccountMeta::new(order.0, false))
+            .collect::<Vec<_>>();
+        let ix = self
+            .program
+            .anchor_instruction(args::LinkOrders {})
+            .anchor_accounts(
+                accounts::LinkOrders {
+                    owner,
+                    store: self.program.store.0,
+                },
+                false,
+            )
+            .anchor_accounts(orders, false)
+            .build();
+
+        Ok(AtomicGroup::with_instructions(&self.payer, [ix]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use gmsol_solana_utils::transaction_builder::default_before_sign;
@@ -202,4 +274,22 @@ mod tests {
             )?;
         Ok(())
     }
+
+    #[test]
+    fn link_orders() -> crate::Result<()> {
+        let payer = Pubkey::new_unique();
+        let orders = [Pubkey::new_unique(), Pubkey::new_unique()];
+        LinkOrders::builder()
+            .payer(payer)
+            .orders(orders.iter().copied().map(Into::into).collect::<Vec<_>>())
+            .build()
+            .into_atomic_group(&())?
+            .partially_signed_transaction_with_blockhash_and_options(
+                Default::default(),
+                Default::default(),
+                None,
+                default_before_sign,
+            )?;
+        Ok(())
+    }
 }
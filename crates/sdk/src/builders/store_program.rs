@@ -145,6 +145,11 @@ impl StoreProgram {
         crate::pda::find_callback_authority(&self.id).0
     }
 
+    /// Find the PDA for callback program account.
+    pub fn find_callback_program_address(&self, program_id: &Pubkey) -> Pubkey {
+        crate::pda::find_callback_program_address(&self.store, program_id, &self.id).0
+    }
+
     /// Find the PDA for GLV account.
     pub fn find_glv_address(&self, glv_token: &Pubkey) -> Pubkey {
         crate::pda::find_glv_address(glv_token, &self.id).0
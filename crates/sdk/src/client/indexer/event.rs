@@ -0,0 +1,87 @@
+use gmsol_decode::gmsol::programs::GMSOLCPIEvent;
+use solana_sdk::pubkey::Pubkey;
+
+/// Keys used to index an event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventKeys {
+    /// Owner of the action or the position.
+    pub owner: Option<Pubkey>,
+    /// Market token.
+    pub market_token: Option<Pubkey>,
+    /// Position.
+    pub position: Option<Pubkey>,
+    /// Timestamp carried by the event.
+    pub ts: Option<i64>,
+}
+
+impl EventKeys {
+    /// Extract the keys of the given event.
+    ///
+    /// Returns `None` if the event is unknown and should not be indexed.
+    pub fn from_event(event: &GMSOLCPIEvent) -> Option<Self> {
+        use GMSOLCPIEvent as Event;
+
+        let keys = match event {
+            Event::TradeEvent(event) => Self {
+                owner: Some(event.user),
+                market_token: Some(event.market_token),
+                position: Some(event.position),
+                ts: Some(event.ts),
+            },
+            Event::OrderUpdated(event) => Self::action(event.owner, event.market_token, event.ts),
+            Event::OrderRemoved(event) => Self::action(event.owner, event.market_token, event.ts),
+            Event::DepositRemoved(event) => Self::action(event.owner, event.market_token, event.ts),
+            Event::WithdrawalRemoved(event) => {
+                Self::action(event.owner, event.market_token, event.ts)
+            }
+            Event::ShiftRemoved(event) => Self::action(event.owner, event.market_token, event.ts),
+            Event::GlvDepositRemoved(event) => {
+                Self::action(event.owner, event.market_token, event.ts)
+            }
+            Event::GlvWithdrawalRemoved(event) => {
+                Self::action(event.owner, event.market_token, event.ts)
+            }
+            Event::DepositExecuted(event) => Self::market(event.market_token),
+            Event::WithdrawalExecuted(event) => Self::market(event.market_token),
+            Event::PositionIncreased(event) => Self::market(event.market_token),
+            Event::PositionDecreased(event) => Self::market(event.market_token),
+            Event::SwapExecuted(event) => Self::market(event.market_token),
+            Event::MarketFeesUpdated(event) => Self::market(event.market_token),
+            Event::BorrowingFeesUpdated(event) => Self::market(event.market_token),
+            Event::MarketStateUpdated(event) => Self::market(event.market_token),
+            Event::MarketTokenValue(event) => Self::market(event.market_token),
+            Event::GlvPricing(event) => Self::market(event.market_token),
+            Event::InsufficientFundingFeePayment(event) => Self {
+                ts: Some(event.ts),
+                ..Self::market(event.market_token)
+            },
+            Event::GtUpdated(event) => Self {
+                owner: event.receiver,
+                ..Default::default()
+            },
+            Event::GtBuyback(event) => Self {
+                ts: Some(event.ts),
+                ..Default::default()
+            },
+            Event::GlvTokenValue(_) => Self::default(),
+            Event::UnknownOwnedData(_) => return None,
+        };
+        Some(keys)
+    }
+
+    fn action(owner: Pubkey, market_token: Pubkey, ts: i64) -> Self {
+        Self {
+            owner: Some(owner),
+            market_token: Some(market_token),
+            position: None,
+            ts: Some(ts),
+        }
+    }
+
+    fn market(market_token: Pubkey) -> Self {
+        Self {
+            market_token: Some(market_token),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,576 @@
+/// Event keys.
+pub mod event;
+
+/// Event store.
+pub mod store;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use futures_util::{Stream, TryStreamExt};
+use gmsol_decode::{decoder::TransactionDecoder, gmsol::programs::GMSOLCPIEvent, Decode};
+use gmsol_solana_utils::utils::WithSlot;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+
+use self::store::{Checkpoint, PendingEvent};
+
+pub use self::{
+    event::EventKeys,
+    store::{EventStore, IndexedEvent},
+};
+
+/// Default number of the transactions indexed in a page.
+pub const DEFAULT_PAGE_SIZE: usize = 1000;
+
+/// Source of the transactions to index.
+pub trait TransactionSource {
+    /// Get the signatures of the oldest `limit` transactions after `after` (exclusive),
+    /// ordered from the oldest to the newest.
+    fn signatures(
+        &self,
+        after: Option<&Signature>,
+        limit: usize,
+    ) -> impl Future<Output = crate::Result<Vec<WithSlot<Signature>>>>;
+
+    /// Get the transaction with the given signature.
+    fn transaction(
+        &self,
+        signature: &Signature,
+    ) -> impl Future<Output = crate::Result<EncodedConfirmedTransactionWithStatusMeta>>;
+}
+
+/// Transaction source backed by RPC.
+///
+/// The signatures can only be fetched from the newest to the oldest by RPC,
+/// so each page requires walking back to `after`, while only keeping the
+/// oldest `limit` signatures in memory. Use [`start_after`](Self::start_after)
+/// to avoid walking back through the whole history of the address.
+pub struct RpcTransactionSource {
+    client: Arc<RpcClient>,
+    address: Pubkey,
+    commitment: CommitmentConfig,
+    batch: Option<usize>,
+    start: Option<Signature>,
+}
+
+impl RpcTransactionSource {
+    /// Create a source following the transactions of the given address.
+    pub fn new(client: Arc<RpcClient>, address: &Pubkey, commitment: CommitmentConfig) -> Self {
+        Self {
+            client,
+            address: *address,
+            commitment,
+            batch: None,
+            start: None,
+        }
+    }
+
+    /// Set the batch size of the signature requests.
+    pub fn batch(mut self, batch: usize) -> Self {
+        self.batch = Some(batch);
+        self
+    }
+
+    /// Only follow the transactions after the given signature (exclusive)
+    /// when there is no checkpoint.
+    pub fn start_after(mut self, signature: Signature) -> Self {
+        self.start = Some(signature);
+        self
+    }
+}
+
+impl TransactionSource for RpcTransactionSource {
+    async fn signatures(
+        &self,
+        after: Option<&Signature>,
+        limit: usize,
+    ) -> crate::Result<Vec<WithSlot<Signature>>> {
+        use super::transaction_history::fetch_transaction_history_with_config;
+
+        let stream = fetch_transaction_history_with_config(
+            self.client.clone(),
+            &self.address,
+            self.commitment,
+            after.or(self.start.as_ref()).copied(),
+            None,
+            self.batch,
+        )
+        .await?;
+        oldest(stream, limit).await
+    }
+
+    async fn transaction(
+        &self,
+        signature: &Signature,
+    ) -> crate::Result<EncodedConfirmedTransactionWithStatusMeta> {
+        self.client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(self.commitment),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(crate::Error::custom)
+    }
+}
+
+/// Recorded transactions, ordered from the oldest to the newest.
+///
+/// The recorded transactions are stored in the same JSON format as the
+/// `getTransaction` RPC responses, making it possible to replay them
+/// without network access.
+#[derive(Debug, Clone, Default)]
+pub struct RecordedTransactions {
+    transactions: Vec<WithSlot<Signature>>,
+    // Kept in the JSON form since the decoded transactions are not `Clone`.
+    data: HashMap<Signature, serde_json::Value>,
+}
+
+impl RecordedTransactions {
+    /// Create from the given transactions.
+    pub fn new(
+        transactions: impl IntoIterator<Item = EncodedConfirmedTransactionWithStatusMeta>,
+    ) -> crate::Result<Self> {
+        let mut recorded = Self::default();
+        for transaction in transactions {
+            recorded.push(transaction)?;
+        }
+        Ok(recorded)
+    }
+
+    /// Parse recorded transactions from JSON.
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        let transactions: Vec<EncodedConfirmedTransactionWithStatusMeta> =
+            serde_json::from_str(json)?;
+        Self::new(transactions)
+    }
+
+    /// Serialize the recorded transactions into JSON.
+    pub fn to_json(&self) -> crate::Result<String> {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|signature| &self.data[signature.value()])
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_string_pretty(&transactions)?)
+    }
+
+    /// Record the transactions after `after` (exclusive) from the given source.
+    pub async fn record(
+        source: &impl TransactionSource,
+        after: Option<&Signature>,
+    ) -> crate::Result<Self> {
+        let mut recorded = Self::default();
+        let mut after = after.copied();
+        loop {
+            let signatures = source.signatures(after.as_ref(), DEFAULT_PAGE_SIZE).await?;
+            for signature in signatures.iter() {
+                recorded.push(source.transaction(signature.value()).await?)?;
+            }
+            if signatures.len() < DEFAULT_PAGE_SIZE {
+                break;
+            }
+            after = signatures.last().map(|signature| *signature.value());
+        }
+        Ok(recorded)
+    }
+
+    /// Append a transaction. It must be newer than the recorded ones.
+    pub fn push(
+        &mut self,
+        transaction: EncodedConfirmedTransactionWithStatusMeta,
+    ) -> crate::Result<&mut Self> {
+        let signature = transaction
+            .transaction
+            .transaction
+            .decode()
+            .and_then(|txn| txn.signatures.first().copied())
+            .ok_or_else(|| crate::Error::custom("[indexer] failed to decode transaction"))?;
+        if self.data.contains_key(&signature) {
+            return Err(crate::Error::custom(format!(
+                "[indexer] duplicate transaction: {signature}"
+            )));
+        }
+        let slot = transaction.slot;
+        self.data
+            .insert(signature, serde_json::to_value(transaction)?);
+        self.transactions.push(WithSlot::new(slot, signature));
+        Ok(self)
+    }
+
+    /// Returns the number of the recorded transactions.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns whether there are no recorded transactions.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+impl TransactionSource for RecordedTransactions {
+    async fn signatures(
+        &self,
+        after: Option<&Signature>,
+        limit: usize,
+    ) -> crate::Result<Vec<WithSlot<Signature>>> {
+        let start = match after {
+            Some(after) => self
+                .transactions
+                .iter()
+                .position(|signature| signature.value() == after)
+                .map(|idx| idx + 1)
+                .ok_or_else(|| {
+                    crate::Error::custom(format!("[indexer] unknown checkpoint: {after}"))
+                })?,
+            None => 0,
+        };
+        let end = start.saturating_add(limit).min(self.transactions.len());
+        Ok(self.transactions[start..end].to_vec())
+    }
+
+    async fn transaction(
+        &self,
+        signature: &Signature,
+    ) -> crate::Result<EncodedConfirmedTransactionWithStatusMeta> {
+        let transaction = self.data.get(signature).ok_or(crate::Error::NotFound)?;
+        Ok(serde_json::from_value(transaction.clone())?)
+    }
+}
+
+/// Summary of a sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// Number of the processed transactions.
+    pub transactions: usize,
+    /// Number of the indexed events.
+    pub events: usize,
+}
+
+/// Collect the oldest `limit` items of a stream yielding from the newest to the oldest,
+/// ordered from the oldest to the newest.
+async fn oldest<T>(
+    stream: impl Stream<Item = crate::Result<T>>,
+    limit: usize,
+) -> crate::Result<Vec<T>> {
+    let mut stream = pin!(stream);
+    let mut oldest = VecDeque::with_capacity(limit);
+    while let Some(item) = stream.try_next().await? {
+        if oldest.len() == limit {
+            oldest.pop_front();
+        }
+        if limit != 0 {
+            oldest.push_back(item);
+        }
+    }
+    Ok(oldest.into_iter().rev().collect())
+}
+
+/// Indexer of the CPI events emitted by a program.
+///
+/// The transactions are fetched and indexed page by page from the oldest to the newest.
+/// Each processed transaction is written together with the checkpoint,
+/// so an interrupted sync can be resumed from the last processed transaction.
+pub struct EventIndexer<S> {
+    source: S,
+    store: EventStore,
+    program_id: Pubkey,
+    event_authority: Pubkey,
+    page_size: usize,
+}
+
+impl<S: TransactionSource> EventIndexer<S> {
+    /// Create an indexer of the CPI events emitted by the given program.
+    pub fn new(source: S, store: EventStore, program_id: &Pubkey) -> Self {
+        Self {
+            source,
+            store,
+            program_id: *program_id,
+            event_authority: crate::pda::find_event_authority_address(program_id).0,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Set the maximum number of the transactions fetched in a page.
+    ///
+    /// Defaults to [`DEFAULT_PAGE_SIZE`].
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Get the event store.
+    pub fn store(&self) -> &EventStore {
+        &self.store
+    }
+
+    /// Get the transaction source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Index the transactions after the checkpoint.
+    pub async fn sync(&self) -> crate::Result<SyncSummary> {
+        let mut summary = SyncSummary::default();
+        loop {
+            let checkpoint = self.store.checkpoint()?;
+            let signatures = self
+                .source
+                .signatures(
+                    checkpoint.as_ref().map(|checkpoint| &checkpoint.signature),
+                    self.page_size,
+                )
+                .await?;
+            let is_last_page = signatures.len() < self.page_size;
+
+            for signature in signatures {
+                let slot = signature.slot();
+                let signature = *signature.value();
+                let transaction = self.source.transaction(&signature).await?;
+                let events = self.extract_events(slot, signature, &transaction)?;
+                self.store.insert_transaction(
+                    &Checkpoint { slot, signature },
+                    transaction.block_time,
+                    &events,
+                )?;
+                summary.transactions += 1;
+                summary.events += events.len();
+                tracing::debug!(%signature, events=%events.len(), "indexed transaction");
+            }
+
+            if is_last_page {
+                break;
+            }
+            tracing::info!(transactions=%summary.transactions, "indexed page");
+        }
+        Ok(summary)
+    }
+
+    /// Keep syncing with the given interval.
+    pub fn follow(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = crate::Result<SyncSummary>> + '_ {
+        async_stream::stream! {
+            loop {
+                yield self.sync().await;
+                tokio::time::sleep(interval).await;
+            }
+        }
+    }
+
+    fn extract_events(
+        &self,
+        slot: u64,
+        signature: Signature,
+        transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> crate::Result<Vec<PendingEvent>> {
+        let Some(meta) = transaction.transaction.meta.as_ref() else {
+            return Err(crate::Error::custom("[indexer] missing transaction meta"));
+        };
+        if meta.err.is_some() {
+            return Ok(Vec::default());
+        }
+
+        let mut decoder = TransactionDecoder::new(slot, signature, &transaction.transaction);
+        let events = decoder
+            .add_cpi_event_authority_and_program_id(self.event_authority, self.program_id)?
+            .extract_cpi_events()?;
+
+        let mut pending = Vec::with_capacity(events.events.len());
+        for (index, event) in events.events.into_iter().enumerate() {
+            let decoded = GMSOLCPIEvent::decode(&event)?;
+            let Some(keys) = EventKeys::from_event(&decoded) else {
+                tracing::debug!(%signature, index, "skipped unknown event");
+                continue;
+            };
+            pending.push(PendingEvent {
+                index: index.try_into()?,
+                keys,
+                event,
+            });
+        }
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSACTIONS: &str = include_str!("test_data/transactions.json");
+
+    const ALICE: &str = "955dG3DUKvbRxC7exyd81t8KdKkU4MCMkiCZEEXLApxc";
+    const BOB: &str = "DFK4931fqk7pnrqdcvDWozDABepb2BeTwE5DYfiRPi4j";
+    const MARKET_1: &str = "B9ZNLdrTa5xJmxGbPzmv6VUJn2RDwjDLaVbWHXVnstaD";
+    const MARKET_2: &str = "5WaA2eKzmL5Vvp791VvVXE6QkJ1GrcLrvE5HiEeNMtiH";
+    const POSITION_1: &str = "aze8XJewJF1762pmSqwuqJ5WfW6NSeNQMkcxCuxGCqt";
+
+    fn pubkey(address: &str) -> Pubkey {
+        address.parse().unwrap()
+    }
+
+    fn new_indexer(
+        transactions: RecordedTransactions,
+        store: EventStore,
+    ) -> EventIndexer<RecordedTransactions> {
+        EventIndexer::new(transactions, store, &gmsol_programs::gmsol_store::ID)
+    }
+
+    #[tokio::test]
+    async fn test_index_recorded_transactions() -> crate::Result<()> {
+        let transactions = RecordedTransactions::from_json(TRANSACTIONS)?;
+        assert_eq!(transactions.len(), 5);
+        let last = *transactions
+            .signatures(None, DEFAULT_PAGE_SIZE)
+            .await?
+            .last()
+            .unwrap()
+            .value();
+
+        let indexer = new_indexer(transactions, EventStore::in_memory()?);
+        let summary = indexer.sync().await?;
+        assert_eq!(
+            summary,
+            SyncSummary {
+                transactions: 5,
+                events: 5
+            }
+        );
+        assert_eq!(indexer.store().checkpoint()?.unwrap().signature, last);
+        assert_eq!(indexer.sync().await?, SyncSummary::default());
+
+        let store = indexer.store();
+        let events = store.events(..)?;
+        assert_eq!(events.len(), 5);
+        assert!(events.windows(2).all(|pair| pair[0].ts <= pair[1].ts));
+
+        let alice = store.events_by_owner(&pubkey(ALICE), ..)?;
+        assert_eq!(alice.len(), 3);
+        assert!(matches!(alice[0].event, GMSOLCPIEvent::TradeEvent(_)));
+        assert!(matches!(alice[1].event, GMSOLCPIEvent::OrderRemoved(_)));
+        assert_eq!(alice[0].signature, alice[1].signature);
+        assert_eq!((alice[0].index, alice[1].index), (0, 1));
+        let GMSOLCPIEvent::TradeEvent(trade) = &alice[2].event else {
+            panic!("expecting a trade event");
+        };
+        assert_eq!(trade.market_token, pubkey(MARKET_2));
+
+        assert_eq!(
+            store
+                .events_by_owner(&pubkey(ALICE), 1_700_000_300..)?
+                .len(),
+            1
+        );
+        assert!(store
+            .events_by_owner(&pubkey(ALICE), ..1_700_000_100)?
+            .is_empty());
+        assert_eq!(store.events_by_owner(&pubkey(BOB), ..)?.len(), 1);
+        assert_eq!(store.events_by_market(&pubkey(MARKET_1), ..)?.len(), 3);
+        assert_eq!(
+            store
+                .events_by_market(&pubkey(MARKET_1), 1_700_000_100..=1_700_000_100)?
+                .len(),
+            2
+        );
+
+        let position = store.events_by_position(&pubkey(POSITION_1), ..)?;
+        assert_eq!(position.len(), 1);
+        let GMSOLCPIEvent::TradeEvent(trade) = &position[0].event else {
+            panic!("expecting a trade event");
+        };
+        assert_eq!(trade.user, pubkey(ALICE));
+        assert_eq!(trade.trade_id, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_checkpoint() -> crate::Result<()> {
+        let transactions = RecordedTransactions::from_json(TRANSACTIONS)?;
+        let signatures = transactions.signatures(None, DEFAULT_PAGE_SIZE).await?;
+
+        let mut partial = RecordedTransactions::default();
+        for signature in &signatures[..2] {
+            partial.push(transactions.transaction(signature.value()).await?)?;
+        }
+
+        let indexer = new_indexer(partial, EventStore::in_memory()?);
+        assert_eq!(
+            indexer.sync().await?,
+            SyncSummary {
+                transactions: 2,
+                events: 3
+            }
+        );
+        let EventIndexer { store, .. } = indexer;
+        assert_eq!(
+            store.checkpoint()?.unwrap().signature,
+            *signatures[1].value()
+        );
+
+        let indexer = new_indexer(transactions, store);
+        assert_eq!(
+            indexer.sync().await?,
+            SyncSummary {
+                transactions: 3,
+                events: 2
+            }
+        );
+        assert_eq!(indexer.store().events(..)?.len(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_in_pages() -> crate::Result<()> {
+        let transactions = RecordedTransactions::from_json(TRANSACTIONS)?;
+        let signatures = transactions.signatures(None, DEFAULT_PAGE_SIZE).await?;
+
+        let values = |signatures: &[WithSlot<Signature>]| {
+            signatures
+                .iter()
+                .map(|signature| *signature.value())
+                .collect::<Vec<_>>()
+        };
+        let page = transactions.signatures(None, 2).await?;
+        assert_eq!(values(&page), values(&signatures[..2]));
+        let page = transactions
+            .signatures(Some(signatures[3].value()), 2)
+            .await?;
+        assert_eq!(values(&page), values(&signatures[4..]));
+
+        let indexer = new_indexer(transactions, EventStore::in_memory()?).page_size(2);
+        assert_eq!(
+            indexer.sync().await?,
+            SyncSummary {
+                transactions: 5,
+                events: 5
+            }
+        );
+        assert_eq!(
+            indexer.store().checkpoint()?.unwrap().signature,
+            *signatures[4].value()
+        );
+        assert_eq!(indexer.sync().await?, SyncSummary::default());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oldest_items() -> crate::Result<()> {
+        let newest_first = || futures_util::stream::iter((0..5).rev().map(Ok));
+        assert_eq!(oldest(newest_first(), 2).await?, [0, 1]);
+        assert_eq!(oldest(newest_first(), 10).await?, [0, 1, 2, 3, 4]);
+        assert!(oldest(newest_first(), 0).await?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    ops::{Bound, RangeBounds},
+    path::Path,
+};
+
+use gmsol_decode::{decoder::CPIEvent, gmsol::programs::GMSOLCPIEvent, Decode};
+use redb::{backends::InMemoryBackend, Database, TableDefinition};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use super::event::EventKeys;
+
+/// Events keyed by `slot | signature | index`.
+const EVENTS: TableDefinition<&[u8], &[u8]> = TableDefinition::new("events");
+/// Index keyed by `ts | event key`.
+const EVENTS_BY_TIME: TableDefinition<&[u8], ()> = TableDefinition::new("events_by_time");
+/// Index keyed by `owner | ts | event key`.
+const EVENTS_BY_OWNER: TableDefinition<&[u8], ()> = TableDefinition::new("events_by_owner");
+/// Index keyed by `market token | ts | event key`.
+const EVENTS_BY_MARKET: TableDefinition<&[u8], ()> = TableDefinition::new("events_by_market");
+/// Index keyed by `position | ts | event key`.
+const EVENTS_BY_POSITION: TableDefinition<&[u8], ()> = TableDefinition::new("events_by_position");
+/// Metadata of the store.
+const META: TableDefinition<&str, &[u8]> = TableDefinition::new("meta");
+
+const CHECKPOINT: &str = "checkpoint";
+
+const SIGNATURE_LEN: usize = 64;
+const EVENT_KEY_LEN: usize = 8 + SIGNATURE_LEN + 2;
+const TS_LEN: usize = 8;
+const PUBKEY_LEN: usize = 32;
+
+/// The last transaction processed by the indexer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Slot of the transaction.
+    pub slot: u64,
+    /// Signature of the transaction.
+    pub signature: Signature,
+}
+
+impl Checkpoint {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + SIGNATURE_LEN);
+        bytes.extend_from_slice(&self.slot.to_be_bytes());
+        bytes.extend_from_slice(self.signature.as_ref());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        if bytes.len() != 8 + SIGNATURE_LEN {
+            return Err(crate::Error::custom("[indexer] invalid checkpoint"));
+        }
+        let (slot, signature) = bytes.split_at(8);
+        Ok(Self {
+            slot: u64::from_be_bytes(slot.try_into().map_err(crate::Error::custom)?),
+            signature: Signature::try_from(signature).map_err(crate::Error::custom)?,
+        })
+    }
+}
+
+/// An event persisted in the [`EventStore`].
+#[derive(Debug)]
+pub struct IndexedEvent {
+    /// Signature of the transaction where the event was emitted.
+    pub signature: Signature,
+    /// Slot of the transaction.
+    pub slot: u64,
+    /// Index of the event in the transaction.
+    pub index: u16,
+    /// Timestamp of the event.
+    ///
+    /// Falls back to the block time if the event does not carry its own timestamp.
+    pub ts: i64,
+    /// The decoded event.
+    pub event: GMSOLCPIEvent,
+}
+
+/// An event to be written to the [`EventStore`].
+pub(super) struct PendingEvent {
+    pub(super) index: u16,
+    pub(super) keys: EventKeys,
+    pub(super) event: CPIEvent,
+}
+
+/// Embedded store of indexed events.
+pub struct EventStore {
+    db: Database,
+}
+
+impl EventStore {
+    /// Open (or create) an event store at the given path.
+    pub fn open(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let db = Database::create(path).map_err(crate::Error::custom)?;
+        Self::init(db)
+    }
+
+    /// Create an in-memory event store.
+    pub fn in_memory() -> crate::Result<Self> {
+        let db = Database::builder()
+            .create_with_backend(InMemoryBackend::new())
+            .map_err(crate::Error::custom)?;
+        Self::init(db)
+    }
+
+    fn init(db: Database) -> crate::Result<Self> {
+        let txn = db.begin_write().map_err(crate::Error::custom)?;
+        txn.open_table(EVENTS).map_err(crate::Error::custom)?;
+        for table in [
+            EVENTS_BY_TIME,
+            EVENTS_BY_OWNER,
+            EVENTS_BY_MARKET,
+            EVENTS_BY_POSITION,
+        ] {
+            txn.open_table(table).map_err(crate::Error::custom)?;
+        }
+        txn.open_table(META).map_err(crate::Error::custom)?;
+        txn.commit().map_err(crate::Error::custom)?;
+        Ok(Self { db })
+    }
+
+    /// Get the last processed transaction.
+    pub fn checkpoint(&self) -> crate::Result<Option<Checkpoint>> {
+        let txn = self.db.begin_read().map_err(crate::Error::custom)?;
+        let meta = txn.open_table(META).map_err(crate::Error::custom)?;
+        meta.get(CHECKPOINT)
+            .map_err(crate::Error::custom)?
+            .map(|value| Checkpoint::from_bytes(value.value()))
+            .transpose()
+    }
+
+    /// Write the events of a transaction and advance the checkpoint atomically.
+    pub(super) fn insert_transaction(
+        &self,
+        checkpoint: &Checkpoint,
+        block_time: Option<i64>,
+        events: &[PendingEvent],
+    ) -> crate::Result<()> {
+        let txn = self.db.begin_write().map_err(crate::Error::custom)?;
+        {
+            let mut table = txn.open_table(EVENTS).map_err(crate::Error::custom)?;
+            let mut by_time = txn
+                .open_table(EVENTS_BY_TIME)
+                .map_err(crate::Error::custom)?;
+            let mut by_owner = txn
+                .open_table(EVENTS_BY_OWNER)
+                .map_err(crate::Error::custom)?;
+            let mut by_market = txn
+                .open_table(EVENTS_BY_MARKET)
+                .map_err(crate::Error::custom)?;
+            let mut by_position = txn
+                .open_table(EVENTS_BY_POSITION)
+                .map_err(crate::Error::custom)?;
+
+            for pending in events {
+                let key = event_key(checkpoint.slot, &checkpoint.signature, pending.index);
+                let ts = pending.keys.ts.or(block_time).unwrap_or_default();
+
+                let data = pending.event.data();
+                let mut value = Vec::with_capacity(TS_LEN + PUBKEY_LEN + data.len());
+                value.extend_from_slice(&ts.to_be_bytes());
+                value.extend_from_slice(pending.event.program_id().as_ref());
+                value.extend_from_slice(data);
+                table
+                    .insert(key.as_slice(), value.as_slice())
+                    .map_err(crate::Error::custom)?;
+
+                by_time
+                    .insert(index_key(&[], ts, &key).as_slice(), ())
+                    .map_err(crate::Error::custom)?;
+                if let Some(owner) = pending.keys.owner.as_ref() {
+                    by_owner
+                        .insert(index_key(owner.as_ref(), ts, &key).as_slice(), ())
+                        .map_err(crate::Error::custom)?;
+                }
+                if let Some(market_token) = pending.keys.market_token.as_ref() {
+                    by_market
+                        .insert(index_key(market_token.as_ref(), ts, &key).as_slice(), ())
+                        .map_err(crate::Error::custom)?;
+                }
+                if let Some(position) = pending.keys.position.as_ref() {
+                    by_position
+                        .insert(index_key(position.as_ref(), ts, &key).as_slice(), ())
+                        .map_err(crate::Error::custom)?;
+                }
+            }
+
+            let mut meta = txn.open_table(META).map_err(crate::Error::custom)?;
+            meta.insert(CHECKPOINT, checkpoint.to_bytes().as_slice())
+                .map_err(crate::Error::custom)?;
+        }
+        txn.commit().map_err(crate::Error::custom)?;
+        Ok(())
+    }
+
+    /// Get the events in the given time range.
+    pub fn events(&self, range: impl RangeBounds<i64>) -> crate::Result<Vec<IndexedEvent>> {
+        self.query(EVENTS_BY_TIME, &[], range)
+    }
+
+    /// Get the events of the given owner in the given time range.
+    pub fn events_by_owner(
+        &self,
+        owner: &Pubkey,
+        range: impl RangeBounds<i64>,
+    ) -> crate::Result<Vec<IndexedEvent>> {
+        self.query(EVENTS_BY_OWNER, owner.as_ref(), range)
+    }
+
+    /// Get the events of the given market in the given time range.
+    pub fn events_by_market(
+        &self,
+        market_token: &Pubkey,
+        range: impl RangeBounds<i64>,
+    ) -> crate::Result<Vec<IndexedEvent>> {
+        self.query(EVENTS_BY_MARKET, market_token.as_ref(), range)
+    }
+
+    /// Get the events of the given position in the given time range.
+    pub fn events_by_position(
+        &self,
+        position: &Pubkey,
+        range: impl RangeBounds<i64>,
+    ) -> crate::Result<Vec<IndexedEvent>> {
+        self.query(EVENTS_BY_POSITION, position.as_ref(), range)
+    }
+
+    fn query(
+        &self,
+        index: TableDefinition<&[u8], ()>,
+        prefix: &[u8],
+        range: impl RangeBounds<i64>,
+    ) -> crate::Result<Vec<IndexedEvent>> {
+        let Some((start, end)) = ts_range(range) else {
+            return Ok(Vec::default());
+        };
+        let lower = index_key(prefix, start, &[]);
+        let upper = index_key(prefix, end, &[u8::MAX; EVENT_KEY_LEN]);
+
+        let txn = self.db.begin_read().map_err(crate::Error::custom)?;
+        let index = txn.open_table(index).map_err(crate::Error::custom)?;
+        let table = txn.open_table(EVENTS).map_err(crate::Error::custom)?;
+
+        let mut events = Vec::default();
+        for entry in index
+            .range(lower.as_slice()..=upper.as_slice())
+            .map_err(crate::Error::custom)?
+        {
+            let (key, _) = entry.map_err(crate::Error::custom)?;
+            let key = key.value();
+            let key = &key[key.len() - EVENT_KEY_LEN..];
+            let value = table
+                .get(key)
+                .map_err(crate::Error::custom)?
+                .ok_or_else(|| crate::Error::custom("[indexer] missing indexed event"))?;
+            events.push(decode_event(key, value.value())?);
+        }
+        Ok(events)
+    }
+}
+
+fn event_key(slot: u64, signature: &Signature, index: u16) -> [u8; EVENT_KEY_LEN] {
+    let mut key = [0; EVENT_KEY_LEN];
+    key[..8].copy_from_slice(&slot.to_be_bytes());
+    key[8..8 + SIGNATURE_LEN].copy_from_slice(signature.as_ref());
+    key[8 + SIGNATURE_LEN..].copy_from_slice(&index.to_be_bytes());
+    key
+}
+
+/// Map `i64` to bytes preserving the order.
+fn sortable_ts(ts: i64) -> [u8; TS_LEN] {
+    ((ts as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+fn index_key(prefix: &[u8], ts: i64, suffix: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(prefix.len() + TS_LEN + suffix.len());
+    key.extend_from_slice(prefix);
+    key.extend_from_slice(&sortable_ts(ts));
+    key.extend_from_slice(suffix);
+    key
+}
+
+/// Convert the range into an inclusive range, returns `None` if it is empty.
+fn ts_range(range: impl RangeBounds<i64>) -> Option<(i64, i64)> {
+    let start = match range.start_bound() {
+        Bound::Included(ts) => *ts,
+        Bound::Excluded(ts) => ts.checked_add(1)?,
+        Bound::Unbounded => i64::MIN,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(ts) => *ts,
+        Bound::Excluded(ts) => ts.checked_sub(1)?,
+        Bound::Unbounded => i64::MAX,
+    };
+    (start <= end).then_some((start, end))
+}
+
+fn decode_event(key: &[u8], value: &[u8]) -> crate::Result<IndexedEvent> {
+    if value.len() < TS_LEN + PUBKEY_LEN {
+        return Err(crate::Error::custom("[indexer] invalid event data"));
+    }
+    let (slot, rest) = key.split_at(8);
+    let (signature, index) = rest.split_at(SIGNATURE_LEN);
+    let (ts, rest) = value.split_at(TS_LEN);
+    let (program_id, data) = rest.split_at(PUBKEY_LEN);
+
+    let program_id = Pubkey::try_from(program_id).map_err(crate::Error::custom)?;
+    let event = GMSOLCPIEvent::decode(&CPIEvent::new(program_id, data.to_vec()))?;
+
+    Ok(IndexedEvent {
+        signature: Signature::try_from(signature).map_err(crate::Error::custom)?,
+        slot: u64::from_be_bytes(slot.try_into().map_err(crate::Error::custom)?),
+        index: u16::from_be_bytes(index.try_into().map_err(crate::Error::custom)?),
+        ts: i64::from_be_bytes(ts.try_into().map_err(crate::Error::custom)?),
+        event,
+    })
+}
@@ -1,7 +1,10 @@
 use bytes::Bytes;
 use gmsol_programs::{
     anchor_lang::{self, AccountDeserialize},
-    gmsol_timelock::{accounts::InstructionHeader, ID},
+    gmsol_timelock::{
+        accounts::{Executor, InstructionHeader, TimelockConfig},
+        ID,
+    },
 };
 use gmsol_utils::{
     dynamic_access::get,
@@ -22,6 +25,14 @@ pub struct InstructionBuffer {
     accounts: Bytes,
 }
 
+impl InstructionBuffer {
+    /// Get the delay of the instruction, honoring the delay overrides of
+    /// the `config` and the min delay of the `executor`.
+    pub fn delay(&self, config: &TimelockConfig, executor: &Executor) -> u32 {
+        config.delay_for(executor, &self.header.program_id, &self.data)
+    }
+}
+
 impl InstructionAccess for InstructionBuffer {
     fn wallet(&self) -> Result<Pubkey, InstructionError> {
         match create_executor_wallet_pda(&self.header.executor, self.header.wallet_bump, &ID) {
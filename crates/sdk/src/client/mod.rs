@@ -51,6 +51,10 @@ pub mod squads;
 #[cfg(feature = "chaoslabs-risk-oracle")]
 pub mod risk_oracle;
 
+/// Local indexer of the store program events.
+#[cfg(feature = "indexer")]
+pub mod indexer;
+
 use std::{
     collections::BTreeMap,
     ops::Deref,
@@ -496,6 +500,16 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
         crate::pda::find_user_address(store, owner, self.store_program_id()).0
     }
 
+    /// Find PDA for user delegation account.
+    pub fn find_user_delegation_address(
+        &self,
+        store: &Pubkey,
+        owner: &Pubkey,
+        delegate: &Pubkey,
+    ) -> Pubkey {
+        crate::pda::find_user_delegation_address(store, owner, delegate, self.store_program_id()).0
+    }
+
     /// Find PDA for referral code.
     pub fn find_referral_code_address(&self, store: &Pubkey, code: ReferralCodeBytes) -> Pubkey {
         crate::pda::find_referral_code_address(store, code, self.store_program_id()).0
@@ -601,6 +615,27 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
         crate::pda::find_treasury_receiver_address(config, self.treasury_program_id()).0
     }
 
+    /// Find PDA for buyback plan.
+    pub fn find_buyback_plan_address(
+        &self,
+        config: &Pubkey,
+        swap_in_token: &Pubkey,
+        swap_out_token: &Pubkey,
+    ) -> Pubkey {
+        crate::pda::find_buyback_plan_address(
+            config,
+            swap_in_token,
+            swap_out_token,
+            self.treasury_program_id(),
+        )
+        .0
+    }
+
+    /// Find PDA for buyback owner.
+    pub fn find_buyback_owner_address(&self, buyback_plan: &Pubkey) -> Pubkey {
+        crate::pda::find_buyback_owner_address(buyback_plan, self.treasury_program_id()).0
+    }
+
     /// Find PDA for timelock config.
     pub fn find_timelock_config_address(&self, store: &Pubkey) -> Pubkey {
         crate::pda::find_timelock_config_address(store, self.timelock_program_id()).0
@@ -621,6 +656,11 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
         crate::pda::find_callback_authority(self.store_program_id()).0
     }
 
+    /// Find the PDA for callback program account.
+    pub fn find_callback_program_address(&self, store: &Pubkey, program_id: &Pubkey) -> Pubkey {
+        crate::pda::find_callback_program_address(store, program_id, self.store_program_id()).0
+    }
+
     /// Find the PDA for virtual inventory for swaps.
     pub fn find_virtual_inventory_for_swaps_address(&self, store: &Pubkey, index: u32) -> Pubkey {
         crate::pda::find_virtual_inventory_for_swaps_address(store, index, self.store_program_id())
@@ -641,6 +681,11 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
         .0
     }
 
+    /// Find the PDA for risk oracle.
+    pub fn find_risk_oracle_address(&self, store: &Pubkey) -> Pubkey {
+        crate::pda::find_risk_oracle_address(store, self.store_program_id()).0
+    }
+
     pub(crate) fn get_callback_params(&self, callback: Option<&Callback>) -> CallbackParams {
         match callback {
             Some(callback) => CallbackParams {
@@ -1006,6 +1051,79 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
         Ok(positions)
     }
 
+    /// Fetch all [`UserDelegation`](store_accounts::UserDelegation) accounts of the given
+    /// owner or delegate.
+    pub async fn user_delegations(
+        &self,
+        store: &Pubkey,
+        owner: Option<&Pubkey>,
+        delegate: Option<&Pubkey>,
+    ) -> crate::Result<BTreeMap<Pubkey, store_accounts::UserDelegation>> {
+        let filter = match (owner, delegate) {
+            (Some(owner), delegate) => {
+                let mut bytes = owner.as_ref().to_owned();
+                if let Some(delegate) = delegate {
+                    bytes.extend_from_slice(delegate.as_ref());
+                }
+                Some(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    bytemuck::offset_of!(store_accounts::UserDelegation, owner) + DISC_OFFSET,
+                    &bytes,
+                )))
+            }
+            (None, Some(delegate)) => Some(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                bytemuck::offset_of!(store_accounts::UserDelegation, delegate) + DISC_OFFSET,
+                delegate.as_ref(),
+            ))),
+            (None, None) => None,
+        };
+
+        let store_filter = StoreFilter::new(
+            store,
+            bytemuck::offset_of!(store_accounts::UserDelegation, store),
+        );
+
+        let delegations = self
+            .store_accounts::<ZeroCopy<store_accounts::UserDelegation>>(Some(store_filter), filter)
+            .await?
+            .into_iter()
+            .map(|(pubkey, d)| (pubkey, d.0))
+            .collect();
+
+        Ok(delegations)
+    }
+
+    /// Fetch [`Oracle`](store_accounts::Oracle) account with its address.
+    pub async fn oracle(&self, address: &Pubkey) -> crate::Result<store_accounts::Oracle> {
+        let oracle = self
+            .account::<ZeroCopy<store_accounts::Oracle>>(address)
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        Ok(oracle.0)
+    }
+
+    /// Fetch [`RiskOracle`](store_accounts::RiskOracle) account of the given store.
+    pub async fn risk_oracle(&self, store: &Pubkey) -> crate::Result<store_accounts::RiskOracle> {
+        let risk_oracle = self
+            .account::<ZeroCopy<store_accounts::RiskOracle>>(&self.find_risk_oracle_address(store))
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        Ok(risk_oracle.0)
+    }
+
+    /// Fetch the [`CallbackProgram`](store_accounts::CallbackProgram) account of the given program.
+    pub async fn callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+    ) -> crate::Result<Option<store_accounts::CallbackProgram>> {
+        let callback_program = self
+            .account::<ZeroCopy<store_accounts::CallbackProgram>>(
+                &self.find_callback_program_address(store, program_id),
+            )
+            .await?;
+        Ok(callback_program.map(|account| account.0))
+    }
+
     /// Fetch [`Position`](store_accounts::Position) account with its address.
     pub async fn position(&self, address: &Pubkey) -> crate::Result<store_accounts::Position> {
         let position = self
@@ -1335,6 +1453,20 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
         Ok(events)
     }
 
+    /// Create an indexer persisting the CPI events of the store program into the given store.
+    #[cfg(feature = "indexer")]
+    pub fn store_event_indexer(
+        &self,
+        store: indexer::EventStore,
+    ) -> indexer::EventIndexer<indexer::RpcTransactionSource> {
+        let source = indexer::RpcTransactionSource::new(
+            Arc::new(self.store_program().rpc()),
+            &self.store_event_authority(),
+            self.commitment(),
+        );
+        indexer::EventIndexer::new(source, store, self.store_program_id())
+    }
+
     /// Wait for an order to be completed using current slot as min context slot.
     #[cfg(feature = "decode")]
     pub async fn complete_order(
@@ -1511,6 +1643,30 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
             .collect())
     }
 
+    /// Get the buyback plans of the treasury of the given store.
+    pub async fn buyback_plans(
+        &self,
+        store: &Pubkey,
+    ) -> crate::Result<BTreeMap<Pubkey, gmsol_programs::gmsol_treasury::accounts::BuybackPlan>>
+    {
+        use gmsol_programs::gmsol_treasury::accounts::BuybackPlan;
+
+        let config = self.find_treasury_config_address(store);
+        let config_filter = RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            DISC_OFFSET + bytemuck::offset_of!(BuybackPlan, config),
+            config.as_ref(),
+        ));
+        accounts_lazy_with_context::<ZeroCopy<BuybackPlan>, _>(
+            self.treasury_program(),
+            Some(config_filter),
+            ProgramAccountsConfig::default(),
+        )
+        .await?
+        .into_value()
+        .map(|res| res.map(|(address, plan)| (address, plan.0)))
+        .collect()
+    }
+
     /// Fetch [`InstructionBuffer`] account with its address.
     pub async fn instruction_buffer(
         &self,
@@ -1518,6 +1674,17 @@ impl<C: Clone + Deref<Target = impl Signer>> Client<C> {
     ) -> crate::Result<Option<InstructionBuffer>> {
         self.account::<InstructionBuffer>(address).await
     }
+
+    /// Fetch timelock [`Proposal`](gmsol_programs::gmsol_timelock::accounts::Proposal) account with its address.
+    pub async fn timelock_proposal(
+        &self,
+        address: &Pubkey,
+    ) -> crate::Result<Option<gmsol_programs::gmsol_timelock::accounts::Proposal>> {
+        Ok(self
+            .account::<ZeroCopy<gmsol_programs::gmsol_timelock::accounts::Proposal>>(address)
+            .await?
+            .map(|proposal| proposal.0))
+    }
 }
 
 /// Store Filter.
@@ -1,11 +1,45 @@
-use std::ops::Deref;
+use std::{future::Future, ops::Deref};
 
-use gmsol_programs::gmsol_competition::{
-    client::{accounts, args},
-    ID,
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use gmsol_programs::{
+    anchor_lang::Discriminator,
+    gmsol_competition::{
+        accounts::{Competition, LeaderboardPage, Participant, PrizePool},
+        client::{accounts, args},
+        constants::{LEADERBOARD_PAGE_LEN, ROI_DECIMALS},
+        types::{LeaderEntry, ScoringMode},
+        ID,
+    },
 };
-use gmsol_solana_utils::transaction_builder::TransactionBuilder;
-use solana_sdk::{pubkey::Pubkey, signer::Signer, system_program};
+use gmsol_solana_utils::{bundle_builder::BundleBuilder, transaction_builder::TransactionBuilder};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_config::RpcAccountInfoConfig,
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signer::Signer, system_program};
+
+use crate::client::accounts::{
+    accounts_lazy_with_context, get_account_with_context, get_program_accounts_with_context,
+    ProgramAccountsConfig, ProgramAccountsConfigForRpc,
+};
+
+use super::token_account::TokenAccountOps;
+
+/// The offset of the `competition` field in the [`Participant`] account data.
+const PARTICIPANT_COMPETITION_OFFSET: usize = 8 + 1;
+
+/// The size of a [`Participant`] account in the legacy layout.
+const LEGACY_PARTICIPANT_LEN: u64 = 8 + 105;
+
+/// The size of a [`Competition`] account in the legacy layout.
+const LEGACY_COMPETITION_LEN: usize = 8 + 367;
+
+/// The default number of participants to rank per transaction.
+const DEFAULT_RANK_CHUNK_SIZE: usize = 16;
+
+/// The default number of traders to pay per transaction.
+const DEFAULT_PRIZE_CHUNK_SIZE: usize = 8;
 
 /// Operations for competition.
 pub trait CompetitionOps<C> {
@@ -21,6 +55,102 @@ pub trait CompetitionOps<C> {
 
     /// Close a participant account.
     fn close_participant(&self, competition: &Pubkey) -> TransactionBuilder<C>;
+
+    /// Fetch all participants of the given competition, sorted by rank.
+    ///
+    /// The participants are ranked in the same order as the final standings.
+    fn competition_participants(
+        &self,
+        competition: &Pubkey,
+    ) -> impl Future<Output = crate::Result<Vec<(Pubkey, Participant)>>>;
+
+    /// Fetch the final standings of the given competition from its leaderboard pages.
+    fn competition_standings(
+        &self,
+        competition: &Pubkey,
+    ) -> impl Future<Output = crate::Result<Vec<LeaderEntry>>>;
+
+    /// Append the given participants to the final standings.
+    fn append_leaderboard_page(
+        &self,
+        competition: &Pubkey,
+        page_index: u32,
+        participants: &[Pubkey],
+    ) -> TransactionBuilder<C>;
+
+    /// Finalize the standings of the given competition.
+    fn finalize_standings(&self, competition: &Pubkey) -> TransactionBuilder<C>;
+
+    /// Create a [`BundleBuilder`] to rank all the unranked participants of the given competition
+    /// and finalize its standings.
+    fn rank_competition(
+        &self,
+        competition: &Pubkey,
+        chunk_size: Option<usize>,
+    ) -> impl Future<Output = crate::Result<BundleBuilder<C>>>;
+
+    /// Migrate a competition account from the legacy layout.
+    fn migrate_competition(&self, competition: &Pubkey) -> TransactionBuilder<C>;
+
+    /// Migrate a participant account from the legacy layout.
+    fn migrate_participant(
+        &self,
+        competition: &Pubkey,
+        participant: &Pubkey,
+    ) -> TransactionBuilder<C>;
+
+    /// Create a [`BundleBuilder`] to migrate the given competition and all its participants
+    /// that are still in the legacy layout.
+    fn migrate_competition_accounts(
+        &self,
+        competition: &Pubkey,
+    ) -> impl Future<Output = crate::Result<BundleBuilder<C>>>;
+
+    /// Initialize the prize pool of a competition.
+    fn initialize_prize_pool(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        schedule: Vec<u16>,
+    ) -> TransactionBuilder<C, Pubkey>;
+
+    /// Fund the prize pool of a competition.
+    fn fund_prize_pool(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        source: Option<&Pubkey>,
+        amount: u64,
+    ) -> TransactionBuilder<C>;
+
+    /// Pay the prizes to the given traders, which must be ranked in the given page.
+    fn distribute_prizes(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        page_index: u32,
+        traders: &[Pubkey],
+    ) -> TransactionBuilder<C>;
+
+    /// Create a [`BundleBuilder`] to pay all the unpaid prizes of the given competition.
+    fn distribute_competition_prizes(
+        &self,
+        competition: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        chunk_size: Option<usize>,
+    ) -> impl Future<Output = crate::Result<BundleBuilder<C>>>;
+
+    /// Reclaim the remaining prizes after the distribution is completed.
+    fn reclaim_prizes(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        receiver: Option<&Pubkey>,
+    ) -> TransactionBuilder<C>;
 }
 
 impl<C: Deref<Target = impl Signer> + Clone> CompetitionOps<C> for crate::Client<C> {
@@ -71,6 +201,381 @@ impl<C: Deref<Target = impl Signer> + Clone> CompetitionOps<C> for crate::Client
                 participant,
             })
     }
+
+    async fn competition_participants(
+        &self,
+        competition: &Pubkey,
+    ) -> crate::Result<Vec<(Pubkey, Participant)>> {
+        let filter = RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            PARTICIPANT_COMPETITION_OFFSET,
+            competition.as_ref(),
+        ));
+        let mode = self
+            .account::<Competition>(competition)
+            .await?
+            .ok_or(crate::Error::NotFound)?
+            .scoring_mode;
+        let mut participants: Vec<(Pubkey, Participant)> = accounts_lazy_with_context(
+            &self.program(ID),
+            Some(filter),
+            ProgramAccountsConfig::default(),
+        )
+        .await?
+        .into_value()
+        .collect::<crate::Result<_>>()?;
+        participants.sort_by(|(_, a), (_, b)| {
+            participant_score(b, &mode)
+                .cmp(&participant_score(a, &mode))
+                .then_with(|| a.trader.cmp(&b.trader))
+        });
+        Ok(participants)
+    }
+
+    async fn competition_standings(&self, competition: &Pubkey) -> crate::Result<Vec<LeaderEntry>> {
+        let account = self
+            .account::<Competition>(competition)
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        let page_len = u32::from(LEADERBOARD_PAGE_LEN);
+        let page_count = account.ranked_count.div_ceil(page_len);
+        let mut standings = Vec::with_capacity(account.ranked_count as usize);
+        for index in 0..page_count {
+            let page = crate::pda::find_leaderboard_page_address(competition, index, &ID).0;
+            let page = self
+                .account::<LeaderboardPage>(&page)
+                .await?
+                .ok_or(crate::Error::NotFound)?;
+            standings.extend(page.entries);
+        }
+        Ok(standings)
+    }
+
+    fn append_leaderboard_page(
+        &self,
+        competition: &Pubkey,
+        page_index: u32,
+        participants: &[Pubkey],
+    ) -> TransactionBuilder<C> {
+        let page = crate::pda::find_leaderboard_page_address(competition, page_index, &ID).0;
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::AppendLeaderboardPage { page_index })
+            .anchor_accounts(accounts::AppendLeaderboardPage {
+                authority: self.payer(),
+                competition: *competition,
+                page,
+                system_program: system_program::ID,
+            })
+            .accounts(
+                participants
+                    .iter()
+                    .map(|participant| AccountMeta::new_readonly(*participant, false))
+                    .collect(),
+            )
+    }
+
+    fn finalize_standings(&self, competition: &Pubkey) -> TransactionBuilder<C> {
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::FinalizeStandings {})
+            .anchor_accounts(accounts::FinalizeStandings {
+                authority: self.payer(),
+                competition: *competition,
+            })
+    }
+
+    async fn rank_competition(
+        &self,
+        competition: &Pubkey,
+        chunk_size: Option<usize>,
+    ) -> crate::Result<BundleBuilder<C>> {
+        let account = self
+            .account::<Competition>(competition)
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        let participants = self.competition_participants(competition).await?;
+        let chunk_size = chunk_size.unwrap_or(DEFAULT_RANK_CHUNK_SIZE).max(1);
+        let page_len = usize::from(LEADERBOARD_PAGE_LEN);
+
+        let mut bundle = self.bundle();
+        let mut rank = account.ranked_count as usize;
+        while rank < participants.len() {
+            let page_end = (rank / page_len + 1) * page_len;
+            let end = (rank + chunk_size).min(page_end).min(participants.len());
+            let addresses = participants[rank..end]
+                .iter()
+                .map(|(address, _)| *address)
+                .collect::<Vec<_>>();
+            let page_index = (rank / page_len) as u32;
+            bundle
+                .try_push(self.append_leaderboard_page(competition, page_index, &addresses))
+                .map_err(|(_, err)| err)?;
+            rank = end;
+        }
+        if !account.standings_finalized {
+            bundle
+                .try_push(self.finalize_standings(competition))
+                .map_err(|(_, err)| err)?;
+        }
+        Ok(bundle)
+    }
+
+    fn migrate_competition(&self, competition: &Pubkey) -> TransactionBuilder<C> {
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::MigrateCompetition {})
+            .anchor_accounts(accounts::MigrateCompetition {
+                authority: self.payer(),
+                competition: *competition,
+                system_program: system_program::ID,
+            })
+    }
+
+    fn migrate_participant(
+        &self,
+        competition: &Pubkey,
+        participant: &Pubkey,
+    ) -> TransactionBuilder<C> {
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::MigrateParticipant {})
+            .anchor_accounts(accounts::MigrateParticipant {
+                payer: self.payer(),
+                competition: *competition,
+                participant: *participant,
+                system_program: system_program::ID,
+            })
+    }
+
+    async fn migrate_competition_accounts(
+        &self,
+        competition: &Pubkey,
+    ) -> crate::Result<BundleBuilder<C>> {
+        let account = get_account_with_context(self.rpc(), competition, Default::default())
+            .await?
+            .into_value()
+            .ok_or(crate::Error::NotFound)?;
+
+        let config = ProgramAccountsConfigForRpc {
+            filters: Some(vec![
+                RpcFilterType::DataSize(LEGACY_PARTICIPANT_LEN),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, Participant::DISCRIMINATOR)),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    PARTICIPANT_COMPETITION_OFFSET,
+                    competition.as_ref(),
+                )),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                ..Default::default()
+            },
+        };
+        let participants = get_program_accounts_with_context(self.rpc(), &ID, config)
+            .await?
+            .into_value();
+
+        let mut bundle = self.bundle();
+        if account.data.len() == LEGACY_COMPETITION_LEN {
+            bundle
+                .try_push(self.migrate_competition(competition))
+                .map_err(|(_, err)| err)?;
+        }
+        for (participant, _) in participants {
+            bundle
+                .try_push(self.migrate_participant(competition, &participant))
+                .map_err(|(_, err)| err)?;
+        }
+        Ok(bundle)
+    }
+
+    fn initialize_prize_pool(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        schedule: Vec<u16>,
+    ) -> TransactionBuilder<C, Pubkey> {
+        let prize_pool = crate::pda::find_prize_pool_address(competition, &ID).0;
+        self.program(ID)
+            .transaction()
+            .output(prize_pool)
+            .anchor_args(args::InitializePrizePool { schedule })
+            .anchor_accounts(accounts::InitializePrizePool {
+                authority: self.payer(),
+                competition: *competition,
+                mint: *mint,
+                prize_pool,
+                vault: crate::pda::find_prize_vault_address(competition, &ID).0,
+                token_program: token_program_id.copied().unwrap_or(anchor_spl::token::ID),
+                system_program: system_program::ID,
+            })
+    }
+
+    fn fund_prize_pool(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        source: Option<&Pubkey>,
+        amount: u64,
+    ) -> TransactionBuilder<C> {
+        let funder = self.payer();
+        let token_program = token_program_id.copied().unwrap_or(anchor_spl::token::ID);
+        let source = source.copied().unwrap_or_else(|| {
+            get_associated_token_address_with_program_id(&funder, mint, &token_program)
+        });
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::FundPrizePool { amount })
+            .anchor_accounts(accounts::FundPrizePool {
+                funder,
+                competition: *competition,
+                prize_pool: crate::pda::find_prize_pool_address(competition, &ID).0,
+                mint: *mint,
+                source,
+                vault: crate::pda::find_prize_vault_address(competition, &ID).0,
+                token_program,
+            })
+    }
+
+    fn distribute_prizes(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        page_index: u32,
+        traders: &[Pubkey],
+    ) -> TransactionBuilder<C> {
+        let token_program = token_program_id.copied().unwrap_or(anchor_spl::token::ID);
+        let mut txn = self.program(ID).transaction();
+        for trader in traders {
+            txn = txn.merge(self.prepare_associated_token_account(
+                mint,
+                &token_program,
+                Some(trader),
+            ));
+        }
+        txn.merge(
+            self.program(ID)
+                .transaction()
+                .anchor_args(args::DistributePrizes { page_index })
+                .anchor_accounts(accounts::DistributePrizes {
+                    competition: *competition,
+                    prize_pool: crate::pda::find_prize_pool_address(competition, &ID).0,
+                    page: crate::pda::find_leaderboard_page_address(competition, page_index, &ID).0,
+                    mint: *mint,
+                    vault: crate::pda::find_prize_vault_address(competition, &ID).0,
+                    token_program,
+                })
+                .accounts(
+                    traders
+                        .iter()
+                        .map(|trader| {
+                            AccountMeta::new(
+                                get_associated_token_address_with_program_id(
+                                    trader,
+                                    mint,
+                                    &token_program,
+                                ),
+                                false,
+                            )
+                        })
+                        .collect(),
+                ),
+        )
+    }
+
+    async fn distribute_competition_prizes(
+        &self,
+        competition: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        chunk_size: Option<usize>,
+    ) -> crate::Result<BundleBuilder<C>> {
+        let prize_pool = crate::pda::find_prize_pool_address(competition, &ID).0;
+        let prize_pool = self
+            .account::<PrizePool>(&prize_pool)
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        let standings = self.competition_standings(competition).await?;
+        let chunk_size = chunk_size.unwrap_or(DEFAULT_PRIZE_CHUNK_SIZE).max(1);
+        let page_len = usize::from(LEADERBOARD_PAGE_LEN);
+        let rewarded_count = prize_pool.schedule.len().min(standings.len());
+
+        let mut bundle = self.bundle();
+        let mut rank = prize_pool.distributed_count as usize;
+        while rank < rewarded_count {
+            let page_end = (rank / page_len + 1) * page_len;
+            let end = (rank + chunk_size).min(page_end).min(rewarded_count);
+            let traders = standings[rank..end]
+                .iter()
+                .map(|entry| entry.address)
+                .collect::<Vec<_>>();
+            let page_index = (rank / page_len) as u32;
+            bundle
+                .try_push(self.distribute_prizes(
+                    competition,
+                    &prize_pool.mint,
+                    token_program_id,
+                    page_index,
+                    &traders,
+                ))
+                .map_err(|(_, err)| err)?;
+            rank = end;
+        }
+        Ok(bundle)
+    }
+
+    fn reclaim_prizes(
+        &self,
+        competition: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: Option<&Pubkey>,
+        receiver: Option<&Pubkey>,
+    ) -> TransactionBuilder<C> {
+        let authority = self.payer();
+        let token_program = token_program_id.copied().unwrap_or(anchor_spl::token::ID);
+        let receiver = receiver.copied().unwrap_or_else(|| {
+            get_associated_token_address_with_program_id(&authority, mint, &token_program)
+        });
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::ReclaimPrizes {})
+            .anchor_accounts(accounts::ReclaimPrizes {
+                authority,
+                competition: *competition,
+                prize_pool: crate::pda::find_prize_pool_address(competition, &ID).0,
+                mint: *mint,
+                vault: crate::pda::find_prize_vault_address(competition, &ID).0,
+                receiver,
+                token_program,
+            })
+    }
+}
+
+/// Returns the score of the participant under the given scoring mode.
+///
+/// This must be kept consistent with the competition program.
+pub fn participant_score(participant: &Participant, mode: &ScoringMode) -> i128 {
+    match mode {
+        ScoringMode::Volume => i128::try_from(participant.volume).unwrap_or(i128::MAX),
+        ScoringMode::Pnl => participant.realized_pnl,
+        ScoringMode::Roi => {
+            if participant.collateral_value == 0 {
+                return 0;
+            }
+            let collateral_value =
+                i128::try_from(participant.collateral_value).unwrap_or(i128::MAX);
+            let unit = 10i128.pow(u32::from(ROI_DECIMALS));
+            match participant.realized_pnl.checked_mul(unit) {
+                Some(value) => value / collateral_value,
+                None => (participant.realized_pnl / collateral_value).saturating_mul(unit),
+            }
+        }
+    }
 }
 
 /// Competition Params.
@@ -83,6 +588,8 @@ pub struct CompetitionParams {
     extension_cap: i64,
     only_count_increase: bool,
     volume_merge_window: i64,
+    #[builder(default = ScoringMode::Volume)]
+    scoring_mode: ScoringMode,
 }
 
 impl From<CompetitionParams> for args::InitializeCompetition {
@@ -95,6 +602,7 @@ impl From<CompetitionParams> for args::InitializeCompetition {
             extension_cap,
             only_count_increase,
             volume_merge_window,
+            scoring_mode,
         } = params;
         Self {
             start_time,
@@ -104,6 +612,7 @@ impl From<CompetitionParams> for args::InitializeCompetition {
             extension_cap,
             only_count_increase,
             volume_merge_window,
+            scoring_mode,
         }
     }
 }
@@ -36,18 +36,18 @@ use glv_withdrawal::{
 };
 use gmsol_programs::gmsol_store::{
     client::{accounts, args},
-    types::UpdateOrderParams,
+    types::{UpdateOrderOptions, UpdateOrderParams},
 };
 use gmsol_solana_utils::{transaction_builder::TransactionBuilder, IntoAtomicGroup};
 use gmsol_utils::{
-    order::{OrderKind, PositionCutKind},
+    order::{OrderKind, PositionCutKind, TrailingDistance},
     pubkey::optional_address,
     swap::SwapActionParams,
 };
 use market_state::{UpdateClosedStateBuilder, UpdateFeesStateBuilder};
 use order::{
     CloseOrderBuilder, CreateOrderBuilder, ExecuteOrderBuilder, OrderParams, PositionCutBuilder,
-    UpdateAdlBuilder,
+    UpdateAdlBuilder, UpdateTrailingStopBuilder,
 };
 use shift::{CloseShiftBuilder, CreateShiftBuilder, ExecuteShiftBuilder};
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
@@ -57,7 +57,7 @@ use crate::{
     builders::{
         callback::{Callback, CallbackParams},
         market_state::{UpdateClosedState, UpdateFeesState},
-        order::update::SetShouldKeepPositionAccount,
+        order::update::{LinkOrders, SetShouldKeepPositionAccount},
         position::CloseEmptyPosition,
     },
     client::Client,
@@ -156,6 +156,8 @@ pub trait ExchangeOps<C> {
             trigger_price: None,
             is_long,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
         };
         self.create_order(store, market_token, is_collateral_token_long, params)
     }
@@ -180,6 +182,8 @@ pub trait ExchangeOps<C> {
             trigger_price: None,
             is_long,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
         };
         self.create_order(store, market_token, is_collateral_token_long, params)
     }
@@ -208,6 +212,8 @@ pub trait ExchangeOps<C> {
             trigger_price: None,
             is_long: true,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
         };
         let mut builder = self.create_order(store, market_token, is_output_token_long, params);
         builder
@@ -238,6 +244,8 @@ pub trait ExchangeOps<C> {
             trigger_price: Some(price),
             is_long,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
         };
         self.create_order(store, market_token, is_collateral_token_long, params)
     }
@@ -264,6 +272,8 @@ pub trait ExchangeOps<C> {
             trigger_price: Some(price),
             is_long,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
         };
         self.create_order(store, market_token, is_collateral_token_long, params)
     }
@@ -290,6 +300,40 @@ pub trait ExchangeOps<C> {
             trigger_price: Some(price),
             is_long,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
+        };
+        self.create_order(store, market_token, is_collateral_token_long, params)
+    }
+
+    /// Create a trailing-stop decrease order.
+    ///
+    /// The `price` is used as the initial best price, from which the trigger price
+    /// trails by the given `distance`.
+    #[allow(clippy::too_many_arguments)]
+    fn trailing_stop(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        is_long: bool,
+        decrement_size_in_usd: u128,
+        price: u128,
+        distance: TrailingDistance,
+        is_collateral_token_long: bool,
+        collateral_withdrawal_amount: u64,
+    ) -> CreateOrderBuilder<C> {
+        let params = OrderParams {
+            kind: OrderKind::TrailingStopDecrease,
+            decrease_position_swap_type: None,
+            min_output_amount: 0,
+            size_delta_usd: decrement_size_in_usd,
+            initial_collateral_delta_amount: collateral_withdrawal_amount,
+            acceptable_price: None,
+            trigger_price: Some(price),
+            is_long,
+            valid_from_ts: None,
+            trailing_distance: Some(distance),
+            twap: None,
         };
         self.create_order(store, market_token, is_collateral_token_long, params)
     }
@@ -320,6 +364,8 @@ pub trait ExchangeOps<C> {
             trigger_price: None,
             is_long: true,
             valid_from_ts: None,
+            trailing_distance: None,
+            twap: None,
         };
         let mut builder = self.create_order(store, market_token, is_output_token_long, params);
         builder
@@ -338,6 +384,39 @@ pub trait ExchangeOps<C> {
         hint: Option<Option<Callback>>,
     ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
 
+    /// Update an order with options.
+    fn update_order_with_options(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        order: &Pubkey,
+        params: UpdateOrderParams,
+        options: UpdateOrderOptions,
+        hint: Option<Option<Callback>>,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Update an order as a delegate of the owner.
+    #[allow(clippy::too_many_arguments)]
+    fn update_order_by_delegate(
+        &self,
+        store: &Pubkey,
+        owner: &Pubkey,
+        market_token: &Pubkey,
+        order: &Pubkey,
+        params: UpdateOrderParams,
+        options: UpdateOrderOptions,
+        hint: Option<Option<Callback>>,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Link orders (one-cancels-other).
+    ///
+    /// When one of the linked orders is executed, the others will be cancelled.
+    fn link_orders(
+        &self,
+        store: &Pubkey,
+        orders: &[Pubkey],
+    ) -> crate::Result<TransactionBuilder<C>>;
+
     /// Execute an order.
     fn execute_order(
         &self,
@@ -387,6 +466,14 @@ pub trait ExchangeOps<C> {
         for_short: bool,
     ) -> crate::Result<UpdateAdlBuilder<C>>;
 
+    /// Update the best price of a trailing-stop order.
+    fn update_trailing_stop_order(
+        &self,
+        store: &Pubkey,
+        oracle: &Pubkey,
+        order: &Pubkey,
+    ) -> UpdateTrailingStopBuilder<C>;
+
     /// Create a GLV deposit.
     fn create_glv_deposit(
         &self,
@@ -557,6 +644,23 @@ impl<C: Deref<Target = impl Signer> + Clone> ExchangeOps<C> for Client<C> {
         order: &Pubkey,
         params: UpdateOrderParams,
         hint: Option<Option<Callback>>,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let options = UpdateOrderOptions {
+            trailing_distance: None,
+            best_price: None,
+        };
+        self.update_order_with_options(store, market_token, order, params, options, hint)
+            .await
+    }
+
+    async fn update_order_with_options(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        order: &Pubkey,
+        params: UpdateOrderParams,
+        options: UpdateOrderOptions,
+        hint: Option<Option<Callback>>,
     ) -> crate::Result<TransactionBuilder<C>> {
         let callback = match hint {
             Some(callback) => callback,
@@ -572,7 +676,7 @@ impl<C: Deref<Target = impl Signer> + Clone> ExchangeOps<C> for Client<C> {
             callback_partitioned_data_account,
             ..
         } = self.get_callback_params(callback.as_ref());
-        Ok(self
+        let update = self
             .store_transaction()
             .anchor_accounts(accounts::UpdateOrderV2 {
                 owner: self.payer(),
@@ -585,8 +689,75 @@ impl<C: Deref<Target = impl Signer> + Clone> ExchangeOps<C> for Client<C> {
                 callback_program,
                 callback_shared_data_account,
                 callback_partitioned_data_account,
+            });
+        // Only use the new instruction when the options are required.
+        if options.is_empty() {
+            Ok(update.anchor_args(args::UpdateOrderV2 { params }))
+        } else {
+            Ok(update.anchor_args(args::UpdateOrderV3 { params, options }))
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn update_order_by_delegate(
+        &self,
+        store: &Pubkey,
+        owner: &Pubkey,
+        market_token: &Pubkey,
+        order: &Pubkey,
+        params: UpdateOrderParams,
+        options: UpdateOrderOptions,
+        hint: Option<Option<Callback>>,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let callback = match hint {
+            Some(callback) => callback,
+            None => {
+                let order = self.order(order).await?;
+                Callback::from_header(&order.header)?
+            }
+        };
+        let CallbackParams {
+            callback_authority,
+            callback_program,
+            callback_shared_data_account,
+            callback_partitioned_data_account,
+            ..
+        } = self.get_callback_params(callback.as_ref());
+        let delegate = self.payer();
+        Ok(self
+            .store_transaction()
+            .anchor_accounts(accounts::UpdateOrderByDelegate {
+                delegate,
+                owner: *owner,
+                store: *store,
+                delegation: self.find_user_delegation_address(store, owner, &delegate),
+                market: self.find_market_address(store, market_token),
+                order: *order,
+                event_authority: self.store_event_authority(),
+                program: *self.store_program_id(),
+                callback_authority,
+                callback_program,
+                callback_shared_data_account,
+                callback_partitioned_data_account,
             })
-            .anchor_args(args::UpdateOrderV2 { params }))
+            .anchor_args(args::UpdateOrderByDelegate { params, options }))
+    }
+
+    fn link_orders(
+        &self,
+        store: &Pubkey,
+        orders: &[Pubkey],
+    ) -> crate::Result<TransactionBuilder<C>> {
+        if orders.len() < 2 {
+            return Err(crate::Error::custom("at least two orders are required"));
+        }
+        let link = LinkOrders::builder()
+            .payer(self.payer())
+            .program(self.store_program_for_builders(store))
+            .orders(orders.iter().copied().map(Into::into).collect::<Vec<_>>())
+            .build()
+            .into_atomic_group(&())?;
+        Ok(self.store_transaction().pre_atomic_group(link, false))
     }
 
     fn execute_order(
@@ -682,6 +853,15 @@ impl<C: Deref<Target = impl Signer> + Clone> ExchangeOps<C> for Client<C> {
         UpdateAdlBuilder::try_new(self, store, oracle, market_token, for_long, for_short)
     }
 
+    fn update_trailing_stop_order(
+        &self,
+        store: &Pubkey,
+        oracle: &Pubkey,
+        order: &Pubkey,
+    ) -> UpdateTrailingStopBuilder<C> {
+        UpdateTrailingStopBuilder::new(self, store, oracle, order)
+    }
+
     fn create_glv_deposit(
         &self,
         store: &Pubkey,
@@ -8,7 +8,7 @@ use anchor_spl::associated_token::get_associated_token_address;
 use gmsol_programs::gmsol_store::{
     accounts::{Market, Order, Position, Store, UserHeader},
     client::{accounts, args},
-    types::{CreateOrderParams, DecreasePositionSwapType},
+    types::{CreateOrderOptions, CreateOrderParams, DecreasePositionSwapType},
     ID,
 };
 use gmsol_solana_utils::{
@@ -21,7 +21,7 @@ use gmsol_utils::{
     action::ActionFlag,
     market::MarketMeta,
     oracle::PriceProviderKind,
-    order::{OrderKind, PositionCutKind},
+    order::{OrderKind, PositionCutKind, TrailingDistance, TwapParams},
     pubkey::optional_address,
     swap::SwapActionParams,
     token_config::{token_records, TokenMapAccess, TokensWithFeed},
@@ -85,6 +85,10 @@ pub struct OrderParams {
     pub is_long: bool,
     /// Valid from timestamp.
     pub valid_from_ts: Option<i64>,
+    /// Trailing distance (trailing-stop orders only).
+    pub trailing_distance: Option<TrailingDistance>,
+    /// TWAP params (market swap and market decrease orders only).
+    pub twap: Option<TwapParams>,
 }
 
 /// Create Order Builder.
@@ -103,7 +107,8 @@ pub struct CreateOrderBuilder<'a, C> {
     long_token_account: Option<Pubkey>,
     short_token_account: Option<Pubkey>,
     should_unwrap_native_token: bool,
-    receiver: Pubkey,
+    receiver: Option<Pubkey>,
+    delegate_of: Option<Pubkey>,
     callback: Option<Callback>,
     alts: HashMap<Pubkey, Vec<Pubkey>>,
 }
@@ -142,7 +147,8 @@ where
             long_token_account: None,
             short_token_account: None,
             should_unwrap_native_token: true,
-            receiver: client.payer(),
+            receiver: None,
+            delegate_of: None,
             callback: None,
             alts: Default::default(),
         }
@@ -235,6 +241,15 @@ where
         self
     }
 
+    /// Execute the order in TWAP slices.
+    ///
+    /// Only market swap and market decrease orders are supported.
+    /// Note that the execution fee is charged once per slice.
+    pub fn twap(&mut self, slices: u8, interval: u32) -> &mut Self {
+        self.params.twap = Some(TwapParams { slices, interval });
+        self
+    }
+
     /// Set whether to unwrap native token.
     /// Defaults to should unwrap.
     pub fn should_unwrap_native_token(&mut self, should_unwrap: bool) -> &mut Self {
@@ -243,9 +258,20 @@ where
     }
 
     /// Set receiver.
-    /// Defaults to the payer.
+    /// Defaults to the owner.
     pub fn receiver(&mut self, receiver: Pubkey) -> &mut Self {
-        self.receiver = receiver;
+        self.receiver = Some(receiver);
+        self
+    }
+
+    /// Create the order as a delegate of the given owner.
+    ///
+    /// The payer must have been authorized by the `owner` with a user delegation.
+    /// The receiver of the order must be the `owner`, and the initial collateral
+    /// is transferred from the token account of the `owner`, which the payer must
+    /// have been approved to transfer from.
+    pub fn delegate_of(&mut self, owner: Pubkey) -> &mut Self {
+        self.delegate_of = Some(owner);
         self
     }
 
@@ -270,6 +296,22 @@ where
         }))
     }
 
+    /// Participant in a GT incentive campaign.
+    #[cfg(gt_incentive)]
+    pub fn gt_incentive_campaign(&mut self, campaign: &Pubkey) -> &mut Self {
+        use crate::programs::gmsol_gt_incentive::ID;
+
+        let participant =
+            crate::pda::find_gt_incentive_participant_address(campaign, &self.client.payer(), &ID)
+                .0;
+        self.callback(Some(Callback {
+            version: 0,
+            program: ID.into(),
+            shared_data: (*campaign).into(),
+            partitioned_data: participant.into(),
+        }))
+    }
+
     /// Insert an Address Lookup Table.
     pub fn add_alt(&mut self, account: AddressLookupTableAccount) -> &mut Self {
         self.alts.insert(account.key, account.addresses);
@@ -281,6 +323,48 @@ where
             .find_market_address(&self.store, &self.market_token)
     }
 
+    fn owner(&self) -> Pubkey {
+        self.delegate_of.unwrap_or_else(|| self.client.payer())
+    }
+
+    fn prepare_position(
+        &self,
+        position: Pubkey,
+        params: CreateOrderParams,
+    ) -> TransactionBuilder<'a, C> {
+        let owner = self.owner();
+        let market = self.market();
+        let txn = self.client.store_transaction();
+        match self.delegate_of {
+            Some(_) => {
+                let delegate = self.client.payer();
+                txn.anchor_accounts(accounts::PreparePositionByDelegate {
+                    delegate,
+                    owner,
+                    store: self.store,
+                    delegation: self.client.find_user_delegation_address(
+                        &self.store,
+                        &owner,
+                        &delegate,
+                    ),
+                    market,
+                    position,
+                    system_program: system_program::ID,
+                })
+                .anchor_args(args::PreparePositionByDelegate { params })
+            }
+            None => txn
+                .anchor_accounts(accounts::PreparePosition {
+                    owner,
+                    store: self.store,
+                    market,
+                    position,
+                    system_program: system_program::ID,
+                })
+                .anchor_args(args::PreparePosition { params }),
+        }
+    }
+
     async fn prepare_hint(&mut self) -> crate::Result<CreateOrderHint> {
         loop {
             if let Some(hint) = self.hint {
@@ -309,10 +393,11 @@ where
             | OrderKind::Liquidation
             | OrderKind::LimitIncrease
             | OrderKind::LimitDecrease
-            | OrderKind::StopLossDecrease => {
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => {
                 let position = self.client.find_position_address(
                     &self.store,
-                    &self.client.payer(),
+                    &self.owner(),
                     &self.market_token,
                     &output_token,
                     self.params.is_long,
@@ -339,9 +424,10 @@ where
                     let output_token = self.output_token().await?;
                     self.initial_token.set_token(output_token);
                 }
+                let owner = self.owner();
                 let Some((token, account)) = self
                     .initial_token
-                    .get_or_fetch_token_and_token_account(self.client, Some(&self.client.payer()))
+                    .get_or_fetch_token_and_token_account(self.client, Some(&owner))
                     .await?
                 else {
                     return Err(crate::Error::custom(
@@ -353,7 +439,8 @@ where
             OrderKind::MarketDecrease
             | OrderKind::Liquidation
             | OrderKind::LimitDecrease
-            | OrderKind::StopLossDecrease => Ok(None),
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => Ok(None),
             kind => Err(crate::Error::custom(format!(
                 "unsupported order kind: {kind:?}"
             ))),
@@ -362,7 +449,10 @@ where
 
     async fn get_final_output_token(&mut self) -> crate::Result<Pubkey> {
         match &self.params.kind {
-            OrderKind::MarketDecrease | OrderKind::LimitDecrease | OrderKind::StopLossDecrease => {
+            OrderKind::MarketDecrease
+            | OrderKind::LimitDecrease
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => {
                 if self.final_token.is_none() {
                     let output_token = self.output_token().await?;
                     self.final_token = Some(output_token);
@@ -411,6 +501,14 @@ where
         Ok(params)
     }
 
+    /// Get [`CreateOrderOptions`].
+    pub fn create_order_options(&self) -> CreateOrderOptions {
+        CreateOrderOptions {
+            trailing_distance: self.params.trailing_distance.map(Into::into),
+            twap: self.params.twap.map(Into::into),
+        }
+    }
+
     /// Create [`TransactionBuilder`] to prepare account.
     pub async fn build_prepare_position(
         &mut self,
@@ -419,18 +517,7 @@ where
             return Err(crate::Error::custom("invalid order kind"));
         };
         let params = self.create_order_params()?;
-        let prepare_position = self
-            .client
-            .store_transaction()
-            .anchor_accounts(accounts::PreparePosition {
-                owner: self.client.payer(),
-                store: self.store,
-                market: self.market(),
-                position,
-                system_program: system_program::ID,
-            })
-            .anchor_args(args::PreparePosition { params });
-        Ok(prepare_position.output(position))
+        Ok(self.prepare_position(position, params).output(position))
     }
 
     /// Create [`TransactionBuilder`] and return order address and optional position address.
@@ -440,9 +527,23 @@ where
         let token_program_id = anchor_spl::token::ID;
 
         let nonce = self.nonce.unwrap_or_else(|| generate_nonce().to_bytes());
-        let owner = &self.client.payer();
-        let receiver = self.receiver;
-        let order = self.client.find_order_address(&self.store, owner, &nonce);
+        let payer = self.client.payer();
+        let owner = &self.owner();
+        let receiver = self.receiver.unwrap_or(*owner);
+        if self.delegate_of.is_some() {
+            if receiver != *owner {
+                return Err(crate::Error::custom(
+                    "the receiver must be the owner when creating orders as a delegate",
+                ));
+            }
+            if self.callback.is_some() {
+                return Err(crate::Error::custom(
+                    "callback is not supported when creating orders as a delegate",
+                ));
+            }
+        }
+        // The order address is derived from the creator, i.e., the payer.
+        let order = self.client.find_order_address(&self.store, &payer, &nonce);
         let (initial_collateral_token, initial_collateral_token_account) =
             self.initial_collateral_accounts().await?.unzip();
         let final_output_token = self.get_final_output_token().await?;
@@ -457,6 +558,7 @@ where
                 | OrderKind::LimitDecrease
                 | OrderKind::MarketDecrease
                 | OrderKind::StopLossDecrease
+                | OrderKind::TrailingStopDecrease
         );
         let (long_token, short_token) = if is_swap {
             (None, None)
@@ -489,6 +591,7 @@ where
 
         let kind = self.params.kind;
         let params = self.create_order_params()?;
+        let options = self.create_order_options();
 
         let mut prepare = match kind {
             OrderKind::MarketSwap | OrderKind::LimitSwap => {
@@ -550,24 +653,18 @@ where
                     Some(&receiver),
                 );
 
-                let prepare_position = self
-                    .client
-                    .store_transaction()
-                    .anchor_accounts(accounts::PreparePosition {
-                        owner: *owner,
-                        store: self.store,
-                        market: self.market(),
-                        position: position.expect("must provided"),
-                        system_program: system_program::ID,
-                    })
-                    .anchor_args(args::PreparePosition { params });
+                let prepare_position =
+                    self.prepare_position(position.expect("must provided"), params);
 
                 escrow
                     .merge(long_token_ata)
                     .merge(short_token_ata)
                     .merge(prepare_position)
             }
-            OrderKind::MarketDecrease | OrderKind::LimitDecrease | OrderKind::StopLossDecrease => {
+            OrderKind::MarketDecrease
+            | OrderKind::LimitDecrease
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => {
                 let long_token =
                     long_token.ok_or(crate::Error::custom("long token is not provided"))?;
                 let short_token =
@@ -617,17 +714,20 @@ where
             }
         };
 
-        let prepare_user = self
-            .client
-            .store_transaction()
-            .anchor_accounts(accounts::PrepareUser {
-                owner: *owner,
-                store: self.store,
-                user,
-                system_program: system_program::ID,
-            })
-            .anchor_args(args::PrepareUser {});
-        prepare = prepare.merge(prepare_user);
+        // The user account can only be prepared by the owner.
+        if self.delegate_of.is_none() {
+            let prepare_user = self
+                .client
+                .store_transaction()
+                .anchor_accounts(accounts::PrepareUser {
+                    owner: *owner,
+                    store: self.store,
+                    user,
+                    system_program: system_program::ID,
+                })
+                .anchor_args(args::PrepareUser {});
+            prepare = prepare.merge(prepare_user);
+        }
 
         let CallbackParams {
             callback_version,
@@ -652,56 +752,131 @@ where
             }
         }
 
-        let create = self
-            .client
-            .store_transaction()
-            .accounts(fix_optional_account_metas(
-                accounts::CreateOrderV2 {
-                    store: self.store,
-                    order,
-                    position,
-                    market: self.market(),
-                    owner: *owner,
-                    receiver,
-                    user,
-                    initial_collateral_token,
-                    final_output_token,
-                    long_token,
-                    short_token,
-                    initial_collateral_token_escrow,
-                    final_output_token_escrow: final_output_token_accounts
-                        .map(|(escrow, _)| escrow),
-                    long_token_escrow: long_token_accounts.map(|(escrow, _)| escrow),
-                    short_token_escrow: short_token_accounts.map(|(escrow, _)| escrow),
-                    initial_collateral_token_source: initial_collateral_token_account,
-                    system_program: system_program::ID,
-                    token_program: anchor_spl::token::ID,
-                    associated_token_program: anchor_spl::associated_token::ID,
-                    callback_authority,
-                    callback_program,
-                    callback_shared_data_account,
-                    callback_partitioned_data_account,
-                    event_authority: self.client.store_event_authority(),
-                    program: *self.client.store_program_id(),
-                },
-                &ID,
-                self.client.store_program_id(),
-            ))
-            .anchor_args(args::CreateOrderV2 {
-                nonce,
-                params,
-                callback_version,
-            })
-            .accounts(
-                self.swap_path
-                    .iter()
-                    .map(|mint| AccountMeta {
-                        pubkey: self.client.find_market_address(&self.store, mint),
-                        is_signer: false,
-                        is_writable: false,
+        #[cfg(gt_incentive)]
+        if let Some(callback) = self.callback.as_ref() {
+            use crate::ops::gt_incentive::GtIncentiveOps;
+            if callback.program.0 == crate::programs::gmsol_gt_incentive::ID {
+                let (prepare_participant, participant) = self
+                    .client
+                    .create_gt_incentive_participant_idempotent(&callback.shared_data, None)
+                    .swap_output(());
+                if participant != callback.partitioned_data.0 {
+                    return Err(crate::Error::custom("invalid participant account"));
+                }
+                prepare = prepare.merge(prepare_participant);
+            }
+        }
+
+        let final_output_token_escrow = final_output_token_accounts.map(|(escrow, _)| escrow);
+        let long_token_escrow = long_token_accounts.map(|(escrow, _)| escrow);
+        let short_token_escrow = short_token_accounts.map(|(escrow, _)| escrow);
+        let create = match self.delegate_of {
+            Some(_) => self
+                .client
+                .store_transaction()
+                .accounts(fix_optional_account_metas(
+                    accounts::CreateOrderByDelegate {
+                        delegate: payer,
+                        owner: *owner,
+                        store: self.store,
+                        delegation: self.client.find_user_delegation_address(
+                            &self.store,
+                            owner,
+                            &payer,
+                        ),
+                        market: self.market(),
+                        user,
+                        order,
+                        position,
+                        initial_collateral_token,
+                        final_output_token,
+                        long_token,
+                        short_token,
+                        initial_collateral_token_escrow,
+                        final_output_token_escrow,
+                        long_token_escrow,
+                        short_token_escrow,
+                        initial_collateral_token_source: initial_collateral_token_account,
+                        system_program: system_program::ID,
+                        token_program: anchor_spl::token::ID,
+                        associated_token_program: anchor_spl::associated_token::ID,
+                        event_authority: self.client.store_event_authority(),
+                        program: *self.client.store_program_id(),
+                    },
+                    &ID,
+                    self.client.store_program_id(),
+                ))
+                .anchor_args(args::CreateOrderByDelegate {
+                    nonce,
+                    params,
+                    options,
+                }),
+            None => {
+                let create = self
+                    .client
+                    .store_transaction()
+                    .accounts(fix_optional_account_metas(
+                        accounts::CreateOrderV2 {
+                            store: self.store,
+                            order,
+                            position,
+                            market: self.market(),
+                            owner: *owner,
+                            receiver,
+                            user,
+                            initial_collateral_token,
+                            final_output_token,
+                            long_token,
+                            short_token,
+                            initial_collateral_token_escrow,
+                            final_output_token_escrow,
+                            long_token_escrow,
+                            short_token_escrow,
+                            initial_collateral_token_source: initial_collateral_token_account,
+                            system_program: system_program::ID,
+                            token_program: anchor_spl::token::ID,
+                            associated_token_program: anchor_spl::associated_token::ID,
+                            callback_authority,
+                            callback_program,
+                            callback_shared_data_account,
+                            callback_partitioned_data_account,
+                            callback_program_config: callback_program.map(|program_id| {
+                                self.client
+                                    .find_callback_program_address(&self.store, &program_id)
+                            }),
+                            event_authority: self.client.store_event_authority(),
+                            program: *self.client.store_program_id(),
+                        },
+                        &ID,
+                        self.client.store_program_id(),
+                    ));
+                // Only use the new instruction when the options are required.
+                if options.is_empty() {
+                    create.anchor_args(args::CreateOrderV2 {
+                        nonce,
+                        params,
+                        callback_version,
                     })
-                    .collect::<Vec<_>>(),
-            );
+                } else {
+                    create.anchor_args(args::CreateOrderV3 {
+                        nonce,
+                        params,
+                        callback_version,
+                        options,
+                    })
+                }
+            }
+        };
+        let create = create.accounts(
+            self.swap_path
+                .iter()
+                .map(|mint| AccountMeta {
+                    pubkey: self.client.find_market_address(&self.store, mint),
+                    is_signer: false,
+                    is_writable: false,
+                })
+                .collect::<Vec<_>>(),
+        );
 
         Ok((
             prepare.merge(create).lookup_tables(self.alts.clone()),
@@ -754,6 +929,8 @@ pub struct ExecuteOrderHint {
     should_unwrap_native_token: bool,
     callback: Option<Callback>,
     virtual_inventories: BTreeSet<Pubkey>,
+    linked_orders: Vec<Pubkey>,
+    has_remaining_twap_slices: bool,
 }
 
 impl ExecuteOrderHint {
@@ -909,6 +1086,14 @@ where
                 .get_flag(ActionFlag::ShouldUnwrapNativeToken),
             callback: Callback::from_header(&order.header)?,
             virtual_inventories,
+            linked_orders: order
+                .linked_orders
+                .iter()
+                .filter_map(optional_address)
+                .copied()
+                .collect(),
+            // The order is kept pending if there are slices left after this execution.
+            has_remaining_twap_slices: order.params.twap_remaining_slices() > 1,
         });
         Ok(self)
     }
@@ -984,6 +1169,37 @@ where
         }
     }
 
+    /// Get the accounts for closing the linked orders after the execution.
+    async fn linked_order_accounts(
+        &self,
+        hint: &ExecuteOrderHint,
+    ) -> crate::Result<Vec<AccountMeta>> {
+        let mut accounts = Vec::with_capacity(hint.linked_orders.len() * 5);
+        for linked_order in hint.linked_orders.iter() {
+            let group = match self.client.account::<ZeroCopy<Order>>(linked_order).await? {
+                Some(ZeroCopy(order)) => {
+                    let tokens = &order.tokens;
+                    let missing = || crate::Error::custom("missing escrow account");
+                    [
+                        *linked_order,
+                        tokens.final_output_token.account().ok_or_else(missing)?,
+                        tokens.long_token.account().ok_or_else(missing)?,
+                        tokens.short_token.account().ok_or_else(missing)?,
+                        order.header.rent_receiver,
+                    ]
+                }
+                // The other accounts are ignored if the linked order has been closed.
+                None => [*linked_order; 5],
+            };
+            accounts.extend(
+                group
+                    .into_iter()
+                    .map(|pubkey| AccountMeta::new(pubkey, false)),
+            );
+        }
+        Ok(accounts)
+    }
+
     /// Set token map.
     pub fn token_map(&mut self, address: Pubkey) -> &mut Self {
         self.token_map = Some(address);
@@ -1019,6 +1235,8 @@ where
             self.event_buffer_index,
         );
 
+        let linked_orders = self.linked_order_accounts(&hint).await?;
+
         let kind = hint.kind;
         let is_swap = matches!(kind, OrderKind::LimitSwap | OrderKind::MarketSwap);
         let mut require_claimable_accounts = false;
@@ -1032,7 +1250,10 @@ where
         } = self.client.get_callback_params(hint.callback.as_ref());
 
         let mut execute_order = match kind {
-            OrderKind::MarketDecrease | OrderKind::LimitDecrease | OrderKind::StopLossDecrease => {
+            OrderKind::MarketDecrease
+            | OrderKind::LimitDecrease
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => {
                 require_claimable_accounts = true;
 
                 self.client
@@ -1181,6 +1402,7 @@ where
                     .into_iter()
                     .chain(swap_markets)
                     .chain(virtual_inventories)
+                    .chain(linked_orders)
                     .collect::<Vec<_>>(),
             )
             .compute_budget(ComputeBudget::default().with_limit(EXECUTE_ORDER_COMPUTE_BUDGET))
@@ -1202,7 +1424,10 @@ where
             execute_order = prepare_event_buffer.merge(execute_order);
         }
 
-        if self.close {
+        // A TWAP order with remaining slices is still pending after the execution.
+        let should_close = self.close && !hint.has_remaining_twap_slices;
+
+        if should_close {
             let close = self
                 .client
                 .close_order(&self.order)?
@@ -1258,6 +1483,7 @@ where
             .map_err(|(_, err)| err)?
             .try_push(post_builder)
             .map_err(|(_, err)| err)?;
+
         Ok(bundle)
     }
 }
@@ -1308,6 +1534,7 @@ pub struct CloseOrderBuilder<'a, C> {
     hint: Option<CloseOrderHint>,
     reason: String,
     skip_callback: bool,
+    by_delegate: bool,
 }
 
 /// Close Order Hint.
@@ -1372,6 +1599,7 @@ where
             hint: None,
             reason: "cancelled".into(),
             skip_callback: false,
+            by_delegate: false,
         }
     }
 
@@ -1403,6 +1631,12 @@ where
         self
     }
 
+    /// Set whether to close the order as a delegate of the owner.
+    pub fn by_delegate(&mut self, enable: bool) -> &mut Self {
+        self.by_delegate = enable;
+        self
+    }
+
     async fn prepare_hint(&mut self) -> crate::Result<CloseOrderHint> {
         match &self.hint {
             Some(hint) => Ok(hint.clone()),
@@ -1436,6 +1670,10 @@ where
         let referrer_user = hint
             .referrer
             .map(|owner| self.client.find_user_address(&hint.store, &owner));
+        let delegation = self.by_delegate.then(|| {
+            self.client
+                .find_user_delegation_address(&hint.store, &owner, &payer)
+        });
         let CallbackParams {
             callback_authority,
             callback_program,
@@ -1501,6 +1739,7 @@ where
                     callback_program,
                     callback_shared_data_account,
                     callback_partitioned_data_account,
+                    delegation,
                 },
                 &ID,
                 self.client.store_program_id(),
@@ -2163,10 +2402,7 @@ impl<'a, C: Deref<Target = impl Signer> + Clone> UpdateAdlBuilder<'a, C> {
     /// Build [`TransactionBuilder`] for auto-delevearaging the position.
     pub async fn build_txns(&mut self) -> crate::Result<Vec<TransactionBuilder<'a, C>>> {
         let hint = self.prepare_hint().await?;
-        let feeds = self
-            .feeds_parser
-            .parse(hint.feeds())
-            .collect::<Result<Vec<_>, _>>()?;
+        let feeds = self.feeds_parser.parse_and_sort_by_tokens(hint.feeds())?;
 
         let mut txns = vec![];
 
@@ -2298,3 +2534,152 @@ impl<C> SetExecutionFee for UpdateAdlBuilder<'_, C> {
         self
     }
 }
+
+/// Update trailing-stop order Instruction Builder.
+pub struct UpdateTrailingStopBuilder<'a, C> {
+    client: &'a crate::Client<C>,
+    store: Pubkey,
+    oracle: Pubkey,
+    order: Pubkey,
+    hint: Option<UpdateTrailingStopHint>,
+    feeds_parser: FeedsParser,
+    alts: HashMap<Pubkey, Vec<Pubkey>>,
+}
+
+/// Hint for `update_trailing_stop_order`.
+#[derive(Clone)]
+pub struct UpdateTrailingStopHint {
+    market: Pubkey,
+    prices: UpdateAdlHint,
+}
+
+impl UpdateTrailingStopHint {
+    /// Get feeds.
+    pub fn feeds(&self) -> &TokensWithFeed {
+        self.prices.feeds()
+    }
+}
+
+impl<'a, C: Deref<Target = impl Signer> + Clone> UpdateTrailingStopBuilder<'a, C> {
+    pub(super) fn new(
+        client: &'a crate::Client<C>,
+        store: &Pubkey,
+        oracle: &Pubkey,
+        order: &Pubkey,
+    ) -> Self {
+        Self {
+            client,
+            store: *store,
+            oracle: *oracle,
+            order: *order,
+            hint: None,
+            feeds_parser: FeedsParser::default(),
+            alts: Default::default(),
+        }
+    }
+
+    /// Insert an Address Lookup Table.
+    pub fn add_alt(&mut self, account: AddressLookupTableAccount) -> &mut Self {
+        self.alts.insert(account.key, account.addresses);
+        self
+    }
+
+    /// Prepare hint for updating the trailing-stop order.
+    pub async fn prepare_hint(&mut self) -> crate::Result<UpdateTrailingStopHint> {
+        match &self.hint {
+            Some(hint) => Ok(hint.clone()),
+            None => {
+                let order = self.client.order(&self.order).await?;
+                if !matches!(order.params.kind()?, OrderKind::TrailingStopDecrease) {
+                    return Err(crate::Error::custom("not a trailing-stop order"));
+                }
+                let market_address = order.header.market;
+                let market = self.client.market(&market_address).await?;
+                let hint = UpdateTrailingStopHint {
+                    market: market_address,
+                    prices: UpdateAdlHint::from_market(self.client, &market).await?,
+                };
+                self.hint = Some(hint.clone());
+                Ok(hint)
+            }
+        }
+    }
+
+    /// Build [`TransactionBuilder`] for updating the trailing-stop order.
+    pub async fn build(&mut self) -> crate::Result<TransactionBuilder<'a, C>> {
+        let hint = self.prepare_hint().await?;
+        let feeds = self.feeds_parser.parse_and_sort_by_tokens(hint.feeds())?;
+
+        let rpc = self
+            .client
+            .store_transaction()
+            .accounts(fix_optional_account_metas(
+                accounts::UpdateTrailingStopOrder {
+                    authority: self.client.payer(),
+                    store: self.store,
+                    token_map: hint.prices.token_map,
+                    oracle: self.oracle,
+                    market: hint.market,
+                    order: self.order,
+                    chainlink_program: None,
+                    event_authority: self.client.store_event_authority(),
+                    program: *self.client.store_program_id(),
+                },
+                &ID,
+                self.client.store_program_id(),
+            ))
+            .anchor_args(args::UpdateTrailingStopOrder {})
+            .accounts(feeds)
+            .lookup_tables(self.alts.clone());
+
+        Ok(rpc)
+    }
+}
+
+impl<'a, C: Deref<Target = impl Signer> + Clone> MakeBundleBuilder<'a, C>
+    for UpdateTrailingStopBuilder<'a, C>
+{
+    async fn build_with_options(
+        &mut self,
+        options: BundleOptions,
+    ) -> gmsol_solana_utils::Result<BundleBuilder<'a, C>> {
+        let mut bundle = self.client.bundle_with_options(options);
+
+        bundle.push(
+            self.build()
+                .await
+                .map_err(gmsol_solana_utils::Error::custom)?,
+        )?;
+
+        Ok(bundle)
+    }
+}
+
+impl<C: Deref<Target = impl Signer> + Clone> PullOraclePriceConsumer
+    for UpdateTrailingStopBuilder<'_, C>
+{
+    async fn feed_ids(&mut self) -> crate::Result<FeedIds> {
+        let hint = self.prepare_hint().await?;
+        Ok(FeedIds::new(self.store, hint.feeds().clone()))
+    }
+
+    fn process_feeds(
+        &mut self,
+        provider: PriceProviderKind,
+        map: FeedAddressMap,
+    ) -> crate::Result<()> {
+        self.feeds_parser
+            .insert_pull_oracle_feed_parser(provider, map);
+        Ok(())
+    }
+}
+
+impl<C> SetExecutionFee for UpdateTrailingStopBuilder<'_, C> {
+    fn is_execution_fee_estimation_required(&self) -> bool {
+        false
+    }
+
+    fn set_execution_fee(&mut self, _lamports: u64) -> &mut Self {
+        self
+    }
+}
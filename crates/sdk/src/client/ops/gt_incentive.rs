@@ -0,0 +1,223 @@
+use std::ops::Deref;
+
+use gmsol_programs::gmsol_gt_incentive::{
+    client::{accounts, args},
+    ID,
+};
+use gmsol_solana_utils::transaction_builder::TransactionBuilder;
+use solana_sdk::{pubkey::Pubkey, signer::Signer, system_program};
+
+use super::user::UserOps;
+
+/// Operations for GT incentive program.
+pub trait GtIncentiveOps<C> {
+    /// Initialize the GT incentive config for the given store.
+    ///
+    /// The payer must be the store admin. The `reward_budget` caps the total GT
+    /// rewards that can be funded to the campaigns.
+    fn initialize_gt_incentive(
+        &self,
+        store: &Pubkey,
+        reward_budget: u64,
+    ) -> TransactionBuilder<C, Pubkey>;
+
+    /// Set the GT reward budget of the GT incentive config. The payer must be the store admin.
+    fn set_gt_incentive_reward_budget(
+        &self,
+        store: &Pubkey,
+        reward_budget: u64,
+    ) -> TransactionBuilder<C>;
+
+    /// Initialize a GT incentive campaign.
+    fn initialize_gt_incentive_campaign(
+        &self,
+        store: &Pubkey,
+        params: &GtIncentiveCampaignParams,
+    ) -> TransactionBuilder<C, Pubkey>;
+
+    /// Fund a GT incentive campaign with the given amount of GT.
+    fn fund_gt_incentive_campaign(
+        &self,
+        store: &Pubkey,
+        campaign: &Pubkey,
+        amount: u64,
+    ) -> TransactionBuilder<C>;
+
+    /// Create GT incentive participant account idempotently.
+    fn create_gt_incentive_participant_idempotent(
+        &self,
+        campaign: &Pubkey,
+        trader: Option<&Pubkey>,
+    ) -> TransactionBuilder<C, Pubkey>;
+
+    /// Claim the GT rewards of the given campaign.
+    fn claim_gt_incentive_reward(
+        &self,
+        store: &Pubkey,
+        campaign: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>>;
+
+    /// Close a GT incentive participant account.
+    fn close_gt_incentive_participant(&self, campaign: &Pubkey) -> TransactionBuilder<C>;
+}
+
+impl<C: Deref<Target = impl Signer> + Clone> GtIncentiveOps<C> for crate::Client<C> {
+    fn initialize_gt_incentive(
+        &self,
+        store: &Pubkey,
+        reward_budget: u64,
+    ) -> TransactionBuilder<C, Pubkey> {
+        let config = crate::pda::find_gt_incentive_config_address(store, &ID).0;
+        self.program(ID)
+            .transaction()
+            .output(config)
+            .anchor_args(args::Initialize { reward_budget })
+            .anchor_accounts(accounts::Initialize {
+                authority: self.payer(),
+                store: *store,
+                config,
+                store_program: *self.store_program_id(),
+                system_program: system_program::ID,
+            })
+    }
+
+    fn set_gt_incentive_reward_budget(
+        &self,
+        store: &Pubkey,
+        reward_budget: u64,
+    ) -> TransactionBuilder<C> {
+        let config = crate::pda::find_gt_incentive_config_address(store, &ID).0;
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::SetRewardBudget { reward_budget })
+            .anchor_accounts(accounts::SetRewardBudget {
+                authority: self.payer(),
+                store: *store,
+                config,
+                store_program: *self.store_program_id(),
+            })
+    }
+
+    fn initialize_gt_incentive_campaign(
+        &self,
+        store: &Pubkey,
+        params: &GtIncentiveCampaignParams,
+    ) -> TransactionBuilder<C, Pubkey> {
+        let config = crate::pda::find_gt_incentive_config_address(store, &ID).0;
+        let campaign =
+            crate::pda::find_gt_incentive_campaign_address(&config, params.start_time, &ID).0;
+        self.program(ID)
+            .transaction()
+            .output(campaign)
+            .anchor_args(args::InitializeCampaign::from(params.clone()))
+            .anchor_accounts(accounts::InitializeCampaign {
+                authority: self.payer(),
+                config,
+                campaign,
+                system_program: system_program::ID,
+            })
+    }
+
+    fn fund_gt_incentive_campaign(
+        &self,
+        store: &Pubkey,
+        campaign: &Pubkey,
+        amount: u64,
+    ) -> TransactionBuilder<C> {
+        let config = crate::pda::find_gt_incentive_config_address(store, &ID).0;
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::FundCampaign { amount })
+            .anchor_accounts(accounts::FundCampaign {
+                authority: self.payer(),
+                config,
+                campaign: *campaign,
+            })
+    }
+
+    fn create_gt_incentive_participant_idempotent(
+        &self,
+        campaign: &Pubkey,
+        trader: Option<&Pubkey>,
+    ) -> TransactionBuilder<C, Pubkey> {
+        let payer = self.payer();
+        let trader = trader.copied().unwrap_or(payer);
+        let participant =
+            crate::pda::find_gt_incentive_participant_address(campaign, &trader, &ID).0;
+        self.program(ID)
+            .transaction()
+            .output(participant)
+            .anchor_args(args::CreateParticipantIdempotent {})
+            .anchor_accounts(accounts::CreateParticipantIdempotent {
+                payer,
+                campaign: *campaign,
+                participant,
+                trader,
+                system_program: system_program::ID,
+            })
+    }
+
+    fn claim_gt_incentive_reward(
+        &self,
+        store: &Pubkey,
+        campaign: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let trader = self.payer();
+        let config = crate::pda::find_gt_incentive_config_address(store, &ID).0;
+        let participant =
+            crate::pda::find_gt_incentive_participant_address(campaign, &trader, &ID).0;
+        let claim = self
+            .program(ID)
+            .transaction()
+            .anchor_args(args::ClaimReward {})
+            .anchor_accounts(accounts::ClaimReward {
+                trader,
+                config,
+                campaign: *campaign,
+                participant,
+                store: *store,
+                gt_user: self.find_user_address(store, &trader),
+                event_authority: self.store_event_authority(),
+                gt_program: *self.store_program_id(),
+            });
+        Ok(self.prepare_user(store)?.merge(claim))
+    }
+
+    fn close_gt_incentive_participant(&self, campaign: &Pubkey) -> TransactionBuilder<C> {
+        let trader = self.payer();
+        let participant =
+            crate::pda::find_gt_incentive_participant_address(campaign, &trader, &ID).0;
+        self.program(ID)
+            .transaction()
+            .anchor_args(args::CloseParticipant {})
+            .anchor_accounts(accounts::CloseParticipant {
+                trader,
+                campaign: *campaign,
+                participant,
+            })
+    }
+}
+
+/// GT Incentive Campaign Params.
+#[derive(Debug, Clone, typed_builder::TypedBuilder)]
+pub struct GtIncentiveCampaignParams {
+    start_time: i64,
+    end_time: i64,
+    #[builder(default)]
+    only_count_increase: bool,
+}
+
+impl From<GtIncentiveCampaignParams> for args::InitializeCampaign {
+    fn from(params: GtIncentiveCampaignParams) -> Self {
+        let GtIncentiveCampaignParams {
+            start_time,
+            end_time,
+            only_count_increase,
+        } = params;
+        Self {
+            start_time,
+            end_time,
+            only_count_increase,
+        }
+    }
+}
@@ -11,7 +11,9 @@ use gmsol_solana_utils::{
     IntoAtomicGroup,
 };
 use gmsol_utils::{
-    market::{MarketConfigFactor, MarketConfigFlag, MarketConfigKey, MarketMeta},
+    market::{
+        MarketConfigFactor, MarketConfigFlag, MarketConfigKey, MarketMeta, MarketTradingMode,
+    },
     oracle::PriceProviderKind,
     token_config::{token_records, TokensWithFeed},
 };
@@ -161,6 +163,14 @@ pub trait MarketOps<C> {
         enable: bool,
     ) -> TransactionBuilder<C>;
 
+    /// Set the trading mode of the market.
+    fn set_market_trading_mode(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        mode: MarketTradingMode,
+    ) -> TransactionBuilder<C>;
+
     /// Initialize Market Config Buffer.
     fn initialize_market_config_buffer<'a>(
         &'a self,
@@ -198,6 +208,35 @@ pub trait MarketOps<C> {
         buffer: &Pubkey,
     ) -> TransactionBuilder<C>;
 
+    /// Schedule a ramped update of market config by key.
+    fn schedule_market_config_update(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        key: MarketConfigKey,
+        value: &Factor,
+        start_at: i64,
+        duration: u32,
+    ) -> TransactionBuilder<C>;
+
+    /// Schedule ramped updates of market config with the buffer.
+    fn schedule_market_config_update_with_buffer(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        buffer: &Pubkey,
+        start_at: i64,
+        duration: u32,
+    ) -> TransactionBuilder<C>;
+
+    /// Cancel the scheduled update of market config by key.
+    fn cancel_market_config_schedule(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        key: MarketConfigKey,
+    ) -> TransactionBuilder<C>;
+
     /// Set market config updatable.
     fn set_market_config_updatable(
         &self,
@@ -449,6 +488,21 @@ impl<C: Deref<Target = impl Signer> + Clone> MarketOps<C> for crate::Client<C> {
             })
     }
 
+    fn set_market_trading_mode(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        mode: MarketTradingMode,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_args(args::SetMarketTradingMode { mode: mode.into() })
+            .anchor_accounts(accounts::SetMarketTradingMode {
+                authority: self.payer(),
+                store: *store,
+                market: self.find_market_address(store, market_token),
+            })
+    }
+
     fn initialize_market_config_buffer<'a>(
         &'a self,
         store: &Pubkey,
@@ -533,6 +587,64 @@ impl<C: Deref<Target = impl Signer> + Clone> MarketOps<C> for crate::Client<C> {
             })
     }
 
+    fn schedule_market_config_update(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        key: MarketConfigKey,
+        value: &Factor,
+        start_at: i64,
+        duration: u32,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_args(args::ScheduleMarketConfigUpdate {
+                key: key.to_string(),
+                value: *value,
+                start_at,
+                duration,
+            })
+            .anchor_accounts(accounts::UpdateMarketConfig {
+                authority: self.payer(),
+                store: *store,
+                market: self.find_market_address(store, market_token),
+            })
+    }
+
+    fn schedule_market_config_update_with_buffer(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        buffer: &Pubkey,
+        start_at: i64,
+        duration: u32,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_args(args::ScheduleMarketConfigUpdateWithBuffer { start_at, duration })
+            .anchor_accounts(accounts::UpdateMarketConfigWithBuffer {
+                authority: self.payer(),
+                store: *store,
+                market: self.find_market_address(store, market_token),
+                buffer: *buffer,
+            })
+    }
+
+    fn cancel_market_config_schedule(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        key: MarketConfigKey,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_args(args::CancelMarketConfigSchedule {
+                key: key.to_string(),
+            })
+            .anchor_accounts(accounts::UpdateMarketConfig {
+                authority: self.payer(),
+                store: *store,
+                market: self.find_market_address(store, market_token),
+            })
+    }
+
     fn set_market_config_updatable(
         &self,
         store: &Pubkey,
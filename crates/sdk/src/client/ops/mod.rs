@@ -53,10 +53,17 @@ pub mod idl;
 /// Operations for virtual inventory accounts.
 pub mod virtual_inventory;
 
+/// Operations for risk oracle.
+pub mod risk_oracle;
+
 /// Operations for liquidity-provider program.
 #[cfg(liquidity_provider)]
 pub mod liquidity_provider;
 
+/// Operations for GT incentive program.
+#[cfg(gt_incentive)]
+pub mod gt_incentive;
+
 pub use alt::AddressLookupTableOps;
 pub use config::ConfigOps;
 pub use exchange::ExchangeOps;
@@ -65,6 +72,7 @@ pub use gt::GtOps;
 pub use idl::IdlOps;
 pub use market::MarketOps;
 pub use oracle::OracleOps;
+pub use risk_oracle::RiskOracleOps;
 pub use role::RoleOps;
 pub use store::StoreOps;
 pub use system::SystemProgramOps;
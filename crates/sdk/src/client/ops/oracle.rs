@@ -1,13 +1,34 @@
-use std::{future::Future, ops::Deref};
+use std::{
+    collections::{BTreeSet, HashMap},
+    future::Future,
+    ops::Deref,
+};
 
 use gmsol_programs::gmsol_store::{
     accounts::Oracle,
     client::{accounts, args},
+    ID,
+};
+use gmsol_solana_utils::{
+    bundle_builder::{BundleBuilder, BundleOptions},
+    make_bundle_builder::{MakeBundleBuilder, SetExecutionFee},
+    transaction_builder::TransactionBuilder,
+};
+use gmsol_utils::{
+    oracle::PriceProviderKind,
+    token_config::{token_records, TokensWithFeed},
 };
-use gmsol_solana_utils::transaction_builder::TransactionBuilder;
-use gmsol_utils::oracle::PriceProviderKind;
 use solana_sdk::{
-    pubkey::Pubkey, signer::Signer, system_instruction::create_account, system_program,
+    address_lookup_table::AddressLookupTableAccount, pubkey::Pubkey, signer::Signer,
+    system_instruction::create_account, system_program,
+};
+
+use crate::{
+    client::{
+        feeds_parser::{FeedAddressMap, FeedsParser},
+        pull_oracle::{FeedIds, PullOraclePriceConsumer},
+    },
+    utils::optional::fix_optional_account_metas,
 };
 
 /// Arguments for updating a Chainlink price feed.
@@ -43,6 +64,14 @@ pub trait OracleOps<C> {
         feed_id: &Pubkey,
     ) -> (TransactionBuilder<C>, Pubkey);
 
+    /// Update the price circuit breakers of the given tokens.
+    fn update_price_circuit_breakers(
+        &self,
+        store: &Pubkey,
+        oracle: &Pubkey,
+        tokens: impl IntoIterator<Item = Pubkey>,
+    ) -> UpdatePriceCircuitBreakersBuilder<C>;
+
     /// Update price feed with chainlink.
     #[cfg(feature = "gmsol-chainlink-datastreams")]
     fn update_price_feed_with_chainlink_and_authority<'a>(
@@ -144,6 +173,15 @@ impl<C: Deref<Target = impl Signer> + Clone> OracleOps<C> for crate::Client<C> {
         (rpc, price_feed)
     }
 
+    fn update_price_circuit_breakers(
+        &self,
+        store: &Pubkey,
+        oracle: &Pubkey,
+        tokens: impl IntoIterator<Item = Pubkey>,
+    ) -> UpdatePriceCircuitBreakersBuilder<C> {
+        UpdatePriceCircuitBreakersBuilder::new(self, store, oracle, tokens)
+    }
+
     #[cfg(feature = "gmsol-chainlink-datastreams")]
     fn update_price_feed_with_chainlink_and_authority<'a>(
         &'a self,
@@ -195,3 +233,156 @@ impl<C: Deref<Target = impl Signer> + Clone> OracleOps<C> for crate::Client<C> {
         }
     }
 }
+
+/// Update price circuit breakers Instruction Builder.
+pub struct UpdatePriceCircuitBreakersBuilder<'a, C> {
+    client: &'a crate::Client<C>,
+    store: Pubkey,
+    oracle: Pubkey,
+    tokens: BTreeSet<Pubkey>,
+    hint: Option<UpdatePriceCircuitBreakersHint>,
+    feeds_parser: FeedsParser,
+    alts: HashMap<Pubkey, Vec<Pubkey>>,
+}
+
+impl<'a, C: Deref<Target = impl Signer> + Clone> UpdatePriceCircuitBreakersBuilder<'a, C> {
+    fn new(
+        client: &'a crate::Client<C>,
+        store: &Pubkey,
+        oracle: &Pubkey,
+        tokens: impl IntoIterator<Item = Pubkey>,
+    ) -> Self {
+        Self {
+            client,
+            store: *store,
+            oracle: *oracle,
+            tokens: tokens.into_iter().collect(),
+            hint: None,
+            feeds_parser: FeedsParser::default(),
+            alts: Default::default(),
+        }
+    }
+
+    /// Insert an Address Lookup Table.
+    pub fn add_alt(&mut self, account: AddressLookupTableAccount) -> &mut Self {
+        self.alts.insert(account.key, account.addresses);
+        self
+    }
+
+    /// Prepare hint for updating the price circuit breakers.
+    pub async fn prepare_hint(&mut self) -> crate::Result<UpdatePriceCircuitBreakersHint> {
+        match &self.hint {
+            Some(hint) => Ok(hint.clone()),
+            None => {
+                let token_map_address = self
+                    .client
+                    .authorized_token_map_address(&self.store)
+                    .await?
+                    .ok_or(crate::Error::custom(
+                        "token map is not configurated for the store",
+                    ))?;
+                let token_map = self.client.token_map(&token_map_address).await?;
+                let records =
+                    token_records(&token_map, &self.tokens).map_err(crate::Error::custom)?;
+                let tokens_with_feed =
+                    TokensWithFeed::try_from_records(records).map_err(crate::Error::custom)?;
+                let hint = UpdatePriceCircuitBreakersHint {
+                    token_map: token_map_address,
+                    tokens_with_feed,
+                };
+                self.hint = Some(hint.clone());
+                Ok(hint)
+            }
+        }
+    }
+
+    /// Build [`TransactionBuilder`] for updating the price circuit breakers.
+    pub async fn build(&mut self) -> crate::Result<TransactionBuilder<'a, C>> {
+        let hint = self.prepare_hint().await?;
+        let feeds = self.feeds_parser.parse_and_sort_by_tokens(hint.feeds())?;
+
+        let rpc = self
+            .client
+            .store_transaction()
+            .accounts(fix_optional_account_metas(
+                accounts::UpdatePriceCircuitBreakers {
+                    authority: self.client.payer(),
+                    store: self.store,
+                    token_map: hint.token_map,
+                    oracle: self.oracle,
+                    chainlink_program: None,
+                },
+                &ID,
+                self.client.store_program_id(),
+            ))
+            .anchor_args(args::UpdatePriceCircuitBreakers {
+                tokens: self.tokens.iter().copied().collect(),
+            })
+            .accounts(feeds)
+            .lookup_tables(self.alts.clone());
+
+        Ok(rpc)
+    }
+}
+
+/// Hint for `update_price_circuit_breakers`.
+#[derive(Clone)]
+pub struct UpdatePriceCircuitBreakersHint {
+    token_map: Pubkey,
+    tokens_with_feed: TokensWithFeed,
+}
+
+impl UpdatePriceCircuitBreakersHint {
+    /// Get feeds.
+    pub fn feeds(&self) -> &TokensWithFeed {
+        &self.tokens_with_feed
+    }
+}
+
+impl<'a, C: Deref<Target = impl Signer> + Clone> MakeBundleBuilder<'a, C>
+    for UpdatePriceCircuitBreakersBuilder<'a, C>
+{
+    async fn build_with_options(
+        &mut self,
+        options: BundleOptions,
+    ) -> gmsol_solana_utils::Result<BundleBuilder<'a, C>> {
+        let mut bundle = self.client.bundle_with_options(options);
+
+        bundle.push(
+            self.build()
+                .await
+                .map_err(gmsol_solana_utils::Error::custom)?,
+        )?;
+
+        Ok(bundle)
+    }
+}
+
+impl<C: Deref<Target = impl Signer> + Clone> PullOraclePriceConsumer
+    for UpdatePriceCircuitBreakersBuilder<'_, C>
+{
+    async fn feed_ids(&mut self) -> crate::Result<FeedIds> {
+        let hint = self.prepare_hint().await?;
+        Ok(FeedIds::new(self.store, hint.tokens_with_feed))
+    }
+
+    fn process_feeds(
+        &mut self,
+        provider: PriceProviderKind,
+        map: FeedAddressMap,
+    ) -> crate::Result<()> {
+        self.feeds_parser
+            .insert_pull_oracle_feed_parser(provider, map);
+        Ok(())
+    }
+}
+
+impl<C> SetExecutionFee for UpdatePriceCircuitBreakersBuilder<'_, C> {
+    fn is_execution_fee_estimation_required(&self) -> bool {
+        false
+    }
+
+    fn set_execution_fee(&mut self, _lamports: u64) -> &mut Self {
+        self
+    }
+}
@@ -0,0 +1,149 @@
+use std::ops::Deref;
+
+use anchor_lang::system_program;
+use gmsol_programs::gmsol_store::{
+    client::{accounts, args},
+    types::RiskOracleRecommendation,
+};
+use gmsol_solana_utils::transaction_builder::TransactionBuilder;
+use gmsol_utils::market::MarketConfigKey;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+/// Operations for risk oracle.
+pub trait RiskOracleOps<C> {
+    /// Initialize the risk oracle account of the store.
+    ///
+    /// `signer` is the keccak hash of the compressed secp256k1 public key of
+    /// the risk oracle signer.
+    fn initialize_risk_oracle(
+        &self,
+        store: &Pubkey,
+        signer: &Pubkey,
+        max_age: u32,
+    ) -> TransactionBuilder<C, Pubkey>;
+
+    /// Update the signer and the max age of recommendations.
+    fn update_risk_oracle(
+        &self,
+        store: &Pubkey,
+        signer: &Pubkey,
+        max_age: u32,
+    ) -> TransactionBuilder<C>;
+
+    /// Set the bounded range of the given key.
+    fn set_risk_oracle_bound(
+        &self,
+        store: &Pubkey,
+        key: MarketConfigKey,
+        min: u128,
+        max: u128,
+    ) -> TransactionBuilder<C>;
+
+    /// Remove the bounded range of the given key.
+    fn remove_risk_oracle_bound(
+        &self,
+        store: &Pubkey,
+        key: MarketConfigKey,
+    ) -> TransactionBuilder<C>;
+
+    /// Apply a signed recommendation to the market.
+    fn apply_risk_oracle_recommendation(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        recommendation: RiskOracleRecommendation,
+    ) -> TransactionBuilder<C>;
+}
+
+impl<C: Deref<Target = impl Signer> + Clone> RiskOracleOps<C> for crate::Client<C> {
+    fn initialize_risk_oracle(
+        &self,
+        store: &Pubkey,
+        signer: &Pubkey,
+        max_age: u32,
+    ) -> TransactionBuilder<C, Pubkey> {
+        let risk_oracle = self.find_risk_oracle_address(store);
+        self.store_transaction()
+            .anchor_accounts(accounts::InitializeRiskOracle {
+                authority: self.payer(),
+                store: *store,
+                risk_oracle,
+                system_program: system_program::ID,
+            })
+            .anchor_args(args::InitializeRiskOracle {
+                signer: *signer,
+                max_age,
+            })
+            .output(risk_oracle)
+    }
+
+    fn update_risk_oracle(
+        &self,
+        store: &Pubkey,
+        signer: &Pubkey,
+        max_age: u32,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::UpdateRiskOracle {
+                authority: self.payer(),
+                store: *store,
+                risk_oracle: self.find_risk_oracle_address(store),
+            })
+            .anchor_args(args::UpdateRiskOracle {
+                signer: *signer,
+                max_age,
+            })
+    }
+
+    fn set_risk_oracle_bound(
+        &self,
+        store: &Pubkey,
+        key: MarketConfigKey,
+        min: u128,
+        max: u128,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::SetRiskOracleBound {
+                authority: self.payer(),
+                store: *store,
+                risk_oracle: self.find_risk_oracle_address(store),
+            })
+            .anchor_args(args::SetRiskOracleBound {
+                key: key.to_string(),
+                min,
+                max,
+            })
+    }
+
+    fn remove_risk_oracle_bound(
+        &self,
+        store: &Pubkey,
+        key: MarketConfigKey,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::RemoveRiskOracleBound {
+                authority: self.payer(),
+                store: *store,
+                risk_oracle: self.find_risk_oracle_address(store),
+            })
+            .anchor_args(args::RemoveRiskOracleBound {
+                key: key.to_string(),
+            })
+    }
+
+    fn apply_risk_oracle_recommendation(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        recommendation: RiskOracleRecommendation,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::ApplyRiskOracleRecommendation {
+                authority: self.payer(),
+                store: *store,
+                risk_oracle: self.find_risk_oracle_address(store),
+                market: self.find_market_address(store, market_token),
+            })
+            .anchor_args(args::ApplyRiskOracleRecommendation { recommendation })
+    }
+}
@@ -34,6 +34,34 @@ pub trait StoreOps<C> {
     /// Initialize callback authority.
     fn initialize_callback_authority(&self) -> TransactionBuilder<C>;
 
+    /// Add a program to the callback program allowlist of the store.
+    ///
+    /// `action_kinds` is the bitmap of the action kinds the program is allowed to
+    /// receive callbacks for.
+    fn register_callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> TransactionBuilder<C, Pubkey>;
+
+    /// Update the allowed action kinds and the compute unit limit hint of a callback program.
+    fn update_callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> TransactionBuilder<C>;
+
+    /// Remove a program from the callback program allowlist of the store.
+    fn unregister_callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+    ) -> TransactionBuilder<C>;
+
     /// Update last restarted slot.
     fn update_last_restarted_slot(&self, store: &Pubkey) -> TransactionBuilder<C>;
 }
@@ -124,6 +152,62 @@ impl<C: Deref<Target = impl Signer> + Clone> StoreOps<C> for crate::Client<C> {
             })
     }
 
+    fn register_callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> TransactionBuilder<C, Pubkey> {
+        let callback_program = self.find_callback_program_address(store, program_id);
+        self.store_transaction()
+            .anchor_accounts(accounts::RegisterCallbackProgram {
+                authority: self.payer(),
+                store: *store,
+                program: *program_id,
+                callback_program,
+                system_program: system_program::ID,
+            })
+            .anchor_args(args::RegisterCallbackProgram {
+                action_kinds,
+                compute_unit_limit,
+            })
+            .output(callback_program)
+    }
+
+    fn update_callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::UpdateCallbackProgram {
+                authority: self.payer(),
+                store: *store,
+                callback_program: self.find_callback_program_address(store, program_id),
+            })
+            .anchor_args(args::UpdateCallbackProgram {
+                action_kinds,
+                compute_unit_limit,
+            })
+    }
+
+    fn unregister_callback_program(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::UnregisterCallbackProgram {
+                authority: self.payer(),
+                store: *store,
+                callback_program: self.find_callback_program_address(store, program_id),
+            })
+            .anchor_args(args::UnregisterCallbackProgram {})
+    }
+
     fn update_last_restarted_slot(&self, store: &Pubkey) -> TransactionBuilder<C> {
         let authority = self.payer();
         self.store_transaction()
@@ -30,6 +30,34 @@ pub trait TimelockOps<C> {
     /// Increase timelock delay.
     fn increase_timelock_delay(&self, store: &Pubkey, delta: u32) -> TransactionBuilder<C>;
 
+    /// Set the delay override for the instructions of the given program.
+    ///
+    /// If `discriminator` is provided, the override only applies to the
+    /// instructions with the discriminator.
+    fn set_timelock_delay_override(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        discriminator: Option<[u8; 8]>,
+        delay: u32,
+    ) -> TransactionBuilder<C>;
+
+    /// Remove the delay override.
+    fn remove_timelock_delay_override(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        discriminator: Option<[u8; 8]>,
+    ) -> TransactionBuilder<C>;
+
+    /// Set the min delay of the instructions executed by the executor of the given role.
+    fn set_executor_min_delay(
+        &self,
+        store: &Pubkey,
+        role: &str,
+        min_delay: u32,
+    ) -> crate::Result<TransactionBuilder<C>>;
+
     /// Initialize [`Executor`] account.
     fn initialize_executor(
         &self,
@@ -88,6 +116,47 @@ pub trait TimelockOps<C> {
         hint: Option<ExecuteTimelockedInstructionHint<'_>>,
     ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
 
+    /// Create a timelocked proposal for the given instruction buffers.
+    ///
+    /// The instructions will be executed atomically in the given order.
+    fn create_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        role: &str,
+        proposal: impl Signer + 'static,
+        buffers: impl IntoIterator<Item = Pubkey>,
+        depends_on: Option<&Pubkey>,
+    ) -> crate::Result<TransactionBuilder<C, Pubkey>>;
+
+    /// Approve timelocked proposal.
+    fn approve_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+        role_hint: Option<&str>,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Cancel timelocked proposal and its instructions.
+    fn cancel_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Execute timelocked proposal.
+    fn execute_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Close executed timelocked proposal.
+    fn close_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
     /// Timelock-bypassed revoke role.
     fn timelock_bypassed_revoke_role(
         &self,
@@ -143,6 +212,67 @@ impl<C: Deref<Target = impl Signer> + Clone> TimelockOps<C> for crate::Client<C>
             })
     }
 
+    fn set_timelock_delay_override(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        discriminator: Option<[u8; 8]>,
+        delay: u32,
+    ) -> TransactionBuilder<C> {
+        self.timelock_transaction()
+            .anchor_args(args::SetDelayOverride {
+                program_id: *program_id,
+                discriminator,
+                delay,
+            })
+            .anchor_accounts(accounts::SetDelayOverride {
+                authority: self.payer(),
+                store: *store,
+                timelock_config: self.find_timelock_config_address(store),
+                store_program: *self.store_program_id(),
+            })
+    }
+
+    fn remove_timelock_delay_override(
+        &self,
+        store: &Pubkey,
+        program_id: &Pubkey,
+        discriminator: Option<[u8; 8]>,
+    ) -> TransactionBuilder<C> {
+        self.timelock_transaction()
+            .anchor_args(args::RemoveDelayOverride {
+                program_id: *program_id,
+                discriminator,
+            })
+            .anchor_accounts(accounts::SetDelayOverride {
+                authority: self.payer(),
+                store: *store,
+                timelock_config: self.find_timelock_config_address(store),
+                store_program: *self.store_program_id(),
+            })
+    }
+
+    fn set_executor_min_delay(
+        &self,
+        store: &Pubkey,
+        role: &str,
+        min_delay: u32,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let executor = self.find_executor_address(store, role)?;
+        Ok(self
+            .timelock_transaction()
+            .anchor_args(args::SetExecutorMinDelay {
+                role: role.to_string(),
+                min_delay,
+            })
+            .anchor_accounts(accounts::SetExecutorMinDelay {
+                authority: self.payer(),
+                store: *store,
+                executor,
+                store_program: *self.store_program_id(),
+            }))
+    }
+
     fn initialize_executor(
         &self,
         store: &Pubkey,
@@ -437,6 +567,175 @@ impl<C: Deref<Target = impl Signer> + Clone> TimelockOps<C> for crate::Client<C>
             .accounts(accounts))
     }
 
+    fn create_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        role: &str,
+        proposal: impl Signer + 'static,
+        buffers: impl IntoIterator<Item = Pubkey>,
+        depends_on: Option<&Pubkey>,
+    ) -> crate::Result<TransactionBuilder<C, Pubkey>> {
+        let executor = self.find_executor_address(store, role)?;
+        let address = proposal.pubkey();
+        Ok(self
+            .timelock_transaction()
+            .anchor_args(args::CreateProposal {
+                depends_on: depends_on.copied(),
+            })
+            .anchor_accounts(accounts::CreateProposal {
+                authority: self.payer(),
+                store: *store,
+                executor,
+                proposal: address,
+                depends_on: depends_on.copied(),
+                store_program: *self.store_program_id(),
+                system_program: system_program::ID,
+            })
+            .accounts(
+                buffers
+                    .into_iter()
+                    .map(|pubkey| AccountMeta::new(pubkey, false))
+                    .collect::<Vec<_>>(),
+            )
+            .owned_signer(Arc::new(proposal))
+            .output(address))
+    }
+
+    async fn approve_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+        role_hint: Option<&str>,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let role = match role_hint {
+            Some(role) => role.to_string(),
+            None => {
+                let executor = self
+                    .timelock_proposal(proposal)
+                    .await?
+                    .ok_or(crate::Error::NotFound)?
+                    .executor;
+                let executor = self
+                    .account::<ZeroCopy<Executor>>(&executor)
+                    .await?
+                    .ok_or(crate::Error::NotFound)?
+                    .0;
+                executor.role_name()?.to_string()
+            }
+        };
+        let executor = self.find_executor_address(store, &role)?;
+        Ok(self
+            .timelock_transaction()
+            .anchor_args(args::ApproveProposal { role })
+            .anchor_accounts(accounts::ApproveProposal {
+                authority: self.payer(),
+                store: *store,
+                executor,
+                proposal: *proposal,
+                store_program: *self.store_program_id(),
+            }))
+    }
+
+    async fn cancel_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let account = self
+            .timelock_proposal(proposal)
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        Ok(self
+            .timelock_transaction()
+            .anchor_args(args::CancelProposal {})
+            .anchor_accounts(accounts::CancelProposal {
+                authority: self.payer(),
+                store: *store,
+                rent_receiver: account.rent_receiver,
+                proposal: *proposal,
+                depends_on: account.depends_on().copied(),
+                store_program: *self.store_program_id(),
+            })
+            .accounts(
+                account
+                    .instructions()
+                    .iter()
+                    .map(|pubkey| AccountMeta::new(*pubkey, false))
+                    .collect::<Vec<_>>(),
+            ))
+    }
+
+    async fn execute_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let account = self
+            .timelock_proposal(proposal)
+            .await?
+            .ok_or(crate::Error::NotFound)?;
+        let executor = account.executor;
+        let wallet = self.find_executor_wallet_address(&executor);
+
+        let mut buffers = Vec::with_capacity(account.instructions().len());
+        let mut accounts = Vec::<AccountMeta>::default();
+        for address in account.instructions() {
+            let buffer = self
+                .instruction_buffer(address)
+                .await?
+                .ok_or(crate::Error::NotFound)?;
+            buffers.push(AccountMeta::new(*address, false));
+            for mut meta in buffer.accounts().map(AccountMeta::from) {
+                if meta.pubkey == wallet {
+                    meta.is_signer = false;
+                }
+                match accounts.iter_mut().find(|a| a.pubkey == meta.pubkey) {
+                    Some(existing) => existing.is_writable |= meta.is_writable,
+                    None => accounts.push(meta),
+                }
+            }
+        }
+        buffers.append(&mut accounts);
+
+        Ok(self
+            .timelock_transaction()
+            .anchor_args(args::ExecuteProposal {})
+            .anchor_accounts(accounts::ExecuteProposal {
+                authority: self.payer(),
+                store: *store,
+                timelock_config: self.find_timelock_config_address(store),
+                executor,
+                wallet,
+                rent_receiver: account.rent_receiver,
+                proposal: *proposal,
+                depends_on: account.depends_on().copied(),
+                store_program: *self.store_program_id(),
+            })
+            .accounts(buffers))
+    }
+
+    async fn close_timelocked_proposal(
+        &self,
+        store: &Pubkey,
+        proposal: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let rent_receiver = self
+            .timelock_proposal(proposal)
+            .await?
+            .ok_or(crate::Error::NotFound)?
+            .rent_receiver;
+        Ok(self
+            .timelock_transaction()
+            .anchor_args(args::CloseProposal {})
+            .anchor_accounts(accounts::CloseProposal {
+                authority: self.payer(),
+                store: *store,
+                rent_receiver,
+                proposal: *proposal,
+                store_program: *self.store_program_id(),
+            }))
+    }
+
     fn timelock_bypassed_revoke_role(
         &self,
         store: &Pubkey,
@@ -3,7 +3,8 @@ use std::ops::Deref;
 use gmsol_programs::gmsol_store::client::{accounts, args};
 use gmsol_solana_utils::transaction_builder::TransactionBuilder;
 use gmsol_utils::{
-    oracle::PriceProviderKind, price::market_status::MarketStatusFlag,
+    oracle::{CompositePriceMode, PriceProviderKind},
+    price::market_status::MarketStatusFlag,
     token_config::UpdateTokenConfigParams,
 };
 use solana_sdk::{pubkey::Pubkey, signer::Signer, system_program};
@@ -94,6 +95,37 @@ pub trait TokenConfigOps<C> {
         provider: PriceProviderKind,
         update: UpdateFeedConfig,
     ) -> crate::Result<TransactionBuilder<C>>;
+
+    /// Set composite price config.
+    fn set_composite_price(
+        &self,
+        store: &Pubkey,
+        token_map: &Pubkey,
+        token: &Pubkey,
+        mode: CompositePriceMode,
+        sources: &[PriceProviderKind],
+        max_disagreement_factor: Option<&Value>,
+    ) -> crate::Result<TransactionBuilder<C>>;
+
+    /// Set price circuit breaker config.
+    ///
+    /// Passing `None` as `max_move_factor` disables the circuit breaker.
+    fn set_price_circuit_breaker(
+        &self,
+        store: &Pubkey,
+        token_map: &Pubkey,
+        token: &Pubkey,
+        max_move_factor: Option<&Value>,
+        window: u32,
+    ) -> crate::Result<TransactionBuilder<C>>;
+
+    /// Reset the price circuit breaker of the given token.
+    fn reset_price_circuit_breaker(
+        &self,
+        store: &Pubkey,
+        token_map: &Pubkey,
+        token: &Pubkey,
+    ) -> TransactionBuilder<C>;
 }
 
 impl<C: Deref<Target = impl Signer> + Clone> TokenConfigOps<C> for crate::Client<C> {
@@ -283,6 +315,73 @@ impl<C: Deref<Target = impl Signer> + Clone> TokenConfigOps<C> for crate::Client
             });
         Ok(txn)
     }
+
+    fn set_composite_price(
+        &self,
+        store: &Pubkey,
+        token_map: &Pubkey,
+        token: &Pubkey,
+        mode: CompositePriceMode,
+        sources: &[PriceProviderKind],
+        max_disagreement_factor: Option<&Value>,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let authority = self.payer();
+        let txn = self
+            .store_transaction()
+            .anchor_accounts(accounts::SetCompositePrice {
+                authority,
+                store: *store,
+                token_map: *token_map,
+            })
+            .anchor_args(args::SetCompositePrice {
+                token: *token,
+                mode: mode.into(),
+                sources: sources.iter().map(|kind| u8::from(*kind)).collect(),
+                max_disagreement_factor: max_disagreement_factor
+                    .map(|f| f.to_u128())
+                    .transpose()?,
+            });
+        Ok(txn)
+    }
+
+    fn set_price_circuit_breaker(
+        &self,
+        store: &Pubkey,
+        token_map: &Pubkey,
+        token: &Pubkey,
+        max_move_factor: Option<&Value>,
+        window: u32,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let authority = self.payer();
+        let txn = self
+            .store_transaction()
+            .anchor_accounts(accounts::SetPriceCircuitBreaker {
+                authority,
+                store: *store,
+                token_map: *token_map,
+            })
+            .anchor_args(args::SetPriceCircuitBreaker {
+                token: *token,
+                max_move_factor: max_move_factor.map(|f| f.to_u128()).transpose()?,
+                window,
+            });
+        Ok(txn)
+    }
+
+    fn reset_price_circuit_breaker(
+        &self,
+        store: &Pubkey,
+        token_map: &Pubkey,
+        token: &Pubkey,
+    ) -> TransactionBuilder<C> {
+        self.store_transaction()
+            .anchor_accounts(accounts::ResetPriceCircuitBreaker {
+                authority: self.payer(),
+                store: *store,
+                token_map: *token_map,
+            })
+            .anchor_args(args::ResetPriceCircuitBreaker { token: *token })
+    }
 }
 
 /// Contains updated parameters for the feed config.
@@ -2,7 +2,7 @@ use std::{future::Future, ops::Deref};
 
 use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 use gmsol_programs::gmsol_treasury::{
-    accounts::{Config, GtBank, GtExchange, TreasuryVaultConfig},
+    accounts::{BuybackPlan, Config, GtBank, GtExchange, TreasuryVaultConfig},
     client::{accounts, args},
     ID,
 };
@@ -183,6 +183,36 @@ pub trait TreasuryOps<C> {
         order: &Pubkey,
         hint: Option<(&Pubkey, &Pubkey)>,
     ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Create a buyback plan swapping along the swap path ending with the market of `market_token`.
+    fn create_buyback_plan(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        swap_in_token: &Pubkey,
+        swap_out_token: &Pubkey,
+        params: BuybackPlanParams,
+        treasury_vault_config_hint: Option<&Pubkey>,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C, Pubkey>>>;
+
+    /// Close a buyback plan.
+    fn close_buyback_plan(&self, store: &Pubkey, buyback_plan: &Pubkey) -> TransactionBuilder<C>;
+
+    /// Execute the next slice of a buyback plan.
+    ///
+    /// The last slice is settled first if it has not been settled.
+    fn execute_buyback_plan(
+        &self,
+        store: &Pubkey,
+        buyback_plan: &Pubkey,
+        oracle: &Pubkey,
+    ) -> ExecuteBuybackPlanBuilder<C>;
+
+    /// Settle the last slice of a buyback plan.
+    fn settle_buyback_plan(
+        &self,
+        buyback_plan: &Pubkey,
+    ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
 }
 
 impl<C: Deref<Target = impl Signer> + Clone> TreasuryOps<C> for crate::Client<C> {
@@ -798,6 +828,8 @@ impl<C: Deref<Target = impl Signer> + Clone> TreasuryOps<C> for crate::Client<C>
                 callback_program,
                 callback_shared_data_account,
                 callback_partitioned_data_account,
+                callback_program_config: callback_program
+                    .map(|program_id| self.find_callback_program_address(store, &program_id)),
             })
             .accounts(swap_path);
 
@@ -883,6 +915,113 @@ impl<C: Deref<Target = impl Signer> + Clone> TreasuryOps<C> for crate::Client<C>
 
         Ok(prepare.merge(cancel))
     }
+
+    async fn create_buyback_plan(
+        &self,
+        store: &Pubkey,
+        market_token: &Pubkey,
+        swap_in_token: &Pubkey,
+        swap_out_token: &Pubkey,
+        params: BuybackPlanParams,
+        treasury_vault_config_hint: Option<&Pubkey>,
+    ) -> crate::Result<TransactionBuilder<C, Pubkey>> {
+        let BuybackPlanParams {
+            budget,
+            slice,
+            interval,
+            max_slippage,
+            swap_path,
+        } = params;
+        if max_slippage > crate::constants::MARKET_USD_UNIT {
+            return Err(crate::Error::custom(
+                "cannot use a max slippage greater than 1",
+            ));
+        }
+        if slice == 0 || slice > budget {
+            return Err(crate::Error::custom(
+                "slice must be positive and not greater than the budget",
+            ));
+        }
+        let (config, treasury_vault_config) =
+            find_config_addresses(self, store, treasury_vault_config_hint).await?;
+        let buyback_plan = self.find_buyback_plan_address(&config, swap_in_token, swap_out_token);
+        let swap_path = swap_path
+            .iter()
+            .chain(Some(market_token))
+            .map(|token| AccountMeta {
+                pubkey: self.find_market_address(store, token),
+                is_signer: false,
+                is_writable: false,
+            })
+            .collect::<Vec<_>>();
+        Ok(self
+            .treasury_transaction()
+            .anchor_args(args::CreateBuybackPlan {
+                budget,
+                slice,
+                interval,
+                max_slippage,
+            })
+            .anchor_accounts(accounts::CreateBuybackPlan {
+                authority: self.payer(),
+                store: *store,
+                config,
+                treasury_vault_config,
+                swap_in_token: *swap_in_token,
+                swap_out_token: *swap_out_token,
+                buyback_plan,
+                store_program: *self.store_program_id(),
+                system_program: system_program::ID,
+            })
+            .accounts(swap_path)
+            .output(buyback_plan))
+    }
+
+    fn close_buyback_plan(&self, store: &Pubkey, buyback_plan: &Pubkey) -> TransactionBuilder<C> {
+        let config = self.find_treasury_config_address(store);
+        self.treasury_transaction()
+            .anchor_args(args::CloseBuybackPlan {})
+            .anchor_accounts(accounts::CloseBuybackPlan {
+                authority: self.payer(),
+                store: *store,
+                config,
+                buyback_plan: *buyback_plan,
+                buyback_owner: self.find_buyback_owner_address(buyback_plan),
+                receiver: self.find_treasury_receiver_address(&config),
+                store_program: *self.store_program_id(),
+                system_program: system_program::ID,
+            })
+    }
+
+    fn execute_buyback_plan(
+        &self,
+        store: &Pubkey,
+        buyback_plan: &Pubkey,
+        oracle: &Pubkey,
+    ) -> ExecuteBuybackPlanBuilder<C> {
+        ExecuteBuybackPlanBuilder::new(self, store, buyback_plan, oracle)
+    }
+
+    async fn settle_buyback_plan(
+        &self,
+        buyback_plan: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let plan = self
+            .account::<ZeroCopy<BuybackPlan>>(buyback_plan)
+            .await?
+            .ok_or(crate::Error::NotFound)?
+            .0;
+        let order = plan
+            .last_order()
+            .ok_or_else(|| crate::Error::custom("the buyback plan has no swap order"))?;
+        Ok(settle_buyback_plan(
+            self,
+            &plan.config,
+            buyback_plan,
+            &plan.swap_in_token,
+            order,
+        ))
+    }
 }
 
 /// Create Treasury Swap Options.
@@ -1085,6 +1224,320 @@ impl<C> SetExecutionFee for ConfirmGtBuybackBuilder<'_, C> {
     }
 }
 
+/// Buyback plan params.
+#[derive(Debug, Clone)]
+pub struct BuybackPlanParams {
+    /// The total amount of the swap in token to spend.
+    pub budget: u64,
+    /// The max amount of the swap in token to spend in a single swap.
+    pub slice: u64,
+    /// The min interval (in seconds) between two swaps.
+    pub interval: u32,
+    /// The max slippage factor from the oracle price.
+    pub max_slippage: u128,
+    /// The extra market tokens of the swap path, preceding the market of the swap orders.
+    pub swap_path: Vec<Pubkey>,
+}
+
+fn settle_buyback_plan<'a, C: Deref<Target = impl Signer> + Clone>(
+    client: &'a crate::Client<C>,
+    config: &Pubkey,
+    buyback_plan: &Pubkey,
+    swap_in_token: &Pubkey,
+    order: &Pubkey,
+) -> TransactionBuilder<'a, C> {
+    // Currently only SPL-Token is supported.
+    let token_program_id = anchor_spl::token::ID;
+
+    let receiver = client.find_treasury_receiver_address(config);
+    let buyback_owner = client.find_buyback_owner_address(buyback_plan);
+    client
+        .treasury_transaction()
+        .anchor_args(args::SettleBuybackPlan {})
+        .anchor_accounts(accounts::SettleBuybackPlan {
+            authority: client.payer(),
+            config: *config,
+            buyback_plan: *buyback_plan,
+            swap_in_token: *swap_in_token,
+            receiver,
+            swap_in_token_receiver_vault: get_associated_token_address_with_program_id(
+                &receiver,
+                swap_in_token,
+                &token_program_id,
+            ),
+            buyback_owner,
+            swap_in_token_owner_vault: get_associated_token_address_with_program_id(
+                &buyback_owner,
+                swap_in_token,
+                &token_program_id,
+            ),
+            order: *order,
+            token_program: token_program_id,
+        })
+}
+
+/// Execute buyback plan builder.
+pub struct ExecuteBuybackPlanBuilder<'a, C> {
+    client: &'a crate::Client<C>,
+    store: Pubkey,
+    buyback_plan: Pubkey,
+    oracle: Pubkey,
+    nonce: Option<NonceBytes>,
+    with_chainlink_program: bool,
+    feeds_parser: FeedsParser,
+    hint: Option<ExecuteBuybackPlanHint>,
+}
+
+/// Hint for executing buyback plan.
+#[derive(Debug, Clone)]
+pub struct ExecuteBuybackPlanHint {
+    config: Pubkey,
+    treasury_vault_config: Pubkey,
+    token_map: Pubkey,
+    swap_in_token: Pubkey,
+    swap_out_token: Pubkey,
+    swap_path: Vec<Pubkey>,
+    unsettled_order: Option<Pubkey>,
+    feeds: TokensWithFeed,
+}
+
+impl<'a, C: Deref<Target = impl Signer> + Clone> ExecuteBuybackPlanBuilder<'a, C> {
+    pub(super) fn new(
+        client: &'a crate::Client<C>,
+        store: &Pubkey,
+        buyback_plan: &Pubkey,
+        oracle: &Pubkey,
+    ) -> Self {
+        Self {
+            client,
+            store: *store,
+            buyback_plan: *buyback_plan,
+            oracle: *oracle,
+            nonce: None,
+            with_chainlink_program: false,
+            feeds_parser: Default::default(),
+            hint: None,
+        }
+    }
+
+    /// Set the nonce of the swap order.
+    pub fn nonce(&mut self, nonce: NonceBytes) -> &mut Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Prepare [`ExecuteBuybackPlanHint`].
+    pub async fn prepare_hint(&mut self) -> crate::Result<ExecuteBuybackPlanHint> {
+        match &self.hint {
+            Some(hint) => Ok(hint.clone()),
+            None => {
+                let (config, treasury_vault_config) =
+                    find_config_addresses(self.client, &self.store, None).await?;
+                let map_address = self
+                    .client
+                    .authorized_token_map_address(&self.store)
+                    .await?
+                    .ok_or_else(|| crate::Error::custom("token map is not set"))?;
+                let map = self.client.token_map(&map_address).await?;
+                let plan = self
+                    .client
+                    .account::<ZeroCopy<BuybackPlan>>(&self.buyback_plan)
+                    .await?
+                    .ok_or(crate::Error::NotFound)?
+                    .0;
+                let hint = ExecuteBuybackPlanHint {
+                    config,
+                    treasury_vault_config,
+                    token_map: map_address,
+                    swap_in_token: plan.swap_in_token,
+                    swap_out_token: plan.swap_out_token,
+                    swap_path: plan.swap_path().to_vec(),
+                    unsettled_order: if plan.pending == 0 {
+                        None
+                    } else {
+                        plan.last_order().copied()
+                    },
+                    feeds: plan.to_feeds(&map)?,
+                };
+                self.hint = Some(hint.clone());
+                Ok(hint)
+            }
+        }
+    }
+
+    async fn build_txn(&mut self) -> crate::Result<TransactionBuilder<'a, C>> {
+        let hint = self.prepare_hint().await?;
+        let client = self.client;
+        let store = &self.store;
+
+        let nonce = self.nonce.unwrap_or_else(|| generate_nonce().to_bytes());
+        let market = *hint
+            .swap_path
+            .last()
+            .ok_or_else(|| crate::Error::custom("empty swap path"))?;
+        let swap_path = hint
+            .swap_path
+            .iter()
+            .map(|market| AccountMeta {
+                pubkey: *market,
+                is_signer: false,
+                is_writable: false,
+            })
+            .collect::<Vec<_>>();
+
+        let receiver = client.find_treasury_receiver_address(&hint.config);
+        let buyback_owner = client.find_buyback_owner_address(&self.buyback_plan);
+
+        // Currently only SPL-Token is supported.
+        let token_program_id = anchor_spl::token::ID;
+
+        let swap_in_token_receiver_vault = get_associated_token_address_with_program_id(
+            &receiver,
+            &hint.swap_in_token,
+            &token_program_id,
+        );
+        let swap_in_token_owner_vault = get_associated_token_address_with_program_id(
+            &buyback_owner,
+            &hint.swap_in_token,
+            &token_program_id,
+        );
+        let order = client.find_order_address(store, &buyback_owner, &nonce);
+        let swap_in_token_escrow = get_associated_token_address_with_program_id(
+            &order,
+            &hint.swap_in_token,
+            &token_program_id,
+        );
+        let swap_out_token_escrow = get_associated_token_address_with_program_id(
+            &order,
+            &hint.swap_out_token,
+            &token_program_id,
+        );
+
+        let prepare_swap_in_escrow = client.prepare_associated_token_account(
+            &hint.swap_in_token,
+            &token_program_id,
+            Some(&order),
+        );
+        let prepare_swap_out_escrow = client.prepare_associated_token_account(
+            &hint.swap_out_token,
+            &token_program_id,
+            Some(&order),
+        );
+        let prepare_ata = client.prepare_associated_token_account(
+            &hint.swap_out_token,
+            &token_program_id,
+            Some(&receiver),
+        );
+
+        let chainlink_program = if self.with_chainlink_program {
+            Some(program_ids::CHAINLINK)
+        } else {
+            None
+        };
+
+        let feeds = self.feeds_parser.parse_and_sort_by_tokens(&hint.feeds)?;
+
+        let execute = client
+            .treasury_transaction()
+            .anchor_args(args::ExecuteBuybackPlan { nonce })
+            .accounts(fix_optional_account_metas(
+                accounts::ExecuteBuybackPlan {
+                    authority: client.payer(),
+                    store: *store,
+                    config: hint.config,
+                    treasury_vault_config: hint.treasury_vault_config,
+                    buyback_plan: self.buyback_plan,
+                    swap_in_token: hint.swap_in_token,
+                    swap_out_token: hint.swap_out_token,
+                    swap_in_token_receiver_vault,
+                    market,
+                    receiver,
+                    buyback_owner,
+                    swap_in_token_owner_vault,
+                    user: client.find_user_address(store, &buyback_owner),
+                    swap_in_token_escrow,
+                    swap_out_token_escrow,
+                    order,
+                    token_map: hint.token_map,
+                    oracle: self.oracle,
+                    event_authority: client.store_event_authority(),
+                    store_program: *client.store_program_id(),
+                    token_program: token_program_id,
+                    associated_token_program: anchor_spl::associated_token::ID,
+                    system_program: system_program::ID,
+                    chainlink_program,
+                },
+                &ID,
+                client.treasury_program_id(),
+            ))
+            .accounts(feeds)
+            .accounts(swap_path);
+
+        Ok(prepare_ata
+            .merge(prepare_swap_in_escrow)
+            .merge(prepare_swap_out_escrow)
+            .merge(execute))
+    }
+}
+
+impl<'a, C: Deref<Target = impl Signer> + Clone> MakeBundleBuilder<'a, C>
+    for ExecuteBuybackPlanBuilder<'a, C>
+{
+    async fn build_with_options(
+        &mut self,
+        options: BundleOptions,
+    ) -> gmsol_solana_utils::Result<BundleBuilder<'a, C>> {
+        let mut tx = self.client.bundle_with_options(options);
+
+        let hint = self
+            .prepare_hint()
+            .await
+            .map_err(gmsol_solana_utils::Error::custom)?;
+        if let Some(order) = hint.unsettled_order {
+            tx.try_push(settle_buyback_plan(
+                self.client,
+                &hint.config,
+                &self.buyback_plan,
+                &hint.swap_in_token,
+                &order,
+            ))?;
+        }
+
+        tx.try_push(
+            self.build_txn()
+                .await
+                .map_err(gmsol_solana_utils::Error::custom)?,
+        )?;
+
+        Ok(tx)
+    }
+}
+
+impl<C: Deref<Target = impl Signer> + Clone> PullOraclePriceConsumer
+    for ExecuteBuybackPlanBuilder<'_, C>
+{
+    async fn feed_ids(&mut self) -> crate::Result<FeedIds> {
+        let hint = self.prepare_hint().await?;
+        Ok(FeedIds::new(self.store, hint.feeds))
+    }
+
+    fn process_feeds(
+        &mut self,
+        provider: PriceProviderKind,
+        map: FeedAddressMap,
+    ) -> crate::Result<()> {
+        self.feeds_parser
+            .insert_pull_oracle_feed_parser(provider, map);
+        Ok(())
+    }
+}
+
+impl<C> SetExecutionFee for ExecuteBuybackPlanBuilder<'_, C> {
+    fn set_execution_fee(&mut self, _lamports: u64) -> &mut Self {
+        self
+    }
+}
+
 async fn find_config_addresses<C: Deref<Target = impl Signer> + Clone>(
     client: &crate::Client<C>,
     store: &Pubkey,
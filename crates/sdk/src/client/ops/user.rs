@@ -3,6 +3,7 @@ use std::{future::Future, ops::Deref};
 use gmsol_programs::gmsol_store::{
     accounts::{ReferralCodeV2, UserHeader},
     client::{accounts, args},
+    types::DelegationScope,
 };
 use gmsol_solana_utils::transaction_builder::TransactionBuilder;
 use gmsol_utils::pubkey::optional_address;
@@ -52,6 +53,29 @@ pub trait UserOps<C> {
         code: ReferralCodeBytes,
         hint_owner: Option<Pubkey>,
     ) -> impl Future<Output = crate::Result<TransactionBuilder<C>>>;
+
+    /// Authorize the delegate to trade on behalf of the payer.
+    fn create_user_delegation(
+        &self,
+        store: &Pubkey,
+        delegate: &Pubkey,
+        scope: DelegationScope,
+    ) -> crate::Result<TransactionBuilder<C, Pubkey>>;
+
+    /// Update the scope of a delegation.
+    fn update_user_delegation(
+        &self,
+        store: &Pubkey,
+        delegate: &Pubkey,
+        scope: DelegationScope,
+    ) -> crate::Result<TransactionBuilder<C>>;
+
+    /// Revoke a delegation.
+    fn revoke_user_delegation(
+        &self,
+        store: &Pubkey,
+        delegate: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>>;
 }
 
 impl<C: Deref<Target = impl Signer> + Clone> UserOps<C> for crate::Client<C> {
@@ -234,4 +258,65 @@ impl<C: Deref<Target = impl Signer> + Clone> UserOps<C> for crate::Client<C> {
             .anchor_args(args::AcceptReferralCode {});
         Ok(rpc)
     }
+
+    fn create_user_delegation(
+        &self,
+        store: &Pubkey,
+        delegate: &Pubkey,
+        scope: DelegationScope,
+    ) -> crate::Result<TransactionBuilder<C, Pubkey>> {
+        let owner = self.payer();
+        let user = self.find_user_address(store, &owner);
+        let delegation = self.find_user_delegation_address(store, &owner, delegate);
+        let rpc = self
+            .store_transaction()
+            .anchor_accounts(accounts::CreateUserDelegation {
+                owner,
+                store: *store,
+                user,
+                delegate: *delegate,
+                delegation,
+                system_program: system_program::ID,
+            })
+            .anchor_args(args::CreateUserDelegation { scope })
+            .output(delegation);
+        Ok(rpc)
+    }
+
+    fn update_user_delegation(
+        &self,
+        store: &Pubkey,
+        delegate: &Pubkey,
+        scope: DelegationScope,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let owner = self.payer();
+        let delegation = self.find_user_delegation_address(store, &owner, delegate);
+        let rpc = self
+            .store_transaction()
+            .anchor_accounts(accounts::UpdateUserDelegation {
+                owner,
+                store: *store,
+                delegation,
+            })
+            .anchor_args(args::UpdateUserDelegation { scope });
+        Ok(rpc)
+    }
+
+    fn revoke_user_delegation(
+        &self,
+        store: &Pubkey,
+        delegate: &Pubkey,
+    ) -> crate::Result<TransactionBuilder<C>> {
+        let owner = self.payer();
+        let delegation = self.find_user_delegation_address(store, &owner, delegate);
+        let rpc = self
+            .store_transaction()
+            .anchor_accounts(accounts::RevokeUserDelegation {
+                owner,
+                store: *store,
+                delegation,
+            })
+            .anchor_args(args::RevokeUserDelegation {});
+        Ok(rpc)
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use crate::core::market::MarketConfigKey;
+use crate::core::{market::MarketConfigKey, risk_oracle};
+use gmsol_programs::gmsol_store::types::{RiskOracleRecommendation, RiskOracleValue};
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
@@ -33,29 +34,66 @@ impl EncodedRecommendation {
     pub fn market_pubkey(&self) -> crate::Result<Pubkey> {
         Pubkey::try_from(self.market_address.as_str()).map_err(crate::Error::custom)
     }
+
+    /// Returns the `(value, decimals)` pairs of the keys of the parameter,
+    /// in the order they are signed.
+    pub fn values(&self) -> crate::Result<Vec<(u64, u8)>> {
+        let (_, parameter) = risk_oracle::find_parameter(&self.parameter_name)
+            .ok_or_else(|| crate::Error::custom("unsupported parameter_name"))?;
+        parameter
+            .keys
+            .iter()
+            .map(|(key, _)| {
+                let v = *self
+                    .new_values
+                    .get(*key)
+                    .ok_or_else(|| crate::Error::custom(format!("missing value for {key}")))?;
+                let d = *self
+                    .decimals
+                    .get(*key)
+                    .ok_or_else(|| crate::Error::custom(format!("missing decimals for {key}")))?;
+                Ok((v, d))
+            })
+            .collect()
+    }
+
+    pub fn reference_id_bytes(&self) -> crate::Result<[u8; 16]> {
+        let v = uuid::Uuid::parse_str(&self.reference_id).map_err(crate::Error::custom)?;
+        Ok(*v.as_bytes())
+    }
+
+    pub fn signature_bytes(&self) -> crate::Result<[u8; 64]> {
+        let sig_str = self.signature.trim();
+        let sig_hex = sig_str
+            .strip_prefix("0x")
+            .or_else(|| sig_str.strip_prefix("0X"))
+            .unwrap_or(sig_str);
+        let sig_vec = hex::decode(sig_hex).map_err(crate::Error::custom)?;
+        sig_vec
+            .try_into()
+            .map_err(|_| crate::Error::custom("invalid signature length; expected 64 bytes"))
+    }
+
+    /// Convert to the recommendation to be applied on-chain.
+    pub fn to_recommendation(&self) -> crate::Result<RiskOracleRecommendation> {
+        Ok(RiskOracleRecommendation {
+            parameter_name: self.parameter_name.clone(),
+            values: self
+                .values()?
+                .into_iter()
+                .map(|(value, decimals)| RiskOracleValue { value, decimals })
+                .collect(),
+            timestamp: self.timestamp,
+            protocol: self.protocol.clone(),
+            reference_id: self.reference_id_bytes()?,
+            signature: self.signature_bytes()?,
+            recovery_id: self.recovery_id,
+        })
+    }
 }
 
 pub fn map_key(chaos_key: &str, parameter_name: &str) -> Option<MarketConfigKey> {
-    match parameter_name {
-        "oiCaps" => match chaos_key {
-            "oiCaps/maxOpenInterestForLongs/v1" => Some(MarketConfigKey::MaxOpenInterestForLong),
-            "oiCaps/maxOpenInterestForShorts/v1" => Some(MarketConfigKey::MaxOpenInterestForShort),
-            _ => None,
-        },
-        "priceImpact" => match chaos_key {
-            "priceImpact/negativePositionImpactFactor/v1" => {
-                Some(MarketConfigKey::PositionImpactNegativeFactor)
-            }
-            "priceImpact/positivePositionImpactFactor/v1" => {
-                Some(MarketConfigKey::PositionImpactPositiveFactor)
-            }
-            "priceImpact/positionImpactExponentFactor/v1" => {
-                Some(MarketConfigKey::PositionImpactExponent)
-            }
-            _ => None,
-        },
-        _ => None,
-    }
+    risk_oracle::map_key(parameter_name, chaos_key)
 }
 
 pub fn to_per_market_updates(
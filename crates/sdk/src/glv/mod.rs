@@ -7,6 +7,10 @@ pub mod status;
 /// GLV calculator trait.
 pub mod calculator;
 
+/// GLV rebalancing planner.
+#[cfg(simulation)]
+pub mod rebalance;
+
 pub use calculator::GlvCalculator;
 pub use model::GlvModel;
 pub use status::GlvStatus;
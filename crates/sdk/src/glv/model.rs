@@ -111,4 +111,44 @@ impl GlvModel {
 
         Ok(())
     }
+
+    /// Shift market tokens between the markets in GLV.
+    pub fn shift(
+        &mut self,
+        from_market_token: &Pubkey,
+        to_market_token: &Pubkey,
+        amount: u64,
+        received: u64,
+    ) -> crate::Result<()> {
+        let next_from_balance = self
+            .glv
+            .market_config(from_market_token)
+            .ok_or_else(|| {
+                crate::Error::custom(format!("[GLV] `{from_market_token}` not found in GLV"))
+            })?
+            .balance
+            .checked_sub(amount)
+            .ok_or(crate::Error::custom("[GLV] market token balance underflow"))?;
+        let next_to_balance = self
+            .glv
+            .market_config(to_market_token)
+            .ok_or_else(|| {
+                crate::Error::custom(format!("[GLV] `{to_market_token}` not found in GLV"))
+            })?
+            .balance
+            .checked_add(received)
+            .ok_or(crate::Error::custom("[GLV] market token balance overflow"))?;
+
+        let glv = Arc::make_mut(&mut self.glv);
+        glv.markets
+            .get_mut(from_market_token)
+            .expect("must exist")
+            .balance = next_from_balance;
+        glv.markets
+            .get_mut(to_market_token)
+            .expect("must exist")
+            .balance = next_to_balance;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,519 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use gmsol_model::utils::{apply_factor, div_to_factor};
+use gmsol_programs::gmsol_store::types::CreateShiftParams;
+use gmsol_utils::glv::GlvMarketFlag;
+use solana_sdk::pubkey::Pubkey;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    constants,
+    glv::{GlvCalculator, GlvModel},
+    serde::StringPubkey,
+    simulation::{SimulationOptions, Simulator},
+};
+
+/// Max number of attempts to fit a shift into the constraints by halving its value.
+const MAX_ATTEMPTS_PER_SHIFT: usize = 8;
+
+/// Rebalancing target of a GLV market.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(serde, serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebalanceTarget {
+    /// The market is expected to hold the given share (as a factor) of the GLV value.
+    Weight(u128),
+    /// The market may hold at most the given share (as a factor) of the GLV value.
+    ///
+    /// The excess is shifted to the markets with [`Weight`](Self::Weight) targets first,
+    /// then to the markets that are below their max exposures.
+    MaxExposure(u128),
+}
+
+/// Options for [`GlvRebalancePlanner`].
+#[derive(Debug, Clone)]
+pub struct RebalanceOptions {
+    /// Slippage factor applied to the simulated output of each shift to derive
+    /// its min output amount.
+    pub slippage: u128,
+    /// Max number of shifts to plan.
+    pub max_shifts: usize,
+    /// Differences below this value are left as is.
+    ///
+    /// The min shift value of the GLV is always respected.
+    pub min_shift_value: u128,
+    /// Simulation options.
+    pub simulation: SimulationOptions,
+}
+
+impl Default for RebalanceOptions {
+    fn default() -> Self {
+        Self {
+            slippage: 0,
+            max_shifts: 10,
+            min_shift_value: 0,
+            simulation: Default::default(),
+        }
+    }
+}
+
+/// A planned GLV shift.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct RebalanceStep {
+    /// From market token.
+    pub from_market_token: StringPubkey,
+    /// To market token.
+    pub to_market_token: StringPubkey,
+    /// Amount of the from market token to shift.
+    pub from_market_token_amount: u64,
+    /// Simulated amount of the to market token to receive.
+    pub to_market_token_amount: u64,
+    /// Min amount of the to market token to receive, with the slippage applied.
+    pub min_to_market_token_amount: u64,
+    /// Value of the shifted market tokens, as validated by the program.
+    pub from_value: u128,
+    /// Value of the received market tokens, as validated by the program.
+    pub to_value: u128,
+    /// Price impact factor of the shift, zero if the impact is positive.
+    pub price_impact_factor: u128,
+    /// The earliest time the shift can be executed, assuming the previous
+    /// shifts are executed as soon as possible.
+    pub executable_after: i64,
+}
+
+/// Allocation of a GLV market.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct MarketAllocation {
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Rebalancing target.
+    pub target: Option<RebalanceTarget>,
+    /// Target value.
+    pub target_value: u128,
+    /// Value before rebalancing.
+    pub value_before: u128,
+    /// Estimated value after rebalancing.
+    pub value_after: u128,
+    /// Share of the GLV value before rebalancing.
+    pub weight_before: u128,
+    /// Estimated share of the GLV value after rebalancing.
+    pub weight_after: u128,
+}
+
+/// GLV rebalancing plan.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct RebalancePlan {
+    /// GLV token.
+    pub glv_token: StringPubkey,
+    /// GLV value before rebalancing.
+    pub total_value_before: u128,
+    /// Estimated GLV value after rebalancing.
+    pub total_value_after: u128,
+    /// Shifts to execute in order.
+    pub steps: Vec<RebalanceStep>,
+    /// Allocations of the GLV markets.
+    pub markets: Vec<MarketAllocation>,
+    /// The value that is still above the targets after the planned shifts.
+    pub unresolved_value: u128,
+}
+
+/// Plans the GLV shifts to reach the given targets.
+///
+/// Each step shifts from the market furthest above its target to the market
+/// furthest below its target, so that the number of shifts is kept small. The
+/// shifts are simulated in order and sized to respect the shift constraints of
+/// the GLV, including the min shift value, the max price impact and the max
+/// amount and value of the receiving market.
+#[derive(Debug, TypedBuilder)]
+pub struct GlvRebalancePlanner<'a> {
+    simulator: &'a Simulator,
+    glv_token: &'a Pubkey,
+    targets: &'a BTreeMap<Pubkey, RebalanceTarget>,
+    #[builder(default)]
+    options: RebalanceOptions,
+}
+
+struct Slot {
+    market_token: Pubkey,
+    target: Option<RebalanceTarget>,
+    target_value: u128,
+    max_value: Option<u128>,
+    is_deposit_allowed: bool,
+}
+
+impl Slot {
+    /// Returns `(surplus, capacity, spare capacity)` for the given value.
+    fn balances(&self, value: u128) -> (u128, u128, u128) {
+        let surplus = value.saturating_sub(self.target_value);
+        if !self.is_deposit_allowed {
+            return (surplus, 0, 0);
+        }
+        match self.target {
+            Some(RebalanceTarget::Weight(_)) => {
+                (surplus, self.target_value.saturating_sub(value), 0)
+            }
+            Some(RebalanceTarget::MaxExposure(_)) => (
+                surplus,
+                0,
+                self.max_value
+                    .expect("must exist for max exposure")
+                    .saturating_sub(value),
+            ),
+            None => (surplus, 0, 0),
+        }
+    }
+}
+
+impl GlvRebalancePlanner<'_> {
+    /// Plan the rebalancing.
+    pub fn plan(self) -> crate::Result<RebalancePlan> {
+        let Self {
+            simulator,
+            glv_token,
+            targets,
+            options,
+        } = self;
+
+        let glv = simulator.get_glv(glv_token).ok_or_else(|| {
+            crate::Error::custom(format!(
+                "[rebalance] GLV for GLV token `{glv_token}` not found"
+            ))
+        })?;
+
+        let mut total_weight = 0u128;
+        for (market_token, target) in targets {
+            if glv.market_config(market_token).is_none() {
+                return Err(crate::Error::custom(format!(
+                    "[rebalance] the GLV does not include the market token `{market_token}`"
+                )));
+            }
+            let factor = match target {
+                RebalanceTarget::Weight(factor) => {
+                    total_weight = total_weight.saturating_add(*factor);
+                    factor
+                }
+                RebalanceTarget::MaxExposure(factor) => factor,
+            };
+            if *factor > constants::MARKET_USD_UNIT {
+                return Err(crate::Error::custom(format!(
+                    "[rebalance] the target of `{market_token}` exceeds 100%"
+                )));
+            }
+        }
+        if total_weight > constants::MARKET_USD_UNIT {
+            return Err(crate::Error::custom(
+                "[rebalance] the sum of the target weights exceeds 100%",
+            ));
+        }
+
+        let market_tokens = glv.market_tokens().collect::<Vec<_>>();
+        let values_before = values_of(simulator, glv_token, &market_tokens)?;
+        let total_value_before = sum(&values_before)?;
+
+        let slots = market_tokens
+            .iter()
+            .zip(values_before.iter())
+            .map(|(market_token, value)| {
+                let target = targets.get(market_token).copied();
+                let share = |factor: u128| {
+                    apply_factor::<_, { constants::MARKET_DECIMALS }>(&total_value_before, &factor)
+                        .ok_or_else(|| crate::Error::custom("[rebalance] target value overflow"))
+                };
+                let (target_value, max_value) = match target {
+                    Some(RebalanceTarget::Weight(factor)) => (share(factor)?, None),
+                    Some(RebalanceTarget::MaxExposure(factor)) => {
+                        let max_value = share(factor)?;
+                        ((*value).min(max_value), Some(max_value))
+                    }
+                    None => (*value, None),
+                };
+                let is_deposit_allowed = glv
+                    .market_config(market_token)
+                    .expect("must exist")
+                    .flags
+                    .get_flag(GlvMarketFlag::IsDepositAllowed);
+                Ok(Slot {
+                    market_token: *market_token,
+                    target,
+                    target_value,
+                    max_value,
+                    is_deposit_allowed,
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let min_shift_value = options.min_shift_value.max(glv.shift_min_value);
+        let interval = i64::from(glv.shift_min_interval_secs);
+        let mut executable_after = glv.shift_last_executed_at;
+
+        let mut simulator = simulator.clone();
+        let mut values = values_before.clone();
+        let mut blocked = BTreeSet::<(usize, usize)>::default();
+        let mut steps = Vec::default();
+
+        while steps.len() < options.max_shifts {
+            let Some((from, to, value)) = best_pair(&slots, &values, &blocked) else {
+                break;
+            };
+            if value < min_shift_value {
+                break;
+            }
+            let from_market_token = &slots[from].market_token;
+            let to_market_token = &slots[to].market_token;
+            let Some((next, mut step)) = try_shift(
+                &simulator,
+                glv_token,
+                from_market_token,
+                to_market_token,
+                value,
+                min_shift_value,
+                &options,
+            )?
+            else {
+                blocked.insert((from, to));
+                continue;
+            };
+            executable_after = executable_after.saturating_add(interval);
+            step.executable_after = executable_after;
+            simulator = next;
+            values = values_of(&simulator, glv_token, &market_tokens)?;
+            steps.push(step);
+        }
+
+        let total_value_after = sum(&values)?;
+        let mut unresolved_value = 0u128;
+        let markets = slots
+            .iter()
+            .zip(values_before.iter().zip(values.iter()))
+            .map(|(slot, (value_before, value_after))| {
+                unresolved_value = unresolved_value.saturating_add(slot.balances(*value_after).0);
+                Ok(MarketAllocation {
+                    market_token: slot.market_token.into(),
+                    target: slot.target,
+                    target_value: slot.target_value,
+                    value_before: *value_before,
+                    value_after: *value_after,
+                    weight_before: weight(*value_before, total_value_before)?,
+                    weight_after: weight(*value_after, total_value_after)?,
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(RebalancePlan {
+            glv_token: (*glv_token).into(),
+            total_value_before,
+            total_value_after,
+            steps,
+            markets,
+            unresolved_value,
+        })
+    }
+}
+
+fn values_of(
+    simulator: &Simulator,
+    glv_token: &Pubkey,
+    market_tokens: &[Pubkey],
+) -> crate::Result<Vec<u128>> {
+    market_tokens
+        .iter()
+        .map(|market_token| simulator.get_market_token_value_in_glv(glv_token, market_token, false))
+        .collect()
+}
+
+fn sum(values: &[u128]) -> crate::Result<u128> {
+    values.iter().try_fold(0u128, |total, value| {
+        total
+            .checked_add(*value)
+            .ok_or_else(|| crate::Error::custom("[rebalance] GLV value overflow"))
+    })
+}
+
+fn weight(value: u128, total: u128) -> crate::Result<u128> {
+    if total == 0 {
+        return Ok(0);
+    }
+    div_to_factor::<_, { constants::MARKET_DECIMALS }>(&value, &total, false)
+        .ok_or_else(|| crate::Error::custom("[rebalance] failed to calculate weight"))
+}
+
+/// Find the pair of markets with the largest transferable value.
+///
+/// Capacities below the weight targets are filled before the spare
+/// capacities below the max exposures.
+fn best_pair(
+    slots: &[Slot],
+    values: &[u128],
+    blocked: &BTreeSet<(usize, usize)>,
+) -> Option<(usize, usize, u128)> {
+    let balances = slots
+        .iter()
+        .zip(values)
+        .map(|(slot, value)| slot.balances(*value))
+        .collect::<Vec<_>>();
+
+    let find = |use_spare: bool| {
+        let mut best: Option<(usize, usize, u128)> = None;
+        for (from, (surplus, _, _)) in balances.iter().enumerate() {
+            if *surplus == 0 {
+                continue;
+            }
+            for (to, (_, capacity, spare)) in balances.iter().enumerate() {
+                let capacity = if use_spare { spare } else { capacity };
+                if from == to || *capacity == 0 || blocked.contains(&(from, to)) {
+                    continue;
+                }
+                let value = (*surplus).min(*capacity);
+                if best.map(|(_, _, best)| best < value).unwrap_or(true) {
+                    best = Some((from, to, value));
+                }
+            }
+        }
+        best
+    };
+
+    find(false).or_else(|| find(true))
+}
+
+/// Simulate a shift of the given value, halving it until the shift constraints are met.
+///
+/// Returns the simulator after the shift along with the step.
+fn try_shift(
+    simulator: &Simulator,
+    glv_token: &Pubkey,
+    from_market_token: &Pubkey,
+    to_market_token: &Pubkey,
+    mut value: u128,
+    min_shift_value: u128,
+    options: &RebalanceOptions,
+) -> crate::Result<Option<(Simulator, RebalanceStep)>> {
+    let glv = simulator.get_glv(glv_token).expect("must exist");
+    let balance = glv
+        .market_config(from_market_token)
+        .expect("must exist")
+        .balance;
+
+    for _ in 0..MAX_ATTEMPTS_PER_SHIFT {
+        if value < min_shift_value {
+            break;
+        }
+
+        let amount = {
+            let (market, prices) = simulator.get_market_with_prices(from_market_token)?;
+            let amount = gmsol_model::glv::get_market_token_amount_for_glv_value(
+                &prices,
+                market,
+                value,
+                false,
+                constants::MARKET_USD_TO_AMOUNT_DIVISOR,
+            )?;
+            u64::try_from(amount).unwrap_or(u64::MAX).min(balance)
+        };
+        if amount == 0 {
+            break;
+        }
+
+        let mut next = simulator.clone();
+        let params = CreateShiftParams {
+            execution_lamports: 0,
+            from_market_token_amount: amount,
+            min_to_market_token_amount: 0,
+        };
+        let Ok(output) = next
+            .simulate_shift(from_market_token, to_market_token, &params)
+            .build()
+            .execute_with_options(options.simulation.clone())
+        else {
+            value /= 2;
+            continue;
+        };
+        let received = u64::try_from(*output.deposit_report().minted())
+            .map_err(|_| crate::Error::custom("[rebalance] received amount overflow"))?;
+
+        let glv = next.get_glv_mut(glv_token).expect("must exist");
+        glv.shift(from_market_token, to_market_token, amount, received)?;
+        let glv = next.get_glv(glv_token).expect("must exist");
+
+        // Validate the shift as the program does, with the market states after the shift.
+        let from_value = value_for_market(&next, from_market_token, amount, true)?;
+        let to_value = value_for_market(&next, to_market_token, received, true)?;
+        if from_value < min_shift_value {
+            break;
+        }
+        let price_impact_factor = if from_value < to_value {
+            0
+        } else {
+            div_to_factor::<_, { constants::MARKET_DECIMALS }>(
+                &from_value.abs_diff(to_value),
+                &from_value,
+                false,
+            )
+            .ok_or_else(|| crate::Error::custom("[rebalance] failed to calculate price impact"))?
+        };
+        if price_impact_factor > glv.shift_max_price_impact_factor
+            || !is_within_market_limits(&next, glv, to_market_token)?
+        {
+            value /= 2;
+            continue;
+        }
+
+        let min_to_market_token_amount = apply_factor::<_, { constants::MARKET_DECIMALS }>(
+            &u128::from(received),
+            &constants::MARKET_USD_UNIT.saturating_sub(options.slippage),
+        )
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or_else(|| crate::Error::custom("[rebalance] failed to apply slippage"))?;
+
+        return Ok(Some((
+            next,
+            RebalanceStep {
+                from_market_token: (*from_market_token).into(),
+                to_market_token: (*to_market_token).into(),
+                from_market_token_amount: amount,
+                to_market_token_amount: received,
+                min_to_market_token_amount,
+                from_value,
+                to_value,
+                price_impact_factor,
+                executable_after: 0,
+            },
+        )));
+    }
+
+    Ok(None)
+}
+
+fn value_for_market(
+    simulator: &Simulator,
+    market_token: &Pubkey,
+    amount: u64,
+    maximize: bool,
+) -> crate::Result<u128> {
+    let (market, prices) = simulator.get_market_with_prices(market_token)?;
+    Ok(
+        gmsol_model::glv::get_glv_value_for_market(&prices, market, amount.into(), maximize)?
+            .market_token_value_in_glv,
+    )
+}
+
+/// Returns whether the balance of the given market is within the max amount and max value.
+fn is_within_market_limits(
+    simulator: &Simulator,
+    glv: &GlvModel,
+    market_token: &Pubkey,
+) -> crate::Result<bool> {
+    let config = glv.market_config(market_token).expect("must exist");
+    if config.max_amount > 0 && config.balance > config.max_amount {
+        return Ok(false);
+    }
+    if config.max_value > 0 {
+        let value = value_for_market(simulator, market_token, config.balance, true)?;
+        if value > config.max_value {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
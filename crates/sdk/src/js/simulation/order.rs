@@ -8,7 +8,7 @@ use crate::{
     builders::order::{CreateOrderKind, CreateOrderParams},
     js::simulation::{encode_borsh_base64, encode_bytemuck_base64},
     serde::StringPubkey,
-    simulation::order::OrderSimulationOutput,
+    simulation::order::{OrderSimulationOutput, TwapImpactEstimate},
 };
 
 use crate::js::position::JsPositionModel;
@@ -70,6 +70,27 @@ pub struct SwapOrderSimulationOutput {
     report: Vec<String>,
 }
 
+/// Estimated price impact of a TWAP execution versus a single fill.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct TwapImpactEstimateOutput {
+    slices: Vec<i128>,
+    total: i128,
+    single_fill: i128,
+    improvement: i128,
+}
+
+impl From<TwapImpactEstimate> for TwapImpactEstimateOutput {
+    fn from(estimate: TwapImpactEstimate) -> Self {
+        Self {
+            improvement: estimate.improvement(),
+            slices: estimate.slices,
+            total: estimate.total,
+            single_fill: estimate.single_fill,
+        }
+    }
+}
+
 #[wasm_bindgen(js_class = OrderSimulationOutput)]
 impl JsOrderSimulationOutput {
     /// Returns increase order simulation output.
@@ -15,8 +15,12 @@ use crate::{
     glv::{GlvCalculator, GlvStatus},
     js::glv::JsGlvModel,
     market::Value,
+    position::{status::PositionStatus, CalculatePositionStatusOptions},
     serde::StringPubkey,
-    simulation::{order::UpdatePriceOptions, SimulationOptions, Simulator},
+    simulation::{
+        order::{TwapSimulationOptions, UpdatePriceOptions},
+        SimulationOptions, Simulator,
+    },
 };
 
 use crate::js::{
@@ -27,7 +31,7 @@ use super::{
     deposit::{JsDepositSimulationOutput, SimulateDepositArgs},
     glv_deposit::{JsGlvDepositSimulationOutput, SimulateGlvDepositArgs},
     glv_withdrawal::{JsGlvWithdrawalSimulationOutput, SimulateGlvWithdrawalArgs},
-    order::{JsOrderSimulationOutput, SimulateOrderArgs},
+    order::{JsOrderSimulationOutput, SimulateOrderArgs, TwapImpactEstimateOutput},
     shift::{JsShiftSimulationOutput, SimulateShiftArgs},
     withdrawal::{JsWithdrawalSimulationOutput, SimulateWithdrawalArgs},
 };
@@ -73,6 +77,33 @@ pub struct GetGlvTokenValueArgs {
     maximize: bool,
 }
 
+/// Arguments for advancing the simulated clock.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct AdvanceClockArgs {
+    /// The market to advance the clock of. All markets are advanced if not provided.
+    #[serde(default)]
+    market_token: Option<StringPubkey>,
+    /// Duration to advance in seconds.
+    duration: u64,
+}
+
+/// Output of advancing the simulated clock.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct AdvanceClockOutput {
+    /// The advanced markets.
+    markets: Vec<AdvancedMarketClock>,
+}
+
+/// The clock of a market after being advanced.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct AdvancedMarketClock {
+    market_token: StringPubkey,
+    timestamp: i64,
+}
+
 #[wasm_bindgen(js_class = Simulator)]
 impl JsSimulator {
     /// Get market by its market token.
@@ -146,6 +177,38 @@ impl JsSimulator {
         self.disable_vis
     }
 
+    /// Advance the simulated clock, accruing borrowing and funding fees.
+    pub fn advance_clock(&mut self, args: AdvanceClockArgs) -> crate::Result<AdvanceClockOutput> {
+        let reports = self
+            .simulator
+            .advance_clock(args.market_token.as_deref(), args.duration)?;
+        Ok(AdvanceClockOutput {
+            markets: reports
+                .into_iter()
+                .map(|report| AdvancedMarketClock {
+                    market_token: report.market_token.into(),
+                    timestamp: report.timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    /// Calculate the status of the given position against the current state,
+    /// including the pending borrowing and funding fees and the liquidation price.
+    pub fn get_position_status(
+        &self,
+        position: &JsPosition,
+        include_virtual_inventory_impact: Option<bool>,
+    ) -> crate::Result<PositionStatus> {
+        self.simulator.position_status(
+            &position.position,
+            CalculatePositionStatusOptions {
+                include_virtual_inventory_impact: include_virtual_inventory_impact
+                    .unwrap_or_default(),
+            },
+        )
+    }
+
     /// Simulate an order execution.
     pub fn simulate_order(
         &mut self,
@@ -188,6 +251,44 @@ impl JsSimulator {
         Ok(JsOrderSimulationOutput { output })
     }
 
+    /// Estimate the price impact of executing an order in TWAP slices versus a single fill.
+    ///
+    /// The simulator is not modified.
+    pub fn estimate_twap_impact(
+        &mut self,
+        args: SimulateOrderArgs,
+        position: Option<JsPosition>,
+        assume_recovery: Option<bool>,
+    ) -> crate::Result<TwapImpactEstimateOutput> {
+        let SimulateOrderArgs {
+            kind,
+            params,
+            collateral_or_swap_out_token,
+            pay_token,
+            receive_token,
+            swap_path,
+            skip_limit_price_validation,
+            ..
+        } = args;
+        let swap_path = convert_swap_path(swap_path.as_deref());
+        let estimate = self
+            .simulator
+            .simulate_order(kind, &params, &collateral_or_swap_out_token)
+            .pay_token(pay_token.as_deref())
+            .receive_token(receive_token.as_deref())
+            .position(position.as_ref().map(|p| &p.position))
+            .swap_path(&swap_path)
+            .build()
+            .estimate_twap_impact(TwapSimulationOptions {
+                simulation: SimulationOptions {
+                    skip_limit_price_validation: skip_limit_price_validation.unwrap_or_default(),
+                    disable_vis: self.disable_vis,
+                },
+                assume_recovery: assume_recovery.unwrap_or_default(),
+            })?;
+        Ok(estimate.into())
+    }
+
     /// Simulate a deposit execution.
     pub fn simulate_deposit(
         &mut self,
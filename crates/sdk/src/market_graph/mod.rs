@@ -48,6 +48,10 @@ pub mod config;
 /// Error type.
 pub mod error;
 
+/// Swap route optimization.
+#[cfg(simulation)]
+pub mod route;
+
 /// Execution simulation.
 #[deprecated(since = "0.8.0", note = "use `Simulator` instead")]
 #[cfg(simulation)]
@@ -1191,6 +1195,306 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(backtest)]
+    fn backtest() -> crate::Result<()> {
+        use std::collections::HashMap;
+
+        use crate::simulation::backtest::{
+            Backtest, BacktestOrder, Frame, PositionKey, StrategyContext, TimeSeries,
+        };
+
+        let _tracing = setup_fmt_tracing("info");
+
+        let usdc: Pubkey = USDC.parse().unwrap();
+        let market_token: Pubkey = SOL_BALANCED_MARKET_TOKEN.parse().unwrap();
+
+        let (g, _) = create_and_update_market_graph()?;
+        let mut simulator = g.to_simulator(Default::default());
+        simulator.advance_clock(Some(&market_token), 0)?;
+
+        let market = simulator.get_market(&market_token).expect("must exist");
+        let start = market.current_timestamp();
+        let meta = market.meta;
+        let prices = simulator.get_prices(&meta).expect("must have prices");
+
+        // The index token price rises by 20% at the last frame.
+        let frame = |timestamp: i64, factor: u128| {
+            let scale = |price: &Price<u128>| Price {
+                min: price.min * factor / 100,
+                max: price.max * factor / 100,
+            };
+            Frame {
+                timestamp,
+                prices: HashMap::from([
+                    (
+                        meta.index_token_mint.into(),
+                        scale(&prices.index_token_price),
+                    ),
+                    (meta.long_token_mint.into(), scale(&prices.long_token_price)),
+                    (meta.short_token_mint.into(), prices.short_token_price),
+                ]),
+                markets: vec![],
+            }
+        };
+        let day = 24 * 3600;
+        let series = TimeSeries::new(vec![
+            frame(start + 60, 100),
+            frame(start + day, 100),
+            frame(start + 2 * day, 120),
+        ])?;
+
+        let amount = 5_000_000;
+        let order = |kind: CreateOrderKind, is_long: bool, size: u128| {
+            BacktestOrder::builder()
+                .kind(kind)
+                .params(
+                    CreateOrderParams::builder()
+                        .amount(amount)
+                        .is_long(is_long)
+                        .size(size)
+                        .market_token(market_token)
+                        .build(),
+                )
+                .collateral_or_swap_out_token(usdc)
+                .build()
+        };
+        let long = PositionKey {
+            market_token,
+            collateral_token: usdc,
+            is_long: true,
+        };
+        let mut strategy = |ctx: &StrategyContext<'_>| -> crate::Result<Vec<BacktestOrder>> {
+            let orders = match ctx.timestamp() - start {
+                // Open a 2x long and a 10x short, and try to close a position that does not exist.
+                60 => vec![
+                    order(
+                        CreateOrderKind::MarketIncrease,
+                        true,
+                        10 * constants::MARKET_USD_UNIT,
+                    ),
+                    order(
+                        CreateOrderKind::MarketIncrease,
+                        false,
+                        50 * constants::MARKET_USD_UNIT,
+                    ),
+                    BacktestOrder::builder()
+                        .kind(CreateOrderKind::MarketDecrease)
+                        .params(
+                            CreateOrderParams::builder()
+                                .amount(0)
+                                .is_long(true)
+                                .size(constants::MARKET_USD_UNIT)
+                                .market_token(market_token)
+                                .build(),
+                        )
+                        .collateral_or_swap_out_token(meta.long_token_mint)
+                        .build(),
+                ],
+                // Close the long position.
+                elapsed if elapsed == day => {
+                    let position = ctx.position(&long).expect("the long position must be open");
+                    let mut order = order(
+                        CreateOrderKind::MarketDecrease,
+                        true,
+                        position.state.size_in_usd,
+                    );
+                    order.params.amount = 0;
+                    vec![order]
+                }
+                _ => vec![],
+            };
+            Ok(orders)
+        };
+
+        let report = Backtest::builder()
+            .simulator(simulator)
+            .series(&series)
+            .build()
+            .run(&mut strategy)?;
+
+        assert_eq!(report.start, series.start());
+        assert_eq!(report.end, series.end());
+        assert_eq!(report.rejected_orders.len(), 1);
+        assert!(matches!(
+            report.rejected_orders[0].kind,
+            CreateOrderKind::MarketDecrease
+        ));
+        assert_eq!(report.positions.len(), 2);
+
+        let long = &report.positions[0];
+        assert!(long.is_long);
+        assert_eq!(long.opened_at, start + 60);
+        assert_eq!(long.closed_at, Some(start + day));
+        assert!(!long.is_liquidated());
+        assert_eq!(long.max_size_in_usd, 10 * constants::MARKET_USD_UNIT);
+        assert!(long.fees.order > 0);
+        assert_eq!(long.unrealized_pnl, 0);
+
+        let short = &report.positions[1];
+        assert!(!short.is_long);
+        assert_eq!(short.closed_at, Some(start + 2 * day));
+        let liquidation = short.liquidation.as_ref().expect("must be liquidated");
+        assert_eq!(liquidation.timestamp, start + 2 * day);
+        assert_eq!(liquidation.size_in_usd, 50 * constants::MARKET_USD_UNIT);
+        assert!(liquidation.pnl < 0);
+        assert!(short.fees.borrowing > 0);
+        assert!(short.fees.funding > 0);
+
+        assert_eq!(report.liquidations().count(), 1);
+        assert_eq!(
+            report.total_fees().total(),
+            long.fees.total() + short.fees.total()
+        );
+        assert_eq!(report.total_net_pnl(), long.net_pnl() + short.net_pnl());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(simulation)]
+    fn advance_clock_accrues_fees() -> crate::Result<()> {
+        use crate::simulation::order::OrderSimulationOutput;
+
+        let _tracing = setup_fmt_tracing("info");
+
+        let usdc: Pubkey = USDC.parse().unwrap();
+        let market_token: Pubkey = SOL_BALANCED_MARKET_TOKEN.parse().unwrap();
+
+        let (g, _) = create_and_update_market_graph()?;
+        let mut simulator = g.to_simulator(Default::default());
+
+        // Bring the borrowing and funding states of the snapshot up to date.
+        simulator.advance_clock(Some(&market_token), 0)?;
+
+        // Shorts pay both borrowing and funding fees in this market.
+        let amount = 5_000_000;
+        let size = 50 * constants::MARKET_USD_UNIT;
+        let params = CreateOrderParams::builder()
+            .amount(amount)
+            .is_long(false)
+            .size(size)
+            .market_token(market_token)
+            .build();
+        let OrderSimulationOutput::Increase { position, .. } = simulator
+            .simulate_order(CreateOrderKind::MarketIncrease, &params, &usdc)
+            .build()
+            .execute_with_options(Default::default())?
+        else {
+            unreachable!()
+        };
+        let position = position.position_arc().clone();
+        let before = simulator.position_status(&position, Default::default())?;
+
+        let reports = simulator.advance_clock(Some(&market_token), 24 * 3600)?;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].market_token, market_token);
+        let after = simulator.position_status(&position, Default::default())?;
+
+        assert!(after.pending_borrowing_fee_value > before.pending_borrowing_fee_value);
+        assert!(after.pending_funding_fee_value > before.pending_funding_fee_value);
+        assert!(after.net_value < before.net_value);
+
+        // The accrued fees move the liquidation price of the short position down.
+        let liquidation_price = after
+            .liquidation_price
+            .expect("must have a liquidation price");
+        assert!(
+            liquidation_price
+                < before
+                    .liquidation_price
+                    .expect("must have a liquidation price")
+        );
+        assert!(after.margin_health < before.margin_health);
+        assert!(
+            after.margin_health.expect("must have margin health") >= constants::MARKET_USD_UNIT
+        );
+
+        // The position becomes liquidatable once the price rises above the liquidation price.
+        let index_token = simulator
+            .get_market(&market_token)
+            .expect("must exist")
+            .meta
+            .index_token_mint;
+        let price = liquidation_price * 101 / 100;
+        simulator.insert_price(
+            &index_token,
+            Arc::new(Price {
+                min: price,
+                max: price,
+            }),
+        )?;
+        let status = simulator.position_status(&position, Default::default())?;
+        assert!(
+            status.margin_health.expect("must have margin health") < constants::MARKET_USD_UNIT
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(simulation)]
+    fn position_scanner() -> crate::Result<()> {
+        use crate::{
+            position::scanner::{PositionScanner, ScanOptions},
+            simulation::order::OrderSimulationOutput,
+        };
+
+        let _tracing = setup_fmt_tracing("info");
+
+        let wsol: Pubkey = WSOL.parse().unwrap();
+        let market_token: Pubkey = SOL_BALANCED_MARKET_TOKEN.parse().unwrap();
+
+        let (g, _) = create_and_update_market_graph()?;
+        let mut simulator = g.to_simulator(Default::default());
+
+        let sol_price = 10821227000000;
+        let amount = 5 * constants::MARKET_USD_UNIT / sol_price;
+        let size = 100 * constants::MARKET_USD_UNIT;
+        let params = CreateOrderParams::builder()
+            .amount(amount)
+            .is_long(true)
+            .size(size)
+            .market_token(market_token)
+            .build();
+        let OrderSimulationOutput::Increase { position, .. } = simulator
+            .simulate_order(CreateOrderKind::MarketIncrease, &params, &wsol)
+            .build()
+            .execute_with_options(Default::default())?
+        else {
+            unreachable!()
+        };
+
+        let market = simulator.get_market(&market_token).unwrap().clone();
+        let prices = simulator.get_prices(&market.meta).unwrap();
+        let address = Pubkey::new_unique();
+        let mut scanner = PositionScanner::default();
+        scanner
+            .insert_market(market)
+            .insert_position(address, position.position_arc().clone())
+            .update_prices(&market_token, prices)?;
+
+        let report = scanner.scan(&ScanOptions::default())?;
+        assert!(report.liquidations.is_empty());
+        assert!(report.skipped.is_empty());
+
+        let mut dropped = prices;
+        for price in [
+            &mut dropped.index_token_price,
+            &mut dropped.long_token_price,
+        ] {
+            price.min = price.min * 9 / 10;
+            price.max = price.max * 9 / 10;
+        }
+        scanner.update_prices(&market_token, dropped)?;
+        let report = scanner.scan(&ScanOptions::default())?;
+        println!("{report:?}");
+        assert_eq!(report.liquidations.len(), 1);
+        assert_eq!(*report.liquidations[0].position, address);
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(simulation)]
     fn swap_order_simulation() -> crate::Result<()> {
@@ -1251,6 +1555,157 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(simulation)]
+    fn twap_swap_impact_estimate() -> crate::Result<()> {
+        use crate::{
+            builders::order::TwapParams,
+            simulation::order::{OrderSimulationOutput, TwapSimulationOptions},
+        };
+
+        let _tracing = setup_fmt_tracing("info");
+
+        let bome: Pubkey = BOME.parse().unwrap();
+        let wsol: Pubkey = WSOL.parse().unwrap();
+        let market_token: Pubkey = SOL_BALANCED_MARKET_TOKEN.parse().unwrap();
+
+        let (mut g, _) = create_and_update_market_graph()?;
+
+        g.update_value(constants::MARKET_USD_UNIT * 6);
+        g.update_max_steps(5);
+
+        let paths = g.best_swap_paths(&bome, false)?;
+        let (_, best_path) = paths.to(&wsol);
+
+        let bome_price = 101468850000;
+        let amount = 5 * constants::MARKET_USD_UNIT / bome_price;
+        let params = CreateOrderParams::builder()
+            .amount(amount)
+            .is_long(true)
+            .size(0)
+            .market_token(market_token)
+            .twap(TwapParams {
+                slices: 4,
+                interval: 60,
+            })
+            .build();
+
+        let mut simulator = g.to_simulator(Default::default());
+        let single_fill = simulator
+            .clone()
+            .simulate_order(CreateOrderKind::MarketSwap, &params, &wsol)
+            .pay_token(Some(&bome))
+            .swap_path(&best_path)
+            .build()
+            .execute_with_options(Default::default())?;
+        let OrderSimulationOutput::Swap(_) = &single_fill else {
+            unreachable!()
+        };
+
+        for assume_recovery in [false, true] {
+            let estimate = simulator
+                .simulate_order(CreateOrderKind::MarketSwap, &params, &wsol)
+                .pay_token(Some(&bome))
+                .swap_path(&best_path)
+                .build()
+                .estimate_twap_impact(TwapSimulationOptions {
+                    assume_recovery,
+                    ..Default::default()
+                })?;
+            println!("assume_recovery={assume_recovery}: {estimate:?}");
+            assert_eq!(estimate.slices.len(), 4);
+            assert_eq!(estimate.single_fill, single_fill.price_impact_value());
+        }
+
+        // The estimation must not modify the simulator.
+        let output = simulator
+            .simulate_order(CreateOrderKind::MarketSwap, &params, &wsol)
+            .pay_token(Some(&bome))
+            .swap_path(&best_path)
+            .build()
+            .execute_with_options(Default::default())?;
+        assert_eq!(
+            output.price_impact_value(),
+            single_fill.price_impact_value()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(simulation)]
+    fn split_swap_route() -> crate::Result<()> {
+        use crate::market_graph::route::SwapRouteOptions;
+
+        let _tracing = setup_fmt_tracing("info");
+
+        let bome: Pubkey = BOME.parse().unwrap();
+        let wsol: Pubkey = WSOL.parse().unwrap();
+
+        let (mut g, _) = create_and_update_market_graph()?;
+
+        g.update_value(constants::MARKET_USD_UNIT * 6);
+        g.update_max_steps(5);
+
+        let paths = g.best_swap_paths(&bome, false)?;
+        let (_, best_path) = paths.to(&wsol);
+
+        let bome_price = 101468850000;
+        let amount = 5 * constants::MARKET_USD_UNIT / bome_price;
+
+        let simulator = g.to_simulator(Default::default());
+        let single = simulator
+            .clone()
+            .swap_along_path(&best_path, &bome, amount, None)?
+            .amount();
+
+        let options = SwapRouteOptions {
+            max_paths: 2,
+            ..Default::default()
+        };
+        let route = g.optimize_swap_route(&simulator, &bome, &wsol, amount, options.clone())?;
+
+        assert_eq!(route.source, bome);
+        assert_eq!(route.target, wsol);
+        assert!(!route.splits.is_empty() && route.splits.len() <= 2);
+        assert_eq!(
+            route
+                .splits
+                .iter()
+                .map(|split| split.amount_in)
+                .sum::<u128>(),
+            amount
+        );
+        assert_eq!(
+            route
+                .splits
+                .iter()
+                .map(|split| split.amount_out)
+                .sum::<u128>(),
+            route.amount_out
+        );
+        for split in &route.splits {
+            assert!(!split.path.is_empty() && split.path.len() <= 5);
+        }
+        // Allow a small tolerance for the rounding of chunked simulations.
+        assert!(route.amount_out >= single - single / 1_000);
+
+        // A narrow beam must still find a route.
+        let narrow = g.optimize_swap_route(
+            &simulator,
+            &bome,
+            &wsol,
+            amount,
+            SwapRouteOptions {
+                beam_width: 1,
+                ..options
+            },
+        )?;
+        assert!(!narrow.splits.is_empty());
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(simulation)]
     fn deposit_simulation() -> crate::Result<()> {
@@ -1487,4 +1942,101 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(simulation)]
+    fn glv_rebalance_planning() -> crate::Result<()> {
+        use std::collections::BTreeMap;
+
+        use gmsol_utils::glv::GlvMarketFlag;
+
+        use crate::glv::rebalance::{GlvRebalancePlanner, RebalanceOptions, RebalanceTarget};
+
+        let _tracing = setup_fmt_tracing("info");
+
+        let sol_market_token: Pubkey = SOL_BALANCED_MARKET_TOKEN.parse().unwrap();
+        let bnb_market_token: Pubkey = BNB_BALANCED_MARKET_TOKEN.parse().unwrap();
+        let aave_market_token: Pubkey = AAVE_BALANCED_MARKET_TOKEN.parse().unwrap();
+
+        let (glv_token, glv) = get_glv();
+        let mut account = *glv;
+        account.shift_max_price_impact_factor = constants::MARKET_USD_UNIT / 100;
+        for market_token in [sol_market_token, bnb_market_token, aave_market_token] {
+            account
+                .markets
+                .get_mut(&market_token)
+                .unwrap()
+                .flags
+                .set_flag(GlvMarketFlag::IsDepositAllowed, true);
+        }
+        let glv = GlvModel::new(Arc::new(account), glv.supply());
+
+        let (g, _) = create_and_update_market_graph()?;
+        let mut simulator = g.to_simulator(Default::default());
+        simulator.insert_glv(glv);
+
+        let targets = BTreeMap::from([
+            (
+                sol_market_token,
+                RebalanceTarget::Weight(constants::MARKET_USD_UNIT * 6 / 10),
+            ),
+            (
+                bnb_market_token,
+                RebalanceTarget::Weight(constants::MARKET_USD_UNIT * 3 / 10),
+            ),
+            (
+                aave_market_token,
+                RebalanceTarget::MaxExposure(constants::MARKET_USD_UNIT / 10),
+            ),
+        ]);
+        let plan = GlvRebalancePlanner::builder()
+            .simulator(&simulator)
+            .glv_token(&glv_token)
+            .targets(&targets)
+            .options(RebalanceOptions {
+                slippage: constants::MARKET_USD_UNIT / 100,
+                ..Default::default()
+            })
+            .build()
+            .plan()?;
+
+        assert_eq!(*plan.glv_token, glv_token);
+        assert!(!plan.steps.is_empty());
+        assert!(plan.steps.len() <= RebalanceOptions::default().max_shifts);
+        for step in plan.steps.iter() {
+            assert_ne!(step.from_market_token, step.to_market_token);
+            assert!(step.min_to_market_token_amount < step.to_market_token_amount);
+        }
+        for market_token in targets.keys() {
+            let allocation = plan
+                .markets
+                .iter()
+                .find(|market| *market.market_token == *market_token)
+                .expect("must have an allocation for each target");
+            assert!(allocation.target.is_some());
+        }
+
+        let before = plan
+            .markets
+            .iter()
+            .map(|market| market.target_value.abs_diff(market.value_before));
+        let after = plan
+            .markets
+            .iter()
+            .map(|market| market.target_value.abs_diff(market.value_after));
+        assert!(after.sum::<u128>() < before.sum::<u128>());
+
+        // The planning must not change the given simulator.
+        assert_eq!(
+            simulator
+                .get_glv(&glv_token)
+                .unwrap()
+                .market_config(&sol_market_token)
+                .unwrap()
+                .balance,
+            1_000 * 1_000_000_000
+        );
+
+        Ok(())
+    }
 }
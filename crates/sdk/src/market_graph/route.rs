@@ -0,0 +1,269 @@
+use gmsol_utils::swap::SwapActionParams;
+use petgraph::{graph::NodeIndex, visit::EdgeRef};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::simulation::{SimulationOptions, Simulator};
+
+use super::MarketGraph;
+
+const DEFAULT_MAX_PATHS: usize = 3;
+const DEFAULT_MAX_CANDIDATES: usize = 8;
+const DEFAULT_CHUNKS: usize = 20;
+const DEFAULT_BEAM_WIDTH: usize = 32;
+
+/// Options for [`MarketGraph::optimize_swap_route`].
+#[derive(Debug, Clone)]
+pub struct SwapRouteOptions {
+    /// Max number of paths that the swap can be split across.
+    pub max_paths: usize,
+    /// Max number of candidate paths to evaluate.
+    ///
+    /// Candidates are ranked by their estimated exchange rates in the graph.
+    pub max_candidates: usize,
+    /// Max number of partial paths kept at each step of the candidate search.
+    ///
+    /// Only the partial paths with the best estimated exchange rates are extended,
+    /// so the search stays bounded in dense graphs.
+    pub beam_width: usize,
+    /// Number of chunks that the input amount is divided into for allocation.
+    pub chunks: usize,
+    /// Max length of each path.
+    ///
+    /// It is always capped by the max steps of the graph and the on-chain max swap path length.
+    pub max_path_length: Option<usize>,
+    /// Options for the swap simulations.
+    pub simulation: SimulationOptions,
+}
+
+impl Default for SwapRouteOptions {
+    fn default() -> Self {
+        Self {
+            max_paths: DEFAULT_MAX_PATHS,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            beam_width: DEFAULT_BEAM_WIDTH,
+            chunks: DEFAULT_CHUNKS,
+            max_path_length: None,
+            simulation: Default::default(),
+        }
+    }
+}
+
+/// A part of a [`SwapRoute`].
+#[derive(Debug, Clone)]
+pub struct SwapSplit {
+    /// Swap path.
+    pub path: Vec<Pubkey>,
+    /// Input amount allocated to the path.
+    pub amount_in: u128,
+    /// Output amount of the path.
+    pub amount_out: u128,
+}
+
+/// A swap route split across parallel paths.
+#[derive(Debug, Clone)]
+pub struct SwapRoute {
+    /// Source token.
+    pub source: Pubkey,
+    /// Target token.
+    pub target: Pubkey,
+    /// Total input amount.
+    pub amount_in: u128,
+    /// Total output amount.
+    pub amount_out: u128,
+    /// Splits, in the order in which they are expected to be executed.
+    pub splits: Vec<SwapSplit>,
+}
+
+impl MarketGraph {
+    /// Find the allocation of the given `amount` across parallel swap paths from `source`
+    /// to `target` that maximizes the total output.
+    ///
+    /// The input amount is divided into chunks, and each chunk is allocated to the path
+    /// with the largest marginal output, simulated with [`Simulator::swap_along_path`]
+    /// on top of the previously allocated chunks. Therefore, the price impact of paths
+    /// sharing the same markets is accounted for.
+    ///
+    /// The splits are simulated to be executed one after another, in the returned order.
+    pub fn optimize_swap_route(
+        &self,
+        simulator: &Simulator,
+        source: &Pubkey,
+        target: &Pubkey,
+        amount: u128,
+        options: SwapRouteOptions,
+    ) -> crate::Result<SwapRoute> {
+        if source == target {
+            return Err(crate::Error::custom(
+                "[route] the source and the target must be different",
+            ));
+        }
+        if amount == 0 {
+            return Err(crate::Error::custom("[route] the amount must be positive"));
+        }
+
+        let candidates = self.candidate_swap_paths(source, target, &options)?;
+        if candidates.is_empty() {
+            return Err(crate::Error::custom(format!(
+                "[route] no swap path found from {source} to {target}"
+            )));
+        }
+
+        let chunks = (options.chunks.max(1) as u128).min(amount);
+        let chunk = amount / chunks;
+        let remainder = amount % chunks;
+
+        let mut working = simulator.clone();
+        let mut allocations = vec![0u128; candidates.len()];
+        let mut used = 0;
+        for idx in 0..chunks {
+            let chunk = if idx == 0 { chunk + remainder } else { chunk };
+            let can_use_new_path = used < options.max_paths.max(1);
+            let mut best: Option<(usize, u128, Simulator)> = None;
+            for (path_idx, path) in candidates.iter().enumerate() {
+                if !can_use_new_path && allocations[path_idx] == 0 {
+                    continue;
+                }
+                let mut simulator = working.clone();
+                let Ok(output) = simulator.swap_along_path(
+                    path,
+                    source,
+                    chunk,
+                    Some(options.simulation.clone()),
+                ) else {
+                    continue;
+                };
+                if best
+                    .as_ref()
+                    .map(|(_, best_amount, _)| output.amount() > *best_amount)
+                    .unwrap_or(true)
+                {
+                    best = Some((path_idx, output.amount(), simulator));
+                }
+            }
+            let Some((path_idx, _, simulator)) = best else {
+                return Err(crate::Error::custom(format!(
+                    "[route] failed to allocate {chunk} of the {amount} input to any path"
+                )));
+            };
+            if allocations[path_idx] == 0 {
+                used += 1;
+            }
+            allocations[path_idx] += chunk;
+            working = simulator;
+        }
+
+        // Re-simulate with the allocated amounts, since each split will be executed
+        // as a single swap.
+        let mut simulator = simulator.clone();
+        let mut splits = Vec::with_capacity(used);
+        let mut amount_out = 0u128;
+        for (path, amount_in) in candidates.into_iter().zip(allocations) {
+            if amount_in == 0 {
+                continue;
+            }
+            let output = simulator.swap_along_path(
+                &path,
+                source,
+                amount_in,
+                Some(options.simulation.clone()),
+            )?;
+            amount_out = amount_out
+                .checked_add(output.amount())
+                .ok_or_else(|| crate::Error::custom("[route] output amount overflow"))?;
+            splits.push(SwapSplit {
+                path,
+                amount_in,
+                amount_out: output.amount(),
+            });
+        }
+
+        Ok(SwapRoute {
+            source: *source,
+            target: *target,
+            amount_in: amount,
+            amount_out,
+            splits,
+        })
+    }
+
+    /// Search the swap paths from `source` to `target` that do not visit a token twice,
+    /// ranked by their estimated exchange rates.
+    ///
+    /// This is a beam search: at each step, only the best [`beam_width`](SwapRouteOptions::beam_width)
+    /// partial paths are extended.
+    fn candidate_swap_paths(
+        &self,
+        source: &Pubkey,
+        target: &Pubkey,
+        options: &SwapRouteOptions,
+    ) -> crate::Result<Vec<Vec<Pubkey>>> {
+        let source_ix = self
+            .collateral_tokens
+            .get(source)
+            .ok_or_else(|| crate::Error::custom("the source is not a known collateral token"))?
+            .ix;
+        let target_ix = self
+            .collateral_tokens
+            .get(target)
+            .ok_or_else(|| crate::Error::custom("the target is not a known collateral token"))?
+            .ix;
+
+        let max_length = options
+            .max_path_length
+            .unwrap_or(usize::MAX)
+            .min(self.config.max_steps)
+            .min(SwapActionParams::MAX_TOTAL_LENGTH);
+        let max_candidates = options.max_candidates.max(1);
+        let beam_width = options.beam_width.max(1);
+
+        let mut paths = Vec::<(Decimal, Vec<Pubkey>)>::new();
+        let mut beam = vec![PartialPath {
+            cost: Decimal::ZERO,
+            nodes: vec![source_ix],
+            path: vec![],
+        }];
+        for _ in 0..max_length {
+            let mut next_beam = Vec::new();
+            for partial in beam.iter() {
+                let current = *partial.nodes.last().expect("must not be empty");
+                for edge in self.graph.edges(current) {
+                    let next = edge.target();
+                    if partial.nodes.contains(&next) {
+                        continue;
+                    }
+                    let Some(edge_cost) = edge.weight().cost() else {
+                        continue;
+                    };
+                    let cost = partial.cost + edge_cost;
+                    let mut path = partial.path.clone();
+                    path.push(edge.weight().market_token);
+                    if next == target_ix {
+                        paths.push((cost, path));
+                    } else {
+                        let mut nodes = partial.nodes.clone();
+                        nodes.push(next);
+                        next_beam.push(PartialPath { cost, nodes, path });
+                    }
+                }
+            }
+            paths.sort_by_key(|(cost, _)| *cost);
+            paths.truncate(max_candidates);
+
+            next_beam.sort_by_key(|partial| partial.cost);
+            next_beam.truncate(beam_width);
+            if next_beam.is_empty() {
+                break;
+            }
+            beam = next_beam;
+        }
+
+        Ok(paths.into_iter().map(|(_, path)| path).collect())
+    }
+}
+
+struct PartialPath {
+    cost: Decimal,
+    nodes: Vec<NodeIndex>,
+    path: Vec<Pubkey>,
+}
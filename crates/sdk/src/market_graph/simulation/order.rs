@@ -48,7 +48,8 @@ impl OrderSimulation<'_> {
             CreateOrderKind::MarketIncrease | CreateOrderKind::LimitIncrease => self.increase(),
             CreateOrderKind::MarketDecrease
             | CreateOrderKind::LimitDecrease
-            | CreateOrderKind::StopLossDecrease => self.decrease(),
+            | CreateOrderKind::StopLossDecrease
+            | CreateOrderKind::TrailingStopDecrease => self.decrease(),
             CreateOrderKind::MarketSwap | CreateOrderKind::LimitSwap => self.swap(options),
         }
     }
@@ -149,9 +150,11 @@ impl OrderSimulation<'_> {
 
         if matches!(
             kind,
-            CreateOrderKind::LimitDecrease | CreateOrderKind::StopLossDecrease
+            CreateOrderKind::LimitDecrease
+                | CreateOrderKind::StopLossDecrease
+                | CreateOrderKind::TrailingStopDecrease
         ) {
-            let Some(trigger_price) = params.trigger_price else {
+            let Some(trigger_price) = params.effective_trigger_price() else {
                 return Err(crate::Error::custom("[sim] trigger price is required"));
             };
             let price = Price {
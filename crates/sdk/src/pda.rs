@@ -17,7 +17,8 @@ use gmsol_programs::gmsol_timelock::accounts as timelock_accounts;
 use gmsol_programs::gmsol_liquidity_provider::accounts as liquidity_provider_accounts;
 
 pub use gmsol_programs::gmsol_store::constants::{
-    VIRTUAL_INVENTORY_FOR_POSITIONS_SEED, VIRTUAL_INVENTORY_FOR_SWAPS_SEED,
+    CALLBACK_PROGRAM_SEED, RISK_ORACLE_SEED, VIRTUAL_INVENTORY_FOR_POSITIONS_SEED,
+    VIRTUAL_INVENTORY_FOR_SWAPS_SEED,
 };
 
 /// Nonce bytes.
@@ -71,6 +72,9 @@ pub const TRADE_DATA_SEED: &[u8] = b"trade_event_data";
 /// Seed for [`User`](store_accounts::UserHeader).
 pub const USER_SEED: &[u8] = b"user";
 
+/// Seed for [`UserDelegation`](store_accounts::UserDelegation).
+pub const USER_DELEGATION_SEED: &[u8] = b"user_delegation";
+
 /// Seed for [`ReferralCodeV2`](store_accounts::ReferralCodeV2).
 pub const REFERRAL_CODE_SEED: &[u8] = b"referral_code";
 
@@ -111,6 +115,14 @@ pub const GT_BANK_SEED: &[u8] = b"gt_bank";
 #[cfg(treasury)]
 pub const TREASURY_RECEIVER_SEED: &[u8] = b"receiver";
 
+/// Seed for [`BuybackPlan`](treasury_accounts::BuybackPlan).
+#[cfg(treasury)]
+pub const BUYBACK_PLAN_SEED: &[u8] = b"buyback_plan";
+
+/// Seed for buyback owner.
+#[cfg(treasury)]
+pub const BUYBACK_OWNER_SEED: &[u8] = b"buyback_owner";
+
 /// Seed for [`TimelockConfig`](timelock_accounts::TimelockConfig).
 #[cfg(timelock)]
 pub const TIMELOCK_CONFIG_SEED: &[u8] = b"timelock_config";
@@ -334,6 +346,24 @@ pub fn find_user_address(
     )
 }
 
+/// Find PDA for user delegation account.
+pub fn find_user_delegation_address(
+    store: &Pubkey,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    store_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            USER_DELEGATION_SEED,
+            store.as_ref(),
+            owner.as_ref(),
+            delegate.as_ref(),
+        ],
+        store_program_id,
+    )
+}
+
 /// Find PDA for referral code account.
 pub fn find_referral_code_address(
     store: &Pubkey,
@@ -469,6 +499,11 @@ pub fn find_virtual_inventory_for_positions_address(
     )
 }
 
+/// Find PDA for [`RiskOracle`](store_accounts::RiskOracle) account.
+pub fn find_risk_oracle_address(store: &Pubkey, store_program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RISK_ORACLE_SEED, store.as_ref()], store_program_id)
+}
+
 /// Find PDA for global treasury config.
 #[cfg(treasury)]
 pub fn find_treasury_config_address(store: &Pubkey, treasury_program_id: &Pubkey) -> (Pubkey, u8) {
@@ -521,6 +556,37 @@ pub fn find_treasury_receiver_address(
     )
 }
 
+/// Find PDA for buyback plan.
+#[cfg(treasury)]
+pub fn find_buyback_plan_address(
+    config: &Pubkey,
+    swap_in_token: &Pubkey,
+    swap_out_token: &Pubkey,
+    treasury_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BUYBACK_PLAN_SEED,
+            config.as_ref(),
+            swap_in_token.as_ref(),
+            swap_out_token.as_ref(),
+        ],
+        treasury_program_id,
+    )
+}
+
+/// Find PDA for buyback owner.
+#[cfg(treasury)]
+pub fn find_buyback_owner_address(
+    buyback_plan: &Pubkey,
+    treasury_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BUYBACK_OWNER_SEED, buyback_plan.as_ref()],
+        treasury_program_id,
+    )
+}
+
 /// Find PDA for timelock config.
 #[cfg(timelock)]
 pub fn find_timelock_config_address(store: &Pubkey, timelock_program_id: &Pubkey) -> (Pubkey, u8) {
@@ -563,6 +629,18 @@ pub fn find_callback_authority(store_program_id: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[CALLBACK_AUTHORITY_SEED], store_program_id)
 }
 
+/// Find PDA for callback program account.
+pub fn find_callback_program_address(
+    store: &Pubkey,
+    program_id: &Pubkey,
+    store_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CALLBACK_PROGRAM_SEED, store.as_ref(), program_id.as_ref()],
+        store_program_id,
+    )
+}
+
 /// Find PDA for competition account.
 #[cfg(competition)]
 pub fn find_competition_address(
@@ -593,6 +671,53 @@ pub fn find_participant_address(
     )
 }
 
+/// Find PDA for competition leaderboard page account.
+#[cfg(competition)]
+pub fn find_leaderboard_page_address(
+    competition: &Pubkey,
+    index: u32,
+    competition_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    use gmsol_programs::gmsol_competition::constants::LEADERBOARD_PAGE_SEED;
+
+    Pubkey::find_program_address(
+        &[
+            LEADERBOARD_PAGE_SEED,
+            competition.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        competition_program_id,
+    )
+}
+
+/// Find PDA for competition prize pool account.
+#[cfg(competition)]
+pub fn find_prize_pool_address(
+    competition: &Pubkey,
+    competition_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    use gmsol_programs::gmsol_competition::constants::PRIZE_POOL_SEED;
+
+    Pubkey::find_program_address(
+        &[PRIZE_POOL_SEED, competition.as_ref()],
+        competition_program_id,
+    )
+}
+
+/// Find PDA for competition prize vault.
+#[cfg(competition)]
+pub fn find_prize_vault_address(
+    competition: &Pubkey,
+    competition_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    use gmsol_programs::gmsol_competition::constants::PRIZE_VAULT_SEED;
+
+    Pubkey::find_program_address(
+        &[PRIZE_VAULT_SEED, competition.as_ref()],
+        competition_program_id,
+    )
+}
+
 /// Find PDA for global state account for the LP program.
 #[cfg(liquidity_provider)]
 pub fn find_lp_global_state_address(lp_program_id: &Pubkey) -> (Pubkey, u8) {
@@ -653,3 +778,44 @@ pub fn find_lp_token_controller_address(
         lp_program_id,
     )
 }
+
+/// Find PDA for GT incentive config account.
+#[cfg(gt_incentive)]
+pub fn find_gt_incentive_config_address(
+    store: &Pubkey,
+    gt_incentive_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    use gmsol_programs::gmsol_gt_incentive::constants::CONFIG_SEED;
+
+    Pubkey::find_program_address(&[CONFIG_SEED, store.as_ref()], gt_incentive_program_id)
+}
+
+/// Find PDA for GT incentive campaign account.
+#[cfg(gt_incentive)]
+pub fn find_gt_incentive_campaign_address(
+    config: &Pubkey,
+    start_time: i64,
+    gt_incentive_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    use gmsol_programs::gmsol_gt_incentive::constants::CAMPAIGN_SEED;
+
+    Pubkey::find_program_address(
+        &[CAMPAIGN_SEED, config.as_ref(), &start_time.to_le_bytes()],
+        gt_incentive_program_id,
+    )
+}
+
+/// Find PDA for GT incentive participant account.
+#[cfg(gt_incentive)]
+pub fn find_gt_incentive_participant_address(
+    campaign: &Pubkey,
+    trader: &Pubkey,
+    gt_incentive_program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    use gmsol_programs::gmsol_gt_incentive::constants::PARTICIPANT_SEED;
+
+    Pubkey::find_program_address(
+        &[PARTICIPANT_SEED, campaign.as_ref(), trader.as_ref()],
+        gt_incentive_program_id,
+    )
+}
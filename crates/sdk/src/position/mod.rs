@@ -1,6 +1,5 @@
 use gmsol_model::{
-    num::Unsigned, num_traits::Zero, price::Prices, PerpMarket, PerpMarketExt, Position,
-    PositionExt, PositionState,
+    num::Unsigned, num_traits::Zero, price::Prices, Position, PositionExt, PositionState,
 };
 use gmsol_programs::model::PositionModel;
 use status::PositionStatus;
@@ -10,6 +9,12 @@ use crate::constants;
 /// Position status.
 pub mod status;
 
+/// Position scanner.
+pub mod scanner;
+
+/// Realized PnL and fee statement.
+pub mod statement;
+
 /// Options for calculating position status.
 #[derive(Debug, Clone, Default)]
 pub struct CalculatePositionStatusOptions {
@@ -100,17 +105,6 @@ impl PositionCalculations for PositionModel {
         let price_impact =
             self.position_price_impact(&size_delta_usd, options.include_virtual_inventory_impact)?;
 
-        let mut price_impact_value = price_impact.value;
-        if price_impact_value.is_negative() {
-            self.market().cap_negative_position_price_impact(
-                &size_delta_usd,
-                true,
-                &mut price_impact_value,
-            )?;
-        } else {
-            price_impact_value = Zero::zero();
-        }
-
         let total_position_fees = self.position_fees(
             &collateral_token_price,
             position_size_in_usd,
@@ -148,40 +142,12 @@ impl PositionCalculations for PositionModel {
         };
 
         // liquidation price
-        let params = self.market().position_params()?;
-        let min_collateral_factor = params.min_collateral_factor();
-        let min_collateral_value = params.min_collateral_value();
-        let liquidation_collateral_usd = gmsol_model::utils::apply_factor::<
-            _,
-            { constants::MARKET_DECIMALS },
-        >(position_size_in_usd, min_collateral_factor)
-        .max(Some(*min_collateral_value))
-        .ok_or(gmsol_model::Error::Computation(
-            "calculating liquidation collateral usd",
-        ))?;
-
-        let liquidation_price = if position_size_in_tokens.is_zero() {
-            None
-        } else {
-            collateral_value
-                .checked_add_signed(price_impact_value)
-                .and_then(|a| a.checked_sub(pending_borrowing_fee_value))
-                .and_then(|a| a.checked_sub(pending_funding_fee_value))
-                .and_then(|a| a.checked_sub(close_order_fee_value))
-                .and_then(|remaining_collateral_usd| {
-                    if self.is_long() {
-                        liquidation_collateral_usd
-                            .checked_add(*position_size_in_usd)?
-                            .checked_sub(remaining_collateral_usd)?
-                            .checked_div(*position_size_in_tokens)
-                    } else {
-                        remaining_collateral_usd
-                            .checked_add(*position_size_in_usd)?
-                            .checked_sub(liquidation_collateral_usd)?
-                            .checked_div(*position_size_in_tokens)
-                    }
-                })
-        };
+        let is_collateral_index_token =
+            self.position().collateral_token == self.market_model().meta.index_token_mint;
+        let liquidation_price = self.liquidation_price(prices, is_collateral_index_token)?;
+
+        // margin health
+        let margin_health = self.margin_health(prices)?;
 
         Ok(PositionStatus {
             entry_price,
@@ -195,6 +161,7 @@ impl PositionCalculations for PositionModel {
             net_value,
             leverage,
             liquidation_price,
+            margin_health,
         })
     }
 }
@@ -0,0 +1,396 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use gmsol_model::{
+    market::base::PnlFactorExceeded, num::MulDiv, price::Prices, utils::div_to_factor_signed,
+    BaseMarket, BaseMarketExt, PnlFactorKind, Position as _, PositionExt, PositionState as _,
+};
+use gmsol_programs::{
+    gmsol_store::accounts::Position,
+    model::{MarketModel, PositionModel},
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{constants, serde::StringPubkey};
+
+/// Options for [`PositionScanner::scan`].
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Whether to look for liquidatable positions.
+    pub liquidation: bool,
+    /// Whether to look for market sides requiring ADL and their candidates.
+    pub adl: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            liquidation: true,
+            adl: true,
+        }
+    }
+}
+
+/// A liquidatable position.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct LiquidationCandidate {
+    /// Position address.
+    pub position: StringPubkey,
+    /// Owner of the position.
+    pub owner: StringPubkey,
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Whether the position is a long position.
+    pub is_long: bool,
+    /// Position size in USD.
+    pub size_in_usd: u128,
+    /// The reason why the position is liquidatable.
+    pub reason: String,
+    /// Margin health of the position, see
+    /// [`PositionStatus::margin_health`](super::status::PositionStatus::margin_health).
+    pub margin_health: Option<u128>,
+}
+
+/// A market side that requires ADL.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct AdlRequirement {
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Whether it is the long side.
+    pub is_long: bool,
+    /// Current PnL factor of the side.
+    pub pnl_factor: i128,
+    /// The PnL factor above which ADL is required.
+    pub max_pnl_factor: u128,
+    /// The min PnL factor allowed after ADL.
+    pub min_pnl_factor_after_adl: u128,
+    /// Estimated PnL value to realize to bring the PnL factor down to
+    /// `min_pnl_factor_after_adl`.
+    pub pnl_to_realize: u128,
+}
+
+/// A position to auto-deleverage.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct AdlCandidate {
+    /// Position address.
+    pub position: StringPubkey,
+    /// Owner of the position.
+    pub owner: StringPubkey,
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Whether the position is a long position.
+    pub is_long: bool,
+    /// Position size in USD.
+    pub size_in_usd: u128,
+    /// Pending PnL value of the position.
+    pub pnl: i128,
+    /// The ratio of the pending PnL value to the size of the position.
+    pub pnl_factor: i128,
+    /// Suggested size to deleverage.
+    pub size_delta_usd: u128,
+}
+
+/// A position that could not be evaluated.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct SkippedPosition {
+    /// Position address.
+    pub position: StringPubkey,
+    /// The reason why the position was skipped.
+    pub reason: String,
+}
+
+/// Report of [`PositionScanner::scan`].
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Liquidatable positions, most urgent first.
+    pub liquidations: Vec<LiquidationCandidate>,
+    /// Market sides requiring ADL, most urgent first.
+    pub adl_requirements: Vec<AdlRequirement>,
+    /// ADL candidates, grouped by market side in the order of `adl_requirements`.
+    ///
+    /// Within each group, the positions are ranked by their PnL factors, and only
+    /// those needed to realize the required PnL are included.
+    pub adl_candidates: Vec<AdlCandidate>,
+    /// Positions that could not be evaluated.
+    pub skipped: Vec<SkippedPosition>,
+}
+
+impl ScanReport {
+    /// Returns whether there is nothing to do.
+    pub fn is_empty(&self) -> bool {
+        self.liquidations.is_empty() && self.adl_requirements.is_empty()
+    }
+}
+
+/// Position scanner.
+///
+/// Evaluates positions against the current prices with [`MarketModel`] and
+/// [`PositionModel`] to find liquidatable positions and ADL candidates.
+#[derive(Debug, Clone, Default)]
+pub struct PositionScanner {
+    markets: BTreeMap<Pubkey, (MarketModel, Option<Prices<u128>>)>,
+    positions: BTreeMap<Pubkey, Arc<Position>>,
+}
+
+impl PositionScanner {
+    /// Insert or replace a market, keeping its prices if any.
+    pub fn insert_market(&mut self, market: MarketModel) -> &mut Self {
+        let market_token = market.meta.market_token_mint;
+        match self.markets.get_mut(&market_token) {
+            Some((current, _)) => *current = market,
+            None => {
+                self.markets.insert(market_token, (market, None));
+            }
+        }
+        self
+    }
+
+    /// Update the prices of a market.
+    ///
+    /// # Errors
+    /// Returns error if the market is not found.
+    pub fn update_prices(
+        &mut self,
+        market_token: &Pubkey,
+        prices: Prices<u128>,
+    ) -> crate::Result<&mut Self> {
+        let (_, current) = self.markets.get_mut(market_token).ok_or_else(|| {
+            crate::Error::custom(format!("[scanner] market `{market_token}` is not found"))
+        })?;
+        *current = Some(prices);
+        Ok(self)
+    }
+
+    /// Insert or replace a position.
+    pub fn insert_position(&mut self, address: Pubkey, position: Arc<Position>) -> &mut Self {
+        self.positions.insert(address, position);
+        self
+    }
+
+    /// Returns the markets.
+    pub fn markets(&self) -> impl Iterator<Item = &MarketModel> {
+        self.markets.values().map(|(market, _)| market)
+    }
+
+    /// Returns the positions.
+    pub fn positions(&self) -> impl Iterator<Item = (&Pubkey, &Arc<Position>)> {
+        self.positions.iter()
+    }
+
+    /// Load the markets of the given store and their positions.
+    ///
+    /// Loads all markets if `market_tokens` is `None`. The prices must be provided
+    /// with [`update_prices`](Self::update_prices) before scanning.
+    #[cfg(feature = "client")]
+    pub async fn fetch<C: std::ops::Deref<Target = impl solana_sdk::signer::Signer> + Clone>(
+        client: &crate::Client<C>,
+        store: &Pubkey,
+        market_tokens: Option<&[Pubkey]>,
+    ) -> crate::Result<Self> {
+        let mut scanner = Self::default();
+        for market in client.markets(store).await?.into_values() {
+            let market_token = market.meta.market_token_mint;
+            if market_tokens
+                .map(|tokens| tokens.contains(&market_token))
+                .unwrap_or(true)
+            {
+                // The market token supply is irrelevant to the positions.
+                scanner.insert_market(MarketModel::from_parts(market, 1));
+            }
+        }
+        match market_tokens {
+            Some(market_tokens) => {
+                for market_token in market_tokens {
+                    for (address, position) in
+                        client.positions(store, None, Some(market_token)).await?
+                    {
+                        scanner.insert_position(address, Arc::new(position));
+                    }
+                }
+            }
+            None => {
+                for (address, position) in client.positions(store, None, None).await? {
+                    scanner.insert_position(address, Arc::new(position));
+                }
+            }
+        }
+        Ok(scanner)
+    }
+
+    /// Scan the positions.
+    ///
+    /// Empty positions are ignored, and positions whose markets or prices are missing
+    /// are reported as skipped.
+    pub fn scan(&self, options: &ScanOptions) -> crate::Result<ScanReport> {
+        let mut report = ScanReport::default();
+        let mut adl_candidates = BTreeMap::<(Pubkey, bool), Vec<AdlCandidate>>::default();
+
+        for (address, position) in self.positions.iter() {
+            let size_in_usd = position.state.size_in_usd;
+            if size_in_usd == 0 {
+                continue;
+            }
+            let mut skip = |reason: String| {
+                report.skipped.push(SkippedPosition {
+                    position: (*address).into(),
+                    reason,
+                });
+            };
+            let Some((market, prices)) = self.markets.get(&position.market_token) else {
+                skip("market is not loaded".to_string());
+                continue;
+            };
+            let Some(prices) = prices else {
+                skip("missing prices".to_string());
+                continue;
+            };
+            let model = match PositionModel::new(market.clone(), position.clone()) {
+                Ok(model) => model,
+                Err(err) => {
+                    skip(err.to_string());
+                    continue;
+                }
+            };
+
+            if options.liquidation {
+                match model.check_liquidatable(prices, true, true) {
+                    Ok(Some(reason)) => {
+                        report.liquidations.push(LiquidationCandidate {
+                            position: (*address).into(),
+                            owner: position.owner.into(),
+                            market_token: position.market_token.into(),
+                            is_long: model.is_long(),
+                            size_in_usd,
+                            reason: reason.to_string(),
+                            margin_health: model.margin_health(prices)?,
+                        });
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        skip(err.to_string());
+                        continue;
+                    }
+                }
+            }
+
+            if options.adl {
+                let pnl = match model.pnl_value(prices, model.size_in_usd()) {
+                    Ok((pnl, _, _)) => pnl,
+                    Err(err) => {
+                        skip(err.to_string());
+                        continue;
+                    }
+                };
+                if pnl > 0 {
+                    let pnl_factor = div_to_factor_signed::<_, { constants::MARKET_DECIMALS }>(
+                        &pnl,
+                        &size_in_usd,
+                    )
+                    .ok_or_else(|| {
+                        crate::Error::custom("[scanner] failed to calculate the pnl factor")
+                    })?;
+                    adl_candidates
+                        .entry((position.market_token, model.is_long()))
+                        .or_default()
+                        .push(AdlCandidate {
+                            position: (*address).into(),
+                            owner: position.owner.into(),
+                            market_token: position.market_token.into(),
+                            is_long: model.is_long(),
+                            size_in_usd,
+                            pnl,
+                            pnl_factor,
+                            size_delta_usd: size_in_usd,
+                        });
+                }
+            }
+        }
+
+        // The closer to being insolvent, the more urgent; larger positions come first.
+        report.liquidations.sort_by(|a, b| {
+            match (a.margin_health, b.margin_health) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| b.size_in_usd.cmp(&a.size_in_usd))
+        });
+
+        let mut groups = Vec::default();
+        for ((market_token, is_long), mut candidates) in adl_candidates {
+            let Some((market, Some(prices))) = self.markets.get(&market_token) else {
+                continue;
+            };
+            let Some(exceeded) =
+                market.pnl_factor_exceeded(prices, PnlFactorKind::ForAdl, is_long)?
+            else {
+                continue;
+            };
+            let min_pnl_factor_after_adl =
+                market.pnl_factor_config(PnlFactorKind::MinAfterAdl, is_long)?;
+            let pnl_to_realize = PnlFactorExceeded {
+                pnl_factor: exceeded.pnl_factor,
+                max_pnl_factor: min_pnl_factor_after_adl,
+                pool_value: exceeded.pool_value,
+            }
+            .exceeded_pnl::<{ constants::MARKET_DECIMALS }>()
+            .unwrap_or(0);
+
+            candidates.sort_by_key(|candidate| Reverse((candidate.pnl_factor, candidate.pnl)));
+            let mut remaining = pnl_to_realize;
+            candidates.retain_mut(|candidate| {
+                if remaining == 0 {
+                    return false;
+                }
+                let pnl = candidate.pnl.unsigned_abs();
+                if pnl > remaining {
+                    // Round down to avoid realizing more than required.
+                    candidate.size_delta_usd = candidate
+                        .size_in_usd
+                        .checked_mul_div(&remaining, &pnl)
+                        .unwrap_or(candidate.size_in_usd);
+                    remaining = 0;
+                } else {
+                    remaining -= pnl;
+                }
+                candidate.size_delta_usd != 0
+            });
+
+            // Urgency is measured by how far the pnl factor exceeds the max pnl factor.
+            let urgency = div_to_factor_signed::<_, { constants::MARKET_DECIMALS }>(
+                &exceeded.pnl_factor,
+                &exceeded.max_pnl_factor,
+            )
+            .unwrap_or(i128::MAX);
+            groups.push((
+                urgency,
+                AdlRequirement {
+                    market_token: market_token.into(),
+                    is_long,
+                    pnl_factor: exceeded.pnl_factor,
+                    max_pnl_factor: exceeded.max_pnl_factor,
+                    min_pnl_factor_after_adl,
+                    pnl_to_realize,
+                },
+                candidates,
+            ));
+        }
+        groups.sort_by_key(|(urgency, _, _)| Reverse(*urgency));
+        for (_, requirement, candidates) in groups {
+            report.adl_requirements.push(requirement);
+            report.adl_candidates.extend(candidates);
+        }
+
+        Ok(report)
+    }
+}
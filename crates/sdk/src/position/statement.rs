@@ -0,0 +1,735 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::{Bound, RangeBounds},
+};
+
+use gmsol_programs::gmsol_store::events::TradeEvent;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    constants::MARKET_DECIMALS,
+    core::{market::MarketMeta, order::TradeFlag, token_config::TokenMapAccess},
+    serde::StringPubkey,
+    utils::{market::MarketDecimals, unsigned_fixed_to_decimal, Amount, Value},
+};
+
+/// Builder for [`Statement`].
+///
+/// Aggregates [`TradeEvent`]s into per-trade, per-position and per-market ledgers.
+/// Token amounts are normalized with the token decimals of the market, and values are
+/// normalized with [`MARKET_DECIMALS`](crate::constants::MARKET_DECIMALS).
+#[derive(Debug, Clone)]
+pub struct StatementBuilder {
+    owner: Option<Pubkey>,
+    start: Bound<i64>,
+    end: Bound<i64>,
+    markets: HashMap<Pubkey, (MarketMeta, MarketDecimals)>,
+    trades: Vec<TradeRecord>,
+    positions: BTreeMap<Pubkey, PositionLedger>,
+    market_totals: BTreeMap<Pubkey, Totals>,
+}
+
+impl Default for StatementBuilder {
+    fn default() -> Self {
+        Self {
+            owner: None,
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+            markets: Default::default(),
+            trades: Default::default(),
+            positions: Default::default(),
+            market_totals: Default::default(),
+        }
+    }
+}
+
+impl StatementBuilder {
+    /// Only include the trades of the given owner.
+    pub fn owner(mut self, owner: &Pubkey) -> Self {
+        self.owner = Some(*owner);
+        self
+    }
+
+    /// Only include the trades with timestamps in the given range.
+    pub fn time_range(mut self, range: impl RangeBounds<i64>) -> Self {
+        self.start = range.start_bound().cloned();
+        self.end = range.end_bound().cloned();
+        self
+    }
+
+    /// Insert a market, using the token map to resolve its token decimals.
+    pub fn insert_market(
+        &mut self,
+        meta: &MarketMeta,
+        token_map: &impl TokenMapAccess,
+    ) -> crate::Result<&mut Self> {
+        let decimals = MarketDecimals::new(meta, token_map)?;
+        Ok(self.insert_market_with_decimals(meta, decimals))
+    }
+
+    /// Insert a market with the given token decimals.
+    pub fn insert_market_with_decimals(
+        &mut self,
+        meta: &MarketMeta,
+        decimals: MarketDecimals,
+    ) -> &mut Self {
+        self.markets
+            .insert(meta.market_token_mint, (*meta, decimals));
+        self
+    }
+
+    /// Add a trade to the statement.
+    ///
+    /// Returns `false` if the trade is filtered out by the owner or the time range.
+    /// The market of the trade must have been inserted.
+    pub fn add_trade(&mut self, event: &TradeEvent) -> crate::Result<bool> {
+        if self.owner.is_some_and(|owner| owner != event.user)
+            || !(self.start, self.end).contains(&event.ts)
+        {
+            return Ok(false);
+        }
+
+        let (meta, decimals) = self.markets.get(&event.market_token).ok_or_else(|| {
+            crate::Error::custom(format!(
+                "market `{}` of the trade is not provided",
+                event.market_token
+            ))
+        })?;
+        let trade = Trade::new(event, meta)?;
+
+        self.positions
+            .entry(event.position)
+            .or_insert_with(|| PositionLedger {
+                market_token: event.market_token,
+                collateral_token: trade.collateral_token,
+                is_long: event.is_long(),
+                is_collateral_long: event.is_collateral_long(),
+                decimals: *decimals,
+                fees: Default::default(),
+                claimable_long_token_amount: 0,
+                claimable_short_token_amount: 0,
+                totals: Default::default(),
+            })
+            .add(&trade)?;
+        self.market_totals
+            .entry(event.market_token)
+            .or_default()
+            .add(&trade)?;
+        self.trades.push(trade.to_record(event, decimals)?);
+
+        Ok(true)
+    }
+
+    /// Add trades to the statement.
+    ///
+    /// Returns the number of trades included.
+    pub fn add_trades<'a>(
+        &mut self,
+        events: impl IntoIterator<Item = &'a TradeEvent>,
+    ) -> crate::Result<usize> {
+        let mut count = 0;
+        for event in events {
+            if self.add_trade(event)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Build the statement.
+    pub fn build(self) -> crate::Result<Statement> {
+        let Self {
+            owner,
+            mut trades,
+            positions,
+            market_totals,
+            ..
+        } = self;
+
+        trades.sort_by_key(|trade| (trade.ts, trade.slot, trade.trade_id));
+
+        let positions = positions
+            .into_iter()
+            .map(|(position, ledger)| ledger.to_summary(&position))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let markets = market_totals
+            .into_iter()
+            .map(|(market_token, totals)| {
+                Ok(MarketSummary {
+                    market_token: market_token.into(),
+                    trades: totals.trades,
+                    first_ts: totals.first_ts,
+                    last_ts: totals.last_ts,
+                    volume: Value::from_u128(totals.volume),
+                    realized_pnl: Value::from_i128(totals.realized_pnl),
+                    price_impact_value: Value::from_i128(totals.price_impact_value),
+                    order_fee_value: Value::from_u128(totals.fee_values.order),
+                    borrowing_fee_value: Value::from_u128(totals.fee_values.borrowing),
+                    funding_fee_value: Value::from_u128(totals.fee_values.funding),
+                    liquidation_fee_value: Value::from_u128(totals.fee_values.liquidation),
+                    total_fee_value: Value::from_u128(totals.fee_values.total()?),
+                    net_pnl: Value::from_i128(totals.net_pnl()?),
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Statement {
+            owner: owner.map(Into::into),
+            trades,
+            positions,
+            markets,
+        })
+    }
+}
+
+/// Realized PnL and fee statement.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Statement {
+    /// Owner.
+    pub owner: Option<StringPubkey>,
+    /// Trades, ordered by time.
+    pub trades: Vec<TradeRecord>,
+    /// Per-position summaries.
+    pub positions: Vec<PositionSummary>,
+    /// Per-market summaries.
+    pub markets: Vec<MarketSummary>,
+}
+
+impl Statement {
+    /// Create a builder.
+    pub fn builder() -> StatementBuilder {
+        StatementBuilder::default()
+    }
+}
+
+/// A trade in the statement.
+///
+/// Fee amounts are in collateral token units, and fee values are
+/// calculated with the min price of the collateral token at the time of the trade.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TradeRecord {
+    /// Timestamp.
+    pub ts: i64,
+    /// Slot.
+    pub slot: u64,
+    /// Trade ID.
+    pub trade_id: u64,
+    /// Order.
+    pub order: StringPubkey,
+    /// Position.
+    pub position: StringPubkey,
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Collateral token.
+    pub collateral_token: StringPubkey,
+    /// Whether the position is long.
+    pub is_long: bool,
+    /// Whether the trade increases the position.
+    pub is_increase: bool,
+    /// Size delta value.
+    pub size_delta_usd: Value,
+    /// Size delta in index tokens.
+    pub size_delta_in_tokens: Amount,
+    /// Execution price of the index token.
+    pub execution_price: Value,
+    /// Realized PnL.
+    pub realized_pnl: Value,
+    /// Price impact value.
+    pub price_impact_value: Value,
+    /// Order fee amount, including the parts for the receiver and the pool.
+    pub order_fee: Amount,
+    /// Borrowing fee amount.
+    pub borrowing_fee: Amount,
+    /// Funding fee amount.
+    pub funding_fee: Amount,
+    /// Liquidation fee amount.
+    pub liquidation_fee: Amount,
+    /// Claimable funding fee in long token.
+    pub claimable_funding_long_token: Amount,
+    /// Claimable funding fee in short token.
+    pub claimable_funding_short_token: Amount,
+    /// Order fee value.
+    pub order_fee_value: Value,
+    /// Borrowing fee value.
+    pub borrowing_fee_value: Value,
+    /// Funding fee value.
+    pub funding_fee_value: Value,
+    /// Liquidation fee value.
+    pub liquidation_fee_value: Value,
+}
+
+/// Summary of the trades of a position.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct PositionSummary {
+    /// Position.
+    pub position: StringPubkey,
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Collateral token.
+    pub collateral_token: StringPubkey,
+    /// Whether the position is long.
+    pub is_long: bool,
+    /// Number of trades.
+    pub trades: u64,
+    /// Timestamp of the first trade.
+    pub first_ts: i64,
+    /// Timestamp of the last trade.
+    pub last_ts: i64,
+    /// Total size delta value.
+    pub volume: Value,
+    /// Realized PnL.
+    pub realized_pnl: Value,
+    /// Price impact value.
+    pub price_impact_value: Value,
+    /// Order fee amount.
+    pub order_fee: Amount,
+    /// Borrowing fee amount.
+    pub borrowing_fee: Amount,
+    /// Funding fee amount.
+    pub funding_fee: Amount,
+    /// Liquidation fee amount.
+    pub liquidation_fee: Amount,
+    /// Claimable funding fee in long token.
+    pub claimable_funding_long_token: Amount,
+    /// Claimable funding fee in short token.
+    pub claimable_funding_short_token: Amount,
+    /// Total fee value.
+    pub total_fee_value: Value,
+    /// Realized PnL net of fees, excluding price impact.
+    pub net_pnl: Value,
+}
+
+/// Summary of the trades in a market.
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct MarketSummary {
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Number of trades.
+    pub trades: u64,
+    /// Timestamp of the first trade.
+    pub first_ts: i64,
+    /// Timestamp of the last trade.
+    pub last_ts: i64,
+    /// Total size delta value.
+    pub volume: Value,
+    /// Realized PnL.
+    pub realized_pnl: Value,
+    /// Price impact value.
+    pub price_impact_value: Value,
+    /// Order fee value.
+    pub order_fee_value: Value,
+    /// Borrowing fee value.
+    pub borrowing_fee_value: Value,
+    /// Funding fee value.
+    pub funding_fee_value: Value,
+    /// Liquidation fee value.
+    pub liquidation_fee_value: Value,
+    /// Total fee value.
+    pub total_fee_value: Value,
+    /// Realized PnL net of fees, excluding price impact.
+    pub net_pnl: Value,
+}
+
+/// Raw amounts of a trade.
+struct Trade {
+    ts: i64,
+    collateral_token: Pubkey,
+    size_delta_usd: u128,
+    size_delta_in_tokens: u128,
+    realized_pnl: i128,
+    price_impact_value: i128,
+    fees: Fees,
+    fee_values: Fees,
+    claimable_long_token_amount: u128,
+    claimable_short_token_amount: u128,
+}
+
+impl Trade {
+    fn new(event: &TradeEvent, meta: &MarketMeta) -> crate::Result<Self> {
+        let (collateral_token, collateral_token_price) = if event.is_collateral_long() {
+            (meta.long_token_mint, event.prices.long.min)
+        } else {
+            (meta.short_token_mint, event.prices.short.min)
+        };
+
+        let fees = Fees {
+            order: event
+                .fees
+                .order_fee_for_receiver_amount
+                .checked_add(event.fees.order_fee_for_pool_amount)
+                .ok_or(gmsol_model::Error::Computation("calculating order fee"))?,
+            borrowing: event.fees.total_borrowing_fee_amount,
+            funding: event.fees.funding_fee_amount,
+            liquidation: event.fees.liquidation_fee_amount,
+        };
+        let fee_values = fees.values(collateral_token_price)?;
+
+        Ok(Self {
+            ts: event.ts,
+            collateral_token,
+            size_delta_usd: event.before.size_in_usd.abs_diff(event.after.size_in_usd),
+            size_delta_in_tokens: event
+                .before
+                .size_in_tokens
+                .abs_diff(event.after.size_in_tokens),
+            realized_pnl: event.pnl.pnl,
+            price_impact_value: event.price_impact_value,
+            fees,
+            fee_values,
+            claimable_long_token_amount: event.fees.claimable_funding_fee_long_token_amount,
+            claimable_short_token_amount: event.fees.claimable_funding_fee_short_token_amount,
+        })
+    }
+
+    fn to_record(
+        &self,
+        event: &TradeEvent,
+        decimals: &MarketDecimals,
+    ) -> crate::Result<TradeRecord> {
+        let collateral_token_decimals = if event.is_collateral_long() {
+            decimals.long_token_decimals
+        } else {
+            decimals.short_token_decimals
+        };
+        let amount = |amount| Amount::from_u128(amount, collateral_token_decimals);
+
+        Ok(TradeRecord {
+            ts: event.ts,
+            slot: event.slot,
+            trade_id: event.trade_id,
+            order: event.order.into(),
+            position: event.position.into(),
+            market_token: event.market_token.into(),
+            collateral_token: self.collateral_token.into(),
+            is_long: event.is_long(),
+            is_increase: event.get_flag(TradeFlag::IsIncrease),
+            size_delta_usd: Value::from_u128(self.size_delta_usd),
+            size_delta_in_tokens: Amount::from_u128(
+                self.size_delta_in_tokens,
+                decimals.index_token_decimals,
+            )?,
+            execution_price: price_per_token(event.execution_price, decimals.index_token_decimals)?,
+            realized_pnl: Value::from_i128(self.realized_pnl),
+            price_impact_value: Value::from_i128(self.price_impact_value),
+            order_fee: amount(self.fees.order)?,
+            borrowing_fee: amount(self.fees.borrowing)?,
+            funding_fee: amount(self.fees.funding)?,
+            liquidation_fee: amount(self.fees.liquidation)?,
+            claimable_funding_long_token: Amount::from_u128(
+                self.claimable_long_token_amount,
+                decimals.long_token_decimals,
+            )?,
+            claimable_funding_short_token: Amount::from_u128(
+                self.claimable_short_token_amount,
+                decimals.short_token_decimals,
+            )?,
+            order_fee_value: Value::from_u128(self.fee_values.order),
+            borrowing_fee_value: Value::from_u128(self.fee_values.borrowing),
+            funding_fee_value: Value::from_u128(self.fee_values.funding),
+            liquidation_fee_value: Value::from_u128(self.fee_values.liquidation),
+        })
+    }
+}
+
+/// Fee amounts or values.
+#[derive(Debug, Clone, Copy, Default)]
+struct Fees {
+    order: u128,
+    borrowing: u128,
+    funding: u128,
+    liquidation: u128,
+}
+
+impl Fees {
+    fn values(&self, price: u128) -> crate::Result<Self> {
+        let value = |amount: u128| {
+            amount
+                .checked_mul(price)
+                .ok_or(gmsol_model::Error::Computation("calculating fee value"))
+        };
+        Ok(Self {
+            order: value(self.order)?,
+            borrowing: value(self.borrowing)?,
+            funding: value(self.funding)?,
+            liquidation: value(self.liquidation)?,
+        })
+    }
+
+    fn add(&mut self, other: &Self) -> crate::Result<()> {
+        let add = |a: u128, b: u128| {
+            a.checked_add(b)
+                .ok_or(gmsol_model::Error::Computation("accumulating fees"))
+        };
+        self.order = add(self.order, other.order)?;
+        self.borrowing = add(self.borrowing, other.borrowing)?;
+        self.funding = add(self.funding, other.funding)?;
+        self.liquidation = add(self.liquidation, other.liquidation)?;
+        Ok(())
+    }
+
+    fn total(&self) -> crate::Result<u128> {
+        Ok(self
+            .order
+            .checked_add(self.borrowing)
+            .and_then(|total| total.checked_add(self.funding))
+            .and_then(|total| total.checked_add(self.liquidation))
+            .ok_or(gmsol_model::Error::Computation("calculating total fees"))?)
+    }
+}
+
+/// Accumulated values of trades.
+#[derive(Debug, Clone, Default)]
+struct Totals {
+    trades: u64,
+    first_ts: i64,
+    last_ts: i64,
+    volume: u128,
+    realized_pnl: i128,
+    price_impact_value: i128,
+    fee_values: Fees,
+}
+
+impl Totals {
+    fn add(&mut self, trade: &Trade) -> crate::Result<()> {
+        if self.trades == 0 {
+            self.first_ts = trade.ts;
+            self.last_ts = trade.ts;
+        } else {
+            self.first_ts = self.first_ts.min(trade.ts);
+            self.last_ts = self.last_ts.max(trade.ts);
+        }
+        self.trades += 1;
+        self.volume = self
+            .volume
+            .checked_add(trade.size_delta_usd)
+            .ok_or(gmsol_model::Error::Computation("accumulating volume"))?;
+        self.realized_pnl = self
+            .realized_pnl
+            .checked_add(trade.realized_pnl)
+            .ok_or(gmsol_model::Error::Computation("accumulating realized pnl"))?;
+        self.price_impact_value = self
+            .price_impact_value
+            .checked_add(trade.price_impact_value)
+            .ok_or(gmsol_model::Error::Computation(
+                "accumulating price impact value",
+            ))?;
+        self.fee_values.add(&trade.fee_values)?;
+        Ok(())
+    }
+
+    fn net_pnl(&self) -> crate::Result<i128> {
+        let total_fee_value = i128::try_from(self.fee_values.total()?)?;
+        Ok(self
+            .realized_pnl
+            .checked_sub(total_fee_value)
+            .ok_or(gmsol_model::Error::Computation("calculating net pnl"))?)
+    }
+}
+
+/// Ledger of a position.
+#[derive(Debug, Clone)]
+struct PositionLedger {
+    market_token: Pubkey,
+    collateral_token: Pubkey,
+    is_long: bool,
+    is_collateral_long: bool,
+    decimals: MarketDecimals,
+    fees: Fees,
+    claimable_long_token_amount: u128,
+    claimable_short_token_amount: u128,
+    totals: Totals,
+}
+
+impl PositionLedger {
+    fn add(&mut self, trade: &Trade) -> crate::Result<()> {
+        self.fees.add(&trade.fees)?;
+        self.claimable_long_token_amount = self
+            .claimable_long_token_amount
+            .checked_add(trade.claimable_long_token_amount)
+            .ok_or(gmsol_model::Error::Computation(
+                "accumulating claimable funding fees",
+            ))?;
+        self.claimable_short_token_amount = self
+            .claimable_short_token_amount
+            .checked_add(trade.claimable_short_token_amount)
+            .ok_or(gmsol_model::Error::Computation(
+                "accumulating claimable funding fees",
+            ))?;
+        self.totals.add(trade)
+    }
+
+    fn to_summary(&self, position: &Pubkey) -> crate::Result<PositionSummary> {
+        let decimals = &self.decimals;
+        let collateral_token_decimals = if self.is_collateral_long {
+            decimals.long_token_decimals
+        } else {
+            decimals.short_token_decimals
+        };
+        let amount = |amount| Amount::from_u128(amount, collateral_token_decimals);
+        let totals = &self.totals;
+
+        Ok(PositionSummary {
+            position: (*position).into(),
+            market_token: self.market_token.into(),
+            collateral_token: self.collateral_token.into(),
+            is_long: self.is_long,
+            trades: totals.trades,
+            first_ts: totals.first_ts,
+            last_ts: totals.last_ts,
+            volume: Value::from_u128(totals.volume),
+            realized_pnl: Value::from_i128(totals.realized_pnl),
+            price_impact_value: Value::from_i128(totals.price_impact_value),
+            order_fee: amount(self.fees.order)?,
+            borrowing_fee: amount(self.fees.borrowing)?,
+            funding_fee: amount(self.fees.funding)?,
+            liquidation_fee: amount(self.fees.liquidation)?,
+            claimable_funding_long_token: Amount::from_u128(
+                self.claimable_long_token_amount,
+                decimals.long_token_decimals,
+            )?,
+            claimable_funding_short_token: Amount::from_u128(
+                self.claimable_short_token_amount,
+                decimals.short_token_decimals,
+            )?,
+            total_fee_value: Value::from_u128(totals.fee_values.total()?),
+            net_pnl: Value::from_i128(totals.net_pnl()?),
+        })
+    }
+}
+
+/// Convert a unit price to the price per token.
+fn price_per_token(unit_price: u128, token_decimals: u8) -> crate::Result<Value> {
+    let price = MARKET_DECIMALS
+        .checked_sub(token_decimals)
+        .and_then(|decimals| unsigned_fixed_to_decimal(unit_price, decimals))
+        .ok_or_else(|| crate::Error::custom("price exceeds the maximum value"))?;
+    Ok(Value(price.normalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use gmsol_programs::anchor_lang::AnchorDeserialize;
+
+    use crate::core::order::TradeFlagContainer;
+
+    use super::*;
+
+    const USD: u128 = 10u128.pow(MARKET_DECIMALS as u32);
+
+    fn meta() -> MarketMeta {
+        MarketMeta {
+            market_token_mint: Pubkey::new_unique(),
+            index_token_mint: Pubkey::new_unique(),
+            long_token_mint: Pubkey::new_unique(),
+            short_token_mint: Pubkey::new_unique(),
+        }
+    }
+
+    fn decimals() -> MarketDecimals {
+        MarketDecimals {
+            index_token_decimals: 9,
+            long_token_decimals: 9,
+            short_token_decimals: 6,
+        }
+    }
+
+    fn trade(
+        meta: &MarketMeta,
+        user: &Pubkey,
+        position: &Pubkey,
+        ts: i64,
+        is_increase: bool,
+    ) -> TradeEvent {
+        let mut event = TradeEvent::deserialize(&mut &[0u8; 4096][..]).unwrap();
+        let mut flags = TradeFlagContainer::default();
+        flags.set_flag(TradeFlag::IsLong, true);
+        flags.set_flag(TradeFlag::IsIncrease, is_increase);
+        event.flags = flags.into_value();
+        event.market_token = meta.market_token_mint;
+        event.user = *user;
+        event.position = *position;
+        event.ts = ts;
+        // 1 USDC = 1 USD.
+        event.prices.short.min = USD / 10u128.pow(6);
+        // 1 SOL = 100 USD.
+        event.execution_price = 100 * USD / 10u128.pow(9);
+        event.fees.order_fee_for_receiver_amount = 500_000;
+        event.fees.order_fee_for_pool_amount = 500_000;
+        if is_increase {
+            event.after.size_in_usd = 1_000 * USD;
+            event.after.size_in_tokens = 10 * 10u128.pow(9);
+        } else {
+            event.before.size_in_usd = 1_000 * USD;
+            event.before.size_in_tokens = 10 * 10u128.pow(9);
+            event.pnl.pnl = 50 * USD as i128;
+            event.fees.total_borrowing_fee_amount = 200_000;
+            event.fees.funding_fee_amount = 100_000;
+        }
+        event
+    }
+
+    #[test]
+    fn test_statement() -> crate::Result<()> {
+        let meta = meta();
+        let owner = Pubkey::new_unique();
+        let position = Pubkey::new_unique();
+
+        let mut builder = Statement::builder().owner(&owner).time_range(..300);
+        builder.insert_market_with_decimals(&meta, decimals());
+        let included = builder.add_trades(&[
+            trade(&meta, &owner, &position, 200, false),
+            trade(&meta, &owner, &position, 100, true),
+            trade(&meta, &Pubkey::new_unique(), &position, 150, true),
+            trade(&meta, &owner, &position, 300, true),
+        ])?;
+        assert_eq!(included, 2);
+
+        let statement = builder.build()?;
+
+        assert_eq!(statement.trades.len(), 2);
+        let open = &statement.trades[0];
+        assert_eq!(open.ts, 100);
+        assert!(open.is_increase);
+        assert_eq!(open.collateral_token, meta.short_token_mint.into());
+        assert_eq!(open.size_delta_usd, "1000".parse().unwrap());
+        assert_eq!(open.size_delta_in_tokens, "10".parse().unwrap());
+        assert_eq!(open.execution_price, "100".parse().unwrap());
+        assert_eq!(open.order_fee, "1".parse().unwrap());
+        assert_eq!(open.order_fee_value, "1".parse().unwrap());
+
+        assert_eq!(statement.positions.len(), 1);
+        let summary = &statement.positions[0];
+        assert_eq!(summary.trades, 2);
+        assert_eq!((summary.first_ts, summary.last_ts), (100, 200));
+        assert_eq!(summary.volume, "2000".parse().unwrap());
+        assert_eq!(summary.realized_pnl, "50".parse().unwrap());
+        assert_eq!(summary.order_fee, "2".parse().unwrap());
+        assert_eq!(summary.borrowing_fee, "0.2".parse().unwrap());
+        assert_eq!(summary.funding_fee, "0.1".parse().unwrap());
+        assert_eq!(summary.total_fee_value, "2.3".parse().unwrap());
+        assert_eq!(summary.net_pnl, "47.7".parse().unwrap());
+
+        assert_eq!(statement.markets.len(), 1);
+        let market = &statement.markets[0];
+        assert_eq!(market.market_token, meta.market_token_mint.into());
+        assert_eq!(market.borrowing_fee_value, "0.2".parse().unwrap());
+        assert_eq!(market.net_pnl, summary.net_pnl);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_statement_without_market() {
+        let meta = meta();
+        let owner = Pubkey::new_unique();
+        let mut builder = Statement::builder();
+        assert!(builder
+            .add_trade(&trade(&meta, &owner, &Pubkey::new_unique(), 0, true))
+            .is_err());
+    }
+}
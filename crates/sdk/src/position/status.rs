@@ -24,6 +24,11 @@ pub struct PositionStatus {
     pub net_value: i128,
     /// Leverage.
     pub leverage: Option<u128>,
-    /// Liquidation price.
+    /// Liquidation price, i.e., the index token price at which the position becomes liquidatable.
+    ///
+    /// `None` if the position cannot be liquidated by the index token price moving alone.
     pub liquidation_price: Option<u128>,
+    /// Margin health, i.e., the ratio of the remaining collateral value on close to
+    /// the min collateral value for liquidation. The position is liquidatable below `1`.
+    pub margin_health: Option<u128>,
 }
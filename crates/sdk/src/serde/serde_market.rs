@@ -3,10 +3,10 @@ use gmsol_programs::{
     constants::MARKET_DECIMALS,
     gmsol_store::{
         accounts::{Market, MarketConfigBuffer},
-        types::{Clocks, MarketConfig, MarketMeta, OtherState, Pool, Pools},
+        types::{Clocks, MarketConfig, MarketConfigSchedule, MarketMeta, OtherState, Pool, Pools},
     },
 };
-use gmsol_utils::market::{MarketConfigKey, MarketFlag};
+use gmsol_utils::market::{MarketConfigKey, MarketFlag, MarketTradingMode};
 use indexmap::IndexMap;
 use strum::IntoEnumIterator;
 
@@ -35,6 +35,9 @@ pub struct SerdeMarket {
     pub is_adl_enabled_for_short: bool,
     /// Is GT minting enabled.
     pub is_gt_minting_enabled: bool,
+    /// Trading mode.
+    #[cfg_attr(serde, serde(default))]
+    pub trading_mode: MarketTradingMode,
     /// The unix timestamp when the closed state was updated.
     pub closed_updated_at: i64,
     /// Store address.
@@ -49,6 +52,9 @@ pub struct SerdeMarket {
     pub pools: SerdeMarketPools,
     /// Config.
     pub config: SerdeMarketConfig,
+    /// Scheduled config updates.
+    #[cfg_attr(serde, serde(default))]
+    pub config_schedules: Vec<SerdeMarketConfigSchedule>,
 }
 
 impl SerdeMarket {
@@ -64,6 +70,7 @@ impl SerdeMarket {
             is_adl_enabled_for_long: flags.get_flag(MarketFlag::AutoDeleveragingEnabledForLong),
             is_adl_enabled_for_short: flags.get_flag(MarketFlag::AutoDeleveragingEnabledForShort),
             is_gt_minting_enabled: flags.get_flag(MarketFlag::GTEnabled),
+            trading_mode: market.trading_mode(),
             closed_updated_at: market.closed_state_updated_at,
             store: market.store.into(),
             meta: (&market.meta).into(),
@@ -71,6 +78,14 @@ impl SerdeMarket {
             clocks: (&market.state.clocks).into(),
             pools: SerdeMarketPools::from_pools(&market.state.pools, decimals)?,
             config: SerdeMarketConfig::from_market_config(&market.config, decimals)?,
+            config_schedules: market
+                .state
+                .config_schedules
+                .iter()
+                .map(|schedule| {
+                    SerdeMarketConfigSchedule::from_market_config_schedule(schedule, decimals)
+                })
+                .collect::<crate::Result<_>>()?,
         })
     }
 }
@@ -352,6 +367,40 @@ impl SerdeMarketConfig {
     }
 }
 
+/// Serializable version of [`MarketConfigSchedule`].
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct SerdeMarketConfigSchedule {
+    /// Key.
+    pub key: MarketConfigKey,
+    /// Value before the ramp.
+    pub from: Amount,
+    /// Target value.
+    pub target: Amount,
+    /// Start time of the ramp.
+    pub start_at: i64,
+    /// End time of the ramp.
+    pub end_at: i64,
+}
+
+impl SerdeMarketConfigSchedule {
+    /// Create from [`MarketConfigSchedule`].
+    pub fn from_market_config_schedule(
+        schedule: &MarketConfigSchedule,
+        decimals: MarketDecimals,
+    ) -> crate::Result<Self> {
+        let key = schedule.key()?;
+        let decimals = decimals.market_config_decimals(key)?;
+        Ok(Self {
+            key,
+            from: Amount::from_u128(schedule.from, decimals)?,
+            target: Amount::from_u128(schedule.target, decimals)?,
+            start_at: schedule.start_at,
+            end_at: schedule.end_at(),
+        })
+    }
+}
+
 /// Differences between two [`SerdeMarketConfig`]s.
 #[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
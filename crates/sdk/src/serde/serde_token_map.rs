@@ -1,5 +1,5 @@
 use gmsol_utils::{
-    oracle::PriceProviderKind,
+    oracle::{CompositePriceMode, PriceProviderKind},
     token_config::{FeedConfig, TokenConfig},
 };
 use indexmap::IndexMap;
@@ -27,6 +27,12 @@ pub struct SerdeTokenConfig {
     pub feeds: IndexMap<PriceProviderKind, SerdeFeedConfig>,
     /// Heartbeat duration.
     pub heartbeat_duration: u32,
+    /// Composite price config.
+    #[cfg_attr(serde, serde(default))]
+    pub composite_price: Option<SerdeCompositePrice>,
+    /// Price circuit breaker config.
+    #[cfg_attr(serde, serde(default))]
+    pub price_circuit_breaker: Option<SerdePriceCircuitBreaker>,
 }
 
 impl<'a> TryFrom<&'a TokenConfig> for SerdeTokenConfig {
@@ -50,10 +56,60 @@ impl<'a> TryFrom<&'a TokenConfig> for SerdeTokenConfig {
             expected_provider: config.expected_provider().map_err(crate::Error::custom)?,
             feeds,
             heartbeat_duration: config.heartbeat_duration(),
+            composite_price: SerdeCompositePrice::from_token_config(config)?,
+            price_circuit_breaker: config.price_circuit_breaker().map(|(factor, window)| {
+                SerdePriceCircuitBreaker {
+                    max_move_factor: Value::from_u128(factor),
+                    window,
+                    is_tripped: config.price_circuit_breaker_state().is_tripped(),
+                }
+            }),
         })
     }
 }
 
+/// Serializable version of the composite price config of [`TokenConfig`].
+#[derive(Debug, Clone)]
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+pub struct SerdeCompositePrice {
+    /// Composite price mode.
+    pub mode: CompositePriceMode,
+    /// Price sources, starting with the expected provider.
+    pub sources: Vec<PriceProviderKind>,
+    /// Max disagreement factor.
+    #[cfg_attr(serde, serde(default))]
+    pub max_disagreement_factor: Option<Value>,
+}
+
+impl SerdeCompositePrice {
+    /// Create from [`TokenConfig`].
+    ///
+    /// Returns `None` if the composite price is not enabled for the token.
+    pub fn from_token_config(config: &TokenConfig) -> crate::Result<Option<Self>> {
+        if !config.is_composite() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            mode: config.composite_mode().map_err(crate::Error::custom)?,
+            sources: config.price_sources().map_err(crate::Error::custom)?,
+            max_disagreement_factor: config.max_disagreement_factor().map(Value::from_u128),
+        }))
+    }
+}
+
+/// Serializable version of the price circuit breaker config of [`TokenConfig`].
+#[derive(Debug, Clone)]
+#[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
+pub struct SerdePriceCircuitBreaker {
+    /// Max price move factor within a window.
+    pub max_move_factor: Value,
+    /// Window in seconds.
+    pub window: u32,
+    /// Whether the circuit breaker has been tripped.
+    #[cfg_attr(serde, serde(default))]
+    pub is_tripped: bool,
+}
+
 /// Encoding.
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(serde, derive(serde::Serialize, serde::Deserialize))]
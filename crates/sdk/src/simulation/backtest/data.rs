@@ -0,0 +1,154 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use gmsol_model::price::Price;
+use gmsol_programs::{gmsol_store::accounts::Market, model::MarketModel};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    serde::StringPubkey, utils::zero_copy::try_deserialize_zero_copy_from_base64_with_options,
+};
+
+/// A snapshot of a market account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketSnapshot {
+    /// Base64-encoded account data of the market.
+    pub data: String,
+    /// Whether the account data is encoded without the discriminator.
+    #[serde(default)]
+    pub no_discriminator: bool,
+    /// Total supply of the market token.
+    pub supply: u64,
+}
+
+impl MarketSnapshot {
+    /// Decode into a [`MarketModel`].
+    pub fn to_model(&self) -> crate::Result<MarketModel> {
+        let market = try_deserialize_zero_copy_from_base64_with_options::<Market>(
+            &self.data,
+            self.no_discriminator,
+        )?;
+        Ok(MarketModel::from_parts(Arc::new(market.0), self.supply))
+    }
+}
+
+/// A frame of a [`TimeSeries`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Frame {
+    /// Unix timestamp of the frame.
+    pub timestamp: i64,
+    /// Token prices (in unit price) at this frame, keyed by token mint address.
+    #[serde(default)]
+    pub prices: HashMap<StringPubkey, Price<u128>>,
+    /// Market snapshots to replace the market states in the simulator with.
+    #[serde(default)]
+    pub markets: Vec<MarketSnapshot>,
+}
+
+/// A time series of token prices and market snapshots.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimeSeries {
+    frames: Vec<Frame>,
+}
+
+impl TimeSeries {
+    /// Create from frames.
+    ///
+    /// # Errors
+    /// Returns error if the timestamps of the frames are not strictly increasing.
+    pub fn new(frames: Vec<Frame>) -> crate::Result<Self> {
+        if let Some(window) = frames
+            .windows(2)
+            .find(|window| window[0].timestamp >= window[1].timestamp)
+        {
+            return Err(crate::Error::custom(format!(
+                "[backtest] frame timestamps must be strictly increasing, but {} >= {}",
+                window[0].timestamp, window[1].timestamp
+            )));
+        }
+        Ok(Self { frames })
+    }
+
+    /// Parse from a JSON array of frames.
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        let frames = serde_json::from_str(json)?;
+        Self::new(frames)
+    }
+
+    /// Parse from JSON Lines, one frame per line.
+    ///
+    /// Blank lines are ignored.
+    pub fn from_json_lines(lines: &str) -> crate::Result<Self> {
+        let frames = lines
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(frames)
+    }
+
+    /// Load from a local file.
+    ///
+    /// Files with the `.jsonl` extension are parsed as JSON Lines, others as a JSON array.
+    pub fn load(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|err| {
+            crate::Error::custom(format!(
+                "[backtest] failed to read `{}`: {err}",
+                path.display()
+            ))
+        })?;
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            Self::from_json_lines(&content)
+        } else {
+            Self::from_json(&content)
+        }
+    }
+
+    /// Returns the frames.
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Returns the timestamp of the first frame.
+    pub fn start(&self) -> Option<i64> {
+        self.frames.first().map(|frame| frame.timestamp)
+    }
+
+    /// Returns the timestamp of the last frame.
+    pub fn end(&self) -> Option<i64> {
+        self.frames.last().map(|frame| frame.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "So11111111111111111111111111111111111111112";
+
+    #[test]
+    fn parse_json_lines() -> crate::Result<()> {
+        let lines = format!(
+            r#"{{"timestamp":1,"prices":{{"{TOKEN}":{{"min":100000000000000000000,"max":100000000000000000001}}}}}}
+
+{{"timestamp":2}}
+"#
+        );
+        let series = TimeSeries::from_json_lines(&lines)?;
+        assert_eq!(series.frames().len(), 2);
+        assert_eq!(series.start(), Some(1));
+        assert_eq!(series.end(), Some(2));
+        let token = TOKEN.parse::<StringPubkey>()?;
+        let price = series.frames()[0].prices[&token];
+        assert_eq!(price.min, 100_000_000_000_000_000_000);
+        assert_eq!(price.max, 100_000_000_000_000_000_001);
+        assert!(series.frames()[1].prices.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unordered_frames() {
+        let json = r#"[{"timestamp":2},{"timestamp":2}]"#;
+        assert!(TimeSeries::from_json(json).is_err());
+    }
+}
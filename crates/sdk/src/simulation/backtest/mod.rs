@@ -0,0 +1,401 @@
+/// Time series of prices and market snapshots.
+pub mod data;
+
+/// Backtest reports.
+pub mod report;
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use gmsol_model::{
+    action::decrease_position::DecreasePositionFlags, MarketAction, PositionExt, PositionMutExt,
+    PositionState,
+};
+use gmsol_programs::{gmsol_store::accounts::Position, model::PositionModel};
+use solana_sdk::pubkey::Pubkey;
+use typed_builder::TypedBuilder;
+
+use crate::builders::order::{CreateOrderKind, CreateOrderParams};
+
+use super::{
+    order::{with_vi_models_if_some, OrderSimulationOutput},
+    SimulationOptions, Simulator,
+};
+
+pub use data::{Frame, MarketSnapshot, TimeSeries};
+pub use report::{BacktestReport, FeesPaid, Liquidation, PositionReport, RejectedOrder};
+
+/// An order emitted by a [`Strategy`].
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct BacktestOrder {
+    /// Order kind.
+    pub kind: CreateOrderKind,
+    /// Order params.
+    pub params: CreateOrderParams,
+    /// Collateral token for position orders, or swap out token for swap orders.
+    pub collateral_or_swap_out_token: Pubkey,
+    /// Pay token.
+    #[builder(default, setter(strip_option))]
+    pub pay_token: Option<Pubkey>,
+    /// Receive token.
+    #[builder(default, setter(strip_option))]
+    pub receive_token: Option<Pubkey>,
+    /// Swap path.
+    #[builder(default)]
+    pub swap_path: Vec<Pubkey>,
+}
+
+impl BacktestOrder {
+    fn position_key(&self) -> PositionKey {
+        PositionKey {
+            market_token: self.params.market_token.0,
+            collateral_token: self.collateral_or_swap_out_token,
+            is_long: self.params.is_long,
+        }
+    }
+}
+
+/// Key of a position in a backtest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PositionKey {
+    /// Market token.
+    pub market_token: Pubkey,
+    /// Collateral token.
+    pub collateral_token: Pubkey,
+    /// Whether the position is a long position.
+    pub is_long: bool,
+}
+
+#[derive(Debug)]
+struct OpenPosition {
+    position: Arc<Position>,
+    report: usize,
+}
+
+/// Context for a [`Strategy`] to make decisions on.
+pub struct StrategyContext<'a> {
+    frame: &'a Frame,
+    simulator: &'a Simulator,
+    positions: &'a BTreeMap<PositionKey, OpenPosition>,
+}
+
+impl StrategyContext<'_> {
+    /// Returns the timestamp of the current frame.
+    pub fn timestamp(&self) -> i64 {
+        self.frame.timestamp
+    }
+
+    /// Returns the current frame.
+    pub fn frame(&self) -> &Frame {
+        self.frame
+    }
+
+    /// Returns the simulator.
+    pub fn simulator(&self) -> &Simulator {
+        self.simulator
+    }
+
+    /// Returns the open positions.
+    pub fn positions(&self) -> impl Iterator<Item = (&PositionKey, &Arc<Position>)> {
+        self.positions
+            .iter()
+            .map(|(key, open)| (key, &open.position))
+    }
+
+    /// Get the open position with the given key.
+    pub fn position(&self, key: &PositionKey) -> Option<&Arc<Position>> {
+        self.positions.get(key).map(|open| &open.position)
+    }
+}
+
+/// A trading strategy to evaluate with [`Backtest`].
+pub trait Strategy {
+    /// Returns the orders to execute at the current frame.
+    ///
+    /// The orders are executed one after another right after this method returns.
+    /// Orders that fail to execute, e.g., limit orders whose trigger prices have not
+    /// been reached, are rejected and recorded in the report.
+    fn on_frame(&mut self, ctx: &StrategyContext<'_>) -> crate::Result<Vec<BacktestOrder>>;
+}
+
+impl<F> Strategy for F
+where
+    F: FnMut(&StrategyContext<'_>) -> crate::Result<Vec<BacktestOrder>>,
+{
+    fn on_frame(&mut self, ctx: &StrategyContext<'_>) -> crate::Result<Vec<BacktestOrder>> {
+        (self)(ctx)
+    }
+}
+
+/// Options for [`Backtest`].
+#[derive(Debug, Default, Clone)]
+pub struct BacktestOptions {
+    /// Options for simulating order executions.
+    pub simulation: SimulationOptions,
+    /// Whether to skip liquidating positions.
+    pub skip_liquidations: bool,
+}
+
+/// A backtest driving a [`Simulator`] over a [`TimeSeries`].
+///
+/// For each frame, the backtest:
+/// 1. Updates the token prices and replaces the markets with the provided snapshots.
+/// 2. Advances the clocks of the markets to the frame timestamp, distributing position
+///    impact and updating the borrowing and funding states.
+/// 3. Liquidates the positions that have become liquidatable.
+/// 4. Executes the orders emitted by the [`Strategy`].
+#[derive(Debug, TypedBuilder)]
+pub struct Backtest<'a> {
+    simulator: Simulator,
+    series: &'a TimeSeries,
+    #[builder(default)]
+    options: BacktestOptions,
+}
+
+#[derive(Default)]
+struct State {
+    positions: BTreeMap<PositionKey, OpenPosition>,
+    report: BacktestReport,
+}
+
+impl Backtest<'_> {
+    /// Run the backtest with the given strategy.
+    pub fn run(mut self, strategy: &mut impl Strategy) -> crate::Result<BacktestReport> {
+        let mut state = State::default();
+        state.report.start = self.series.start();
+        state.report.end = self.series.end();
+
+        for frame in self.series.frames() {
+            self.apply_frame(frame)?;
+            self.accrue_fees(frame.timestamp)?;
+            if !self.options.skip_liquidations {
+                self.liquidate(frame.timestamp, &mut state)?;
+            }
+            let orders = strategy.on_frame(&StrategyContext {
+                frame,
+                simulator: &self.simulator,
+                positions: &state.positions,
+            })?;
+            for order in orders {
+                self.execute(frame.timestamp, &order, &mut state)?;
+            }
+        }
+
+        self.finalize(&mut state)?;
+
+        Ok(state.report)
+    }
+
+    fn apply_frame(&mut self, frame: &Frame) -> crate::Result<()> {
+        for (token, price) in frame.prices.iter() {
+            self.simulator.insert_price(token, Arc::new(*price))?;
+        }
+        for snapshot in frame.markets.iter() {
+            self.simulator.insert_market(snapshot.to_model()?);
+        }
+        Ok(())
+    }
+
+    fn accrue_fees(&mut self, timestamp: i64) -> crate::Result<()> {
+        let market_tokens = self
+            .simulator
+            .markets()
+            .map(|(market_token, _)| *market_token)
+            .collect::<Vec<_>>();
+        for market_token in market_tokens {
+            if self.simulator.get_prices_for_market(&market_token).is_ok() {
+                self.simulator
+                    .advance_clock_to(Some(&market_token), timestamp)?;
+            } else {
+                // The clocks are not advanced without prices, so the elapsed time will be
+                // accounted for at the next update.
+                self.simulator
+                    .get_market_mut(&market_token)
+                    .expect("market must exist")
+                    .set_clock_override(Some(timestamp));
+            }
+        }
+        Ok(())
+    }
+
+    fn liquidate(&mut self, timestamp: i64, state: &mut State) -> crate::Result<()> {
+        let keys = state.positions.keys().copied().collect::<Vec<_>>();
+        for key in keys {
+            let Some(market) = self.simulator.get_market(&key.market_token) else {
+                continue;
+            };
+            let (Some(prices), Some(collateral_price)) = (
+                self.simulator.get_prices(&market.meta),
+                self.simulator.get_price(&key.collateral_token),
+            ) else {
+                continue;
+            };
+            let market = market.clone();
+            let open = state.positions.get(&key).expect("must exist");
+
+            let position = PositionModel::new(market.clone(), open.position.clone())?;
+            let Some(reason) = position.check_liquidatable(&prices, true, true)? else {
+                continue;
+            };
+            let size_in_usd = *position.size_in_usd();
+
+            let vi_ctx = if self.options.simulation.disable_vis {
+                None
+            } else {
+                Some(self.simulator.vis_mut())
+            };
+            let (report, position) = with_vi_models_if_some(
+                &market,
+                Some(&open.position),
+                vi_ctx,
+                key.is_long,
+                &key.collateral_token,
+                |position: &mut PositionModel| {
+                    let report = position
+                        .decrease(
+                            prices,
+                            size_in_usd,
+                            None,
+                            0,
+                            DecreasePositionFlags {
+                                is_insolvent_close_allowed: true,
+                                is_liquidation_order: true,
+                                is_cap_size_delta_usd_allowed: false,
+                            },
+                        )?
+                        .execute()?;
+                    Ok(report)
+                },
+            )?;
+            self.simulator
+                .insert_market(position.market_model().clone());
+
+            let open = state.positions.remove(&key).expect("must exist");
+            let record = &mut state.report.positions[open.report];
+            let pnl = *report.pnl().pnl();
+            record.fees.add(report.fees(), &collateral_price)?;
+            record.realized_pnl = record.realized_pnl.saturating_add(pnl);
+            record.closed_at = Some(timestamp);
+            record.liquidation = Some(Liquidation {
+                timestamp,
+                reason: reason.to_string(),
+                size_in_usd,
+                pnl,
+            });
+        }
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        timestamp: i64,
+        order: &BacktestOrder,
+        state: &mut State,
+    ) -> crate::Result<()> {
+        let key = order.position_key();
+        let position = state.positions.get(&key).map(|open| open.position.clone());
+        let collateral_price = self.simulator.get_price(&key.collateral_token);
+
+        // Execute against a copy so that a failed execution leaves no partial effects.
+        let mut simulator = self.simulator.clone();
+        let output = simulator
+            .simulate_order(
+                order.kind,
+                &order.params,
+                &order.collateral_or_swap_out_token,
+            )
+            .pay_token(order.pay_token.as_ref())
+            .receive_token(order.receive_token.as_ref())
+            .swap_path(&order.swap_path)
+            .position(position.as_ref())
+            .build()
+            .execute_with_options(self.options.simulation.clone());
+
+        let output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                state.report.rejected_orders.push(RejectedOrder {
+                    timestamp,
+                    kind: order.kind,
+                    market_token: order.params.market_token,
+                    reason: err.to_string(),
+                });
+                return Ok(());
+            }
+        };
+        self.simulator = simulator;
+
+        match output {
+            OrderSimulationOutput::Increase {
+                report, position, ..
+            } => {
+                let collateral_price = collateral_price.ok_or_else(|| {
+                    crate::Error::custom("[backtest] collateral price is not ready")
+                })?;
+                let index = match state.positions.get(&key) {
+                    Some(open) => open.report,
+                    None => {
+                        state.report.positions.push(PositionReport {
+                            market_token: key.market_token.into(),
+                            collateral_token: key.collateral_token.into(),
+                            is_long: key.is_long,
+                            opened_at: timestamp,
+                            closed_at: None,
+                            max_size_in_usd: 0,
+                            realized_pnl: 0,
+                            unrealized_pnl: 0,
+                            fees: FeesPaid::default(),
+                            liquidation: None,
+                        });
+                        state.report.positions.len() - 1
+                    }
+                };
+                let record = &mut state.report.positions[index];
+                record.fees.add(report.fees(), &collateral_price)?;
+                record.max_size_in_usd = record.max_size_in_usd.max(*position.size_in_usd());
+                state.positions.insert(
+                    key,
+                    OpenPosition {
+                        position: position.position_arc().clone(),
+                        report: index,
+                    },
+                );
+            }
+            OrderSimulationOutput::Decrease {
+                report, position, ..
+            } => {
+                let collateral_price = collateral_price.ok_or_else(|| {
+                    crate::Error::custom("[backtest] collateral price is not ready")
+                })?;
+                let open = state.positions.get_mut(&key).expect("must exist");
+                let record = &mut state.report.positions[open.report];
+                record.fees.add(report.fees(), &collateral_price)?;
+                record.realized_pnl = record.realized_pnl.saturating_add(*report.pnl().pnl());
+                if report.should_remove() {
+                    record.closed_at = Some(timestamp);
+                    state.positions.remove(&key);
+                } else {
+                    open.position = position.position_arc().clone();
+                }
+            }
+            OrderSimulationOutput::Swap(_) => {}
+        }
+
+        Ok(())
+    }
+
+    fn finalize(&self, state: &mut State) -> crate::Result<()> {
+        for open in state.positions.values() {
+            let market_token = &open.position.market_token;
+            let Some(market) = self.simulator.get_market(market_token) else {
+                continue;
+            };
+            let Some(prices) = self.simulator.get_prices(&market.meta) else {
+                continue;
+            };
+            let position = PositionModel::new(market.clone(), open.position.clone())?;
+            let (pnl, _, _) = position.pnl_value(&prices, position.size_in_usd())?;
+            state.report.positions[open.report].unrealized_pnl = pnl;
+        }
+        Ok(())
+    }
+}
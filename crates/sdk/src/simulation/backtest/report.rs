@@ -0,0 +1,173 @@
+use gmsol_model::{params::fee::PositionFees, price::Price};
+use serde::{Deserialize, Serialize};
+
+use crate::{builders::order::CreateOrderKind, serde::StringPubkey};
+
+/// Fees paid by a position, in USD values.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FeesPaid {
+    /// Order fees.
+    pub order: u128,
+    /// Borrowing fees.
+    pub borrowing: u128,
+    /// Funding fees.
+    pub funding: u128,
+    /// Liquidation fees.
+    pub liquidation: u128,
+}
+
+impl FeesPaid {
+    /// Returns the total value of the fees.
+    pub fn total(&self) -> u128 {
+        self.order
+            .saturating_add(self.borrowing)
+            .saturating_add(self.funding)
+            .saturating_add(self.liquidation)
+    }
+
+    pub(super) fn add(
+        &mut self,
+        fees: &PositionFees<u128>,
+        collateral_price: &Price<u128>,
+    ) -> crate::Result<()> {
+        let value = |amount: &u128| {
+            amount
+                .checked_mul(collateral_price.min)
+                .ok_or_else(|| crate::Error::custom("[backtest] fee value overflow"))
+        };
+        self.order = self.order.saturating_add(*fees.order_fees().fee_value());
+        self.borrowing = self
+            .borrowing
+            .saturating_add(value(fees.borrowing_fees().fee_amount())?);
+        self.funding = self
+            .funding
+            .saturating_add(value(fees.funding_fees().amount())?);
+        if let Some(liquidation) = fees.liquidation_fees() {
+            self.liquidation = self.liquidation.saturating_add(*liquidation.fee_value());
+        }
+        Ok(())
+    }
+}
+
+/// A liquidation that happened during the backtest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Liquidation {
+    /// Timestamp of the liquidation.
+    pub timestamp: i64,
+    /// Reason of the liquidation.
+    pub reason: String,
+    /// Position size in USD before the liquidation.
+    pub size_in_usd: u128,
+    /// Realized PnL value of the liquidation.
+    pub pnl: i128,
+}
+
+/// Report of a position over its lifetime, from being opened to being closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionReport {
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Collateral token.
+    pub collateral_token: StringPubkey,
+    /// Whether the position is a long position.
+    pub is_long: bool,
+    /// Timestamp at which the position was opened.
+    pub opened_at: i64,
+    /// Timestamp at which the position was closed, `None` if it is still open.
+    pub closed_at: Option<i64>,
+    /// The max size in USD the position has reached.
+    pub max_size_in_usd: u128,
+    /// Realized PnL value.
+    pub realized_pnl: i128,
+    /// Unrealized PnL value at the end of the backtest.
+    ///
+    /// Pending borrowing and funding fees are not included.
+    pub unrealized_pnl: i128,
+    /// Fees paid.
+    pub fees: FeesPaid,
+    /// The liquidation that closed the position, if any.
+    pub liquidation: Option<Liquidation>,
+}
+
+impl PositionReport {
+    /// Returns whether the position is still open.
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+
+    /// Returns whether the position was liquidated.
+    pub fn is_liquidated(&self) -> bool {
+        self.liquidation.is_some()
+    }
+
+    /// Returns the net PnL value, i.e., realized and unrealized PnL minus the fees paid.
+    pub fn net_pnl(&self) -> i128 {
+        let fees = i128::try_from(self.fees.total()).unwrap_or(i128::MAX);
+        self.realized_pnl
+            .saturating_add(self.unrealized_pnl)
+            .saturating_sub(fees)
+    }
+}
+
+/// An order that failed to execute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedOrder {
+    /// Timestamp of the frame at which the order was emitted.
+    pub timestamp: i64,
+    /// Order kind.
+    pub kind: CreateOrderKind,
+    /// Market token.
+    pub market_token: StringPubkey,
+    /// Reason of the rejection.
+    pub reason: String,
+}
+
+/// Backtest report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestReport {
+    /// Timestamp of the first frame.
+    pub start: Option<i64>,
+    /// Timestamp of the last frame.
+    pub end: Option<i64>,
+    /// Positions in the order they were opened.
+    pub positions: Vec<PositionReport>,
+    /// Rejected orders.
+    pub rejected_orders: Vec<RejectedOrder>,
+}
+
+impl BacktestReport {
+    /// Returns the liquidations.
+    pub fn liquidations(&self) -> impl Iterator<Item = &Liquidation> {
+        self.positions
+            .iter()
+            .filter_map(|position| position.liquidation.as_ref())
+    }
+
+    /// Returns the total realized PnL value.
+    pub fn total_realized_pnl(&self) -> i128 {
+        self.positions
+            .iter()
+            .map(|position| position.realized_pnl)
+            .fold(0, i128::saturating_add)
+    }
+
+    /// Returns the total fees paid.
+    pub fn total_fees(&self) -> FeesPaid {
+        self.positions
+            .iter()
+            .fold(FeesPaid::default(), |acc, position| FeesPaid {
+                order: acc.order.saturating_add(position.fees.order),
+                borrowing: acc.borrowing.saturating_add(position.fees.borrowing),
+                funding: acc.funding.saturating_add(position.fees.funding),
+                liquidation: acc.liquidation.saturating_add(position.fees.liquidation),
+            })
+    }
+
+    /// Returns the total net PnL value.
+    pub fn total_net_pnl(&self) -> i128 {
+        self.positions
+            .iter()
+            .map(PositionReport::net_pnl)
+            .fold(0, i128::saturating_add)
+    }
+}
@@ -19,4 +19,8 @@ pub mod glv_deposit;
 /// GLV withdrawal simulation.
 pub mod glv_withdrawal;
 
-pub use simulator::{SimulationOptions, Simulator, SwapOutput, TokenState};
+/// Backtesting on top of the simulator.
+#[cfg(backtest)]
+pub mod backtest;
+
+pub use simulator::{AdvanceClockReport, SimulationOptions, Simulator, SwapOutput, TokenState};
@@ -16,6 +16,7 @@ use gmsol_programs::{
     gmsol_store::accounts::Position,
     model::{MarketModel, PositionModel, VirtualInventoryModel},
 };
+use gmsol_utils::order::TwapParams;
 use rust_decimal::prelude::Zero;
 use solana_sdk::pubkey::Pubkey;
 use typed_builder::TypedBuilder;
@@ -69,7 +70,148 @@ pub struct UpdatePriceOptions {
     pub limit_swap_slippage: Option<u128>,
 }
 
+/// Options for TWAP impact estimation.
+#[derive(Debug, Default, Clone)]
+pub struct TwapSimulationOptions {
+    /// Options for simulating each execution.
+    pub simulation: SimulationOptions,
+    /// Whether to assume that the markets have recovered to their initial states
+    /// (e.g., by arbitrage) before each slice is executed.
+    ///
+    /// If not set, the slices are executed one after another against the evolving markets.
+    pub assume_recovery: bool,
+}
+
+/// The estimated price impact of a TWAP execution versus a single fill.
+#[derive(Debug, Clone)]
+pub struct TwapImpactEstimate {
+    /// Price impact value of each slice.
+    pub slices: Vec<i128>,
+    /// Total price impact value of the TWAP execution.
+    pub total: i128,
+    /// Price impact value of a single fill.
+    pub single_fill: i128,
+}
+
+impl TwapImpactEstimate {
+    /// Returns the improvement of the TWAP execution over a single fill.
+    ///
+    /// A positive value means that the TWAP execution is expected to take less negative impact.
+    pub fn improvement(&self) -> i128 {
+        self.total.saturating_sub(self.single_fill)
+    }
+}
+
+impl OrderSimulationOutput {
+    /// Returns the total price impact value of the execution, including the swaps.
+    pub fn price_impact_value(&self) -> i128 {
+        let (swaps, impact) = match self {
+            Self::Increase { swaps, report, .. } => {
+                (swaps.as_slice(), *report.execution().price_impact_value())
+            }
+            Self::Decrease { swaps, report, .. } => {
+                (swaps.as_slice(), *report.price_impact_value())
+            }
+            Self::Swap(output) => (output.reports(), 0),
+        };
+        swaps
+            .iter()
+            .map(|report| *report.price_impact())
+            .fold(impact, i128::saturating_add)
+    }
+}
+
 impl OrderSimulation<'_> {
+    /// Estimate the total price impact of executing the order in TWAP slices
+    /// versus filling it at once.
+    ///
+    /// The TWAP params of the order are required, and only market swap and market
+    /// decrease orders are supported. The simulator is not modified.
+    pub fn estimate_twap_impact(
+        self,
+        options: TwapSimulationOptions,
+    ) -> crate::Result<TwapImpactEstimate> {
+        let Self {
+            simulator,
+            kind,
+            params,
+            collateral_or_swap_out_token,
+            pay_token,
+            receive_token,
+            swap_path,
+            position,
+        } = self;
+
+        if !matches!(
+            kind,
+            CreateOrderKind::MarketSwap | CreateOrderKind::MarketDecrease
+        ) {
+            return Err(crate::Error::custom(
+                "[sim] only market swap and market decrease orders can be executed in TWAP slices",
+            ));
+        }
+        let Some(twap) = params.twap.map(TwapParams::from) else {
+            return Err(crate::Error::custom("[sim] TWAP params are required"));
+        };
+        if !twap.is_valid() {
+            return Err(crate::Error::custom(format!(
+                "[sim] invalid number of TWAP slices: {}",
+                twap.slices
+            )));
+        }
+        let slices = twap.slices;
+
+        let simulate = |simulator: &mut Simulator,
+                        params: &CreateOrderParams,
+                        position: Option<&Arc<Position>>| {
+            OrderSimulation {
+                simulator,
+                kind,
+                params,
+                collateral_or_swap_out_token,
+                pay_token,
+                receive_token,
+                swap_path,
+                position,
+            }
+            .execute_with_options(options.simulation.clone())
+        };
+
+        let single_fill = simulate(&mut simulator.clone(), params, position)?.price_impact_value();
+
+        let initial = simulator.clone();
+        let mut current = initial.clone();
+        let mut position = position.cloned();
+        let mut remaining = params.clone();
+        let mut impacts = Vec::with_capacity(slices.into());
+        for executed in 0..slices {
+            let remaining_slices = slices - executed;
+            let mut slice = remaining.clone();
+            slice.size = TwapParams::next_slice(remaining.size, remaining_slices);
+            slice.amount = TwapParams::next_slice(remaining.amount, remaining_slices);
+            slice.min_output = TwapParams::next_slice(remaining.min_output, remaining_slices);
+
+            if options.assume_recovery {
+                current = initial.clone();
+            }
+            let output = simulate(&mut current, &slice, position.as_ref())?;
+            impacts.push(output.price_impact_value());
+            if let OrderSimulationOutput::Decrease { position: p, .. } = output {
+                position = Some(p.position_arc().clone());
+            }
+
+            remaining.size -= slice.size;
+            remaining.amount -= slice.amount;
+            remaining.min_output -= slice.min_output;
+        }
+
+        Ok(TwapImpactEstimate {
+            total: impacts.iter().copied().fold(0, i128::saturating_add),
+            slices: impacts,
+            single_fill,
+        })
+    }
+
     /// Execute the simulation with the given options.
     pub fn execute_with_options(
         self,
@@ -81,7 +223,8 @@ impl OrderSimulation<'_> {
             }
             CreateOrderKind::MarketDecrease
             | CreateOrderKind::LimitDecrease
-            | CreateOrderKind::StopLossDecrease => self.decrease(options),
+            | CreateOrderKind::StopLossDecrease
+            | CreateOrderKind::TrailingStopDecrease => self.decrease(options),
             CreateOrderKind::MarketSwap | CreateOrderKind::LimitSwap => self.swap(options),
         }
     }
@@ -102,8 +245,9 @@ impl OrderSimulation<'_> {
         match self.kind {
             CreateOrderKind::LimitIncrease
             | CreateOrderKind::LimitDecrease
-            | CreateOrderKind::StopLossDecrease => {
-                let Some(trigger_price) = self.params.trigger_price else {
+            | CreateOrderKind::StopLossDecrease
+            | CreateOrderKind::TrailingStopDecrease => {
+                let Some(trigger_price) = self.params.effective_trigger_price() else {
                     return Err(crate::Error::custom("[sim] trigger price is required"));
                 };
                 let token = self.get_market()?.meta.index_token_mint;
@@ -322,8 +466,8 @@ impl OrderSimulation<'_> {
                         }
                     }
                 }
-                CreateOrderKind::StopLossDecrease => {
-                    let Some(trigger_price) = params.trigger_price else {
+                CreateOrderKind::StopLossDecrease | CreateOrderKind::TrailingStopDecrease => {
+                    let Some(trigger_price) = params.effective_trigger_price() else {
                         return Err(crate::Error::custom("[sim] trigger price is required"));
                     };
                     if is_long {
@@ -479,7 +623,7 @@ impl OrderSimulation<'_> {
     }
 }
 
-fn with_vi_models_if_some<T>(
+pub(super) fn with_vi_models_if_some<T>(
     market: &MarketModel,
     position: Option<&Arc<Position>>,
     vi_map: Option<&mut BTreeMap<Pubkey, VirtualInventoryModel>>,
@@ -4,16 +4,23 @@ use std::{
 };
 
 use gmsol_model::{
-    action::swap::SwapReport,
+    action::{
+        distribute_position_impact::DistributePositionImpactReport, swap::SwapReport,
+        update_borrowing_state::UpdateBorrowingReport, update_funding_state::UpdateFundingReport,
+    },
     price::{Price, Prices},
-    MarketAction, SwapMarketMutExt,
+    BorrowingFeeMarketMutExt, MarketAction, PerpMarketMutExt, PositionImpactMarketMutExt,
+    SwapMarketMutExt,
 };
 use gmsol_programs::{
-    gmsol_store::types::{
-        CreateDepositParams, CreateGlvDepositParams, CreateGlvWithdrawalParams, CreateShiftParams,
-        CreateWithdrawalParams, MarketMeta,
+    gmsol_store::{
+        accounts::Position,
+        types::{
+            CreateDepositParams, CreateGlvDepositParams, CreateGlvWithdrawalParams,
+            CreateShiftParams, CreateWithdrawalParams, MarketMeta,
+        },
     },
-    model::{MarketModel, VirtualInventoryModel},
+    model::{MarketModel, PositionModel, VirtualInventoryModel},
 };
 use solana_sdk::pubkey::Pubkey;
 
@@ -21,6 +28,7 @@ use crate::{
     builders::order::{CreateOrderKind, CreateOrderParams},
     glv::{calculator::GlvCalculator, model::GlvModel},
     market::caluclator::MarketCalculator,
+    position::{status::PositionStatus, CalculatePositionStatusOptions, PositionCalculations},
     simulation::order::OrderSimulation,
 };
 
@@ -155,6 +163,11 @@ impl Simulator {
         self.markets.get_mut(market_token)
     }
 
+    /// Insert market model.
+    pub fn insert_market(&mut self, market: MarketModel) -> Option<MarketModel> {
+        self.markets.insert(market.meta.market_token_mint, market)
+    }
+
     /// Get prices for the given token.
     pub fn get_price(&self, token: &Pubkey) -> Option<Price<u128>> {
         Some(*self.tokens.get(token)?.price.as_deref()?)
@@ -396,6 +409,100 @@ impl Simulator {
         self.vis.iter()
     }
 
+    /// Advance the simulated clock by the given duration in seconds.
+    ///
+    /// Only the clock of the given market is advanced, or the clocks of all markets
+    /// if `market_token` is `None`. See [`Simulator::advance_clock_to`] for details.
+    pub fn advance_clock(
+        &mut self,
+        market_token: Option<&Pubkey>,
+        duration_in_seconds: u64,
+    ) -> crate::Result<Vec<AdvanceClockReport>> {
+        let duration = i64::try_from(duration_in_seconds)?;
+        self.advance_clocks_with(market_token, |market| {
+            market
+                .current_timestamp()
+                .checked_add(duration)
+                .ok_or_else(|| crate::Error::custom("[sim] timestamp overflow"))
+        })
+    }
+
+    /// Advance the simulated clock to the given timestamp.
+    ///
+    /// Only the clock of the given market is advanced, or the clocks of all markets
+    /// if `market_token` is `None`. The position impact is distributed and the borrowing
+    /// and funding states are updated for the elapsed time at the configured rates.
+    ///
+    /// The clock of a market starts from the current system time if it has never been
+    /// advanced. When advancing all markets, the markets whose prices are not ready
+    /// are skipped.
+    pub fn advance_clock_to(
+        &mut self,
+        market_token: Option<&Pubkey>,
+        timestamp: i64,
+    ) -> crate::Result<Vec<AdvanceClockReport>> {
+        self.advance_clocks_with(market_token, |_| Ok(timestamp))
+    }
+
+    fn advance_clocks_with(
+        &mut self,
+        market_token: Option<&Pubkey>,
+        mut timestamp: impl FnMut(&MarketModel) -> crate::Result<i64>,
+    ) -> crate::Result<Vec<AdvanceClockReport>> {
+        let market_tokens = match market_token {
+            Some(market_token) => vec![*market_token],
+            None => {
+                let mut market_tokens = self
+                    .markets
+                    .iter()
+                    .filter(|(_, market)| self.get_prices(&market.meta).is_some())
+                    .map(|(market_token, _)| *market_token)
+                    .collect::<Vec<_>>();
+                market_tokens.sort();
+                market_tokens
+            }
+        };
+
+        let mut reports = Vec::with_capacity(market_tokens.len());
+        for market_token in market_tokens {
+            let prices = self.get_prices_for_market(&market_token)?;
+            let market = self
+                .get_market_mut(&market_token)
+                .expect("market must exist");
+            let timestamp = timestamp(market)?;
+            market.set_clock_override(Some(timestamp));
+            let position_impact = market.distribute_position_impact()?.execute()?;
+            let borrowing = market.update_borrowing(&prices)?.execute()?;
+            let funding = market.update_funding(&prices)?.execute()?;
+            reports.push(AdvanceClockReport {
+                market_token,
+                timestamp,
+                position_impact,
+                borrowing,
+                funding,
+            });
+        }
+        Ok(reports)
+    }
+
+    /// Calculate the status of the given position against the current state of the simulator,
+    /// including the pending borrowing and funding fees and the liquidation price.
+    pub fn position_status(
+        &self,
+        position: &Arc<Position>,
+        options: CalculatePositionStatusOptions,
+    ) -> crate::Result<PositionStatus> {
+        let (market, prices) = self.get_market_with_prices(&position.market_token)?;
+        let mut market = market.clone();
+        let mut vis = options
+            .include_virtual_inventory_impact
+            .then(|| self.vis.clone());
+        market.with_vis_if(vis.as_mut(), |market| {
+            PositionModel::new(market.clone(), position.clone())?
+                .status_with_options(&prices, options)
+        })
+    }
+
     /// Create a builder for order simulation.
     pub fn simulate_order<'a>(
         &'a mut self,
@@ -529,6 +636,21 @@ impl SwapOutput {
     }
 }
 
+/// Report of advancing the clock of a market.
+#[derive(Debug)]
+pub struct AdvanceClockReport {
+    /// Market token.
+    pub market_token: Pubkey,
+    /// The timestamp the clock has been advanced to.
+    pub timestamp: i64,
+    /// Position impact distribution report.
+    pub position_impact: DistributePositionImpactReport<u128>,
+    /// Borrowing state update report.
+    pub borrowing: UpdateBorrowingReport<u128>,
+    /// Funding state update report.
+    pub funding: UpdateFundingReport<u128, i128>,
+}
+
 impl MarketCalculator for Simulator {
     fn get_market_model(&self, market_token: &Pubkey) -> Option<&MarketModel> {
         self.get_market(market_token)
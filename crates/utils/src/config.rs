@@ -50,6 +50,8 @@ pub enum DomainDisabledFlag {
     GlvWithdrawal = 13,
     /// GLV shift.
     GlvShift = 14,
+    /// Trailing-stop Decrease Order.
+    TrailingStopDecrease = 15,
 }
 
 impl TryFrom<OrderKind> for DomainDisabledFlag {
@@ -66,6 +68,7 @@ impl TryFrom<OrderKind> for DomainDisabledFlag {
             OrderKind::LimitIncrease => Ok(Self::LimitIncrease),
             OrderKind::LimitDecrease => Ok(Self::LimitDecrease),
             OrderKind::StopLossDecrease => Ok(Self::StopLossDecrease),
+            OrderKind::TrailingStopDecrease => Ok(Self::TrailingStopDecrease),
         }
     }
 }
@@ -105,3 +105,20 @@ pub enum InstructionFlag {
     Approved,
     // CHECK: cannot have more than `MAX_IX_FLAGS` flags.
 }
+
+/// Max number of proposal flags.
+pub const MAX_PROPOSAL_FLAGS: usize = 8;
+
+/// Max number of instructions in a proposal.
+pub const MAX_PROPOSAL_INSTRUCTIONS: usize = 16;
+
+/// Flags of Proposal.
+#[derive(num_enum::IntoPrimitive)]
+#[repr(u8)]
+pub enum ProposalFlag {
+    /// Approved.
+    Approved,
+    /// Executed.
+    Executed,
+    // CHECK: cannot have more than `MAX_PROPOSAL_FLAGS` flags.
+}
@@ -57,6 +57,9 @@ pub mod role;
 /// Definitions related to users.
 pub mod user;
 
+/// Definitions related to risk oracle.
+pub mod risk_oracle;
+
 /// Definitions related to instructions.
 #[cfg(feature = "instruction")]
 pub mod instruction;
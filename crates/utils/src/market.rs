@@ -14,6 +14,9 @@ pub const MAX_MARKET_CONFIG_FACTORS: usize = 128;
 /// Max number of market flags.
 pub const MAX_MARKET_FLAGS: usize = 8;
 
+/// Max number of scheduled market config updates of a market.
+pub const MAX_MARKET_CONFIG_SCHEDULES: usize = 8;
+
 /// Max number of virtual inventory flags.
 pub const MAX_VIRTUAL_INVENTORY_FLAGS: usize = 8;
 
@@ -359,6 +362,31 @@ impl std::fmt::Display for MarketConfigFactor {
     }
 }
 
+/// Returns the value of a market config factor ramped linearly from `from` to `to`
+/// over `duration` seconds starting at `start_at`.
+///
+/// The value is `from` before `start_at` and `to` once the ramp has ended,
+/// so a zero `duration` is a step change at `start_at`.
+pub fn ramp_config_factor(from: u128, to: u128, start_at: i64, duration: u32, now: i64) -> u128 {
+    let elapsed = now.saturating_sub(start_at);
+    if elapsed < 0 {
+        return from;
+    }
+    let duration = u128::from(duration);
+    let elapsed = elapsed.unsigned_abs() as u128;
+    if elapsed >= duration {
+        return to;
+    }
+    let delta = from.abs_diff(to);
+    // `elapsed < duration <= u32::MAX`, so neither product can overflow.
+    let step = (delta / duration) * elapsed + (delta % duration) * elapsed / duration;
+    if to >= from {
+        from + step
+    } else {
+        from - step
+    }
+}
+
 /// Market Flags.
 #[derive(num_enum::IntoPrimitive)]
 #[repr(u8)]
@@ -376,9 +404,47 @@ pub enum MarketFlag {
     GTEnabled,
     /// Indicates whether the market is closed.
     Closed,
+    /// Is reduce-only.
+    ReduceOnly,
+    /// Is close-only.
+    CloseOnly,
     // CHECK: cannot have more than `MAX_MARKET_FLAGS` flags.
 }
 
+/// Trading mode of a market.
+#[derive(
+    AnchorSerialize,
+    AnchorDeserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    InitSpace,
+    strum::EnumString,
+    strum::Display,
+    num_enum::IntoPrimitive,
+    num_enum::TryFromPrimitive,
+)]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "clap", clap(rename_all = "snake_case"))]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum MarketTradingMode {
+    /// All orders are allowed.
+    #[default]
+    Normal,
+    /// Only decrease orders, liquidations and auto-deleveraging are allowed.
+    ReduceOnly,
+    /// Only orders closing the whole position, liquidations and auto-deleveraging
+    /// are allowed.
+    CloseOnly,
+}
+
 /// Virtual Inventory Flags.
 #[derive(num_enum::IntoPrimitive)]
 #[repr(u8)]
@@ -387,3 +453,22 @@ pub enum VirtualInventoryFlag {
     Disabled,
     // CHECK: cannot have more than `MAX_VIRTUAL_INVENTORY_FLAGS` flags.
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp_config_factor() {
+        assert_eq!(ramp_config_factor(100, 200, 10, 100, 0), 100);
+        assert_eq!(ramp_config_factor(100, 200, 10, 100, 10), 100);
+        assert_eq!(ramp_config_factor(100, 200, 10, 100, 35), 125);
+        assert_eq!(ramp_config_factor(100, 200, 10, 100, 110), 200);
+        assert_eq!(ramp_config_factor(200, 100, 10, 100, 35), 175);
+        assert_eq!(ramp_config_factor(200, 100, 10, 0, 10), 100);
+        assert_eq!(
+            ramp_config_factor(0, u128::MAX, 0, u32::MAX, i64::from(u32::MAX) - 1),
+            u128::MAX - u128::MAX / u128::from(u32::MAX),
+        );
+    }
+}
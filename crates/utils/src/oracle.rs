@@ -52,6 +52,62 @@ pub enum PriceProviderKind {
     Switchboard = 3,
 }
 
+/// Composite price mode of a token.
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    TryFromPrimitive,
+    IntoPrimitive,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::EnumString,
+    strum::Display,
+)]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "enum-iter", derive(strum::EnumIter))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "clap", clap(rename_all = "snake_case"))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[non_exhaustive]
+pub enum CompositePriceMode {
+    /// The token is priced by the expected provider only.
+    #[default]
+    Disabled = 0,
+    /// The token is priced by the median of all its price sources.
+    Median = 1,
+    /// The token is priced by the expected provider, falling back to the
+    /// other price sources in provider order when its price is stale or its
+    /// market is closed.
+    PrimaryWithFallback = 2,
+}
+
+/// Returns the median of the given prices.
+///
+/// The min and max prices are taken independently. For an even number of
+/// prices, the lower of the two middle min prices and the higher of the two
+/// middle max prices are used, so the spread is never narrowed.
+///
+/// Returns `None` if `prices` is empty.
+pub fn median_price(prices: &[Price]) -> Option<Price> {
+    if prices.is_empty() {
+        return None;
+    }
+    let mut mins = prices.iter().map(|price| price.min).collect::<Vec<_>>();
+    let mut maxs = prices.iter().map(|price| price.max).collect::<Vec<_>>();
+    mins.sort_by_key(Decimal::to_unit_price);
+    maxs.sort_by_key(Decimal::to_unit_price);
+    let len = prices.len();
+    Some(Price {
+        min: mins[(len - 1) / 2],
+        max: maxs[len / 2],
+    })
+}
+
 /// Convert pyth price value with confidence to [`Price`].
 pub fn pyth_price_with_confidence_to_price(
     price: i64,
@@ -117,3 +173,51 @@ pub enum OracleFlag {
     Cleared,
     // CHECK: should have no more than `MAX_ORACLE_FLAGS` of flags.
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(min: u32, max: u32) -> Price {
+        Price {
+            min: Decimal {
+                value: min,
+                decimal_multiplier: 10,
+            },
+            max: Decimal {
+                value: max,
+                decimal_multiplier: 10,
+            },
+        }
+    }
+
+    fn values(price: &Price) -> (u32, u32) {
+        (price.min.value, price.max.value)
+    }
+
+    #[test]
+    fn test_median_price() {
+        assert!(median_price(&[]).is_none());
+        assert_eq!(values(&median_price(&[price(99, 101)]).unwrap()), (99, 101));
+        assert_eq!(
+            values(&median_price(&[price(99, 101), price(100, 104), price(90, 92)]).unwrap()),
+            (99, 101)
+        );
+        assert_eq!(
+            values(&median_price(&[price(100, 104), price(98, 99)]).unwrap()),
+            (98, 104)
+        );
+        assert_eq!(
+            values(
+                &median_price(&[
+                    price(97, 98),
+                    price(99, 103),
+                    price(100, 101),
+                    price(95, 110)
+                ])
+                .unwrap()
+            ),
+            (97, 103)
+        );
+    }
+}
@@ -44,6 +44,11 @@ pub enum OrderKind {
     LimitDecrease,
     /// Stop-Loss Decrease.
     StopLossDecrease,
+    /// Trailing-Stop Decrease.
+    ///
+    /// The trigger price follows the best price seen since creation by the configured
+    /// [`TrailingDistance`].
+    TrailingStopDecrease,
 }
 
 impl OrderKind {
@@ -74,13 +79,94 @@ impl OrderKind {
                 | Self::Liquidation
                 | Self::AutoDeleveraging
                 | Self::StopLossDecrease
+                | Self::TrailingStopDecrease
         )
     }
 
+    /// Is trailing-stop order.
+    pub fn is_trailing_stop(&self) -> bool {
+        matches!(self, Self::TrailingStopDecrease)
+    }
+
     /// Is market decrease.
     pub fn is_market_decrease(&self) -> bool {
         matches!(self, Self::MarketDecrease)
     }
+
+    /// Returns whether the order can be linked with other orders (one-cancels-other).
+    pub fn is_linkable(&self) -> bool {
+        matches!(
+            self,
+            Self::LimitDecrease | Self::StopLossDecrease | Self::TrailingStopDecrease
+        )
+    }
+
+    /// Returns whether the order can be executed in TWAP slices.
+    pub fn is_twap_supported(&self) -> bool {
+        matches!(self, Self::MarketSwap | Self::MarketDecrease)
+    }
+}
+
+/// Trailing distance of a trailing-stop order.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TrailingDistance {
+    /// A fixed distance from the best price (in unit price).
+    Absolute(u128),
+    /// A distance expressed as a factor of the best price.
+    Factor(u128),
+}
+
+impl TrailingDistance {
+    /// Get the raw value of the distance.
+    pub fn value(&self) -> u128 {
+        match self {
+            Self::Absolute(value) | Self::Factor(value) => *value,
+        }
+    }
+
+    /// Returns whether the distance is a factor.
+    pub fn is_factor(&self) -> bool {
+        matches!(self, Self::Factor(_))
+    }
+}
+
+/// TWAP (time-weighted average price) execution params.
+///
+/// A TWAP order splits its total size into `slices` slices, each of which is
+/// executed as a normal market execution at least `interval` seconds apart.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TwapParams {
+    /// The total number of slices.
+    pub slices: u8,
+    /// The minimum interval between two slices in seconds.
+    pub interval: u32,
+}
+
+impl TwapParams {
+    /// Min number of slices.
+    pub const MIN_SLICES: u8 = 2;
+    /// Max number of slices.
+    pub const MAX_SLICES: u8 = 64;
+
+    /// Returns whether the params are valid.
+    pub fn is_valid(&self) -> bool {
+        (Self::MIN_SLICES..=Self::MAX_SLICES).contains(&self.slices)
+    }
+
+    /// Returns the amount of the next slice.
+    ///
+    /// The remaining amount is split evenly into the remaining slices,
+    /// and the last slice takes all of the remaining amount.
+    pub fn next_slice(remaining: u128, remaining_slices: u8) -> u128 {
+        if remaining_slices <= 1 {
+            remaining
+        } else {
+            remaining / u128::from(remaining_slices)
+        }
+    }
 }
 
 /// Order side.
@@ -187,5 +273,7 @@ crate::flags!(TradeFlag, 8, u8);
 pub enum OrderFlag {
     /// Whether to keep position account when empty.
     ShouldKeepPositionAccount,
+    /// Whether the trailing distance is a factor of the best price.
+    IsTrailingDistanceFactor,
     // CHECK: should have no more than `MAX_ORDER_FLAGS` of flags.
 }
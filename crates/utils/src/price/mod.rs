@@ -120,6 +120,8 @@ pub enum OraclePriceFlag {
     Synthetic,
     /// Is Market Opened.
     Open,
+    /// The price circuit breaker of the token has been tripped.
+    CircuitBreakerTripped,
     // CHECK: should have no more than `MAX_ORACLE_PRICE_FLAG` of flags.
 }
 
@@ -0,0 +1,343 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::market::MarketConfigKey;
+
+/// Max number of risk oracle parameters.
+pub const MAX_RISK_ORACLE_PARAMETERS: usize = 8;
+
+/// Max length of the parameter name in a signed recommendation.
+pub const PARAMETER_NAME_LEN: usize = 32;
+
+/// Max length of the protocol name in a signed recommendation.
+pub const PROTOCOL_LEN: usize = 16;
+
+/// A risk oracle parameter.
+///
+/// A recommendation of a parameter updates all its keys at once.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RiskOracleParameter {
+    /// Parameter name.
+    pub name: &'static str,
+    /// Recommendation keys and the market config keys they map to,
+    /// sorted by recommendation key.
+    pub keys: &'static [(&'static str, MarketConfigKey)],
+}
+
+/// Supported risk oracle parameters.
+///
+/// The index of a parameter is part of the on-chain state, so new parameters
+/// must be appended.
+pub const RISK_ORACLE_PARAMETERS: &[RiskOracleParameter] = &[
+    RiskOracleParameter {
+        name: "oiCaps",
+        keys: &[
+            (
+                "oiCaps/maxOpenInterestForLongs/v1",
+                MarketConfigKey::MaxOpenInterestForLong,
+            ),
+            (
+                "oiCaps/maxOpenInterestForShorts/v1",
+                MarketConfigKey::MaxOpenInterestForShort,
+            ),
+        ],
+    },
+    RiskOracleParameter {
+        name: "priceImpact",
+        keys: &[
+            (
+                "priceImpact/negativePositionImpactFactor/v1",
+                MarketConfigKey::PositionImpactNegativeFactor,
+            ),
+            (
+                "priceImpact/positionImpactExponentFactor/v1",
+                MarketConfigKey::PositionImpactExponent,
+            ),
+            (
+                "priceImpact/positivePositionImpactFactor/v1",
+                MarketConfigKey::PositionImpactPositiveFactor,
+            ),
+        ],
+    },
+    RiskOracleParameter {
+        name: "funding",
+        keys: &[
+            (
+                "funding/fundingDecreaseFactorPerSecond/v1",
+                MarketConfigKey::FundingFeeDecreaseFactorPerSecond,
+            ),
+            (
+                "funding/fundingExponentFactor/v1",
+                MarketConfigKey::FundingFeeExponent,
+            ),
+            (
+                "funding/fundingFactor/v1",
+                MarketConfigKey::FundingFeeFactor,
+            ),
+            (
+                "funding/fundingIncreaseFactorPerSecond/v1",
+                MarketConfigKey::FundingFeeIncreaseFactorPerSecond,
+            ),
+            (
+                "funding/maxFundingFactorPerSecond/v1",
+                MarketConfigKey::FundingFeeMaxFactorPerSecond,
+            ),
+            (
+                "funding/minFundingFactorPerSecond/v1",
+                MarketConfigKey::FundingFeeMinFactorPerSecond,
+            ),
+            (
+                "funding/thresholdForDecreaseFunding/v1",
+                MarketConfigKey::FundingFeeThresholdForDecreaseFunding,
+            ),
+            (
+                "funding/thresholdForStableFunding/v1",
+                MarketConfigKey::FundingFeeThresholdForStableFunding,
+            ),
+        ],
+    },
+    RiskOracleParameter {
+        name: "borrowing",
+        keys: &[
+            (
+                "borrowing/aboveOptimalUsageBorrowingFactorForLongs/v1",
+                MarketConfigKey::BorrowingFeeAboveOptimalUsageFactorForLong,
+            ),
+            (
+                "borrowing/aboveOptimalUsageBorrowingFactorForShorts/v1",
+                MarketConfigKey::BorrowingFeeAboveOptimalUsageFactorForShort,
+            ),
+            (
+                "borrowing/baseBorrowingFactorForLongs/v1",
+                MarketConfigKey::BorrowingFeeBaseFactorForLong,
+            ),
+            (
+                "borrowing/baseBorrowingFactorForShorts/v1",
+                MarketConfigKey::BorrowingFeeBaseFactorForShort,
+            ),
+            (
+                "borrowing/borrowingExponentFactorForLongs/v1",
+                MarketConfigKey::BorrowingFeeExponentForLong,
+            ),
+            (
+                "borrowing/borrowingExponentFactorForShorts/v1",
+                MarketConfigKey::BorrowingFeeExponentForShort,
+            ),
+            (
+                "borrowing/borrowingFactorForLongs/v1",
+                MarketConfigKey::BorrowingFeeFactorForLong,
+            ),
+            (
+                "borrowing/borrowingFactorForShorts/v1",
+                MarketConfigKey::BorrowingFeeFactorForShort,
+            ),
+            (
+                "borrowing/optimalUsageFactorForLongs/v1",
+                MarketConfigKey::BorrowingFeeOptimalUsageFactorForLong,
+            ),
+            (
+                "borrowing/optimalUsageFactorForShorts/v1",
+                MarketConfigKey::BorrowingFeeOptimalUsageFactorForShort,
+            ),
+        ],
+    },
+    RiskOracleParameter {
+        name: "reserveFactor",
+        keys: &[
+            (
+                "reserveFactor/openInterestReserveFactor/v1",
+                MarketConfigKey::OpenInterestReserveFactor,
+            ),
+            (
+                "reserveFactor/reserveFactor/v1",
+                MarketConfigKey::ReserveFactor,
+            ),
+        ],
+    },
+    RiskOracleParameter {
+        name: "maxPnlFactor",
+        keys: &[
+            (
+                "maxPnlFactor/maxPnlFactorForAdlLongs/v1",
+                MarketConfigKey::MaxPnlFactorForLongAdl,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForAdlShorts/v1",
+                MarketConfigKey::MaxPnlFactorForShortAdl,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForDepositsLongs/v1",
+                MarketConfigKey::MaxPnlFactorForLongDeposit,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForDepositsShorts/v1",
+                MarketConfigKey::MaxPnlFactorForShortDeposit,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForTradersLongs/v1",
+                MarketConfigKey::MaxPnlFactorForLongTrader,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForTradersShorts/v1",
+                MarketConfigKey::MaxPnlFactorForShortTrader,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForWithdrawalsLongs/v1",
+                MarketConfigKey::MaxPnlFactorForLongWithdrawal,
+            ),
+            (
+                "maxPnlFactor/maxPnlFactorForWithdrawalsShorts/v1",
+                MarketConfigKey::MaxPnlFactorForShortWithdrawal,
+            ),
+            (
+                "maxPnlFactor/minPnlFactorAfterAdlLongs/v1",
+                MarketConfigKey::MinPnlFactorAfterLongAdl,
+            ),
+            (
+                "maxPnlFactor/minPnlFactorAfterAdlShorts/v1",
+                MarketConfigKey::MinPnlFactorAfterShortAdl,
+            ),
+        ],
+    },
+    RiskOracleParameter {
+        name: "swapImpact",
+        keys: &[
+            (
+                "swapImpact/negativeSwapImpactFactor/v1",
+                MarketConfigKey::SwapImpactNegativeFactor,
+            ),
+            (
+                "swapImpact/positiveSwapImpactFactor/v1",
+                MarketConfigKey::SwapImpactPositiveFactor,
+            ),
+            (
+                "swapImpact/swapImpactExponentFactor/v1",
+                MarketConfigKey::SwapImpactExponent,
+            ),
+        ],
+    },
+];
+
+const _: () = assert!(RISK_ORACLE_PARAMETERS.len() <= MAX_RISK_ORACLE_PARAMETERS);
+
+/// Find the risk oracle parameter with the given name and its index.
+pub fn find_parameter(name: &str) -> Option<(usize, &'static RiskOracleParameter)> {
+    RISK_ORACLE_PARAMETERS
+        .iter()
+        .enumerate()
+        .find(|(_, parameter)| parameter.name == name)
+}
+
+/// Map a recommendation key of the given parameter to [`MarketConfigKey`].
+pub fn map_key(parameter_name: &str, key: &str) -> Option<MarketConfigKey> {
+    let (_, parameter) = find_parameter(parameter_name)?;
+    parameter
+        .keys
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, key)| *key)
+}
+
+/// Returns whether the values of the given key must be integers.
+pub fn is_exponent(key: MarketConfigKey) -> bool {
+    matches!(
+        key,
+        MarketConfigKey::PositionImpactExponent
+            | MarketConfigKey::SwapImpactExponent
+            | MarketConfigKey::FundingFeeExponent
+            | MarketConfigKey::BorrowingFeeExponentForLong
+            | MarketConfigKey::BorrowingFeeExponentForShort
+    )
+}
+
+/// Convert a recommended `value` with `decimals` to a config value with `target_decimals`.
+///
+/// Exponents are rounded half to even, and the extra decimals of other keys
+/// are truncated. Returns `None` on overflow.
+pub fn scale_value(
+    key: MarketConfigKey,
+    value: u64,
+    decimals: u8,
+    target_decimals: u8,
+) -> Option<u128> {
+    let value = u128::from(value);
+    if is_exponent(key) {
+        let unit = 10u128.checked_pow(decimals.into())?;
+        let (mut integer, rem) = (value / unit, value % unit);
+        let twice_rem = rem * 2;
+        if twice_rem > unit || (twice_rem == unit && integer % 2 == 1) {
+            integer += 1;
+        }
+        integer.checked_mul(10u128.checked_pow(target_decimals.into())?)
+    } else if decimals <= target_decimals {
+        value.checked_mul(10u128.checked_pow((target_decimals - decimals).into())?)
+    } else {
+        Some(value / 10u128.checked_pow((decimals - target_decimals).into())?)
+    }
+}
+
+fn pad<const N: usize>(s: &str) -> [u8; N] {
+    let mut out = [0u8; N];
+    let bytes = s.as_bytes();
+    let len = bytes.len().min(N);
+    out[..len].copy_from_slice(&bytes[..len]);
+    out
+}
+
+/// Build the message signed by the risk oracle.
+///
+/// `values` are the `(value, decimals)` pairs of the keys of the parameter,
+/// in the order of [`RiskOracleParameter::keys`].
+pub fn recommendation_message(
+    parameter_name: &str,
+    market_token: &Pubkey,
+    values: impl IntoIterator<Item = (u64, u8)>,
+    timestamp: u64,
+    protocol: &str,
+    reference_id: &[u8; 16],
+) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(256);
+    msg.extend_from_slice(&pad::<PARAMETER_NAME_LEN>(parameter_name));
+    msg.extend_from_slice(market_token.as_ref());
+    for (value, decimals) in values {
+        msg.extend_from_slice(&value.to_le_bytes());
+        msg.push(decimals);
+    }
+    msg.extend_from_slice(&timestamp.to_le_bytes());
+    msg.extend_from_slice(&pad::<PROTOCOL_LEN>(protocol));
+    msg.extend_from_slice(reference_id);
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_keys_are_sorted() {
+        for parameter in RISK_ORACLE_PARAMETERS {
+            assert!(
+                parameter.keys.windows(2).all(|w| w[0].0 < w[1].0),
+                "keys of {} are not sorted",
+                parameter.name
+            );
+            assert!(parameter
+                .keys
+                .iter()
+                .all(|(key, _)| key.starts_with(parameter.name)));
+        }
+    }
+
+    #[test]
+    fn test_scale_value() {
+        let key = MarketConfigKey::ReserveFactor;
+        assert_eq!(scale_value(key, 15, 1, 20), Some(15 * 10u128.pow(19)));
+        assert_eq!(scale_value(key, 123_456, 25, 20), Some(1));
+
+        let key = MarketConfigKey::PositionImpactExponent;
+        assert_eq!(scale_value(key, 25, 1, 20), Some(2 * 10u128.pow(20)));
+        assert_eq!(scale_value(key, 35, 1, 20), Some(4 * 10u128.pow(20)));
+        assert_eq!(scale_value(key, 26, 1, 20), Some(3 * 10u128.pow(20)));
+        assert_eq!(scale_value(key, 2, 0, 20), Some(2 * 10u128.pow(20)));
+        assert_eq!(scale_value(key, u64::MAX, 0, 38), None);
+    }
+}
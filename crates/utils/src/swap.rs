@@ -115,13 +115,21 @@ impl SwapActionParams {
     }
 
     /// Convert to token records.
+    ///
+    /// Tokens priced from a composite of providers get a record for each price source.
     pub fn to_token_records<'a>(
         &'a self,
         map: &'a impl TokenMapAccess,
     ) -> impl Iterator<Item = TokenConfigResult<TokenRecord>> + 'a {
-        self.tokens().iter().map(|token| {
-            let config = map.get(token).ok_or(TokenConfigError::NotFound)?;
-            TokenRecord::from_config(*token, config)
+        self.tokens().iter().flat_map(|token| {
+            let records = map
+                .get(token)
+                .ok_or(TokenConfigError::NotFound)
+                .and_then(|config| TokenRecord::from_price_sources(*token, config));
+            match records {
+                Ok(records) => records.into_iter().map(Ok).collect::<Vec<_>>(),
+                Err(err) => vec![Err(err)],
+            }
         })
     }
 
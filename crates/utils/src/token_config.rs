@@ -6,8 +6,11 @@ use crate::{
     chunk_by::chunk_by,
     fixed_str::{bytes_to_fixed_str, FixedStrError},
     market::HasMarketMeta,
-    oracle::PriceProviderKind,
-    price::market_status::{MarketStatusFlag, MarketStatusFlagContainer},
+    oracle::{CompositePriceMode, PriceProviderKind},
+    price::{
+        market_status::{MarketStatusFlag, MarketStatusFlagContainer},
+        Decimal,
+    },
     pubkey::DEFAULT_PUBKEY,
     swap::HasSwapParams,
 };
@@ -50,6 +53,15 @@ pub enum TokenConfigError {
     /// Max deviation factor too small.
     #[error("max deviation factor too small")]
     MaxDeviationFactorTooSmall,
+    /// Invalid composite price mode.
+    #[error("invalid composite price mode")]
+    InvalidCompositePriceMode,
+    /// Invalid composite price sources.
+    #[error("invalid composite price sources: {0}")]
+    InvalidCompositePriceSources(&'static str),
+    /// Invalid price circuit breaker.
+    #[error("invalid price circuit breaker: {0}")]
+    InvalidPriceCircuitBreaker(&'static str),
 }
 
 pub(crate) type TokenConfigResult<T> = std::result::Result<T, TokenConfigError>;
@@ -91,8 +103,83 @@ pub struct TokenConfig {
     pub feeds: [FeedConfig; MAX_FEEDS],
     /// Heartbeat duration.
     pub heartbeat_duration: u32,
+    /// Composite price mode.
+    pub composite_mode: u8,
+    /// Bitmap of the price providers used as composite price sources.
+    pub composite_sources: u8,
     #[cfg_attr(feature = "debug", debug(skip))]
-    reserved: [u8; 32],
+    padding_0: [u8; 2],
+    /// The maximum allowed disagreement ratio between composite price sources.
+    /// A value of `0` means no restriction is applied.
+    max_disagreement_ratio: u32,
+    /// The maximum allowed price move ratio within a circuit breaker window.
+    /// A value of `0` means the price circuit breaker is disabled.
+    max_price_move_ratio: u32,
+    /// The price circuit breaker window in seconds.
+    pub price_move_window: u32,
+    /// The price circuit breaker state.
+    circuit_breaker: PriceCircuitBreakerState,
+}
+
+/// Price circuit breaker state of a token.
+///
+/// Tracks the reference price at the start of the current window,
+/// and whether the breaker has been tripped.
+#[zero_copy]
+#[derive(PartialEq, Eq, Default)]
+#[cfg_attr(feature = "debug", derive(derive_more::Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PriceCircuitBreakerState {
+    ref_price: u32,
+    ref_decimal_multiplier: u8,
+    is_tripped: u8,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_0: [u8; 2],
+    /// Start time of the current window.
+    /// A value of `0` means no window has been started.
+    pub window_start: i64,
+}
+
+impl PriceCircuitBreakerState {
+    /// Returns whether a window has been started.
+    pub fn is_started(&self) -> bool {
+        self.window_start != 0
+    }
+
+    /// Returns whether the breaker has been tripped.
+    pub fn is_tripped(&self) -> bool {
+        self.is_tripped != 0
+    }
+
+    /// Get the reference price of the current window.
+    pub fn ref_price(&self) -> Decimal {
+        Decimal {
+            value: self.ref_price,
+            decimal_multiplier: self.ref_decimal_multiplier,
+        }
+    }
+
+    /// Start a new window with the given reference price.
+    pub fn start_window(&mut self, ref_price: Decimal, now: i64) {
+        self.ref_price = ref_price.value;
+        self.ref_decimal_multiplier = ref_price.decimal_multiplier;
+        self.is_tripped = 0;
+        self.window_start = now;
+    }
+
+    /// Trip the breaker.
+    pub fn trip(&mut self) {
+        self.is_tripped = 1;
+    }
+
+    /// Reset the breaker.
+    ///
+    /// Returns whether the breaker was tripped.
+    pub fn reset(&mut self) -> bool {
+        let was_tripped = self.is_tripped();
+        *self = Self::default();
+        was_tripped
+    }
 }
 
 #[cfg(feature = "display")]
@@ -111,6 +198,28 @@ impl std::fmt::Display for TokenConfig {
                 .map(|kind| kind.to_string())
                 .unwrap_or("*unknown*".to_string())
         )?;
+        if self.is_composite() {
+            writeln!(
+                f,
+                "Composite Price: {} of [{}]",
+                self.composite_mode()
+                    .map(|mode| mode.to_string())
+                    .unwrap_or("*unknown*".to_string()),
+                self.price_sources()
+                    .map(|sources| sources
+                        .iter()
+                        .map(|kind| kind.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "))
+                    .unwrap_or("*unknown*".to_string())
+            )?;
+        }
+        if let Some((factor, window)) = self.price_circuit_breaker() {
+            writeln!(
+                f,
+                "Price Circuit Breaker: max move factor {factor} within {window}s"
+            )?;
+        }
         Ok(())
     }
 }
@@ -184,6 +293,181 @@ impl TokenConfig {
         self.get_feed(&self.expected_provider()?)
     }
 
+    /// Get composite price mode.
+    pub fn composite_mode(&self) -> TokenConfigResult<CompositePriceMode> {
+        CompositePriceMode::try_from(self.composite_mode)
+            .map_err(|_| TokenConfigError::InvalidCompositePriceMode)
+    }
+
+    /// Returns whether the token is priced from a composite of providers.
+    pub fn is_composite(&self) -> bool {
+        self.composite_mode != u8::from(CompositePriceMode::Disabled)
+    }
+
+    /// Get the price sources of the token.
+    ///
+    /// The expected provider always comes first, followed by the other
+    /// composite price sources in provider order.
+    pub fn price_sources(&self) -> TokenConfigResult<Vec<PriceProviderKind>> {
+        let expected = self.expected_provider()?;
+        let mut sources = vec![expected];
+        if self.is_composite() {
+            for index in 0..MAX_FEEDS {
+                if self.composite_sources & (1 << index) == 0 {
+                    continue;
+                }
+                let kind = PriceProviderKind::try_from(index as u8)
+                    .map_err(|_| TokenConfigError::InvalidProviderIndex)?;
+                if kind != expected {
+                    sources.push(kind);
+                }
+            }
+        }
+        Ok(sources)
+    }
+
+    /// Returns whether the given provider is a price source of the token.
+    pub fn is_price_source(&self, provider: &PriceProviderKind) -> TokenConfigResult<bool> {
+        if self.is_composite() {
+            Ok(self.composite_sources & (1 << (*provider as u8)) != 0)
+        } else {
+            Ok(self.expected_provider()? == *provider)
+        }
+    }
+
+    /// Get the maximum allowed disagreement factor between composite price sources.
+    pub fn max_disagreement_factor(&self) -> Option<u128> {
+        let ratio = self.max_disagreement_ratio;
+        if ratio == 0 {
+            None
+        } else {
+            Some(u128::from(ratio) * FeedConfig::RATIO_MULTIPLIER)
+        }
+    }
+
+    /// Set composite price config.
+    ///
+    /// The `sources` must contain the expected provider and at least one
+    /// other provider with an initialized feed config, unless `mode` is
+    /// [`CompositePriceMode::Disabled`], in which case the composite
+    /// price config is cleared.
+    pub fn set_composite_price(
+        &mut self,
+        mode: CompositePriceMode,
+        sources: &[PriceProviderKind],
+        max_disagreement_factor: Option<u128>,
+    ) -> TokenConfigResult<()> {
+        if mode == CompositePriceMode::Disabled {
+            self.composite_mode = mode.into();
+            self.composite_sources = 0;
+            self.max_disagreement_ratio = 0;
+            return Ok(());
+        }
+
+        let mut bitmap = 0u8;
+        for source in sources {
+            self.get_feed_config(source)?;
+            let bit = 1 << (*source as u8);
+            if bitmap & bit != 0 {
+                return Err(TokenConfigError::InvalidCompositePriceSources(
+                    "duplicated source",
+                ));
+            }
+            bitmap |= bit;
+        }
+        if bitmap.count_ones() < 2 {
+            return Err(TokenConfigError::InvalidCompositePriceSources(
+                "requires at least two sources",
+            ));
+        }
+        if bitmap & (1 << self.expected_provider) == 0 {
+            return Err(TokenConfigError::InvalidCompositePriceSources(
+                "the expected provider must be a source",
+            ));
+        }
+
+        let ratio = match max_disagreement_factor {
+            Some(factor) => {
+                let ratio = (factor / FeedConfig::RATIO_MULTIPLIER)
+                    .try_into()
+                    .map_err(|_| TokenConfigError::ExceedMaxRatio)?;
+                if ratio == 0 {
+                    return Err(TokenConfigError::MaxDeviationFactorTooSmall);
+                }
+                ratio
+            }
+            None => 0,
+        };
+
+        self.composite_mode = mode.into();
+        self.composite_sources = bitmap;
+        self.max_disagreement_ratio = ratio;
+        Ok(())
+    }
+
+    /// Get the price circuit breaker config, i.e., the maximum allowed
+    /// price move factor and the window (in seconds) it applies to.
+    ///
+    /// Returns `None` if the circuit breaker is disabled.
+    pub fn price_circuit_breaker(&self) -> Option<(u128, u32)> {
+        let ratio = self.max_price_move_ratio;
+        if ratio == 0 {
+            None
+        } else {
+            Some((
+                u128::from(ratio) * FeedConfig::RATIO_MULTIPLIER,
+                self.price_move_window,
+            ))
+        }
+    }
+
+    /// Get the price circuit breaker state.
+    pub fn price_circuit_breaker_state(&self) -> &PriceCircuitBreakerState {
+        &self.circuit_breaker
+    }
+
+    /// Get the mutable price circuit breaker state.
+    pub fn price_circuit_breaker_state_mut(&mut self) -> &mut PriceCircuitBreakerState {
+        &mut self.circuit_breaker
+    }
+
+    /// Set price circuit breaker config.
+    ///
+    /// Passing `None` as `max_move_factor` disables the circuit breaker.
+    /// The breaker state is reset.
+    pub fn set_price_circuit_breaker(
+        &mut self,
+        max_move_factor: Option<u128>,
+        window: u32,
+    ) -> TokenConfigResult<()> {
+        let Some(factor) = max_move_factor else {
+            self.max_price_move_ratio = 0;
+            self.price_move_window = 0;
+            self.circuit_breaker.reset();
+            return Ok(());
+        };
+
+        if window == 0 {
+            return Err(TokenConfigError::InvalidPriceCircuitBreaker(
+                "window must not be zero",
+            ));
+        }
+
+        let ratio = (factor / FeedConfig::RATIO_MULTIPLIER)
+            .try_into()
+            .map_err(|_| TokenConfigError::ExceedMaxRatio)?;
+        if ratio == 0 {
+            return Err(TokenConfigError::InvalidPriceCircuitBreaker(
+                "max move factor is too small",
+            ));
+        }
+
+        self.max_price_move_ratio = ratio;
+        self.price_move_window = window;
+        self.circuit_breaker.reset();
+        Ok(())
+    }
+
     /// Set enabled.
     pub fn set_enabled(&mut self, enable: bool) {
         self.set_flag(TokenConfigFlag::Enabled, enable)
@@ -541,6 +825,21 @@ impl TokenRecord {
         ))
     }
 
+    /// Create [`TokenRecord`]s for all the price sources of the token config.
+    ///
+    /// Tokens priced from a composite of providers get a record for each
+    /// price source, otherwise the result is the same as [`from_config`](Self::from_config).
+    pub fn from_price_sources(token: Pubkey, config: &TokenConfig) -> TokenConfigResult<Vec<Self>> {
+        if !config.is_composite() {
+            return Ok(vec![Self::from_config(token, config)?]);
+        }
+        config
+            .price_sources()?
+            .into_iter()
+            .map(|provider| Ok(Self::new(token, config.get_feed(&provider)?, provider)))
+            .collect()
+    }
+
     /// Get token address.
     pub fn token(&self) -> &Pubkey {
         &self.token
@@ -587,17 +886,18 @@ impl TokensWithFeed {
 }
 
 /// Collect token records for the give tokens.
+///
+/// Tokens priced from a composite of providers get a record for each price source.
 pub fn token_records<A: TokenMapAccess>(
     token_map: &A,
     tokens: &BTreeSet<Pubkey>,
 ) -> TokenConfigResult<Vec<TokenRecord>> {
-    tokens
-        .iter()
-        .map(|token| {
-            let config = token_map.get(token).ok_or(TokenConfigError::NotFound)?;
-            TokenRecord::from_config(*token, config)
-        })
-        .collect::<TokenConfigResult<Vec<_>>>()
+    let mut records = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let config = token_map.get(token).ok_or(TokenConfigError::NotFound)?;
+        records.extend(TokenRecord::from_price_sources(*token, config)?);
+    }
+    Ok(records)
 }
 
 /// Tokens Collector.
@@ -634,7 +934,17 @@ impl TokensCollector {
     }
 
     /// Convert to a vec.
+    ///
+    /// Tokens priced from a composite of providers are repeated for each price
+    /// source, matching the tokens of [`to_feeds`](Self::to_feeds).
     pub fn into_vec(mut self, token_map: &impl TokenMapAccess) -> TokenConfigResult<Vec<Pubkey>> {
+        if self
+            .tokens
+            .iter()
+            .any(|token| token_map.get(token).is_some_and(TokenConfig::is_composite))
+        {
+            return Ok(self.to_feeds(token_map)?.tokens);
+        }
         token_map
             .sort_tokens_by_provider(&mut self.tokens)
             .map_err(|_| TokenConfigError::NotFound)?;
@@ -648,14 +958,11 @@ impl TokensCollector {
 
     /// Convert to [`TokensWithFeed`].
     pub fn to_feeds(&self, token_map: &impl TokenMapAccess) -> TokenConfigResult<TokensWithFeed> {
-        let records = self
-            .tokens
-            .iter()
-            .map(|token| {
-                let config = token_map.get(token).ok_or(TokenConfigError::NotFound)?;
-                TokenRecord::from_config(*token, config)
-            })
-            .collect::<TokenConfigResult<Vec<_>>>()?;
+        let mut records = Vec::with_capacity(self.tokens.len());
+        for token in self.tokens.iter() {
+            let config = token_map.get(token).ok_or(TokenConfigError::NotFound)?;
+            records.extend(TokenRecord::from_price_sources(*token, config)?);
+        }
         TokensWithFeed::try_from_records(records)
     }
 }
@@ -713,3 +1020,99 @@ mod market_status_tests {
         assert!(config.set_market_status_flag(MarketStatusFlag::AllowPreMarket, false));
     }
 }
+
+#[cfg(test)]
+mod composite_price_tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    fn config() -> TokenConfig {
+        let mut config = TokenConfig::zeroed();
+        for (kind, feed) in [
+            (PriceProviderKind::ChainlinkDataStreams, [1; 32]),
+            (PriceProviderKind::Pyth, [2; 32]),
+        ] {
+            config
+                .set_feed_config(&kind, FeedConfig::new(Pubkey::new_from_array(feed)))
+                .unwrap();
+        }
+        config.set_expected_provider(PriceProviderKind::Pyth);
+        config
+    }
+
+    #[test]
+    fn price_sources_start_with_expected_provider() {
+        let mut config = config();
+        assert!(config.price_sources().unwrap() == vec![PriceProviderKind::Pyth]);
+
+        config
+            .set_composite_price(
+                CompositePriceMode::Median,
+                &[
+                    PriceProviderKind::ChainlinkDataStreams,
+                    PriceProviderKind::Pyth,
+                ],
+                Some(FeedConfig::RATIO_MULTIPLIER * 100),
+            )
+            .unwrap();
+        assert!(config.is_composite());
+        assert!(
+            config.price_sources().unwrap()
+                == vec![
+                    PriceProviderKind::Pyth,
+                    PriceProviderKind::ChainlinkDataStreams
+                ]
+        );
+        assert!(!config
+            .is_price_source(&PriceProviderKind::Switchboard)
+            .unwrap());
+        assert_eq!(
+            config.max_disagreement_factor(),
+            Some(FeedConfig::RATIO_MULTIPLIER * 100)
+        );
+
+        let token = Pubkey::new_from_array([3; 32]);
+        let records = TokenRecord::from_price_sources(token, &config).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(*records[1].feed(), Pubkey::new_from_array([1; 32]));
+
+        config
+            .set_composite_price(CompositePriceMode::Disabled, &[], None)
+            .unwrap();
+        assert!(!config.is_composite());
+        assert!(config.max_disagreement_factor().is_none());
+        assert_eq!(
+            TokenRecord::from_price_sources(token, &config)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn invalid_composite_price_sources() {
+        let mut config = config();
+        assert!(config
+            .set_composite_price(CompositePriceMode::Median, &[PriceProviderKind::Pyth], None)
+            .is_err());
+        assert!(config
+            .set_composite_price(
+                CompositePriceMode::Median,
+                &[PriceProviderKind::Pyth, PriceProviderKind::Switchboard],
+                None,
+            )
+            .is_err());
+        config.set_expected_provider(PriceProviderKind::Switchboard);
+        assert!(config
+            .set_composite_price(
+                CompositePriceMode::PrimaryWithFallback,
+                &[
+                    PriceProviderKind::Pyth,
+                    PriceProviderKind::ChainlinkDataStreams
+                ],
+                None,
+            )
+            .is_err());
+        assert!(!config.is_composite());
+    }
+}
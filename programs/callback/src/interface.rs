@@ -9,6 +9,9 @@ pub use crate::{
 #[cfg(not(feature = "no-competition"))]
 const COMPETITION_ID: Pubkey = pubkey!("2AxuNr6euZPKQbTwNsLBjzFTZFAevA85F4PW9m9Dv8pc");
 
+#[cfg(not(feature = "no-gt-incentive"))]
+const GT_INCENTIVE_ID: Pubkey = pubkey!("6iMXVSjiBf75ce9QaUVRnpsUikW8SZm8EaPVGDPfsCso");
+
 /// Callback interface for GMX-Solana.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct CallbackInterface;
@@ -20,6 +23,8 @@ impl Ids for CallbackInterface {
             crate::ID,
             #[cfg(not(feature = "no-competition"))]
             COMPETITION_ID,
+            #[cfg(not(feature = "no-gt-incentive"))]
+            GT_INCENTIVE_ID,
         ];
 
         IDS
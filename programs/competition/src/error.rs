@@ -20,4 +20,30 @@ pub enum CompetitionError {
     CompetitionInProgress,
     #[msg("invalid volume merge window")]
     InvalidVolumeMergeWindow,
+    #[msg("competition is not finalized")]
+    CompetitionNotFinalized,
+    #[msg("invalid leaderboard page")]
+    InvalidLeaderboardPage,
+    #[msg("leaderboard page is full")]
+    LeaderboardPageFull,
+    #[msg("invalid ranking order")]
+    InvalidRankingOrder,
+    #[msg("invalid participant")]
+    InvalidParticipant,
+    #[msg("invalid prize schedule")]
+    InvalidPrizeSchedule,
+    #[msg("invalid prize account")]
+    InvalidPrizeAccount,
+    #[msg("prize distribution has started")]
+    PrizeDistributionStarted,
+    #[msg("prize distribution is not completed")]
+    PrizeDistributionNotCompleted,
+    #[msg("invalid amount")]
+    InvalidAmount,
+    #[msg("competition is already finalized")]
+    CompetitionFinalized,
+    #[msg("account is already migrated")]
+    AlreadyMigrated,
+    #[msg("standings are incomplete")]
+    IncompleteStandings,
 }
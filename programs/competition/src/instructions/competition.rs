@@ -1,6 +1,6 @@
 use crate::{
     error::CompetitionError,
-    states::{Competition, COMPETITION_SEED},
+    states::{Competition, ScoringMode, COMPETITION_SEED},
 };
 use anchor_lang::prelude::*;
 
@@ -40,6 +40,7 @@ impl InitializeCompetition<'_> {
         extension_cap: i64,
         only_count_increase: bool,
         volume_merge_window: i64,
+        scoring_mode: ScoringMode,
     ) -> Result<()> {
         let now = Clock::get()?.unix_timestamp;
         require!(start_time > now, CompetitionError::InvalidTimeRange);
@@ -74,6 +75,11 @@ impl InitializeCompetition<'_> {
         comp.extension_triggerer = None;
         comp.only_count_increase = only_count_increase;
         comp.volume_merge_window = volume_merge_window;
+        comp.participant_count = 0;
+        comp.ranked_count = 0;
+        comp.last_ranked = None;
+        comp.scoring_mode = scoring_mode;
+        comp.standings_finalized = false;
         Ok(())
     }
 }
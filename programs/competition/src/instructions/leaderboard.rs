@@ -0,0 +1,139 @@
+use crate::{
+    states::{
+        Competition, LeaderEntry, LeaderboardPage, Participant, LEADERBOARD_PAGE_LEN,
+        LEADERBOARD_PAGE_SEED,
+    },
+    CompetitionError,
+};
+use anchor_lang::prelude::*;
+
+/// Append ranked participants to the final standings.
+///
+/// This instruction can only be called by the authority of the competition
+/// after it ends and before the standings are finalized. The participant
+/// accounts must be passed as remaining accounts in strictly descending
+/// order (by score, then by address), continuing from the last ranked entry.
+#[derive(Accounts)]
+#[instruction(page_index: u32)]
+pub struct AppendLeaderboardPage<'info> {
+    /// The authority of the competition, which also funds the page when it
+    /// does **not** exist.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The competition account.
+    #[account(mut, has_one = authority)]
+    pub competition: Account<'info, Competition>,
+    /// The leaderboard page PDA.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + LeaderboardPage::INIT_SPACE,
+        seeds = [
+            LEADERBOARD_PAGE_SEED,
+            competition.key().as_ref(),
+            &page_index.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub page: Account<'info, LeaderboardPage>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AppendLeaderboardPage<'info> {
+    pub(crate) fn invoke(ctx: Context<'_, '_, 'info, 'info, Self>, page_index: u32) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let competition = ctx.accounts.competition.key();
+        let comp = &mut ctx.accounts.competition;
+
+        require!(comp.is_ended(now), CompetitionError::CompetitionInProgress);
+        require!(
+            !comp.standings_finalized,
+            CompetitionError::CompetitionFinalized
+        );
+        require_eq!(
+            page_index,
+            comp.next_page_index(),
+            CompetitionError::InvalidLeaderboardPage
+        );
+
+        let page = &mut ctx.accounts.page;
+        if page.competition == Pubkey::default() {
+            page.bump = ctx.bumps.page;
+            page.competition = competition;
+            page.index = page_index;
+        }
+
+        for info in ctx.remaining_accounts {
+            require_gt!(
+                LEADERBOARD_PAGE_LEN as usize,
+                page.entries.len(),
+                CompetitionError::LeaderboardPageFull
+            );
+
+            let participant = Account::<Participant>::try_from(info)?;
+            require_keys_eq!(
+                participant.competition,
+                competition,
+                CompetitionError::InvalidParticipant
+            );
+
+            let entry = LeaderEntry::new(&participant, comp.scoring_mode);
+            if let Some(last) = comp.last_ranked.as_ref() {
+                require!(
+                    last.ranks_before(&entry),
+                    CompetitionError::InvalidRankingOrder
+                );
+            }
+
+            page.entries.push(entry);
+            comp.last_ranked = Some(entry);
+            comp.ranked_count = comp
+                .ranked_count
+                .checked_add(1)
+                .ok_or_else(|| error!(CompetitionError::InvalidParticipant))?;
+        }
+
+        msg!(
+            "competition: ranked={} participants={}",
+            comp.ranked_count,
+            comp.participant_count
+        );
+        Ok(())
+    }
+}
+
+/// Finalize the standings of a competition.
+///
+/// This instruction can only be called by the authority of the competition
+/// after it ends and all participants are ranked. Once finalized, no more participants can be ranked, and the
+/// prizes can be distributed and the participant accounts can be closed.
+#[derive(Accounts)]
+pub struct FinalizeStandings<'info> {
+    /// The authority of the competition.
+    pub authority: Signer<'info>,
+    /// The competition account.
+    #[account(mut, has_one = authority)]
+    pub competition: Account<'info, Competition>,
+}
+
+impl FinalizeStandings<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let comp = &mut ctx.accounts.competition;
+
+        require!(comp.is_ended(now), CompetitionError::CompetitionInProgress);
+        require!(
+            !comp.standings_finalized,
+            CompetitionError::CompetitionFinalized
+        );
+        require_eq!(
+            comp.ranked_count,
+            comp.participant_count,
+            CompetitionError::IncompleteStandings
+        );
+
+        comp.standings_finalized = true;
+        Ok(())
+    }
+}
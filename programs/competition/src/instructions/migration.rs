@@ -0,0 +1,263 @@
+use crate::{
+    states::{
+        Competition, LeaderEntry, Participant, ScoringMode, COMPETITION_SEED, PARTICIPANT_SEED,
+    },
+    CompetitionError,
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+/// The legacy layout of [`LeaderEntry`].
+#[derive(AnchorDeserialize)]
+struct LegacyLeaderEntry {
+    address: Pubkey,
+    volume: u128,
+}
+
+/// The legacy layout of [`Competition`].
+#[derive(AnchorDeserialize)]
+struct LegacyCompetition {
+    bump: u8,
+    authority: Pubkey,
+    start_time: i64,
+    end_time: i64,
+    leaderboard: Vec<LegacyLeaderEntry>,
+    volume_threshold: u128,
+    extension_duration: i64,
+    extension_cap: i64,
+    extension_triggerer: Option<Pubkey>,
+    only_count_increase: bool,
+    volume_merge_window: i64,
+}
+
+impl From<LegacyCompetition> for Competition {
+    fn from(legacy: LegacyCompetition) -> Self {
+        Self {
+            bump: legacy.bump,
+            authority: legacy.authority,
+            start_time: legacy.start_time,
+            end_time: legacy.end_time,
+            // Legacy competitions were always ranked by volume.
+            leaderboard: legacy
+                .leaderboard
+                .into_iter()
+                .map(|entry| LeaderEntry {
+                    address: entry.address,
+                    volume: entry.volume,
+                    score: i128::try_from(entry.volume).unwrap_or(i128::MAX),
+                })
+                .collect(),
+            volume_threshold: legacy.volume_threshold,
+            extension_duration: legacy.extension_duration,
+            extension_cap: legacy.extension_cap,
+            extension_triggerer: legacy.extension_triggerer,
+            only_count_increase: legacy.only_count_increase,
+            volume_merge_window: legacy.volume_merge_window,
+            participant_count: 0,
+            ranked_count: 0,
+            last_ranked: None,
+            scoring_mode: ScoringMode::Volume,
+            standings_finalized: false,
+        }
+    }
+}
+
+/// The legacy layout of [`Participant`].
+#[derive(AnchorDeserialize)]
+struct LegacyParticipant {
+    bump: u8,
+    competition: Pubkey,
+    trader: Pubkey,
+    volume: u128,
+    last_updated_at: i64,
+    merged_volume: u128,
+}
+
+impl From<LegacyParticipant> for Participant {
+    fn from(legacy: LegacyParticipant) -> Self {
+        Self {
+            bump: legacy.bump,
+            competition: legacy.competition,
+            trader: legacy.trader,
+            volume: legacy.volume,
+            last_updated_at: legacy.last_updated_at,
+            merged_volume: legacy.merged_volume,
+            realized_pnl: 0,
+            collateral_value: 0,
+            entered_positions: Vec::new(),
+        }
+    }
+}
+
+/// Migrate a [`Competition`] account from the legacy layout.
+///
+/// This instruction can only be called by the authority of the competition,
+/// which also funds the extra rent.
+#[derive(Accounts)]
+pub struct MigrateCompetition<'info> {
+    /// The authority of the competition.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The competition account in the legacy layout.
+    /// CHECK: validated in the instruction.
+    #[account(mut, owner = crate::ID)]
+    pub competition: UncheckedAccount<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateCompetition<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let info = ctx.accounts.competition.to_account_info();
+        let legacy = read_legacy::<Competition, LegacyCompetition>(&info)?;
+
+        require_keys_eq!(
+            legacy.authority,
+            ctx.accounts.authority.key(),
+            ErrorCode::ConstraintHasOne
+        );
+        let expected = Pubkey::create_program_address(
+            &[
+                COMPETITION_SEED,
+                legacy.authority.as_ref(),
+                &legacy.start_time.to_le_bytes(),
+                &[legacy.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+        require_keys_eq!(expected, info.key(), ErrorCode::ConstraintSeeds);
+
+        let competition = Competition::from(legacy);
+        realloc_and_write(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            8 + Competition::INIT_SPACE,
+            &competition,
+        )?;
+
+        msg!("competition: migrated competition={}", info.key());
+        Ok(())
+    }
+}
+
+/// Migrate a [`Participant`] account from the legacy layout.
+///
+/// This instruction can be called by anyone, and the payer funds the extra
+/// rent. The competition must have been migrated first. Migrated participants
+/// are counted in the participant count of the competition.
+#[derive(Accounts)]
+pub struct MigrateParticipant<'info> {
+    /// Payer that funds the extra rent.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The competition account this participant belongs to.
+    #[account(mut)]
+    pub competition: Account<'info, Competition>,
+    /// The participant account in the legacy layout.
+    /// CHECK: validated in the instruction.
+    #[account(mut, owner = crate::ID)]
+    pub participant: UncheckedAccount<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateParticipant<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let info = ctx.accounts.participant.to_account_info();
+        let legacy = read_legacy::<Participant, LegacyParticipant>(&info)?;
+
+        let competition = ctx.accounts.competition.key();
+        require_keys_eq!(
+            legacy.competition,
+            competition,
+            CompetitionError::InvalidParticipant
+        );
+        let expected = Pubkey::create_program_address(
+            &[
+                PARTICIPANT_SEED,
+                competition.as_ref(),
+                legacy.trader.as_ref(),
+                &[legacy.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+        require_keys_eq!(expected, info.key(), ErrorCode::ConstraintSeeds);
+
+        let participant = Participant::from(legacy);
+        realloc_and_write(
+            &info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            8 + Participant::INIT_SPACE,
+            &participant,
+        )?;
+
+        let comp = &mut ctx.accounts.competition;
+        comp.participant_count = comp
+            .participant_count
+            .checked_add(1)
+            .ok_or_else(|| error!(CompetitionError::InvalidParticipant))?;
+
+        msg!(
+            "competition: migrated participant={} trader={}",
+            info.key(),
+            participant.trader
+        );
+        Ok(())
+    }
+}
+
+/// Read the legacy data of an account of type `T`.
+///
+/// Accounts in the current layout are always allocated with the full space of `T`,
+/// so a shorter account must be in the legacy layout.
+fn read_legacy<T, L>(info: &AccountInfo) -> Result<L>
+where
+    T: Discriminator + Space,
+    L: AnchorDeserialize,
+{
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == *T::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    require_gt!(
+        8 + T::INIT_SPACE,
+        data.len(),
+        CompetitionError::AlreadyMigrated
+    );
+    let legacy = L::deserialize(&mut &data[8..])?;
+    Ok(legacy)
+}
+
+/// Realloc the account to `space` and write `value` into it.
+fn realloc_and_write<'info, T: AccountSerialize>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    value: &T,
+) -> Result<()> {
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(info.lamports());
+    if required_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+
+    info.realloc(space, true)?;
+    let mut data = info.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
@@ -6,9 +6,21 @@ pub mod competition;
 /// Lazily create a [`Participant`](crate::states::Participant) PDA.
 pub mod participant;
 
+/// Build the final standings in [`LeaderboardPage`](crate::states::LeaderboardPage)s.
+pub mod leaderboard;
+
+/// Manage and distribute the [`PrizePool`](crate::states::PrizePool).
+pub mod prize;
+
+/// Migrate accounts from the legacy layouts.
+pub mod migration;
+
 /// Callback entry invoked by the GMX‑Solana store program on each trade.
 pub mod trade_callback;
 
 pub use competition::*;
+pub use leaderboard::*;
+pub use migration::*;
 pub use participant::*;
+pub use prize::*;
 pub use trade_callback::*;
@@ -6,13 +6,15 @@ use anchor_lang::prelude::*;
 ///
 /// This instruction can be called by the store‑program (via CPI) before the
 /// first trade of a trader is recorded.  
-/// If the account already exists the call is a no‑op.
+/// If the account already exists the call is a no‑op. New participants
+/// cannot join once the competition has ended.
 #[derive(Accounts)]
 pub struct CreateParticipantIdempotent<'info> {
     /// Payer that funds the new PDA when it does **not** exist.
     #[account(mut)]
     pub payer: Signer<'info>,
     /// The competition account this participant belongs to.
+    #[account(mut)]
     pub competition: Account<'info, Competition>,
     /// The participant PDA.
     #[account(
@@ -37,13 +39,15 @@ pub struct CreateParticipantIdempotent<'info> {
 /// Close the [`Participant`] account.
 ///
 /// This instruction can be called by the trader to close their participant account
-/// and recover the rent.
+/// and recover the rent. After the competition ends, the participant account can
+/// only be closed once the final standings are complete.
 #[derive(Accounts)]
 pub struct CloseParticipant<'info> {
     /// The trader that owns the participant account.
     #[account(mut)]
     pub trader: Signer<'info>,
     /// The competition account this participant belongs to.
+    #[account(mut)]
     pub competition: Account<'info, Competition>,
     /// The participant PDA to close.
     #[account(
@@ -64,12 +68,17 @@ pub struct CloseParticipant<'info> {
 impl CloseParticipant<'_> {
     pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
         let now = Clock::get()?.unix_timestamp;
-        let comp = &ctx.accounts.competition;
+        let comp = &mut ctx.accounts.competition;
 
-        require!(
-            now < comp.start_time || now > comp.end_time,
-            CompetitionError::CompetitionInProgress
-        );
+        if now < comp.start_time {
+            comp.participant_count = comp.participant_count.saturating_sub(1);
+        } else {
+            require!(comp.is_ended(now), CompetitionError::CompetitionInProgress);
+            require!(
+                comp.is_finalized(now),
+                CompetitionError::CompetitionNotFinalized
+            );
+        }
 
         Ok(())
     }
@@ -89,6 +98,10 @@ impl CreateParticipantIdempotent<'_> {
             let now = Clock::get()?.unix_timestamp;
             let trader = self.trader.key();
             require_keys_neq!(trader, default_pubkey);
+            require!(
+                !self.competition.is_ended(now),
+                CompetitionError::OutsideCompetitionTime
+            );
 
             p.bump = bump;
             p.competition = self.competition.key();
@@ -96,6 +109,14 @@ impl CreateParticipantIdempotent<'_> {
             p.volume = 0;
             p.last_updated_at = now;
             p.merged_volume = 0;
+            p.realized_pnl = 0;
+            p.collateral_value = 0;
+
+            let comp = &mut self.competition;
+            comp.participant_count = comp
+                .participant_count
+                .checked_add(1)
+                .ok_or_else(|| error!(CompetitionError::InvalidParticipant))?;
         }
         Ok(())
     }
@@ -0,0 +1,312 @@
+use crate::{
+    states::{
+        Competition, LeaderboardPage, PrizePool, LEADERBOARD_PAGE_SEED, MAX_PRIZE_RANKS,
+        PRIZE_POOL_SEED, PRIZE_SCHEDULE_DENOMINATOR, PRIZE_VAULT_SEED,
+    },
+    CompetitionError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Initialize the [`PrizePool`] of a competition.
+///
+/// The `schedule` is the share of the prize pool (in basis points) paid to
+/// each rank, starting from the first place.
+#[derive(Accounts)]
+pub struct InitializePrizePool<'info> {
+    /// The authority of the competition.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The competition account.
+    #[account(has_one = authority)]
+    pub competition: Account<'info, Competition>,
+    /// The prize token mint.
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// The prize pool PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PrizePool::INIT_SPACE,
+        seeds = [PRIZE_POOL_SEED, competition.key().as_ref()],
+        bump,
+    )]
+    pub prize_pool: Account<'info, PrizePool>,
+    /// The prize vault.
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PRIZE_VAULT_SEED, competition.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = prize_pool,
+        token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// The token program.
+    pub token_program: Interface<'info, TokenInterface>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializePrizePool<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>, schedule: Vec<u16>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !ctx.accounts.competition.is_ended(now),
+            CompetitionError::OutsideCompetitionTime
+        );
+        require!(
+            !schedule.is_empty() && schedule.len() <= MAX_PRIZE_RANKS as usize,
+            CompetitionError::InvalidPrizeSchedule
+        );
+        let total_share = schedule.iter().map(|share| u32::from(*share)).sum::<u32>();
+        require_gte!(
+            u32::from(PRIZE_SCHEDULE_DENOMINATOR),
+            total_share,
+            CompetitionError::InvalidPrizeSchedule
+        );
+
+        let pool = &mut ctx.accounts.prize_pool;
+        pool.bump = ctx.bumps.prize_pool;
+        pool.competition = ctx.accounts.competition.key();
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.total_amount = 0;
+        pool.distributed_count = 0;
+        pool.schedule = schedule;
+        Ok(())
+    }
+}
+
+/// Fund the [`PrizePool`] of a competition.
+///
+/// Anyone can fund the prize pool before the distribution starts.
+#[derive(Accounts)]
+pub struct FundPrizePool<'info> {
+    /// The funder.
+    pub funder: Signer<'info>,
+    /// The competition account.
+    pub competition: Account<'info, Competition>,
+    /// The prize pool.
+    #[account(has_one = competition, has_one = mint, has_one = vault)]
+    pub prize_pool: Account<'info, PrizePool>,
+    /// The prize token mint.
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// The source token account.
+    #[account(mut, token::mint = mint, token::authority = funder)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    /// The prize vault.
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// The token program.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl FundPrizePool<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>, amount: u64) -> Result<()> {
+        require!(amount != 0, CompetitionError::InvalidAmount);
+        require!(
+            !ctx.accounts.prize_pool.is_distribution_started(),
+            CompetitionError::PrizeDistributionStarted
+        );
+
+        let accounts = &ctx.accounts;
+        let cpi_ctx = CpiContext::new(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.source.to_account_info(),
+                mint: accounts.mint.to_account_info(),
+                to: accounts.vault.to_account_info(),
+                authority: accounts.funder.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount, accounts.mint.decimals)
+    }
+}
+
+/// Distribute the prizes to the ranked participants of the given page.
+///
+/// This instruction is permissionless and can only be called once the final
+/// standings are complete. The token accounts of the traders to be paid must
+/// be passed as remaining accounts, in rank order, starting from the first
+/// unpaid rank.
+#[derive(Accounts)]
+#[instruction(page_index: u32)]
+pub struct DistributePrizes<'info> {
+    /// The competition account.
+    pub competition: Account<'info, Competition>,
+    /// The prize pool.
+    #[account(mut, has_one = competition, has_one = mint, has_one = vault)]
+    pub prize_pool: Account<'info, PrizePool>,
+    /// The leaderboard page.
+    #[account(
+        seeds = [
+            LEADERBOARD_PAGE_SEED,
+            competition.key().as_ref(),
+            &page_index.to_le_bytes(),
+        ],
+        bump = page.bump,
+        has_one = competition,
+    )]
+    pub page: Account<'info, LeaderboardPage>,
+    /// The prize token mint.
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// The prize vault.
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// The token program.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> DistributePrizes<'info> {
+    pub(crate) fn invoke(ctx: Context<'_, '_, 'info, 'info, Self>, _page_index: u32) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let comp = &ctx.accounts.competition;
+        require!(
+            comp.is_finalized(now),
+            CompetitionError::CompetitionNotFinalized
+        );
+
+        let rewarded_count = ctx.accounts.prize_pool.rewarded_count(comp.ranked_count);
+        if ctx.accounts.prize_pool.distributed_count >= rewarded_count {
+            msg!("competition: all prizes have been distributed");
+            return Ok(());
+        }
+
+        if !ctx.accounts.prize_pool.is_distribution_started() {
+            ctx.accounts.prize_pool.total_amount = ctx.accounts.vault.amount;
+        }
+
+        let page = &ctx.accounts.page;
+        let first_rank = page.first_rank();
+        let end_rank = first_rank
+            .saturating_add(page.entries.len() as u32)
+            .min(rewarded_count);
+        let rank = ctx.accounts.prize_pool.distributed_count;
+        require!(
+            first_rank <= rank && rank < end_rank,
+            CompetitionError::InvalidLeaderboardPage
+        );
+
+        for info in ctx.remaining_accounts {
+            let rank = ctx.accounts.prize_pool.distributed_count;
+            if rank >= end_rank {
+                break;
+            }
+            let entry = ctx.accounts.page.entries[(rank - first_rank) as usize];
+            let to = InterfaceAccount::<TokenAccount>::try_from(info)?;
+            require_keys_eq!(
+                to.owner,
+                entry.address,
+                CompetitionError::InvalidPrizeAccount
+            );
+            require_keys_eq!(
+                to.mint,
+                ctx.accounts.mint.key(),
+                CompetitionError::InvalidPrizeAccount
+            );
+
+            let amount = ctx
+                .accounts
+                .prize_pool
+                .prize_for(rank)
+                .ok_or_else(|| error!(CompetitionError::InvalidAmount))?;
+            if amount != 0 {
+                ctx.accounts.transfer_from_vault(info, amount)?;
+            }
+            ctx.accounts.prize_pool.distributed_count += 1;
+
+            msg!(
+                "competition: rank={} trader={} prize={}",
+                rank,
+                entry.address,
+                amount
+            );
+        }
+
+        Ok(())
+    }
+
+    fn transfer_from_vault(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        let signer_seeds = self.prize_pool.signer_seeds();
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: to.clone(),
+                authority: self.prize_pool.to_account_info(),
+            },
+        );
+        transfer_checked(
+            cpi_ctx.with_signer(&[&signer_seeds]),
+            amount,
+            self.mint.decimals,
+        )
+    }
+}
+
+/// Reclaim the remaining prizes after the distribution is completed.
+#[derive(Accounts)]
+pub struct ReclaimPrizes<'info> {
+    /// The authority of the competition.
+    pub authority: Signer<'info>,
+    /// The competition account.
+    #[account(has_one = authority)]
+    pub competition: Account<'info, Competition>,
+    /// The prize pool.
+    #[account(has_one = competition, has_one = mint, has_one = vault)]
+    pub prize_pool: Account<'info, PrizePool>,
+    /// The prize token mint.
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// The prize vault.
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// The receiver token account.
+    #[account(mut, token::mint = mint)]
+    pub receiver: InterfaceAccount<'info, TokenAccount>,
+    /// The token program.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl ReclaimPrizes<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let comp = &ctx.accounts.competition;
+        require!(
+            comp.is_finalized(now),
+            CompetitionError::CompetitionNotFinalized
+        );
+        let pool = &ctx.accounts.prize_pool;
+        require_gte!(
+            pool.distributed_count,
+            pool.rewarded_count(comp.ranked_count),
+            CompetitionError::PrizeDistributionNotCompleted
+        );
+
+        let amount = ctx.accounts.vault.amount;
+        if amount == 0 {
+            msg!("competition: no prizes to reclaim");
+            return Ok(());
+        }
+
+        let accounts = &ctx.accounts;
+        let signer_seeds = accounts.prize_pool.signer_seeds();
+        let cpi_ctx = CpiContext::new(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: accounts.vault.to_account_info(),
+                mint: accounts.mint.to_account_info(),
+                to: accounts.receiver.to_account_info(),
+                authority: accounts.prize_pool.to_account_info(),
+            },
+        );
+        transfer_checked(
+            cpi_ctx.with_signer(&[&signer_seeds]),
+            amount,
+            accounts.mint.decimals,
+        )
+    }
+}
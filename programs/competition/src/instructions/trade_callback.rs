@@ -7,7 +7,8 @@ use crate::{
 };
 use anchor_lang::prelude::*;
 use gmsol_callback::{interface::ActionKind, CALLBACK_AUTHORITY_SEED};
-use gmsol_programs::gmsol_store::accounts::TradeData;
+use gmsol_programs::gmsol_store::{accounts::TradeData, types::TradeFees};
+use gmsol_utils::order::{TradeFlag, TradeFlagContainer};
 
 /// Accounts for `on_created`.
 #[derive(Accounts)]
@@ -147,8 +148,8 @@ impl OnExecuted<'_> {
             return Ok(());
         }
 
-        // Get volume from the trade event.
-        let volume = {
+        // Get volume, net PnL, collateral value and position changes from the trade event.
+        let (volume, pnl, collateral_value, position) = {
             let Some(trade_event) = &ctx.accounts.trade_event else {
                 msg!("competition: no trade event");
                 return Ok(());
@@ -179,48 +180,102 @@ impl OnExecuted<'_> {
                     .abs_diff(trade_event.before.size_in_usd)
             };
 
-            // Skip trades with zero volume.
-            if volume == 0 {
+            let flags = TradeFlagContainer::from_value(trade_event.flags);
+            let collateral_price = if flags.get_flag(TradeFlag::IsCollateralLong) {
+                trade_event.prices.long.min
+            } else {
+                trade_event.prices.short.min
+            };
+            let position = PositionChange {
+                address: trade_event.position,
+                is_increase: flags.get_flag(TradeFlag::IsIncrease),
+                is_opened: trade_event.before.size_in_usd == 0,
+                is_closed: trade_event.after.size_in_usd == 0,
+            };
+            let (pnl, collateral_value) = if position.is_increase {
+                let collateral_value = trade_event
+                    .after
+                    .collateral_amount
+                    .saturating_sub(trade_event.before.collateral_amount)
+                    .saturating_mul(collateral_price);
+                (
+                    Self::net_pnl(0, &trade_event.fees, collateral_price),
+                    collateral_value,
+                )
+            } else {
+                (
+                    Self::net_pnl(trade_event.pnl.pnl, &trade_event.fees, collateral_price),
+                    0,
+                )
+            };
+
+            // Skip trades with nothing to record.
+            if volume == 0 && pnl == 0 && collateral_value == 0 {
                 msg!("competition: skipped trade with zero volume");
                 return Ok(());
             }
-            volume
+            (volume, pnl, collateral_value, position)
         };
 
         ctx.accounts.with_participant(|comp, part| {
-            part.volume = part.volume.saturating_add(volume);
-
-            // Determine if trade volume should be merged based on time window.
-            let time_diff = now.saturating_sub(part.last_updated_at);
-            part.last_updated_at = now;
-            if time_diff <= comp.volume_merge_window {
-                // Within the merge window, add to merged volume.
-                part.merged_volume = part.merged_volume.saturating_add(volume);
-
-                // Check if merged volume exceeds threshold.
-                if part.merged_volume >= comp.volume_threshold {
-                    Self::extend_competition_time(comp, part, part.merged_volume)?;
-                    // Reset merged volume after triggering extension.
-                    part.merged_volume = 0;
-                }
-            } else {
-                // Outside the merge window, check single trade volume.
-                if volume >= comp.volume_threshold {
-                    Self::extend_competition_time(comp, part, volume)?;
-                    part.merged_volume = 0;
+            if volume != 0 {
+                part.volume = part.volume.saturating_add(volume);
+
+                // Determine if trade volume should be merged based on time window.
+                let time_diff = now.saturating_sub(part.last_updated_at);
+                part.last_updated_at = now;
+                if time_diff <= comp.volume_merge_window {
+                    // Within the merge window, add to merged volume.
+                    part.merged_volume = part.merged_volume.saturating_add(volume);
+
+                    // Check if merged volume exceeds threshold.
+                    if part.merged_volume >= comp.volume_threshold {
+                        Self::extend_competition_time(comp, part, part.merged_volume)?;
+                        // Reset merged volume after triggering extension.
+                        part.merged_volume = 0;
+                    }
                 } else {
-                    part.merged_volume = volume;
+                    // Outside the merge window, check single trade volume.
+                    if volume >= comp.volume_threshold {
+                        Self::extend_competition_time(comp, part, volume)?;
+                        part.merged_volume = 0;
+                    } else {
+                        part.merged_volume = volume;
+                    }
                 }
             }
 
+            // Only count the PnL realized from positions opened during the competition,
+            // since the PnL of a position opened before the start may have accrued before it.
+            let pnl = if position.is_increase {
+                if position.is_opened && !part.enter_position(&position.address) {
+                    msg!(
+                        "competition: too many entered positions, position={} is not tracked",
+                        position.address
+                    );
+                }
+                pnl
+            } else if part.is_entered(&position.address) {
+                if position.is_closed {
+                    part.exit_position(&position.address);
+                }
+                pnl
+            } else {
+                0
+            };
+            part.realized_pnl = part.realized_pnl.saturating_add(pnl);
+            part.collateral_value = part.collateral_value.saturating_add(collateral_value);
+
             Self::update_leaderboard(comp, part);
 
             msg!(
-                "competition: trader={} new_volume={} volume_delta={} merged_volume={}",
+                "competition: trader={} new_volume={} volume_delta={} merged_volume={} realized_pnl={} collateral_value={}",
                 part.trader,
                 part.volume,
                 volume,
-                part.merged_volume
+                part.merged_volume,
+                part.realized_pnl,
+                part.collateral_value,
             );
 
             Ok(())
@@ -291,26 +346,36 @@ impl OnExecuted<'_> {
         Ok(())
     }
 
+    /// Returns the PnL net of the fees paid by the trader.
+    ///
+    /// The fees are paid in the collateral token, and the total cost matches
+    /// the one used by the position model (order, borrowing, liquidation and
+    /// funding fees).
+    fn net_pnl(pnl: i128, fees: &TradeFees, collateral_price: u128) -> i128 {
+        let total_cost = fees
+            .order_fee_for_receiver_amount
+            .saturating_add(fees.order_fee_for_pool_amount)
+            .saturating_add(fees.total_borrowing_fee_amount)
+            .saturating_add(fees.liquidation_fee_amount)
+            .saturating_add(fees.funding_fee_amount);
+        let fee_value = total_cost.saturating_mul(collateral_price);
+        pnl.saturating_sub(i128::try_from(fee_value).unwrap_or(i128::MAX))
+    }
+
     fn update_leaderboard(comp: &mut Competition, part: &Participant) {
-        let entry = if let Some(pos) = comp
+        let entry = LeaderEntry::new(part, comp.scoring_mode);
+        if let Some(pos) = comp
             .leaderboard
             .iter()
             .position(|e| e.address == part.trader)
         {
-            let mut old = comp.leaderboard.remove(pos);
-            old.volume = part.volume;
-            old
-        } else {
-            LeaderEntry {
-                address: part.trader,
-                volume: part.volume,
-            }
-        };
+            comp.leaderboard.remove(pos);
+        }
 
         let insert_pos = comp
             .leaderboard
             .iter()
-            .rposition(|e| e.volume >= entry.volume)
+            .rposition(|e| e.score >= entry.score)
             .map(|pos| pos + 1)
             .unwrap_or(0);
 
@@ -323,6 +388,14 @@ impl OnExecuted<'_> {
     }
 }
 
+/// The change of the position of a trade.
+struct PositionChange {
+    address: Pubkey,
+    is_increase: bool,
+    is_opened: bool,
+    is_closed: bool,
+}
+
 /// Accounts for other callbacks.
 #[derive(Accounts)]
 #[instruction(authority_bump: u8)]
@@ -7,6 +7,8 @@ pub mod states;
 pub use error::CompetitionError;
 pub use instructions::*;
 
+use states::ScoringMode;
+
 declare_id!("2AxuNr6euZPKQbTwNsLBjzFTZFAevA85F4PW9m9Dv8pc");
 
 #[program]
@@ -14,6 +16,9 @@ pub mod gmsol_competition {
     use super::*;
 
     /// Initialize the global [`Competition`](crate::states::Competition) PDA.
+    ///
+    /// The `scoring_mode` decides how participants are ranked. The time
+    /// extension is always triggered by the traded volume.
     #[allow(clippy::too_many_arguments)]
     pub fn initialize_competition(
         ctx: Context<InitializeCompetition>,
@@ -24,6 +29,7 @@ pub mod gmsol_competition {
         extension_cap: i64,
         only_count_increase: bool,
         volume_merge_window: i64,
+        scoring_mode: ScoringMode,
     ) -> Result<()> {
         InitializeCompetition::invoke(
             ctx,
@@ -34,6 +40,7 @@ pub mod gmsol_competition {
             extension_cap,
             only_count_increase,
             volume_merge_window,
+            scoring_mode,
         )
     }
 
@@ -113,6 +120,61 @@ pub mod gmsol_competition {
     pub fn close_participant(ctx: Context<CloseParticipant>) -> Result<()> {
         CloseParticipant::invoke(ctx)
     }
+
+    /// Append ranked participants to the final standings.
+    ///
+    /// The [`Participant`](crate::states::Participant) accounts must be passed
+    /// as remaining accounts in strictly descending order.
+    pub fn append_leaderboard_page<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AppendLeaderboardPage<'info>>,
+        page_index: u32,
+    ) -> Result<()> {
+        AppendLeaderboardPage::invoke(ctx, page_index)
+    }
+
+    /// Finalize the standings of a competition.
+    pub fn finalize_standings(ctx: Context<FinalizeStandings>) -> Result<()> {
+        FinalizeStandings::invoke(ctx)
+    }
+
+    /// Migrate a [`Competition`](crate::states::Competition) account from the legacy layout.
+    pub fn migrate_competition(ctx: Context<MigrateCompetition>) -> Result<()> {
+        MigrateCompetition::invoke(ctx)
+    }
+
+    /// Migrate a [`Participant`](crate::states::Participant) account from the legacy layout.
+    pub fn migrate_participant(ctx: Context<MigrateParticipant>) -> Result<()> {
+        MigrateParticipant::invoke(ctx)
+    }
+
+    /// Initialize the [`PrizePool`](crate::states::PrizePool) of a competition.
+    pub fn initialize_prize_pool(
+        ctx: Context<InitializePrizePool>,
+        schedule: Vec<u16>,
+    ) -> Result<()> {
+        InitializePrizePool::invoke(ctx, schedule)
+    }
+
+    /// Fund the [`PrizePool`](crate::states::PrizePool) of a competition.
+    pub fn fund_prize_pool(ctx: Context<FundPrizePool>, amount: u64) -> Result<()> {
+        FundPrizePool::invoke(ctx, amount)
+    }
+
+    /// Pay the prizes to the ranked participants of the given leaderboard page.
+    ///
+    /// The token accounts of the traders must be passed as remaining accounts
+    /// in rank order.
+    pub fn distribute_prizes<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributePrizes<'info>>,
+        page_index: u32,
+    ) -> Result<()> {
+        DistributePrizes::invoke(ctx, page_index)
+    }
+
+    /// Reclaim the remaining prizes after the distribution is completed.
+    pub fn reclaim_prizes(ctx: Context<ReclaimPrizes>) -> Result<()> {
+        ReclaimPrizes::invoke(ctx)
+    }
 }
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -11,10 +11,55 @@ pub const COMPETITION_SEED: &[u8] = b"competition";
 #[constant]
 pub const PARTICIPANT_SEED: &[u8] = b"participant";
 
+/// The seed for [`LeaderboardPage`] account.
+#[constant]
+pub const LEADERBOARD_PAGE_SEED: &[u8] = b"leaderboard_page";
+
+/// The seed for [`PrizePool`] account.
+#[constant]
+pub const PRIZE_POOL_SEED: &[u8] = b"prize_pool";
+
+/// The seed for the prize vault.
+#[constant]
+pub const PRIZE_VAULT_SEED: &[u8] = b"prize_vault";
+
 /// The maximum number of leaderboard entries kept on chain.
 #[constant]
 pub const MAX_LEADERBOARD_LEN: u8 = 5;
 
+/// The maximum number of entries in a single [`LeaderboardPage`].
+#[constant]
+pub const LEADERBOARD_PAGE_LEN: u8 = 32;
+
+/// The maximum number of ranks that can be rewarded by a [`PrizePool`].
+#[constant]
+pub const MAX_PRIZE_RANKS: u8 = 64;
+
+/// The denominator of the prize schedule (in basis points).
+#[constant]
+pub const PRIZE_SCHEDULE_DENOMINATOR: u16 = 10_000;
+
+/// The number of decimals of ROI scores.
+#[constant]
+pub const ROI_DECIMALS: u8 = 8;
+
+/// The maximum number of positions opened during the competition tracked for a participant.
+#[constant]
+pub const MAX_ENTERED_POSITIONS: u8 = 16;
+
+/// The scoring mode of a competition.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ScoringMode {
+    /// Rank by the cumulative traded volume.
+    #[default]
+    Volume,
+    /// Rank by the realized PnL net of fees.
+    Pnl,
+    /// Rank by the realized PnL net of fees divided by the collateral value put in.
+    Roi,
+}
+
 /// A single leaderboard record.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -23,6 +68,26 @@ pub struct LeaderEntry {
     pub address: Pubkey,
     /// The cumulative traded volume.
     pub volume: u128,
+    /// The score under the scoring mode of the competition.
+    pub score: i128,
+}
+
+impl LeaderEntry {
+    /// Create an entry for the given participant.
+    pub fn new(participant: &Participant, mode: ScoringMode) -> Self {
+        Self {
+            address: participant.trader,
+            volume: participant.volume,
+            score: participant.score(mode),
+        }
+    }
+
+    /// Returns whether this entry must be ranked strictly before `other`.
+    ///
+    /// Entries are ordered by score (descending) and then by address (ascending).
+    pub fn ranks_before(&self, other: &Self) -> bool {
+        self.score > other.score || (self.score == other.score && self.address < other.address)
+    }
 }
 
 /// The global competition data.
@@ -53,6 +118,16 @@ pub struct Competition {
     pub only_count_increase: bool,
     /// Time window in seconds for merging volumes from the same trader.
     pub volume_merge_window: i64,
+    /// The number of participants.
+    pub participant_count: u32,
+    /// The number of participants ranked in the leaderboard pages.
+    pub ranked_count: u32,
+    /// The last ranked entry.
+    pub last_ranked: Option<LeaderEntry>,
+    /// The scoring mode.
+    pub scoring_mode: ScoringMode,
+    /// Whether the final standings have been finalized by the authority.
+    pub standings_finalized: bool,
 }
 
 impl Competition {
@@ -60,6 +135,21 @@ impl Competition {
     pub fn is_ongoing(&self, now: i64) -> bool {
         now >= self.start_time && now <= self.end_time
     }
+
+    /// Returns whether the competition has ended at the given time.
+    pub fn is_ended(&self, now: i64) -> bool {
+        now > self.end_time
+    }
+
+    /// Returns whether the final standings are complete at the given time.
+    pub fn is_finalized(&self, now: i64) -> bool {
+        self.is_ended(now) && self.standings_finalized
+    }
+
+    /// Returns the index of the leaderboard page that the next ranked entry goes to.
+    pub fn next_page_index(&self) -> u32 {
+        self.ranked_count / u32::from(LEADERBOARD_PAGE_LEN)
+    }
 }
 
 /// The per-trader statistics.
@@ -79,4 +169,134 @@ pub struct Participant {
     pub last_updated_at: i64,
     /// The merged volume within the time window.
     pub merged_volume: u128,
+    /// The cumulative realized PnL, net of the fees paid.
+    pub realized_pnl: i128,
+    /// The cumulative value of collateral put into positions.
+    pub collateral_value: u128,
+    /// The positions opened during the competition.
+    /// Only the PnL realized from these positions is counted.
+    #[max_len(MAX_ENTERED_POSITIONS)]
+    pub entered_positions: Vec<Pubkey>,
+}
+
+impl Participant {
+    /// Returns whether the given position was opened during the competition.
+    pub fn is_entered(&self, position: &Pubkey) -> bool {
+        self.entered_positions.contains(position)
+    }
+
+    /// Record that the given position is opened during the competition.
+    ///
+    /// Returns `false` if there is no room for the position.
+    pub(crate) fn enter_position(&mut self, position: &Pubkey) -> bool {
+        if self.is_entered(position) {
+            return true;
+        }
+        if self.entered_positions.len() >= usize::from(MAX_ENTERED_POSITIONS) {
+            return false;
+        }
+        self.entered_positions.push(*position);
+        true
+    }
+
+    /// Remove the given position from the entered positions once it is closed.
+    pub(crate) fn exit_position(&mut self, position: &Pubkey) {
+        self.entered_positions.retain(|entered| entered != position);
+    }
+
+    /// Returns the score under the given scoring mode.
+    pub fn score(&self, mode: ScoringMode) -> i128 {
+        match mode {
+            ScoringMode::Volume => i128::try_from(self.volume).unwrap_or(i128::MAX),
+            ScoringMode::Pnl => self.realized_pnl,
+            ScoringMode::Roi => {
+                if self.collateral_value == 0 {
+                    return 0;
+                }
+                let collateral_value = i128::try_from(self.collateral_value).unwrap_or(i128::MAX);
+                let unit = 10i128.pow(u32::from(ROI_DECIMALS));
+                match self.realized_pnl.checked_mul(unit) {
+                    Some(value) => value / collateral_value,
+                    None => (self.realized_pnl / collateral_value).saturating_mul(unit),
+                }
+            }
+        }
+    }
+}
+
+/// A page of the final standings.
+///
+/// Pages are appended after the competition ends, and the entries across
+/// all pages are kept in strictly descending order.
+#[account]
+#[derive(InitSpace)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct LeaderboardPage {
+    /// Bump seed.
+    pub bump: u8,
+    /// The competition account this page belongs to.
+    pub competition: Pubkey,
+    /// The index of this page.
+    pub index: u32,
+    /// The ranked entries.
+    #[max_len(LEADERBOARD_PAGE_LEN)]
+    pub entries: Vec<LeaderEntry>,
+}
+
+impl LeaderboardPage {
+    /// Returns the rank (starting from `0`) of the first entry of this page.
+    pub fn first_rank(&self) -> u32 {
+        self.index.saturating_mul(u32::from(LEADERBOARD_PAGE_LEN))
+    }
+}
+
+/// The prize pool of a competition.
+#[account]
+#[derive(InitSpace)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PrizePool {
+    /// Bump seed.
+    pub bump: u8,
+    /// The competition account this prize pool belongs to.
+    pub competition: Pubkey,
+    /// The prize token mint.
+    pub mint: Pubkey,
+    /// The prize vault.
+    pub vault: Pubkey,
+    /// The total prize amount, snapshotted when the distribution starts.
+    pub total_amount: u64,
+    /// The number of ranks that have been paid.
+    pub distributed_count: u32,
+    /// The share of the total prize (in basis points) for each rank.
+    #[max_len(MAX_PRIZE_RANKS)]
+    pub schedule: Vec<u16>,
+}
+
+impl PrizePool {
+    /// Get signer seeds.
+    pub(crate) fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            PRIZE_POOL_SEED,
+            self.competition.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    /// Returns whether the distribution has started.
+    pub fn is_distribution_started(&self) -> bool {
+        self.total_amount != 0 || self.distributed_count != 0
+    }
+
+    /// Returns the number of ranks to be paid given the number of ranked participants.
+    pub fn rewarded_count(&self, ranked_count: u32) -> u32 {
+        (self.schedule.len() as u32).min(ranked_count)
+    }
+
+    /// Returns the prize amount of the given rank (starting from `0`).
+    pub fn prize_for(&self, rank: u32) -> Option<u64> {
+        let share = *self.schedule.get(rank as usize)?;
+        let amount = u128::from(self.total_amount) * u128::from(share)
+            / u128::from(PRIZE_SCHEDULE_DENOMINATOR);
+        amount.try_into().ok()
+    }
 }
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum GtIncentiveError {
+    #[msg("outside campaign time")]
+    OutsideCampaignTime,
+    #[msg("invalid trade event")]
+    InvalidTradeEvent,
+    #[msg("invalid action kind")]
+    InvalidActionKind,
+    #[msg("invalid time range")]
+    InvalidTimeRange,
+    #[msg("invalid amount")]
+    InvalidAmount,
+    #[msg("campaign is still in progress")]
+    CampaignInProgress,
+    #[msg("there are unclaimed rewards")]
+    UnclaimedRewards,
+    #[msg("invalid GT user account")]
+    InvalidUser,
+    #[msg("math overflow")]
+    MathOverflow,
+    #[msg("permission denied")]
+    PermissionDenied,
+    #[msg("reward budget exceeded")]
+    RewardBudgetExceeded,
+}
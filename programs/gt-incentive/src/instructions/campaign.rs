@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::GtIncentiveError,
+    states::{Campaign, Config, CAMPAIGN_SEED},
+};
+
+/// Initialize a [`Campaign`] account.
+///
+/// Must be invoked by the config authority before the epoch ends.
+#[derive(Accounts)]
+#[instruction(start_time: i64)]
+pub struct InitializeCampaign<'info> {
+    /// Payer and the authority of the config.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The config account.
+    #[account(has_one = authority)]
+    pub config: Account<'info, Config>,
+    /// The campaign PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Campaign::INIT_SPACE,
+        seeds = [
+            CAMPAIGN_SEED,
+            config.key().as_ref(),
+            &start_time.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeCampaign<'_> {
+    pub(crate) fn invoke(
+        ctx: Context<Self>,
+        start_time: i64,
+        end_time: i64,
+        only_count_increase: bool,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(start_time < end_time, GtIncentiveError::InvalidTimeRange);
+        require!(end_time > now, GtIncentiveError::InvalidTimeRange);
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.bump = ctx.bumps.campaign;
+        campaign.config = ctx.accounts.config.key();
+        campaign.start_time = start_time;
+        campaign.end_time = end_time;
+        campaign.only_count_increase = only_count_increase;
+        campaign.total_rewards = 0;
+        campaign.claimed_rewards = 0;
+        campaign.total_volume = 0;
+        Ok(())
+    }
+}
+
+/// Fund a [`Campaign`] with GT rewards.
+///
+/// The funded amount is the total GT to be minted to the participants,
+/// and can be increased at any time, even after the epoch ends. The total
+/// amount funded to all campaigns is capped by the reward budget of the config.
+#[derive(Accounts)]
+pub struct FundCampaign<'info> {
+    /// The authority of the config.
+    pub authority: Signer<'info>,
+    /// The config account.
+    #[account(mut, has_one = authority)]
+    pub config: Account<'info, Config>,
+    /// The campaign to fund.
+    #[account(mut, has_one = config)]
+    pub campaign: Account<'info, Campaign>,
+}
+
+impl FundCampaign<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>, amount: u64) -> Result<()> {
+        require_gt!(amount, 0, GtIncentiveError::InvalidAmount);
+
+        let config = &mut ctx.accounts.config;
+        let funded_rewards = config
+            .funded_rewards
+            .checked_add(amount)
+            .ok_or_else(|| error!(GtIncentiveError::MathOverflow))?;
+        require_gte!(
+            config.reward_budget,
+            funded_rewards,
+            GtIncentiveError::RewardBudgetExceeded
+        );
+        config.funded_rewards = funded_rewards;
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.total_rewards = campaign
+            .total_rewards
+            .checked_add(amount)
+            .ok_or_else(|| error!(GtIncentiveError::MathOverflow))?;
+
+        msg!(
+            "gt-incentive: funded campaign with amount={}, total_rewards={}, funded_rewards={}",
+            amount,
+            campaign.total_rewards,
+            funded_rewards
+        );
+        Ok(())
+    }
+}
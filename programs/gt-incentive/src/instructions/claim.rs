@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use gmsol_programs::gmsol_store::{
+    accounts::{Store, UserHeader},
+    cpi::{accounts::MintGtReward, mint_gt_reward},
+    program::GmsolStore,
+};
+
+use crate::{
+    error::GtIncentiveError,
+    states::{Campaign, Config, Participant, PARTICIPANT_SEED},
+};
+
+/// Claim the GT rewards of a [`Participant`].
+///
+/// Rewards can only be claimed after the campaign ends and are minted
+/// through the `mint_gt_reward` instruction of the store program.
+#[derive(Accounts)]
+pub struct ClaimReward<'info> {
+    /// The trader that owns the participant account.
+    pub trader: Signer<'info>,
+    /// The config account.
+    #[account(has_one = store)]
+    pub config: Account<'info, Config>,
+    /// The campaign account.
+    #[account(mut, has_one = config)]
+    pub campaign: Account<'info, Campaign>,
+    /// The participant PDA.
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_SEED,
+            campaign.key().as_ref(),
+            trader.key().as_ref(),
+        ],
+        bump = participant.bump,
+        has_one = campaign,
+        has_one = trader,
+    )]
+    pub participant: Account<'info, Participant>,
+    /// The store account (mutated by CPI).
+    #[account(mut)]
+    pub store: AccountLoader<'info, Store>,
+    /// The GT user account of the trader.
+    #[account(
+        mut,
+        has_one = store,
+        constraint = gt_user.load()?.owner == trader.key() @ GtIncentiveError::InvalidUser,
+    )]
+    pub gt_user: AccountLoader<'info, UserHeader>,
+    /// CHECK: The event authority of the store program, checked by the CPI.
+    pub event_authority: UncheckedAccount<'info>,
+    /// The store program.
+    pub gt_program: Program<'info, GmsolStore>,
+}
+
+impl ClaimReward<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.campaign.is_ended(now),
+            GtIncentiveError::CampaignInProgress
+        );
+
+        let amount = ctx
+            .accounts
+            .participant
+            .claimable_rewards(&ctx.accounts.campaign)
+            .ok_or_else(|| error!(GtIncentiveError::MathOverflow))?;
+
+        if amount == 0 {
+            msg!("gt-incentive: no rewards to claim");
+            return Ok(());
+        }
+
+        let participant = &mut ctx.accounts.participant;
+        participant.claimed_rewards = participant
+            .claimed_rewards
+            .checked_add(amount)
+            .ok_or_else(|| error!(GtIncentiveError::MathOverflow))?;
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.claimed_rewards = campaign
+            .claimed_rewards
+            .checked_add(amount)
+            .ok_or_else(|| error!(GtIncentiveError::MathOverflow))?;
+
+        ctx.accounts.mint_gt_reward(amount)?;
+
+        msg!(
+            "gt-incentive: trader={} claimed={} volume={}",
+            ctx.accounts.participant.trader,
+            amount,
+            ctx.accounts.participant.volume
+        );
+        Ok(())
+    }
+
+    fn mint_gt_reward(&self, amount: u64) -> Result<()> {
+        let signer_seeds = self.config.signer_seeds();
+        let ctx = CpiContext::new(
+            self.gt_program.to_account_info(),
+            MintGtReward {
+                authority: self.config.to_account_info(),
+                store: self.store.to_account_info(),
+                user: self.gt_user.to_account_info(),
+                event_authority: self.event_authority.to_account_info(),
+                program: self.gt_program.to_account_info(),
+            },
+        );
+        mint_gt_reward(ctx.with_signer(&[&signer_seeds]), amount)
+    }
+}
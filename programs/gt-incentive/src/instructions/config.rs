@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use gmsol_programs::gmsol_store::{
+    accounts::Store,
+    cpi::{accounts::CheckAdmin, check_admin},
+    program::GmsolStore,
+};
+
+use crate::{
+    error::GtIncentiveError,
+    states::{Config, CONFIG_SEED},
+};
+
+/// Initialize the [`Config`] account for the given store.
+///
+/// Only the store admin can initialize the config, and becomes its authority.
+/// The config address must then be granted the `GT_CONTROLLER` role
+/// in the store before any rewards can be claimed.
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    /// Payer and the authority of the config. Must be the store admin.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The config PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [CONFIG_SEED, store.key().as_ref()],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// The store program.
+    pub store_program: Program<'info, GmsolStore>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl Initialize<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>, reward_budget: u64) -> Result<()> {
+        only_store_admin(
+            &ctx.accounts.store_program,
+            &ctx.accounts.authority,
+            ctx.accounts.store.as_ref(),
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        config.bump = ctx.bumps.config;
+        config.store = ctx.accounts.store.key();
+        config.authority = ctx.accounts.authority.key();
+        config.reward_budget = reward_budget;
+        config.funded_rewards = 0;
+        msg!(
+            "gt-incentive: initialized config for store={} with authority={}, reward_budget={}",
+            config.store,
+            config.authority,
+            config.reward_budget
+        );
+        Ok(())
+    }
+}
+
+/// Set the GT reward budget of the [`Config`].
+///
+/// Only the store admin can set the budget, which caps the total GT rewards
+/// that the config authority can fund to the campaigns.
+#[derive(Accounts)]
+pub struct SetRewardBudget<'info> {
+    /// The store admin.
+    pub authority: Signer<'info>,
+    /// The store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The config account.
+    #[account(mut, has_one = store)]
+    pub config: Account<'info, Config>,
+    /// The store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+impl SetRewardBudget<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>, reward_budget: u64) -> Result<()> {
+        only_store_admin(
+            &ctx.accounts.store_program,
+            &ctx.accounts.authority,
+            ctx.accounts.store.as_ref(),
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        require_gte!(
+            reward_budget,
+            config.funded_rewards,
+            GtIncentiveError::InvalidAmount
+        );
+        config.reward_budget = reward_budget;
+        msg!(
+            "gt-incentive: set reward_budget={}, funded_rewards={}",
+            config.reward_budget,
+            config.funded_rewards
+        );
+        Ok(())
+    }
+}
+
+/// Check that the `authority` is the admin of the `store` through CPI.
+fn only_store_admin<'info>(
+    store_program: &Program<'info, GmsolStore>,
+    authority: &Signer<'info>,
+    store: &AccountInfo<'info>,
+) -> Result<()> {
+    let is_admin = check_admin(CpiContext::new(
+        store_program.to_account_info(),
+        CheckAdmin {
+            authority: authority.to_account_info(),
+            store: store.clone(),
+        },
+    ))?
+    .get();
+    require!(is_admin, GtIncentiveError::PermissionDenied);
+    Ok(())
+}
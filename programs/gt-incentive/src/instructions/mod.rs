@@ -0,0 +1,22 @@
+//! Instruction dispatch table for the gmsol-gt-incentive program.
+
+/// Initialize the [`Config`](crate::states::Config) account.
+pub mod config;
+
+/// Create and fund [`Campaign`](crate::states::Campaign) accounts.
+pub mod campaign;
+
+/// Lazily create a [`Participant`](crate::states::Participant) PDA.
+pub mod participant;
+
+/// Callback entry invoked by the GMX-Solana store program on each trade.
+pub mod trade_callback;
+
+/// Claim GT rewards.
+pub mod claim;
+
+pub use campaign::*;
+pub use claim::*;
+pub use config::*;
+pub use participant::*;
+pub use trade_callback::*;
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::GtIncentiveError,
+    states::{Campaign, Participant, PARTICIPANT_SEED},
+};
+
+/// Create [`Participant`] account idempotently.
+///
+/// Must be called before the first trade of a trader in the campaign.
+/// If the account already exists the call is a no-op.
+#[derive(Accounts)]
+pub struct CreateParticipantIdempotent<'info> {
+    /// Payer that funds the new PDA when it does **not** exist.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The campaign account this participant belongs to.
+    pub campaign: Account<'info, Campaign>,
+    /// The participant PDA.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Participant::INIT_SPACE,
+        seeds = [
+            PARTICIPANT_SEED,
+            campaign.key().as_ref(),
+            trader.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub participant: Account<'info, Participant>,
+    /// The trader address.
+    /// CHECK: Only the address is required.
+    pub trader: UncheckedAccount<'info>,
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateParticipantIdempotent<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let bump = ctx.bumps.participant;
+        let campaign = ctx.accounts.campaign.key();
+        let trader = ctx.accounts.trader.key();
+        let p = &mut ctx.accounts.participant;
+        let default_pubkey = Pubkey::default();
+        if p.trader == default_pubkey {
+            require_keys_neq!(trader, default_pubkey);
+
+            p.bump = bump;
+            p.campaign = campaign;
+            p.trader = trader;
+            p.volume = 0;
+            p.claimed_rewards = 0;
+            p.last_updated_at = Clock::get()?.unix_timestamp;
+        }
+        Ok(())
+    }
+}
+
+/// Close the [`Participant`] account.
+///
+/// This instruction can be called by the trader to close their participant account
+/// and recover the rent, once the campaign is not in progress and all rewards
+/// have been claimed.
+#[derive(Accounts)]
+pub struct CloseParticipant<'info> {
+    /// The trader that owns the participant account.
+    #[account(mut)]
+    pub trader: Signer<'info>,
+    /// The campaign account this participant belongs to.
+    pub campaign: Account<'info, Campaign>,
+    /// The participant PDA to close.
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_SEED,
+            campaign.key().as_ref(),
+            trader.key().as_ref(),
+        ],
+        bump = participant.bump,
+        has_one = campaign,
+        has_one = trader,
+        close = trader,
+    )]
+    pub participant: Account<'info, Participant>,
+}
+
+impl CloseParticipant<'_> {
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let campaign = &ctx.accounts.campaign;
+
+        require!(
+            !campaign.is_ongoing(now),
+            GtIncentiveError::CampaignInProgress
+        );
+
+        let claimable = ctx
+            .accounts
+            .participant
+            .claimable_rewards(campaign)
+            .ok_or_else(|| error!(GtIncentiveError::MathOverflow))?;
+        require_eq!(claimable, 0, GtIncentiveError::UnclaimedRewards);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+use anchor_lang::prelude::*;
+use gmsol_callback::{interface::ActionKind, CALLBACK_AUTHORITY_SEED};
+use gmsol_programs::gmsol_store::accounts::TradeData;
+
+use crate::{
+    error::GtIncentiveError,
+    states::{Campaign, Participant, CALLER_PROGRAM_ID, PARTICIPANT_SEED},
+};
+
+/// Accounts for `on_created`.
+#[derive(Accounts)]
+#[instruction(authority_bump: u8)]
+pub struct OnCreated<'info> {
+    /// The callback-authority PDA (must be a signer).
+    #[account(
+        seeds = [CALLBACK_AUTHORITY_SEED],
+        bump = authority_bump,
+        seeds::program = CALLER_PROGRAM_ID,
+    )]
+    pub authority: Signer<'info>,
+    /// The campaign account.
+    pub campaign: Account<'info, Campaign>,
+    /// The participant PDA.
+    #[account(
+        seeds = [
+            PARTICIPANT_SEED,
+            campaign.key().as_ref(),
+            trader.key().as_ref(),
+        ],
+        bump = participant.bump,
+        has_one = campaign,
+        has_one = trader,
+    )]
+    pub participant: Account<'info, Participant>,
+    /// The trader public key.
+    /// CHECK: Only the address is required.
+    pub trader: UncheckedAccount<'info>,
+    /// The action account.
+    /// CHECK: this is just a placeholder.
+    pub action: UncheckedAccount<'info>,
+}
+
+impl OnCreated<'_> {
+    pub(crate) fn invoke(
+        ctx: Context<Self>,
+        _authority_bump: u8,
+        action_kind: u8,
+        callback_version: u8,
+        _extra_account_count: u8,
+    ) -> Result<()> {
+        // Only callback version `0` is supported.
+        require_eq!(callback_version, 0);
+        // Only setup callback for orders.
+        require_eq!(
+            action_kind,
+            ActionKind::Order as u8,
+            GtIncentiveError::InvalidActionKind
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !ctx.accounts.campaign.is_ended(now),
+            GtIncentiveError::OutsideCampaignTime
+        );
+        Ok(())
+    }
+}
+
+/// Accounts for `on_executed`.
+#[derive(Accounts)]
+#[instruction(authority_bump: u8)]
+pub struct OnExecuted<'info> {
+    /// The callback-authority PDA (must be a signer).
+    #[account(
+        seeds = [CALLBACK_AUTHORITY_SEED],
+        bump = authority_bump,
+        seeds::program = CALLER_PROGRAM_ID,
+    )]
+    pub authority: Signer<'info>,
+    /// The campaign account.
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    /// The participant PDA.
+    /// CHECK: Validation is performed only during the campaign.
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_SEED,
+            campaign.key().as_ref(),
+            trader.key().as_ref(),
+        ],
+        bump
+    )]
+    pub participant: UncheckedAccount<'info>,
+    /// The trader public key.
+    /// CHECK: Only the address is required.
+    pub trader: UncheckedAccount<'info>,
+    /// The action account.
+    /// CHECK: this is just a placeholder.
+    pub action: UncheckedAccount<'info>,
+    /// CHECK: this is just a placeholder.
+    pub position: UncheckedAccount<'info>,
+    /// Trade event data.
+    pub trade_event: Option<AccountLoader<'info, TradeData>>,
+}
+
+impl OnExecuted<'_> {
+    /// Core entry point called by the store program.
+    pub(crate) fn invoke(
+        ctx: Context<Self>,
+        _authority_bump: u8,
+        action_kind: u8,
+        callback_version: u8,
+        success: bool,
+        extra_account_count: u8,
+    ) -> Result<()> {
+        // Validate callback parameters.
+        require_eq!(callback_version, 0);
+        require_eq!(
+            action_kind,
+            ActionKind::Order as u8,
+            GtIncentiveError::InvalidActionKind
+        );
+        require_gte!(extra_account_count, 2);
+
+        // Only process successful order actions.
+        if !success {
+            msg!("gt-incentive: ignore failed order");
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+
+        if !ctx.accounts.campaign.is_ongoing(now) {
+            msg!("gt-incentive: outside of the campaign time");
+            return Ok(());
+        }
+
+        // Get volume from the trade event.
+        let volume = {
+            let Some(trade_event) = &ctx.accounts.trade_event else {
+                msg!("gt-incentive: no trade event");
+                return Ok(());
+            };
+            let trade_event = trade_event
+                .load()
+                .map_err(|_| GtIncentiveError::InvalidTradeEvent)?;
+
+            // Validate that the trade event belongs to the trader.
+            require_keys_eq!(
+                trade_event.user,
+                ctx.accounts.trader.key(),
+                GtIncentiveError::InvalidTradeEvent
+            );
+
+            let volume = if ctx.accounts.campaign.only_count_increase {
+                trade_event
+                    .after
+                    .size_in_usd
+                    .saturating_sub(trade_event.before.size_in_usd)
+            } else {
+                trade_event
+                    .after
+                    .size_in_usd
+                    .abs_diff(trade_event.before.size_in_usd)
+            };
+
+            // Skip trades with zero volume.
+            if volume == 0 {
+                msg!("gt-incentive: skipped trade with zero volume");
+                return Ok(());
+            }
+            volume
+        };
+
+        ctx.accounts.with_participant(|campaign, part| {
+            part.volume = part.volume.saturating_add(volume);
+            part.last_updated_at = now;
+            campaign.total_volume = campaign.total_volume.saturating_add(volume);
+
+            msg!(
+                "gt-incentive: trader={} new_volume={} volume_delta={} total_volume={}",
+                part.trader,
+                part.volume,
+                volume,
+                campaign.total_volume
+            );
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    fn with_participant(
+        &mut self,
+        f: impl FnOnce(&mut Campaign, &mut Participant) -> Result<()>,
+    ) -> Result<()> {
+        let AccountInfo {
+            key,
+            lamports,
+            data,
+            owner,
+            rent_epoch,
+            is_signer,
+            is_writable,
+            executable,
+        } = self.participant.as_ref();
+        let mut lamports = lamports.borrow_mut();
+        let mut data = data.borrow_mut();
+        let info = AccountInfo::new(
+            key,
+            *is_signer,
+            *is_writable,
+            *lamports,
+            *data,
+            owner,
+            *executable,
+            *rent_epoch,
+        );
+        let mut participant = Account::<Participant>::try_from(&info)?;
+        require_keys_eq!(participant.trader, self.trader.key());
+        require_keys_eq!(participant.campaign, self.campaign.key());
+        (f)(&mut self.campaign, &mut participant)?;
+        participant.exit(&crate::ID)?;
+        Ok(())
+    }
+}
+
+/// Accounts for other callbacks.
+#[derive(Accounts)]
+#[instruction(authority_bump: u8)]
+pub struct OnCallback<'info> {
+    /// The callback-authority PDA (must be a signer).
+    #[account(
+        seeds = [CALLBACK_AUTHORITY_SEED],
+        bump = authority_bump,
+        seeds::program = CALLER_PROGRAM_ID,
+    )]
+    pub authority: Signer<'info>,
+    /// CHECK: No need to validate the campaign account.
+    pub campaign: UncheckedAccount<'info>,
+    /// CHECK: No need to validate the participant account.
+    pub participant: UncheckedAccount<'info>,
+    /// The trader public key.
+    /// CHECK: Only the address is required.
+    pub trader: UncheckedAccount<'info>,
+    /// The action account.
+    /// CHECK: this is just a placeholder.
+    pub action: UncheckedAccount<'info>,
+}
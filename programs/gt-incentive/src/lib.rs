@@ -1,23 +1,136 @@
 use anchor_lang::prelude::*;
 
+pub mod error;
+pub mod instructions;
+pub mod states;
+
+pub use error::GtIncentiveError;
+pub use instructions::*;
+
 declare_id!("6iMXVSjiBf75ce9QaUVRnpsUikW8SZm8EaPVGDPfsCso");
 
 #[program]
 pub mod gmsol_gt_incentive {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        msg!("Greetings from: {:?}", ctx.program_id);
+    /// Initialize the [`Config`](crate::states::Config) PDA for the given store.
+    ///
+    /// Only the store admin is allowed to invoke. The `reward_budget` caps the total
+    /// GT rewards that can be funded to the campaigns.
+    pub fn initialize(ctx: Context<Initialize>, reward_budget: u64) -> Result<()> {
+        Initialize::invoke(ctx, reward_budget)
+    }
+
+    /// Set the GT reward budget of the [`Config`](crate::states::Config).
+    ///
+    /// Only the store admin is allowed to invoke. The budget cannot be lower than
+    /// the rewards that have already been funded.
+    pub fn set_reward_budget(ctx: Context<SetRewardBudget>, reward_budget: u64) -> Result<()> {
+        SetRewardBudget::invoke(ctx, reward_budget)
+    }
+
+    /// Initialize a [`Campaign`](crate::states::Campaign) PDA.
+    pub fn initialize_campaign(
+        ctx: Context<InitializeCampaign>,
+        start_time: i64,
+        end_time: i64,
+        only_count_increase: bool,
+    ) -> Result<()> {
+        InitializeCampaign::invoke(ctx, start_time, end_time, only_count_increase)
+    }
+
+    /// Increase the GT rewards of a [`Campaign`](crate::states::Campaign).
+    ///
+    /// The total funded rewards cannot exceed the reward budget of the config.
+    pub fn fund_campaign(ctx: Context<FundCampaign>, amount: u64) -> Result<()> {
+        FundCampaign::invoke(ctx, amount)
+    }
+
+    /// Create [`Participant`](crate::states::Participant) PDA idempotently.
+    pub fn create_participant_idempotent(ctx: Context<CreateParticipantIdempotent>) -> Result<()> {
+        CreateParticipantIdempotent::invoke(ctx)
+    }
+
+    // ---------------------------------------------------------------------
+    // Callbacks expected by the GMX-Solana store-program
+    // ---------------------------------------------------------------------
+
+    /// Triggered immediately **after an order is created**.
+    /// Rejects orders created after the campaign ends.
+    pub fn on_created(
+        ctx: Context<OnCreated>,
+        authority_bump: u8,
+        action_kind: u8,
+        callback_version: u8,
+        extra_account_count: u8,
+    ) -> Result<()> {
+        OnCreated::invoke(
+            ctx,
+            authority_bump,
+            action_kind,
+            callback_version,
+            extra_account_count,
+        )
+    }
+
+    /// Triggered when an order is updated.
+    /// Currently ignored by the GT incentive program.
+    pub fn on_updated(
+        _ctx: Context<OnCallback>,
+        _authority_bump: u8,
+        _action_kind: u8,
+        _callback_version: u8,
+        _extra_account_count: u8,
+    ) -> Result<()> {
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Triggered when an order is **executed**.
+    /// Accumulates the trading volume of the participant and the campaign.
+    pub fn on_executed(
+        ctx: Context<OnExecuted>,
+        authority_bump: u8,
+        action_kind: u8,
+        callback_version: u8,
+        success: bool,
+        extra_account_count: u8,
+    ) -> Result<()> {
+        OnExecuted::invoke(
+            ctx,
+            authority_bump,
+            action_kind,
+            callback_version,
+            success,
+            extra_account_count,
+        )
+    }
 
-    pub system_program: Program<'info, System>,
+    /// Triggered when an order is **closed / cancelled**.
+    /// Currently ignored by the GT incentive program.
+    pub fn on_closed(
+        _ctx: Context<OnCallback>,
+        _authority_bump: u8,
+        _action_kind: u8,
+        _callback_version: u8,
+        _extra_account_count: u8,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Claim the GT rewards of the participant once the campaign has ended.
+    ///
+    /// The rewards are shared among the participants in proportion to their
+    /// trading volume and minted via the `mint_gt_reward` instruction of the
+    /// store program, which requires the [`Config`](crate::states::Config)
+    /// to have the `GT_CONTROLLER` role.
+    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
+        ClaimReward::invoke(ctx)
+    }
+
+    /// Close the participant account and recover rent.
+    pub fn close_participant(ctx: Context<CloseParticipant>) -> Result<()> {
+        CloseParticipant::invoke(ctx)
+    }
 }
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use gmsol_model::num::MulDiv;
+
+/// The expected program ID of the caller.
+pub const CALLER_PROGRAM_ID: Pubkey = gmsol_programs::gmsol_store::ID_CONST;
+
+/// The seed for [`Config`] account.
+#[constant]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// The seed for [`Campaign`] account.
+#[constant]
+pub const CAMPAIGN_SEED: &[u8] = b"campaign";
+
+/// The seed for [`Participant`] account.
+#[constant]
+pub const PARTICIPANT_SEED: &[u8] = b"participant";
+
+/// The per-store configuration.
+///
+/// The address of this account is expected to be granted the
+/// `GT_CONTROLLER` role in the store, so that it can mint GT rewards.
+#[account]
+#[derive(InitSpace)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Config {
+    /// Bump seed.
+    pub bump: u8,
+    /// The store this config belongs to.
+    pub store: Pubkey,
+    /// The authority allowed to create and fund campaigns.
+    pub authority: Pubkey,
+    /// The maximum total GT rewards that can be funded to the campaigns,
+    /// set by the store admin.
+    pub reward_budget: u64,
+    /// The total GT rewards funded to the campaigns.
+    pub funded_rewards: u64,
+}
+
+impl Config {
+    pub(crate) fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            CONFIG_SEED,
+            self.store.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
+/// A GT reward campaign covering a single epoch.
+///
+/// The GT rewards of the campaign are shared among the participants
+/// in proportion to their trading volume during the epoch.
+#[account]
+#[derive(InitSpace)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Campaign {
+    /// Bump seed.
+    pub bump: u8,
+    /// The config account this campaign belongs to.
+    pub config: Pubkey,
+    /// The epoch start timestamp.
+    pub start_time: i64,
+    /// The epoch end timestamp.
+    pub end_time: i64,
+    /// Whether to only count volume from position increases.
+    pub only_count_increase: bool,
+    /// The total GT rewards funded.
+    pub total_rewards: u64,
+    /// The total GT rewards claimed.
+    pub claimed_rewards: u64,
+    /// The total trading volume of all participants.
+    pub total_volume: u128,
+}
+
+impl Campaign {
+    /// Returns whether the campaign is ongoing at the given time.
+    pub fn is_ongoing(&self, now: i64) -> bool {
+        now >= self.start_time && now <= self.end_time
+    }
+
+    /// Returns whether the campaign has ended at the given time.
+    pub fn is_ended(&self, now: i64) -> bool {
+        now > self.end_time
+    }
+
+    /// Returns the GT rewards allocated to the given volume.
+    ///
+    /// Returns `None` if overflow.
+    pub fn rewards_for(&self, volume: u128) -> Option<u64> {
+        if self.total_volume == 0 {
+            return Some(0);
+        }
+        u128::from(self.total_rewards)
+            .checked_mul_div(&volume, &self.total_volume)?
+            .try_into()
+            .ok()
+    }
+}
+
+/// The per-trader statistics.
+#[account]
+#[derive(InitSpace)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Participant {
+    /// Bump seed.
+    pub bump: u8,
+    /// The campaign account this entry belongs to.
+    pub campaign: Pubkey,
+    /// The trader address.
+    pub trader: Pubkey,
+    /// The cumulative traded volume.
+    pub volume: u128,
+    /// The GT rewards claimed.
+    pub claimed_rewards: u64,
+    /// The last update timestamp.
+    pub last_updated_at: i64,
+}
+
+impl Participant {
+    /// Returns the GT rewards that can be claimed now.
+    ///
+    /// Returns `None` if overflow.
+    pub fn claimable_rewards(&self, campaign: &Campaign) -> Option<u64> {
+        Some(
+            campaign
+                .rewards_for(self.volume)?
+                .saturating_sub(self.claimed_rewards),
+        )
+    }
+}
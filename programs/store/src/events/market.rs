@@ -8,6 +8,7 @@ use gmsol_model::{
     },
     PoolKind,
 };
+use gmsol_utils::risk_oracle::MAX_RISK_ORACLE_PARAMETERS;
 
 use crate::states::{
     market::{pool::Pool, Clocks},
@@ -156,9 +157,11 @@ pub struct EventOtherState {
     pub short_token_balance: u64,
     /// Funding factor per second.
     pub funding_factor_per_second: i128,
+    /// Last applied timestamps of the risk oracle parameters.
+    pub risk_oracle_timestamps: [u64; MAX_RISK_ORACLE_PARAMETERS],
     #[cfg_attr(feature = "debug", debug(skip))]
     #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
-    pub(crate) reserved: [u8; 256],
+    pub(crate) reserved: [u8; 192],
 }
 
 static_assertions::const_assert_eq!(EventOtherState::INIT_SPACE, OtherState::INIT_SPACE);
@@ -25,10 +25,14 @@ mod market;
 /// GT events.
 mod gt;
 
+/// Oracle events.
+mod oracle;
+
 pub use deposit::*;
 pub use glv::*;
 pub use gt::*;
 pub use market::*;
+pub use oracle::*;
 pub use order::*;
 pub use shift::*;
 pub use swap::*;
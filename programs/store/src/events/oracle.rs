@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Price circuit breaker tripped event.
+#[event]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PriceCircuitBreakerTripped {
+    /// Event time.
+    pub ts: i64,
+    /// Store account.
+    pub store: Pubkey,
+    /// Token.
+    pub token: Pubkey,
+    /// Reference unit price at the start of the window.
+    pub ref_price: u128,
+    /// The (mid) unit price that tripped the breaker.
+    pub price: u128,
+    /// Start time of the window.
+    pub window_start: i64,
+}
@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
 use gmsol_callback::CALLBACK_AUTHORITY_SEED;
+use gmsol_utils::InitSpace;
 
-use crate::states::callback::CallbackAuthority;
+use crate::{
+    internal,
+    states::{
+        callback::{CallbackAuthority, CallbackProgram, CALLBACK_PROGRAM_SEED},
+        Store,
+    },
+};
 
 /// Initialize the [`CallbackAuthority`] account.
 #[derive(Accounts)]
@@ -28,3 +35,140 @@ impl InitializeCallbackAuthority<'_> {
         Ok(())
     }
 }
+
+/// The accounts definitions for [`register_callback_program`](crate::gmsol_store::register_callback_program).
+#[derive(Accounts)]
+pub struct RegisterCallbackProgram<'info> {
+    /// Authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The callback program to register.
+    /// CHECK: only the address is used.
+    #[account(executable)]
+    pub program: UncheckedAccount<'info>,
+    /// The callback program account to initialize.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CallbackProgram::INIT_SPACE,
+        seeds = [CALLBACK_PROGRAM_SEED, store.key().as_ref(), program.key().as_ref()],
+        bump,
+    )]
+    pub callback_program: AccountLoader<'info, CallbackProgram>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+impl RegisterCallbackProgram<'_> {
+    /// Add the program to the callback program allowlist of the store.
+    ///
+    /// # CHECK
+    /// - Only CONFIG_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_unchecked(
+        ctx: Context<Self>,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> Result<()> {
+        ctx.accounts.callback_program.load_init()?.init(
+            ctx.bumps.callback_program,
+            ctx.accounts.store.key(),
+            ctx.accounts.program.key(),
+            action_kinds,
+            compute_unit_limit,
+        );
+        msg!(
+            "[Callback] registered callback program {}",
+            ctx.accounts.program.key()
+        );
+        Ok(())
+    }
+}
+
+impl<'info> internal::Authentication<'info> for RegisterCallbackProgram<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
+/// The accounts definitions for [`update_callback_program`](crate::gmsol_store::update_callback_program).
+#[derive(Accounts)]
+pub struct UpdateCallbackProgram<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The callback program account to update.
+    #[account(mut, has_one = store)]
+    pub callback_program: AccountLoader<'info, CallbackProgram>,
+}
+
+impl UpdateCallbackProgram<'_> {
+    /// Update the allowed action kinds and the compute unit limit hint.
+    ///
+    /// # CHECK
+    /// - Only CONFIG_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_unchecked(
+        ctx: Context<Self>,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> Result<()> {
+        ctx.accounts
+            .callback_program
+            .load_mut()?
+            .update(action_kinds, compute_unit_limit);
+        Ok(())
+    }
+}
+
+impl<'info> internal::Authentication<'info> for UpdateCallbackProgram<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
+/// The accounts definitions for [`unregister_callback_program`](crate::gmsol_store::unregister_callback_program).
+#[derive(Accounts)]
+pub struct UnregisterCallbackProgram<'info> {
+    /// Authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The callback program account to close.
+    #[account(mut, close = authority, has_one = store)]
+    pub callback_program: AccountLoader<'info, CallbackProgram>,
+}
+
+impl UnregisterCallbackProgram<'_> {
+    /// Remove the program from the callback program allowlist of the store.
+    ///
+    /// # CHECK
+    /// - Only CONFIG_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_unchecked(ctx: Context<Self>) -> Result<()> {
+        msg!(
+            "[Callback] unregistered callback program {}",
+            ctx.accounts.callback_program.load()?.program_id
+        );
+        Ok(())
+    }
+}
+
+impl<'info> internal::Authentication<'info> for UnregisterCallbackProgram<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
-use gmsol_callback::{interface::CallbackInterface, CALLBACK_AUTHORITY_SEED};
+use gmsol_callback::CALLBACK_AUTHORITY_SEED;
 use gmsol_utils::order::OrderKind;
 
 use crate::{
@@ -12,8 +12,8 @@ use crate::{
         execution_fee::PayExecutionFeeOperation,
         market::{MarketTransferInOperation, MarketTransferOutOperation},
         order::{
-            ExecuteOrderOperation, ProcessTransferOutOperation, RemovePosition,
-            ShouldSendTradeEvent,
+            finish_twap_slice, CloseLinkedOrdersOperation, ExecuteOrderOperation,
+            ProcessTransferOutOperation, RemovePosition, ShouldSendTradeEvent,
         },
     },
     states::{
@@ -23,7 +23,7 @@ use crate::{
             swap::SwapActionParamsExt,
         },
         feature::ActionDisabledFlag,
-        order::{Order, TransferOut},
+        order::{Order, TransferOut, TwapRemaining},
         position::Position,
         user::UserHeader,
         Market, Oracle, Seed, Store, TokenMapHeader, TokenMapLoader,
@@ -297,7 +297,9 @@ pub struct ExecuteIncreaseOrSwapOrderV2<'info> {
     )]
     pub callback_authority: Option<Account<'info, CallbackAuthority>>,
     /// Callback program.
-    pub callback_program: Option<Interface<'info, CallbackInterface>>,
+    /// CHECK: checked against the callback program recorded in the order.
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
     /// Config account for callback.
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
@@ -350,18 +352,32 @@ impl<'info> ExecuteIncreaseOrSwapOrderV2<'info> {
         let event_authority = accounts.event_authority.clone();
         let event_emitter = EventEmitter::new(&event_authority, ctx.bumps.event_authority);
 
+        // Only the next slice is executed for TWAP orders.
+        let twap = accounts.order.load_mut()?.params.start_twap_slice()?;
+
         accounts.transfer_tokens_in(&signer, remaining_accounts, &event_emitter)?;
 
         let (is_position_removed, transfer_out, should_send_trade_event) = accounts
-            .perform_execution(remaining_accounts, throw_on_execution_error, &event_emitter)?;
+            .perform_execution(
+                remaining_accounts,
+                throw_on_execution_error,
+                twap,
+                &event_emitter,
+            )?;
 
         let executed = transfer_out.executed();
-        if executed {
+        if !executed {
+            // Must be called before the remaining amounts of a TWAP order are restored.
+            accounts.transfer_tokens_out(remaining_accounts, &event_emitter)?;
+        }
+        let has_remaining_slices = finish_twap_slice(&accounts.order, twap, executed)?;
+        if has_remaining_slices {
+            accounts.process_transfer_out(&transfer_out, &event_emitter)?;
+        } else if executed {
             accounts.order.load_mut()?.header.completed()?;
             accounts.process_transfer_out(&transfer_out, &event_emitter)?;
         } else {
             accounts.order.load_mut()?.header.cancelled()?;
-            accounts.transfer_tokens_out(remaining_accounts, &event_emitter)?;
         }
 
         if should_send_trade_event {
@@ -470,6 +486,7 @@ impl<'info> ExecuteIncreaseOrSwapOrderV2<'info> {
         &mut self,
         remaining_accounts: &'info [AccountInfo<'info>],
         throw_on_execution_error: bool,
+        twap: Option<TwapRemaining>,
         event_emitter: &EventEmitter<'_, 'info>,
     ) -> Result<(RemovePosition, Box<TransferOut>, ShouldSendTradeEvent)> {
         let allow_closed = {
@@ -501,6 +518,7 @@ impl<'info> ExecuteIncreaseOrSwapOrderV2<'info> {
             .position(self.position.as_ref())
             .event(self.event.as_ref())
             .throw_on_execution_error(throw_on_execution_error)
+            .twap(twap)
             .executor(self.authority.to_account_info())
             .allow_closed(allow_closed)
             .event_emitter(*event_emitter)
@@ -593,6 +611,10 @@ impl<'info> ExecuteIncreaseOrSwapOrderV2<'info> {
 ///     markets excluding the current market in the swap params.
 ///   - M+N..M+N+V. `[writable]` V virtual inventory accounts, where V represents the total
 ///     number of unique virtual inventories required by the markets.
+///   - M+N+V..M+N+V+5L. `[writable]` L groups of accounts for closing the linked orders, where L
+///     is the number of orders linked with the order to execute. Each group consists of the
+///     linked order account, its final output token, long token and short token escrow accounts,
+///     and its rent receiver.
 #[event_cpi]
 #[derive(Accounts)]
 #[instruction(recent_timestamp: i64)]
@@ -777,7 +799,9 @@ pub struct ExecuteDecreaseOrderV2<'info> {
     )]
     pub callback_authority: Option<Account<'info, CallbackAuthority>>,
     /// Callback program.
-    pub callback_program: Option<Interface<'info, CallbackInterface>>,
+    /// CHECK: checked against the callback program recorded in the order.
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
     /// Config account for callback.
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
@@ -807,7 +831,11 @@ impl<'info> ExecuteDecreaseOrderV2<'info> {
         throw_on_execution_error: bool,
     ) -> Result<()> {
         let accounts = &mut ctx.accounts;
-        let remaining_accounts = ctx.remaining_accounts;
+        let (remaining_accounts, linked_orders) =
+            CloseLinkedOrdersOperation::split_remaining_accounts(
+                &accounts.order,
+                ctx.remaining_accounts,
+            )?;
 
         let kind = accounts.order.load()?.params().kind()?;
 
@@ -824,13 +852,29 @@ impl<'info> ExecuteDecreaseOrderV2<'info> {
 
         let event_authority = accounts.event_authority.clone();
         let event_emitter = EventEmitter::new(&event_authority, ctx.bumps.event_authority);
+        // Only the next slice is executed for TWAP orders.
+        let twap = accounts.order.load_mut()?.params.start_twap_slice()?;
+
         let (is_position_removed, transfer_out, should_send_trade_event) = accounts
-            .perform_execution(remaining_accounts, throw_on_execution_error, &event_emitter)?;
+            .perform_execution(
+                remaining_accounts,
+                throw_on_execution_error,
+                twap,
+                &event_emitter,
+            )?;
 
         let executed = transfer_out.executed();
-        if executed {
+        let mut has_remaining_slices = finish_twap_slice(&accounts.order, twap, executed)?;
+        if has_remaining_slices && is_position_removed {
+            msg!("[TWAP] the position is removed, the remaining slices are skipped");
+            has_remaining_slices = false;
+        }
+        if has_remaining_slices {
+            accounts.process_transfer_out(remaining_accounts, &transfer_out, &event_emitter)?;
+        } else if executed {
             accounts.order.load_mut()?.header.completed()?;
             accounts.process_transfer_out(remaining_accounts, &transfer_out, &event_emitter)?;
+            accounts.close_linked_orders(linked_orders, &event_emitter)?;
         } else {
             accounts.order.load_mut()?.header.cancelled()?;
         }
@@ -857,6 +901,7 @@ impl<'info> ExecuteDecreaseOrderV2<'info> {
         &mut self,
         remaining_accounts: &'info [AccountInfo<'info>],
         throw_on_execution_error: bool,
+        twap: Option<TwapRemaining>,
         event_emitter: &EventEmitter<'_, 'info>,
     ) -> Result<(RemovePosition, Box<TransferOut>, ShouldSendTradeEvent)> {
         // Note: We only need the tokens here, the feeds are not necessary.
@@ -875,6 +920,7 @@ impl<'info> ExecuteDecreaseOrderV2<'info> {
             .position(Some(&self.position))
             .event(Some(&self.event))
             .throw_on_execution_error(throw_on_execution_error)
+            .twap(twap)
             .executor(self.authority.to_account_info())
             .event_emitter(*event_emitter)
             .callback_authority(self.callback_authority.as_ref())
@@ -945,6 +991,22 @@ impl<'info> ExecuteDecreaseOrderV2<'info> {
         Ok(())
     }
 
+    #[inline(never)]
+    fn close_linked_orders(
+        &self,
+        linked_orders: &'info [AccountInfo<'info>],
+        event_emitter: &EventEmitter<'_, 'info>,
+    ) -> Result<()> {
+        CloseLinkedOrdersOperation::builder()
+            .store(&self.store)
+            .order(&self.order)
+            .token_program(self.token_program.to_account_info())
+            .linked_orders(linked_orders)
+            .event_emitter(*event_emitter)
+            .build()
+            .execute()
+    }
+
     #[inline(never)]
     fn pay_execution_fee(&self, execution_fee: u64) -> Result<()> {
         let execution_lamports = self.order.load()?.execution_lamports(execution_fee);
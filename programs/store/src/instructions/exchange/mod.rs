@@ -19,6 +19,9 @@ pub mod execute_order;
 /// Update ADL state.
 pub mod update_adl;
 
+/// Update trailing-stop orders.
+pub mod update_trailing_stop;
+
 /// Position cut.
 pub mod position_cut;
 
@@ -45,6 +48,7 @@ pub use shift::*;
 pub use update_adl::*;
 pub use update_closed::*;
 pub use update_fees::*;
+pub use update_trailing_stop::*;
 pub use withdrawal::*;
 
 use crate::CoreError;
@@ -5,10 +5,7 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token::{transfer_checked, Mint, Token, TokenAccount, TransferChecked},
 };
-use gmsol_callback::{
-    interface::{ActionKind, CallbackInterface},
-    CALLBACK_AUTHORITY_SEED,
-};
+use gmsol_callback::{interface::ActionKind, CALLBACK_AUTHORITY_SEED};
 use gmsol_model::{utils::apply_factor, PositionStateExt};
 use gmsol_utils::{action::ActionCallbackKind, config::AmountKey, InitSpace};
 
@@ -17,18 +14,18 @@ use crate::{
     events::{EventEmitter, GtUpdated, OrderCreated, OrderUpdated},
     ops::{
         execution_fee::TransferExecutionFeeOperation,
-        order::{CreateOrderOperation, CreateOrderParams},
+        order::{CreateOrderArgs, CreateOrderOperation, CreateOrderParams},
     },
     order::internal::Close,
     states::{
-        callback::CallbackAuthority,
+        callback::{CallbackAuthority, CallbackProgram},
         common::action::{Action, On},
         feature::ActionDisabledFlag,
         order::{Order, OrderKind},
         position::PositionKind,
-        user::UserHeader,
+        user::{UserDelegation, UserHeader},
         HasMarketMeta, Market, NonceBytes, Position, RoleKey, Seed, Store, StoreWalletSigner,
-        UpdateOrderParams,
+        UpdateOrderOptions, UpdateOrderParams,
     },
     utils::{internal, token::is_associated_token_account_or_owner},
     CoreError,
@@ -79,6 +76,7 @@ pub(crate) fn prepare_position(
         &ctx.accounts.position,
         ctx.bumps.position,
         params.to_position_kind()?,
+        ctx.accounts.owner.key,
         &ctx.accounts.owner,
         collateral_token,
         &market_token,
@@ -89,12 +87,87 @@ pub(crate) fn prepare_position(
     Ok(())
 }
 
+/// The accounts definition for the
+/// [`prepare_position_by_delegate`](crate::gmsol_store::prepare_position_by_delegate) instruction.
+#[derive(Accounts)]
+#[instruction(params: CreateOrderParams)]
+pub struct PreparePositionByDelegate<'info> {
+    /// The delegate.
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    /// The owner of the position.
+    /// CHECK: only the address is used, which is checked by the `delegation`.
+    pub owner: UncheckedAccount<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// The delegation.
+    #[account(
+        has_one = store,
+        has_one = owner,
+        has_one = delegate,
+        seeds = [UserDelegation::SEED, store.key().as_ref(), owner.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.load()?.bump,
+    )]
+    pub delegation: AccountLoader<'info, UserDelegation>,
+    /// Market.
+    #[account(has_one = store)]
+    pub market: AccountLoader<'info, Market>,
+    /// The position.
+    #[account(
+        init_if_needed,
+        payer = delegate,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [
+            Position::SEED,
+            store.key().as_ref(),
+            owner.key().as_ref(),
+            market.load()?.meta().market_token_mint.as_ref(),
+            params.collateral_token(market.load()?.meta()).as_ref(),
+            &[params.to_position_kind()? as u8],
+        ],
+        bump,
+    )]
+    pub position: AccountLoader<'info, Position>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn prepare_position_by_delegate(
+    ctx: Context<PreparePositionByDelegate>,
+    params: &CreateOrderParams,
+) -> Result<()> {
+    let store = ctx.accounts.store.key();
+    let meta = *ctx.accounts.market.load()?.meta();
+    let market_token = meta.market_token_mint;
+    ctx.accounts.delegation.load()?.validate_order(
+        Clock::get()?.unix_timestamp,
+        &market_token,
+        params.kind,
+        params.size_delta_value,
+    )?;
+    let collateral_token = params.collateral_token(&meta);
+    validate_and_initialize_position_if_needed(
+        &ctx.accounts.position,
+        ctx.bumps.position,
+        params.to_position_kind()?,
+        ctx.accounts.owner.key,
+        &ctx.accounts.delegate,
+        collateral_token,
+        &market_token,
+        meta.is_pure(),
+        &store,
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn validate_and_initialize_position_if_needed<'info>(
     position_loader: &AccountLoader<'info, Position>,
     bump: u8,
     kind: PositionKind,
-    owner: &AccountInfo<'info>,
+    owner_key: &Pubkey,
+    payer: &AccountInfo<'info>,
     collateral_token: &Pubkey,
     market_token: &Pubkey,
     is_pure_market: bool,
@@ -103,7 +176,6 @@ fn validate_and_initialize_position_if_needed<'info>(
 ) -> Result<()> {
     let mut should_transfer_in = false;
 
-    let owner_key = owner.key;
     match position_loader.load_init() {
         Ok(mut position) => {
             position.try_init(
@@ -140,7 +212,7 @@ fn validate_and_initialize_position_if_needed<'info>(
     if should_transfer_in {
         TransferExecutionFeeOperation::builder()
             .payment(position_loader.to_account_info())
-            .payer(owner.clone())
+            .payer(payer.clone())
             .execution_lamports(Order::position_cut_rent(is_pure_market, true)?)
             .system_program(system_program)
             .build()
@@ -302,7 +374,9 @@ pub struct CreateOrderV2<'info> {
     )]
     pub callback_authority: Option<Account<'info, CallbackAuthority>>,
     /// Callback program.
-    pub callback_program: Option<Interface<'info, CallbackInterface>>,
+    /// CHECK: checked against the `callback_program_config`.
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
     /// Config account for callback.
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
@@ -311,10 +385,13 @@ pub struct CreateOrderV2<'info> {
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
     pub callback_partitioned_data_account: Option<UncheckedAccount<'info>>,
+    /// The allowlist entry of the callback program.
+    #[account(has_one = store)]
+    pub callback_program_config: Option<AccountLoader<'info, CallbackProgram>>,
 }
 
 impl<'info> internal::Create<'info, Order> for CreateOrderV2<'info> {
-    type CreateParams = CreateOrderParams;
+    type CreateParams = CreateOrderArgs;
 
     fn action(&self) -> AccountInfo<'info> {
         self.order.to_account_info()
@@ -328,7 +405,8 @@ impl<'info> internal::Create<'info, Order> for CreateOrderV2<'info> {
         self.system_program.to_account_info()
     }
 
-    fn validate(&self, params: &Self::CreateParams) -> Result<()> {
+    fn validate(&self, args: &Self::CreateParams) -> Result<()> {
+        let params = &args.params;
         self.store
             .load()?
             .validate_not_restarted()?
@@ -345,23 +423,15 @@ impl<'info> internal::Create<'info, Order> for CreateOrderV2<'info> {
 
     fn create_impl(
         &mut self,
-        params: &Self::CreateParams,
+        args: &Self::CreateParams,
         nonce: &NonceBytes,
         bumps: &Self::Bumps,
         remaining_accounts: &'info [AccountInfo<'info>],
         callback_version: Option<u8>,
     ) -> Result<()> {
+        let params = &args.params;
         self.transfer_tokens(params)?;
 
-        let kind = params.kind;
-        let allow_closed = match kind {
-            // Allow creating order to increase collateral while the market is closed.
-            OrderKind::MarketIncrease => {
-                params.size_delta_value == 0 && params.initial_collateral_delta_amount != 0
-            }
-            _ => false,
-        };
-
         let ops = CreateOrderOperation::builder()
             .order(self.order.clone())
             .market(self.market.clone())
@@ -371,89 +441,31 @@ impl<'info> internal::Create<'info, Order> for CreateOrderV2<'info> {
             .nonce(nonce)
             .bump(bumps.order)
             .params(params)
+            .options(args.options.clone())
             .swap_path(remaining_accounts)
             .callback_version(callback_version)
             .callback_authority(self.callback_authority.as_ref())
             .callback_program(self.callback_program.as_deref())
             .callback_shared_data_account(self.callback_shared_data_account.as_deref())
             .callback_partitioned_data_account(self.callback_partitioned_data_account.as_deref())
+            .callback_program_config(self.callback_program_config.as_ref())
             .event_emitter(Some(EventEmitter::new(
                 &self.event_authority,
                 bumps.event_authority,
             )))
-            .allow_closed(allow_closed)
+            .allow_closed(allows_closed_market(params))
             .build();
 
-        match kind {
-            OrderKind::MarketSwap | OrderKind::LimitSwap => {
-                let swap_in = self
-                    .initial_collateral_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                let swap_out = self
-                    .final_output_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                ops.swap()
-                    .swap_in_token(swap_in.as_ref())
-                    .swap_out_token(swap_out.as_ref())
-                    .build()
-                    .execute()?;
-            }
-            OrderKind::MarketIncrease | OrderKind::LimitIncrease => {
-                let initial_collateral = self
-                    .initial_collateral_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                let long_token = self
-                    .long_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                let short_token = self
-                    .short_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                ops.increase()
-                    .position(
-                        self.position
-                            .as_ref()
-                            .ok_or_else(|| error!(CoreError::PositionIsRequired))?,
-                    )
-                    .initial_collateral_token(initial_collateral.as_ref())
-                    .long_token(long_token.as_ref())
-                    .short_token(short_token.as_ref())
-                    .build()
-                    .execute()?;
-            }
-            OrderKind::MarketDecrease | OrderKind::LimitDecrease | OrderKind::StopLossDecrease => {
-                let final_output = self
-                    .final_output_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                let long_token = self
-                    .long_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                let short_token = self
-                    .short_token_escrow
-                    .as_ref()
-                    .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-                ops.decrease()
-                    .position(
-                        self.position
-                            .as_ref()
-                            .ok_or_else(|| error!(CoreError::PositionIsRequired))?,
-                    )
-                    .final_output_token(final_output.as_ref())
-                    .long_token(long_token.as_ref())
-                    .short_token(short_token.as_ref())
-                    .build()
-                    .execute()?;
-            }
-            _ => {
-                return err!(CoreError::OrderKindNotAllowed);
-            }
-        }
+        execute_create_order(
+            ops,
+            params.kind,
+            self.position.as_ref(),
+            self.initial_collateral_token_escrow.as_deref(),
+            self.final_output_token_escrow.as_deref(),
+            self.long_token_escrow.as_deref(),
+            self.short_token_escrow.as_deref(),
+        )?;
+
         emit!(OrderCreated::new(
             self.store.key(),
             self.order.key(),
@@ -466,47 +478,361 @@ impl<'info> internal::Create<'info, Order> for CreateOrderV2<'info> {
 
 impl CreateOrderV2<'_> {
     fn transfer_tokens(&mut self, params: &CreateOrderParams) -> Result<()> {
-        let kind = params.kind;
-        if !matches!(
-            kind,
-            OrderKind::MarketSwap
-                | OrderKind::LimitSwap
-                | OrderKind::MarketIncrease
-                | OrderKind::LimitIncrease
-        ) {
-            return Ok(());
+        transfer_initial_collateral(
+            params,
+            self.token_program.to_account_info(),
+            self.owner.to_account_info(),
+            self.initial_collateral_token.as_deref(),
+            self.initial_collateral_token_source.as_deref(),
+            self.initial_collateral_token_escrow.as_deref_mut(),
+        )
+    }
+}
+
+/// Returns whether the order is allowed to be created while the market is closed.
+fn allows_closed_market(params: &CreateOrderParams) -> bool {
+    match params.kind {
+        // Allow creating order to increase collateral while the market is closed.
+        OrderKind::MarketIncrease => {
+            params.size_delta_value == 0 && params.initial_collateral_delta_amount != 0
         }
-        let amount = params.initial_collateral_delta_amount;
-        if amount != 0 {
-            let token = self
-                .initial_collateral_token
-                .as_ref()
-                .ok_or_else(|| error!(CoreError::MissingInitialCollateralToken))?;
-            let from = self
-                .initial_collateral_token_source
-                .as_ref()
+        _ => false,
+    }
+}
+
+fn transfer_initial_collateral<'info>(
+    params: &CreateOrderParams,
+    token_program: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token: Option<&Account<'info, Mint>>,
+    from: Option<&Account<'info, TokenAccount>>,
+    to: Option<&mut Account<'info, TokenAccount>>,
+) -> Result<()> {
+    let kind = params.kind;
+    if !matches!(
+        kind,
+        OrderKind::MarketSwap
+            | OrderKind::LimitSwap
+            | OrderKind::MarketIncrease
+            | OrderKind::LimitIncrease
+    ) {
+        return Ok(());
+    }
+    let amount = params.initial_collateral_delta_amount;
+    if amount != 0 {
+        let token = token.ok_or_else(|| error!(CoreError::MissingInitialCollateralToken))?;
+        let from = from.ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+        let to = to.ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+
+        transfer_checked(
+            CpiContext::new(
+                token_program,
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: token.to_account_info(),
+                    to: to.to_account_info(),
+                    authority,
+                },
+            ),
+            amount,
+            token.decimals,
+        )?;
+
+        to.reload()?;
+    }
+    Ok(())
+}
+
+#[inline(never)]
+fn execute_create_order<'info>(
+    ops: CreateOrderOperation<'_, 'info>,
+    kind: OrderKind,
+    position: Option<&AccountLoader<'info, Position>>,
+    initial_collateral_token_escrow: Option<&Account<'info, TokenAccount>>,
+    final_output_token_escrow: Option<&Account<'info, TokenAccount>>,
+    long_token_escrow: Option<&Account<'info, TokenAccount>>,
+    short_token_escrow: Option<&Account<'info, TokenAccount>>,
+) -> Result<()> {
+    match kind {
+        OrderKind::MarketSwap | OrderKind::LimitSwap => {
+            let swap_in = initial_collateral_token_escrow
                 .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
-            let to = self
-                .initial_collateral_token_escrow
-                .as_mut()
+            let swap_out = final_output_token_escrow
                 .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            ops.swap()
+                .swap_in_token(swap_in)
+                .swap_out_token(swap_out)
+                .build()
+                .execute()?;
+        }
+        OrderKind::MarketIncrease | OrderKind::LimitIncrease => {
+            let initial_collateral = initial_collateral_token_escrow
+                .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            let long_token =
+                long_token_escrow.ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            let short_token =
+                short_token_escrow.ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            ops.increase()
+                .position(position.ok_or_else(|| error!(CoreError::PositionIsRequired))?)
+                .initial_collateral_token(initial_collateral)
+                .long_token(long_token)
+                .short_token(short_token)
+                .build()
+                .execute()?;
+        }
+        OrderKind::MarketDecrease
+        | OrderKind::LimitDecrease
+        | OrderKind::StopLossDecrease
+        | OrderKind::TrailingStopDecrease => {
+            let final_output = final_output_token_escrow
+                .ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            let long_token =
+                long_token_escrow.ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            let short_token =
+                short_token_escrow.ok_or_else(|| error!(CoreError::TokenAccountNotProvided))?;
+            ops.decrease()
+                .position(position.ok_or_else(|| error!(CoreError::PositionIsRequired))?)
+                .final_output_token(final_output)
+                .long_token(long_token)
+                .short_token(short_token)
+                .build()
+                .execute()?;
+        }
+        _ => {
+            return err!(CoreError::OrderKindNotAllowed);
+        }
+    }
+    Ok(())
+}
+
+/// The accounts definitions for the
+/// [`create_order_by_delegate`](crate::gmsol_store::create_order_by_delegate) instruction.
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..M. `[]` M market accounts, where M represents the length of the
+///     swap path for initial collateral token or final output token.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(nonce: [u8; 32], params: CreateOrderParams)]
+pub struct CreateOrderByDelegate<'info> {
+    /// The delegate creating the order, which pays for the rent and execution fee.
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    /// The owner of the order to be created, which is also the receiver of the output funds.
+    /// CHECK: only the address is used, which is checked by the `delegation`.
+    pub owner: UncheckedAccount<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// The delegation.
+    #[account(
+        has_one = store,
+        has_one = owner,
+        has_one = delegate,
+        seeds = [UserDelegation::SEED, store.key().as_ref(), owner.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.load()?.bump,
+    )]
+    pub delegation: AccountLoader<'info, UserDelegation>,
+    /// Market.
+    #[account(mut, has_one = store)]
+    pub market: AccountLoader<'info, Market>,
+    /// User Account of the owner.
+    #[account(
+        mut,
+        constraint = user.load()?.is_initialized() @ CoreError::InvalidUserAccount,
+        has_one = owner,
+        has_one = store,
+        seeds = [UserHeader::SEED, store.key().as_ref(), owner.key().as_ref()],
+        bump = user.load()?.bump,
+    )]
+    pub user: AccountLoader<'info, UserHeader>,
+    /// The order to be created, whose address is derived from the `delegate`.
+    #[account(
+        init,
+        space = 8 + Order::INIT_SPACE,
+        payer = delegate,
+        seeds = [Order::SEED, store.key().as_ref(), delegate.key().as_ref(), &nonce],
+        bump,
+    )]
+    pub order: AccountLoader<'info, Order>,
+    /// The related position.
+    #[account(
+        mut,
+        has_one = store,
+        has_one = owner,
+        constraint = position.load()?.market_token == market.load()?.meta().market_token_mint @ CoreError::MarketTokenMintMismatched,
+        constraint = position.load()?.collateral_token == *params.collateral_token(&*market.load()?) @ CoreError::InvalidPosition,
+        constraint = position.load()?.kind()? == params.to_position_kind()? @ CoreError::InvalidPosition,
+        seeds = [
+            Position::SEED,
+            store.key().as_ref(),
+            owner.key().as_ref(),
+            market.load()?.meta().market_token_mint.as_ref(),
+            params.collateral_token(market.load()?.meta()).as_ref(),
+            &[params.to_position_kind()? as u8],
+        ],
+        bump = position.load()?.bump,
+    )]
+    pub position: Option<AccountLoader<'info, Position>>,
+    /// Initial collateral token / swap in token.
+    /// Only required by increase and swap orders.
+    pub initial_collateral_token: Option<Box<Account<'info, Mint>>>,
+    /// Final output token.
+    pub final_output_token: Box<Account<'info, Mint>>,
+    /// Long token of the market.
+    #[account(constraint = market.load()?.meta().long_token_mint == long_token.key())]
+    pub long_token: Option<Box<Account<'info, Mint>>>,
+    /// Short token of the market.
+    #[account(constraint = market.load()?.meta().short_token_mint == short_token.key())]
+    pub short_token: Option<Box<Account<'info, Mint>>>,
+    /// Initial collateral token escrow account.
+    /// Only required by increase and swap orders.
+    #[account(
+        mut,
+        associated_token::mint = initial_collateral_token,
+        associated_token::authority = order,
+    )]
+    pub initial_collateral_token_escrow: Option<Box<Account<'info, TokenAccount>>>,
+    /// Final output token escrow account.
+    /// Only required by decrease and swap orders.
+    #[account(
+        mut,
+        associated_token::mint = final_output_token,
+        associated_token::authority = order,
+    )]
+    pub final_output_token_escrow: Option<Box<Account<'info, TokenAccount>>>,
+    /// Long token escrow.
+    /// Only required by increase and decrease orders.
+    #[account(
+        mut,
+        associated_token::mint = long_token,
+        associated_token::authority = order,
+    )]
+    pub long_token_escrow: Option<Box<Account<'info, TokenAccount>>>,
+    /// Short token escrow.
+    /// Only required by increase and decrease orders.
+    #[account(
+        mut,
+        associated_token::mint = short_token,
+        associated_token::authority = order,
+    )]
+    pub short_token_escrow: Option<Box<Account<'info, TokenAccount>>>,
+    /// The source initial token account.
+    /// Only required by increase and swap orders. The `delegate` must be
+    /// approved to transfer from this account.
+    #[account(
+        mut,
+        token::mint = initial_collateral_token,
+    )]
+    pub initial_collateral_token_source: Option<Box<Account<'info, TokenAccount>>>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+    /// The token program.
+    pub token_program: Program<'info, Token>,
+    /// The associated token program.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> internal::Create<'info, Order> for CreateOrderByDelegate<'info> {
+    type CreateParams = CreateOrderArgs;
+
+    fn action(&self) -> AccountInfo<'info> {
+        self.order.to_account_info()
+    }
+
+    fn payer(&self) -> AccountInfo<'info> {
+        self.delegate.to_account_info()
+    }
 
-            transfer_checked(
-                CpiContext::new(
-                    self.token_program.to_account_info(),
-                    TransferChecked {
-                        from: from.to_account_info(),
-                        mint: token.to_account_info(),
-                        to: to.to_account_info(),
-                        authority: self.owner.to_account_info(),
-                    },
-                ),
-                amount,
-                token.decimals,
+    fn system_program(&self) -> AccountInfo<'info> {
+        self.system_program.to_account_info()
+    }
+
+    fn validate(&self, args: &Self::CreateParams) -> Result<()> {
+        let params = &args.params;
+        self.store
+            .load()?
+            .validate_not_restarted()?
+            .validate_feature_enabled(
+                params
+                    .kind
+                    .try_into()
+                    .map_err(CoreError::from)
+                    .map_err(|err| error!(err))?,
+                ActionDisabledFlag::Create,
             )?;
+        self.delegation.load()?.validate_order(
+            Clock::get()?.unix_timestamp,
+            &self.market.load()?.meta().market_token_mint,
+            params.kind,
+            params.size_delta_value,
+        )?;
+        Ok(())
+    }
+
+    fn create_impl(
+        &mut self,
+        args: &Self::CreateParams,
+        nonce: &NonceBytes,
+        bumps: &Self::Bumps,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        callback_version: Option<u8>,
+    ) -> Result<()> {
+        let params = &args.params;
+        require!(callback_version.is_none(), CoreError::InvalidArgument);
+
+        transfer_initial_collateral(
+            params,
+            self.token_program.to_account_info(),
+            self.delegate.to_account_info(),
+            self.initial_collateral_token.as_deref(),
+            self.initial_collateral_token_source.as_deref(),
+            self.initial_collateral_token_escrow.as_deref_mut(),
+        )?;
+
+        // The output funds of the order can only be sent to the owner, while the
+        // rent is returned to the delegate.
+        let ops = CreateOrderOperation::builder()
+            .order(self.order.clone())
+            .market(self.market.clone())
+            .store(self.store.clone())
+            .owner(self.owner.to_account_info())
+            .receiver(self.owner.to_account_info())
+            .creator(self.delegate.to_account_info())
+            .rent_receiver(self.delegate.to_account_info())
+            .nonce(nonce)
+            .bump(bumps.order)
+            .params(params)
+            .options(args.options.clone())
+            .swap_path(remaining_accounts)
+            .callback_version(None)
+            .callback_authority(None)
+            .callback_program(None)
+            .callback_shared_data_account(None)
+            .callback_partitioned_data_account(None)
+            .event_emitter(Some(EventEmitter::new(
+                &self.event_authority,
+                bumps.event_authority,
+            )))
+            .allow_closed(allows_closed_market(params))
+            .build();
+
+        execute_create_order(
+            ops,
+            params.kind,
+            self.position.as_ref(),
+            self.initial_collateral_token_escrow.as_deref(),
+            self.final_output_token_escrow.as_deref(),
+            self.long_token_escrow.as_deref(),
+            self.short_token_escrow.as_deref(),
+        )?;
+
+        emit!(OrderCreated::new(
+            self.store.key(),
+            self.order.key(),
+            self.position.as_ref().map(|a| a.key()),
+        )?);
 
-            to.reload()?;
-        }
         Ok(())
     }
 }
@@ -646,7 +972,9 @@ pub struct CloseOrderV2<'info> {
     )]
     pub callback_authority: Option<Account<'info, CallbackAuthority>>,
     /// Callback program.
-    pub callback_program: Option<Interface<'info, CallbackInterface>>,
+    /// CHECK: checked against the callback program recorded in the order.
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
     /// Config account for callback.
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
@@ -655,6 +983,14 @@ pub struct CloseOrderV2<'info> {
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
     pub callback_partitioned_data_account: Option<UncheckedAccount<'info>>,
+    /// The delegation of the `owner` to the `executor`.
+    /// Only required if the `executor` is closing the order as a delegate.
+    #[account(
+        has_one = store,
+        has_one = owner,
+        constraint = delegation.load()?.delegate == executor.key() @ CoreError::PermissionDenied,
+    )]
+    pub delegation: Option<AccountLoader<'info, UserDelegation>>,
 }
 
 impl<'info> internal::Authentication<'info> for CloseOrderV2<'info> {
@@ -676,6 +1012,20 @@ impl<'info> internal::Close<'info, Order> for CloseOrderV2<'info> {
         self.rent_receiver.to_account_info()
     }
 
+    fn is_caller_delegate(&self) -> Result<bool> {
+        let Some(delegation) = self.delegation.as_ref() else {
+            return Ok(false);
+        };
+        let order = self.order.load()?;
+        delegation.load()?.validate_order(
+            Clock::get()?.unix_timestamp,
+            &order.market_token,
+            order.params().kind()?,
+            0,
+        )?;
+        Ok(true)
+    }
+
     #[inline(never)]
     fn validate(&self) -> Result<()> {
         let order = self.order.load()?;
@@ -993,7 +1343,9 @@ pub struct UpdateOrderV2<'info> {
     )]
     pub callback_authority: Option<Account<'info, CallbackAuthority>>,
     /// Callback program.
-    pub callback_program: Option<Interface<'info, CallbackInterface>>,
+    /// CHECK: checked against the callback program recorded in the order.
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
     /// Config account for callback.
     /// CHECK: expected to be checked by the callback program.
     #[account(mut)]
@@ -1005,85 +1357,196 @@ pub struct UpdateOrderV2<'info> {
 }
 
 impl UpdateOrderV2<'_> {
-    pub(crate) fn invoke(ctx: Context<Self>, params: &UpdateOrderParams) -> Result<()> {
-        // Validate feature enabled.
+    pub(crate) fn invoke(
+        ctx: Context<Self>,
+        params: &UpdateOrderParams,
+        options: &UpdateOrderOptions,
+    ) -> Result<()> {
+        let accounts = &ctx.accounts;
+        update_order(
+            &accounts.store,
+            &accounts.market,
+            &accounts.order,
+            params,
+            options,
+            EventEmitter::new(&accounts.event_authority, ctx.bumps.event_authority),
+        )?;
+        handle_order_updated(
+            &accounts.order,
+            &accounts.owner,
+            accounts.callback_authority.as_ref(),
+            accounts.callback_program.as_deref(),
+            accounts.callback_shared_data_account.as_deref(),
+            accounts.callback_partitioned_data_account.as_deref(),
+        )?;
+        Ok(())
+    }
+}
+
+/// The accounts definitions for [`update_order_by_delegate`](crate::gmsol_store::update_order_by_delegate).
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateOrderByDelegate<'info> {
+    /// The delegate.
+    pub delegate: Signer<'info>,
+    /// The owner of the order.
+    /// CHECK: only the address is used, which is checked by the `delegation`.
+    pub owner: UncheckedAccount<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// The delegation.
+    #[account(
+        has_one = store,
+        has_one = owner,
+        has_one = delegate,
+        seeds = [UserDelegation::SEED, store.key().as_ref(), owner.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.load()?.bump,
+    )]
+    pub delegation: AccountLoader<'info, UserDelegation>,
+    /// Market.
+    #[account(mut, has_one = store)]
+    pub market: AccountLoader<'info, Market>,
+    /// Order to update.
+    #[account(
+        mut,
+        constraint = order.load()?.header.store == store.key() @ CoreError::StoreMismatched,
+        constraint = order.load()?.header.market == market.key() @ CoreError::MarketMismatched,
+        constraint = order.load()?.header.owner == owner.key() @ CoreError::OwnerMismatched,
+        constraint = order.load()?.header.action_state()?.is_pending() @ CoreError::PreconditionsAreNotMet,
+    )]
+    pub order: AccountLoader<'info, Order>,
+    /// Callback authority.
+    #[account(
+        seeds = [CALLBACK_AUTHORITY_SEED],
+        bump = callback_authority.bump(),
+    )]
+    pub callback_authority: Option<Account<'info, CallbackAuthority>>,
+    /// Callback program.
+    /// CHECK: checked against the callback program recorded in the order.
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
+    /// Config account for callback.
+    /// CHECK: expected to be checked by the callback program.
+    #[account(mut)]
+    pub callback_shared_data_account: Option<UncheckedAccount<'info>>,
+    /// Action stats account for callback.
+    /// CHECK: expected to be checked by the callback program.
+    #[account(mut)]
+    pub callback_partitioned_data_account: Option<UncheckedAccount<'info>>,
+}
+
+impl UpdateOrderByDelegate<'_> {
+    pub(crate) fn invoke(
+        ctx: Context<Self>,
+        params: &UpdateOrderParams,
+        options: &UpdateOrderOptions,
+    ) -> Result<()> {
+        let accounts = &ctx.accounts;
         {
-            let order = ctx.accounts.order.load()?;
-            ctx.accounts
-                .store
-                .load()?
-                .validate_not_restarted()?
-                .validate_feature_enabled(
-                    order
-                        .params()
-                        .kind()?
-                        .try_into()
-                        .map_err(CoreError::from)
-                        .map_err(|err| error!(err))?,
-                    ActionDisabledFlag::Update,
-                )?;
+            let order = accounts.order.load()?;
+            let size_delta_value = params
+                .size_delta_value
+                .unwrap_or(order.params().size_delta_value);
+            accounts.delegation.load()?.validate_order(
+                Clock::get()?.unix_timestamp,
+                &order.market_token,
+                order.params().kind()?,
+                size_delta_value,
+            )?;
         }
-
-        let id = ctx
-            .accounts
-            .market
-            .load_mut()?
-            .indexer_mut()
-            .next_order_id()?;
-        ctx.accounts.order.load_mut()?.update(id, params)?;
-        ctx.accounts.emit_event(ctx.bumps.event_authority)?;
-        ctx.accounts.handle_updated()?;
+        update_order(
+            &accounts.store,
+            &accounts.market,
+            &accounts.order,
+            params,
+            options,
+            EventEmitter::new(&accounts.event_authority, ctx.bumps.event_authority),
+        )?;
+        handle_order_updated(
+            &accounts.order,
+            &accounts.owner,
+            accounts.callback_authority.as_ref(),
+            accounts.callback_program.as_deref(),
+            accounts.callback_shared_data_account.as_deref(),
+            accounts.callback_partitioned_data_account.as_deref(),
+        )?;
         Ok(())
     }
+}
 
-    fn emit_event(&self, bump: u8) -> Result<()> {
-        let event_emitter = EventEmitter::new(&self.event_authority, bump);
-        let order_address = self.order.key();
-        let order = self.order.load()?;
-        event_emitter.emit_cpi(&OrderUpdated::new(false, &order_address, &order)?)?;
-        Ok(())
+fn update_order<'info>(
+    store: &AccountLoader<'info, Store>,
+    market: &AccountLoader<'info, Market>,
+    order: &AccountLoader<'info, Order>,
+    params: &UpdateOrderParams,
+    options: &UpdateOrderOptions,
+    event_emitter: EventEmitter<'_, 'info>,
+) -> Result<()> {
+    // Validate feature enabled.
+    {
+        let order = order.load()?;
+        store
+            .load()?
+            .validate_not_restarted()?
+            .validate_feature_enabled(
+                order
+                    .params()
+                    .kind()?
+                    .try_into()
+                    .map_err(CoreError::from)
+                    .map_err(|err| error!(err))?,
+                ActionDisabledFlag::Update,
+            )?;
     }
 
-    #[inline(never)]
-    fn handle_updated(&self) -> Result<()> {
-        match self.order.load()?.header.callback_kind()? {
-            ActionCallbackKind::Disabled => {}
-            ActionCallbackKind::General => {
-                if let Some(authority) = self.callback_authority.as_ref() {
-                    let program = self
-                        .callback_program
-                        .as_ref()
-                        .ok_or_else(|| error!(CoreError::InvalidArgument))?;
-                    let shared_data = self
-                        .callback_shared_data_account
-                        .as_ref()
-                        .ok_or_else(|| error!(CoreError::InvalidArgument))?;
-                    let partitioned_data = self
-                        .callback_partitioned_data_account
-                        .as_ref()
-                        .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+    let id = market.load_mut()?.indexer_mut().next_order_id()?;
+    order.load_mut()?.update(id, params, options)?;
 
-                    self.order.load()?.header.invoke_general_callback(
-                        On::Updated(ActionKind::Order),
-                        authority,
-                        program,
-                        shared_data,
-                        partitioned_data,
-                        &self.owner,
-                        self.order.as_ref(),
-                        &[],
-                    )?;
-                } else {
-                    msg!("[Callback] callback is specified, but required accounts are missing");
-                    return err!(CoreError::InvalidArgument);
-                }
-            }
-            kind => {
-                msg!("[Callback] unsupported callback kind: {}", kind);
+    let order_address = order.key();
+    let order = order.load()?;
+    event_emitter.emit_cpi(&OrderUpdated::new(false, &order_address, &order)?)?;
+    Ok(())
+}
+
+#[inline(never)]
+fn handle_order_updated<'info>(
+    order: &AccountLoader<'info, Order>,
+    owner: &AccountInfo<'info>,
+    callback_authority: Option<&Account<'info, CallbackAuthority>>,
+    callback_program: Option<&AccountInfo<'info>>,
+    callback_shared_data_account: Option<&AccountInfo<'info>>,
+    callback_partitioned_data_account: Option<&AccountInfo<'info>>,
+) -> Result<()> {
+    match order.load()?.header.callback_kind()? {
+        ActionCallbackKind::Disabled => {}
+        ActionCallbackKind::General => {
+            if let Some(authority) = callback_authority {
+                let program = callback_program.ok_or_else(|| error!(CoreError::InvalidArgument))?;
+                let shared_data = callback_shared_data_account
+                    .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+                let partitioned_data = callback_partitioned_data_account
+                    .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+
+                order.load()?.header.invoke_general_callback(
+                    On::Updated(ActionKind::Order),
+                    authority,
+                    program,
+                    shared_data,
+                    partitioned_data,
+                    owner,
+                    order.as_ref(),
+                    &[],
+                )?;
+            } else {
+                msg!("[Callback] callback is specified, but required accounts are missing");
+                return err!(CoreError::InvalidArgument);
             }
         }
-        Ok(())
+        kind => {
+            msg!("[Callback] unsupported callback kind: {}", kind);
+        }
     }
+    Ok(())
 }
 
 /// The accounts definition for the [`cancel_order_if_no_position`](crate::gmsol_store::cancel_order_if_no_position)
@@ -1188,3 +1651,71 @@ impl SetShouldKeepPositionAccount<'_> {
         Ok(())
     }
 }
+
+/// The accounts definitions for [`link_orders`](crate::gmsol_store::link_orders).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[writable]` N order accounts to link, where `2 <= N <= MAX_LINKED_ORDERS`.
+#[derive(Accounts)]
+pub struct LinkOrders<'info> {
+    /// Owner.
+    pub owner: Signer<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+}
+
+impl<'info> LinkOrders<'info> {
+    pub(crate) fn invoke(ctx: Context<'_, '_, 'info, 'info, Self>) -> Result<()> {
+        let orders = ctx.remaining_accounts;
+        require_gte!(orders.len(), 2, CoreError::InvalidArgument);
+        require_gte!(
+            Order::MAX_LINKED_ORDERS,
+            orders.len(),
+            CoreError::ExceedMaxLengthLimit
+        );
+
+        let addresses = orders.iter().map(|info| info.key()).collect::<Vec<_>>();
+        require_eq!(
+            addresses.iter().collect::<HashSet<_>>().len(),
+            addresses.len(),
+            CoreError::InvalidArgument
+        );
+
+        let store = ctx.accounts.store.key();
+        let owner = ctx.accounts.owner.key();
+        let mut market = None;
+        let mut position = None;
+        for info in orders {
+            require!(info.is_writable, CoreError::InvalidArgument);
+            let loader = AccountLoader::<Order>::try_from(info)?;
+            let mut order = loader.load_mut()?;
+            require_keys_eq!(order.header.store, store, CoreError::StoreMismatched);
+            require_keys_eq!(order.header.owner, owner, CoreError::OwnerMismatched);
+            require!(
+                order.header.action_state()?.is_pending(),
+                CoreError::PreconditionsAreNotMet
+            );
+            // All the linked orders must be in the same market.
+            let order_market = order.header.market;
+            require_keys_eq!(
+                *market.get_or_insert(order_market),
+                order_market,
+                CoreError::MarketMismatched
+            );
+            // All the linked orders must act on the same position.
+            let order_position = (
+                order.params.side()?.is_long(),
+                order.params.collateral_token,
+            );
+            require!(
+                *position.get_or_insert(order_position) == order_position,
+                CoreError::PositionMismatched
+            );
+            order.link(info.key, &addresses)?;
+        }
+
+        msg!("[Order] linked {} orders", addresses.len());
+        Ok(())
+    }
+}
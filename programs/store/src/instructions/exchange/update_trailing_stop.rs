@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{EventEmitter, OrderUpdated},
+    states::{common::action::Action, Chainlink, Market, Oracle, Order, Store, TokenMapHeader},
+    utils::internal,
+    CoreError,
+};
+
+/// The accounts definition for [`update_trailing_stop_order`](crate::gmsol_store::update_trailing_stop_order).
+///
+/// *[See also the documentation for the instruction.](crate::gmsol_store::update_trailing_stop_order)*
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[]` N feed accounts, where N represents the total number of unique tokens
+///     in the market.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTrailingStopOrder<'info> {
+    /// The address authorized to execute this instruction.
+    pub authority: Signer<'info>,
+    /// The store that owns the market.
+    #[account(has_one = token_map)]
+    pub store: AccountLoader<'info, Store>,
+    /// Token map.
+    #[account(has_one = store)]
+    pub token_map: AccountLoader<'info, TokenMapHeader>,
+    /// The oracle buffer to use.
+    #[account(mut, has_one = store)]
+    pub oracle: AccountLoader<'info, Oracle>,
+    /// The market of the order.
+    #[account(has_one = store)]
+    pub market: AccountLoader<'info, Market>,
+    /// The trailing-stop order to update.
+    #[account(
+        mut,
+        constraint = order.load()?.header().store == store.key() @ CoreError::StoreMismatched,
+        constraint = order.load()?.header().market == market.key() @ CoreError::MarketMismatched,
+        constraint = order.load()?.header().action_state()?.is_pending() @ CoreError::PreconditionsAreNotMet,
+    )]
+    pub order: AccountLoader<'info, Order>,
+    /// Chainlink Program.
+    pub chainlink_program: Option<Program<'info, Chainlink>>,
+}
+
+/// CHECK: only ORDER_KEEPER is authorized to perform this action.
+pub(crate) fn unchecked_update_trailing_stop_order<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateTrailingStopOrder<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let market = accounts.market.load()?;
+    let tokens = market
+        .meta()
+        .ordered_tokens()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let updated = accounts.oracle.load_mut()?.with_prices(
+        &accounts.store,
+        &accounts.token_map,
+        &tokens,
+        ctx.remaining_accounts,
+        |oracle, _remaining_accounts| {
+            let mut order = accounts.order.load_mut()?;
+            // Only prices observed after the last update of the order can be used.
+            require_gte!(
+                oracle.min_oracle_ts(),
+                order.header().updated_at,
+                CoreError::OraclePricesOlderThanOrder
+            );
+            let prices = oracle.market_prices(&*market)?;
+            order
+                .params_mut()
+                .update_best_price(&prices.index_token_price)
+        },
+    )?;
+
+    if updated {
+        let order = accounts.order.load()?;
+        msg!(
+            "[Order] trailing-stop updated: best_price = {}, trigger_price = {}",
+            order.params().best_price(),
+            order.params().trigger_price(),
+        );
+        EventEmitter::new(&accounts.event_authority, ctx.bumps.event_authority)
+            .emit_cpi(&OrderUpdated::new(false, &accounts.order.key(), &order)?)?;
+    } else {
+        msg!("[Order] trailing-stop best price is not improved");
+    }
+
+    Ok(())
+}
+
+impl<'info> internal::Authentication<'info> for UpdateTrailingStopOrder<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
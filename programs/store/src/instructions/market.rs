@@ -6,6 +6,7 @@ use crate::{
             revertible::{Revertible, RevertibleMarket},
             status::MarketStatus,
             utils::ValidateMarketBalances,
+            MarketTradingMode,
         },
         Factor, HasMarketMeta, MaxAgeValidator, Oracle,
     },
@@ -23,7 +24,7 @@ use gmsol_utils::InitSpace;
 use crate::{
     constants,
     states::{
-        market::config::{EntryArgs, MarketConfigBuffer},
+        market::config::{EntryArgs, MarketConfigBuffer, MarketConfigKey},
         Market, Seed, Store, TokenMapAccess, TokenMapHeader, TokenMapLoader,
     },
     utils::internal,
@@ -352,6 +353,28 @@ pub(crate) fn unchecked_update_market_config(
     key: &str,
     value: Factor,
 ) -> Result<()> {
+    let key = parse_updatable_factor_key(&ctx, key)?;
+    {
+        let mut market = ctx.accounts.market.load_mut()?;
+        if market.remove_config_schedule(key) {
+            msg!("the scheduled update of {} is cancelled", key);
+        }
+        *market.get_config_by_key_mut(key)? = value;
+    }
+    msg!(
+        "{}: set {} = {}",
+        ctx.accounts.market.load()?.meta.market_token_mint,
+        key,
+        value
+    );
+    Ok(())
+}
+
+/// Parse the factor key and check that the authority is allowed to update it.
+fn parse_updatable_factor_key(
+    ctx: &Context<UpdateMarketConfig>,
+    key: &str,
+) -> Result<MarketConfigKey> {
     let key = key
         .parse()
         .map_err(|_| error!(CoreError::InvalidMarketConfigKey))?;
@@ -363,14 +386,62 @@ pub(crate) fn unchecked_update_market_config(
         .is_factor_updatable(key)?
     {
         msg!("[CHECK] checking permissions for updating `{}`", key);
-        internal::Authenticate::only_market_keeper(&ctx)?;
+        internal::Authenticate::only_market_keeper(ctx)?;
     }
-    *ctx.accounts.market.load_mut()?.get_config_by_key_mut(key)? = value;
+    Ok(key)
+}
+
+/// Schedule a ramped update of market config by key.
+///
+/// # CHECK
+/// - The [`authority`](UpdateMarketConfig::authority) must have
+///   permission to update market config.
+pub(crate) fn unchecked_schedule_market_config_update(
+    ctx: Context<UpdateMarketConfig>,
+    key: &str,
+    value: Factor,
+    start_at: i64,
+    duration: u32,
+) -> Result<()> {
+    let key = parse_updatable_factor_key(&ctx, key)?;
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .market
+        .load_mut()?
+        .schedule_config_update(key, value, start_at, duration, now)?;
     msg!(
-        "{}: set {} = {}",
+        "{}: scheduled {} to {} from {} over {}s",
         ctx.accounts.market.load()?.meta.market_token_mint,
         key,
-        value
+        value,
+        start_at.max(now),
+        duration,
+    );
+    Ok(())
+}
+
+/// Cancel the scheduled update of market config by key.
+///
+/// # CHECK
+/// - The [`authority`](UpdateMarketConfig::authority) must have
+///   permission to update market config.
+pub(crate) fn unchecked_cancel_market_config_schedule(
+    ctx: Context<UpdateMarketConfig>,
+    key: &str,
+) -> Result<()> {
+    let key = parse_updatable_factor_key(&ctx, key)?;
+    let cancelled = ctx
+        .accounts
+        .market
+        .load_mut()?
+        .cancel_config_schedule(key, Clock::get()?.unix_timestamp)?;
+    require!(cancelled, CoreError::NotFound);
+    let market = ctx.accounts.market.load()?;
+    msg!(
+        "{}: cancelled the scheduled update of {}, current value = {}",
+        market.meta.market_token_mint,
+        key,
+        market.get_config_by_key(key).copied().unwrap_or_default(),
     );
     Ok(())
 }
@@ -438,6 +509,54 @@ pub struct UpdateMarketConfigWithBuffer<'info> {
 pub(crate) fn unchecked_update_market_config_with_buffer(
     ctx: Context<UpdateMarketConfigWithBuffer>,
 ) -> Result<()> {
+    let buffer = &ctx.accounts.buffer;
+    validate_market_config_buffer(&ctx)?;
+
+    ctx.accounts
+        .market
+        .load_mut()?
+        .update_config_with_buffer(buffer)?;
+    msg!(
+        "{} updated with buffer {}",
+        ctx.accounts.market.load()?.description()?,
+        buffer.key()
+    );
+    Ok(())
+}
+
+/// Schedule ramped updates of market config with buffer.
+///
+/// # CHECK
+/// - The [`authority`](UpdateMarketConfigWithBuffer::authority) must have
+///   permission to update market config.
+pub(crate) fn unchecked_schedule_market_config_update_with_buffer(
+    ctx: Context<UpdateMarketConfigWithBuffer>,
+    start_at: i64,
+    duration: u32,
+) -> Result<()> {
+    let buffer = &ctx.accounts.buffer;
+    validate_market_config_buffer(&ctx)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    {
+        let mut market = ctx.accounts.market.load_mut()?;
+        for entry in buffer.iter() {
+            market.schedule_config_update(entry.key()?, entry.value(), start_at, duration, now)?;
+        }
+    }
+    msg!(
+        "{} scheduled updates with buffer {} from {} over {}s",
+        ctx.accounts.market.load()?.description()?,
+        buffer.key(),
+        start_at.max(now),
+        duration,
+    );
+    Ok(())
+}
+
+/// Check that the buffer has not expired and that the authority is allowed
+/// to update all the keys in it.
+fn validate_market_config_buffer(ctx: &Context<UpdateMarketConfigWithBuffer>) -> Result<()> {
     let buffer = &ctx.accounts.buffer;
     require_gt!(
         buffer.expiry,
@@ -445,7 +564,7 @@ pub(crate) fn unchecked_update_market_config_with_buffer(
         CoreError::InvalidArgument
     );
 
-    if let Err(err) = internal::Authenticate::only_market_keeper(&ctx) {
+    if let Err(err) = internal::Authenticate::only_market_keeper(ctx) {
         let store = ctx.accounts.store.load()?;
         for entry in buffer.iter() {
             let key = entry.key()?;
@@ -455,16 +574,6 @@ pub(crate) fn unchecked_update_market_config_with_buffer(
             }
         }
     }
-
-    ctx.accounts
-        .market
-        .load_mut()?
-        .update_config_with_buffer(buffer)?;
-    msg!(
-        "{} updated with buffer {}",
-        ctx.accounts.market.load()?.description()?,
-        buffer.key()
-    );
     Ok(())
 }
 
@@ -730,6 +839,49 @@ impl<'info> internal::Authentication<'info> for ToggleGTMinting<'info> {
     }
 }
 
+/// The accounts definition for [`set_market_trading_mode`](crate::gmsol_store::set_market_trading_mode).
+///
+/// *[See also the documentation for the instruction.](crate::gmsol_store::set_market_trading_mode)*
+#[derive(Accounts)]
+pub struct SetMarketTradingMode<'info> {
+    /// The caller.
+    pub authority: Signer<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// Market.
+    #[account(mut, has_one = store)]
+    pub market: AccountLoader<'info, Market>,
+}
+
+/// Set the trading mode of the market.
+///
+/// ## CHECK
+/// - Only MARKET_KEEPER can use this instruction.
+pub(crate) fn unchecked_set_market_trading_mode(
+    ctx: Context<SetMarketTradingMode>,
+    mode: MarketTradingMode,
+) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+    let previous = market.set_trading_mode(mode)?;
+    msg!(
+        "{}: trading mode changed from `{}` to `{}`",
+        market.description()?,
+        previous,
+        mode
+    );
+    Ok(())
+}
+
+impl<'info> internal::Authentication<'info> for SetMarketTradingMode<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
 /// The accounts definition for [`claim_fees_from_market`](crate::gmsol_store::claim_fees_from_market).
 ///
 /// *[See also the documentation for the instruction.](crate::gmsol_store::claim_fees_from_market)*
@@ -43,6 +43,9 @@ pub mod callback;
 /// Instructions for virtual inventories.
 pub mod virtual_inventory;
 
+/// Instructions for risk oracle.
+pub mod risk_oracle;
+
 pub use callback::*;
 pub use config::*;
 pub use exchange::*;
@@ -52,6 +55,7 @@ pub use gt::*;
 pub use market::*;
 pub use migration::*;
 pub use oracle::*;
+pub use risk_oracle::*;
 pub use roles::*;
 pub use store::*;
 pub use token::*;
@@ -72,6 +72,59 @@ impl<'info> internal::Authentication<'info> for ClearAllPrices<'info> {
     }
 }
 
+/// The accounts definition for [`update_price_circuit_breakers`](crate::gmsol_store::update_price_circuit_breakers).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[]` N feed accounts, where N represents the total number of unique tokens
+///     to update.
+#[derive(Accounts)]
+pub struct UpdatePriceCircuitBreakers<'info> {
+    /// The caller.
+    pub authority: Signer<'info>,
+    /// Store.
+    #[account(has_one = token_map)]
+    pub store: AccountLoader<'info, Store>,
+    /// Token map.
+    #[account(mut, has_one = store)]
+    pub token_map: AccountLoader<'info, TokenMapHeader>,
+    /// The oracle buffer to use.
+    #[account(mut, has_one = store)]
+    pub oracle: AccountLoader<'info, Oracle>,
+    /// Chainlink Program.
+    pub chainlink_program: Option<Program<'info, Chainlink>>,
+}
+
+/// Track the prices of the given tokens with their price circuit breakers.
+/// CHECK: only ORDER_KEEPER is allowed to invoke.
+pub(crate) fn unchecked_update_price_circuit_breakers<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdatePriceCircuitBreakers<'info>>,
+    tokens: Vec<Pubkey>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let accounts = ctx.accounts;
+    accounts.oracle.load_mut()?.with_prices(
+        &accounts.store,
+        &accounts.token_map,
+        &tokens,
+        ctx.remaining_accounts,
+        |oracle, _remaining_accounts| {
+            let mut token_map = accounts.token_map.load_token_map_mut()?;
+            oracle.track_price_circuit_breakers(&mut token_map, &tokens, now)
+        },
+    )
+}
+
+impl<'info> internal::Authentication<'info> for UpdatePriceCircuitBreakers<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
 /// The accounts definition for [`set_prices_from_price_feed`](crate::gmsol_store::set_prices_from_price_feed).
 ///
 /// Remaining accounts expected by this instruction:
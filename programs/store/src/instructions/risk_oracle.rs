@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use gmsol_utils::InitSpace;
+
+use crate::{
+    internal,
+    states::{
+        market::config::MarketConfigKey,
+        risk_oracle::{RiskOracle, RiskOracleRecommendation, RISK_ORACLE_SEED},
+        Factor, Market, Store,
+    },
+    CoreError,
+};
+
+/// The accounts definitions for [`initialize_risk_oracle`](crate::gmsol_store::initialize_risk_oracle).
+#[derive(Accounts)]
+pub struct InitializeRiskOracle<'info> {
+    /// Authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The risk oracle account to initialize.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RiskOracle::INIT_SPACE,
+        seeds = [RISK_ORACLE_SEED, store.key().as_ref()],
+        bump,
+    )]
+    pub risk_oracle: AccountLoader<'info, RiskOracle>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeRiskOracle<'_> {
+    /// Initialize the [`RiskOracle`] account of the store.
+    ///
+    /// # CHECK
+    /// - Only MARKET_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_unchecked(ctx: Context<Self>, signer: Pubkey, max_age: u32) -> Result<()> {
+        ctx.accounts.risk_oracle.load_init()?.init(
+            ctx.bumps.risk_oracle,
+            ctx.accounts.store.key(),
+            signer,
+            max_age,
+        );
+        Ok(())
+    }
+}
+
+impl<'info> internal::Authentication<'info> for InitializeRiskOracle<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
+/// The accounts definitions for the instructions that configure the risk oracle.
+#[derive(Accounts)]
+pub struct ConfigureRiskOracle<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The risk oracle account to configure.
+    #[account(mut, has_one = store)]
+    pub risk_oracle: AccountLoader<'info, RiskOracle>,
+}
+
+impl ConfigureRiskOracle<'_> {
+    /// Update the signer and the max age of recommendations.
+    ///
+    /// # CHECK
+    /// - Only MARKET_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_update_unchecked(
+        ctx: Context<Self>,
+        signer: Pubkey,
+        max_age: u32,
+    ) -> Result<()> {
+        ctx.accounts.risk_oracle.load_mut()?.update(signer, max_age);
+        Ok(())
+    }
+
+    /// Set the bound of the given key.
+    ///
+    /// # CHECK
+    /// - Only MARKET_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_set_bound_unchecked(
+        ctx: Context<Self>,
+        key: &str,
+        min: Factor,
+        max: Factor,
+    ) -> Result<()> {
+        let key = parse_key(key)?;
+        ctx.accounts
+            .risk_oracle
+            .load_mut()?
+            .set_bound(key, min, max)?;
+        msg!("[Risk Oracle] set bound of {} to [{}, {}]", key, min, max);
+        Ok(())
+    }
+
+    /// Remove the bound of the given key.
+    ///
+    /// # CHECK
+    /// - Only MARKET_KEEPER is allowed to invoke.
+    pub(crate) fn invoke_remove_bound_unchecked(ctx: Context<Self>, key: &str) -> Result<()> {
+        let key = parse_key(key)?;
+        ctx.accounts.risk_oracle.load_mut()?.remove_bound(key)?;
+        msg!("[Risk Oracle] removed bound of {}", key);
+        Ok(())
+    }
+}
+
+impl<'info> internal::Authentication<'info> for ConfigureRiskOracle<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
+fn parse_key(key: &str) -> Result<MarketConfigKey> {
+    key.parse()
+        .map_err(|_| error!(CoreError::InvalidMarketConfigKey))
+}
+
+/// The accounts definitions for
+/// [`apply_risk_oracle_recommendation`](crate::gmsol_store::apply_risk_oracle_recommendation).
+#[derive(Accounts)]
+pub struct ApplyRiskOracleRecommendation<'info> {
+    /// Relayer.
+    pub authority: Signer<'info>,
+    /// Store account.
+    pub store: AccountLoader<'info, Store>,
+    /// The risk oracle account.
+    #[account(
+        has_one = store,
+        seeds = [RISK_ORACLE_SEED, store.key().as_ref()],
+        bump = risk_oracle.load()?.bump,
+    )]
+    pub risk_oracle: AccountLoader<'info, RiskOracle>,
+    /// The market to update.
+    #[account(mut, has_one = store)]
+    pub market: AccountLoader<'info, Market>,
+}
+
+impl ApplyRiskOracleRecommendation<'_> {
+    /// Verify and apply the recommendation to the market.
+    pub(crate) fn invoke(
+        ctx: Context<Self>,
+        recommendation: &RiskOracleRecommendation,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let mut market = ctx.accounts.market.load_mut()?;
+        let market_token = market.meta.market_token_mint;
+        let (index, updates) =
+            ctx.accounts
+                .risk_oracle
+                .load()?
+                .verify(&market_token, recommendation, now)?;
+        market.apply_risk_oracle_updates(index, recommendation.timestamp, &updates)?;
+        for (key, value) in updates {
+            msg!("[Risk Oracle] {}: set {} = {}", market_token, key, value);
+        }
+        Ok(())
+    }
+}
@@ -5,8 +5,8 @@ use gmsol_utils::token_config::TokenConfigFlag;
 
 use crate::{
     states::{
-        PriceProviderKind, Store, TokenConfigExt, TokenMapAccess, TokenMapAccessMut,
-        TokenMapHeader, TokenMapLoader, UpdateTokenConfigParams,
+        CompositePriceMode, PriceProviderKind, Store, TokenConfigExt, TokenMapAccess,
+        TokenMapAccessMut, TokenMapHeader, TokenMapLoader, UpdateTokenConfigParams,
     },
     utils::internal,
     CoreError,
@@ -294,6 +294,11 @@ pub(crate) fn unchecked_set_expected_provider(
         CoreError::PreconditionsAreNotMet
     );
 
+    if config.is_composite() && !config.is_price_source(&provider).map_err(CoreError::from)? {
+        msg!("[Set Expected Provider] the expected provider must be a composite price source");
+        return err!(CoreError::PreconditionsAreNotMet);
+    }
+
     config.set_expected_provider(provider);
     Ok(())
 }
@@ -387,6 +392,112 @@ impl SetFeedConfig<'_> {
     }
 }
 
+/// Set the composite price config for the given token.
+///
+/// ## CHECK
+/// - Only [`MARKET_KEEPER`](crate::states::RoleKey::MARKET_KEEPER) can perform this action.
+pub(crate) fn unchecked_set_composite_price(
+    ctx: Context<SetFeedConfig>,
+    token: Pubkey,
+    mode: CompositePriceMode,
+    sources: &[PriceProviderKind],
+    max_disagreement_factor: Option<u128>,
+) -> Result<()> {
+    let mut map = ctx.accounts.token_map.load_token_map_mut()?;
+    let config = map
+        .get_mut(&token)
+        .ok_or_else(|| error!(CoreError::NotFound))?;
+
+    config
+        .set_composite_price(mode, sources, max_disagreement_factor)
+        .map_err(CoreError::from)?;
+
+    msg!(
+        "[Set Composite Price] token = {}, mode = {}, sources = {}",
+        token,
+        mode,
+        sources.len()
+    );
+
+    Ok(())
+}
+
+/// Set the price circuit breaker config for the given token.
+///
+/// ## CHECK
+/// - Only [`MARKET_KEEPER`](crate::states::RoleKey::MARKET_KEEPER) can perform this action.
+pub(crate) fn unchecked_set_price_circuit_breaker(
+    ctx: Context<SetFeedConfig>,
+    token: Pubkey,
+    max_move_factor: Option<u128>,
+    window: u32,
+) -> Result<()> {
+    let mut map = ctx.accounts.token_map.load_token_map_mut()?;
+    let config = map
+        .get_mut(&token)
+        .ok_or_else(|| error!(CoreError::NotFound))?;
+
+    config
+        .set_price_circuit_breaker(max_move_factor, window)
+        .map_err(CoreError::from)?;
+
+    msg!(
+        "[Set Price Circuit Breaker] token = {}, max_move_factor = {:?}, window = {}",
+        token,
+        max_move_factor,
+        window
+    );
+
+    Ok(())
+}
+
+/// The accounts definition for [`reset_price_circuit_breaker`](crate::gmsol_store::reset_price_circuit_breaker).
+#[derive(Accounts)]
+pub struct ResetPriceCircuitBreaker<'info> {
+    /// The authority of the instruction.
+    pub authority: Signer<'info>,
+    /// The store.
+    #[account(has_one = token_map)]
+    pub store: AccountLoader<'info, Store>,
+    /// The token map of the store.
+    #[account(mut, has_one = store)]
+    pub token_map: AccountLoader<'info, TokenMapHeader>,
+}
+
+/// Reset the price circuit breaker of the given token.
+///
+/// ## CHECK
+/// - Only [`MARKET_KEEPER`](crate::states::RoleKey::MARKET_KEEPER) can perform this action.
+pub(crate) fn unchecked_reset_price_circuit_breaker(
+    ctx: Context<ResetPriceCircuitBreaker>,
+    token: Pubkey,
+) -> Result<()> {
+    let mut map = ctx.accounts.token_map.load_token_map_mut()?;
+    let was_tripped = map
+        .get_mut(&token)
+        .ok_or_else(|| error!(CoreError::NotFound))?
+        .price_circuit_breaker_state_mut()
+        .reset();
+
+    msg!(
+        "[Reset Price Circuit Breaker] token = {}, was_tripped = {}",
+        token,
+        was_tripped
+    );
+
+    Ok(())
+}
+
+impl<'info> internal::Authentication<'info> for ResetPriceCircuitBreaker<'info> {
+    fn authority(&self) -> &Signer<'info> {
+        &self.authority
+    }
+
+    fn store(&self) -> &AccountLoader<'info, Store> {
+        &self.store
+    }
+}
+
 impl<'info> internal::Authentication<'info> for SetFeedConfig<'info> {
     fn authority(&self) -> &Signer<'info> {
         &self.authority
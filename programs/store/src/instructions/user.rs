@@ -3,7 +3,7 @@ use gmsol_utils::InitSpace;
 
 use crate::{
     states::{
-        user::{ReferralCodeBytes, ReferralCodeV2, UserHeader},
+        user::{DelegationScope, ReferralCodeBytes, ReferralCodeV2, UserDelegation, UserHeader},
         Seed, Store,
     },
     CoreError,
@@ -308,3 +308,107 @@ pub(crate) fn cancel_referral_code_transfer(
 
     Ok(())
 }
+
+/// The accounts definitions for [`create_user_delegation`](crate::gmsol_store::create_user_delegation)
+/// instruction.
+#[derive(Accounts)]
+pub struct CreateUserDelegation<'info> {
+    /// Owner.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// User Account.
+    #[account(
+        constraint = user.load()?.is_initialized() @ CoreError::InvalidUserAccount,
+        has_one = owner,
+        has_one = store,
+        seeds = [UserHeader::SEED, store.key().as_ref(), owner.key().as_ref()],
+        bump = user.load()?.bump,
+    )]
+    pub user: AccountLoader<'info, UserHeader>,
+    /// The delegate.
+    /// CHECK: only the address is used.
+    pub delegate: UncheckedAccount<'info>,
+    /// The delegation account to create.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UserDelegation::INIT_SPACE,
+        seeds = [UserDelegation::SEED, store.key().as_ref(), owner.key().as_ref(), delegate.key().as_ref()],
+        bump,
+    )]
+    pub delegation: AccountLoader<'info, UserDelegation>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn create_user_delegation(
+    ctx: Context<CreateUserDelegation>,
+    scope: &DelegationScope,
+) -> Result<()> {
+    ctx.accounts.delegation.load_init()?.init(
+        ctx.bumps.delegation,
+        &ctx.accounts.store.key(),
+        ctx.accounts.owner.key,
+        ctx.accounts.delegate.key,
+        scope,
+    )?;
+    msg!(
+        "[Delegation] {} is now a delegate of {}",
+        ctx.accounts.delegate.key,
+        ctx.accounts.owner.key
+    );
+    Ok(())
+}
+
+/// The accounts definitions for [`update_user_delegation`](crate::gmsol_store::update_user_delegation)
+/// instruction.
+#[derive(Accounts)]
+pub struct UpdateUserDelegation<'info> {
+    /// Owner.
+    pub owner: Signer<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// The delegation account to update.
+    #[account(
+        mut,
+        has_one = store,
+        has_one = owner,
+    )]
+    pub delegation: AccountLoader<'info, UserDelegation>,
+}
+
+pub(crate) fn update_user_delegation(
+    ctx: Context<UpdateUserDelegation>,
+    scope: &DelegationScope,
+) -> Result<()> {
+    ctx.accounts.delegation.load_mut()?.set_scope(scope)
+}
+
+/// The accounts definitions for [`revoke_user_delegation`](crate::gmsol_store::revoke_user_delegation)
+/// instruction.
+#[derive(Accounts)]
+pub struct RevokeUserDelegation<'info> {
+    /// Owner.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// The delegation account to close.
+    #[account(
+        mut,
+        close = owner,
+        has_one = store,
+        has_one = owner,
+    )]
+    pub delegation: AccountLoader<'info, UserDelegation>,
+}
+
+pub(crate) fn revoke_user_delegation(ctx: Context<RevokeUserDelegation>) -> Result<()> {
+    msg!(
+        "[Delegation] revoked the delegation of {}",
+        ctx.accounts.delegation.load()?.delegate
+    );
+    Ok(())
+}
@@ -85,6 +85,9 @@
 //! - [`set_expected_provider`]: Set the expected provider for the given token.
 //! - [`set_feed_config_v2`]: Set the feed config of the given provider for the given token.
 //! - [`set_feed_config_market_status_flag`]: Set a market-status flag on the feed config of the given provider for the given token.
+//! - [`set_composite_price`]: Set the composite price config for the given token.
+//! - [`set_price_circuit_breaker`]: Set the price circuit breaker config for the given token.
+//! - [`reset_price_circuit_breaker`]: Reset the price circuit breaker of the given token.
 //! - [`is_token_config_enabled`](gmsol_store::is_token_config_enabled): Check if the config for the given token is enabled.
 //! - [`token_expected_provider`](gmsol_store::token_expected_provider): Get the expected provider set for the given token.
 //! - [`token_feed`](gmsol_store::token_feed): Get the feed address of the given provider set for the given token.
@@ -97,6 +100,8 @@
 //! #### Instructions for [`Oracle`](states::Oracle) accounts
 //! - [`initialize_oracle`](gmsol_store::initialize_oracle): Initialize a new [`Oracle`](states::Oracle) account.
 //! - [`clear_all_prices`]: Clear the prices of the given oracle account.
+//! - [`update_price_circuit_breakers`]: Update the price circuit breakers of the given tokens
+//!   with the prices from the provided price feeds.
 //! - [`set_prices_from_price_feed`]: Validate and set prices parsed from the
 //!   provided price feed accounts.
 //! - [`initialize_price_feed`]: Initialize a custom price feed.
@@ -154,6 +159,7 @@
 //! - [`prepare_trade_event_buffer`](gmsol_store::prepare_trade_event_buffer): Prepare trade event buffer.
 //! - [`create_order_v2`]: Create an order by the owner.
 //! - [`update_order_v2`]: Update an order by the owner.
+//! - [`link_orders`]: Link orders so that executing one cancels the others.
 //! - [`execute_increase_or_swap_order_v2`]: Execute an order by keepers.
 //! - [`execute_decrease_order_v2`]: Execute a decrease order by keepers.
 //! - [`close_order_v2`]: Close an order, either by the owner or by keepers.
@@ -161,6 +167,7 @@
 //! - [`liquidate`]: Perform a liquidation by keepers.
 //! - [`auto_deleverage`]: Perform an ADL by keepers.
 //! - [`update_adl_state`]: Update the ADL state of the market.
+//! - [`update_trailing_stop_order`]: Update the best price of a trailing-stop order.
 //!
 //! ## GLV (GMX Liquidity Vault) Pools
 //! The instructions for providing functionalities for GLV are as follows:
@@ -234,16 +241,18 @@ use self::{
     ops::{
         deposit::CreateDepositParams,
         glv::{CreateGlvDepositParams, CreateGlvWithdrawalParams},
-        order::{CreateOrderParams, PositionCutKind},
+        order::{CreateOrderArgs, CreateOrderOptions, CreateOrderParams, PositionCutKind},
         shift::CreateShiftParams,
         withdrawal::CreateWithdrawalParams,
     },
     states::{
         glv::UpdateGlvParams,
-        market::{config::EntryArgs, status::MarketStatus},
-        order::UpdateOrderParams,
+        market::{config::EntryArgs, status::MarketStatus, MarketTradingMode},
+        order::{UpdateOrderOptions, UpdateOrderParams},
+        risk_oracle::RiskOracleRecommendation,
         token_config::UpdateTokenConfigParams,
-        FactorKey, PriceProviderKind,
+        user::DelegationScope,
+        CompositePriceMode, FactorKey, PriceProviderKind,
     },
     utils::internal,
 };
@@ -1009,6 +1018,126 @@ pub mod gmsol_store {
         )
     }
 
+    /// Set the composite price config for the given token.
+    ///
+    /// A token with composite price enabled is priced from the median, or the
+    /// expected provider with fallback, of the prices of all its price sources,
+    /// and a feed account is required for each of them. The fallback only applies
+    /// when a price is stale or its market is closed; other errors, such as a
+    /// feed account mismatch, are always returned.
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts*](SetFeedConfig).
+    ///
+    /// # Arguments
+    /// - `token`: The token whose config will be updated.
+    /// - `mode`: The [`CompositePriceMode`](states::CompositePriceMode) index.
+    ///   The composite price config is cleared if it is `Disabled`.
+    /// - `sources`: The indices of the providers used as price sources.
+    ///   Must include the expected provider.
+    /// - `max_disagreement_factor`: The maximum allowed disagreement between the prices
+    ///   of the sources and the aggregated price. `None` means no restriction is applied.
+    ///
+    /// # Errors
+    /// - The [`authority`](SetFeedConfig::authority) must be a signer
+    ///   and a MARKET_KEEPER in the given store.
+    /// - The [`token_map`](SetFeedConfig::token_map) must be an initialized token map account
+    ///   owned by the `store`.
+    /// - The given `token` must exist in the token map.
+    /// - The `mode` and `sources` must be valid indices.
+    /// - Unless disabled, there must be at least two distinct `sources`, including the
+    ///   expected provider, and each of them must have an initialized feed config.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn set_composite_price(
+        ctx: Context<SetFeedConfig>,
+        token: Pubkey,
+        mode: u8,
+        sources: Vec<u8>,
+        max_disagreement_factor: Option<u128>,
+    ) -> Result<()> {
+        let mode =
+            CompositePriceMode::try_from(mode).map_err(|_| error!(CoreError::InvalidArgument))?;
+        let sources = sources
+            .into_iter()
+            .map(|provider| {
+                PriceProviderKind::try_from(provider)
+                    .map_err(|_| error!(CoreError::InvalidProviderKindIndex))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        instructions::unchecked_set_composite_price(
+            ctx,
+            token,
+            mode,
+            &sources,
+            max_disagreement_factor,
+        )
+    }
+
+    /// Set the price circuit breaker config for the given token.
+    ///
+    /// Once enabled, the breaker state is stored in the token config and its windows are
+    /// tracked with [`update_price_circuit_breakers`]. If the price moves away from the price
+    /// at the start of the current window by more than the given factor before the window
+    /// elapses, the price is rejected and the breaker is tripped by the next update: increase
+    /// and swap orders, as well as deposits, withdrawals, shifts and GLV actions involving
+    /// the token are rejected until the breaker is reset, while decrease orders and
+    /// liquidations are unaffected.
+    ///
+    /// Note that the execution of actions never tracks the prices, the circuit breaker
+    /// only takes effect once [`update_price_circuit_breakers`] has started a window.
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts*](SetFeedConfig).
+    ///
+    /// # Arguments
+    /// - `token`: The token whose config will be updated.
+    /// - `max_move_factor`: The maximum allowed price move within a window.
+    ///   `None` disables the circuit breaker.
+    /// - `window`: The length of the window in seconds.
+    ///
+    /// # Errors
+    /// - The [`authority`](SetFeedConfig::authority) must be a signer
+    ///   and a MARKET_KEEPER in the given store.
+    /// - The [`token_map`](SetFeedConfig::token_map) must be an initialized token map account
+    ///   owned by the `store`.
+    /// - The given `token` must exist in the token map.
+    /// - Unless disabled, the `window` must not be zero and the `max_move_factor` must be
+    ///   representable as a feed config ratio.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn set_price_circuit_breaker(
+        ctx: Context<SetFeedConfig>,
+        token: Pubkey,
+        max_move_factor: Option<u128>,
+        window: u32,
+    ) -> Result<()> {
+        instructions::unchecked_set_price_circuit_breaker(ctx, token, max_move_factor, window)
+    }
+
+    /// Reset the price circuit breaker of the given token.
+    ///
+    /// The breaker is cleared and starts a new window with the next tracked price,
+    /// so the actions involving the token are allowed again.
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts*](ResetPriceCircuitBreaker).
+    ///
+    /// # Arguments
+    /// - `token`: The token whose price circuit breaker will be reset.
+    ///
+    /// # Errors
+    /// - The [`authority`](ResetPriceCircuitBreaker::authority) must be a signer
+    ///   and a MARKET_KEEPER in the given store.
+    /// - The [`token_map`](ResetPriceCircuitBreaker::token_map) must be the token map of
+    ///   the `store`.
+    /// - The given `token` must exist in the token map.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn reset_price_circuit_breaker(
+        ctx: Context<ResetPriceCircuitBreaker>,
+        token: Pubkey,
+    ) -> Result<()> {
+        instructions::unchecked_reset_price_circuit_breaker(ctx, token)
+    }
+
     /// Return whether the token config is enabled.
     ///
     /// # Accounts
@@ -1205,6 +1334,42 @@ pub mod gmsol_store {
         instructions::unchecked_clear_all_prices(ctx)
     }
 
+    /// Update the price circuit breakers of the given tokens with the prices from the
+    /// provided price feeds.
+    ///
+    /// For each token with a price circuit breaker, a new window is started with the price
+    /// if the current one has elapsed. Otherwise, the breaker is tripped if the price has
+    /// moved away from the price at the start of the window by more than the configured factor.
+    ///
+    /// The execution of actions only checks the breaker states without tracking the prices,
+    /// so that it does not have to write-lock the token map. Keepers must call this
+    /// instruction for the tokens with price circuit breakers at least once per window,
+    /// otherwise no price of the tokens is rejected.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](UpdatePriceCircuitBreakers)*
+    ///
+    /// # Arguments
+    /// - `tokens`: The tokens to update.
+    ///
+    /// # Errors
+    /// - The [`authority`](UpdatePriceCircuitBreakers::authority) must be a signer and have the
+    ///   ORDER_KEEPER role in the store.
+    /// - The [`store`](UpdatePriceCircuitBreakers::store) must be an initialized store account
+    ///   owned by the store program.
+    /// - The [`token_map`](UpdatePriceCircuitBreakers::token_map) must be the token map of
+    ///   the store, and all the `tokens` must exist in it.
+    /// - The [`oracle`](UpdatePriceCircuitBreakers::oracle) must be an initialized oracle account
+    ///   owned by the given store.
+    /// - Price feed accounts must be valid and provided in the order of `tokens`.
+    #[access_control(internal::Authenticate::only_order_keeper(&ctx))]
+    pub fn update_price_circuit_breakers<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdatePriceCircuitBreakers<'info>>,
+        tokens: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::unchecked_update_price_circuit_breakers(ctx, tokens)
+    }
+
     /// Set prices from the provided price feeds.
     ///
     /// This instruction updates token prices in the oracle account using data from configured price feeds.
@@ -1230,6 +1395,7 @@ pub mod gmsol_store {
     /// - The number of tokens provided cannot exceed [`MAX_TOKENS`](crate::states::oracle::price_map::PriceMap::MAX_TOKENS).
     /// - Each token in `tokens` must be configured and enabled in the token map.
     /// - For each token, there must be a valid corresponding price feed account included in the remaining accounts.
+    ///   Tokens with composite price enabled require a price feed account for each of their price sources.
     #[access_control(internal::Authenticate::only_oracle_controller(&ctx))]
     pub fn set_prices_from_price_feed<'info>(
         ctx: Context<'_, '_, 'info, 'info, SetPricesFromPriceFeed<'info>>,
@@ -1443,7 +1609,8 @@ pub mod gmsol_store {
     /// Update an item in the market config.
     ///
     /// This instruction allows the `authority` to update a single configuration value in the market's
-    /// configuration. The key must be one of the predefined market config keys.
+    /// configuration. The key must be one of the predefined market config keys. Any scheduled
+    /// update of the key is cancelled.
     ///
     /// # Accounts
     /// [*See the documentation for the accounts.*](UpdateMarketConfig)
@@ -1501,7 +1668,7 @@ pub mod gmsol_store {
     ///
     /// This instruction allows the `authority` to update multiple market configuration values at once
     /// by applying the changes stored in a buffer account. The buffer must contain valid configuration
-    /// keys and values.
+    /// keys and values. Any scheduled updates of the keys are cancelled.
     ///
     /// # Accounts
     /// [*See the documentation for the accounts.*](UpdateMarketConfigWithBuffer)
@@ -1526,6 +1693,101 @@ pub mod gmsol_store {
         instructions::unchecked_update_market_config_with_buffer(ctx)
     }
 
+    /// Schedule a gradual update of an item in the market config.
+    ///
+    /// The effective value is ramped linearly from the current value to the target value over
+    /// `duration` seconds starting at `start_at`, and is applied whenever the market is loaded
+    /// for execution. Scheduling a key that already has a schedule replaces it, and updating
+    /// the key directly cancels it.
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts.*](UpdateMarketConfig)
+    ///
+    /// # Arguments
+    /// - `key`: The configuration key to update. Must be a valid key defined in
+    ///   [`MarketConfigKey`](states::market::config::MarketConfigKey).
+    /// - `value`: The target value of the configuration key.
+    /// - `start_at`: The time to start the ramp at. A time in the past starts it immediately.
+    /// - `duration`: The duration of the ramp in seconds. Zero means a step change at `start_at`.
+    ///
+    /// # Errors
+    /// - The [`authority`](UpdateMarketConfig::authority) must be a signer and have permission to update
+    ///   market config.
+    /// - The [`store`](UpdateMarketConfig::store) must be an initialized store account owned by this program.
+    /// - The [`market`](UpdateMarketConfig::market) must be an initialized market account owned by the store.
+    /// - The provided `key` must be defined in [`MarketConfigKey`](states::market::config::MarketConfigKey).
+    /// - The number of scheduled updates of the market must not exceed
+    ///   [`MAX_MARKET_CONFIG_SCHEDULES`](states::market::config::MAX_MARKET_CONFIG_SCHEDULES).
+    #[access_control(internal::Authenticate::ensure_can_update_market_config(&ctx))]
+    pub fn schedule_market_config_update(
+        ctx: Context<UpdateMarketConfig>,
+        key: String,
+        value: u128,
+        start_at: i64,
+        duration: u32,
+    ) -> Result<()> {
+        instructions::unchecked_schedule_market_config_update(ctx, &key, value, start_at, duration)
+    }
+
+    /// Schedule gradual updates of the market config using a pre-populated
+    /// [`MarketConfigBuffer`](crate::states::market::config::MarketConfigBuffer) account.
+    ///
+    /// Every entry of the buffer is scheduled with the same `start_at` and `duration`, as in
+    /// [`schedule_market_config_update`](crate::gmsol_store::schedule_market_config_update).
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts.*](UpdateMarketConfigWithBuffer)
+    ///
+    /// # Arguments
+    /// - `start_at`: The time to start the ramps at. A time in the past starts them immediately.
+    /// - `duration`: The duration of the ramps in seconds.
+    ///
+    /// # Errors
+    /// - The [`authority`](UpdateMarketConfigWithBuffer::authority) must be a signer and have permission to update
+    ///   market config.
+    /// - The [`store`](UpdateMarketConfigWithBuffer::store) must be an initialized store account
+    ///   owned by this program.
+    /// - The [`market`](UpdateMarketConfigWithBuffer::market) must be an initialized market account
+    ///   owned by the store.
+    /// - The [`buffer`](UpdateMarketConfigWithBuffer::buffer) must be:
+    ///   - An initialized market config buffer account
+    ///   - Owned by both the store and the authority
+    ///   - Not expired
+    /// - The number of scheduled updates of the market must not exceed
+    ///   [`MAX_MARKET_CONFIG_SCHEDULES`](states::market::config::MAX_MARKET_CONFIG_SCHEDULES).
+    #[access_control(internal::Authenticate::ensure_can_update_market_config(&ctx))]
+    pub fn schedule_market_config_update_with_buffer(
+        ctx: Context<UpdateMarketConfigWithBuffer>,
+        start_at: i64,
+        duration: u32,
+    ) -> Result<()> {
+        instructions::unchecked_schedule_market_config_update_with_buffer(ctx, start_at, duration)
+    }
+
+    /// Cancel the scheduled update of an item in the market config.
+    ///
+    /// The item keeps its effective value at the time of cancellation.
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts.*](UpdateMarketConfig)
+    ///
+    /// # Arguments
+    /// - `key`: The configuration key of the scheduled update.
+    ///
+    /// # Errors
+    /// - The [`authority`](UpdateMarketConfig::authority) must be a signer and have permission to update
+    ///   market config.
+    /// - The [`store`](UpdateMarketConfig::store) must be an initialized store account owned by this program.
+    /// - The [`market`](UpdateMarketConfig::market) must be an initialized market account owned by the store.
+    /// - The provided `key` must have a scheduled update.
+    #[access_control(internal::Authenticate::ensure_can_update_market_config(&ctx))]
+    pub fn cancel_market_config_schedule(
+        ctx: Context<UpdateMarketConfig>,
+        key: String,
+    ) -> Result<()> {
+        instructions::unchecked_cancel_market_config_schedule(ctx, &key)
+    }
+
     /// Calculate the current market status.
     ///
     /// This instruction calculates and returns the current status of a market, including metrics like
@@ -1784,6 +2046,37 @@ pub mod gmsol_store {
         instructions::unchecked_toggle_gt_minting(ctx, enable)
     }
 
+    /// Set the trading mode of the given market.
+    ///
+    /// This instruction allows a MARKET_KEEPER to restrict the orders accepted by the given
+    /// market without affecting other markets. In reduce-only mode, only decrease orders are
+    /// accepted. In close-only mode, only decrease orders closing the whole position are
+    /// accepted. Liquidation and auto-deleveraging are allowed in every mode. Swap orders
+    /// are rejected in every mode other than normal, and so is any order whose swap path
+    /// goes through another market that is not in normal mode.
+    ///
+    /// The trading mode is checked both when an order is created and when it is executed.
+    ///
+    /// # Accounts
+    /// [*See the documentation for the accounts.*](SetMarketTradingMode)
+    ///
+    /// # Arguments
+    /// - `mode`: The [`MarketTradingMode`](states::market::MarketTradingMode) index.
+    ///
+    /// # Errors
+    /// - The [`authority`](SetMarketTradingMode::authority) must be a signer and be a
+    ///   MARKET_KEEPER in the store.
+    /// - The [`store`](SetMarketTradingMode::store) must be an initialized store account.
+    /// - The [`market`](SetMarketTradingMode::market) must be an initialized market account
+    ///   and owned by the store.
+    /// - The `mode` must be a valid index.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn set_market_trading_mode(ctx: Context<SetMarketTradingMode>, mode: u8) -> Result<()> {
+        let mode =
+            MarketTradingMode::try_from(mode).map_err(|_| error!(CoreError::InvalidArgument))?;
+        instructions::unchecked_set_market_trading_mode(ctx, mode)
+    }
+
     /// Claim fees from the given market.
     ///
     /// # Accounts
@@ -2163,6 +2456,35 @@ pub mod gmsol_store {
         instructions::prepare_position(ctx, &params)
     }
 
+    /// Prepare the position account for orders by a delegate of the owner.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](PreparePositionByDelegate)*
+    ///
+    /// # Arguments
+    /// - `params`: Order Parameters.
+    ///
+    /// # Errors
+    /// This instruction will fail if:
+    /// - The [`delegate`](PreparePositionByDelegate::delegate) is not a signer or has insufficient
+    ///   balance for the rent.
+    /// - The [`delegation`](PreparePositionByDelegate::delegation) does not authorize the `delegate`
+    ///   to trade on behalf of the [`owner`](PreparePositionByDelegate::owner), has expired, or
+    ///   does not cover the given market, order kind or size.
+    /// - The [`store`](PreparePositionByDelegate::store) is not properly initialized.
+    /// - The [`market`](PreparePositionByDelegate::market) is not initialized, is disabled, or not
+    ///   owned by the `store`.
+    /// - The [`position`](PreparePositionByDelegate::position) address is not a valid PDA derived
+    ///   from the `owner` and expected seeds.
+    /// - The position account is neither uninitialized nor validly initialized with `store` as the
+    ///   store and `owner` as the owner.
+    pub fn prepare_position_by_delegate(
+        ctx: Context<PreparePositionByDelegate>,
+        params: CreateOrderParams,
+    ) -> Result<()> {
+        instructions::prepare_position_by_delegate(ctx, &params)
+    }
+
     /// Create an order by the owner.
     ///
     /// # Accounts
@@ -2208,13 +2530,81 @@ pub mod gmsol_store {
     ///   by the `store`.
     /// - The accounts related to callback must be provided if
     ///   [`callback_authority`](CreateOrderV2::callback_authority) is provided.
+    /// - The [`callback_program_config`](CreateOrderV2::callback_program_config) must be
+    ///   provided if a callback is requested, and allow the callback program to receive
+    ///   callbacks for orders.
     pub fn create_order_v2<'info>(
         mut ctx: Context<'_, '_, 'info, 'info, CreateOrderV2<'info>>,
         nonce: [u8; 32],
         params: CreateOrderParams,
         callback_version: Option<u8>,
     ) -> Result<()> {
-        internal::Create::create(&mut ctx, &nonce, &params, callback_version)
+        let args = CreateOrderArgs::new(params, Default::default());
+        internal::Create::create(&mut ctx, &nonce, &args, callback_version)
+    }
+
+    /// Create an order with options by the owner.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](CreateOrderV2)*
+    ///
+    /// # Arguments
+    /// - `nonce`: Nonce bytes used to derive the address for the order.
+    /// - `params`: Order Parameters specifying the market, order kind, and other details.
+    /// - `callback_version`: The version of the callback to use.
+    /// - `options`: Order Options, including the trailing distance and the TWAP params.
+    ///
+    /// # Errors
+    /// This instruction will fail if:
+    /// - The trailing distance is provided but the order is not a trailing-stop order, or it
+    ///   is not less than the trigger price (as an absolute distance) or one (as a factor).
+    /// - The TWAP params are provided but the order is not a market swap or market decrease
+    ///   order, or the number of slices is out of range.
+    /// - Any of the conditions of [`create_order_v2`] is not met.
+    pub fn create_order_v3<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, CreateOrderV2<'info>>,
+        nonce: [u8; 32],
+        params: CreateOrderParams,
+        callback_version: Option<u8>,
+        options: CreateOrderOptions,
+    ) -> Result<()> {
+        let args = CreateOrderArgs::new(params, options);
+        internal::Create::create(&mut ctx, &nonce, &args, callback_version)
+    }
+
+    /// Create an order by a delegate of the owner.
+    ///
+    /// The output funds of the order are always sent to the owner.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](CreateOrderByDelegate)*
+    ///
+    /// # Arguments
+    /// - `nonce`: Nonce bytes used to derive the address for the order.
+    /// - `params`: Order Parameters specifying the market, order kind, and other details.
+    /// - `options`: Order Options, including the trailing distance and the TWAP params.
+    ///
+    /// # Errors
+    /// This instruction will fail if:
+    /// - The [`delegate`](CreateOrderByDelegate::delegate) is not a signer or has insufficient
+    ///   balance for the execution fee and rent.
+    /// - The [`delegation`](CreateOrderByDelegate::delegation) does not authorize the `delegate`
+    ///   to trade on behalf of the [`owner`](CreateOrderByDelegate::owner), has expired, or
+    ///   does not cover the given market, order kind or size.
+    /// - The [`order`](CreateOrderByDelegate::order) is not uninitialized or the address is not
+    ///   a valid PDA derived from the `delegate`, `nonce` and expected seeds.
+    /// - The `delegate` is not approved to transfer from the
+    ///   [`initial_collateral_token_source`](CreateOrderByDelegate::initial_collateral_token_source)
+    ///   when initial collateral is provided.
+    /// - Any of the conditions of [`create_order_v3`] is not met, with the `owner` as the receiver.
+    pub fn create_order_by_delegate<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, CreateOrderByDelegate<'info>>,
+        nonce: [u8; 32],
+        params: CreateOrderParams,
+        options: CreateOrderOptions,
+    ) -> Result<()> {
+        let args = CreateOrderArgs::new(params, options);
+        internal::Create::create(&mut ctx, &nonce, &args, None)
     }
 
     /// Close an order, either by the owner or by keepers.
@@ -2227,7 +2617,8 @@ pub mod gmsol_store {
     ///
     /// # Errors
     /// - The [`executor`](CloseOrderV2::executor) must be a signer and either the owner
-    ///   of the `order` or a ORDER_KEEPER in the store.
+    ///   of the `order`, a delegate of the owner authorized by the
+    ///   [`delegation`](CloseOrderV2::delegation), or a ORDER_KEEPER in the store.
     /// - The [`store`](CloseOrderV2::store) must be initialized.
     /// - The [`owner`](CloseOrderV2::owner) must be the owner of the `order`.
     /// - The [`user`](CloseOrderV2::user) must be initialized and correspond to the `owner`.
@@ -2325,7 +2716,52 @@ pub mod gmsol_store {
     /// - The feature must be enabled in the `store` for updating the given kind of `order`.
     /// - The updated parameters must be valid for the order type.
     pub fn update_order_v2(ctx: Context<UpdateOrderV2>, params: UpdateOrderParams) -> Result<()> {
-        UpdateOrderV2::invoke(ctx, &params)
+        UpdateOrderV2::invoke(ctx, &params, &Default::default())
+    }
+
+    /// Update an order with options by the owner.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](UpdateOrderV2)*
+    ///
+    /// # Arguments
+    /// - `params`: Update Order Parameters.
+    /// - `options`: Update Order Options, including the trailing distance and the best price.
+    ///
+    /// # Errors
+    /// - The options must be empty unless the `order` is a trailing-stop order.
+    /// - The trailing distance must be less than the best price (as an absolute distance)
+    ///   or one (as a factor).
+    /// - Any of the conditions of [`update_order_v2`] is not met.
+    pub fn update_order_v3(
+        ctx: Context<UpdateOrderV2>,
+        params: UpdateOrderParams,
+        options: UpdateOrderOptions,
+    ) -> Result<()> {
+        UpdateOrderV2::invoke(ctx, &params, &options)
+    }
+
+    /// Update an order by a delegate of the owner.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](UpdateOrderByDelegate)*
+    ///
+    /// # Arguments
+    /// - `params`: Update Order Parameters.
+    /// - `options`: Update Order Options, including the trailing distance and the best price.
+    ///
+    /// # Errors
+    /// - The [`delegate`](UpdateOrderByDelegate::delegate) must be a signer.
+    /// - The [`delegation`](UpdateOrderByDelegate::delegation) must authorize the `delegate` to
+    ///   trade on behalf of the [`owner`](UpdateOrderByDelegate::owner), must not have expired,
+    ///   and must cover the market, kind and updated size of the `order`.
+    /// - Any of the conditions of [`update_order_v3`] is not met.
+    pub fn update_order_by_delegate(
+        ctx: Context<UpdateOrderByDelegate>,
+        params: UpdateOrderParams,
+        options: UpdateOrderOptions,
+    ) -> Result<()> {
+        UpdateOrderByDelegate::invoke(ctx, &params, &options)
     }
 
     /// Set whether to keep position account for an order by the owner.
@@ -2348,8 +2784,30 @@ pub mod gmsol_store {
         SetShouldKeepPositionAccount::invoke(ctx, keep)
     }
 
+    /// Link orders by the owner, so that executing one of them closes the others
+    /// (one-cancels-other).
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](LinkOrders)*
+    ///
+    /// # Errors
+    /// - The [`owner`](LinkOrders::owner) must be a signer and the owner of all the orders.
+    /// - The number of orders provided in the remaining accounts must be between `2` and
+    ///   [`Order::MAX_LINKED_ORDERS`](states::Order::MAX_LINKED_ORDERS).
+    /// - The orders must be:
+    ///   - Initialized, distinct and owned by both the `store` and the `owner`
+    ///   - Associated with the same market and the same position (side and collateral token)
+    ///   - In a pending state
+    ///   - Limit, stop-loss or trailing-stop decrease orders without callbacks, and not linked yet
+    pub fn link_orders<'info>(ctx: Context<'_, '_, 'info, 'info, LinkOrders<'info>>) -> Result<()> {
+        LinkOrders::invoke(ctx)
+    }
+
     /// Execute an increase/swap order by keepers.
     ///
+    /// For TWAP orders, only the next slice is executed. The order is kept pending until all
+    /// of its slices are executed.
+    ///
     /// # Accounts
     /// *[See the documentation for the accounts.](ExecuteIncreaseOrSwapOrderV2)*
     ///
@@ -2416,6 +2874,13 @@ pub mod gmsol_store {
 
     /// Execute a decrease order by keepers.
     ///
+    /// If the order is linked with other orders, the pending linked orders will be cancelled
+    /// and closed once the order is executed successfully, with their rent and execution
+    /// lamports refunded to their rent receivers.
+    ///
+    /// For TWAP orders, only the next slice is executed. The order is kept pending until all
+    /// of its slices are executed.
+    ///
     /// # Accounts
     /// *[See the documentation for the accounts.](ExecuteDecreaseOrderV2)*
     ///
@@ -2567,6 +3032,33 @@ pub mod gmsol_store {
         instructions::unchecked_update_adl_state(ctx, is_long)
     }
 
+    /// Update the best price of a trailing-stop order with oracle prices by keepers.
+    ///
+    /// The trigger price of the order follows the best price by the trailing distance.
+    /// The best price is also ratcheted with the execution prices when the order is
+    /// executed, so this instruction is only needed to track the prices in between.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](UpdateTrailingStopOrder)*
+    ///
+    /// # Errors
+    /// - The [`authority`](UpdateTrailingStopOrder::authority) must be a signer and have the
+    ///   ORDER_KEEPER role in the store.
+    /// - The [`store`](UpdateTrailingStopOrder::store) must be initialized.
+    /// - The [`oracle`](UpdateTrailingStopOrder::oracle) must be an initialized [`Oracle`](states::Oracle)
+    ///   account that is owned by the store.
+    /// - The [`market`](UpdateTrailingStopOrder::market) must be owned by the store.
+    /// - The [`order`](UpdateTrailingStopOrder::order) must be a pending trailing-stop order
+    ///   of the `market`.
+    /// - Price feed accounts must be valid and provided in the market's sorted token list order.
+    /// - The oracle prices must not be older than the last update of the order.
+    #[access_control(internal::Authenticate::only_order_keeper(&ctx))]
+    pub fn update_trailing_stop_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateTrailingStopOrder<'info>>,
+    ) -> Result<()> {
+        instructions::unchecked_update_trailing_stop_order(ctx)
+    }
+
     /// Perform an ADL (Auto-Deleveraging) by keepers.
     ///
     /// # Accounts
@@ -3168,6 +3660,63 @@ pub mod gmsol_store {
         instructions::accept_referral_code(ctx)
     }
 
+    /// Create a delegation that authorizes a delegate to trade on behalf of the owner.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](CreateUserDelegation)*
+    ///
+    /// # Arguments
+    /// - `scope`: The scope of the delegation.
+    ///
+    /// # Errors
+    /// - The [`owner`](CreateUserDelegation::owner) must be a signer.
+    /// - The [`store`](CreateUserDelegation::store) must be properly initialized.
+    /// - The [`user`](CreateUserDelegation::user) must be initialized and correspond to the `owner`.
+    /// - The [`delegation`](CreateUserDelegation::delegation) must be uninitialized and its address
+    ///   must be a valid PDA derived from the `owner` and the
+    ///   [`delegate`](CreateUserDelegation::delegate).
+    /// - The `delegate` must not be the `owner`.
+    /// - The `scope` must be valid.
+    pub fn create_user_delegation(
+        ctx: Context<CreateUserDelegation>,
+        scope: DelegationScope,
+    ) -> Result<()> {
+        instructions::create_user_delegation(ctx, &scope)
+    }
+
+    /// Update the scope of a delegation.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](UpdateUserDelegation)*
+    ///
+    /// # Arguments
+    /// - `scope`: The new scope of the delegation.
+    ///
+    /// # Errors
+    /// - The [`owner`](UpdateUserDelegation::owner) must be a signer and the owner of the
+    ///   [`delegation`](UpdateUserDelegation::delegation).
+    /// - The `delegation` must be owned by the [`store`](UpdateUserDelegation::store).
+    /// - The `scope` must be valid.
+    pub fn update_user_delegation(
+        ctx: Context<UpdateUserDelegation>,
+        scope: DelegationScope,
+    ) -> Result<()> {
+        instructions::update_user_delegation(ctx, &scope)
+    }
+
+    /// Revoke a delegation.
+    ///
+    /// # Accounts
+    /// *[See the documentation for the accounts.](RevokeUserDelegation)*
+    ///
+    /// # Errors
+    /// - The [`owner`](RevokeUserDelegation::owner) must be a signer and the owner of the
+    ///   [`delegation`](RevokeUserDelegation::delegation).
+    /// - The `delegation` must be owned by the [`store`](RevokeUserDelegation::store).
+    pub fn revoke_user_delegation(ctx: Context<RevokeUserDelegation>) -> Result<()> {
+        instructions::revoke_user_delegation(ctx)
+    }
+
     // ===========================================
     //                GLV Operations
     // ===========================================
@@ -3855,6 +4404,46 @@ pub mod gmsol_store {
         InitializeCallbackAuthority::invoke(ctx)
     }
 
+    /// Add a program to the callback program allowlist of the store.
+    ///
+    /// # Arguments
+    /// - `action_kinds`: The bitmap of the action kinds the program is allowed to receive
+    ///   callbacks for, where the bit at the index of an
+    ///   [`ActionKind`](gmsol_callback::interface::ActionKind) is set if the kind is allowed.
+    /// - `compute_unit_limit`: The hint for the compute unit limit required by the callback.
+    ///
+    /// # Errors
+    /// - The [`program`](RegisterCallbackProgram::program) must be executable.
+    /// - The program must not have been registered.
+    #[access_control(internal::Authenticate::only_config_keeper(&ctx))]
+    pub fn register_callback_program(
+        ctx: Context<RegisterCallbackProgram>,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> Result<()> {
+        RegisterCallbackProgram::invoke_unchecked(ctx, action_kinds, compute_unit_limit)
+    }
+
+    /// Update the allowed action kinds and the compute unit limit hint of a
+    /// [`CallbackProgram`](crate::states::callback::CallbackProgram) account.
+    #[access_control(internal::Authenticate::only_config_keeper(&ctx))]
+    pub fn update_callback_program(
+        ctx: Context<UpdateCallbackProgram>,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) -> Result<()> {
+        UpdateCallbackProgram::invoke_unchecked(ctx, action_kinds, compute_unit_limit)
+    }
+
+    /// Remove a program from the callback program allowlist of the store.
+    ///
+    /// Actions created with a callback to the program before its removal can
+    /// still be executed or closed with the callback.
+    #[access_control(internal::Authenticate::only_config_keeper(&ctx))]
+    pub fn unregister_callback_program(ctx: Context<UnregisterCallbackProgram>) -> Result<()> {
+        UnregisterCallbackProgram::invoke_unchecked(ctx)
+    }
+
     /// Close an unused [`VirtualInventory`](crate::states::market::virtual_inventory::VirtualInventory) account.
     #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
     pub fn close_virtual_inventory(ctx: Context<CloseVirtualInventory>) -> Result<()> {
@@ -3930,6 +4519,71 @@ pub mod gmsol_store {
     ) -> Result<()> {
         JoinOrLeaveVirtualInventoryForPositions::invoke_leave_unchecked(ctx)
     }
+
+    /// Initialize the [`RiskOracle`](crate::states::risk_oracle::RiskOracle) account of the store.
+    ///
+    /// # Arguments
+    /// - `signer`: The keccak hash of the compressed secp256k1 public key of the risk oracle signer.
+    /// - `max_age`: The max age of recommendations in seconds. `0` means unlimited.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn initialize_risk_oracle(
+        ctx: Context<InitializeRiskOracle>,
+        signer: Pubkey,
+        max_age: u32,
+    ) -> Result<()> {
+        InitializeRiskOracle::invoke_unchecked(ctx, signer, max_age)
+    }
+
+    /// Update the signer and the max age of recommendations of the
+    /// [`RiskOracle`](crate::states::risk_oracle::RiskOracle) account.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn update_risk_oracle(
+        ctx: Context<ConfigureRiskOracle>,
+        signer: Pubkey,
+        max_age: u32,
+    ) -> Result<()> {
+        ConfigureRiskOracle::invoke_update_unchecked(ctx, signer, max_age)
+    }
+
+    /// Set the bounded range of a market config key that the risk oracle is allowed to update.
+    ///
+    /// # Errors
+    /// - The `key` must be a valid [`MarketConfigKey`](states::market::config::MarketConfigKey).
+    /// - `min` must not be greater than `max`.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn set_risk_oracle_bound(
+        ctx: Context<ConfigureRiskOracle>,
+        key: String,
+        min: u128,
+        max: u128,
+    ) -> Result<()> {
+        ConfigureRiskOracle::invoke_set_bound_unchecked(ctx, &key, min, max)
+    }
+
+    /// Remove the bounded range of a market config key, disallowing the risk oracle to update it.
+    #[access_control(internal::Authenticate::only_market_keeper(&ctx))]
+    pub fn remove_risk_oracle_bound(ctx: Context<ConfigureRiskOracle>, key: String) -> Result<()> {
+        ConfigureRiskOracle::invoke_remove_bound_unchecked(ctx, &key)
+    }
+
+    /// Apply a recommendation signed by the risk oracle to the market.
+    ///
+    /// Anyone can relay a recommendation, since it is applied only if it is signed
+    /// by the signer of the risk oracle and every value is within its bound.
+    ///
+    /// # Errors
+    /// - The parameter of the recommendation must be supported, and the values of
+    ///   all its keys must be provided.
+    /// - The signature must be signed by the [`signer`](crate::states::risk_oracle::RiskOracle::signer).
+    /// - The recommendation must be newer than the last applied recommendation of the same
+    ///   parameter, and not older than the max age.
+    /// - Every value must be within the bound of its key.
+    pub fn apply_risk_oracle_recommendation(
+        ctx: Context<ApplyRiskOracleRecommendation>,
+        recommendation: RiskOracleRecommendation,
+    ) -> Result<()> {
+        ApplyRiskOracleRecommendation::invoke(ctx, &recommendation)
+    }
 }
 
 /// Result type with [`CoreError`] as error type.
@@ -4361,6 +5015,51 @@ pub enum CoreError {
     /// Market is closed.
     #[msg("market is closed")]
     MarketClosed,
+    // ===========================================
+    //              Order Errors (2)
+    // ===========================================
+    /// Invalid trailing distance.
+    #[msg("invalid trailing distance")]
+    InvalidTrailingDistance,
+    /// Oracle prices are older than the order.
+    #[msg("oracle prices are older than the order")]
+    OraclePricesOlderThanOrder,
+    /// Order kind is not linkable.
+    #[msg("order kind is not linkable")]
+    OrderKindNotLinkable,
+    /// Linked order mismatched.
+    #[msg("linked order mismatched")]
+    LinkedOrderMismatched,
+    /// Invalid TWAP params.
+    #[msg("invalid TWAP params")]
+    InvalidTwapParams,
+    // ===========================================
+    //              Oracle Errors (2)
+    // ===========================================
+    /// Price circuit breaker has been tripped.
+    #[msg("price circuit breaker has been tripped")]
+    PriceCircuitBreakerTripped,
+    // ===========================================
+    //            Risk Oracle Errors
+    // ===========================================
+    /// Invalid risk oracle recommendation.
+    #[msg("invalid risk oracle recommendation")]
+    InvalidRiskOracleRecommendation,
+    /// Risk oracle signature verification failed.
+    #[msg("risk oracle signature verification failed")]
+    RiskOracleSignatureVerificationFailed,
+    /// Stale risk oracle recommendation.
+    #[msg("stale risk oracle recommendation")]
+    StaleRiskOracleRecommendation,
+    /// Risk oracle value is out of bounds.
+    #[msg("risk oracle value is out of bounds")]
+    RiskOracleValueOutOfBounds,
+    // ===========================================
+    //              Market Errors (3)
+    // ===========================================
+    /// Order is not allowed by the trading mode of the market.
+    #[msg("order is not allowed by the trading mode of the market")]
+    TradingModeRestricted,
 }
 
 #[cfg(not(feature = "no-entrypoint"))]
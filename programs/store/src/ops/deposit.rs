@@ -233,7 +233,22 @@ impl ExecuteDepositOperation<'_, '_> {
     }
 
     fn validate_oracle(&self) -> CoreResult<()> {
-        self.oracle.validate_time(self)
+        self.oracle.validate_time(self)?;
+        self.validate_price_circuit_breakers()
+    }
+
+    /// Deposits are not allowed while the price circuit breaker of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> CoreResult<()> {
+        let market = self
+            .market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        let deposit = self
+            .deposit
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*market, deposit.swap().tokens())
     }
 
     fn validate_before_execution(&self) -> Result<()> {
@@ -15,7 +15,7 @@ use crate::{
     states::{
         common::{
             action::{Action, ActionExt, ActionParams, ActionSigner},
-            swap::SwapActionParamsExt,
+            swap::{HasSwapParams, SwapActionParamsExt},
         },
         glv::{GlvShift, GlvWithdrawal},
         market::revertible::Revertible,
@@ -296,7 +296,22 @@ impl ExecuteGlvDepositOperation<'_, '_> {
     }
 
     fn validate_oracle(&self) -> CoreResult<()> {
-        self.oracle.validate_time(self)
+        self.oracle.validate_time(self)?;
+        self.validate_price_circuit_breakers()
+    }
+
+    /// GLV deposits are not allowed while the price circuit breaker of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> CoreResult<()> {
+        let market = self
+            .market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        let glv_deposit = self
+            .glv_deposit
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*market, glv_deposit.swap().tokens())
     }
 
     fn validate_before_execution(&self) -> Result<()> {
@@ -786,7 +801,22 @@ impl ExecuteGlvWithdrawalOperation<'_, '_> {
     }
 
     fn validate_oracle(&self) -> CoreResult<()> {
-        self.oracle.validate_time(self)
+        self.oracle.validate_time(self)?;
+        self.validate_price_circuit_breakers()
+    }
+
+    /// GLV withdrawals are not allowed while the price circuit breaker of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> CoreResult<()> {
+        let market = self
+            .market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        let glv_withdrawal = self
+            .glv_withdrawal
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*market, glv_withdrawal.swap().tokens())
     }
 
     fn validate_market(&self) -> Result<()> {
@@ -1249,7 +1279,24 @@ impl ExecuteGlvShiftOperation<'_, '_> {
     }
 
     fn validate_oracle(&self) -> CoreResult<()> {
-        self.oracle.validate_time(self)
+        self.oracle.validate_time(self)?;
+        self.validate_price_circuit_breakers()
+    }
+
+    /// GLV shifts are not allowed while the price circuit breaker of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> CoreResult<()> {
+        let from_market = self
+            .from_market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        let to_market = self
+            .to_market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*from_market, [])?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*to_market, [])
     }
 
     fn validate_before_execution(&self) -> Result<()> {
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount};
+use anchor_spl::token::{close_account, CloseAccount, Mint, TokenAccount};
 use gmsol_callback::interface::ActionKind;
 use gmsol_model::{
     action::decrease_position::{DecreasePositionFlags, DecreasePositionSwapType},
@@ -14,9 +14,9 @@ use typed_builder::TypedBuilder;
 use crate::{
     events::{EventEmitter, OrderUpdated, PositionDecreased, PositionIncreased, TradeData},
     states::{
-        callback::CallbackAuthority,
+        callback::{CallbackAuthority, CallbackProgram},
         common::{
-            action::{Action, ActionExt, ActionParams, On},
+            action::{Action, ActionExt, ActionParams, Closable, On},
             swap::SwapActionParamsExt,
         },
         market::{
@@ -28,7 +28,10 @@ use crate::{
             },
             utils::{Adl, ClosableMarket, ValidateMarketBalances},
         },
-        order::{Order, OrderActionParams, OrderKind, OrderTokenAccounts, TransferOut},
+        order::{
+            Order, OrderActionParams, OrderKind, OrderTokenAccounts, TrailingDistance, TransferOut,
+            TwapParams, TwapRemaining,
+        },
         position::PositionKind,
         user::UserHeader,
         AmountKey, HasMarketMeta, Market, MarketPriceOptions, NonceBytes, Oracle, Position, Store,
@@ -81,6 +84,52 @@ impl ActionParams for CreateOrderParams {
     }
 }
 
+/// Create Order Options.
+///
+/// The order parameters that are not part of [`CreateOrderParams`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, InitSpace)]
+pub struct CreateOrderOptions {
+    /// Trailing distance.
+    /// Only required by trailing-stop orders.
+    pub trailing_distance: Option<TrailingDistance>,
+    /// TWAP params.
+    /// Only available for market swap and market decrease orders.
+    pub twap: Option<TwapParams>,
+}
+
+impl CreateOrderOptions {
+    /// Execution lamports for all executions.
+    ///
+    /// A TWAP order is executed once per slice, so the execution fee
+    /// for every slice is required.
+    fn total_execution_lamports(&self, execution_lamports: u64) -> u64 {
+        match &self.twap {
+            Some(twap) => execution_lamports.saturating_mul(twap.slices.max(1).into()),
+            None => execution_lamports,
+        }
+    }
+}
+
+/// Create Order Arguments, including the options.
+#[derive(Clone, Debug)]
+pub(crate) struct CreateOrderArgs {
+    pub(crate) params: CreateOrderParams,
+    pub(crate) options: CreateOrderOptions,
+}
+
+impl CreateOrderArgs {
+    pub(crate) fn new(params: CreateOrderParams, options: CreateOrderOptions) -> Self {
+        Self { params, options }
+    }
+}
+
+impl ActionParams for CreateOrderArgs {
+    fn execution_lamports(&self) -> u64 {
+        self.options
+            .total_execution_lamports(self.params.execution_lamports)
+    }
+}
+
 impl CreateOrderParams {
     /// Get the related position kind.
     pub fn to_position_kind(&self) -> Result<PositionKind> {
@@ -121,9 +170,19 @@ pub(crate) struct CreateOrderOperation<'a, 'info> {
         )
     )]
     creator: Option<AccountInfo<'info>>,
+    #[builder(
+        default,
+        setter(
+            strip_option,
+            doc = "Set the rent receiver of this order. Defaults to the owner."
+        )
+    )]
+    rent_receiver: Option<AccountInfo<'info>>,
     nonce: &'a NonceBytes,
     bump: u8,
     params: &'a CreateOrderParams,
+    #[builder(default)]
+    options: CreateOrderOptions,
     swap_path: &'info [AccountInfo<'info>],
     callback_version: Option<u8>,
     callback_authority: Option<&'a Account<'info, CallbackAuthority>>,
@@ -131,6 +190,8 @@ pub(crate) struct CreateOrderOperation<'a, 'info> {
     callback_shared_data_account: Option<&'a AccountInfo<'info>>,
     callback_partitioned_data_account: Option<&'a AccountInfo<'info>>,
     #[builder(default)]
+    callback_program_config: Option<&'a AccountLoader<'info, CallbackProgram>>,
+    #[builder(default)]
     allow_closed: bool,
     #[builder(setter(into))]
     event_emitter: Option<EventEmitter<'a, 'info>>,
@@ -168,7 +229,40 @@ impl<'a, 'info> CreateOrderOperation<'a, 'info> {
         self.market
             .load()?
             .validate_with_options(&self.store.key(), self.allow_closed)?;
-        ActionExt::validate_balance(&self.order, self.params.execution_lamports)?;
+        ActionExt::validate_balance(
+            &self.order,
+            self.options
+                .total_execution_lamports(self.params.execution_lamports),
+        )?;
+        require!(
+            self.params.kind.is_trailing_stop() || self.options.trailing_distance.is_none(),
+            CoreError::InvalidTrailingDistance
+        );
+        if let Some(twap) = self.options.twap.as_ref() {
+            require!(
+                self.params.kind.is_twap_supported() && twap.is_valid(),
+                CoreError::InvalidTwapParams
+            );
+        }
+        Ok(())
+    }
+
+    fn validate_trading_mode(&self, is_full_close: bool) -> Result<()> {
+        self.market
+            .load()?
+            .validate_trading_mode(self.params.kind, is_full_close)?;
+        // The current market is covered by the check above.
+        let current_market = self.market.key();
+        for info in self
+            .swap_path
+            .iter()
+            .take(usize::from(self.params.swap_path_length))
+            .filter(|info| *info.key != current_market)
+        {
+            AccountLoader::<Market>::try_from(info)?
+                .load()?
+                .validate_swap_allowed_by_trading_mode()?;
+        }
         Ok(())
     }
 
@@ -210,9 +304,14 @@ impl<'a, 'info> CreateOrderOperation<'a, 'info> {
                 header.unchecked_set_creator(creator.key());
             }
 
+            if let Some(rent_receiver) = self.rent_receiver.as_ref() {
+                header.set_rent_receiver(rent_receiver.key());
+            }
+
             *market_token = self.market.load()?.meta().market_token_mint;
 
             let (from, to) = (f)(self.params, tokens, params)?;
+            params.init_twap(self.options.twap)?;
 
             let market = self.market.load()?;
             let meta = market.meta();
@@ -257,6 +356,11 @@ impl<'a, 'info> CreateOrderOperation<'a, 'info> {
                 .callback_partitioned_data_account
                 .as_ref()
                 .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+            let config = self.callback_program_config.ok_or_else(|| {
+                msg!("[Callback] the callback program config is required");
+                error!(CoreError::InvalidArgument)
+            })?;
+            config.load()?.validate(program.key, ActionKind::Order)?;
             let position = position.unwrap_or(program);
 
             self.order.load_mut()?.header.set_general_callback(
@@ -336,6 +440,7 @@ impl CreateSwapOrderOperation<'_, '_> {
             msg!("cannot create no-op swap order");
             CoreError::InvalidSwapPathLength
         });
+        self.common.validate_trading_mode(false)?;
         require!(
             self.common
                 .market
@@ -411,6 +516,7 @@ impl CreateIncreaseOrderOperation<'_, '_> {
             self.common.params.initial_collateral_delta_amount,
             CoreError::NotEnoughTokenAmount
         );
+        self.common.validate_trading_mode(false)?;
 
         {
             let market = self.common.market.load()?;
@@ -472,6 +578,7 @@ impl CreateDecreaseOrderOperation<'_, '_> {
                     create.min_output,
                     create.decrease_position_swap_type.unwrap_or_default(),
                     create.valid_from_ts,
+                    self.common.options.trailing_distance,
                 )?;
                 Ok((collateral_token, self.final_output_token.mint))
             },
@@ -512,6 +619,10 @@ impl CreateDecreaseOrderOperation<'_, '_> {
                 .validate_for_market(&market, self.common.allow_closed)
                 .map_err(ModelError::from)?;
         }
+
+        let is_full_close =
+            self.common.params.size_delta_value >= self.position.load()?.state.size_in_usd;
+        self.common.validate_trading_mode(is_full_close)?;
         Ok(())
     }
 }
@@ -871,6 +982,9 @@ pub(crate) struct ExecuteOrderOperation<'a, 'info> {
     callback_partitioned_data_account: Option<&'a AccountInfo<'info>>,
     #[builder(default)]
     allow_closed: bool,
+    /// The remaining params of the TWAP order before executing the current slice.
+    #[builder(default)]
+    twap: Option<TwapRemaining>,
 }
 
 pub(crate) type RemovePosition = bool;
@@ -1044,7 +1158,8 @@ impl ExecuteOrderOperation<'_, '_> {
             | OrderKind::AutoDeleveraging
             | OrderKind::LimitIncrease
             | OrderKind::LimitDecrease
-            | OrderKind::StopLossDecrease => {
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => {
                 let position_loader = self.position.as_ref().ok_or_else(|| {
                     *should_throw_error = true;
                     error!(CoreError::PositionIsRequired)
@@ -1111,7 +1226,8 @@ impl ExecuteOrderOperation<'_, '_> {
                     )?,
                     OrderKind::MarketDecrease
                     | OrderKind::LimitDecrease
-                    | OrderKind::StopLossDecrease => execute_decrease_position(
+                    | OrderKind::StopLossDecrease
+                    | OrderKind::TrailingStopDecrease => execute_decrease_position(
                         self.oracle,
                         prices,
                         &mut position,
@@ -1249,7 +1365,12 @@ impl ExecuteOrderOperation<'_, '_> {
 
     fn validate_order(&self, should_throw_error: &mut bool, prices: &Prices<u128>) -> Result<()> {
         self.validate_non_empty_order()?;
-        match self.validate_trigger_price(prices) {
+        match self
+            .validate_trading_mode()
+            .and_then(|()| self.validate_price_circuit_breakers())
+            .and_then(|()| self.update_trailing_stop_best_price(prices))
+            .and_then(|()| self.validate_trigger_price(prices))
+        {
             Ok(()) => Ok(()),
             Err(err) => {
                 if !self.order.load()?.params.kind()?.is_market() {
@@ -1260,6 +1381,53 @@ impl ExecuteOrderOperation<'_, '_> {
         }
     }
 
+    /// Orders must be allowed by the trading mode of the market at execution,
+    /// since the mode may have changed after the orders were created. The same
+    /// applies to the other markets in the swap path.
+    ///
+    /// A TWAP order is a full close if the slices left to execute add up to the
+    /// whole position, so that each of its slices is allowed in close-only mode.
+    fn validate_trading_mode(&self) -> Result<()> {
+        let order = self.order.load()?;
+        let kind = order.params.kind()?;
+        let is_full_close = match self.position.as_ref() {
+            Some(position) if kind.is_decrease_position() => {
+                let size = match self.twap.as_ref() {
+                    Some(remaining) => remaining.size(),
+                    None => order.params.size_delta_value,
+                };
+                size >= position.load()?.state.size_in_usd
+            }
+            _ => false,
+        };
+        let market = self.market.load()?;
+        market.validate_trading_mode(kind, is_full_close)?;
+        // The current market is excluded from the unpacked swap markets.
+        let (swap_markets, _) = order
+            .swap()
+            .unpack_markets_for_swap(&market.meta().market_token_mint, self.remaining_accounts)?;
+        for swap_market in swap_markets {
+            swap_market
+                .load()?
+                .validate_swap_allowed_by_trading_mode()?;
+        }
+        Ok(())
+    }
+
+    /// Increase and swap orders are not allowed while the price circuit breaker
+    /// of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> Result<()> {
+        let order = self.order.load()?;
+        let kind = order.params.kind()?;
+        if !(kind.is_increase_position() || kind.is_swap()) {
+            return Ok(());
+        }
+        let market = self.market.load()?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*market, order.swap().tokens())?;
+        Ok(())
+    }
+
     fn validate_non_empty_order(&self) -> Result<()> {
         let order = self.order.load()?;
         let params = &order.params;
@@ -1289,6 +1457,16 @@ impl ExecuteOrderOperation<'_, '_> {
         Ok(())
     }
 
+    /// Ratchet the best price of a trailing-stop order with the execution prices,
+    /// so that the trigger price never lags behind the observed prices.
+    fn update_trailing_stop_best_price(&self, prices: &Prices<u128>) -> Result<()> {
+        let mut order = self.order.load_mut()?;
+        if order.params.kind()?.is_trailing_stop() {
+            order.params.update_best_price(&prices.index_token_price)?;
+        }
+        Ok(())
+    }
+
     fn validate_trigger_price(&self, prices: &Prices<u128>) -> Result<()> {
         self.order
             .load()?
@@ -1361,7 +1539,10 @@ impl ValidateOracleTime for ExecuteOrderOperation<'_, '_> {
         };
 
         match kind {
-            OrderKind::MarketSwap | OrderKind::MarketIncrease => Ok(Some(updated_at)),
+            // Note: `valid_from_ts` is only set for TWAP orders among market orders.
+            OrderKind::MarketSwap | OrderKind::MarketIncrease => {
+                Ok(Some(updated_at.max(valid_from_ts)))
+            }
             OrderKind::MarketDecrease => {
                 let position = self
                     .position
@@ -1369,12 +1550,15 @@ impl ValidateOracleTime for ExecuteOrderOperation<'_, '_> {
                     .ok_or(CoreError::PositionIsRequired)?
                     .load()
                     .map_err(|_| CoreError::LoadAccountError)?;
-                Ok(Some(updated_at.max(position.state.increased_at)))
+                let last_updated = updated_at.max(position.state.increased_at);
+                Ok(Some(last_updated.max(valid_from_ts)))
             }
             OrderKind::LimitSwap | OrderKind::LimitIncrease => {
                 Ok(Some(updated_at.max(valid_from_ts)))
             }
-            OrderKind::LimitDecrease | OrderKind::StopLossDecrease => {
+            OrderKind::LimitDecrease
+            | OrderKind::StopLossDecrease
+            | OrderKind::TrailingStopDecrease => {
                 let position = self
                     .position
                     .as_ref()
@@ -1402,7 +1586,7 @@ impl ValidateOracleTime for ExecuteOrderOperation<'_, '_> {
     }
 
     fn oracle_updated_before(&self) -> crate::CoreResult<Option<i64>> {
-        let (kind, updated_at) = {
+        let (kind, updated_at, valid_from_ts) = {
             let order = self.order.load().map_err(|_| CoreError::LoadAccountError)?;
             (
                 order
@@ -1410,11 +1594,13 @@ impl ValidateOracleTime for ExecuteOrderOperation<'_, '_> {
                     .kind()
                     .map_err(|_| CoreError::InvalidArgument)?,
                 order.header().updated_at,
+                order.params().valid_from_ts,
             )
         };
         let ts = match kind {
+            // The next slice of a TWAP order expires relative to the time it becomes valid.
             OrderKind::MarketSwap | OrderKind::MarketIncrease | OrderKind::MarketDecrease => {
-                Some(updated_at)
+                Some(updated_at.max(valid_from_ts))
             }
             _ => None,
         };
@@ -1446,6 +1632,213 @@ impl ValidateOracleTime for ExecuteOrderOperation<'_, '_> {
     }
 }
 
+/// Finish the current TWAP slice of the order being executed.
+///
+/// Returns whether the order should be kept pending for the remaining slices.
+#[inline(never)]
+pub(crate) fn finish_twap_slice(
+    order: &AccountLoader<Order>,
+    twap: Option<TwapRemaining>,
+    executed: bool,
+) -> Result<bool> {
+    let Some(remaining) = twap else {
+        return Ok(false);
+    };
+    let mut order = order.load_mut()?;
+    let has_remaining_slices = order.params.finish_twap_slice(remaining, executed)?;
+    if !has_remaining_slices {
+        return Ok(false);
+    }
+    order.header.updated()?;
+    msg!(
+        "[TWAP] {} slices remaining, next slice is valid from {}",
+        order.params.twap_remaining_slices(),
+        order.params.valid_from_ts(),
+    );
+    Ok(true)
+}
+
+/// Operation for closing the linked orders of an executed order.
+#[derive(TypedBuilder)]
+pub(crate) struct CloseLinkedOrdersOperation<'a, 'info> {
+    store: &'a AccountLoader<'info, Store>,
+    order: &'a AccountLoader<'info, Order>,
+    token_program: AccountInfo<'info>,
+    linked_orders: &'info [AccountInfo<'info>],
+    event_emitter: EventEmitter<'a, 'info>,
+}
+
+impl<'info> CloseLinkedOrdersOperation<'_, 'info> {
+    /// The number of accounts required to close a linked order, which are
+    /// `[order, final_output_token_escrow, long_token_escrow, short_token_escrow, rent_receiver]`.
+    pub(crate) const ACCOUNTS_PER_LINKED_ORDER: usize = 5;
+
+    /// Split the linked order accounts from the end of the remaining accounts.
+    ///
+    /// The number of the groups is derived from the orders linked with the `order`.
+    ///
+    /// Returns `(remaining_accounts, linked_orders)`.
+    pub(crate) fn split_remaining_accounts(
+        order: &AccountLoader<'info, Order>,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<(&'info [AccountInfo<'info>], &'info [AccountInfo<'info>])> {
+        let len = order
+            .load()?
+            .linked_orders()
+            .count()
+            .checked_mul(Self::ACCOUNTS_PER_LINKED_ORDER)
+            .ok_or_else(|| error!(CoreError::LinkedOrderMismatched))?;
+        let split_at = remaining_accounts
+            .len()
+            .checked_sub(len)
+            .ok_or_else(|| error!(CoreError::LinkedOrderMismatched))?;
+        Ok(remaining_accounts.split_at(split_at))
+    }
+
+    /// Cancel and close the pending linked orders, refunding their rent and
+    /// execution lamports to their rent receivers.
+    /// CHECK: the order must have been executed successfully.
+    pub(crate) fn execute(self) -> Result<()> {
+        let address = self.order.key();
+        let order = self.order.load()?;
+
+        let linked_orders = self
+            .linked_orders
+            .chunks_exact(Self::ACCOUNTS_PER_LINKED_ORDER);
+        require!(
+            linked_orders.remainder().is_empty(),
+            CoreError::LinkedOrderMismatched
+        );
+        require_eq!(
+            linked_orders.len(),
+            order.linked_orders().count(),
+            CoreError::LinkedOrderMismatched
+        );
+
+        for (idx, accounts) in linked_orders.enumerate() {
+            let info = &accounts[0];
+            require!(
+                order.is_linked_with(info.key),
+                CoreError::LinkedOrderMismatched
+            );
+            require!(
+                !self
+                    .linked_orders
+                    .chunks_exact(Self::ACCOUNTS_PER_LINKED_ORDER)
+                    .take(idx)
+                    .any(|other| other[0].key == info.key),
+                CoreError::LinkedOrderMismatched
+            );
+            self.close_linked_order(&address, accounts)?;
+        }
+
+        Ok(())
+    }
+
+    fn close_linked_order(
+        &self,
+        address: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let [info, final_output_token_escrow, long_token_escrow, short_token_escrow, rent_receiver] =
+            accounts
+        else {
+            return err!(CoreError::LinkedOrderMismatched);
+        };
+
+        // The linked order may have been closed, the other accounts are ignored in this case.
+        if *info.owner != crate::ID || info.data_is_empty() {
+            msg!("[Order] linked order {} has been closed", info.key);
+            return Ok(());
+        }
+
+        let loader = AccountLoader::<Order>::try_from(info)?;
+        let (signer, escrows) = {
+            let mut linked = loader.load_mut()?;
+            if linked.header.store != self.store.key() || !linked.is_linked_with(address) {
+                msg!("[Order] order {} is no longer linked", info.key);
+                return Ok(());
+            }
+            if !linked.header.action_state()?.is_pending() {
+                msg!("[Order] linked order {} is not pending", info.key);
+                return Ok(());
+            }
+            linked.header.cancelled()?;
+            msg!("[Order] linked order {} is cancelled", info.key);
+
+            require_keys_eq!(
+                *linked.header.rent_receiver(),
+                rent_receiver.key(),
+                CoreError::RentReceiverMismatched
+            );
+            let tokens = &linked.tokens;
+            for (expected, escrow) in [
+                (
+                    tokens.final_output_token.account(),
+                    final_output_token_escrow,
+                ),
+                (tokens.long_token.account(), long_token_escrow),
+                (tokens.short_token.account(), short_token_escrow),
+            ] {
+                require!(
+                    expected == Some(escrow.key()),
+                    CoreError::TokenAccountMismatched
+                );
+            }
+            (
+                linked.signer(),
+                [
+                    final_output_token_escrow,
+                    long_token_escrow,
+                    short_token_escrow,
+                ],
+            )
+        };
+
+        // Escrow accounts with remaining tokens can only be closed by the close instruction,
+        // so the cancelled order is kept in this case.
+        let mut unique_escrows = Vec::<&AccountInfo<'info>>::with_capacity(escrows.len());
+        for escrow in escrows {
+            if unique_escrows.iter().any(|seen| seen.key == escrow.key) {
+                continue;
+            }
+            if Account::<TokenAccount>::try_from(escrow)?.amount != 0 {
+                msg!(
+                    "[Order] escrow {} of linked order {} is not empty, skip the close",
+                    escrow.key,
+                    info.key
+                );
+                return Ok(());
+            }
+            unique_escrows.push(escrow);
+        }
+
+        for escrow in unique_escrows {
+            close_account(
+                CpiContext::new(
+                    self.token_program.clone(),
+                    CloseAccount {
+                        account: escrow.clone(),
+                        destination: rent_receiver.clone(),
+                        authority: info.clone(),
+                    },
+                )
+                .with_signer(&[&signer.as_seeds()]),
+            )?;
+        }
+
+        {
+            let linked = loader.load()?;
+            self.event_emitter
+                .emit_cpi(&linked.to_closed_event(info.key, "linked order executed")?)?;
+        }
+        loader.close(rent_receiver.clone())?;
+        msg!("[Order] linked order {} is closed", info.key);
+
+        Ok(())
+    }
+}
+
 #[inline(never)]
 fn execute_swap(
     should_throw_error: &mut bool,
@@ -1600,7 +1993,9 @@ fn execute_decrease_position(
 
         let is_cap_size_delta_usd_allowed = matches!(
             order.params().kind()?,
-            OrderKind::LimitDecrease | OrderKind::StopLossDecrease
+            OrderKind::LimitDecrease
+                | OrderKind::StopLossDecrease
+                | OrderKind::TrailingStopDecrease
         );
 
         // Only required when the order is an ADL order.
@@ -195,7 +195,24 @@ impl ExecuteShiftOperation<'_, '_> {
     }
 
     fn validate_oracle(&self) -> CoreResult<()> {
-        self.oracle.validate_time(self)
+        self.oracle.validate_time(self)?;
+        self.validate_price_circuit_breakers()
+    }
+
+    /// Shifts are not allowed while the price circuit breaker of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> CoreResult<()> {
+        let from_market = self
+            .from_market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        let to_market = self
+            .to_market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*from_market, [])?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*to_market, [])
     }
 
     fn validate_markets_and_shift(&self) -> Result<()> {
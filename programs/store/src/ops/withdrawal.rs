@@ -183,7 +183,22 @@ impl ExecuteWithdrawalOperation<'_, '_> {
     }
 
     fn validate_oracle(&self) -> CoreResult<()> {
-        self.oracle.validate_time(self)
+        self.oracle.validate_time(self)?;
+        self.validate_price_circuit_breakers()
+    }
+
+    /// Withdrawals are not allowed while the price circuit breaker of any involved token is tripped.
+    fn validate_price_circuit_breakers(&self) -> CoreResult<()> {
+        let market = self
+            .market
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        let withdrawal = self
+            .withdrawal
+            .load()
+            .map_err(|_| CoreError::LoadAccountError)?;
+        self.oracle
+            .validate_price_circuit_breakers_for_market(&*market, withdrawal.swap().tokens())
     }
 
     #[inline(never)]
@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
-use gmsol_callback::CALLBACK_AUTHORITY_SEED;
+use gmsol_callback::{interface::ActionKind, CALLBACK_AUTHORITY_SEED};
+use gmsol_utils::InitSpace;
+
+use crate::CoreError;
+
+/// The seed of callback program accounts.
+#[constant]
+pub const CALLBACK_PROGRAM_SEED: &[u8] = b"callback_program";
 
 /// Callback authority.
 #[account]
@@ -19,3 +26,72 @@ impl CallbackAuthority {
         self.bump_bytes[0]
     }
 }
+
+/// An entry of the callback program allowlist of a store.
+///
+/// Actions can only be created with a callback to a program that has
+/// a [`CallbackProgram`] account in the store, and only for the action
+/// kinds allowed by it.
+#[account(zero_copy)]
+#[cfg_attr(feature = "debug", derive(derive_more::Debug))]
+pub struct CallbackProgram {
+    version: u8,
+    pub(crate) bump: u8,
+    /// Bitmap of the allowed action kinds, where the bit at the index of
+    /// an [`ActionKind`] is set if the kind is allowed.
+    pub action_kinds: u8,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_0: [u8; 1],
+    /// Hint for the compute unit limit required by the callback.
+    pub compute_unit_limit: u32,
+    /// Store.
+    pub store: Pubkey,
+    /// The callback program ID.
+    pub program_id: Pubkey,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    reserved: [u8; 64],
+}
+
+impl InitSpace for CallbackProgram {
+    const INIT_SPACE: usize = std::mem::size_of::<Self>();
+}
+
+impl CallbackProgram {
+    pub(crate) fn init(
+        &mut self,
+        bump: u8,
+        store: Pubkey,
+        program_id: Pubkey,
+        action_kinds: u8,
+        compute_unit_limit: u32,
+    ) {
+        self.bump = bump;
+        self.store = store;
+        self.program_id = program_id;
+        self.update(action_kinds, compute_unit_limit);
+    }
+
+    pub(crate) fn update(&mut self, action_kinds: u8, compute_unit_limit: u32) {
+        self.action_kinds = action_kinds;
+        self.compute_unit_limit = compute_unit_limit;
+    }
+
+    /// Returns whether the given action kind is allowed.
+    pub fn is_allowed(&self, kind: ActionKind) -> bool {
+        let index = u8::from(kind);
+        index < u8::BITS as u8 && self.action_kinds & (1 << index) != 0
+    }
+
+    /// Validate that the given program is allowed to receive callbacks for the given action kind.
+    pub(crate) fn validate(&self, program_id: &Pubkey, kind: ActionKind) -> Result<()> {
+        require_keys_eq!(self.program_id, *program_id, {
+            msg!("[Callback] the callback program config does not match the program");
+            CoreError::InvalidArgument
+        });
+        if !self.is_allowed(kind) {
+            msg!("[Callback] the action kind is not allowed for the callback program");
+            return err!(CoreError::PermissionDenied);
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
+use bytemuck::Zeroable;
 
 use crate::{constants, states::Factor, CoreError};
 
 pub use gmsol_utils::market::{
-    MarketConfigFlag, MarketConfigKey, MAX_MARKET_CONFIG_FACTORS, MAX_MARKET_CONFIG_FLAGS,
+    ramp_config_factor, MarketConfigFlag, MarketConfigKey, MAX_MARKET_CONFIG_FACTORS,
+    MAX_MARKET_CONFIG_FLAGS, MAX_MARKET_CONFIG_SCHEDULES,
 };
 
 /// Market Config.
@@ -577,6 +579,145 @@ impl MarketConfig {
 
 gmsol_utils::flags!(MarketConfigFlag, MAX_MARKET_CONFIG_FLAGS, u128);
 
+/// A scheduled update of a market config factor.
+///
+/// The factor is ramped linearly from the value at the time of scheduling
+/// to the target value over `duration` seconds starting at `start_at`.
+#[zero_copy]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarketConfigSchedule {
+    key: u16,
+    is_active: u8,
+    padding_0: [u8; 1],
+    /// Duration of the ramp in seconds.
+    pub duration: u32,
+    /// Start time of the ramp.
+    pub start_at: i64,
+    /// Value before the ramp.
+    pub from: Factor,
+    /// Target value.
+    pub target: Factor,
+}
+
+impl MarketConfigSchedule {
+    /// Get key.
+    pub fn key(&self) -> Result<MarketConfigKey> {
+        self.key
+            .try_into()
+            .map_err(|_| error!(CoreError::InvalidMarketConfigKey))
+    }
+
+    /// Returns whether the schedule is active.
+    pub fn is_active(&self) -> bool {
+        self.is_active != 0
+    }
+
+    /// Returns the end time of the ramp.
+    pub fn end_at(&self) -> i64 {
+        self.start_at.saturating_add(i64::from(self.duration))
+    }
+
+    /// Returns the effective value at the given time.
+    pub fn value_at(&self, now: i64) -> Factor {
+        ramp_config_factor(self.from, self.target, self.start_at, self.duration, now)
+    }
+}
+
+/// Scheduled market config updates.
+#[zero_copy]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarketConfigSchedules {
+    schedules: [MarketConfigSchedule; MAX_MARKET_CONFIG_SCHEDULES],
+}
+
+impl MarketConfigSchedules {
+    /// Get the active schedule of the given key.
+    pub fn get(&self, key: MarketConfigKey) -> Option<&MarketConfigSchedule> {
+        let key = u16::from(key);
+        self.schedules
+            .iter()
+            .find(|schedule| schedule.is_active() && schedule.key == key)
+    }
+
+    /// Create an iterator of the active schedules.
+    pub fn iter(&self) -> impl Iterator<Item = &MarketConfigSchedule> {
+        self.schedules
+            .iter()
+            .filter(|schedule| schedule.is_active())
+    }
+
+    /// Insert or replace the schedule of the given key.
+    pub(super) fn insert(
+        &mut self,
+        key: MarketConfigKey,
+        from: Factor,
+        target: Factor,
+        start_at: i64,
+        duration: u32,
+    ) -> Result<()> {
+        let raw_key = u16::from(key);
+        let schedule = match self
+            .schedules
+            .iter()
+            .position(|schedule| schedule.is_active() && schedule.key == raw_key)
+        {
+            Some(idx) => &mut self.schedules[idx],
+            None => self
+                .schedules
+                .iter_mut()
+                .find(|schedule| !schedule.is_active())
+                .ok_or_else(|| error!(CoreError::ExceedMaxLengthLimit))?,
+        };
+        *schedule = MarketConfigSchedule {
+            key: raw_key,
+            is_active: 1,
+            padding_0: [0; 1],
+            duration,
+            start_at,
+            from,
+            target,
+        };
+        Ok(())
+    }
+
+    /// Remove the schedule of the given key.
+    ///
+    /// Returns whether the schedule was found.
+    pub(super) fn remove(&mut self, key: MarketConfigKey) -> bool {
+        let key = u16::from(key);
+        let mut found = false;
+        for schedule in self.schedules.iter_mut() {
+            if schedule.is_active() && schedule.key == key {
+                *schedule = MarketConfigSchedule::zeroed();
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Apply the schedules at the given time, returning the effective values.
+    ///
+    /// Schedules that have ended are removed.
+    pub(super) fn apply(
+        &mut self,
+        now: i64,
+    ) -> impl Iterator<Item = Result<(MarketConfigKey, Factor)>> + '_ {
+        self.schedules
+            .iter_mut()
+            .filter(|schedule| schedule.is_active())
+            .map(move |schedule| {
+                let key = schedule.key()?;
+                let value = schedule.value_at(now);
+                if now >= schedule.end_at() {
+                    *schedule = MarketConfigSchedule::zeroed();
+                }
+                Ok((key, value))
+            })
+    }
+}
+
 /// An entry of the config buffer.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 #[cfg_attr(feature = "debug", derive(Debug))]
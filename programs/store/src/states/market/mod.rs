@@ -36,7 +36,9 @@ use gmsol_model::{
 };
 use gmsol_utils::{
     market::{MarketError, MarketFlag, MAX_MARKET_FLAGS},
+    order::OrderKind,
     pubkey::{optional_address, DEFAULT_PUBKEY},
+    risk_oracle::MAX_RISK_ORACLE_PARAMETERS,
     token_config::TokenMapAccess,
 };
 use pool::cancel_amounts;
@@ -52,11 +54,11 @@ use crate::{
 use super::{Factor, InitSpace, Oracle, Seed};
 
 use self::{
-    config::{MarketConfig, MarketConfigBuffer, MarketConfigKey},
+    config::{MarketConfig, MarketConfigBuffer, MarketConfigKey, MarketConfigSchedules},
     pool::{Pool, Pools},
 };
 
-pub use gmsol_utils::market::{HasMarketMeta, MarketMeta};
+pub use gmsol_utils::market::{HasMarketMeta, MarketMeta, MarketTradingMode};
 pub use model::AsLiquidityMarket;
 
 /// Market Utils.
@@ -117,9 +119,10 @@ struct State {
     pools: Pools,
     clocks: Clocks,
     other: OtherState,
+    config_schedules: MarketConfigSchedules,
     #[cfg_attr(feature = "debug", debug(skip))]
     #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
-    reserved: [u8; 1024],
+    reserved: [u8; 640],
 }
 
 impl Bump for Market {
@@ -303,6 +306,72 @@ impl Market {
         Ok(self.set_flag(MarketFlag::Closed, closed))
     }
 
+    /// Returns the trading mode of the market.
+    pub fn trading_mode(&self) -> MarketTradingMode {
+        if self.flag(MarketFlag::CloseOnly) {
+            MarketTradingMode::CloseOnly
+        } else if self.flag(MarketFlag::ReduceOnly) {
+            MarketTradingMode::ReduceOnly
+        } else {
+            MarketTradingMode::Normal
+        }
+    }
+
+    /// Set the trading mode of the market.
+    ///
+    /// Return the previous trading mode.
+    pub fn set_trading_mode(&mut self, mode: MarketTradingMode) -> Result<MarketTradingMode> {
+        let previous = self.trading_mode();
+        let (reduce_only, close_only) = match mode {
+            MarketTradingMode::Normal => (false, false),
+            MarketTradingMode::ReduceOnly => (true, false),
+            MarketTradingMode::CloseOnly => (false, true),
+            _ => return err!(CoreError::InvalidArgument),
+        };
+        self.set_flag(MarketFlag::ReduceOnly, reduce_only);
+        self.set_flag(MarketFlag::CloseOnly, close_only);
+        Ok(previous)
+    }
+
+    /// Validate that an order of the given kind is allowed by the trading mode.
+    ///
+    /// `is_full_close` indicates whether the order decreases the whole position.
+    /// Liquidation and auto-deleveraging orders are always allowed, while swap orders
+    /// are only allowed in normal mode.
+    pub(crate) fn validate_trading_mode(&self, kind: OrderKind, is_full_close: bool) -> Result<()> {
+        if matches!(kind, OrderKind::Liquidation | OrderKind::AutoDeleveraging) {
+            return Ok(());
+        }
+        let mode = self.trading_mode();
+        let is_allowed = match mode {
+            MarketTradingMode::Normal => true,
+            MarketTradingMode::ReduceOnly => kind.is_decrease_position(),
+            MarketTradingMode::CloseOnly => kind.is_decrease_position() && is_full_close,
+            _ => false,
+        };
+        if !is_allowed {
+            msg!("[Order] `{}` order is not allowed in `{}` mode", kind, mode);
+            return err!(CoreError::TradingModeRestricted);
+        }
+        Ok(())
+    }
+
+    /// Validate that the market can be used in a swap path by the trading mode.
+    ///
+    /// Swaps are only allowed in normal mode.
+    pub(crate) fn validate_swap_allowed_by_trading_mode(&self) -> Result<()> {
+        let mode = self.trading_mode();
+        if !matches!(mode, MarketTradingMode::Normal) {
+            msg!(
+                "[Swap] market `{}` cannot be used for swaps in `{}` mode",
+                self.meta.market_token_mint,
+                mode
+            );
+            return err!(CoreError::TradingModeRestricted);
+        }
+        Ok(())
+    }
+
     /// Get pool of the given kind.
     #[inline]
     pub fn pool(&self, kind: PoolKind) -> Option<Pool> {
@@ -416,9 +485,12 @@ impl Market {
     }
 
     /// Update config with buffer.
+    ///
+    /// Scheduled updates of the keys in the buffer are cancelled.
     pub fn update_config_with_buffer(&mut self, buffer: &MarketConfigBuffer) -> Result<()> {
         for entry in buffer.iter() {
             let key = entry.key()?;
+            self.state.config_schedules.remove(key);
             let current_value = self
                 .config
                 .get_mut(key)
@@ -429,6 +501,92 @@ impl Market {
         Ok(())
     }
 
+    /// Apply the updates of a risk oracle recommendation.
+    ///
+    /// Scheduled updates of the keys are cancelled.
+    pub(crate) fn apply_risk_oracle_updates(
+        &mut self,
+        index: usize,
+        timestamp: u64,
+        updates: &[(MarketConfigKey, Factor)],
+    ) -> Result<()> {
+        let last = self
+            .state
+            .other
+            .risk_oracle_timestamps
+            .get_mut(index)
+            .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+        require_gt!(timestamp, *last, CoreError::StaleRiskOracleRecommendation);
+        *last = timestamp;
+        for (key, value) in updates {
+            self.state.config_schedules.remove(*key);
+            *self.get_config_by_key_mut(*key)? = *value;
+        }
+        Ok(())
+    }
+
+    /// Get scheduled config updates.
+    pub fn config_schedules(&self) -> &MarketConfigSchedules {
+        &self.state.config_schedules
+    }
+
+    /// Schedule an update of the config factor of the given key.
+    ///
+    /// The factor is ramped linearly from its current value to `target` over
+    /// `duration` seconds starting at `start_at`, which is clamped to `now`.
+    /// Replaces the existing schedule of the key if any.
+    pub(crate) fn schedule_config_update(
+        &mut self,
+        key: MarketConfigKey,
+        target: Factor,
+        start_at: i64,
+        duration: u32,
+        now: i64,
+    ) -> Result<()> {
+        self.apply_config_schedules(now)?;
+        let from = *self
+            .config
+            .get(key)
+            .ok_or_else(|| error!(CoreError::Unimplemented))?;
+        self.state
+            .config_schedules
+            .insert(key, from, target, start_at.max(now), duration)
+    }
+
+    /// Cancel the scheduled update of the config factor of the given key,
+    /// keeping its effective value at `now`.
+    ///
+    /// Returns whether a schedule was found.
+    pub(crate) fn cancel_config_schedule(
+        &mut self,
+        key: MarketConfigKey,
+        now: i64,
+    ) -> Result<bool> {
+        self.apply_config_schedules(now)?;
+        Ok(self.state.config_schedules.remove(key))
+    }
+
+    /// Remove the scheduled update of the config factor of the given key
+    /// without applying it.
+    ///
+    /// Returns whether a schedule was found.
+    pub(crate) fn remove_config_schedule(&mut self, key: MarketConfigKey) -> bool {
+        self.state.config_schedules.remove(key)
+    }
+
+    /// Write the effective values of the scheduled config updates at `now`
+    /// into the config, removing the schedules that have ended.
+    pub(crate) fn apply_config_schedules(&mut self, now: i64) -> Result<()> {
+        let Self { config, state, .. } = self;
+        for res in state.config_schedules.apply(now) {
+            let (key, value) = res?;
+            *config
+                .get_mut(key)
+                .ok_or_else(|| error!(CoreError::Unimplemented))? = value;
+        }
+        Ok(())
+    }
+
     /// Get prices from oracle.
     pub fn prices(&self, oracle: &Oracle) -> Result<Prices<u128>> {
         oracle.market_prices(self)
@@ -677,9 +835,10 @@ pub struct OtherState {
     long_token_balance: u64,
     short_token_balance: u64,
     funding_factor_per_second: i128,
+    risk_oracle_timestamps: [u64; MAX_RISK_ORACLE_PARAMETERS],
     #[cfg_attr(feature = "debug", debug(skip))]
     #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
-    reserved: [u8; 256],
+    reserved: [u8; 192],
 }
 
 impl OtherState {
@@ -703,6 +862,12 @@ impl OtherState {
         self.trade_count
     }
 
+    /// Get the timestamp of the last applied risk oracle recommendation
+    /// of the parameter with the given index.
+    pub fn risk_oracle_timestamp(&self, index: usize) -> Option<u64> {
+        self.risk_oracle_timestamps.get(index).copied()
+    }
+
     /// Next trade id.
     pub fn next_trade_id(&mut self) -> Result<u64> {
         let next_id = self
@@ -953,7 +1118,8 @@ mod tests {
             long_token_balance: u64::MAX,
             short_token_balance: u64::MAX,
             funding_factor_per_second: i128::MAX,
-            reserved: [0; 256],
+            risk_oracle_timestamps: [u64::MAX; MAX_RISK_ORACLE_PARAMETERS],
+            reserved: [0; 192],
         };
 
         let event_clocks = EventOtherState {
@@ -963,6 +1129,7 @@ mod tests {
             long_token_balance: clocks.long_token_balance,
             short_token_balance: clocks.short_token_balance,
             funding_factor_per_second: clocks.funding_factor_per_second,
+            risk_oracle_timestamps: clocks.risk_oracle_timestamps,
             reserved: clocks.reserved,
         };
 
@@ -79,12 +79,14 @@ impl<'a, 'info> RevertibleMarket<'a, 'info> {
     ///
     /// # Notes
     /// - Virtual inventory feature is disable when `virtual_inventories` is `None`.
+    /// - Scheduled config updates are applied at the current time.
     pub(crate) fn new(
         market: &'a AccountLoader<'info, Market>,
         virtual_inventories: Option<&'a RevertibleVirtualInventories<'info>>,
         event_emitter: EventEmitter<'a, 'info>,
     ) -> Result<Self> {
         let mut market = market.load_mut()?;
+        market.apply_config_schedules(Clock::get()?.unix_timestamp)?;
         market.buffer.start_revertible_operation();
 
         let get_enabled_virtual_inventory = |key| {
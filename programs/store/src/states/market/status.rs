@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use gmsol_model::{price::Prices, BaseMarketExt, BorrowingFeeMarketExt, PerpMarket};
 
-use super::Market;
+use super::{Market, MarketTradingMode};
 
 /// Market Status.
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -26,6 +26,8 @@ pub struct MarketStatus {
     pub pool_value_without_pnl_for_long: u128,
     /// Pool value without pnl for short.
     pub pool_value_without_pnl_for_short: u128,
+    /// Trading mode.
+    pub trading_mode: MarketTradingMode,
 }
 
 impl MarketStatus {
@@ -56,6 +58,7 @@ impl MarketStatus {
                 false,
                 maximize_pool_value,
             )?,
+            trading_mode: market.trading_mode(),
         })
     }
 }
@@ -49,19 +49,23 @@ pub mod callback;
 /// Permission stores and related definitions.
 pub mod permissions;
 
+/// Risk oracle.
+pub mod risk_oracle;
+
 pub use deposit::Deposit;
 pub use glv::{Glv, GlvDeposit, GlvShift, GlvWithdrawal};
 pub use market::{
     config::MarketConfigKey, pool::PoolStorage, HasMarketMeta, Market, MarketMeta, OtherState,
 };
 pub use oracle::*;
-pub use order::{Order, OrderActionParams, UpdateOrderParams};
+pub use order::{Order, OrderActionParams, UpdateOrderOptions, UpdateOrderParams};
 pub use position::Position;
+pub use risk_oracle::RiskOracle;
 pub use roles::*;
 pub use shift::*;
 pub use store::*;
 pub use token_config::*;
-pub use user::UserHeader;
+pub use user::{DelegationScope, UserDelegation, UserHeader};
 pub use withdrawal::Withdrawal;
 
 pub type Amount = u64;
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use gmsol_model::utils::apply_factor;
+use gmsol_utils::{
+    token_config::{PriceCircuitBreakerState, TokenConfig},
+    Price,
+};
+
+use crate::{constants, CoreError};
+
+/// A tripped price circuit breaker.
+pub(crate) struct Tripped {
+    pub(crate) ref_price: u128,
+    pub(crate) price: u128,
+    pub(crate) window_start: i64,
+}
+
+/// Price circuit breaker of a token.
+///
+/// The breaker state is stored in the token config, so that it is shared
+/// by all the oracle accounts of the store. The prices are only tracked by
+/// the `update_price_circuit_breakers` instruction, so that executing actions
+/// does not have to write-lock the token map. No price is rejected until a
+/// window has been started by it.
+pub(crate) trait PriceCircuitBreakerExt {
+    /// Returns whether the given price is rejected by the price circuit breaker,
+    /// i.e., the breaker has been tripped, or the price has moved away from the
+    /// reference price of the current window by more than the configured factor.
+    fn is_price_rejected(&self, price: &Price, now: i64) -> Result<bool>;
+
+    /// Track the given price with the price circuit breaker.
+    ///
+    /// A new window is started with the price if the current one has elapsed.
+    /// Returns the trip info if the breaker is tripped by this price.
+    fn track_price(&mut self, price: &Price, now: i64) -> Result<Option<Tripped>>;
+}
+
+impl PriceCircuitBreakerExt for TokenConfig {
+    fn is_price_rejected(&self, price: &Price, now: i64) -> Result<bool> {
+        let Some((max_move_factor, window)) = self.price_circuit_breaker() else {
+            return Ok(false);
+        };
+        let state = self.price_circuit_breaker_state();
+        if state.is_tripped() {
+            return Ok(true);
+        }
+        if !is_in_window(state, window, now) {
+            return Ok(false);
+        }
+        is_moved(state, max_move_factor, mid_price(price)?)
+    }
+
+    fn track_price(&mut self, price: &Price, now: i64) -> Result<Option<Tripped>> {
+        let Some((max_move_factor, window)) = self.price_circuit_breaker() else {
+            return Ok(None);
+        };
+        let mid = mid_price(price)?;
+        let state = self.price_circuit_breaker_state_mut();
+
+        if state.is_tripped() {
+            return Ok(None);
+        }
+
+        if !is_in_window(state, window, now) {
+            let ref_price = price
+                .max
+                .with_unit_price(mid, false)
+                .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+            state.start_window(ref_price, now);
+            return Ok(None);
+        }
+
+        if is_moved(state, max_move_factor, mid)? {
+            state.trip();
+            return Ok(Some(Tripped {
+                ref_price: state.ref_price().to_unit_price(),
+                price: mid,
+                window_start: state.window_start,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+fn mid_price(price: &Price) -> Result<u128> {
+    gmsol_model::price::Price::<u128>::from(price)
+        .checked_mid()
+        .ok_or_else(|| error!(CoreError::InvalidArgument))
+}
+
+fn is_in_window(state: &PriceCircuitBreakerState, window: u32, now: i64) -> bool {
+    state.is_started() && now.saturating_sub(state.window_start) <= i64::from(window)
+}
+
+fn is_moved(state: &PriceCircuitBreakerState, max_move_factor: u128, mid: u128) -> Result<bool> {
+    let ref_price = state.ref_price().to_unit_price();
+    let max_move = apply_factor::<_, { constants::MARKET_DECIMALS }>(&ref_price, &max_move_factor)
+        .ok_or_else(|| error!(CoreError::ValueOverflow))?;
+    Ok(mid.abs_diff(ref_price) > max_move)
+}
+
+#[cfg(test)]
+mod tests {
+    use gmsol_utils::price::Decimal;
+
+    use super::*;
+
+    fn price(value: u32) -> Price {
+        let decimal = Decimal {
+            value,
+            decimal_multiplier: 10,
+        };
+        Price {
+            min: decimal,
+            max: decimal,
+        }
+    }
+
+    fn factor(percent: u128) -> u128 {
+        percent * 10u128.pow(constants::MARKET_DECIMALS as u32 - 2)
+    }
+
+    fn config() -> TokenConfig {
+        let mut config: TokenConfig = bytemuck::Zeroable::zeroed();
+        config
+            .set_price_circuit_breaker(Some(factor(10)), 60)
+            .unwrap();
+        config
+    }
+
+    #[test]
+    fn trip_and_reset() {
+        let mut config = config();
+
+        assert!(config.track_price(&price(1000), 100).unwrap().is_none());
+        assert!(config.track_price(&price(1100), 130).unwrap().is_none());
+        assert!(!config.price_circuit_breaker_state().is_tripped());
+
+        let tripped = config
+            .track_price(&price(1101), 150)
+            .unwrap()
+            .expect("should trip");
+        assert_eq!(tripped.window_start, 100);
+        assert!(config.price_circuit_breaker_state().is_tripped());
+
+        // Stays tripped even after the window elapsed.
+        assert!(config.track_price(&price(1000), 1000).unwrap().is_none());
+        assert!(config.price_circuit_breaker_state().is_tripped());
+        assert!(config.is_price_rejected(&price(1000), 1000).unwrap());
+
+        assert!(config.price_circuit_breaker_state_mut().reset());
+        assert!(!config.price_circuit_breaker_state().is_tripped());
+        assert!(!config.price_circuit_breaker_state().is_started());
+        assert!(!config.is_price_rejected(&price(1000), 1000).unwrap());
+    }
+
+    #[test]
+    fn not_rejected_before_tracked() {
+        let config = config();
+
+        // Prices are only rejected once a window has been started by tracking.
+        assert!(!config.price_circuit_breaker_state().is_started());
+        assert!(!config.is_price_rejected(&price(1000), 100).unwrap());
+        assert!(!config.is_price_rejected(&price(u32::MAX), 100).unwrap());
+    }
+
+    #[test]
+    fn window_rolls_over() {
+        let mut config = config();
+
+        config.track_price(&price(1000), 100).unwrap();
+        assert!(config.track_price(&price(2000), 161).unwrap().is_none());
+        assert_eq!(config.price_circuit_breaker_state().window_start, 161);
+        assert!(!config.price_circuit_breaker_state().is_tripped());
+    }
+
+    #[test]
+    fn reject_moved_price_before_tripped() {
+        let mut config = config();
+
+        assert!(!config.is_price_rejected(&price(2000), 100).unwrap());
+        config.track_price(&price(1000), 100).unwrap();
+        assert!(!config.is_price_rejected(&price(1100), 130).unwrap());
+        assert!(config.is_price_rejected(&price(1101), 130).unwrap());
+        assert!(!config.price_circuit_breaker_state().is_tripped());
+        // The window has elapsed.
+        assert!(!config.is_price_rejected(&price(2000), 161).unwrap());
+    }
+}
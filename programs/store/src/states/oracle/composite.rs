@@ -0,0 +1,389 @@
+use std::borrow::Cow;
+
+use anchor_lang::prelude::*;
+use gmsol_utils::{
+    oracle::{median_price, CompositePriceMode},
+    Price,
+};
+use pyth_solana_receiver_sdk::error::GetPriceError;
+
+use crate::{
+    states::{TokenConfig, TokenMapAccess},
+    CoreError,
+};
+
+use super::{OraclePrice, PriceValidator};
+
+/// Expand the tokens so that there is an entry for each price source.
+///
+/// A token priced from a composite of providers requires a feed account for
+/// each of its price sources. It can either be listed once for each source
+/// (as in the tokens of a `TokensWithFeed`), or listed only once, in which
+/// case the feeds of all its price sources must be placed consecutively at
+/// its position.
+pub(super) fn expand_price_sources<'a>(
+    map: &impl TokenMapAccess,
+    tokens: &'a [Pubkey],
+) -> Result<Cow<'a, [Pubkey]>> {
+    if !tokens
+        .iter()
+        .any(|token| map.get(token).is_some_and(TokenConfig::is_composite))
+    {
+        return Ok(Cow::Borrowed(tokens));
+    }
+
+    let mut expanded = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let num_sources = match map.get(token) {
+            Some(config)
+                if config.is_composite()
+                    && tokens.iter().filter(|other| *other == token).count() == 1 =>
+            {
+                config.price_sources().map_err(CoreError::from)?.len()
+            }
+            _ => 1,
+        };
+        for _ in 0..num_sources {
+            expanded.push(*token);
+        }
+    }
+    Ok(Cow::Owned(expanded))
+}
+
+/// Returns whether the error indicates that a price source is only temporarily
+/// unavailable, i.e. its price is stale or its market is closed.
+///
+/// Other errors, such as account or provider mismatches, must not be covered
+/// by the fallback.
+fn is_unavailable(err: &Error) -> bool {
+    [
+        CoreError::MaxPriceAgeExceeded,
+        CoreError::PriceIsStale,
+        CoreError::PriceFeedNotUpdated,
+        CoreError::MarketNotOpen,
+    ]
+    .into_iter()
+    .map(|code| error!(code))
+    .chain(Some(error!(GetPriceError::PriceTooOld)))
+    .any(|unavailable| *err == unavailable)
+}
+
+/// The parsed prices of composite price sources.
+#[derive(Default)]
+pub(super) struct CompositePrices {
+    entries: Vec<(Pubkey, Result<OraclePrice>)>,
+}
+
+impl CompositePrices {
+    /// Push the parsed price of a price source of the given token.
+    pub(super) fn push(&mut self, token: &Pubkey, price: Result<OraclePrice>) {
+        self.entries.push((*token, price));
+    }
+
+    /// Validate the price sources and aggregate their prices for each token.
+    pub(super) fn aggregate(
+        mut self,
+        validator: &mut PriceValidator,
+        map: &impl TokenMapAccess,
+        mut f: impl FnMut(&Pubkey, &TokenConfig, Price, bool) -> Result<()>,
+    ) -> Result<()> {
+        while let Some((token, _)) = self.entries.first() {
+            let token = *token;
+            let (entries, rest) = std::mem::take(&mut self.entries)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(other, _)| *other == token);
+            self.entries = rest;
+
+            let config = map.get(&token).ok_or_else(|| error!(CoreError::NotFound))?;
+            let mode = config.composite_mode().map_err(CoreError::from)?;
+            let sources = config.price_sources().map_err(CoreError::from)?;
+            require_eq!(entries.len(), sources.len(), CoreError::NotEnoughTokenFeeds);
+
+            let mut prices = std::iter::repeat_with(|| None)
+                .take(sources.len())
+                .collect::<Vec<Option<OraclePrice>>>();
+            for (_, price) in entries {
+                let price = price.and_then(|price| {
+                    validator.validate_one(
+                        config,
+                        &price.provider,
+                        price.parts.oracle_ts,
+                        price.parts.oracle_slot,
+                        &price.parts.price,
+                        price.parts.ref_price.as_ref(),
+                    )?;
+                    Ok(price)
+                });
+                match price {
+                    Ok(price) => {
+                        let index = sources
+                            .iter()
+                            .position(|source| *source == price.provider)
+                            .ok_or_else(|| error!(CoreError::InvalidPriceFeedAccount))?;
+                        require!(prices[index].is_none(), CoreError::InvalidPriceFeedAccount);
+                        prices[index] = Some(price);
+                    }
+                    Err(err)
+                        if mode == CompositePriceMode::PrimaryWithFallback
+                            && is_unavailable(&err) =>
+                    {
+                        msg!(
+                            "[Oracle] a price source of token {} is not available: {}",
+                            token,
+                            err
+                        );
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+
+            let (price, is_open) = match mode {
+                CompositePriceMode::Median => {
+                    let valid = prices.iter().flatten().collect::<Vec<_>>();
+                    require_eq!(
+                        valid.len(),
+                        sources.len(),
+                        CoreError::InvalidPriceFeedAccount
+                    );
+                    let price = median_price(
+                        &valid
+                            .iter()
+                            .map(|price| price.parts.price)
+                            .collect::<Vec<_>>(),
+                    )
+                    .ok_or_else(|| error!(CoreError::Internal))?;
+                    (price, valid.iter().all(|price| price.parts.is_open))
+                }
+                CompositePriceMode::PrimaryWithFallback => {
+                    let chosen = prices.iter().flatten().next().ok_or_else(|| {
+                        msg!("[Oracle] no price source of token {} is available", token);
+                        error!(CoreError::InvalidPriceFeedPrice)
+                    })?;
+                    if chosen.provider != sources[0] {
+                        msg!(
+                            "[Oracle] token {} is priced by the fallback source: {}",
+                            token,
+                            chosen.provider
+                        );
+                    }
+                    (chosen.parts.price, chosen.parts.is_open)
+                }
+                _ => return err!(CoreError::Internal),
+            };
+
+            validator.validate_disagreement(
+                config,
+                &price,
+                prices.iter().flatten().map(|price| &price.parts.price),
+            )?;
+
+            (f)(&token, config, price, is_open)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use gmsol_utils::{
+        oracle::PriceProviderKind,
+        price::Decimal,
+        token_config::{FeedConfig, TokenMapAccess},
+    };
+
+    use super::*;
+    use crate::states::oracle::OraclePriceParts;
+
+    const NOW: i64 = 1_000;
+
+    struct TokenMap(BTreeMap<Pubkey, TokenConfig>);
+
+    impl TokenMapAccess for TokenMap {
+        fn get(&self, token: &Pubkey) -> Option<&TokenConfig> {
+            self.0.get(token)
+        }
+    }
+
+    const SOURCES: [PriceProviderKind; 3] = [
+        PriceProviderKind::Pyth,
+        PriceProviderKind::ChainlinkDataStreams,
+        PriceProviderKind::Switchboard,
+    ];
+
+    fn token_map(
+        token: &Pubkey,
+        mode: CompositePriceMode,
+        max_disagreement_factor: Option<u128>,
+    ) -> TokenMap {
+        let mut config: TokenConfig = bytemuck::Zeroable::zeroed();
+        for kind in SOURCES {
+            config
+                .set_feed_config(&kind, FeedConfig::new(Pubkey::new_unique()))
+                .unwrap();
+        }
+        config.set_expected_provider(PriceProviderKind::Pyth);
+        config
+            .set_composite_price(mode, &SOURCES, max_disagreement_factor)
+            .unwrap();
+        TokenMap([(*token, config)].into_iter().collect())
+    }
+
+    fn price(provider: PriceProviderKind, value: u32) -> Result<OraclePrice> {
+        let decimal = Decimal {
+            value,
+            decimal_multiplier: 10,
+        };
+        Ok(OraclePrice {
+            provider,
+            parts: OraclePriceParts {
+                oracle_slot: 1,
+                oracle_ts: NOW,
+                price: Price {
+                    min: decimal,
+                    max: decimal,
+                },
+                ref_price: None,
+                is_open: true,
+            },
+        })
+    }
+
+    fn aggregate(map: &TokenMap, token: &Pubkey, prices: Vec<Result<OraclePrice>>) -> Result<u32> {
+        let mut composite = CompositePrices::default();
+        for price in prices {
+            composite.push(token, price);
+        }
+        let clock = Clock {
+            unix_timestamp: NOW,
+            ..Default::default()
+        };
+        let mut validator = PriceValidator::with_clock(clock, 60);
+        let mut aggregated = None;
+        composite.aggregate(&mut validator, map, |_, _, price, _| {
+            aggregated = Some(price.min.value);
+            Ok(())
+        })?;
+        Ok(aggregated.expect("must be aggregated"))
+    }
+
+    fn percent(value: u128) -> u128 {
+        value * 10u128.pow(crate::constants::MARKET_DECIMALS as u32 - 2)
+    }
+
+    #[test]
+    fn median_of_price_sources() {
+        let token = Pubkey::new_unique();
+        let map = token_map(&token, CompositePriceMode::Median, None);
+        let prices = vec![
+            price(PriceProviderKind::Pyth, 1_000),
+            price(PriceProviderKind::ChainlinkDataStreams, 1_030),
+            price(PriceProviderKind::Switchboard, 1_010),
+        ];
+        assert_eq!(aggregate(&map, &token, prices).unwrap(), 1_010);
+
+        // All price sources are required.
+        let prices = vec![
+            price(PriceProviderKind::Pyth, 1_000),
+            Err(error!(CoreError::PriceIsStale)),
+            price(PriceProviderKind::Switchboard, 1_010),
+        ];
+        assert_eq!(
+            aggregate(&map, &token, prices).unwrap_err(),
+            error!(CoreError::PriceIsStale)
+        );
+    }
+
+    #[test]
+    fn fallback_on_unavailable_prices() {
+        let token = Pubkey::new_unique();
+        let map = token_map(&token, CompositePriceMode::PrimaryWithFallback, None);
+        let prices = vec![
+            price(PriceProviderKind::Pyth, 1_000),
+            price(PriceProviderKind::ChainlinkDataStreams, 1_030),
+            price(PriceProviderKind::Switchboard, 1_010),
+        ];
+        assert_eq!(aggregate(&map, &token, prices).unwrap(), 1_000);
+
+        for err in [
+            error!(CoreError::PriceFeedNotUpdated),
+            error!(CoreError::MarketNotOpen),
+            error!(GetPriceError::PriceTooOld),
+        ] {
+            let prices = vec![
+                Err(err),
+                price(PriceProviderKind::ChainlinkDataStreams, 1_030),
+                price(PriceProviderKind::Switchboard, 1_010),
+            ];
+            assert_eq!(aggregate(&map, &token, prices).unwrap(), 1_030);
+        }
+
+        // The validation of the price is also covered by the fallback.
+        let mut stale = price(PriceProviderKind::Pyth, 1_000).unwrap();
+        stale.parts.oracle_ts = NOW - 120;
+        let prices = vec![
+            Ok(stale),
+            Err(error!(CoreError::MarketNotOpen)),
+            price(PriceProviderKind::Switchboard, 1_010),
+        ];
+        assert_eq!(aggregate(&map, &token, prices).unwrap(), 1_010);
+    }
+
+    #[test]
+    fn fallback_does_not_cover_invalid_accounts() {
+        let token = Pubkey::new_unique();
+        let map = token_map(&token, CompositePriceMode::PrimaryWithFallback, None);
+        let errors: [fn() -> Error; 3] = [
+            || error!(CoreError::InvalidPriceFeedAccount),
+            || error!(CoreError::InvalidPriceFeedPrice),
+            || error!(ErrorCode::AccountDiscriminatorMismatch),
+        ];
+        for err in errors {
+            let prices = vec![
+                Err(err()),
+                price(PriceProviderKind::ChainlinkDataStreams, 1_030),
+                price(PriceProviderKind::Switchboard, 1_010),
+            ];
+            assert_eq!(aggregate(&map, &token, prices).unwrap_err(), err());
+        }
+
+        // A price from an unexpected provider must be rejected.
+        let prices = vec![
+            price(PriceProviderKind::Pyth, 1_000),
+            price(PriceProviderKind::Pyth, 1_000),
+            price(PriceProviderKind::Switchboard, 1_010),
+        ];
+        assert_eq!(
+            aggregate(&map, &token, prices).unwrap_err(),
+            error!(CoreError::InvalidPriceFeedAccount)
+        );
+    }
+
+    #[test]
+    fn price_sources_must_agree() {
+        let token = Pubkey::new_unique();
+        for mode in [
+            CompositePriceMode::Median,
+            CompositePriceMode::PrimaryWithFallback,
+        ] {
+            let map = token_map(&token, mode, Some(percent(5)));
+            let prices = vec![
+                price(PriceProviderKind::Pyth, 1_000),
+                price(PriceProviderKind::ChainlinkDataStreams, 1_030),
+                price(PriceProviderKind::Switchboard, 1_010),
+            ];
+            assert!(aggregate(&map, &token, prices).is_ok());
+
+            let prices = vec![
+                price(PriceProviderKind::Pyth, 1_000),
+                price(PriceProviderKind::ChainlinkDataStreams, 1_100),
+                price(PriceProviderKind::Switchboard, 1_010),
+            ];
+            assert_eq!(
+                aggregate(&map, &token, prices).unwrap_err(),
+                error!(CoreError::InvalidPriceFeedPrice)
+            );
+        }
+    }
+}
@@ -152,10 +152,19 @@ impl PriceFeed {
         allow_closed: bool,
     ) -> Result<OraclePriceParts> {
         let provider = self.provider()?;
-        require_eq!(
-            token_config.expected_provider().map_err(CoreError::from)?,
-            provider
-        );
+        if token_config.is_composite() {
+            require!(
+                token_config
+                    .is_price_source(&provider)
+                    .map_err(CoreError::from)?,
+                CoreError::InvalidPriceFeedAccount
+            );
+        } else {
+            require_eq!(
+                token_config.expected_provider().map_err(CoreError::from)?,
+                provider
+            );
+        }
 
         let feed_config = token_config
             .get_feed_config(&provider)
@@ -19,11 +19,18 @@ pub mod validator;
 /// Oracle time validation.
 pub mod time;
 
+/// Composite price.
+mod composite;
+
+/// Price circuit breaker.
+pub mod circuit_breaker;
+
 use std::ops::Deref;
 
 use crate::{
     constants,
-    states::{TokenMapAccess, TokenMapLoader},
+    events::PriceCircuitBreakerTripped,
+    states::{TokenMapAccess, TokenMapAccessMut, TokenMapLoader, TokenMapMut},
     CoreError, CoreResult,
 };
 use anchor_lang::prelude::*;
@@ -33,7 +40,11 @@ use gmsol_utils::{
     token_config::FeedConfig,
 };
 
-use self::price_map::PriceMap;
+use self::{
+    circuit_breaker::PriceCircuitBreakerExt,
+    composite::{expand_price_sources, CompositePrices},
+    price_map::PriceMap,
+};
 use super::{HasMarketMeta, Seed, Store, TokenConfig, TokenMapHeader, TokenMapRef};
 
 pub use self::{
@@ -45,7 +56,7 @@ pub use self::{
     validator::PriceValidator,
 };
 
-pub use gmsol_utils::oracle::PriceProviderKind;
+pub use gmsol_utils::oracle::{CompositePriceMode, PriceProviderKind};
 
 gmsol_utils::flags!(OracleFlag, MAX_ORACLE_FLAGS, u8);
 
@@ -104,6 +115,7 @@ impl Oracle {
     ) -> Result<()> {
         require!(self.is_cleared(), CoreError::PricesAreAlreadySet);
         require!(self.primary.is_empty(), CoreError::PricesAreAlreadySet);
+        let tokens = expand_price_sources(map, tokens)?;
         require!(
             tokens.len() <= PriceMap::MAX_TOKENS,
             CoreError::ExceedMaxLengthLimit
@@ -112,6 +124,8 @@ impl Oracle {
             tokens.len() <= remaining_accounts.len(),
             ErrorCode::AccountNotEnoughKeys
         );
+        let now = validator.clock().unix_timestamp;
+        let mut composite = CompositePrices::default();
         // Assume the remaining accounts are arranged in the following way:
         // [token_config, feed; tokens.len()] [..remaining]
         for (idx, token) in tokens.iter().enumerate() {
@@ -120,6 +134,19 @@ impl Oracle {
 
             require!(token_config.is_enabled(), CoreError::TokenConfigDisabled);
 
+            if token_config.is_composite() {
+                composite.push(
+                    token,
+                    OraclePrice::parse_from_feed_account(
+                        validator.clock(),
+                        token_config,
+                        feed,
+                        allow_closed,
+                    ),
+                );
+                continue;
+            }
+
             let oracle_price = OraclePrice::parse_from_feed_account(
                 validator.clock(),
                 token_config,
@@ -135,17 +162,126 @@ impl Oracle {
                 &oracle_price.parts.price,
                 oracle_price.parts.ref_price.as_ref(),
             )?;
-            self.primary.set(
+            self.set_primary_price(
+                now,
                 token,
+                token_config,
                 oracle_price.parts.price,
-                token_config.is_synthetic(),
                 oracle_price.parts.is_open,
             )?;
         }
+        composite.aggregate(
+            &mut validator,
+            map,
+            |token, token_config, price, is_open| {
+                self.set_primary_price(now, token, token_config, price, is_open)
+            },
+        )?;
         self.update_oracle_ts_and_slot(validator)?;
         Ok(())
     }
 
+    /// Set the primary price of the given token, and mark it if the price
+    /// is rejected by the price circuit breaker of the token.
+    fn set_primary_price(
+        &mut self,
+        now: i64,
+        token: &Pubkey,
+        token_config: &TokenConfig,
+        price: gmsol_utils::Price,
+        is_open: bool,
+    ) -> Result<()> {
+        self.primary
+            .set(token, price, token_config.is_synthetic(), is_open)?;
+        if token_config.is_price_rejected(&price, now)? {
+            msg!(
+                "[Oracle] the price of {} is rejected by the price circuit breaker",
+                token
+            );
+            self.primary
+                .get_mut(token)
+                .ok_or_else(|| error!(CoreError::Internal))?
+                .set_circuit_breaker_tripped();
+        }
+        Ok(())
+    }
+
+    /// Track the primary prices of the given tokens with their price circuit breakers.
+    pub(crate) fn track_price_circuit_breakers(
+        &self,
+        map: &mut TokenMapMut,
+        tokens: &[Pubkey],
+        now: i64,
+    ) -> Result<()> {
+        for token in tokens {
+            let price = self
+                .primary
+                .get(token)
+                .ok_or_else(|| error!(CoreError::MissingOraclePrice))?
+                .to_price()?;
+            let config = map
+                .get_mut(token)
+                .ok_or_else(|| error!(CoreError::NotFound))?;
+            if let Some(tripped) = config.track_price(&price, now)? {
+                msg!(
+                    "[Oracle] price circuit breaker tripped, token = {}, ref_price = {}, price = {}",
+                    token,
+                    tripped.ref_price,
+                    tripped.price
+                );
+                emit!(PriceCircuitBreakerTripped {
+                    ts: now,
+                    store: self.store,
+                    token: *token,
+                    ref_price: tripped.ref_price,
+                    price: tripped.price,
+                    window_start: tripped.window_start,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the price circuit breakers of the tokens of the given market,
+    /// together with the given swap tokens.
+    pub(crate) fn validate_price_circuit_breakers_for_market<'a>(
+        &self,
+        market: &'a impl HasMarketMeta,
+        swap_tokens: impl IntoIterator<Item = &'a Pubkey>,
+    ) -> CoreResult<()> {
+        let meta = market.market_meta();
+        self.validate_price_circuit_breakers(
+            [
+                &meta.index_token_mint,
+                &meta.long_token_mint,
+                &meta.short_token_mint,
+            ]
+            .into_iter()
+            .chain(swap_tokens),
+        )
+    }
+
+    /// Validate that none of the given tokens has a price rejected by its price circuit breaker.
+    pub(crate) fn validate_price_circuit_breakers<'a>(
+        &self,
+        tokens: impl IntoIterator<Item = &'a Pubkey>,
+    ) -> CoreResult<()> {
+        for token in tokens {
+            let is_tripped = self
+                .primary
+                .get(token)
+                .is_some_and(|price| price.is_circuit_breaker_tripped());
+            if is_tripped {
+                msg!(
+                    "[Oracle] price circuit breaker of {} has been tripped",
+                    token
+                );
+                return Err(CoreError::PriceCircuitBreakerTripped);
+            }
+        }
+        Ok(())
+    }
+
     /// Get min oracle slot.
     pub fn min_oracle_slot(&self) -> Option<u64> {
         if self.is_cleared() {
@@ -200,22 +336,23 @@ impl Oracle {
         allow_closed: bool,
     ) -> Result<T> {
         let validator = PriceValidator::try_from(store.load()?.deref())?;
-        require_gte!(
-            remaining_accounts.len(),
-            tokens.len(),
-            CoreError::NotEnoughTokenFeeds,
-        );
-        let feeds = &remaining_accounts[..tokens.len()];
-        let remaining_accounts = &remaining_accounts[tokens.len()..];
-        let res = {
+        let (res, remaining_accounts) = {
             let token_map = token_map.load_token_map()?;
-            self.set_prices_from_remaining_accounts(
+            let tokens = expand_price_sources(&token_map, tokens)?;
+            require_gte!(
+                remaining_accounts.len(),
+                tokens.len(),
+                CoreError::NotEnoughTokenFeeds,
+            );
+            let (feeds, remaining_accounts) = remaining_accounts.split_at(tokens.len());
+            let res = self.set_prices_from_remaining_accounts(
                 validator,
                 &token_map,
-                tokens,
+                &tokens,
                 feeds,
                 allow_closed,
-            )
+            );
+            (res, remaining_accounts)
         };
         match res {
             Ok(()) => {
@@ -392,10 +529,19 @@ impl OraclePrice {
             None => return Err(error!(CoreError::InvalidPriceFeedAccount)),
         };
 
-        require_eq!(
-            token_config.expected_provider().map_err(CoreError::from)?,
-            provider
-        );
+        if token_config.is_composite() {
+            require!(
+                token_config
+                    .is_price_source(&provider)
+                    .map_err(CoreError::from)?,
+                CoreError::InvalidPriceFeedAccount
+            );
+        } else {
+            require_eq!(
+                token_config.expected_provider().map_err(CoreError::from)?,
+                provider
+            );
+        }
 
         let feed_config = token_config
             .get_feed_config(&provider)
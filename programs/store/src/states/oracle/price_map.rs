@@ -81,6 +81,16 @@ impl SmallPrices {
         self.flags.get_flag(OraclePriceFlag::Open)
     }
 
+    /// Returns whether the price circuit breaker of the token has been tripped.
+    pub fn is_circuit_breaker_tripped(&self) -> bool {
+        self.flags.get_flag(OraclePriceFlag::CircuitBreakerTripped)
+    }
+
+    pub(super) fn set_circuit_breaker_tripped(&mut self) {
+        self.flags
+            .set_flag(OraclePriceFlag::CircuitBreakerTripped, true);
+    }
+
     /// Convert to [`Price`](gmsol_utils::Price).
     pub fn to_price(&self) -> Result<gmsol_utils::Price> {
         Ok(gmsol_utils::Price {
@@ -28,6 +28,19 @@ impl PriceValidator {
         &self.clock
     }
 
+    #[cfg(test)]
+    pub(super) fn with_clock(clock: Clock, max_age: Amount) -> Self {
+        Self {
+            clock,
+            max_age,
+            max_oracle_timestamp_range: max_age,
+            max_future_timestamp_excess: 0,
+            min_oracle_ts: i64::MAX,
+            max_oracle_ts: i64::MIN,
+            min_oracle_slot: None,
+        }
+    }
+
     pub(super) fn validate_one(
         &mut self,
         token_config: &TokenConfig,
@@ -105,6 +118,52 @@ impl PriceValidator {
         Ok(())
     }
 
+    /// Validate that the prices of the composite price sources do not
+    /// disagree with the aggregated `price` by more than the max
+    /// disagreement factor of the token.
+    pub(super) fn validate_disagreement<'a>(
+        &self,
+        token_config: &TokenConfig,
+        price: &Price,
+        sources: impl IntoIterator<Item = &'a Price>,
+    ) -> Result<()> {
+        use gmsol_model::utils::apply_factor;
+
+        let Some(max_disagreement_factor) = token_config.max_disagreement_factor() else {
+            return Ok(());
+        };
+        let ref_price = gmsol_model::price::Price::<u128>::from(price)
+            .checked_mid()
+            .ok_or_else(|| {
+                msg!("[Price Validator] failed to calculate mid price of the aggregated price");
+                CoreError::InvalidArgument
+            })?;
+        let max_disagreement =
+            apply_factor::<_, { constants::MARKET_DECIMALS }>(&ref_price, &max_disagreement_factor)
+                .ok_or_else(|| {
+                    msg!("[Price Validator] failed to calculate max disagreement for validation");
+                    CoreError::InvalidArgument
+                })?;
+        for source in sources {
+            let mid_price = gmsol_model::price::Price::<u128>::from(source)
+                .checked_mid()
+                .ok_or_else(|| {
+                    msg!("[Price Validator] failed to calculate mid price of a price source");
+                    CoreError::InvalidArgument
+                })?;
+            let disagreement = mid_price.abs_diff(ref_price);
+            if disagreement > max_disagreement {
+                msg!(
+                    "[Price Validator] price sources disagree: {} > {}",
+                    disagreement,
+                    max_disagreement
+                );
+                return err!(CoreError::InvalidPriceFeedPrice);
+            }
+        }
+        Ok(())
+    }
+
     pub(super) fn merge_range(
         &mut self,
         min_oracle_slot: Option<u64>,
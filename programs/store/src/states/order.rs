@@ -3,8 +3,10 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use gmsol_model::{
     action::decrease_position::{DecreasePositionReport, DecreasePositionSwapType},
     price::Price,
+    utils::apply_factor,
 };
 use gmsol_utils::{
+    action::ActionCallbackKind,
     order::{OrderFlag, MAX_ORDER_FLAGS},
     InitSpace as _,
 };
@@ -25,7 +27,7 @@ use super::{
     Oracle, Seed, Store,
 };
 
-pub use gmsol_utils::order::{OrderKind, OrderSide};
+pub use gmsol_utils::order::{OrderKind, OrderSide, TrailingDistance, TwapParams};
 
 gmsol_utils::flags!(OrderFlag, MAX_ORDER_FLAGS, u8);
 
@@ -56,6 +58,27 @@ impl UpdateOrderParams {
     }
 }
 
+/// Update Order Options.
+///
+/// The update parameters that are not part of [`UpdateOrderParams`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Copy, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct UpdateOrderOptions {
+    /// Trailing distance.
+    /// Only available for trailing-stop orders.
+    pub trailing_distance: Option<TrailingDistance>,
+    /// Reset the best price.
+    /// Only available for trailing-stop orders.
+    pub best_price: Option<u128>,
+}
+
+impl UpdateOrderOptions {
+    /// Is empty.
+    pub fn is_empty(&self) -> bool {
+        self.trailing_distance.is_none() && self.best_price.is_none()
+    }
+}
+
 /// Transfer Out.
 #[zero_copy]
 #[cfg_attr(feature = "debug", derive(derive_more::Debug))]
@@ -325,9 +348,11 @@ pub struct Order {
     pub(crate) gt_reward: u64,
     #[cfg_attr(feature = "debug", debug(skip))]
     padding_1: [u8; 8],
+    /// Linked orders (one-cancels-other).
+    linked_orders: [Pubkey; Order::MAX_LINKED_ORDERS - 1],
     #[cfg_attr(feature = "debug", debug(skip))]
     #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
-    reserved: [u8; 128],
+    reserved: [u8; 64],
 }
 
 impl Seed for Order {
@@ -365,6 +390,9 @@ impl Closable for Order {
 }
 
 impl Order {
+    /// Max number of orders in a linked (one-cancels-other) group.
+    pub const MAX_LINKED_ORDERS: usize = 3;
+
     /// Get rent for position cut.
     pub(crate) fn position_cut_rent(is_pure: bool, include_execution_fee: bool) -> Result<u64> {
         use anchor_spl::token::TokenAccount;
@@ -388,7 +416,8 @@ impl Order {
 
     /// Validate that current timestamp >= `valid_from_ts`.
     pub fn validate_valid_from_ts(&self) -> Result<()> {
-        if self.params.kind()?.is_market() {
+        // The interval between TWAP slices is enforced by `valid_from_ts`.
+        if self.params.kind()?.is_market() && !self.params.is_twap() {
             return Ok(());
         }
         require_gte!(Clock::get()?.unix_timestamp, self.params.valid_from_ts);
@@ -432,7 +461,7 @@ impl Order {
                     );
                 }
             }
-            OrderKind::StopLossDecrease => {
+            OrderKind::StopLossDecrease | OrderKind::TrailingStopDecrease => {
                 if is_long {
                     require_gte!(
                         trigger_price,
@@ -534,6 +563,48 @@ impl Order {
         &self.tokens
     }
 
+    /// Get the addresses of the linked orders.
+    ///
+    /// When one of the linked orders is executed, the others will be cancelled.
+    pub fn linked_orders(&self) -> impl Iterator<Item = &Pubkey> {
+        self.linked_orders
+            .iter()
+            .filter_map(|address| optional_address(address))
+    }
+
+    /// Returns whether the order is linked with other orders.
+    pub fn is_linked(&self) -> bool {
+        self.linked_orders().next().is_some()
+    }
+
+    /// Returns whether the given order is linked with this order.
+    pub fn is_linked_with(&self, order: &Pubkey) -> bool {
+        self.linked_orders().any(|address| address == order)
+    }
+
+    /// Link this order with the given orders.
+    pub(crate) fn link(&mut self, address: &Pubkey, orders: &[Pubkey]) -> Result<()> {
+        require!(!self.is_linked(), CoreError::PreconditionsAreNotMet);
+        require!(
+            self.params.kind()?.is_linkable(),
+            CoreError::OrderKindNotLinkable
+        );
+        // Linked orders are closed without invoking callbacks.
+        require!(
+            matches!(self.header.callback_kind()?, ActionCallbackKind::Disabled),
+            CoreError::InvalidArgument
+        );
+        let mut siblings = orders.iter().filter(|order| *order != address);
+        for slot in self.linked_orders.iter_mut() {
+            if let Some(order) = siblings.next() {
+                *slot = *order;
+            }
+        }
+        require!(siblings.next().is_none(), CoreError::ExceedMaxLengthLimit);
+        require!(self.is_linked(), CoreError::InvalidArgument);
+        Ok(())
+    }
+
     /// Process GT.
     /// CHECK: the order must have been successfully executed.
     #[inline(never)]
@@ -586,10 +657,18 @@ impl Order {
         Ok(())
     }
 
-    pub(crate) fn update(&mut self, id: u64, params: &UpdateOrderParams) -> Result<()> {
+    pub(crate) fn update(
+        &mut self,
+        id: u64,
+        params: &UpdateOrderParams,
+        options: &UpdateOrderOptions,
+    ) -> Result<()> {
         let current = &mut self.params;
         require!(current.is_updatable()?, CoreError::InvalidArgument);
-        require!(!params.is_empty(), CoreError::InvalidArgument);
+        require!(
+            !(params.is_empty() && options.is_empty()),
+            CoreError::InvalidArgument
+        );
 
         self.header.id = id;
 
@@ -601,8 +680,21 @@ impl Order {
             current.acceptable_price = acceptable_price;
         }
 
-        if let Some(trigger_price) = params.trigger_price {
-            current.trigger_price = trigger_price;
+        if current.kind()?.is_trailing_stop() {
+            // The trigger price of a trailing-stop order always follows its best price.
+            require!(params.trigger_price.is_none(), CoreError::InvalidArgument);
+            if let Some(best_price) = options.best_price {
+                current.best_price = best_price;
+            }
+            if let Some(distance) = options.trailing_distance {
+                current.set_trailing_distance(distance)?;
+            }
+            current.trigger_price = current.trailing_trigger_price()?;
+        } else {
+            require!(options.is_empty(), CoreError::InvalidTrailingDistance);
+            if let Some(trigger_price) = params.trigger_price {
+                current.trigger_price = trigger_price;
+            }
         }
 
         if let Some(min_output) = params.min_output {
@@ -704,9 +796,39 @@ pub struct OrderActionParams {
     pub(crate) valid_from_ts: i64,
     #[cfg_attr(feature = "debug", debug(skip))]
     padding_2: [u8; 8],
+    /// Trailing distance (in unit price or factor).
+    /// Only used by trailing-stop orders.
+    trailing_distance: u128,
+    /// The best price seen since creation (in unit price).
+    /// Only used by trailing-stop orders.
+    best_price: u128,
+    /// The total number of TWAP slices.
+    /// Zero if the order is not a TWAP order.
+    twap_slices: u8,
+    /// The number of executed TWAP slices.
+    twap_executed_slices: u8,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_3: [u8; 2],
+    /// The minimum interval between two TWAP slices in seconds.
+    twap_interval: u32,
     #[cfg_attr(feature = "debug", debug(skip))]
     #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
-    reserved: [u8; 64],
+    reserved: [u8; 24],
+}
+
+/// The remaining params of a TWAP order before executing a slice.
+#[derive(Clone, Copy)]
+pub(crate) struct TwapRemaining {
+    amount: u64,
+    size: u128,
+    min_output: u128,
+}
+
+impl TwapRemaining {
+    /// Get the remaining size delta value of all the slices left to execute.
+    pub(crate) fn size(&self) -> u128 {
+        self.size
+    }
 }
 
 impl OrderActionParams {
@@ -815,6 +937,7 @@ impl OrderActionParams {
         min_output: Option<u128>,
         swap_type: DecreasePositionSwapType,
         valid_from_ts: Option<i64>,
+        trailing_distance: Option<TrailingDistance>,
     ) -> Result<()> {
         self.kind = kind.into();
         self.side = if is_long {
@@ -853,13 +976,180 @@ impl OrderActionParams {
                 self.trigger_price = price;
                 self.valid_from_ts = valid_from_ts.unwrap_or(Self::DEFAULT_VALID_FROM_TS);
             }
+            OrderKind::TrailingStopDecrease => {
+                // The trigger price provided on creation is used as the initial best price.
+                let Some(price) = trigger_price else {
+                    return err!(CoreError::InvalidTriggerPrice);
+                };
+                let Some(distance) = trailing_distance else {
+                    return err!(CoreError::InvalidTrailingDistance);
+                };
+                self.best_price = price;
+                self.set_trailing_distance(distance)?;
+                self.trigger_price = self.trailing_trigger_price()?;
+                self.valid_from_ts = valid_from_ts.unwrap_or(Self::DEFAULT_VALID_FROM_TS);
+                return Ok(());
+            }
             _ => {
                 return err!(CoreError::Internal);
             }
         }
+        require!(
+            trailing_distance.is_none(),
+            CoreError::InvalidTrailingDistance
+        );
         Ok(())
     }
 
+    /// Set the trailing distance of a trailing-stop order.
+    ///
+    /// The best price must have been set.
+    fn set_trailing_distance(&mut self, distance: TrailingDistance) -> Result<()> {
+        let value = distance.value();
+        require_neq!(value, 0, CoreError::InvalidTrailingDistance);
+        let max = if distance.is_factor() {
+            crate::constants::MARKET_USD_UNIT
+        } else {
+            self.best_price
+        };
+        require_gt!(max, value, CoreError::InvalidTrailingDistance);
+        self.trailing_distance = value;
+        self.flags
+            .set_flag(OrderFlag::IsTrailingDistanceFactor, distance.is_factor());
+        Ok(())
+    }
+
+    /// Calculate the trigger price of a trailing-stop order from the best price.
+    fn trailing_trigger_price(&self) -> Result<u128> {
+        let Some(distance) = self.trailing_distance() else {
+            return err!(CoreError::InvalidTrailingDistance);
+        };
+        let delta = match distance {
+            TrailingDistance::Absolute(value) => value,
+            TrailingDistance::Factor(factor) => {
+                apply_factor::<_, { crate::constants::MARKET_DECIMALS }>(&self.best_price, &factor)
+                    .ok_or_else(|| error!(CoreError::ValueOverflow))?
+            }
+        };
+        if self.side()?.is_long() {
+            // The trigger price must not fall to zero.
+            self.best_price
+                .checked_sub(delta)
+                .filter(|price| *price != 0)
+                .ok_or_else(|| error!(CoreError::InvalidTrailingDistance))
+        } else {
+            self.best_price
+                .checked_add(delta)
+                .ok_or_else(|| error!(CoreError::ValueOverflow))
+        }
+    }
+
+    /// Update the best price of a trailing-stop order with the given index price,
+    /// and move the trigger price along with it.
+    ///
+    /// Returns whether the best price has been updated.
+    pub(crate) fn update_best_price(&mut self, index_price: &Price<u128>) -> Result<bool> {
+        require!(
+            self.kind()?.is_trailing_stop(),
+            CoreError::OrderKindNotAllowed
+        );
+        let is_long = self.side()?.is_long();
+        // Use the price that would be used to validate the trigger price.
+        let price = *index_price.pick_price(!is_long);
+        let is_better = if is_long {
+            price > self.best_price
+        } else {
+            price < self.best_price
+        };
+        if is_better {
+            self.best_price = price;
+            self.trigger_price = self.trailing_trigger_price()?;
+        }
+        Ok(is_better)
+    }
+
+    pub(crate) fn init_twap(&mut self, twap: Option<TwapParams>) -> Result<()> {
+        let Some(twap) = twap else {
+            return Ok(());
+        };
+        require!(
+            self.kind()?.is_twap_supported() && twap.is_valid(),
+            CoreError::InvalidTwapParams
+        );
+        self.twap_slices = twap.slices;
+        self.twap_interval = twap.interval;
+        Ok(())
+    }
+
+    /// Replace the amounts of a TWAP order with the amounts of its next slice.
+    ///
+    /// Returns the remaining amounts before the slice, or `None` if the order
+    /// is not a TWAP order.
+    pub(crate) fn start_twap_slice(&mut self) -> Result<Option<TwapRemaining>> {
+        if !self.is_twap() {
+            return Ok(None);
+        }
+        let remaining_slices = self.twap_remaining_slices();
+        require_neq!(remaining_slices, 0, CoreError::InvalidTwapParams);
+        let remaining = TwapRemaining {
+            amount: self.initial_collateral_delta_amount,
+            size: self.size_delta_value,
+            min_output: self.min_output,
+        };
+        self.initial_collateral_delta_amount =
+            TwapParams::next_slice(remaining.amount.into(), remaining_slices)
+                .try_into()
+                .map_err(|_| error!(CoreError::ValueOverflow))?;
+        self.size_delta_value = TwapParams::next_slice(remaining.size, remaining_slices);
+        self.min_output = TwapParams::next_slice(remaining.min_output, remaining_slices);
+        msg!(
+            "[TWAP] executing slice {}/{}",
+            self.twap_executed_slices + 1,
+            self.twap_slices
+        );
+        Ok(Some(remaining))
+    }
+
+    /// Finish the current TWAP slice.
+    ///
+    /// If the slice is executed, the remaining amounts are reduced by the slice and
+    /// the next slice will not be valid until the interval has passed. Otherwise,
+    /// the remaining amounts are restored.
+    ///
+    /// Returns whether there are slices left to execute.
+    pub(crate) fn finish_twap_slice(
+        &mut self,
+        remaining: TwapRemaining,
+        executed: bool,
+    ) -> Result<bool> {
+        if !executed {
+            self.initial_collateral_delta_amount = remaining.amount;
+            self.size_delta_value = remaining.size;
+            self.min_output = remaining.min_output;
+            return Ok(false);
+        }
+        self.initial_collateral_delta_amount = remaining
+            .amount
+            .checked_sub(self.initial_collateral_delta_amount)
+            .ok_or_else(|| error!(CoreError::Internal))?;
+        self.size_delta_value = remaining
+            .size
+            .checked_sub(self.size_delta_value)
+            .ok_or_else(|| error!(CoreError::Internal))?;
+        self.min_output = remaining
+            .min_output
+            .checked_sub(self.min_output)
+            .ok_or_else(|| error!(CoreError::Internal))?;
+        self.twap_executed_slices = self
+            .twap_executed_slices
+            .checked_add(1)
+            .ok_or_else(|| error!(CoreError::Internal))?;
+        self.valid_from_ts = Clock::get()?
+            .unix_timestamp
+            .saturating_add(self.twap_interval.into());
+        Ok(self.twap_remaining_slices() != 0)
+    }
+
     /// Get order kind.
     pub fn kind(&self) -> Result<OrderKind> {
         self.kind
@@ -885,6 +1175,7 @@ impl OrderActionParams {
                 | OrderKind::LimitIncrease
                 | OrderKind::LimitDecrease
                 | OrderKind::StopLossDecrease
+                | OrderKind::TrailingStopDecrease
         ))
     }
 
@@ -930,6 +1221,52 @@ impl OrderActionParams {
         self.valid_from_ts
     }
 
+    /// Get trailing distance.
+    ///
+    /// Returns `None` if the order is not a trailing-stop order.
+    pub fn trailing_distance(&self) -> Option<TrailingDistance> {
+        if self.trailing_distance == 0 {
+            return None;
+        }
+        if self.flags.get_flag(OrderFlag::IsTrailingDistanceFactor) {
+            Some(TrailingDistance::Factor(self.trailing_distance))
+        } else {
+            Some(TrailingDistance::Absolute(self.trailing_distance))
+        }
+    }
+
+    /// Get the best price seen since creation (unit price).
+    ///
+    /// Only meaningful for trailing-stop orders.
+    pub fn best_price(&self) -> u128 {
+        self.best_price
+    }
+
+    /// Returns whether the order is a TWAP order.
+    pub fn is_twap(&self) -> bool {
+        self.twap_slices != 0
+    }
+
+    /// Get TWAP params.
+    ///
+    /// Returns `None` if the order is not a TWAP order.
+    pub fn twap(&self) -> Option<TwapParams> {
+        self.is_twap().then_some(TwapParams {
+            slices: self.twap_slices,
+            interval: self.twap_interval,
+        })
+    }
+
+    /// Get the number of executed TWAP slices.
+    pub fn twap_executed_slices(&self) -> u8 {
+        self.twap_executed_slices
+    }
+
+    /// Get the number of TWAP slices left to execute.
+    pub fn twap_remaining_slices(&self) -> u8 {
+        self.twap_slices.saturating_sub(self.twap_executed_slices)
+    }
+
     /// Returns whether the empty position account should be kept.
     pub(crate) fn should_keep_position_account(&self) -> bool {
         self.flags.get_flag(OrderFlag::ShouldKeepPositionAccount)
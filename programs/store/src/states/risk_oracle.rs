@@ -0,0 +1,273 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{keccak, secp256k1_recover::secp256k1_recover},
+};
+use gmsol_utils::{
+    market::MarketConfigKey,
+    risk_oracle::{self, RiskOracleParameter, PARAMETER_NAME_LEN, PROTOCOL_LEN},
+    InitSpace,
+};
+
+use crate::{constants, CoreError};
+
+use super::Factor;
+
+/// The seed of risk oracle accounts.
+#[constant]
+pub const RISK_ORACLE_SEED: &[u8] = b"risk_oracle";
+
+/// Max number of bounds of a risk oracle.
+pub const MAX_RISK_ORACLE_BOUNDS: usize = 64;
+
+/// Risk oracle.
+///
+/// Recommendations signed by the whitelisted signer can be applied to the
+/// markets of the store, as long as every recommended value is within the
+/// bound of its key.
+#[account(zero_copy)]
+#[cfg_attr(feature = "debug", derive(derive_more::Debug))]
+pub struct RiskOracle {
+    version: u8,
+    pub(crate) bump: u8,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_0: [u8; 2],
+    /// Max age of a recommendation in seconds. `0` means unlimited.
+    pub max_age: u32,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_1: [u8; 8],
+    /// Store.
+    pub store: Pubkey,
+    /// Keccak hash of the compressed secp256k1 public key of the signer.
+    pub signer: Pubkey,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    reserved: [u8; 128],
+    bounds: [RiskOracleBound; MAX_RISK_ORACLE_BOUNDS],
+}
+
+impl InitSpace for RiskOracle {
+    const INIT_SPACE: usize = std::mem::size_of::<Self>();
+}
+
+impl RiskOracle {
+    pub(crate) fn init(&mut self, bump: u8, store: Pubkey, signer: Pubkey, max_age: u32) {
+        self.bump = bump;
+        self.store = store;
+        self.signer = signer;
+        self.max_age = max_age;
+    }
+
+    pub(crate) fn update(&mut self, signer: Pubkey, max_age: u32) {
+        self.signer = signer;
+        self.max_age = max_age;
+    }
+
+    /// Get the bound of the given key.
+    pub fn bound(&self, key: MarketConfigKey) -> Option<&RiskOracleBound> {
+        self.bounds.iter().find(|bound| bound.is_for(key))
+    }
+
+    /// Create an iterator over the active bounds.
+    pub fn bounds(&self) -> impl Iterator<Item = &RiskOracleBound> {
+        self.bounds.iter().filter(|bound| bound.is_active())
+    }
+
+    pub(crate) fn set_bound(
+        &mut self,
+        key: MarketConfigKey,
+        min: Factor,
+        max: Factor,
+    ) -> Result<()> {
+        require_gte!(max, min, CoreError::InvalidArgument);
+        let bound = match self.bounds.iter().position(|bound| bound.is_for(key)) {
+            Some(idx) => &mut self.bounds[idx],
+            None => self
+                .bounds
+                .iter_mut()
+                .find(|bound| !bound.is_active())
+                .ok_or_else(|| error!(CoreError::ExceedMaxLengthLimit))?,
+        };
+        bound.key = key.into();
+        bound.is_active = 1;
+        bound.min = min;
+        bound.max = max;
+        Ok(())
+    }
+
+    pub(crate) fn remove_bound(&mut self, key: MarketConfigKey) -> Result<()> {
+        let bound = self
+            .bounds
+            .iter_mut()
+            .find(|bound| bound.is_for(key))
+            .ok_or_else(|| error!(CoreError::NotFound))?;
+        *bound = bytemuck::Zeroable::zeroed();
+        Ok(())
+    }
+
+    /// Verify the recommendation for the given market and convert it to
+    /// config updates.
+    ///
+    /// Returns the index of the parameter and the updates.
+    pub(crate) fn verify(
+        &self,
+        market_token: &Pubkey,
+        recommendation: &RiskOracleRecommendation,
+        now: i64,
+    ) -> Result<(usize, Vec<(MarketConfigKey, Factor)>)> {
+        let (index, parameter) = recommendation.parameter()?;
+
+        let hash = recommendation.hash(parameter, market_token);
+        let pubkey =
+            secp256k1_recover(&hash, recommendation.recovery_id, &recommendation.signature)
+                .map_err(|_| error!(CoreError::RiskOracleSignatureVerificationFailed))?
+                .to_bytes();
+        let mut compressed = [0; 33];
+        compressed[0] = 0x02 | (pubkey[63] & 1);
+        compressed[1..].copy_from_slice(&pubkey[..32]);
+        require_keys_eq!(
+            Pubkey::new_from_array(keccak::hash(&compressed).to_bytes()),
+            self.signer,
+            CoreError::RiskOracleSignatureVerificationFailed
+        );
+
+        if self.max_age != 0 {
+            let timestamp = i64::try_from(recommendation.timestamp)
+                .map_err(|_| error!(CoreError::InvalidArgument))?;
+            require_gte!(
+                timestamp.saturating_add(self.max_age.into()),
+                now,
+                CoreError::StaleRiskOracleRecommendation
+            );
+        }
+
+        parameter
+            .keys
+            .iter()
+            .zip(recommendation.values.iter())
+            .map(|((_, key), value)| {
+                let factor = risk_oracle::scale_value(
+                    *key,
+                    value.value,
+                    value.decimals,
+                    constants::MARKET_DECIMALS,
+                )
+                .ok_or_else(|| error!(CoreError::ValueOverflow))?;
+                let Some(bound) = self.bound(*key) else {
+                    msg!("[Risk Oracle] {} is not bounded", key);
+                    return err!(CoreError::RiskOracleValueOutOfBounds);
+                };
+                if factor < bound.min || factor > bound.max {
+                    msg!(
+                        "[Risk Oracle] {} = {} is out of bounds [{}, {}]",
+                        key,
+                        factor,
+                        bound.min,
+                        bound.max
+                    );
+                    return err!(CoreError::RiskOracleValueOutOfBounds);
+                }
+                Ok((*key, factor))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|updates| (index, updates))
+    }
+}
+
+/// Bounded range of a market config key.
+#[zero_copy]
+#[cfg_attr(feature = "debug", derive(derive_more::Debug))]
+pub struct RiskOracleBound {
+    key: u16,
+    is_active: u8,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_0: [u8; 13],
+    /// Min value.
+    pub min: u128,
+    /// Max value.
+    pub max: u128,
+}
+
+impl RiskOracleBound {
+    /// Returns whether the bound is active.
+    pub fn is_active(&self) -> bool {
+        self.is_active != 0
+    }
+
+    /// Get the key.
+    pub fn key(&self) -> Option<MarketConfigKey> {
+        if !self.is_active() {
+            return None;
+        }
+        MarketConfigKey::try_from(self.key).ok()
+    }
+
+    fn is_for(&self, key: MarketConfigKey) -> bool {
+        self.is_active() && self.key == u16::from(key)
+    }
+}
+
+/// A recommended value.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RiskOracleValue {
+    /// Value.
+    pub value: u64,
+    /// Decimals of the value.
+    pub decimals: u8,
+}
+
+/// A recommendation signed by the risk oracle.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RiskOracleRecommendation {
+    /// Parameter name.
+    pub parameter_name: String,
+    /// Recommended values of the keys of the parameter, sorted by key.
+    pub values: Vec<RiskOracleValue>,
+    /// Unix timestamp of the recommendation in seconds.
+    pub timestamp: u64,
+    /// Protocol name.
+    pub protocol: String,
+    /// Reference id.
+    pub reference_id: [u8; 16],
+    /// Secp256k1 signature.
+    pub signature: [u8; 64],
+    /// Recovery id of the signature.
+    pub recovery_id: u8,
+}
+
+impl RiskOracleRecommendation {
+    fn parameter(&self) -> Result<(usize, &'static RiskOracleParameter)> {
+        require_gte!(
+            PARAMETER_NAME_LEN,
+            self.parameter_name.len(),
+            CoreError::InvalidRiskOracleRecommendation
+        );
+        require_gte!(
+            PROTOCOL_LEN,
+            self.protocol.len(),
+            CoreError::InvalidRiskOracleRecommendation
+        );
+        let (index, parameter) = risk_oracle::find_parameter(&self.parameter_name)
+            .ok_or_else(|| error!(CoreError::InvalidRiskOracleRecommendation))?;
+        require_eq!(
+            self.values.len(),
+            parameter.keys.len(),
+            CoreError::InvalidRiskOracleRecommendation
+        );
+        Ok((index, parameter))
+    }
+
+    fn hash(&self, parameter: &RiskOracleParameter, market_token: &Pubkey) -> [u8; 32] {
+        let msg = risk_oracle::recommendation_message(
+            parameter.name,
+            market_token,
+            self.values
+                .iter()
+                .map(|value| (value.value, value.decimals)),
+            self.timestamp,
+            &self.protocol,
+            &self.reference_id,
+        );
+        keccak::hash(&msg).to_bytes()
+    }
+}
@@ -8,7 +8,7 @@ use gmsol_utils::token_config::{TokenConfigError, TokenConfigFlag};
 
 use crate::{utils::fixed_str::fixed_str_to_bytes, CoreError};
 
-use super::{InitSpace, PriceProviderKind};
+use super::{CompositePriceMode, InitSpace, PriceProviderKind};
 
 pub use gmsol_utils::token_config::{
     FeedConfig, TokenConfig, TokenMapAccess, UpdateTokenConfigParams,
@@ -108,8 +108,10 @@ impl TokenConfigExt for TokenConfig {
             .collect::<Vec<_>>()
             .try_into()
             .map_err(|_| error!(CoreError::InvalidArgument))?;
+        self.set_composite_price(CompositePriceMode::Disabled, &[], None)
+            .map_err(CoreError::from)?;
         if !init {
-            msg!("[Token Config] feeds are rebuilt: market status flags and max deviation ratios are reset");
+            msg!("[Token Config] feeds are rebuilt: market status flags, max deviation ratios and composite price config are reset");
         }
         self.expected_provider = expected_provider.unwrap_or(PriceProviderKind::default() as u8);
         self.heartbeat_duration = heartbeat_duration;
@@ -9,7 +9,7 @@ use crate::{
     CoreError,
 };
 
-use super::Seed;
+use super::{order::OrderKind, Seed};
 
 /// Header of `User` Account.
 #[account(zero_copy)]
@@ -340,3 +340,188 @@ impl UserGtState {
         self.amount
     }
 }
+
+const MAX_DELEGATED_MARKETS: usize = 8;
+
+/// User Delegation Account.
+///
+/// Authorizes the `delegate` to create, update and close orders on behalf of the `owner`,
+/// within the scope recorded in this account. The output funds of the orders are always
+/// sent to the `owner`.
+#[account(zero_copy)]
+#[cfg_attr(feature = "debug", derive(derive_more::Debug))]
+pub struct UserDelegation {
+    version: u8,
+    pub(crate) bump: u8,
+    decrease_only: u8,
+    num_markets: u8,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_0: [u8; 4],
+    expires_at: i64,
+    /// The store.
+    pub(crate) store: Pubkey,
+    /// The owner of the delegated user account.
+    pub(crate) owner: Pubkey,
+    /// The delegate.
+    pub(crate) delegate: Pubkey,
+    max_size_delta_value: u128,
+    markets: [Pubkey; MAX_DELEGATED_MARKETS],
+    #[cfg_attr(feature = "debug", debug(skip))]
+    reserved: [u8; 128],
+}
+
+impl InitSpace for UserDelegation {
+    const INIT_SPACE: usize = std::mem::size_of::<Self>();
+}
+
+impl Seed for UserDelegation {
+    const SEED: &'static [u8] = b"user_delegation";
+}
+
+impl UserDelegation {
+    /// Max number of markets that a delegation can be limited to.
+    pub const MAX_MARKETS: usize = MAX_DELEGATED_MARKETS;
+
+    pub(crate) fn init(
+        &mut self,
+        bump: u8,
+        store: &Pubkey,
+        owner: &Pubkey,
+        delegate: &Pubkey,
+        scope: &DelegationScope,
+    ) -> Result<()> {
+        require_keys_neq!(*owner, *delegate, CoreError::InvalidArgument);
+        self.bump = bump;
+        self.store = *store;
+        self.owner = *owner;
+        self.delegate = *delegate;
+        self.set_scope(scope)
+    }
+
+    pub(crate) fn set_scope(&mut self, scope: &DelegationScope) -> Result<()> {
+        require_gte!(
+            Self::MAX_MARKETS,
+            scope.markets.len(),
+            CoreError::ExceedMaxLengthLimit
+        );
+        for (idx, market_token) in scope.markets.iter().enumerate() {
+            require!(
+                optional_address(market_token).is_some(),
+                CoreError::InvalidArgument
+            );
+            require!(
+                !scope.markets[..idx].contains(market_token),
+                CoreError::InvalidArgument
+            );
+        }
+        if let Some(max_size_delta_value) = scope.max_size_delta_value {
+            require_neq!(max_size_delta_value, 0, CoreError::InvalidArgument);
+        }
+        if let Some(expires_at) = scope.expires_at {
+            require_gt!(
+                expires_at,
+                Clock::get()?.unix_timestamp,
+                CoreError::InvalidArgument
+            );
+        }
+
+        self.decrease_only = u8::from(scope.decrease_only);
+        self.num_markets = scope.markets.len() as u8;
+        self.markets = [DEFAULT_PUBKEY; MAX_DELEGATED_MARKETS];
+        self.markets[..scope.markets.len()].copy_from_slice(&scope.markets);
+        self.max_size_delta_value = scope.max_size_delta_value.unwrap_or_default();
+        self.expires_at = scope.expires_at.unwrap_or_default();
+        Ok(())
+    }
+
+    /// Get the store.
+    pub fn store(&self) -> &Pubkey {
+        &self.store
+    }
+
+    /// Get the owner.
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    /// Get the delegate.
+    pub fn delegate(&self) -> &Pubkey {
+        &self.delegate
+    }
+
+    /// Returns whether the delegate is only allowed to decrease positions.
+    pub fn is_decrease_only(&self) -> bool {
+        self.decrease_only != 0
+    }
+
+    /// Get the market tokens that the delegation is limited to.
+    ///
+    /// Returns an empty slice if all markets are allowed.
+    pub fn markets(&self) -> &[Pubkey] {
+        &self.markets[..usize::from(self.num_markets)]
+    }
+
+    /// Get the max size delta value of an order.
+    pub fn max_size_delta_value(&self) -> Option<u128> {
+        (self.max_size_delta_value != 0).then_some(self.max_size_delta_value)
+    }
+
+    /// Get the expiration timestamp.
+    pub fn expires_at(&self) -> Option<i64> {
+        (self.expires_at != 0).then_some(self.expires_at)
+    }
+
+    /// Validate that the delegation is still in effect.
+    pub(crate) fn validate_not_expired(&self, now: i64) -> Result<()> {
+        if let Some(expires_at) = self.expires_at() {
+            if now >= expires_at {
+                msg!("[Delegation] the delegation expired at {}", expires_at);
+                return err!(CoreError::PermissionDenied);
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate that an order with the given parameters is within the scope.
+    pub(crate) fn validate_order(
+        &self,
+        now: i64,
+        market_token: &Pubkey,
+        kind: OrderKind,
+        size_delta_value: u128,
+    ) -> Result<()> {
+        self.validate_not_expired(now)?;
+        let markets = self.markets();
+        if !markets.is_empty() && !markets.contains(market_token) {
+            msg!("[Delegation] market `{}` is not delegated", market_token);
+            return err!(CoreError::PermissionDenied);
+        }
+        if self.is_decrease_only() && !kind.is_decrease_position() {
+            msg!("[Delegation] only decrease orders are delegated");
+            return err!(CoreError::PermissionDenied);
+        }
+        if let Some(max_size_delta_value) = self.max_size_delta_value() {
+            require_gte!(
+                max_size_delta_value,
+                size_delta_value,
+                CoreError::PermissionDenied
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The scope of a [`UserDelegation`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct DelegationScope {
+    /// The market tokens that the delegate is allowed to trade.
+    /// All markets are allowed if empty.
+    pub markets: Vec<Pubkey>,
+    /// Whether the delegate is only allowed to decrease positions.
+    pub decrease_only: bool,
+    /// The max size delta value of an order. Unlimited if `None`.
+    pub max_size_delta_value: Option<u128>,
+    /// The expiration timestamp. Never expires if `None`.
+    pub expires_at: Option<i64>,
+}
@@ -108,6 +108,11 @@ where
         Ok(false)
     }
 
+    /// Whether the authority is an authorized delegate of the owner.
+    fn is_caller_delegate(&self) -> Result<bool> {
+        Ok(false)
+    }
+
     /// Validate.
     fn validate(&self) -> Result<()>;
 
@@ -152,7 +157,9 @@ where
 
     /// Preprocess.
     fn preprocess(&self) -> Result<IsCallerOwner> {
-        if *self.authority().key == self.action().load()?.header().owner {
+        if *self.authority().key == self.action().load()?.header().owner
+            || self.is_caller_delegate()?
+        {
             Ok(true)
         } else {
             self.only_role(self.expected_keeper_role())?;
@@ -178,3 +178,175 @@ impl<'info> CpiAuthentication<'info> for IncreaseDelay<'info> {
         err!(CoreError::PermissionDenied)
     }
 }
+
+/// The accounts definition for [`set_delay_override`](crate::gmsol_timelock::set_delay_override)
+/// and [`remove_delay_override`](crate::gmsol_timelock::remove_delay_override).
+#[derive(Accounts)]
+pub struct SetDelayOverride<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Timelock config.
+    #[account(mut, has_one = store)]
+    pub timelock_config: AccountLoader<'info, TimelockConfig>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+/// Set delay override.
+///
+/// Only [`TIMELOCK_ADMIN`](crate::roles::TIMELOCK_ADMIN) can use, unless the new delay is
+/// lower than the current one, which requires ADMIN.
+pub(crate) fn set_delay_override(
+    ctx: Context<SetDelayOverride>,
+    program_id: &Pubkey,
+    discriminator: Option<&[u8; 8]>,
+    delay: u32,
+) -> Result<()> {
+    let current = ctx
+        .accounts
+        .timelock_config
+        .load()?
+        .delay_for_override(program_id, discriminator);
+    only_admin_if_lowering(&ctx, delay < current)?;
+    ctx.accounts
+        .timelock_config
+        .load_mut()?
+        .set_delay_override(program_id, discriminator, delay)?;
+    msg!(
+        "[Timelock] Set delay override for program {} (discriminator = {:?}), delay = {}",
+        program_id,
+        discriminator,
+        delay
+    );
+    Ok(())
+}
+
+/// Remove delay override.
+///
+/// Only [`TIMELOCK_ADMIN`](crate::roles::TIMELOCK_ADMIN) can use, unless the removal
+/// lowers the delay, which requires ADMIN.
+pub(crate) fn remove_delay_override(
+    ctx: Context<SetDelayOverride>,
+    program_id: &Pubkey,
+    discriminator: Option<&[u8; 8]>,
+) -> Result<()> {
+    let is_lowering = {
+        let config = ctx.accounts.timelock_config.load()?;
+        config.fallback_delay(program_id, discriminator)
+            < config.delay_for_override(program_id, discriminator)
+    };
+    only_admin_if_lowering(&ctx, is_lowering)?;
+    ctx.accounts
+        .timelock_config
+        .load_mut()?
+        .remove_delay_override(program_id, discriminator)?;
+    msg!(
+        "[Timelock] Removed delay override for program {} (discriminator = {:?})",
+        program_id,
+        discriminator,
+    );
+    Ok(())
+}
+
+/// Lowering a delay requires ADMIN, which is held by the wallet of the admin executor
+/// after initialization, so it can only be done by a timelocked instruction.
+/// Otherwise [`TIMELOCK_ADMIN`](crate::roles::TIMELOCK_ADMIN) is required.
+fn only_admin_if_lowering<'info, T>(ctx: &Context<T>, is_lowering: bool) -> Result<()>
+where
+    T: CpiAuthenticate<'info>,
+{
+    if is_lowering {
+        CpiAuthenticate::only_admin(ctx)
+    } else {
+        CpiAuthenticate::only(ctx, roles::TIMELOCK_ADMIN)
+    }
+}
+
+impl<'info> WithStore<'info> for SetDelayOverride<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for SetDelayOverride<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`set_executor_min_delay`](crate::gmsol_timelock::set_executor_min_delay).
+#[derive(Accounts)]
+#[instruction(role: String)]
+pub struct SetExecutorMinDelay<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Executor.
+    #[account(
+        mut,
+        has_one = store,
+        constraint = executor.load()?.role_name()? == role.as_str() @ CoreError::InvalidArgument,
+        seeds = [
+            Executor::SEED,
+            store.key.as_ref(),
+            &fixed_str_to_bytes::<MAX_ROLE_NAME_LEN>(&role)?,
+        ],
+        bump = executor.load()?.bump,
+    )]
+    pub executor: AccountLoader<'info, Executor>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+/// Set the min delay of the executor.
+///
+/// Only [`TIMELOCK_ADMIN`](crate::roles::TIMELOCK_ADMIN) can use, unless the new min delay
+/// is lower than the current one, which requires ADMIN.
+pub(crate) fn set_executor_min_delay(
+    ctx: Context<SetExecutorMinDelay>,
+    role: &str,
+    min_delay: u32,
+) -> Result<()> {
+    let current = ctx.accounts.executor.load()?.min_delay();
+    only_admin_if_lowering(&ctx, min_delay < current)?;
+    ctx.accounts.executor.load_mut()?.set_min_delay(min_delay);
+    msg!(
+        "[Timelock] Set min delay of executor `{}` to {}",
+        role,
+        min_delay
+    );
+    Ok(())
+}
+
+impl<'info> WithStore<'info> for SetExecutorMinDelay<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for SetExecutorMinDelay<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
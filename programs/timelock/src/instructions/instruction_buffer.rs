@@ -266,7 +266,12 @@ pub struct CancelInstruction<'info> {
 /// Cancel instruction.
 /// # CHECK
 /// Only [`TIMELOCK_ADMIN`](crate::roles::TIMELOCK_ADMIN) can use.
-pub(crate) fn unchecked_cancel_instruction(_ctx: Context<CancelInstruction>) -> Result<()> {
+pub(crate) fn unchecked_cancel_instruction(ctx: Context<CancelInstruction>) -> Result<()> {
+    // Instructions of a proposal must be cancelled with the proposal.
+    require!(
+        ctx.accounts.instruction.load()?.proposal().is_none(),
+        CoreError::PreconditionsAreNotMet
+    );
     Ok(())
 }
 
@@ -330,6 +335,10 @@ pub(crate) fn unchecked_cancel_instructions<'info>(
                 rent_receiver,
                 CoreError::InvalidArgument
             );
+            require!(
+                header.proposal().is_none(),
+                CoreError::PreconditionsAreNotMet
+            );
         }
 
         loader.close(ctx.accounts.rent_receiver.to_account_info())?;
@@ -404,6 +413,12 @@ pub(crate) fn unchecked_execute_instruction(ctx: Context<ExecuteInstruction>) ->
 
     let instruction = ctx.accounts.instruction.load_instruction()?;
 
+    // Instructions of a proposal must be executed with the proposal.
+    require!(
+        instruction.header().proposal().is_none(),
+        CoreError::PreconditionsAreNotMet
+    );
+
     // Validate that the approver still have the required role.
     {
         let store = ctx.accounts.store.load()?;
@@ -418,9 +433,12 @@ pub(crate) fn unchecked_execute_instruction(ctx: Context<ExecuteInstruction>) ->
         );
     }
 
-    let delay = ctx.accounts.timelock_config.load()?.delay();
     require!(
-        instruction.header().is_executable(delay)?,
+        instruction.header().is_executable(
+            &*ctx.accounts.timelock_config.load()?,
+            &*ctx.accounts.executor.load()?,
+            instruction.data(),
+        )?,
         CoreError::PreconditionsAreNotMet
     );
 
@@ -460,7 +478,7 @@ impl<'info> CpiAuthentication<'info> for ExecuteInstruction<'info> {
     }
 }
 
-fn validate_timelocked_role<'info>(
+pub(crate) fn validate_timelocked_role<'info>(
     ctx: &Context<impl CpiAuthenticate<'info>>,
     role: &str,
 ) -> Result<()> {
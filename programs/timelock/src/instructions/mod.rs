@@ -7,6 +7,9 @@ pub mod executor;
 /// Instructions for instruction buffer.
 pub mod instruction_buffer;
 
+/// Instructions for proposals.
+pub mod proposal;
+
 /// Instructions that bypassed timelock.
 pub mod bypass;
 
@@ -14,3 +17,4 @@ pub use bypass::*;
 pub use config::*;
 pub use executor::*;
 pub use instruction_buffer::*;
+pub use proposal::*;
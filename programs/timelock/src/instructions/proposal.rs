@@ -0,0 +1,488 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use gmsol_store::{
+    program::GmsolStore,
+    states::{Seed, Store, MAX_ROLE_NAME_LEN},
+    utils::{fixed_str::fixed_str_to_bytes, CpiAuthentication, WithStore},
+    CoreError,
+};
+use gmsol_utils::InitSpace;
+
+use crate::{
+    roles,
+    states::{
+        config::TimelockConfig, Executor, ExecutorWalletSigner, InstructionAccess,
+        InstructionHeader, InstructionLoader, Proposal,
+    },
+};
+
+use super::instruction_buffer::validate_timelocked_role;
+
+/// The accounts definition for [`create_proposal`](crate::gmsol_timelock::create_proposal).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[writable]` N instruction buffers to add to the proposal, in execution order.
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    /// Authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Expected executor.
+    #[account(has_one = store)]
+    pub executor: AccountLoader<'info, Executor>,
+    /// Proposal to create.
+    #[account(init, payer = authority, space = 8 + Proposal::INIT_SPACE)]
+    pub proposal: AccountLoader<'info, Proposal>,
+    /// The proposal that must be executed before this one.
+    /// Required only if `depends_on` is provided.
+    #[account(mut, has_one = store)]
+    pub depends_on: Option<AccountLoader<'info, Proposal>>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Create proposal.
+/// # CHECK
+/// Only [`TIMELOCK_KEEPER`](crate::roles::TIMELOCK_KEEPER) can use.
+pub(crate) fn unchecked_create_proposal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateProposal<'info>>,
+    depends_on: Option<Pubkey>,
+) -> Result<()> {
+    let proposal = ctx.accounts.proposal.key();
+    let executor = ctx.accounts.executor.key();
+    let rent_receiver = ctx.accounts.authority.key();
+
+    if let Some(depends_on) = depends_on {
+        require_keys_neq!(depends_on, proposal, CoreError::InvalidArgument);
+        let dependency = ctx
+            .accounts
+            .depends_on
+            .as_ref()
+            .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+        require_keys_eq!(dependency.key(), depends_on, CoreError::InvalidArgument);
+        // Prevent the dependency from being closed before this proposal is executed or cancelled.
+        dependency.load_mut()?.add_dependent()?;
+    }
+
+    let mut instructions = Vec::with_capacity(ctx.remaining_accounts.len());
+    for account in ctx.remaining_accounts {
+        require!(account.is_writable, ErrorCode::AccountNotMutable);
+        let loader = AccountLoader::<InstructionHeader>::try_from(account)?;
+        let mut header = loader.load_mut()?;
+        require_keys_eq!(*header.executor(), executor, CoreError::InvalidArgument);
+        require_keys_eq!(
+            *header.rent_receiver(),
+            rent_receiver,
+            CoreError::InvalidArgument
+        );
+        header.set_proposal(proposal)?;
+        instructions.push(account.key());
+    }
+
+    ctx.accounts.proposal.load_init()?.try_init(
+        ctx.accounts.store.key(),
+        executor,
+        rent_receiver,
+        depends_on,
+        &instructions,
+    )?;
+
+    msg!(
+        "[Timelock] created proposal with {} instructions, executor = `{}`",
+        instructions.len(),
+        ctx.accounts.executor.load()?.role_name()?,
+    );
+
+    Ok(())
+}
+
+impl<'info> WithStore<'info> for CreateProposal<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for CreateProposal<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`approve_proposal`](crate::gmsol_timelock::approve_proposal).
+#[derive(Accounts)]
+#[instruction(role: String)]
+pub struct ApproveProposal<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Executor.
+    #[account(
+        has_one = store,
+        constraint = executor.load()?.role_name()? == role.as_str() @ CoreError::InvalidArgument,
+        seeds = [
+            Executor::SEED,
+            store.key.as_ref(),
+            &fixed_str_to_bytes::<MAX_ROLE_NAME_LEN>(&role)?,
+        ],
+        bump = executor.load()?.bump,
+    )]
+    pub executor: AccountLoader<'info, Executor>,
+    /// Proposal to approve.
+    #[account(mut, has_one = store, has_one = executor)]
+    pub proposal: AccountLoader<'info, Proposal>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+/// Approve proposal.
+pub(crate) fn approve_proposal(ctx: Context<ApproveProposal>, role: &str) -> Result<()> {
+    validate_timelocked_role(&ctx, role)?;
+    ctx.accounts
+        .proposal
+        .load_mut()?
+        .approve(ctx.accounts.authority.key())
+}
+
+impl<'info> WithStore<'info> for ApproveProposal<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for ApproveProposal<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`cancel_proposal`](crate::gmsol_timelock::cancel_proposal).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[writable]` All the N instruction buffers of the proposal, in execution order.
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Rent receiver.
+    /// CHECK: only used to receive funds.
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+    /// Proposal to cancel.
+    #[account(
+        mut,
+        has_one = store,
+        has_one = rent_receiver,
+        constraint = !proposal.load()?.is_executed() @ CoreError::PreconditionsAreNotMet,
+        constraint = proposal.load()?.num_dependents() == 0 @ CoreError::PreconditionsAreNotMet,
+        close = rent_receiver,
+    )]
+    pub proposal: AccountLoader<'info, Proposal>,
+    /// The proposal that this one depends on.
+    /// Required only if the proposal has a dependency.
+    #[account(mut, has_one = store)]
+    pub depends_on: Option<AccountLoader<'info, Proposal>>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+/// Cancel proposal.
+/// # CHECK
+/// Only [`TIMELOCK_ADMIN`](crate::roles::TIMELOCK_ADMIN) can use.
+pub(crate) fn unchecked_cancel_proposal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelProposal<'info>>,
+) -> Result<()> {
+    let proposal = ctx.accounts.proposal.load()?;
+    let instructions = proposal.instructions();
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        instructions.len(),
+        CoreError::InvalidArgument
+    );
+
+    for (account, expected) in ctx.remaining_accounts.iter().zip(instructions) {
+        require_keys_eq!(*account.key, *expected, CoreError::InvalidArgument);
+        require!(account.is_writable, ErrorCode::AccountNotMutable);
+        AccountLoader::<InstructionHeader>::try_from(account)?
+            .close(ctx.accounts.rent_receiver.to_account_info())?;
+    }
+
+    if let Some(depends_on) = proposal.depends_on() {
+        let dependency = ctx
+            .accounts
+            .depends_on
+            .as_ref()
+            .ok_or_else(|| error!(CoreError::InvalidArgument))?;
+        require_keys_eq!(dependency.key(), *depends_on, CoreError::InvalidArgument);
+        dependency.load_mut()?.remove_dependent()?;
+    }
+
+    Ok(())
+}
+
+impl<'info> WithStore<'info> for CancelProposal<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for CancelProposal<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`execute_proposal`](crate::gmsol_timelock::execute_proposal).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[writable]` All the N instruction buffers of the proposal, in execution order.
+///   - N... The accounts required by the instructions.
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store.
+    pub store: AccountLoader<'info, Store>,
+    /// Timelock config.
+    #[account(has_one = store)]
+    pub timelock_config: AccountLoader<'info, TimelockConfig>,
+    /// Executor.
+    #[account(has_one = store)]
+    pub executor: AccountLoader<'info, Executor>,
+    /// Executor Wallet.
+    /// CHECK: `wallet` doesn't have to be a system account, allowing
+    /// the instruction to close it.
+    #[account(
+        mut,
+        seeds = [Executor::WALLET_SEED, executor.key().as_ref()],
+        bump = executor.load()?.wallet_bump,
+    )]
+    pub wallet: UncheckedAccount<'info>,
+    /// Rent receiver.
+    /// CHECK: only used to receive funds.
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+    /// Proposal to execute.
+    #[account(mut, has_one = store, has_one = executor, has_one = rent_receiver)]
+    pub proposal: AccountLoader<'info, Proposal>,
+    /// The proposal that must be executed before this one.
+    /// Required only if the proposal has a dependency.
+    #[account(mut, has_one = store)]
+    pub depends_on: Option<AccountLoader<'info, Proposal>>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+/// Execute proposal.
+/// # CHECK
+/// Only [`TIMELOCK_KEEPER`](crate::roles::TIMELOCK_KEEPER) can use.
+pub(crate) fn unchecked_execute_proposal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
+) -> Result<()> {
+    let instructions = {
+        let proposal = ctx.accounts.proposal.load()?;
+        require!(!proposal.is_executed(), CoreError::PreconditionsAreNotMet);
+
+        // Validate that the approver still have the required role.
+        {
+            let store = ctx.accounts.store.load()?;
+            let approver = proposal
+                .approver()
+                .ok_or_else(|| error!(CoreError::PreconditionsAreNotMet))?;
+            let timelocked_role =
+                roles::timelocked_role(ctx.accounts.executor.load()?.role_name()?);
+            require!(
+                store.has_role(approver, &timelocked_role)?,
+                CoreError::PreconditionsAreNotMet
+            );
+        }
+
+        // Validate that the dependency has been executed.
+        if let Some(depends_on) = proposal.depends_on() {
+            let dependency = ctx
+                .accounts
+                .depends_on
+                .as_ref()
+                .ok_or_else(|| error!(CoreError::PreconditionsAreNotMet))?;
+            require_keys_eq!(dependency.key(), *depends_on, CoreError::InvalidArgument);
+            require!(
+                dependency.load()?.is_executed(),
+                CoreError::PreconditionsAreNotMet
+            );
+        }
+
+        let buffers = proposal.instructions();
+        require_gte!(
+            ctx.remaining_accounts.len(),
+            buffers.len(),
+            CoreError::InvalidArgument
+        );
+
+        let config = ctx.accounts.timelock_config.load()?;
+        let executor = ctx.accounts.executor.load()?;
+        let mut delay = 0;
+        let mut instructions = Vec::with_capacity(buffers.len());
+        for (account, expected) in ctx.remaining_accounts.iter().zip(buffers) {
+            require_keys_eq!(*account.key, *expected, CoreError::InvalidArgument);
+            let loader = AccountLoader::<InstructionHeader>::try_from(account)?;
+            let instruction = loader.load_instruction()?;
+            delay = instruction
+                .header()
+                .delay(&config, &executor, instruction.data())
+                .max(delay);
+            instructions.push(instruction.to_instruction(false).map_err(|err| {
+                msg!("Instruction error: {}", err);
+                error!(CoreError::InvalidArgument)
+            })?);
+        }
+
+        // The proposal is executable only after the max delay of its instructions.
+        require!(
+            proposal.is_executable(delay)?,
+            CoreError::PreconditionsAreNotMet
+        );
+
+        instructions
+    };
+
+    let (buffers, remaining_accounts) = ctx.remaining_accounts.split_at(instructions.len());
+
+    let signer = ExecutorWalletSigner::new(
+        ctx.accounts.executor.key(),
+        ctx.accounts.executor.load()?.wallet_bump,
+    );
+
+    for instruction in instructions {
+        invoke_signed(&instruction, remaining_accounts, &[&signer.as_seeds()])?;
+    }
+
+    for account in buffers {
+        require!(account.is_writable, ErrorCode::AccountNotMutable);
+        AccountLoader::<InstructionHeader>::try_from(account)?
+            .close(ctx.accounts.rent_receiver.to_account_info())?;
+    }
+
+    ctx.accounts.proposal.load_mut()?.mark_as_executed()?;
+
+    // The dependency has been validated above.
+    if let Some(dependency) = ctx.accounts.depends_on.as_ref() {
+        if ctx.accounts.proposal.load()?.depends_on().is_some() {
+            dependency.load_mut()?.remove_dependent()?;
+        }
+    }
+
+    msg!(
+        "[Timelock] executed proposal {}",
+        ctx.accounts.proposal.key()
+    );
+
+    Ok(())
+}
+
+impl<'info> WithStore<'info> for ExecuteProposal<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for ExecuteProposal<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`close_proposal`](crate::gmsol_timelock::close_proposal).
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Rent receiver.
+    /// CHECK: only used to receive funds.
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+    /// Executed proposal to close.
+    #[account(
+        mut,
+        has_one = store,
+        has_one = rent_receiver,
+        constraint = proposal.load()?.is_executed() @ CoreError::PreconditionsAreNotMet,
+        constraint = proposal.load()?.num_dependents() == 0 @ CoreError::PreconditionsAreNotMet,
+        close = rent_receiver,
+    )]
+    pub proposal: AccountLoader<'info, Proposal>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+}
+
+/// Close executed proposal.
+/// # CHECK
+/// Only [`TIMELOCK_KEEPER`](crate::roles::TIMELOCK_KEEPER) can use.
+pub(crate) fn unchecked_close_proposal(_ctx: Context<CloseProposal>) -> Result<()> {
+    Ok(())
+}
+
+impl<'info> WithStore<'info> for CloseProposal<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for CloseProposal<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
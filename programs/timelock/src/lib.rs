@@ -31,6 +31,50 @@ pub mod gmsol_timelock {
         instructions::unchecked_increase_delay(ctx, delta)
     }
 
+    /// Set the delay of the instructions of the given program.
+    ///
+    /// If `discriminator` is provided, the override only applies to the instructions
+    /// with the discriminator, and takes precedence over the override for the program.
+    ///
+    /// Requires `TIMELOCK_ADMIN`. Lowering the delay requires ADMIN instead, so it can
+    /// only be done by a timelocked instruction.
+    ///
+    /// At most [`MAX_DELAY_OVERRIDES`](crate::states::MAX_DELAY_OVERRIDES) overrides can be
+    /// set. Setting a new override when the limit is reached fails with `ExceedMaxLengthLimit`;
+    /// remove an existing override first.
+    pub fn set_delay_override(
+        ctx: Context<SetDelayOverride>,
+        program_id: Pubkey,
+        discriminator: Option<[u8; 8]>,
+        delay: u32,
+    ) -> Result<()> {
+        instructions::set_delay_override(ctx, &program_id, discriminator.as_ref(), delay)
+    }
+
+    /// Remove the delay override.
+    ///
+    /// Requires `TIMELOCK_ADMIN`. Removing an override that lowers the delay requires
+    /// ADMIN instead, so it can only be done by a timelocked instruction.
+    pub fn remove_delay_override(
+        ctx: Context<SetDelayOverride>,
+        program_id: Pubkey,
+        discriminator: Option<[u8; 8]>,
+    ) -> Result<()> {
+        instructions::remove_delay_override(ctx, &program_id, discriminator.as_ref())
+    }
+
+    /// Set the min delay of the instructions executed by the executor of the given role.
+    ///
+    /// Requires `TIMELOCK_ADMIN`. Lowering the min delay requires ADMIN instead, so it can
+    /// only be done by a timelocked instruction.
+    pub fn set_executor_min_delay(
+        ctx: Context<SetExecutorMinDelay>,
+        role: String,
+        min_delay: u32,
+    ) -> Result<()> {
+        instructions::set_executor_min_delay(ctx, &role, min_delay)
+    }
+
     /// Initialize executor.
     pub fn initialize_executor(ctx: Context<InitializeExecutor>, role: String) -> Result<()> {
         instructions::initialize_executor(ctx, &role)
@@ -87,6 +131,48 @@ pub mod gmsol_timelock {
         instructions::unchecked_execute_instruction(ctx)
     }
 
+    /// Create a proposal from instruction buffers.
+    ///
+    /// The instructions of a proposal are approved, executed and cancelled together.
+    #[access_control(CpiAuthenticate::only(&ctx, roles::TIMELOCK_KEEPER))]
+    pub fn create_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateProposal<'info>>,
+        depends_on: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::unchecked_create_proposal(ctx, depends_on)
+    }
+
+    /// Approve proposal.
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, role: String) -> Result<()> {
+        instructions::approve_proposal(ctx, &role)
+    }
+
+    /// Cancel proposal and its instructions.
+    ///
+    /// A proposal cannot be cancelled while other pending proposals depend on it.
+    #[access_control(CpiAuthenticate::only(&ctx, roles::TIMELOCK_ADMIN))]
+    pub fn cancel_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelProposal<'info>>,
+    ) -> Result<()> {
+        instructions::unchecked_cancel_proposal(ctx)
+    }
+
+    /// Execute the instructions of the proposal atomically in order.
+    #[access_control(CpiAuthenticate::only(&ctx, roles::TIMELOCK_KEEPER))]
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        instructions::unchecked_execute_proposal(ctx)
+    }
+
+    /// Close executed proposal.
+    ///
+    /// A proposal cannot be closed while other pending proposals depend on it.
+    #[access_control(CpiAuthenticate::only(&ctx, roles::TIMELOCK_KEEPER))]
+    pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+        instructions::unchecked_close_proposal(ctx)
+    }
+
     /// Revoke role.
     #[access_control(CpiAuthenticate::only(&ctx, roles::TIMELOCKED_ADMIN))]
     pub fn revoke_role(ctx: Context<RevokeRole>, role: String) -> Result<()> {
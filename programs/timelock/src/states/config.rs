@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 use gmsol_store::{states::Seed, CoreError};
 
+/// Max number of delay overrides.
+///
+/// The overrides are stored in the reserved space of the config,
+/// which has room for this many of them.
+pub const MAX_DELAY_OVERRIDES: usize = 5;
+
 /// Timelock Config.
 #[account(zero_copy)]
 pub struct TimelockConfig {
@@ -10,7 +16,8 @@ pub struct TimelockConfig {
     delay: u32,
     padding_1: [u8; 4],
     pub(crate) store: Pubkey,
-    reserved: [u8; 256],
+    delay_overrides: [DelayOverride; MAX_DELAY_OVERRIDES],
+    reserved: [u8; 16],
 }
 
 impl Seed for TimelockConfig {
@@ -42,4 +49,204 @@ impl TimelockConfig {
         self.delay = new_delay;
         Ok(new_delay)
     }
+
+    /// Get the active delay overrides.
+    pub fn delay_overrides(&self) -> impl Iterator<Item = &DelayOverride> {
+        self.delay_overrides.iter().filter(|o| o.is_active())
+    }
+
+    /// Get the delay of the instruction with the given `program_id` and `data`.
+    ///
+    /// An override for the discriminator of the instruction takes precedence over
+    /// an override for the whole program, which takes precedence over the default delay.
+    pub fn delay_for(&self, program_id: &Pubkey, data: &[u8]) -> u32 {
+        let discriminator = data.get(..DelayOverride::DISCRIMINATOR_LEN);
+        let mut delay = None;
+        for o in self
+            .delay_overrides()
+            .filter(|o| o.program_id == *program_id)
+        {
+            match o.discriminator() {
+                Some(expected) if discriminator == Some(expected.as_slice()) => {
+                    return o.delay;
+                }
+                None => delay = Some(o.delay),
+                _ => {}
+            }
+        }
+        delay.unwrap_or(self.delay)
+    }
+
+    /// Get the delay of the instructions matching the given override key.
+    ///
+    /// Returns the delay of the override if it exists, otherwise the delay that
+    /// the instructions fall back to.
+    pub fn delay_for_override(&self, program_id: &Pubkey, discriminator: Option<&[u8; 8]>) -> u32 {
+        self.find_delay_override(program_id, discriminator)
+            .map(|idx| self.delay_overrides[idx].delay)
+            .unwrap_or_else(|| self.fallback_delay(program_id, discriminator))
+    }
+
+    /// Get the delay that the instructions matching the given override key fall back to
+    /// when the override is removed.
+    pub fn fallback_delay(&self, program_id: &Pubkey, discriminator: Option<&[u8; 8]>) -> u32 {
+        discriminator
+            .and_then(|_| self.find_delay_override(program_id, None))
+            .map(|idx| self.delay_overrides[idx].delay)
+            .unwrap_or(self.delay)
+    }
+
+    /// Set delay override.
+    ///
+    /// Returns [`CoreError::ExceedMaxLengthLimit`] if the override does not exist
+    /// and there are already [`MAX_DELAY_OVERRIDES`] overrides.
+    pub(crate) fn set_delay_override(
+        &mut self,
+        program_id: &Pubkey,
+        discriminator: Option<&[u8; 8]>,
+        delay: u32,
+    ) -> Result<()> {
+        let idx = match self.find_delay_override(program_id, discriminator) {
+            Some(idx) => idx,
+            None => self
+                .delay_overrides
+                .iter()
+                .position(|o| !o.is_active())
+                .ok_or_else(|| {
+                    msg!(
+                        "[Timelock] at most {} delay overrides can be set",
+                        MAX_DELAY_OVERRIDES
+                    );
+                    error!(CoreError::ExceedMaxLengthLimit)
+                })?,
+        };
+        let o = &mut self.delay_overrides[idx];
+        o.is_active = 1;
+        o.has_discriminator = u8::from(discriminator.is_some());
+        o.discriminator = discriminator.copied().unwrap_or_default();
+        o.program_id = *program_id;
+        o.delay = delay;
+        Ok(())
+    }
+
+    /// Remove delay override.
+    pub(crate) fn remove_delay_override(
+        &mut self,
+        program_id: &Pubkey,
+        discriminator: Option<&[u8; 8]>,
+    ) -> Result<()> {
+        let idx = self
+            .find_delay_override(program_id, discriminator)
+            .ok_or_else(|| error!(CoreError::NotFound))?;
+        self.delay_overrides[idx] = bytemuck::Zeroable::zeroed();
+        Ok(())
+    }
+
+    fn find_delay_override(
+        &self,
+        program_id: &Pubkey,
+        discriminator: Option<&[u8; 8]>,
+    ) -> Option<usize> {
+        self.delay_overrides.iter().position(|o| {
+            o.is_active() && o.program_id == *program_id && o.discriminator() == discriminator
+        })
+    }
+}
+
+/// Delay override for the instructions of a program.
+#[zero_copy]
+pub struct DelayOverride {
+    is_active: u8,
+    has_discriminator: u8,
+    padding_0: [u8; 2],
+    /// Delay.
+    pub delay: u32,
+    discriminator: [u8; 8],
+    /// Program ID.
+    pub program_id: Pubkey,
+}
+
+impl DelayOverride {
+    const DISCRIMINATOR_LEN: usize = 8;
+
+    /// Returns whether the override is active.
+    pub fn is_active(&self) -> bool {
+        self.is_active != 0
+    }
+
+    /// Get the discriminator of the instruction to override.
+    ///
+    /// Returns `None` if the override applies to the whole program.
+    pub fn discriminator(&self) -> Option<&[u8; 8]> {
+        (self.has_discriminator != 0).then_some(&self.discriminator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_overrides() {
+        let mut config: TimelockConfig = bytemuck::Zeroable::zeroed();
+        config.init(0, 86_400, Pubkey::new_unique());
+
+        let program_id = Pubkey::new_unique();
+        let other_program_id = Pubkey::new_unique();
+        let toggle = [1; 8];
+        let transfer = [2; 8];
+        let data = |discriminator: &[u8; 8]| [discriminator.as_slice(), &[0; 4]].concat();
+
+        assert_eq!(config.delay_for(&program_id, &data(&toggle)), 86_400);
+
+        config
+            .set_delay_override(&program_id, Some(&toggle), 600)
+            .unwrap();
+        config.set_delay_override(&program_id, None, 3_600).unwrap();
+        assert_eq!(config.delay_for(&program_id, &data(&toggle)), 600);
+        assert_eq!(config.delay_for(&program_id, &data(&transfer)), 3_600);
+        assert_eq!(config.delay_for(&program_id, &[]), 3_600);
+        assert_eq!(config.delay_for(&other_program_id, &data(&toggle)), 86_400);
+
+        config
+            .set_delay_override(&program_id, Some(&toggle), 60)
+            .unwrap();
+        assert_eq!(config.delay_overrides().count(), 2);
+        assert_eq!(config.delay_for(&program_id, &data(&toggle)), 60);
+
+        config
+            .set_delay_override(&program_id, Some(&transfer), 86_400 * 3)
+            .unwrap();
+        config
+            .set_delay_override(&other_program_id, None, 0)
+            .unwrap();
+        for _ in config.delay_overrides().count()..MAX_DELAY_OVERRIDES {
+            config
+                .set_delay_override(&Pubkey::new_unique(), None, 600)
+                .unwrap();
+        }
+        assert_eq!(config.delay_overrides().count(), MAX_DELAY_OVERRIDES);
+        assert!(config
+            .set_delay_override(&other_program_id, Some(&toggle), 0)
+            .is_err());
+        // Updating an existing override is allowed when the overrides are full.
+        config
+            .set_delay_override(&other_program_id, None, 0)
+            .unwrap();
+
+        assert_eq!(config.delay_for_override(&program_id, Some(&toggle)), 60);
+        assert_eq!(config.fallback_delay(&program_id, Some(&toggle)), 3_600);
+        assert_eq!(config.fallback_delay(&program_id, None), 86_400);
+        assert_eq!(
+            config.delay_for_override(&other_program_id, Some(&transfer)),
+            0
+        );
+
+        config.remove_delay_override(&program_id, None).unwrap();
+        assert!(config.remove_delay_override(&program_id, None).is_err());
+        assert_eq!(config.delay_for(&program_id, &data(&transfer)), 86_400 * 3);
+        assert_eq!(config.delay_for(&program_id, &[]), 86_400);
+        assert_eq!(config.delay_for(&other_program_id, &[]), 0);
+        assert_eq!(config.fallback_delay(&program_id, Some(&toggle)), 86_400);
+    }
 }
@@ -13,7 +13,9 @@ pub struct Executor {
     padding: [u8; 13],
     pub(crate) store: Pubkey,
     role_name: [u8; MAX_ROLE_NAME_LEN],
-    reserved: [u8; 256],
+    min_delay: u32,
+    padding_1: [u8; 12],
+    reserved: [u8; 240],
 }
 
 impl Executor {
@@ -25,6 +27,15 @@ impl Executor {
         bytes_to_fixed_str(&self.role_name)
     }
 
+    /// Get the min delay of the instructions executed by this executor.
+    pub fn min_delay(&self) -> u32 {
+        self.min_delay
+    }
+
+    pub(crate) fn set_min_delay(&mut self, min_delay: u32) {
+        self.min_delay = min_delay;
+    }
+
     pub(crate) fn try_init(
         &mut self,
         bump: u8,
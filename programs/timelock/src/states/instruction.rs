@@ -10,7 +10,7 @@ use gmsol_utils::{
     InitSpace,
 };
 
-use crate::states::create_executor_wallet_pda;
+use crate::states::{create_executor_wallet_pda, Executor, TimelockConfig};
 
 pub use gmsol_utils::instruction::{InstructionAccess, InstructionAccount, InstructionAccountFlag};
 
@@ -34,7 +34,8 @@ pub struct InstructionHeader {
     padding_1: [u8; 12],
     pub(crate) rent_receiver: Pubkey,
     approver: Pubkey,
-    reserved: [u8; 64],
+    proposal: Pubkey,
+    reserved: [u8; 32],
 }
 
 impl InstructionHeader {
@@ -48,6 +49,8 @@ impl InstructionHeader {
     /// Approve.
     pub(crate) fn approve(&mut self, approver: Pubkey) -> Result<()> {
         require!(!self.is_approved(), CoreError::PreconditionsAreNotMet);
+        // Instructions of a proposal must be approved with the proposal.
+        require!(self.proposal().is_none(), CoreError::PreconditionsAreNotMet);
         require_keys_eq!(
             self.approver,
             DEFAULT_PUBKEY,
@@ -80,12 +83,28 @@ impl InstructionHeader {
         optional_address(&self.approver)
     }
 
+    /// Get the delay of the instruction.
+    ///
+    /// The delay is resolved from the delay overrides of the `config`,
+    /// and is at least the min delay of the `executor`.
+    pub fn delay(&self, config: &TimelockConfig, executor: &Executor, data: &[u8]) -> u32 {
+        config
+            .delay_for(&self.program_id, data)
+            .max(executor.min_delay())
+    }
+
     /// Return whether the instruction is executable.
-    pub fn is_executable(&self, delay: u32) -> Result<bool> {
+    pub fn is_executable(
+        &self,
+        config: &TimelockConfig,
+        executor: &Executor,
+        data: &[u8],
+    ) -> Result<bool> {
         let now = Clock::get()?.unix_timestamp;
         let Some(approved_at) = self.approved_at() else {
             return Ok(false);
         };
+        let delay = self.delay(config, executor, data);
         let executable_at = approved_at.saturating_add_unsigned(delay as u64);
         Ok(now >= executable_at)
     }
@@ -110,6 +129,20 @@ impl InstructionHeader {
     pub fn rent_receiver(&self) -> &Pubkey {
         &self.rent_receiver
     }
+
+    /// Get the proposal that the instruction belongs to.
+    pub fn proposal(&self) -> Option<&Pubkey> {
+        optional_address(&self.proposal)
+    }
+
+    /// Add the instruction to the proposal.
+    pub(crate) fn set_proposal(&mut self, proposal: Pubkey) -> Result<()> {
+        require!(!self.is_approved(), CoreError::PreconditionsAreNotMet);
+        require!(self.proposal().is_none(), CoreError::PreconditionsAreNotMet);
+        require_keys_neq!(proposal, DEFAULT_PUBKEY, CoreError::InvalidArgument);
+        self.proposal = proposal;
+        Ok(())
+    }
 }
 
 gmsol_utils::flags!(InstructionFlag, MAX_IX_FLAGS, u8);
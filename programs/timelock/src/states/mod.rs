@@ -7,6 +7,10 @@ pub mod executor;
 /// Timelock Config.
 pub mod config;
 
+/// Proposal.
+pub mod proposal;
+
 pub use config::*;
 pub use executor::*;
 pub use instruction::*;
+pub use proposal::*;
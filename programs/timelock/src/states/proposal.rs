@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use gmsol_store::{
+    utils::pubkey::{optional_address, DEFAULT_PUBKEY},
+    CoreError,
+};
+use gmsol_utils::{
+    instruction::{ProposalFlag, MAX_PROPOSAL_FLAGS, MAX_PROPOSAL_INSTRUCTIONS},
+    InitSpace,
+};
+
+/// Proposal.
+///
+/// A group of instruction buffers that are approved and executed atomically in order.
+#[account(zero_copy)]
+pub struct Proposal {
+    version: u8,
+    flags: ProposalFlagContainer,
+    num_instructions: u8,
+    padding_0: [u8; 5],
+    approved_at: i64,
+    pub(crate) store: Pubkey,
+    pub(crate) executor: Pubkey,
+    pub(crate) rent_receiver: Pubkey,
+    approver: Pubkey,
+    depends_on: Pubkey,
+    instructions: [Pubkey; MAX_PROPOSAL_INSTRUCTIONS],
+    num_dependents: u32,
+    padding_1: [u8; 4],
+    reserved: [u8; 120],
+}
+
+impl InitSpace for Proposal {
+    const INIT_SPACE: usize = std::mem::size_of::<Self>();
+}
+
+impl Proposal {
+    pub(crate) fn try_init(
+        &mut self,
+        store: Pubkey,
+        executor: Pubkey,
+        rent_receiver: Pubkey,
+        depends_on: Option<Pubkey>,
+        instructions: &[Pubkey],
+    ) -> Result<()> {
+        require!(!instructions.is_empty(), CoreError::InvalidArgument);
+        require_gte!(
+            MAX_PROPOSAL_INSTRUCTIONS,
+            instructions.len(),
+            CoreError::ExceedMaxLengthLimit
+        );
+        if let Some(depends_on) = depends_on {
+            require_keys_neq!(depends_on, DEFAULT_PUBKEY, CoreError::InvalidArgument);
+        }
+
+        self.store = store;
+        self.executor = executor;
+        self.rent_receiver = rent_receiver;
+        self.depends_on = depends_on.unwrap_or(DEFAULT_PUBKEY);
+        self.num_instructions = instructions.len() as u8;
+        self.instructions[..instructions.len()].copy_from_slice(instructions);
+        Ok(())
+    }
+
+    /// Approve.
+    pub(crate) fn approve(&mut self, approver: Pubkey) -> Result<()> {
+        require!(!self.is_approved(), CoreError::PreconditionsAreNotMet);
+        require_keys_neq!(approver, DEFAULT_PUBKEY, CoreError::InvalidArgument);
+
+        let clock = Clock::get()?;
+
+        self.flags.set_flag(ProposalFlag::Approved, true);
+        self.approved_at = clock.unix_timestamp;
+        self.approver = approver;
+
+        Ok(())
+    }
+
+    /// Add a pending proposal depending on this one.
+    pub(crate) fn add_dependent(&mut self) -> Result<()> {
+        self.num_dependents = self
+            .num_dependents
+            .checked_add(1)
+            .ok_or_else(|| error!(CoreError::ValueOverflow))?;
+        Ok(())
+    }
+
+    /// Remove a pending proposal depending on this one,
+    /// when it has been executed or cancelled.
+    pub(crate) fn remove_dependent(&mut self) -> Result<()> {
+        self.num_dependents = self
+            .num_dependents
+            .checked_sub(1)
+            .ok_or_else(|| error!(CoreError::Internal))?;
+        Ok(())
+    }
+
+    pub(crate) fn mark_as_executed(&mut self) -> Result<()> {
+        require!(!self.is_executed(), CoreError::PreconditionsAreNotMet);
+        self.flags.set_flag(ProposalFlag::Executed, true);
+        Ok(())
+    }
+
+    /// Returns whether the proposal is approved.
+    pub fn is_approved(&self) -> bool {
+        self.flags.get_flag(ProposalFlag::Approved)
+    }
+
+    /// Returns whether the proposal is executed.
+    pub fn is_executed(&self) -> bool {
+        self.flags.get_flag(ProposalFlag::Executed)
+    }
+
+    /// Get the approved timestamp.
+    pub fn approved_at(&self) -> Option<i64> {
+        self.is_approved().then_some(self.approved_at)
+    }
+
+    /// Get approver.
+    pub fn approver(&self) -> Option<&Pubkey> {
+        optional_address(&self.approver)
+    }
+
+    /// Get the proposal that must be executed before this one.
+    pub fn depends_on(&self) -> Option<&Pubkey> {
+        optional_address(&self.depends_on)
+    }
+
+    /// Get the number of pending proposals depending on this one.
+    ///
+    /// A proposal cannot be cancelled or closed while it has pending dependents.
+    pub fn num_dependents(&self) -> u32 {
+        self.num_dependents
+    }
+
+    /// Get the instruction buffers, in execution order.
+    pub fn instructions(&self) -> &[Pubkey] {
+        &self.instructions[..usize::from(self.num_instructions)]
+    }
+
+    /// Get executor.
+    pub fn executor(&self) -> &Pubkey {
+        &self.executor
+    }
+
+    /// Get rent receiver.
+    pub fn rent_receiver(&self) -> &Pubkey {
+        &self.rent_receiver
+    }
+
+    /// Return whether the proposal is executable with the given delay.
+    pub fn is_executable(&self, delay: u32) -> Result<bool> {
+        let now = Clock::get()?.unix_timestamp;
+        let Some(approved_at) = self.approved_at() else {
+            return Ok(false);
+        };
+        let executable_at = approved_at.saturating_add_unsigned(delay as u64);
+        Ok(now >= executable_at)
+    }
+}
+
+gmsol_utils::flags!(ProposalFlag, MAX_PROPOSAL_FLAGS, u8);
@@ -3,3 +3,7 @@ use anchor_lang::constant;
 /// Receiver Seed.
 #[constant]
 pub const RECEIVER_SEED: &[u8] = b"receiver";
+
+/// Buyback Owner Seed.
+#[constant]
+pub const BUYBACK_OWNER_SEED: &[u8] = b"buyback_owner";
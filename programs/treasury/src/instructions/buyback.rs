@@ -0,0 +1,718 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+use gmsol_store::{
+    cpi::{
+        accounts::{ClearAllPrices, CreateOrderV2, PrepareUser, SetPricesFromPriceFeed},
+        clear_all_prices, create_order_v2, prepare_user, set_prices_from_price_feed,
+    },
+    program::GmsolStore,
+    states::{
+        common::action::Action, Chainlink, HasMarketMeta, Market, NonceBytes, Oracle, Order, Seed,
+        UserHeader,
+    },
+    utils::{CpiAuthentication, WithStore},
+    CoreError,
+};
+use gmsol_utils::InitSpace;
+
+use crate::{
+    constants,
+    states::{
+        buyback::BuybackOwnerSigner, config::ReceiverSigner, BuybackPlan, Config,
+        TreasuryVaultConfig,
+    },
+};
+
+use super::swap::swap_order_params;
+
+/// The accounts definition for [`create_buyback_plan`](crate::gmsol_treasury::create_buyback_plan).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..N. `[]` N market accounts of the swap path, where the last one is the
+///     market of the swap orders.
+#[derive(Accounts)]
+pub struct CreateBuybackPlan<'info> {
+    /// Authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Config.
+    #[account(
+        has_one = store,
+        // Only allow using the authorized treasury vault config.
+        constraint = config.load()?.treasury_vault_config() == Some(&treasury_vault_config.key()) @ CoreError::InvalidArgument,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// Treasury Config.
+    #[account(
+        has_one = config,
+        constraint = !treasury_vault_config.load()?.is_deposit_allowed(&swap_in_token.key()).unwrap_or(false) @ CoreError::InvalidArgument,
+        constraint = treasury_vault_config.load()?.is_deposit_allowed(&swap_out_token.key())? @ CoreError::InvalidArgument,
+    )]
+    pub treasury_vault_config: AccountLoader<'info, TreasuryVaultConfig>,
+    /// Swap in token.
+    pub swap_in_token: Account<'info, Mint>,
+    /// Swap out token.
+    #[account(constraint = swap_in_token.key() != swap_out_token.key() @ CoreError::InvalidArgument)]
+    pub swap_out_token: Account<'info, Mint>,
+    /// The buyback plan account to create.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BuybackPlan::INIT_SPACE,
+        seeds = [
+            BuybackPlan::SEED,
+            config.key().as_ref(),
+            swap_in_token.key().as_ref(),
+            swap_out_token.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub buyback_plan: AccountLoader<'info, BuybackPlan>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a buyback plan.
+/// # CHECK
+/// Only [`TREASURY_ADMIN`](crate::roles::TREASURY_ADMIN) can use.
+pub(crate) fn unchecked_create_buyback_plan<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateBuybackPlan<'info>>,
+    budget: u64,
+    slice: u64,
+    interval: u32,
+    max_slippage: u128,
+) -> Result<()> {
+    let swap_path = ctx.accounts.validate_swap_path(ctx.remaining_accounts)?;
+    let buyback_plan = ctx.accounts.buyback_plan.key();
+    let (_, owner_bump) = Pubkey::find_program_address(
+        &[constants::BUYBACK_OWNER_SEED, buyback_plan.as_ref()],
+        &crate::ID,
+    );
+    ctx.accounts.buyback_plan.load_init()?.try_init(
+        ctx.bumps.buyback_plan,
+        owner_bump,
+        &ctx.accounts.config.key(),
+        &ctx.accounts.swap_in_token.key(),
+        &ctx.accounts.swap_out_token.key(),
+        &swap_path,
+        budget,
+        slice,
+        interval,
+        max_slippage,
+    )?;
+    msg!(
+        "[Treasury] created a buyback plan spending {} in slices of {} every {}s",
+        budget,
+        slice,
+        interval
+    );
+    Ok(())
+}
+
+impl<'info> CreateBuybackPlan<'info> {
+    /// Validate that the given markets form a swap path from the swap-in token
+    /// to the swap-out token, and return their addresses.
+    fn validate_swap_path(&self, markets: &'info [AccountInfo<'info>]) -> Result<Vec<Pubkey>> {
+        let store = self.store.key();
+        let mut current = self.swap_in_token.key();
+        for info in markets {
+            let market = AccountLoader::<Market>::try_from(info)?;
+            let market = market.load()?;
+            market.validate(&store)?;
+            current = *market
+                .market_meta()
+                .opposite_token(&current)
+                .map_err(|_| error!(CoreError::InvalidSwapPath))?;
+        }
+        require_keys_eq!(
+            current,
+            self.swap_out_token.key(),
+            CoreError::InvalidSwapPath
+        );
+        Ok(markets.iter().map(|info| info.key()).collect())
+    }
+}
+
+impl<'info> WithStore<'info> for CreateBuybackPlan<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for CreateBuybackPlan<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`close_buyback_plan`](crate::gmsol_treasury::close_buyback_plan).
+#[derive(Accounts)]
+pub struct CloseBuybackPlan<'info> {
+    /// Authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Config.
+    #[account(has_one = store)]
+    pub config: AccountLoader<'info, Config>,
+    /// The buyback plan account to close.
+    #[account(
+        mut,
+        has_one = config,
+        close = authority,
+        constraint = buyback_plan.load()?.pending() == 0 @ CoreError::PreconditionsAreNotMet,
+    )]
+    pub buyback_plan: AccountLoader<'info, BuybackPlan>,
+    /// The buyback owner.
+    #[account(
+        mut,
+        seeds = [constants::BUYBACK_OWNER_SEED, buyback_plan.key().as_ref()],
+        bump = buyback_plan.load()?.owner_bump,
+    )]
+    pub buyback_owner: SystemAccount<'info>,
+    /// Receiver.
+    #[account(
+        mut,
+        seeds = [constants::RECEIVER_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub receiver: SystemAccount<'info>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+}
+
+/// Close a buyback plan.
+///
+/// The lamports left in the buyback owner are returned to the receiver.
+/// # CHECK
+/// Only [`TREASURY_ADMIN`](crate::roles::TREASURY_ADMIN) can use.
+pub(crate) fn unchecked_close_buyback_plan(ctx: Context<CloseBuybackPlan>) -> Result<()> {
+    let lamports = ctx.accounts.buyback_owner.lamports();
+    if lamports != 0 {
+        let signer = BuybackOwnerSigner::new(
+            ctx.accounts.buyback_plan.key(),
+            ctx.accounts.buyback_plan.load()?.owner_bump,
+        );
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyback_owner.to_account_info(),
+                    to: ctx.accounts.receiver.to_account_info(),
+                },
+            )
+            .with_signer(&[&signer.as_seeds()]),
+            lamports,
+        )?;
+    }
+
+    let plan = ctx.accounts.buyback_plan.load()?;
+    msg!(
+        "[Treasury] closed the buyback plan with {} of {} spent",
+        plan.spent(),
+        plan.budget()
+    );
+    Ok(())
+}
+
+impl<'info> WithStore<'info> for CloseBuybackPlan<'info> {
+    fn store_program(&self) -> AccountInfo<'info> {
+        self.store_program.to_account_info()
+    }
+
+    fn store(&self) -> AccountInfo<'info> {
+        self.store.to_account_info()
+    }
+}
+
+impl<'info> CpiAuthentication<'info> for CloseBuybackPlan<'info> {
+    fn authority(&self) -> AccountInfo<'info> {
+        self.authority.to_account_info()
+    }
+
+    fn on_error(&self) -> Result<()> {
+        err!(CoreError::PermissionDenied)
+    }
+}
+
+/// The accounts definition for [`execute_buyback_plan`](crate::gmsol_treasury::execute_buyback_plan).
+///
+/// Remaining accounts expected by this instruction:
+///
+///   - 0..M. `[]` M feed accounts of the swap in token and the swap out token, sorted by token addresses.
+///   - M..(M+N). `[]` N market accounts of the swap path of the plan.
+#[derive(Accounts)]
+pub struct ExecuteBuybackPlan<'info> {
+    /// Authority, which pays for the swap-in token vault of the buyback owner
+    /// if it does not exist.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Store.
+    /// CHECK: check by CPI.
+    pub store: UncheckedAccount<'info>,
+    /// Config.
+    #[account(
+        has_one = store,
+        // Only allow using the authorized treasury vault config.
+        constraint = config.load()?.treasury_vault_config() == Some(&treasury_vault_config.key()) @ CoreError::InvalidArgument,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// Treasury Config.
+    #[account(
+        has_one = config,
+        constraint = !treasury_vault_config.load()?.is_deposit_allowed(&swap_in_token.key()).unwrap_or(false) @ CoreError::InvalidArgument,
+        constraint = treasury_vault_config.load()?.is_deposit_allowed(&swap_out_token.key())? @ CoreError::InvalidArgument,
+    )]
+    pub treasury_vault_config: AccountLoader<'info, TreasuryVaultConfig>,
+    /// The buyback plan.
+    #[account(
+        mut,
+        has_one = config,
+        has_one = swap_in_token,
+        has_one = swap_out_token,
+    )]
+    pub buyback_plan: AccountLoader<'info, BuybackPlan>,
+    /// Swap in token.
+    pub swap_in_token: Account<'info, Mint>,
+    /// Swap out token.
+    pub swap_out_token: Account<'info, Mint>,
+    /// Swap in token receiver vault.
+    #[account(
+        mut,
+        associated_token::authority = receiver,
+        associated_token::mint = swap_in_token,
+    )]
+    pub swap_in_token_receiver_vault: Account<'info, TokenAccount>,
+    /// Market.
+    /// CHECK: check by CPI.
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// Receiver of the swap output.
+    #[account(
+        mut,
+        seeds = [constants::RECEIVER_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub receiver: SystemAccount<'info>,
+    /// Swap order owner (the buyback owner).
+    #[account(
+        mut,
+        seeds = [constants::BUYBACK_OWNER_SEED, buyback_plan.key().as_ref()],
+        bump = buyback_plan.load()?.owner_bump,
+    )]
+    pub buyback_owner: SystemAccount<'info>,
+    /// Swap in token vault of the buyback owner.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::authority = buyback_owner,
+        associated_token::mint = swap_in_token,
+    )]
+    pub swap_in_token_owner_vault: Account<'info, TokenAccount>,
+    /// The user account for `buyback_owner`.
+    /// CHECK: check by CPI.
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+    /// The escrow account for swap in token.
+    /// CHECK: check by CPI.
+    #[account(mut)]
+    pub swap_in_token_escrow: UncheckedAccount<'info>,
+    /// The escrow account for swap out token.
+    /// CHECK: check by CPI.
+    #[account(mut)]
+    pub swap_out_token_escrow: UncheckedAccount<'info>,
+    /// The order account.
+    /// CHECK: check by CPI.
+    #[account(mut)]
+    pub order: UncheckedAccount<'info>,
+    /// Token map.
+    /// CHECK: check by CPI.
+    pub token_map: UncheckedAccount<'info>,
+    /// Oracle.
+    /// CHECK: the permissions should be checked by the CPI.
+    #[account(mut)]
+    pub oracle: AccountLoader<'info, Oracle>,
+    /// Event authority.
+    /// CHECK: check by CPI.
+    pub event_authority: UncheckedAccount<'info>,
+    /// Store program.
+    pub store_program: Program<'info, GmsolStore>,
+    /// The token program.
+    pub token_program: Program<'info, Token>,
+    /// Associated token program.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// The system program.
+    pub system_program: Program<'info, System>,
+    /// Chainlink program.
+    pub chainlink_program: Option<Program<'info, Chainlink>>,
+}
+
+impl<'info> ExecuteBuybackPlan<'info> {
+    /// Create the swap order of the next slice of the buyback plan.
+    ///
+    /// The swap order is created along the swap path of the plan, with the min
+    /// swap out amount implied by the oracle prices minus the max slippage of the plan.
+    pub(crate) fn invoke(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteBuybackPlan<'info>>,
+        nonce: NonceBytes,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let (swap_in_amount, swap_path, owner_bump) = {
+            let plan = ctx.accounts.buyback_plan.load()?;
+            (
+                plan.next_slice(now)?,
+                plan.swap_path().to_vec(),
+                plan.owner_bump,
+            )
+        };
+
+        let num_feeds = ctx
+            .remaining_accounts
+            .len()
+            .checked_sub(swap_path.len())
+            .ok_or_else(|| error!(ErrorCode::AccountNotEnoughKeys))?;
+        let (feeds, swap_path_accounts) = ctx.remaining_accounts.split_at(num_feeds);
+        require!(
+            swap_path_accounts
+                .iter()
+                .map(|info| info.key)
+                .eq(swap_path.iter()),
+            CoreError::InvalidSwapPath
+        );
+        require_keys_eq!(
+            ctx.accounts.market.key(),
+            *swap_path
+                .last()
+                .ok_or_else(|| error!(CoreError::Internal))?,
+            CoreError::InvalidSwapPath
+        );
+
+        let min_swap_out_amount = ctx.accounts.min_swap_out_amount(feeds, swap_in_amount)?;
+
+        let receiver_signer = ReceiverSigner::new(ctx.accounts.config.key(), ctx.bumps.receiver);
+        let owner_signer = BuybackOwnerSigner::new(ctx.accounts.buyback_plan.key(), owner_bump);
+
+        ctx.accounts
+            .transfer_swap_in_tokens(&receiver_signer, swap_in_amount)?;
+        ctx.accounts.fund_buyback_owner(&receiver_signer)?;
+
+        // Prepare user.
+        let cpi_ctx = ctx.accounts.prepare_user_ctx();
+        prepare_user(cpi_ctx.with_signer(&[&owner_signer.as_seeds()]))?;
+
+        // Create order.
+        let cpi_ctx = ctx.accounts.create_order_ctx();
+        let params = swap_order_params(
+            swap_path.len() as u8,
+            swap_in_amount,
+            Some(min_swap_out_amount),
+        );
+        create_order_v2(
+            cpi_ctx
+                .with_signer(&[&owner_signer.as_seeds()])
+                .with_remaining_accounts(swap_path_accounts.to_vec()),
+            nonce,
+            params,
+            None,
+        )?;
+
+        let order = ctx.accounts.order.key();
+        let mut plan = ctx.accounts.buyback_plan.load_mut()?;
+        plan.record_slice(swap_in_amount, now, &order)?;
+
+        msg!(
+            "[Treasury] buyback slice {}: swapping {} with a min output of {}, {} of {} spent",
+            plan.num_slices(),
+            swap_in_amount,
+            min_swap_out_amount,
+            plan.spent(),
+            plan.budget(),
+        );
+
+        Ok(())
+    }
+
+    /// Transfer the swap-in tokens of the slice from the receiver to the buyback owner.
+    fn transfer_swap_in_tokens(&self, signer: &ReceiverSigner, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.swap_in_token_receiver_vault.to_account_info(),
+                    to: self.swap_in_token_owner_vault.to_account_info(),
+                    authority: self.receiver.to_account_info(),
+                },
+            )
+            .with_signer(&[&signer.as_seeds()]),
+            amount,
+        )
+    }
+
+    /// Fund the buyback owner from the receiver with the lamports required to create
+    /// the swap order.
+    ///
+    /// The rent and the unused execution lamports of a closed order are returned to the
+    /// buyback owner, so only the shortfall is transferred.
+    fn fund_buyback_owner(&self, signer: &ReceiverSigner) -> Result<()> {
+        let rent = Rent::get()?;
+        let mut required = rent
+            .minimum_balance(0)
+            .saturating_add(rent.minimum_balance(8 + Order::INIT_SPACE))
+            .saturating_add(Order::MIN_EXECUTION_LAMPORTS);
+        if self.user.data_is_empty() {
+            required = required.saturating_add(rent.minimum_balance(8 + UserHeader::space(0)));
+        }
+        let lamports = required.saturating_sub(self.buyback_owner.lamports());
+        if lamports != 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.receiver.to_account_info(),
+                        to: self.buyback_owner.to_account_info(),
+                    },
+                )
+                .with_signer(&[&signer.as_seeds()]),
+                lamports,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn min_swap_out_amount(
+        &self,
+        feeds: &'info [AccountInfo<'info>],
+        swap_in_amount: u64,
+    ) -> Result<u64> {
+        let signer = self.config.load()?.signer();
+
+        let swap_in_token = self.swap_in_token.key();
+        let swap_out_token = self.swap_out_token.key();
+        let tokens = BTreeSet::from([swap_in_token, swap_out_token]);
+
+        // Set prices.
+        let ctx = self.set_prices_from_price_feed_ctx();
+        set_prices_from_price_feed(
+            ctx.with_signer(&[&signer.as_seeds()])
+                .with_remaining_accounts(feeds.to_vec()),
+            tokens.into_iter().collect(),
+        )?;
+
+        let (swap_in_token_price, swap_out_token_price) = {
+            let oracle = self.oracle.load()?;
+            (
+                oracle.get_primary_price(&swap_in_token, false)?.min,
+                oracle.get_primary_price(&swap_out_token, false)?.max,
+            )
+        };
+
+        // Clear prices.
+        let ctx = self.clear_all_prices_ctx();
+        clear_all_prices(ctx.with_signer(&[&signer.as_seeds()]))?;
+
+        self.buyback_plan.load()?.min_swap_out_amount(
+            swap_in_amount,
+            swap_in_token_price,
+            swap_out_token_price,
+        )
+    }
+
+    fn set_prices_from_price_feed_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, SetPricesFromPriceFeed<'info>> {
+        CpiContext::new(
+            self.store_program.to_account_info(),
+            SetPricesFromPriceFeed {
+                authority: self.config.to_account_info(),
+                store: self.store.to_account_info(),
+                oracle: self.oracle.to_account_info(),
+                token_map: self.token_map.to_account_info(),
+                chainlink_program: self.chainlink_program.as_ref().map(|a| a.to_account_info()),
+            },
+        )
+    }
+
+    fn clear_all_prices_ctx(&self) -> CpiContext<'_, '_, '_, 'info, ClearAllPrices<'info>> {
+        CpiContext::new(
+            self.store_program.to_account_info(),
+            ClearAllPrices {
+                authority: self.config.to_account_info(),
+                store: self.store.to_account_info(),
+                oracle: self.oracle.to_account_info(),
+            },
+        )
+    }
+
+    fn prepare_user_ctx(&self) -> CpiContext<'_, '_, '_, 'info, PrepareUser<'info>> {
+        CpiContext::new(
+            self.store_program.to_account_info(),
+            PrepareUser {
+                owner: self.buyback_owner.to_account_info(),
+                store: self.store.to_account_info(),
+                user: self.user.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+        )
+    }
+
+    fn create_order_ctx(&self) -> CpiContext<'_, '_, '_, 'info, CreateOrderV2<'info>> {
+        CpiContext::new(
+            self.store_program.to_account_info(),
+            CreateOrderV2 {
+                owner: self.buyback_owner.to_account_info(),
+                receiver: self.receiver.to_account_info(),
+                store: self.store.to_account_info(),
+                market: self.market.to_account_info(),
+                user: self.user.to_account_info(),
+                order: self.order.to_account_info(),
+                position: None,
+                initial_collateral_token: Some(self.swap_in_token.to_account_info()),
+                final_output_token: self.swap_out_token.to_account_info(),
+                long_token: None,
+                short_token: None,
+                initial_collateral_token_escrow: Some(self.swap_in_token_escrow.to_account_info()),
+                final_output_token_escrow: Some(self.swap_out_token_escrow.to_account_info()),
+                long_token_escrow: None,
+                short_token_escrow: None,
+                initial_collateral_token_source: Some(
+                    self.swap_in_token_owner_vault.to_account_info(),
+                ),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                associated_token_program: self.associated_token_program.to_account_info(),
+                callback_authority: None,
+                callback_program: None,
+                callback_shared_data_account: None,
+                callback_partitioned_data_account: None,
+                callback_program_config: None,
+                event_authority: self.event_authority.to_account_info(),
+                program: self.store_program.to_account_info(),
+            },
+        )
+    }
+}
+
+/// The accounts definition for [`settle_buyback_plan`](crate::gmsol_treasury::settle_buyback_plan).
+#[derive(Accounts)]
+pub struct SettleBuybackPlan<'info> {
+    /// Authority.
+    pub authority: Signer<'info>,
+    /// Config.
+    pub config: AccountLoader<'info, Config>,
+    /// The buyback plan.
+    #[account(
+        mut,
+        has_one = config,
+        has_one = swap_in_token,
+    )]
+    pub buyback_plan: AccountLoader<'info, BuybackPlan>,
+    /// Swap in token.
+    pub swap_in_token: Account<'info, Mint>,
+    /// Receiver.
+    #[account(
+        seeds = [constants::RECEIVER_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub receiver: SystemAccount<'info>,
+    /// Swap in token receiver vault.
+    #[account(
+        mut,
+        associated_token::authority = receiver,
+        associated_token::mint = swap_in_token,
+    )]
+    pub swap_in_token_receiver_vault: Account<'info, TokenAccount>,
+    /// The buyback owner.
+    #[account(
+        seeds = [constants::BUYBACK_OWNER_SEED, buyback_plan.key().as_ref()],
+        bump = buyback_plan.load()?.owner_bump,
+    )]
+    pub buyback_owner: SystemAccount<'info>,
+    /// Swap in token vault of the buyback owner.
+    #[account(
+        mut,
+        associated_token::authority = buyback_owner,
+        associated_token::mint = swap_in_token,
+    )]
+    pub swap_in_token_owner_vault: Account<'info, TokenAccount>,
+    /// The swap order of the last slice.
+    /// CHECK: only used to check that the order has been closed.
+    #[account(
+        constraint = buyback_plan.load()?.last_order() == Some(order.key) @ CoreError::InvalidArgument,
+    )]
+    pub order: UncheckedAccount<'info>,
+    /// The token program.
+    pub token_program: Program<'info, Token>,
+}
+
+impl SettleBuybackPlan<'_> {
+    /// Settle the last slice of the buyback plan once its swap order has been closed.
+    ///
+    /// The swap-in tokens refunded to the buyback owner are returned to the receiver,
+    /// and only the swapped amount is charged to the budget.
+    pub(crate) fn invoke(ctx: Context<Self>) -> Result<()> {
+        if !ctx.accounts.order.data_is_empty() {
+            msg!("[Treasury] the swap order of the last slice has not been closed");
+            return err!(CoreError::PreconditionsAreNotMet);
+        }
+
+        let refunded = ctx.accounts.swap_in_token_owner_vault.amount;
+        if refunded != 0 {
+            let signer = BuybackOwnerSigner::new(
+                ctx.accounts.buyback_plan.key(),
+                ctx.accounts.buyback_plan.load()?.owner_bump,
+            );
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.swap_in_token_owner_vault.to_account_info(),
+                        to: ctx.accounts.swap_in_token_receiver_vault.to_account_info(),
+                        authority: ctx.accounts.buyback_owner.to_account_info(),
+                    },
+                )
+                .with_signer(&[&signer.as_seeds()]),
+                refunded,
+            )?;
+        }
+
+        let mut plan = ctx.accounts.buyback_plan.load_mut()?;
+        let charged = plan.settle(refunded)?;
+
+        msg!(
+            "[Treasury] settled buyback slice {}: {} swapped, {} refunded, {} of {} spent",
+            plan.num_slices(),
+            charged,
+            refunded,
+            plan.spent(),
+            plan.budget(),
+        );
+
+        Ok(())
+    }
+}
@@ -13,6 +13,10 @@ pub mod gt_bank;
 /// Instructions for swapping funds.
 pub mod swap;
 
+/// Instructions for buyback plans.
+pub mod buyback;
+
+pub use buyback::*;
 pub use config::*;
 pub use gt_bank::*;
 pub use store::*;
@@ -105,6 +105,9 @@ pub struct CreateSwapV2<'info> {
     /// Callback action stats account.
     /// CHECK: check by CPI.
     pub callback_partitioned_data_account: Option<UncheckedAccount<'info>>,
+    /// Callback program config account.
+    /// CHECK: check by CPI.
+    pub callback_program_config: Option<UncheckedAccount<'info>>,
 }
 
 impl<'info> WithStore<'info> for CreateSwapV2<'info> {
@@ -147,21 +150,7 @@ impl<'info> CreateSwapV2<'info> {
 
         // Create order.
         let cpi_ctx = ctx.accounts.create_order_ctx();
-        let params = CreateOrderParams {
-            kind: OrderKind::MarketSwap,
-            decrease_position_swap_type: None,
-            execution_lamports: Order::MIN_EXECUTION_LAMPORTS,
-            swap_path_length,
-            initial_collateral_delta_amount: swap_in_amount,
-            size_delta_value: 0,
-            is_long: true,
-            is_collateral_long: true,
-            min_output: min_swap_out_amount.map(u128::from),
-            trigger_price: None,
-            acceptable_price: None,
-            should_unwrap_native_token: false,
-            valid_from_ts: None,
-        };
+        let params = swap_order_params(swap_path_length, swap_in_amount, min_swap_out_amount);
         create_order_v2(
             cpi_ctx
                 .with_signer(&[&signer.as_seeds()])
@@ -217,6 +206,7 @@ impl<'info> CreateSwapV2<'info> {
                     .callback_partitioned_data_account
                     .as_deref()
                     .cloned(),
+                callback_program_config: self.callback_program_config.as_deref().cloned(),
                 event_authority: self.event_authority.to_account_info(),
                 program: self.store_program.to_account_info(),
             },
@@ -224,6 +214,29 @@ impl<'info> CreateSwapV2<'info> {
     }
 }
 
+/// Create the params of a market swap order.
+pub(super) fn swap_order_params(
+    swap_path_length: u8,
+    swap_in_amount: u64,
+    min_swap_out_amount: Option<u64>,
+) -> CreateOrderParams {
+    CreateOrderParams {
+        kind: OrderKind::MarketSwap,
+        decrease_position_swap_type: None,
+        execution_lamports: Order::MIN_EXECUTION_LAMPORTS,
+        swap_path_length,
+        initial_collateral_delta_amount: swap_in_amount,
+        size_delta_value: 0,
+        is_long: true,
+        is_collateral_long: true,
+        min_output: min_swap_out_amount.map(u128::from),
+        trigger_price: None,
+        acceptable_price: None,
+        should_unwrap_native_token: false,
+        valid_from_ts: None,
+    }
+}
+
 /// The accounts definition for [`cancel_swap`](crate::gmsol_treasury::cancel_swap).
 #[derive(Accounts)]
 pub struct CancelSwap<'info> {
@@ -362,6 +375,7 @@ impl<'info> CancelSwap<'info> {
                 callback_program: None,
                 callback_shared_data_account: None,
                 callback_partitioned_data_account: None,
+                delegation: None,
             },
         )
     }
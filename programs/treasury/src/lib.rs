@@ -179,6 +179,56 @@ pub mod gmsol_treasury {
     pub fn cancel_swap(ctx: Context<CancelSwap>) -> Result<()> {
         instructions::unchecked_cancel_swap(ctx)
     }
+
+    /// Create a buyback plan.
+    ///
+    /// The market accounts of the swap path must be passed as remaining accounts,
+    /// and are pinned in the plan.
+    ///
+    /// # Arguments
+    /// - `budget`: the total amount of the swap in token to spend.
+    /// - `slice`: the max amount of the swap in token to spend in a single swap.
+    /// - `interval`: the min interval (in seconds) between two swaps.
+    /// - `max_slippage`: the max slippage factor from the oracle price.
+    #[access_control(CpiAuthenticate::only(&ctx, roles::TREASURY_ADMIN))]
+    pub fn create_buyback_plan<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateBuybackPlan<'info>>,
+        budget: u64,
+        slice: u64,
+        interval: u32,
+        max_slippage: u128,
+    ) -> Result<()> {
+        instructions::unchecked_create_buyback_plan(ctx, budget, slice, interval, max_slippage)
+    }
+
+    /// Close a buyback plan.
+    ///
+    /// The last slice of the plan must have been settled.
+    #[access_control(CpiAuthenticate::only(&ctx, roles::TREASURY_ADMIN))]
+    pub fn close_buyback_plan(ctx: Context<CloseBuybackPlan>) -> Result<()> {
+        instructions::unchecked_close_buyback_plan(ctx)
+    }
+
+    /// Create the swap of the next slice of a buyback plan.
+    ///
+    /// Anyone can crank a buyback plan. The swap can only be created along the swap path
+    /// of the plan after the interval has elapsed and the last slice has been settled, and
+    /// its min output is bounded by the oracle prices and the max slippage of the plan.
+    pub fn execute_buyback_plan<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteBuybackPlan<'info>>,
+        nonce: [u8; 32],
+    ) -> Result<()> {
+        ExecuteBuybackPlan::invoke(ctx, nonce)
+    }
+
+    /// Settle the last slice of a buyback plan.
+    ///
+    /// Anyone can settle a buyback plan once the swap order of its last slice has been
+    /// closed. The refunded swap-in tokens are returned to the receiver, and only the
+    /// swapped amount is charged to the budget.
+    pub fn settle_buyback_plan(ctx: Context<SettleBuybackPlan>) -> Result<()> {
+        SettleBuybackPlan::invoke(ctx)
+    }
 }
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -0,0 +1,280 @@
+use anchor_lang::prelude::*;
+use gmsol_store::{
+    constants::{MARKET_DECIMALS, MARKET_USD_UNIT},
+    states::{common::swap::SwapActionParams, Seed},
+    utils::pubkey::optional_address,
+    CoreError,
+};
+use gmsol_utils::InitSpace;
+
+use crate::constants;
+
+/// Max length of the swap path of a buyback plan.
+pub const MAX_SWAP_PATH_LEN: usize = SwapActionParams::MAX_TOTAL_LENGTH;
+
+/// Buyback plan account.
+///
+/// A buyback plan spends a total `budget` of the swap-in token in slices of at most
+/// `slice`, creating at most one swap order per `interval` along a fixed swap path.
+///
+/// The swap orders are owned by the buyback owner PDA of the plan, so that the
+/// swap-in tokens of a cancelled order are refunded to it. A slice is only charged
+/// to the budget once its order has been closed and the plan has been settled.
+#[account(zero_copy)]
+#[cfg_attr(feature = "debug", derive(derive_more::Debug))]
+pub struct BuybackPlan {
+    version: u8,
+    pub(crate) bump: u8,
+    pub(crate) owner_bump: u8,
+    swap_path_len: u8,
+    interval: u32,
+    #[cfg_attr(feature = "debug", debug(skip))]
+    padding_1: [u8; 8],
+    pub(crate) config: Pubkey,
+    pub(crate) swap_in_token: Pubkey,
+    pub(crate) swap_out_token: Pubkey,
+    last_order: Pubkey,
+    budget: u64,
+    slice: u64,
+    spent: u64,
+    last_slice_at: i64,
+    num_slices: u64,
+    pending: u64,
+    max_slippage: u128,
+    swap_path: [Pubkey; MAX_SWAP_PATH_LEN],
+    #[cfg_attr(feature = "debug", debug(skip))]
+    reserved: [u8; 128],
+}
+
+impl Seed for BuybackPlan {
+    const SEED: &'static [u8] = b"buyback_plan";
+}
+
+impl InitSpace for BuybackPlan {
+    const INIT_SPACE: usize = std::mem::size_of::<Self>();
+}
+
+impl BuybackPlan {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn try_init(
+        &mut self,
+        bump: u8,
+        owner_bump: u8,
+        config: &Pubkey,
+        swap_in_token: &Pubkey,
+        swap_out_token: &Pubkey,
+        swap_path: &[Pubkey],
+        budget: u64,
+        slice: u64,
+        interval: u32,
+        max_slippage: u128,
+    ) -> Result<()> {
+        require_neq!(slice, 0, CoreError::InvalidArgument);
+        require_gte!(budget, slice, CoreError::InvalidArgument);
+        require_neq!(interval, 0, CoreError::InvalidArgument);
+        require_gte!(MARKET_USD_UNIT, max_slippage, CoreError::InvalidArgument);
+        require!(!swap_path.is_empty(), CoreError::InvalidSwapPathLength);
+        require_gte!(
+            MAX_SWAP_PATH_LEN,
+            swap_path.len(),
+            CoreError::InvalidSwapPathLength
+        );
+
+        self.bump = bump;
+        self.owner_bump = owner_bump;
+        self.swap_path_len = swap_path.len() as u8;
+        self.swap_path[..swap_path.len()].copy_from_slice(swap_path);
+        self.config = *config;
+        self.swap_in_token = *swap_in_token;
+        self.swap_out_token = *swap_out_token;
+        self.budget = budget;
+        self.slice = slice;
+        self.interval = interval;
+        self.max_slippage = max_slippage;
+
+        Ok(())
+    }
+
+    /// Get the swap-in token.
+    pub fn swap_in_token(&self) -> &Pubkey {
+        &self.swap_in_token
+    }
+
+    /// Get the swap-out token.
+    pub fn swap_out_token(&self) -> &Pubkey {
+        &self.swap_out_token
+    }
+
+    /// Get the total budget (in swap-in token amount).
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Get the max swap-in amount of a slice.
+    pub fn slice(&self) -> u64 {
+        self.slice
+    }
+
+    /// Get the swap-in amount that has been swapped by the settled slices.
+    pub fn spent(&self) -> u64 {
+        self.spent
+    }
+
+    /// Get the swap-in amount of the slice that has not been settled.
+    pub fn pending(&self) -> u64 {
+        self.pending
+    }
+
+    /// Get the remaining budget.
+    pub fn remaining(&self) -> u64 {
+        self.budget
+            .saturating_sub(self.spent)
+            .saturating_sub(self.pending)
+    }
+
+    /// Returns whether the budget has been used up.
+    pub fn is_completed(&self) -> bool {
+        self.remaining() == 0 && self.pending == 0
+    }
+
+    /// Get the market addresses of the swap path.
+    ///
+    /// The last market is the market of the swap orders.
+    pub fn swap_path(&self) -> &[Pubkey] {
+        &self.swap_path[..usize::from(self.swap_path_len)]
+    }
+
+    /// Get the min interval (in seconds) between two slices.
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Get the max slippage factor.
+    pub fn max_slippage(&self) -> u128 {
+        self.max_slippage
+    }
+
+    /// Get the number of executed slices.
+    pub fn num_slices(&self) -> u64 {
+        self.num_slices
+    }
+
+    /// Get the timestamp of the last slice.
+    pub fn last_slice_at(&self) -> Option<i64> {
+        (self.num_slices != 0).then_some(self.last_slice_at)
+    }
+
+    /// Get the swap order created by the last slice.
+    pub fn last_order(&self) -> Option<&Pubkey> {
+        optional_address(&self.last_order)
+    }
+
+    /// Get the swap-in amount of the next slice.
+    ///
+    /// # Errors
+    /// - The last slice must have been settled.
+    /// - The budget must not have been used up.
+    /// - The interval must have elapsed since the last slice.
+    pub(crate) fn next_slice(&self, now: i64) -> Result<u64> {
+        if self.pending != 0 {
+            msg!("[Treasury] the last slice has not been settled");
+            return err!(CoreError::PreconditionsAreNotMet);
+        }
+        let remaining = self.remaining();
+        require_neq!(remaining, 0, CoreError::PreconditionsAreNotMet);
+        if let Some(last_slice_at) = self.last_slice_at() {
+            let next_slice_at = last_slice_at.saturating_add(i64::from(self.interval));
+            if now < next_slice_at {
+                msg!(
+                    "[Treasury] the next slice is not available until {}",
+                    next_slice_at
+                );
+                return err!(CoreError::PreconditionsAreNotMet);
+            }
+        }
+        Ok(remaining.min(self.slice))
+    }
+
+    /// Get the min swap-out amount of a slice allowed by the slippage bound.
+    ///
+    /// The expected output is estimated with the min price of the swap-in token
+    /// and the max price of the swap-out token.
+    pub(crate) fn min_swap_out_amount(
+        &self,
+        swap_in_amount: u64,
+        swap_in_token_price: u128,
+        swap_out_token_price: u128,
+    ) -> Result<u64> {
+        use gmsol_model::utils::apply_factor;
+
+        require_neq!(swap_out_token_price, 0, CoreError::InvalidArgument);
+
+        let expected = u128::from(swap_in_amount)
+            .checked_mul(swap_in_token_price)
+            .ok_or_else(|| error!(CoreError::ValueOverflow))?
+            / swap_out_token_price;
+        let factor = MARKET_USD_UNIT
+            .checked_sub(self.max_slippage)
+            .ok_or_else(|| error!(CoreError::Internal))?;
+        let min_amount = apply_factor::<_, { MARKET_DECIMALS }>(&expected, &factor)
+            .ok_or_else(|| error!(CoreError::ValueOverflow))?;
+        min_amount
+            .try_into()
+            .map_err(|_| error!(CoreError::TokenAmountOverflow))
+    }
+
+    /// Record a slice whose swap order has just been created.
+    ///
+    /// The amount is kept pending until the slice is settled.
+    pub(crate) fn record_slice(&mut self, amount: u64, now: i64, order: &Pubkey) -> Result<()> {
+        require_eq!(self.pending, 0, CoreError::Internal);
+        require_gte!(self.remaining(), amount, CoreError::Internal);
+        self.pending = amount;
+        self.last_slice_at = now;
+        self.last_order = *order;
+        self.num_slices = self.num_slices.saturating_add(1);
+        Ok(())
+    }
+
+    /// Settle the last slice after its swap order has been closed.
+    ///
+    /// Only the part of the pending amount that has not been refunded is charged.
+    /// Returns the charged amount.
+    pub(crate) fn settle(&mut self, refunded: u64) -> Result<u64> {
+        require_neq!(self.pending, 0, CoreError::PreconditionsAreNotMet);
+        let charged = self.pending.saturating_sub(refunded);
+        self.spent = self
+            .spent
+            .checked_add(charged)
+            .ok_or_else(|| error!(CoreError::TokenAmountOverflow))?;
+        self.pending = 0;
+        Ok(charged)
+    }
+}
+
+/// Buyback Owner Signer.
+///
+/// The buyback owner is the owner of the swap orders of a buyback plan.
+pub struct BuybackOwnerSigner {
+    buyback_plan: Pubkey,
+    bump_bytes: [u8; 1],
+}
+
+impl BuybackOwnerSigner {
+    /// Create from buyback plan address and bump.
+    pub fn new(buyback_plan: Pubkey, bump: u8) -> Self {
+        Self {
+            buyback_plan,
+            bump_bytes: [bump],
+        }
+    }
+
+    /// As signer seeds.
+    pub fn as_seeds(&self) -> [&[u8]; 3] {
+        [
+            constants::BUYBACK_OWNER_SEED,
+            self.buyback_plan.as_ref(),
+            &self.bump_bytes,
+        ]
+    }
+}
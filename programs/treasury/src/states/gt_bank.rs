@@ -118,17 +118,17 @@ impl GtBank {
             .tokens()
             .chain(treasury_vault_config.tokens())
             .collect::<BTreeSet<_>>();
-        let records = tokens
-            .iter()
-            .map(|token| {
-                let config = map
-                    .get(token)
-                    .ok_or_else(|| error!(CoreError::UnknownToken))?;
-                TokenRecord::from_config(*token, config)
+        let mut records = Vec::with_capacity(tokens.len());
+        for token in tokens.iter() {
+            let config = map
+                .get(token)
+                .ok_or_else(|| error!(CoreError::UnknownToken))?;
+            records.extend(
+                TokenRecord::from_price_sources(*token, config)
                     .map_err(CoreError::from)
-                    .map_err(|err| error!(err))
-            })
-            .collect::<Result<Vec<_>>>()?;
+                    .map_err(|err| error!(err))?,
+            );
+        }
 
         TokensWithFeed::try_from_records(records)
             .map_err(CoreError::from)
@@ -7,6 +7,10 @@ pub mod treasury;
 /// GT.
 pub mod gt_bank;
 
+/// Buyback.
+pub mod buyback;
+
+pub use buyback::BuybackPlan;
 pub use config::Config;
 pub use gt_bank::GtBank;
 pub use treasury::TreasuryVaultConfig;
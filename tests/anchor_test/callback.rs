@@ -395,3 +395,64 @@ async fn callback() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn callback_program_allowlist() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("callback_program_allowlist");
+    let _enter = span.enter();
+
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+
+    let callback_program = client
+        .callback_program(store, &deployment.callback_program)
+        .await?
+        .expect("must exist");
+    assert_eq!(callback_program.program_id, deployment.callback_program);
+    assert_ne!(
+        callback_program.action_kinds & (1 << u8::from(ActionKind::Order)),
+        0
+    );
+
+    let market_token = deployment
+        .prepare_market(["fBTC", "fBTC", "USDG"], 1_000_007, 6_000_000_000_011, true)
+        .await?;
+
+    let long_collateral_amount = 100_005;
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, long_collateral_amount)
+        .await?;
+
+    // Creating an order with a callback to a program not in the allowlist should fail.
+    let unregistered = gmsol_liquidity_provider::ID;
+    assert!(client
+        .callback_program(store, &unregistered)
+        .await?
+        .is_none());
+    let (rpc, _) = client
+        .market_increase(
+            store,
+            market_token,
+            true,
+            long_collateral_amount,
+            true,
+            5_000 * MARKET_USD_UNIT,
+        )
+        .callback(Some(
+            Callback::builder()
+                .version(0)
+                .partitioned_data(Pubkey::new_unique())
+                .shared_data(Pubkey::new_unique())
+                .program(unregistered)
+                .build(),
+        ))
+        .build_with_address()
+        .await?;
+    rpc.send()
+        .await
+        .expect_err("should throw an error for unregistered callback program");
+
+    Ok(())
+}
@@ -1,10 +1,17 @@
+use eyre::OptionExt;
 use gmsol_competition::{
     instruction::InitializeCompetition,
-    states::{Competition, Participant, COMPETITION_SEED, PARTICIPANT_SEED},
+    states::{
+        Competition, Participant, PrizePool, ScoringMode, COMPETITION_SEED, PARTICIPANT_SEED,
+    },
     ID as COMPETITION_PROGRAM_ID,
 };
 use gmsol_sdk::{
-    builders::callback::Callback, client::ops::ExchangeOps, constants::MARKET_USD_UNIT,
+    builders::callback::Callback,
+    client::ops::ExchangeOps,
+    constants::MARKET_USD_UNIT,
+    ops::competition::{participant_score, CompetitionOps, CompetitionParams},
+    programs::gmsol_competition::types::ScoringMode as SdkScoringMode,
 };
 use rand::Rng;
 use solana_sdk::{pubkey::Pubkey, system_program};
@@ -74,6 +81,7 @@ async fn competition() -> eyre::Result<()> {
             extension_cap,
             only_count_increase,
             volume_merge_window,
+            scoring_mode: ScoringMode::Volume,
         })
         .anchor_accounts(gmsol_competition::accounts::InitializeCompetition {
             payer: client.payer(),
@@ -307,3 +315,574 @@ async fn competition() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn competition_prize_distribution() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("competition_prize_distribution");
+    let _enter = span.enter();
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+
+    let market_token = deployment
+        .prepare_market(["fBTC", "fBTC", "USDG"], 1_000_007, 6_000_000_000_011, true)
+        .await?;
+
+    // Initialize a short competition.
+    let slot = keeper.rpc().get_slot().await?;
+    let now = keeper
+        .rpc()
+        .get_block_time(slot)
+        .await
+        .unwrap_or_else(|_| OffsetDateTime::now_utc().unix_timestamp());
+    let start_time = now + 5;
+    let end_time = start_time + 30;
+    let (rpc, competition) = keeper
+        .initialize_competition(
+            &CompetitionParams::builder()
+                .start_time(start_time)
+                .end_time(end_time)
+                .volume_threshold(u128::MAX)
+                .extension_duration(1)
+                .extension_cap(1)
+                .only_count_increase(false)
+                .volume_merge_window(1)
+                .build(),
+        )
+        .swap_output(());
+    let signature = rpc.send().await?;
+    tracing::info!(%signature, %competition, "initialized competition");
+
+    // Attach and fund the prize pool.
+    let prize_mint = deployment
+        .token("USDG")
+        .ok_or_eyre("USDG not found")?
+        .address;
+    let (rpc, prize_pool) = keeper
+        .initialize_prize_pool(&competition, &prize_mint, None, vec![6_000, 3_000])
+        .swap_output(());
+    let signature = rpc.send().await?;
+    tracing::info!(%signature, %prize_pool, "initialized prize pool");
+
+    let prize = 1_000_000_000;
+    deployment
+        .mint_or_transfer_to("USDG", &keeper.payer(), prize)
+        .await?;
+    let signature = keeper
+        .fund_prize_pool(&competition, &prize_mint, None, None, prize)
+        .send()
+        .await?;
+    tracing::info!(%signature, "funded prize pool");
+
+    // Trade during the competition.
+    tokio::time::sleep(Duration::from_secs(8)).await;
+
+    let mut traders = vec![deployment.user_client(Deployment::DEFAULT_USER)?];
+    if deployment.extra_user_count > 0 {
+        traders.push(deployment.extra_user_client(0)?);
+    }
+    let collateral_amount = 100_005;
+    for (idx, client) in traders.iter().enumerate() {
+        deployment
+            .mint_or_transfer_to("fBTC", &client.payer(), collateral_amount)
+            .await?;
+        let size = (1_000 * (idx as u128 + 1)) * MARKET_USD_UNIT;
+        let (increase, order) = client
+            .market_increase(store, market_token, true, collateral_amount, true, size)
+            .competition(&competition)
+            .build_with_address()
+            .await?;
+        let signature = increase.send().await?;
+        tracing::info!(%order, %signature, "created an increase position order");
+
+        let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+        deployment
+            .execute_with_pyth(
+                builder
+                    .add_alt(deployment.common_alt().clone())
+                    .add_alt(deployment.market_alt().clone()),
+                None,
+                true,
+                true,
+            )
+            .await?;
+
+        let (decrease, order) = client
+            .market_decrease(store, market_token, true, 0, true, size)
+            .competition(&competition)
+            .build_with_address()
+            .await?;
+        let signature = decrease.send().await?;
+        tracing::info!(%order, %signature, "created a decrease position order");
+
+        let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+        deployment
+            .execute_with_pyth(
+                builder
+                    .add_alt(deployment.common_alt().clone())
+                    .add_alt(deployment.market_alt().clone()),
+                None,
+                true,
+                true,
+            )
+            .await?;
+    }
+
+    let competition_account = keeper
+        .account::<Competition>(&competition)
+        .await?
+        .expect("must exist");
+    assert_eq!(
+        competition_account.participant_count as usize,
+        traders.len()
+    );
+
+    // Wait for the competition to end.
+    loop {
+        let slot = keeper.rpc().get_slot().await?;
+        let now = keeper.rpc().get_block_time(slot).await?;
+        if now > end_time {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs((end_time - now + 1) as u64)).await;
+    }
+
+    // Participants cannot leave before the standings are final.
+    let err = traders[0].close_participant(&competition).send().await;
+    assert!(err.is_err());
+
+    // Only the authority can rank the participants.
+    let participants = keeper.competition_participants(&competition).await?;
+    let err = traders[0]
+        .append_leaderboard_page(&competition, 0, &[participants[0].0])
+        .send()
+        .await;
+    assert!(err.is_err());
+
+    // The standings cannot be finalized before all participants are ranked,
+    // so the prizes cannot be reclaimed either.
+    let err = keeper.finalize_standings(&competition).send().await;
+    assert!(err.is_err());
+    let err = keeper
+        .reclaim_prizes(&competition, &prize_mint, None, None)
+        .send()
+        .await;
+    assert!(err.is_err());
+
+    // Rank with one participant per transaction, then finalize the standings.
+    keeper
+        .rank_competition(&competition, Some(1))
+        .await?
+        .build()?
+        .send_all(false)
+        .await
+        .map_err(|(_, err)| err)?;
+
+    let standings = keeper.competition_standings(&competition).await?;
+    let participants = keeper.competition_participants(&competition).await?;
+    let competition_account = keeper
+        .account::<Competition>(&competition)
+        .await?
+        .expect("must exist");
+    assert!(competition_account.standings_finalized);
+    assert_eq!(standings.len(), traders.len());
+    for (entry, (_, participant)) in standings.iter().zip(participants.iter()) {
+        assert_eq!(entry.address, participant.trader);
+        assert_eq!(entry.volume, participant.volume);
+    }
+    assert!(standings.windows(2).all(|w| w[0].volume >= w[1].volume));
+
+    // The standings are finalized, so appending again must fail.
+    let err = keeper
+        .append_leaderboard_page(&competition, 0, &[participants[0].0])
+        .send()
+        .await;
+    assert!(err.is_err());
+
+    // Pay the prizes.
+    let mut balances = Vec::with_capacity(standings.len());
+    for entry in standings.iter() {
+        balances.push(
+            deployment
+                .get_ata_amount(&prize_mint, &entry.address)
+                .await?
+                .unwrap_or_default(),
+        );
+    }
+    keeper
+        .distribute_competition_prizes(&competition, None, None)
+        .await?
+        .build()?
+        .send_all(false)
+        .await
+        .map_err(|(_, err)| err)?;
+
+    let prize_pool_account = keeper
+        .account::<PrizePool>(&prize_pool)
+        .await?
+        .expect("must exist");
+    assert_eq!(prize_pool_account.total_amount, prize);
+    assert_eq!(
+        prize_pool_account.distributed_count as usize,
+        standings.len()
+    );
+    for (rank, (entry, before)) in standings.iter().zip(balances).enumerate() {
+        let after = deployment
+            .get_ata_amount(&prize_mint, &entry.address)
+            .await?
+            .unwrap_or_default();
+        let expected = prize * u64::from(prize_pool_account.schedule[rank]) / 10_000;
+        assert_eq!(after - before, expected);
+    }
+
+    // Distributing again is a no-op.
+    let bundle = keeper
+        .distribute_competition_prizes(&competition, None, None)
+        .await?;
+    assert!(bundle.is_empty());
+
+    // Reclaim the remaining prizes.
+    let signature = keeper
+        .reclaim_prizes(&competition, &prize_mint, None, None)
+        .send()
+        .await?;
+    tracing::info!(%signature, "reclaimed prizes");
+
+    for client in traders.iter() {
+        let signature = client.close_participant(&competition).send().await?;
+        tracing::info!(%signature, "closed participant");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn competition_roi_scoring() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("competition_roi_scoring");
+    let _enter = span.enter();
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+
+    let market_token = deployment
+        .prepare_market(["fBTC", "fBTC", "USDG"], 1_000_007, 6_000_000_000_011, true)
+        .await?;
+
+    let slot = keeper.rpc().get_slot().await?;
+    let now = keeper
+        .rpc()
+        .get_block_time(slot)
+        .await
+        .unwrap_or_else(|_| OffsetDateTime::now_utc().unix_timestamp());
+    let start_time = now + 5;
+    let (rpc, competition) = keeper
+        .initialize_competition(
+            &CompetitionParams::builder()
+                .start_time(start_time)
+                .end_time(start_time + 3600)
+                .volume_threshold(u128::MAX)
+                .extension_duration(1)
+                .extension_cap(1)
+                .only_count_increase(true)
+                .volume_merge_window(1)
+                .scoring_mode(SdkScoringMode::Roi)
+                .build(),
+        )
+        .swap_output(());
+    let signature = rpc.send().await?;
+    tracing::info!(%signature, %competition, "initialized competition");
+
+    tokio::time::sleep(Duration::from_secs(8)).await;
+
+    let collateral_amount = 100_005;
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, collateral_amount)
+        .await?;
+    let size = 1_000 * MARKET_USD_UNIT;
+
+    let (rpc, order) = client
+        .market_increase(store, market_token, true, collateral_amount, true, size)
+        .competition(&competition)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participant = gmsol_sdk::pda::find_participant_address(
+        &competition,
+        &client.payer(),
+        &COMPETITION_PROGRAM_ID,
+    )
+    .0;
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    assert!(participant_account.collateral_value > 0);
+    // The order fee of the increase is charged against the realized PnL.
+    assert!(participant_account.realized_pnl < 0);
+
+    // Decreases are not counted as volume but the realized PnL (net of fees) is recorded.
+    let (rpc, order) = client
+        .market_decrease(store, market_token, true, 0, true, size)
+        .competition(&competition)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a decrease position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participants = keeper.competition_participants(&competition).await?;
+    assert_eq!(participants.len(), 1);
+    let (_, participant_account) = &participants[0];
+    assert_eq!(participant_account.volume, size);
+
+    let competition_account = keeper
+        .account::<Competition>(&competition)
+        .await?
+        .expect("must exist");
+    assert_eq!(competition_account.scoring_mode, ScoringMode::Roi);
+    let entry = competition_account.leaderboard[0];
+    assert_eq!(entry.address, client.payer());
+    assert_eq!(
+        entry.score,
+        participant_score(participant_account, &SdkScoringMode::Roi)
+    );
+    tracing::info!(
+        realized_pnl = participant_account.realized_pnl,
+        collateral_value = participant_account.collateral_value,
+        score = entry.score,
+        "ROI score"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn competition_pnl_top_up() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("competition_pnl_top_up");
+    let _enter = span.enter();
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+
+    let market_token = deployment
+        .prepare_market(["fBTC", "fBTC", "USDG"], 1_000_007, 6_000_000_000_011, true)
+        .await?;
+    let fbtc = deployment.token("fBTC").ok_or_eyre("fBTC not found")?;
+    let position =
+        client.find_position_address(store, &client.payer(), market_token, &fbtc.address, true)?;
+
+    let slot = keeper.rpc().get_slot().await?;
+    let now = keeper
+        .rpc()
+        .get_block_time(slot)
+        .await
+        .unwrap_or_else(|_| OffsetDateTime::now_utc().unix_timestamp());
+    let start_time = now + 20;
+    let (rpc, competition) = keeper
+        .initialize_competition(
+            &CompetitionParams::builder()
+                .start_time(start_time)
+                .end_time(start_time + 3600)
+                .volume_threshold(u128::MAX)
+                .extension_duration(1)
+                .extension_cap(1)
+                .only_count_increase(true)
+                .volume_merge_window(1)
+                .scoring_mode(SdkScoringMode::Pnl)
+                .build(),
+        )
+        .swap_output(());
+    let signature = rpc.send().await?;
+    tracing::info!(%signature, %competition, "initialized competition");
+
+    let collateral_amount = 100_005;
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, collateral_amount * 3)
+        .await?;
+    let size = 1_000 * MARKET_USD_UNIT;
+
+    // Open the position before the competition starts.
+    let (rpc, order) = client
+        .market_increase(store, market_token, true, collateral_amount, true, size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order before the start");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let slot = keeper.rpc().get_slot().await?;
+    let now = keeper
+        .rpc()
+        .get_block_time(slot)
+        .await
+        .unwrap_or_else(|_| OffsetDateTime::now_utc().unix_timestamp());
+    if now <= start_time {
+        tokio::time::sleep(Duration::from_secs((start_time - now + 2) as u64)).await;
+    }
+
+    // A top-up after the start does not make the position count.
+    let (rpc, order) = client
+        .market_increase(
+            store,
+            market_token,
+            true,
+            collateral_amount,
+            true,
+            MARKET_USD_UNIT,
+        )
+        .competition(&competition)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a top-up order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participant = gmsol_sdk::pda::find_participant_address(
+        &competition,
+        &client.payer(),
+        &COMPETITION_PROGRAM_ID,
+    )
+    .0;
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    assert!(participant_account.entered_positions.is_empty());
+    let realized_pnl = participant_account.realized_pnl;
+
+    // Closing the position opened before the start realizes no PnL.
+    let (rpc, order) = client
+        .market_decrease(store, market_token, true, 0, true, size + MARKET_USD_UNIT)
+        .competition(&competition)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a decrease position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    assert_eq!(participant_account.realized_pnl, realized_pnl);
+
+    // A position reopened during the competition is counted.
+    let (rpc, order) = client
+        .market_increase(store, market_token, true, collateral_amount, true, size)
+        .competition(&competition)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order after the start");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    assert_eq!(participant_account.entered_positions, vec![position]);
+    let realized_pnl = participant_account.realized_pnl;
+
+    let (rpc, order) = client
+        .market_decrease(store, market_token, true, 0, true, size)
+        .competition(&competition)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a decrease position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    // The order fee of the decrease is charged against the realized PnL.
+    assert_ne!(participant_account.realized_pnl, realized_pnl);
+    assert!(participant_account.entered_positions.is_empty());
+
+    Ok(())
+}
@@ -1,16 +1,247 @@
+use gmsol_gt_incentive::{
+    states::{Campaign, Config, Participant},
+    GtIncentiveError,
+};
+use gmsol_sdk::{
+    client::ops::ExchangeOps,
+    constants::MARKET_USD_UNIT,
+    ops::gt_incentive::{GtIncentiveCampaignParams, GtIncentiveOps},
+};
+use std::time::Duration;
+use time::OffsetDateTime;
+
 use crate::anchor_test::setup::{current_deployment, Deployment};
-use gmsol_gt_incentive as gt_incentive;
 
 #[tokio::test]
-async fn test_gt_incentive() -> eyre::Result<()> {
+async fn gt_incentive_campaign() -> eyre::Result<()> {
     let deployment = current_deployment().await?;
-    let client = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("gt_incentive_campaign");
+    let _enter = span.enter();
+
+    // The store admin is the authority of the config.
+    let admin = &deployment.client;
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+
+    let market_token = deployment
+        .prepare_market(["fBTC", "fBTC", "USDG"], 1_000_007, 6_000_000_000_011, true)
+        .await?;
+
+    let long_collateral_amount = 100_005;
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, long_collateral_amount)
+        .await?;
+
+    // Create and fund a campaign.
+    let slot = keeper.rpc().get_slot().await?;
+    let now = keeper
+        .rpc()
+        .get_block_time(slot)
+        .await
+        .unwrap_or_else(|_| OffsetDateTime::now_utc().unix_timestamp());
+    let start_time = now;
+    let end_time = start_time + 30;
+    let (rpc, campaign) = admin
+        .initialize_gt_incentive_campaign(
+            store,
+            &GtIncentiveCampaignParams::builder()
+                .start_time(start_time)
+                .end_time(end_time)
+                .build(),
+        )
+        .swap_output(());
+    let signature = rpc.send().await?;
+    tracing::info!(%signature, %campaign, "created campaign");
+
+    let gt_decimals = keeper.store(store).await?.gt.decimals;
+    let rewards = 100 * 10u64.pow(gt_decimals.into());
+    let config_address =
+        gmsol_sdk::pda::find_gt_incentive_config_address(store, &gmsol_gt_incentive::ID).0;
+    let funded_rewards = admin
+        .account::<Config>(&config_address)
+        .await?
+        .expect("must exist")
+        .funded_rewards;
+
+    // Only the store admin is allowed to set the reward budget.
+    let err = keeper
+        .set_gt_incentive_reward_budget(store, funded_rewards + rewards)
+        .send()
+        .await
+        .expect_err("should throw an error on setting the budget by a non-admin");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(GtIncentiveError::PermissionDenied.into())
+    );
+
+    let signature = admin
+        .set_gt_incentive_reward_budget(store, funded_rewards + rewards)
+        .send()
+        .await?;
+    tracing::info!(%signature, "set reward budget");
+
+    let signature = admin
+        .fund_gt_incentive_campaign(store, &campaign, rewards)
+        .send()
+        .await?;
+    tracing::info!(%signature, "funded campaign");
+
+    // The funded rewards cannot exceed the budget.
+    let err = admin
+        .fund_gt_incentive_campaign(store, &campaign, 1)
+        .send()
+        .await
+        .expect_err("should throw an error on exceeding the reward budget");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(GtIncentiveError::RewardBudgetExceeded.into())
+    );
+    let config = admin
+        .account::<Config>(&config_address)
+        .await?
+        .expect("must exist");
+    assert_eq!(config.funded_rewards, funded_rewards + rewards);
+    assert_eq!(config.reward_budget, config.funded_rewards);
+
+    // The budget cannot be lowered below the funded rewards.
+    let err = admin
+        .set_gt_incentive_reward_budget(store, funded_rewards)
+        .send()
+        .await
+        .expect_err("should throw an error on lowering the budget below the funded rewards");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(GtIncentiveError::InvalidAmount.into())
+    );
+
+    // Only the owner of the config is allowed to fund.
+    let err = client
+        .fund_gt_incentive_campaign(store, &campaign, rewards)
+        .send()
+        .await;
+    assert!(err.is_err());
+
+    // Trade during the campaign.
+    let size = 1_000 * MARKET_USD_UNIT;
+    let (rpc, order) = client
+        .market_increase(
+            store,
+            market_token,
+            true,
+            long_collateral_amount,
+            true,
+            size,
+        )
+        .gt_incentive_campaign(&campaign)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let (rpc, order) = client
+        .market_decrease(store, market_token, true, 0, true, size)
+        .gt_incentive_campaign(&campaign)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a decrease position order");
+
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    let participant = gmsol_sdk::pda::find_gt_incentive_participant_address(
+        &campaign,
+        &client.payer(),
+        &gmsol_gt_incentive::ID,
+    )
+    .0;
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    assert_eq!(participant_account.volume, 2 * size);
+    let campaign_account = client
+        .account::<Campaign>(&campaign)
+        .await?
+        .expect("must exist");
+    assert_eq!(campaign_account.total_volume, participant_account.volume);
+
+    // Rewards cannot be claimed before the campaign ends.
+    let err = client
+        .claim_gt_incentive_reward(store, &campaign)?
+        .send()
+        .await;
+    assert!(err.is_err());
+
+    // Wait for the campaign to end.
+    loop {
+        let slot = client.rpc().get_slot().await?;
+        let now = client.rpc().get_block_time(slot).await?;
+        if now > end_time {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs((end_time - now + 1) as u64)).await;
+    }
+
+    let user = client.find_user_address(store, &client.payer());
+    let gt_before = client.user(&user).await?.gt.amount;
+
+    let signature = client
+        .claim_gt_incentive_reward(store, &campaign)?
+        .send()
+        .await?;
+    tracing::info!(%signature, "claimed rewards");
+
+    let gt_after = client.user(&user).await?.gt.amount;
+    assert_eq!(gt_after - gt_before, rewards);
+
+    let participant_account = client
+        .account::<Participant>(&participant)
+        .await?
+        .expect("must exist");
+    assert_eq!(participant_account.claimed_rewards, rewards);
+    let campaign_account = client
+        .account::<Campaign>(&campaign)
+        .await?
+        .expect("must exist");
+    assert_eq!(campaign_account.claimed_rewards, rewards);
+
+    // Claiming again is a no-op.
+    client
+        .claim_gt_incentive_reward(store, &campaign)?
+        .send()
+        .await?;
+    assert_eq!(client.user(&user).await?.gt.amount, gt_after);
 
-    // Placeholder test
-    _ = gt_incentive::accounts::Initialize {
-        authority: client.payer(),
-        system_program: solana_sdk::system_program::ID,
-    };
+    let signature = client
+        .close_gt_incentive_participant(&campaign)
+        .send()
+        .await?;
+    tracing::info!(%signature, "closed participant");
 
     Ok(())
 }
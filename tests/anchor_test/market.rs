@@ -1,8 +1,9 @@
 use gmsol_programs::anchor_lang;
 use gmsol_sdk::{client::ops::MarketOps, constants::MARKET_USD_UNIT, ops::ExchangeOps};
 use gmsol_store::CoreError;
-use gmsol_utils::market::{MarketConfigFlag, MarketConfigKey};
+use gmsol_utils::market::{MarketConfigFlag, MarketConfigKey, MarketTradingMode};
 use solana_sdk::{signature::Keypair, signer::Signer};
+use std::time::Duration;
 use tracing::Instrument;
 
 use crate::anchor_test::setup::{current_deployment, Deployment};
@@ -442,3 +443,299 @@ async fn update_fees_state() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn market_trading_mode() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("market_trading_mode");
+    let _enter = span.enter();
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+
+    let market_token = deployment
+        .prepare_market(
+            Deployment::SELECT_TRADING_MODE_MARKET,
+            1_000_011,
+            6_000_000_000_013,
+            true,
+        )
+        .await?;
+
+    let collateral_amount = 100 * 100_000_000;
+    deployment
+        .mint_or_transfer_to_user("USDG", Deployment::DEFAULT_USER, collateral_amount)
+        .await?;
+
+    let size = 5_000 * MARKET_USD_UNIT;
+
+    // Open a position in normal mode.
+    let (rpc, order) = client
+        .market_increase(store, market_token, false, collateral_amount, true, size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    // Only a MARKET_KEEPER can set the trading mode.
+    let err = client
+        .set_market_trading_mode(store, market_token, MarketTradingMode::ReduceOnly)
+        .send()
+        .await
+        .expect_err("should throw error when called by a non-market-keeper");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Increase orders are rejected in reduce-only mode.
+    let signature = keeper
+        .set_market_trading_mode(store, market_token, MarketTradingMode::ReduceOnly)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "set the market to reduce-only mode");
+    let (rpc, _) = client
+        .market_increase(store, market_token, false, 0, true, size / 10)
+        .build_with_address()
+        .await?;
+    let err = rpc
+        .send()
+        .await
+        .expect_err("should throw error when increasing in reduce-only mode");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::TradingModeRestricted.into())
+    );
+
+    // Swap orders are rejected in reduce-only mode.
+    let usdg = deployment.token("USDG").expect("must exist");
+    let swap_amount = 100_000_000;
+    deployment
+        .mint_or_transfer_to_user("USDG", Deployment::DEFAULT_USER, swap_amount)
+        .await?;
+    let (rpc, _) = client
+        .market_swap(
+            store,
+            market_token,
+            true,
+            &usdg.address,
+            swap_amount,
+            [market_token],
+        )
+        .build_with_address()
+        .await?;
+    let err = rpc
+        .send()
+        .await
+        .expect_err("should throw error when swapping in reduce-only mode");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::TradingModeRestricted.into())
+    );
+
+    // Only full closes are allowed in close-only mode.
+    let signature = keeper
+        .set_market_trading_mode(store, market_token, MarketTradingMode::CloseOnly)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "set the market to close-only mode");
+    let (rpc, _) = client
+        .market_decrease(store, market_token, false, 0, true, size / 2)
+        .build_with_address()
+        .await?;
+    let err = rpc
+        .send()
+        .await
+        .expect_err("should throw error when partially decreasing in close-only mode");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::TradingModeRestricted.into())
+    );
+
+    // A TWAP order closing the whole position is a full close, so each of its
+    // slices is allowed in close-only mode.
+    let slices = 2;
+    let (rpc, order) = client
+        .market_decrease(store, market_token, false, 0, true, size)
+        .twap(slices, 0)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a TWAP decrease order closing the position");
+    for slice in 0..slices {
+        let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+        deployment
+            .execute_with_pyth(
+                builder
+                    .add_alt(deployment.common_alt().clone())
+                    .add_alt(deployment.market_alt().clone()),
+                None,
+                true,
+                true,
+            )
+            .await?;
+        tracing::info!(%order, slice, "executed a slice in close-only mode");
+        if slice + 1 < slices {
+            let pending = client.order(&order).await?;
+            assert_eq!(pending.params.twap_remaining_slices(), slices - slice - 1);
+        }
+    }
+    // The order should have been completed and closed.
+    assert!(client.order(&order).await.is_err());
+
+    let signature = keeper
+        .set_market_trading_mode(store, market_token, MarketTradingMode::Normal)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "set the market back to normal mode");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn schedule_market_config_update() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("schedule_market_config_update");
+    let _enter = span.enter();
+
+    let store = &deployment.store;
+    let selector = ["fBTC", "fBTC", "USDG"];
+    let [index, long_token, short_token] = selector;
+    let market_token = deployment
+        .market_token(index, long_token, short_token)
+        .expect("must exist");
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let user = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let market = keeper.find_market_address(store, market_token);
+    let key = MarketConfigKey::MaxOpenInterestForShort;
+    let duration = 3600;
+
+    let original = *keeper
+        .market(&market)
+        .await?
+        .config
+        .get(key)
+        .expect("must exist");
+    let target = original / 2;
+
+    // Only a MARKET_KEEPER can schedule an update of a non-updatable factor.
+    let err = user
+        .schedule_market_config_update(store, market_token, key, &target, 0, duration)
+        .send()
+        .await
+        .expect_err("should throw error when called by a non-market-keeper");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // A start time in the past is clamped to the current time,
+    // and the config is not changed until the ramp is applied.
+    let signature = keeper
+        .schedule_market_config_update(store, market_token, key, &target, 0, duration)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "scheduled a ramped update");
+    let state = keeper.market(&market).await?;
+    let schedule = *state
+        .state
+        .other
+        .config_schedules
+        .get(key)
+        .expect("must be scheduled");
+    assert_eq!(*state.config.get(key).expect("must exist"), original);
+    assert_eq!(schedule.from, original);
+    assert_eq!(schedule.target, target);
+    assert_eq!(schedule.duration, duration);
+    assert!(schedule.start_at > 0);
+
+    // Executing an action in the middle of the ramp applies the interpolated value.
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    deployment
+        .prepare_market(selector, 1_000_017, 6_000_000_000_019, true)
+        .await?;
+    let slot = keeper.rpc().get_slot().await?;
+    let now = keeper.rpc().get_block_time(slot).await?;
+    let state = keeper.market(&market).await?;
+    let value = *state.config.get(key).expect("must exist");
+    assert!(target < value && value < original);
+    assert!((schedule.start_at..=now).any(|ts| schedule.value_at(ts) == value));
+    assert!(state.state.other.config_schedules.get(key).is_some());
+
+    // Cancelling the schedule keeps the value effective at the cancellation.
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let signature = keeper
+        .cancel_market_config_schedule(store, market_token, key)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "cancelled the scheduled update");
+    let state = keeper.market(&market).await?;
+    let cancelled_value = *state.config.get(key).expect("must exist");
+    assert!(target < cancelled_value && cancelled_value < value);
+    assert!(state.state.other.config_schedules.get(key).is_none());
+
+    let err = keeper
+        .cancel_market_config_schedule(store, market_token, key)
+        .send()
+        .await
+        .expect_err("should throw error when there is no schedule to cancel");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::NotFound.into())
+    );
+
+    // Setting the factor directly overrides the scheduled update.
+    let signature = keeper
+        .schedule_market_config_update(store, market_token, key, &target, 0, duration)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "scheduled a ramped update");
+    let signature = keeper
+        .update_market_config_by_key(store, market_token, key, &original)?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "overrode the scheduled update");
+    let state = keeper.market(&market).await?;
+    assert_eq!(*state.config.get(key).expect("must exist"), original);
+    assert!(state.state.other.config_schedules.get(key).is_none());
+
+    // The target is reached once the ramp has ended, and the schedule is removed.
+    let short_duration = 2;
+    let signature = keeper
+        .schedule_market_config_update(store, market_token, key, &target, 0, short_duration)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "scheduled a short ramped update");
+    tokio::time::sleep(Duration::from_secs(u64::from(short_duration) + 2)).await;
+    deployment
+        .prepare_market(selector, 1_000_017, 6_000_000_000_019, true)
+        .await?;
+    let state = keeper.market(&market).await?;
+    assert_eq!(*state.config.get(key).expect("must exist"), target);
+    assert!(state.state.other.config_schedules.get(key).is_none());
+
+    // Restore the factor.
+    let signature = keeper
+        .update_market_config_by_key(store, market_token, key, &original)?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "restored the factor");
+
+    Ok(())
+}
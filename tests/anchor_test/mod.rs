@@ -32,3 +32,7 @@ mod competition;
 mod liquidity_provider;
 
 mod gt_incentive;
+
+mod risk_oracle;
+
+mod timelock;
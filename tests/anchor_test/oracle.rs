@@ -1,13 +1,28 @@
 use std::time::Duration;
 
-use gmsol_sdk::client::{
-    chainlink::{self, pull_oracle::parse_feed_id},
-    ops::{ExchangeOps, OracleOps},
-    pull_oracle::WithPullOracle,
+use eyre::OptionExt;
+use gmsol_sdk::{
+    client::{
+        chainlink::{self, pull_oracle::parse_feed_id},
+        ops::{ExchangeOps, OracleOps, TokenConfigOps},
+        pull_oracle::WithPullOracle,
+    },
+    ops::token_config::UpdateFeedConfig,
+    utils::Value,
+    Client,
 };
-use gmsol_solana_utils::make_bundle_builder::{EstimateFee, MakeBundleBuilder};
-use gmsol_utils::oracle::PriceProviderKind;
-use solana_sdk::pubkey::Pubkey;
+use gmsol_solana_utils::{
+    make_bundle_builder::{EstimateFee, MakeBundleBuilder},
+    signer::SignerRef,
+};
+use gmsol_store::CoreError;
+use gmsol_utils::{
+    oracle::{CompositePriceMode, PriceProviderKind},
+    token_config::{
+        FeedConfig, TokenMapAccess, UpdateTokenConfigParams, DEFAULT_TIMESTAMP_ADJUSTMENT,
+    },
+};
+use solana_sdk::{pubkey::Pubkey, signer::keypair::Keypair};
 
 use crate::anchor_test::setup::{current_deployment, Deployment};
 
@@ -116,3 +131,546 @@ async fn use_chainlink_data_streams() -> eyre::Result<()> {
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn price_circuit_breaker() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("price_circuit_breaker");
+    let _enter = span.enter();
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+    let token_map = deployment.token_map();
+    let token = deployment
+        .token(Deployment::TOKEN_FOR_CIRCUIT_BREAKER_TEST)
+        .expect("must exist")
+        .address;
+    let usdg = deployment.token("USDG").expect("must exist").address;
+
+    let market_token = deployment
+        .prepare_market(
+            Deployment::SELECT_CIRCUIT_BREAKER_MARKET,
+            1_000_005,
+            6_000_000_000_003,
+            true,
+        )
+        .await?;
+
+    let collateral_amount = 100 * 100_000_000;
+    deployment
+        .mint_or_transfer_to_user("USDG", Deployment::DEFAULT_USER, collateral_amount)
+        .await?;
+
+    let size = 5_000 * 100_000_000_000_000_000_000;
+    let increment_size = size / 10;
+    let position =
+        client.find_position_address(store, &client.payer(), market_token, &usdg, true)?;
+
+    // Open a position before enabling the circuit breaker.
+    let (rpc, order) = client
+        .market_increase(store, market_token, false, collateral_amount, true, size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+
+    // Only a MARKET_KEEPER can configure the circuit breaker.
+    let max_move_factor = Value::from_u128(FeedConfig::RATIO_MULTIPLIER);
+    let err = client
+        .set_price_circuit_breaker(store, &token_map, &token, Some(&max_move_factor), 3600)?
+        .send()
+        .await
+        .expect_err("should throw error when called by a non-market-keeper");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Enable the circuit breaker with the smallest max move factor,
+    // so that almost any price change trips it.
+    let signature = keeper
+        .set_price_circuit_breaker(store, &token_map, &token, Some(&max_move_factor), 3600)?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "enabled the price circuit breaker");
+
+    // Executing actions does not track the prices, the breaker only
+    // starts protecting the token once it is updated by the keeper.
+    let size_before = client.position(&position).await?.state.size_in_usd;
+    let (rpc, order) = client
+        .market_increase(store, market_token, false, 0, true, increment_size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+    let size_after = client.position(&position).await?.state.size_in_usd;
+    assert_eq!(size_after, size_before + increment_size);
+    assert!(!is_price_circuit_breaker_started(&keeper, &token_map, &token).await?);
+
+    // Only an ORDER_KEEPER can update the breaker.
+    let mut builder = client.update_price_circuit_breakers(store, oracle, [token]);
+    let res = deployment
+        .execute_with_pyth(
+            builder.add_alt(deployment.common_alt().clone()),
+            None,
+            false,
+            false,
+        )
+        .await;
+    assert!(
+        res.is_err(),
+        "should throw error when called by a non-order-keeper"
+    );
+
+    // Keep updating the breaker until it is tripped.
+    let mut tripped = false;
+    for _ in 0..10 {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let mut builder = keeper.update_price_circuit_breakers(store, oracle, [token]);
+        deployment
+            .execute_with_pyth(
+                builder.add_alt(deployment.common_alt().clone()),
+                None,
+                true,
+                true,
+            )
+            .await?;
+        if is_price_circuit_breaker_tripped(&keeper, &token_map, &token).await? {
+            tripped = true;
+            break;
+        }
+    }
+    eyre::ensure!(tripped, "the price circuit breaker was never tripped");
+
+    // Increase orders are rejected, even when executed with a freshly initialized oracle.
+    let fresh_oracle = Keypair::new();
+    let (rpc, fresh_oracle) = keeper.initialize_oracle(store, &fresh_oracle, None).await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%fresh_oracle, %signature, "initialized a fresh oracle");
+
+    let size_before = client.position(&position).await?.state.size_in_usd;
+    let (rpc, order) = client
+        .market_increase(store, market_token, false, 0, true, increment_size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, &fresh_oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+    let size_after = client.position(&position).await?.state.size_in_usd;
+    assert_eq!(size_after, size_before);
+
+    // Decrease orders are still allowed while the breaker is tripped.
+    let size_before = client.position(&position).await?.state.size_in_usd;
+    let (rpc, order) = client
+        .market_decrease(store, market_token, false, 0, true, increment_size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a decrease position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+    let size_after = client.position(&position).await?.state.size_in_usd;
+    assert_eq!(size_after, size_before - increment_size);
+
+    // Only a MARKET_KEEPER can reset the breaker.
+    let err = client
+        .reset_price_circuit_breaker(store, &token_map, &token)
+        .send()
+        .await
+        .expect_err("should throw error when called by a non-market-keeper");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Disable and reset the breaker.
+    let signature = keeper
+        .set_price_circuit_breaker(store, &token_map, &token, None, 0)?
+        .merge(keeper.reset_price_circuit_breaker(store, &token_map, &token))
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "disabled and reset the price circuit breaker");
+    assert!(!is_price_circuit_breaker_tripped(&keeper, &token_map, &token).await?);
+
+    // Increase orders are allowed again.
+    let size_before = client.position(&position).await?.state.size_in_usd;
+    let (rpc, order) = client
+        .market_increase(store, market_token, false, 0, true, increment_size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(
+            builder
+                .add_alt(deployment.common_alt().clone())
+                .add_alt(deployment.market_alt().clone()),
+            None,
+            true,
+            true,
+        )
+        .await?;
+    let size_after = client.position(&position).await?.state.size_in_usd;
+    assert_eq!(size_after, size_before + increment_size);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn composite_price() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("composite_price");
+    let _enter = span.enter();
+
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+    let token_map = deployment.token_map();
+    let name = Deployment::TOKEN_FOR_COMPOSITE_PRICE_TEST;
+    let token = deployment.token(name).expect("must exist").address;
+    let sources = [
+        PriceProviderKind::Pyth,
+        PriceProviderKind::ChainlinkDataStreams,
+    ];
+
+    let market_token = deployment
+        .prepare_market(
+            Deployment::SELECT_COMPOSITE_PRICE_MARKET,
+            1_000_011,
+            6_000_000_000_013,
+            true,
+        )
+        .await?;
+
+    // Only a MARKET_KEEPER can set the composite price config.
+    let err = client
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::Median,
+            &sources,
+            None,
+        )?
+        .send()
+        .await
+        .expect_err("should throw error when called by a non-market-keeper");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // At least two price sources are required.
+    let err = keeper
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::Median,
+            &sources[..1],
+            None,
+        )?
+        .send()
+        .await
+        .expect_err("should throw error when only one price source is provided");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::InvalidArgument.into())
+    );
+
+    // Every price source must have a feed config.
+    let err = keeper
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::Median,
+            &sources,
+            None,
+        )?
+        .send()
+        .await
+        .expect_err("should throw error when a price source has no feed config");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::NotFound.into())
+    );
+
+    // Add a Chainlink Data Streams price feed that is never updated, so that
+    // it is always unavailable as a price source. Its address is used as the
+    // feed id so that it is the account passed for the Chainlink source.
+    let index = 0;
+    let feed = keeper.find_price_feed_address(
+        store,
+        &keeper.payer(),
+        index,
+        PriceProviderKind::ChainlinkDataStreams,
+        &token,
+    );
+    let (rpc, _) = keeper.initialize_price_feed(
+        store,
+        index,
+        PriceProviderKind::ChainlinkDataStreams,
+        &token,
+        &feed,
+    );
+    let signature = rpc.send_without_preflight().await?;
+    tracing::info!(%signature, %feed, "created a chainlink feed");
+
+    let map = keeper.token_map(&token_map).await?;
+    let config = map.get(&token).expect("must exist");
+    let params = UpdateTokenConfigParams::from(config).update_price_feed(
+        &PriceProviderKind::ChainlinkDataStreams,
+        feed,
+        None,
+    )?;
+    let signature = keeper
+        .insert_token_config(store, &token_map, name, &token, params, true, false)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "added the chainlink feed config");
+
+    // The max disagreement factor must not be rounded down to zero.
+    let err = keeper
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::Median,
+            &sources,
+            Some(&Value::from_u128(FeedConfig::RATIO_MULTIPLIER - 1)),
+        )?
+        .send()
+        .await
+        .expect_err("should throw error when the max disagreement factor is too small");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::InvalidArgument.into())
+    );
+
+    // Enable the median mode and read it back from the token map.
+    let max_disagreement_factor = 100 * FeedConfig::RATIO_MULTIPLIER;
+    let signature = keeper
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::Median,
+            &sources,
+            Some(&Value::from_u128(max_disagreement_factor)),
+        )?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "enabled the median mode");
+    let map = keeper.token_map(&token_map).await?;
+    let config = map.get(&token).expect("must exist");
+    assert_eq!(config.composite_mode()?, CompositePriceMode::Median);
+    assert_eq!(config.price_sources()?, sources);
+    assert_eq!(
+        config.max_disagreement_factor(),
+        Some(max_disagreement_factor)
+    );
+
+    let amount = 100 * 100_000_000;
+    deployment
+        .mint_or_transfer_to_user("USDG", Deployment::DEFAULT_USER, 2 * amount)
+        .await?;
+    let (rpc, deposit) = client
+        .create_deposit(store, market_token)
+        .short_token(amount, None, None)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%deposit, %signature, "created a deposit");
+
+    // The median requires every price source, so the execution fails
+    // since the Chainlink source is unavailable.
+    let mut builder = keeper.execute_deposit(store, oracle, &deposit, false);
+    let err = deployment
+        .execute_with_pyth(&mut builder, None, false, false)
+        .await
+        .expect_err("should throw error when a price source of the median is unavailable");
+    let code = err.anchor_error_code();
+    assert!(
+        code == Some(CoreError::MarketNotOpen.into())
+            || code == Some(CoreError::PriceFeedNotUpdated.into()),
+        "unexpected error: {err}"
+    );
+
+    // Switch to the fallback mode, in which the unavailable Chainlink source is tolerated.
+    let signature = keeper
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::PrimaryWithFallback,
+            &sources,
+            Some(&Value::from_u128(max_disagreement_factor)),
+        )?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "enabled the fallback mode");
+    let mut builder = keeper.execute_deposit(store, oracle, &deposit, false);
+    deployment
+        .execute_with_pyth(&mut builder, None, false, true)
+        .await?;
+
+    let (rpc, deposit) = client
+        .create_deposit(store, market_token)
+        .short_token(amount, None, None)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%deposit, %signature, "created a deposit");
+
+    // The execution fails when no price source is available.
+    let signature = keeper
+        .update_feed_config(
+            store,
+            &token_map,
+            &token,
+            PriceProviderKind::Pyth,
+            UpdateFeedConfig::builder()
+                .timestamp_adjustment(Some(u32::MAX))
+                .build(),
+        )?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "made the pyth price stale");
+    let mut builder = keeper.execute_deposit(store, oracle, &deposit, false);
+    let err = deployment
+        .execute_with_pyth(&mut builder, None, false, false)
+        .await
+        .expect_err("should throw error when no price source is available");
+    assert_eq!(
+        err.anchor_error_code(),
+        Some(CoreError::InvalidPriceFeedPrice.into())
+    );
+    let signature = keeper
+        .update_feed_config(
+            store,
+            &token_map,
+            &token,
+            PriceProviderKind::Pyth,
+            UpdateFeedConfig::builder()
+                .timestamp_adjustment(Some(DEFAULT_TIMESTAMP_ADJUSTMENT))
+                .build(),
+        )?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "restored the pyth timestamp adjustment");
+
+    // An invalid feed account is not covered by the fallback,
+    // even though the Pyth source is available.
+    let signature = keeper
+        .update_feed_config(
+            store,
+            &token_map,
+            &token,
+            PriceProviderKind::ChainlinkDataStreams,
+            UpdateFeedConfig::builder()
+                .feed_id(Some(Pubkey::new_unique().into()))
+                .build(),
+        )?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "pointed the chainlink feed config at an invalid account");
+    let mut builder = keeper.execute_deposit(store, oracle, &deposit, false);
+    let err = deployment
+        .execute_with_pyth(&mut builder, None, false, false)
+        .await
+        .expect_err("should throw error when a price source has an invalid feed account");
+    assert_eq!(
+        err.anchor_error_code(),
+        Some(CoreError::InvalidPriceFeedAccount.into())
+    );
+
+    // Disable the composite price and execute the pending deposit.
+    let signature = keeper
+        .set_composite_price(
+            store,
+            &token_map,
+            &token,
+            CompositePriceMode::Disabled,
+            &[],
+            None,
+        )?
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "disabled the composite price");
+    let map = keeper.token_map(&token_map).await?;
+    assert!(!map.get(&token).expect("must exist").is_composite());
+    let mut builder = keeper.execute_deposit(store, oracle, &deposit, false);
+    deployment
+        .execute_with_pyth(&mut builder, None, false, true)
+        .await?;
+
+    Ok(())
+}
+
+async fn is_price_circuit_breaker_tripped(
+    client: &Client<SignerRef>,
+    token_map: &Pubkey,
+    token: &Pubkey,
+) -> eyre::Result<bool> {
+    let token_map = client.token_map(token_map).await?;
+    let config = token_map.get(token).ok_or_eyre("token config not found")?;
+    Ok(config.price_circuit_breaker_state().is_tripped())
+}
+
+async fn is_price_circuit_breaker_started(
+    client: &Client<SignerRef>,
+    token_map: &Pubkey,
+    token: &Pubkey,
+) -> eyre::Result<bool> {
+    let token_map = client.token_map(token_map).await?;
+    let config = token_map.get(token).ok_or_eyre("token config not found")?;
+    Ok(config.price_circuit_breaker_state().is_started())
+}
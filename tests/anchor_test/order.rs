@@ -1,11 +1,14 @@
 use std::time::Duration;
 
-use gmsol_programs::gmsol_store::types::{DecreasePositionSwapType, UpdateOrderParams};
+use gmsol_programs::gmsol_store::types::{
+    DecreasePositionSwapType, UpdateOrderOptions, UpdateOrderParams,
+};
 use gmsol_sdk::{
     client::ops::{ExchangeOps, MarketOps},
     constants::MARKET_USD_UNIT,
 };
-use gmsol_utils::market::MarketConfigKey;
+use gmsol_store::CoreError;
+use gmsol_utils::{market::MarketConfigKey, order::TrailingDistance};
 use tracing::Instrument;
 
 use crate::anchor_test::setup::{current_deployment, Deployment};
@@ -763,3 +766,395 @@ async fn update_order() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn trailing_stop_order() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("trailing_stop_order");
+    let _enter = span.enter();
+
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+    let fbtc = deployment.token("fBTC").expect("must exist");
+
+    let long_token_amount = 1_000_011;
+    let short_token_amount = 6_000_000_000_007;
+
+    let market_token = deployment
+        .prepare_market(
+            ["fBTC", "fBTC", "USDG"],
+            long_token_amount,
+            short_token_amount,
+            true,
+        )
+        .await?;
+
+    let long_collateral_amount = 100_000;
+
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, long_collateral_amount)
+        .await?;
+
+    // Open position.
+    let size = 5_000 * MARKET_USD_UNIT;
+    let (rpc, order) = client
+        .market_increase(
+            store,
+            market_token,
+            true,
+            long_collateral_amount,
+            true,
+            size,
+        )
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, %size, "created an order to increase position");
+
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("execute", order=%order))
+        .await?;
+
+    // Create a trailing-stop order with a low initial best price,
+    // so that the best price will be ratcheted up by the keeper.
+    let price = MARKET_USD_UNIT / 10u128.pow(fbtc.config.decimals as u32);
+    let (rpc, order) = client
+        .trailing_stop(
+            store,
+            market_token,
+            true,
+            size,
+            price,
+            TrailingDistance::Factor(MARKET_USD_UNIT / 2),
+            true,
+            0,
+        )
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, %price, "created a trailing-stop order");
+
+    let mut builder = keeper.update_trailing_stop_order(store, oracle, &order);
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("update trailing stop", order=%order))
+        .await?;
+
+    let updated = client.order(&order).await?;
+    assert!(updated.params.best_price > price);
+    assert!(updated.params.trigger_price < updated.params.best_price);
+
+    let signature = client
+        .update_order_with_options(
+            store,
+            market_token,
+            &order,
+            Default::default(),
+            UpdateOrderOptions {
+                trailing_distance: Some(TrailingDistance::Factor(MARKET_USD_UNIT / 4).into()),
+                best_price: None,
+            },
+            None,
+        )
+        .await?
+        .send()
+        .await?;
+    tracing::info!(%order, %signature, "updated the trailing distance");
+
+    // The trigger price of a trailing-stop order cannot be set directly.
+    let err = client
+        .update_order(
+            store,
+            market_token,
+            &order,
+            UpdateOrderParams {
+                trigger_price: Some(price),
+                ..Default::default()
+            },
+            None,
+        )
+        .await?
+        .send()
+        .await
+        .expect_err("should throw an error on setting the trigger price of a trailing-stop order");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::InvalidArgument.into())
+    );
+
+    let signature = client
+        .update_order_with_options(
+            store,
+            market_token,
+            &order,
+            Default::default(),
+            UpdateOrderOptions {
+                trailing_distance: None,
+                best_price: Some(price),
+            },
+            None,
+        )
+        .await?
+        .send()
+        .await?;
+    tracing::info!(%order, %signature, %price, "reset the best price");
+
+    let updated = client.order(&order).await?;
+    assert_eq!(updated.params.best_price, price);
+
+    // An absolute trailing distance must be less than the best price.
+    let err = client
+        .update_order_with_options(
+            store,
+            market_token,
+            &order,
+            Default::default(),
+            UpdateOrderOptions {
+                trailing_distance: Some(TrailingDistance::Absolute(price).into()),
+                best_price: None,
+            },
+            None,
+        )
+        .await?
+        .send()
+        .await
+        .expect_err("should throw an error on a trailing distance not less than the best price");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::InvalidTrailingDistance.into())
+    );
+
+    let err = client
+        .trailing_stop(
+            store,
+            market_token,
+            true,
+            size,
+            price,
+            TrailingDistance::Absolute(price),
+            true,
+            0,
+        )
+        .build_with_address()
+        .await?
+        .0
+        .send()
+        .await
+        .expect_err("should throw an error on a trailing distance not less than the best price");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::InvalidTrailingDistance.into())
+    );
+
+    let signature = client.close_order(&order)?.build().await?.send().await?;
+    tracing::info!(%order, %signature, "cancelled a trailing-stop order");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn linked_orders() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("linked_orders");
+    let _enter = span.enter();
+
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+    let fbtc = deployment.token("fBTC").expect("must exist");
+
+    let long_token_amount = 1_000_013;
+    let short_token_amount = 6_000_000_000_011;
+
+    let market_token = deployment
+        .prepare_market(
+            ["fBTC", "fBTC", "USDG"],
+            long_token_amount,
+            short_token_amount,
+            true,
+        )
+        .await?;
+
+    let long_collateral_amount = 100_000;
+
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, long_collateral_amount)
+        .await?;
+
+    // Open position.
+    let size = 5_000 * MARKET_USD_UNIT;
+    let (rpc, order) = client
+        .market_increase(
+            store,
+            market_token,
+            true,
+            long_collateral_amount,
+            true,
+            size,
+        )
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, %size, "created an order to increase position");
+
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("execute", order=%order))
+        .await?;
+
+    // Create a bracket whose take-profit order is ready to be executed.
+    let price = MARKET_USD_UNIT / 10u128.pow(fbtc.config.decimals as u32);
+    let (rpc, take_profit) = client
+        .limit_decrease(store, market_token, true, size, price, true, 0)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(order=%take_profit, %signature, "created a take-profit order");
+
+    let (rpc, stop_loss) = client
+        .stop_loss(store, market_token, true, size, price, true, 0)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(order=%stop_loss, %signature, "created a stop-loss order");
+
+    // Orders on different positions cannot be linked.
+    let (rpc, opposite) = client
+        .stop_loss(store, market_token, false, size, price, true, 0)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(order=%opposite, %signature, "created a stop-loss order on the short side");
+
+    let err = client
+        .link_orders(store, &[take_profit, opposite])?
+        .send()
+        .await
+        .expect_err("should throw an error on linking orders of different positions");
+    tracing::info!(%err, "expected error");
+
+    let signature = client.close_order(&opposite)?.build().await?.send().await?;
+    tracing::info!(order=%opposite, %signature, "cancelled the stop-loss order on the short side");
+
+    let signature = client
+        .link_orders(store, &[take_profit, stop_loss])?
+        .send()
+        .await?;
+    tracing::info!(%take_profit, %stop_loss, %signature, "linked orders");
+
+    let linked = client.order(&stop_loss).await?;
+    assert!(linked.linked_orders.contains(&take_profit));
+
+    // Orders cannot be linked twice.
+    let err = client
+        .link_orders(store, &[take_profit, stop_loss])?
+        .send()
+        .await
+        .expect_err("should throw an error on linking linked orders");
+    tracing::info!(%err, "expected error");
+
+    let mut builder = keeper.execute_order(store, oracle, &take_profit, true)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("execute", order=%take_profit))
+        .await?;
+
+    // The stop-loss order should have been cancelled and closed.
+    let closed = client.order(&stop_loss).await;
+    assert!(closed.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn twap_market_decrease() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("twap_market_decrease");
+    let _enter = span.enter();
+
+    let client = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+
+    let long_token_amount = 1_000_017;
+    let short_token_amount = 6_000_000_000_013;
+
+    let market_token = deployment
+        .prepare_market(
+            ["fBTC", "fBTC", "USDG"],
+            long_token_amount,
+            short_token_amount,
+            true,
+        )
+        .await?;
+
+    let long_collateral_amount = 100_000;
+
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, long_collateral_amount)
+        .await?;
+
+    // Open position.
+    let size = 5_000 * MARKET_USD_UNIT;
+    let (rpc, order) = client
+        .market_increase(
+            store,
+            market_token,
+            true,
+            long_collateral_amount,
+            true,
+            size,
+        )
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, %size, "created an order to increase position");
+
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("execute", order=%order))
+        .await?;
+
+    // Decrease the position in 2 slices.
+    let slices = 2;
+    let (rpc, order) = client
+        .market_decrease(store, market_token, true, 0, true, size)
+        .twap(slices, 0)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, %size, "created a TWAP order to decrease position");
+
+    let mut builder = keeper.execute_order(store, oracle, &order, true)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("execute slice", order=%order))
+        .await?;
+
+    // The order should be kept pending for the remaining slice.
+    let pending = client.order(&order).await?;
+    assert_eq!(pending.params.twap_remaining_slices(), 1);
+    assert_eq!(pending.params.size_delta_value, size - size / 2);
+
+    let mut builder = keeper.execute_order(store, oracle, &order, true)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .instrument(tracing::info_span!("execute slice", order=%order))
+        .await?;
+
+    // The order should have been completed and closed.
+    assert!(client.order(&order).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use std::time::SystemTime;
+
+use gmsol_sdk::{
+    client::risk_oracle::{types::EncodedRecommendation, verify::build_signed_message},
+    constants::MARKET_USD_UNIT,
+    ops::RiskOracleOps,
+};
+use gmsol_store::CoreError;
+use gmsol_utils::market::MarketConfigKey;
+use libsecp256k1::{Message, PublicKey, SecretKey};
+use solana_sdk::{keccak, pubkey::Pubkey};
+
+use crate::anchor_test::setup::{current_deployment, Deployment};
+
+const REFERENCE_ID: &str = "123e4567-e89b-12d3-a456-426614174000";
+const DECIMALS: u8 = 10;
+
+fn signer_of(secret: &SecretKey) -> Pubkey {
+    let pubkey = PublicKey::from_secret_key(secret);
+    Pubkey::new_from_array(keccak::hash(&pubkey.serialize_compressed()).to_bytes())
+}
+
+fn sign(rec: &mut EncodedRecommendation, secret: &SecretKey) -> eyre::Result<()> {
+    let hash = build_signed_message(rec)?;
+    let (signature, recovery_id) = libsecp256k1::sign(&Message::parse(&hash), secret);
+    rec.signature = signature
+        .serialize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    rec.recovery_id = recovery_id.serialize();
+    Ok(())
+}
+
+fn reserve_factor_recommendation(
+    market_token: &Pubkey,
+    reserve_factor: u64,
+    open_interest_reserve_factor: u64,
+    timestamp: u64,
+) -> EncodedRecommendation {
+    let keys = [
+        (
+            "reserveFactor/openInterestReserveFactor/v1",
+            open_interest_reserve_factor,
+        ),
+        ("reserveFactor/reserveFactor/v1", reserve_factor),
+    ];
+    EncodedRecommendation {
+        parameter_name: "reserveFactor".to_string(),
+        market_address: market_token.to_string(),
+        new_values: keys
+            .iter()
+            .map(|(key, value)| (key.to_string(), *value))
+            .collect(),
+        timestamp,
+        reference_id: REFERENCE_ID.to_string(),
+        protocol: "gmx_solana".to_string(),
+        decimals: keys
+            .iter()
+            .map(|(key, _)| (key.to_string(), DECIMALS))
+            .collect(),
+        signature: String::new(),
+        recovery_id: 0,
+    }
+}
+
+#[tokio::test]
+async fn apply_risk_oracle_recommendation() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("apply_risk_oracle_recommendation");
+    let _enter = span.enter();
+
+    let store = &deployment.store;
+    let market_token = deployment
+        .market_token("fBTC", "fBTC", "USDG")
+        .expect("must exist");
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let relayer = deployment.user_client(Deployment::DEFAULT_USER)?;
+
+    let secret = SecretKey::parse(&[7; 32])?;
+    let signature = keeper
+        .initialize_risk_oracle(store, &signer_of(&secret), 3600)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "initialized risk oracle");
+
+    // Recommend the current values to keep the market unchanged.
+    let unit = 10u128.pow(u32::from(
+        gmsol_store::constants::MARKET_DECIMALS - DECIMALS,
+    ));
+    let market = keeper.market_by_token(store, market_token).await?;
+    let reserve_factor = market
+        .config
+        .get(MarketConfigKey::ReserveFactor)
+        .copied()
+        .unwrap_or_default()
+        / unit;
+    let open_interest_reserve_factor = market
+        .config
+        .get(MarketConfigKey::OpenInterestReserveFactor)
+        .copied()
+        .unwrap_or_default()
+        / unit;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let mut rec = reserve_factor_recommendation(
+        market_token,
+        reserve_factor.try_into()?,
+        open_interest_reserve_factor.try_into()?,
+        now,
+    );
+    sign(&mut rec, &secret)?;
+
+    // The keys are not bounded yet.
+    let err = relayer
+        .apply_risk_oracle_recommendation(store, market_token, rec.to_recommendation()?)
+        .send()
+        .await
+        .expect_err("should throw error when the keys are not bounded");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::RiskOracleValueOutOfBounds.into())
+    );
+
+    // Only a MARKET_KEEPER can set the bounds.
+    let err = relayer
+        .set_risk_oracle_bound(
+            store,
+            MarketConfigKey::ReserveFactor,
+            0,
+            2 * MARKET_USD_UNIT,
+        )
+        .send()
+        .await
+        .expect_err("should throw error when called by a non-market-keeper");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+    for key in [
+        MarketConfigKey::ReserveFactor,
+        MarketConfigKey::OpenInterestReserveFactor,
+    ] {
+        let signature = keeper
+            .set_risk_oracle_bound(store, key, 0, 2 * MARKET_USD_UNIT)
+            .send_without_preflight()
+            .await?;
+        tracing::info!(%signature, %key, "set risk oracle bound");
+    }
+
+    // A recommendation signed by others is rejected.
+    let mut forged = rec.clone();
+    sign(&mut forged, &SecretKey::parse(&[8; 32])?)?;
+    let err = relayer
+        .apply_risk_oracle_recommendation(store, market_token, forged.to_recommendation()?)
+        .send()
+        .await
+        .expect_err("should throw error when signed by others");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::RiskOracleSignatureVerificationFailed.into())
+    );
+
+    let signature = relayer
+        .apply_risk_oracle_recommendation(store, market_token, rec.to_recommendation()?)
+        .send_without_preflight()
+        .await?;
+    tracing::info!(%signature, "applied risk oracle recommendation");
+
+    let market = keeper.market_by_token(store, market_token).await?;
+    assert_eq!(
+        market.config.get(MarketConfigKey::ReserveFactor).copied(),
+        Some(reserve_factor * unit)
+    );
+    assert_eq!(
+        market
+            .config
+            .get(MarketConfigKey::OpenInterestReserveFactor)
+            .copied(),
+        Some(open_interest_reserve_factor * unit)
+    );
+
+    // Replaying the recommendation is rejected.
+    let err = relayer
+        .apply_risk_oracle_recommendation(store, market_token, rec.to_recommendation()?)
+        .send()
+        .await
+        .expect_err("should throw error when replayed");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::StaleRiskOracleRecommendation.into())
+    );
+
+    Ok(())
+}
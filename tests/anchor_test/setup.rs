@@ -184,6 +184,21 @@ impl Deployment {
         Self::TOKEN_FOR_SWAP_TEST,
     ];
 
+    pub const TOKEN_FOR_CIRCUIT_BREAKER_TEST: &'static str = "cBTC";
+
+    /// Market selector for price circuit breaker test.
+    pub const SELECT_CIRCUIT_BREAKER_MARKET: [&'static str; 3] =
+        [Self::TOKEN_FOR_CIRCUIT_BREAKER_TEST, "fBTC", "USDG"];
+
+    pub const TOKEN_FOR_COMPOSITE_PRICE_TEST: &'static str = "pBTC";
+
+    /// Market selector for composite price test.
+    pub const SELECT_COMPOSITE_PRICE_MARKET: [&'static str; 3] =
+        [Self::TOKEN_FOR_COMPOSITE_PRICE_TEST, "fBTC", "USDG"];
+
+    /// Market selector for trading mode test.
+    pub const SELECT_TRADING_MODE_MARKET: [&'static str; 3] = ["fETH", "fBTC", "USDG"];
+
     const SOL_PYTH_FEED_ID: [u8; 32] = [
         0xef, 0x0d, 0x8b, 0x6f, 0xda, 0x2c, 0xeb, 0xa4, 0x1d, 0xa1, 0x5d, 0x40, 0x95, 0xd1, 0xda,
         0x39, 0x2a, 0x0d, 0x2f, 0x8e, 0xd0, 0xc6, 0xc7, 0xbc, 0x0f, 0x4c, 0xfa, 0xc8, 0xc2, 0x80,
@@ -321,6 +336,26 @@ impl Deployment {
                     max_deviation_factor: Some(MAX_DEVIATION_FACTOR),
                 },
             ),
+            (
+                Self::TOKEN_FOR_CIRCUIT_BREAKER_TEST,
+                TokenConfig {
+                    provider: PriceProviderKind::Pyth,
+                    decimals: 6,
+                    feed_id: Pubkey::new_from_array(Self::BTC_PYTH_FEED_ID),
+                    precision: 3,
+                    max_deviation_factor: Some(MAX_DEVIATION_FACTOR),
+                },
+            ),
+            (
+                Self::TOKEN_FOR_COMPOSITE_PRICE_TEST,
+                TokenConfig {
+                    provider: PriceProviderKind::Pyth,
+                    decimals: 6,
+                    feed_id: Pubkey::new_from_array(Self::BTC_PYTH_FEED_ID),
+                    precision: 3,
+                    max_deviation_factor: Some(MAX_DEVIATION_FACTOR),
+                },
+            ),
             (
                 "fETH",
                 TokenConfig {
@@ -375,6 +410,12 @@ impl Deployment {
             Self::SELECT_SWAP_SOURCE_MARKET,
             // For cross-market swap destination test only
             Self::SELECT_SWAP_TARGET_MARKET,
+            // For price circuit breaker test only
+            Self::SELECT_CIRCUIT_BREAKER_MARKET,
+            // For composite price test only
+            Self::SELECT_COMPOSITE_PRICE_MARKET,
+            // For trading mode test only
+            Self::SELECT_TRADING_MODE_MARKET,
         ])
         .await?;
         self.initialize_glv("fBTC", "USDG").await?;
@@ -570,21 +611,25 @@ impl Deployment {
     }
 
     async fn initialize_gt_incentive(&mut self) -> eyre::Result<()> {
-        use gmsol_gt_incentive as gt_incentive;
+        use gmsol_sdk::ops::gt_incentive::GtIncentiveOps;
 
-        let client = self.user_client(Self::DEFAULT_KEEPER)?;
+        // Only the store admin can initialize the config. The reward budget is set by each test.
+        let (init, config) = self
+            .client
+            .initialize_gt_incentive(&self.store, 0)
+            .swap_output(());
 
-        let init_ix = client
-            .store_transaction()
-            .program(gt_incentive::ID)
-            .anchor_args(gt_incentive::instruction::Initialize {})
-            .anchor_accounts(gt_incentive::accounts::Initialize {
-                authority: client.payer(),
-                system_program: system_program::ID,
-            });
+        let signature = init.send().await?;
+        tracing::info!(%signature, %config, "initialized gt-incentive program");
 
-        let signature = init_ix.send().await?;
-        tracing::info!(%signature, "initialized gt-incentive program");
+        // Grant GT_CONTROLLER role to the GT incentive config PDA,
+        // which is required for minting GT rewards.
+        let signature = self
+            .client
+            .grant_role(&self.store, &config, "GT_CONTROLLER")
+            .send()
+            .await?;
+        tracing::info!(%signature, "granted GT_CONTROLLER role to gt-incentive config");
 
         Ok(())
     }
@@ -1296,7 +1341,9 @@ impl Deployment {
     }
 
     async fn initialize_callback(&mut self) -> eyre::Result<()> {
-        use gmsol_callback::{accounts, instruction, states::CONFIG_SEED, ID};
+        use gmsol_callback::{
+            accounts, instruction, interface::ActionKind, states::CONFIG_SEED, ID,
+        };
         self.callback_program = ID;
         self.callback_shared_data = Pubkey::find_program_address(&[CONFIG_SEED], &ID).0;
 
@@ -1321,6 +1368,15 @@ impl Deployment {
                     system_program: system_program::ID,
                 }),
         )?;
+        let action_kinds = 1 << u8::from(ActionKind::Order);
+        for program_id in [&ID, &gmsol_competition::ID, &gmsol_gt_incentive::ID] {
+            tx.push(
+                client
+                    .register_callback_program(&self.store, program_id, action_kinds, 0)
+                    .swap_output(())
+                    .0,
+            )?;
+        }
         tx.build()?.send_all(false)
             .instrument(tracing::info_span!("initalize callback"))
             .await
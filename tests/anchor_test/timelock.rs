@@ -0,0 +1,405 @@
+use std::sync::Arc;
+
+use gmsol_programs::{
+    constants::roles,
+    gmsol_timelock::accounts::{Executor, TimelockConfig},
+};
+use gmsol_sdk::{
+    client::ops::{RoleOps, StoreOps, TimelockOps},
+    utils::zero_copy::ZeroCopy,
+    Client,
+};
+use gmsol_solana_utils::{signer::SignerRef, transaction_builder::TransactionBuilder};
+use gmsol_store::CoreError;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, NullSigner},
+};
+
+use crate::anchor_test::setup::{current_deployment, Deployment};
+
+/// Initialize a new store governed by the timelock, i.e., its authority is the wallet
+/// of the ADMIN executor. The payer of the client is granted all the timelock roles.
+async fn initialize_timelocked_store(
+    client: &Client<SignerRef>,
+    delay: u32,
+) -> eyre::Result<Pubkey> {
+    let key = thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect::<String>();
+    let store = client.find_store_address(&key);
+    let payer = client.payer();
+
+    client
+        .initialize_store::<Keypair>(&key, None, None, None)
+        .send_without_preflight()
+        .await?;
+    for role in [
+        roles::TIMELOCK_ADMIN,
+        roles::TIMELOCK_KEEPER,
+        roles::TIMELOCKED_ADMIN,
+    ] {
+        client
+            .enable_role(&store, role)
+            .send_without_preflight()
+            .await?;
+        client
+            .grant_role(&store, &payer, role)
+            .send_without_preflight()
+            .await?;
+    }
+
+    let (rpc, executor) = client
+        .initialize_executor(&store, roles::ADMIN)?
+        .swap_output(());
+    rpc.send_without_preflight().await?;
+    let wallet = client.find_executor_wallet_address(&executor);
+    client
+        .transfer_store_authority(&store, &wallet)
+        .send_without_preflight()
+        .await?;
+    client
+        .initialize_timelock_config(&store, delay)
+        .send_without_preflight()
+        .await?;
+
+    Ok(store)
+}
+
+/// Create a client whose payer is the wallet of the `ADMIN` executor, for building
+/// the instructions to be timelocked.
+fn admin_wallet_client(
+    client: &Client<SignerRef>,
+    store: &Pubkey,
+) -> eyre::Result<Client<Arc<NullSigner>>> {
+    let executor = client.find_executor_address(store, roles::ADMIN)?;
+    let wallet = client.find_executor_wallet_address(&executor);
+    Ok(client.try_clone_with_payer(Arc::new(NullSigner::new(&wallet)))?)
+}
+
+fn main_instruction<C>(rpc: TransactionBuilder<'_, C>) -> Instruction {
+    rpc.instructions().pop().expect("must have an instruction")
+}
+
+/// Create, approve and execute the instruction built by the `ADMIN` executor wallet.
+async fn execute_as_timelocked_admin(
+    client: &Client<SignerRef>,
+    store: &Pubkey,
+    build: impl FnOnce(&Client<Arc<NullSigner>>) -> Instruction,
+) -> eyre::Result<()> {
+    let instruction = build(&admin_wallet_client(client, store)?);
+
+    let (rpc, buffer) = client
+        .create_timelocked_instruction(store, roles::ADMIN, Keypair::new(), instruction)?
+        .swap_output(());
+    rpc.send_without_preflight().await?;
+    client
+        .approve_timelocked_instruction(store, &buffer, Some(roles::ADMIN))
+        .await?
+        .send_without_preflight()
+        .await?;
+    client
+        .execute_timelocked_instruction(store, &buffer, None)
+        .await?
+        .send_without_preflight()
+        .await?;
+    Ok(())
+}
+
+/// Create a proposal executed by the `ADMIN` executor.
+async fn create_admin_proposal(
+    client: &Client<SignerRef>,
+    store: &Pubkey,
+    instructions: Vec<Instruction>,
+    depends_on: Option<&Pubkey>,
+) -> eyre::Result<(Pubkey, Vec<Pubkey>)> {
+    let mut buffers = Vec::with_capacity(instructions.len());
+    for instruction in instructions {
+        let (rpc, buffer) = client
+            .create_timelocked_instruction(store, roles::ADMIN, Keypair::new(), instruction)?
+            .swap_output(());
+        rpc.send_without_preflight().await?;
+        buffers.push(buffer);
+    }
+    let (rpc, proposal) = client
+        .create_timelocked_proposal(
+            store,
+            roles::ADMIN,
+            Keypair::new(),
+            buffers.iter().copied(),
+            depends_on,
+        )?
+        .swap_output(());
+    rpc.send_without_preflight().await?;
+    Ok((proposal, buffers))
+}
+
+#[tokio::test]
+async fn timelock_delay_changes() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("timelock_delay_changes");
+    let _enter = span.enter();
+
+    let client = &deployment.client;
+    let store = &initialize_timelocked_store(client, 0).await?;
+    let config = client.find_timelock_config_address(store);
+    let program_id = client.store_program_id();
+    let discriminator = Some([1; 8]);
+    let delay_of = |config: &TimelockConfig| {
+        config
+            .delay_overrides()
+            .find(|o| o.program_id == *program_id && o.discriminator() == discriminator.as_ref())
+            .map(|o| o.delay)
+    };
+
+    // Raising the delay only requires `TIMELOCK_ADMIN`.
+    client
+        .set_timelock_delay_override(store, program_id, discriminator, 3_600)
+        .send_without_preflight()
+        .await?;
+
+    // Lowering the delay requires ADMIN.
+    let err = client
+        .set_timelock_delay_override(store, program_id, discriminator, 60)
+        .send()
+        .await
+        .expect_err("should throw error when lowering the delay without ADMIN");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Removing the override lowers the delay to the default one.
+    let err = client
+        .remove_timelock_delay_override(store, program_id, discriminator)
+        .send()
+        .await
+        .expect_err("should throw error when removing the override without ADMIN");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Lowering the delay by a timelocked instruction.
+    execute_as_timelocked_admin(client, store, |wallet_client| {
+        main_instruction(wallet_client.set_timelock_delay_override(
+            store,
+            program_id,
+            discriminator,
+            60,
+        ))
+    })
+    .await?;
+    let account = client
+        .account::<ZeroCopy<TimelockConfig>>(&config)
+        .await?
+        .expect("must exist")
+        .0;
+    assert_eq!(delay_of(&account), Some(60));
+
+    execute_as_timelocked_admin(client, store, |wallet_client| {
+        main_instruction(wallet_client.remove_timelock_delay_override(
+            store,
+            program_id,
+            discriminator,
+        ))
+    })
+    .await?;
+    let account = client
+        .account::<ZeroCopy<TimelockConfig>>(&config)
+        .await?
+        .expect("must exist")
+        .0;
+    assert_eq!(delay_of(&account), None);
+
+    // The same rules apply to the min delay of executors.
+    let executor = client.find_executor_address(store, roles::ADMIN)?;
+    client
+        .set_executor_min_delay(store, roles::ADMIN, 60)?
+        .send_without_preflight()
+        .await?;
+    let err = client
+        .set_executor_min_delay(store, roles::ADMIN, 0)?
+        .send()
+        .await
+        .expect_err("should throw error when lowering the min delay without ADMIN");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+    let account = client
+        .account::<ZeroCopy<Executor>>(&executor)
+        .await?
+        .expect("must exist")
+        .0;
+    assert_eq!(account.min_delay, 60);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timelock_proposals() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("timelock_proposals");
+    let _enter = span.enter();
+
+    let client = &deployment.client;
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let store = &initialize_timelocked_store(client, 0).await?;
+    let wallet_client = admin_wallet_client(client, store)?;
+    let enable_role = |role: &str| main_instruction(wallet_client.enable_role(store, role));
+    let disable_role = |role: &str| main_instruction(wallet_client.disable_role(store, role));
+
+    // The instructions of a proposal are executed atomically.
+    let (proposal, buffers) = create_admin_proposal(
+        client,
+        store,
+        vec![enable_role("ROLE_A"), enable_role("ROLE_A")],
+        None,
+    )
+    .await?;
+
+    // Instructions of a proposal cannot be approved individually.
+    client
+        .approve_timelocked_instruction(store, &buffers[0], Some(roles::ADMIN))
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when approving an instruction of a proposal");
+
+    // Cannot approve without the timelocked role, nor execute before approval.
+    let err = keeper
+        .approve_timelocked_proposal(store, &proposal, Some(roles::ADMIN))
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when approving without the timelocked role");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+    let err = client
+        .execute_timelocked_proposal(store, &proposal)
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when executing an unapproved proposal");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PreconditionsAreNotMet.into())
+    );
+
+    client
+        .approve_timelocked_proposal(store, &proposal, Some(roles::ADMIN))
+        .await?
+        .send_without_preflight()
+        .await?;
+    let err = client
+        .approve_timelocked_proposal(store, &proposal, Some(roles::ADMIN))
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when approving twice");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PreconditionsAreNotMet.into())
+    );
+
+    // The second instruction fails, so the first one must be reverted.
+    client
+        .execute_timelocked_proposal(store, &proposal)
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when an instruction of the proposal fails");
+
+    // Cancel the failed proposal with its instructions.
+    client
+        .cancel_timelocked_proposal(store, &proposal)
+        .await?
+        .send_without_preflight()
+        .await?;
+    assert!(client.timelock_proposal(&proposal).await?.is_none());
+    for buffer in buffers {
+        assert!(client.instruction_buffer(&buffer).await?.is_none());
+    }
+
+    // Proposals are executed in the order of their dependencies.
+    let (first, _) =
+        create_admin_proposal(client, store, vec![enable_role("ROLE_A")], None).await?;
+    let (second, _) =
+        create_admin_proposal(client, store, vec![disable_role("ROLE_A")], Some(&first)).await?;
+    let account = client.timelock_proposal(&first).await?.expect("must exist");
+    assert_eq!(account.num_dependents, 1);
+    for proposal in [&first, &second] {
+        client
+            .approve_timelocked_proposal(store, proposal, Some(roles::ADMIN))
+            .await?
+            .send_without_preflight()
+            .await?;
+    }
+
+    let err = client
+        .execute_timelocked_proposal(store, &second)
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when the dependency is not executed");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PreconditionsAreNotMet.into())
+    );
+
+    // A proposal with pending dependents can be neither cancelled nor closed.
+    let err = client
+        .cancel_timelocked_proposal(store, &first)
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when cancelling a proposal with dependents");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PreconditionsAreNotMet.into())
+    );
+
+    // The first proposal enables the role, which is then disabled by the second one.
+    client
+        .execute_timelocked_proposal(store, &first)
+        .await?
+        .send_without_preflight()
+        .await?;
+    let err = client
+        .close_timelocked_proposal(store, &first)
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when closing a proposal with dependents");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PreconditionsAreNotMet.into())
+    );
+    client
+        .execute_timelocked_proposal(store, &second)
+        .await?
+        .send_without_preflight()
+        .await?;
+
+    let account = client.timelock_proposal(&first).await?.expect("must exist");
+    assert_eq!(account.num_dependents, 0);
+    for proposal in [&second, &first] {
+        client
+            .close_timelocked_proposal(store, proposal)
+            .await?
+            .send_without_preflight()
+            .await?;
+        assert!(client.timelock_proposal(proposal).await?.is_none());
+    }
+
+    Ok(())
+}
@@ -1,5 +1,13 @@
-use gmsol_programs::gmsol_store::accounts::ReferralCodeV2;
-use gmsol_sdk::client::ops::UserOps;
+use std::time::Duration;
+
+use gmsol_programs::gmsol_store::{
+    accounts::ReferralCodeV2,
+    types::{DelegationScope, UpdateOrderOptions, UpdateOrderParams},
+};
+use gmsol_sdk::{
+    client::ops::{ExchangeOps, UserOps},
+    constants::MARKET_USD_UNIT,
+};
 use gmsol_store::CoreError;
 
 use crate::anchor_test::setup::{current_deployment, Deployment};
@@ -87,3 +95,328 @@ async fn referral() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn user_delegation() -> eyre::Result<()> {
+    let deployment = current_deployment().await?;
+    let _guard = deployment.use_accounts().await?;
+    let span = tracing::info_span!("user_delegation");
+    let _enter = span.enter();
+
+    let owner = deployment.user_client(Deployment::DEFAULT_USER)?;
+    let delegate = deployment.user_client(Deployment::USER_1)?;
+    let keeper = deployment.user_client(Deployment::DEFAULT_KEEPER)?;
+    let store = &deployment.store;
+    let oracle = &deployment.oracle();
+    let fbtc = deployment.token("fBTC").expect("must exist");
+    let owner_address = owner.payer();
+    let delegate_address = delegate.payer();
+
+    let market_token = deployment
+        .prepare_market(["fBTC", "fBTC", "USDG"], 1_000_019, 6_000_000_000_023, true)
+        .await?;
+    let other_market_token = deployment
+        .market_token("fETH", "fBTC", "USDG")
+        .expect("must exist");
+
+    // The owner opens a position.
+    let collateral_amount = 100_000;
+    deployment
+        .mint_or_transfer_to_user("fBTC", Deployment::DEFAULT_USER, collateral_amount)
+        .await?;
+    let size = 5_000 * MARKET_USD_UNIT;
+    let position =
+        owner.find_position_address(store, &owner_address, market_token, &fbtc.address, true)?;
+    let (rpc, order) = owner
+        .market_increase(store, market_token, true, collateral_amount, true, size)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order");
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .await?;
+
+    let max_size_delta_value = size / 2;
+    let delta = size / 10;
+
+    // A delegate cannot create orders without a delegation.
+    let (rpc, _) = delegate
+        .market_increase(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    rpc.send()
+        .await
+        .expect_err("should throw error when there is no delegation");
+
+    // Delegate the market with a max size delta value.
+    let (rpc, delegation) = owner
+        .create_user_delegation(
+            store,
+            &delegate_address,
+            DelegationScope {
+                markets: vec![*market_token],
+                decrease_only: false,
+                max_size_delta_value: Some(max_size_delta_value),
+                expires_at: None,
+            },
+        )?
+        .swap_output(());
+    let signature = rpc.send().await?;
+    tracing::info!(%delegation, %signature, "created a delegation");
+    let delegations = owner
+        .user_delegations(store, Some(&owner_address), Some(&delegate_address))
+        .await?;
+    assert!(delegations.contains_key(&delegation));
+
+    // Markets out of the scope are rejected.
+    let (rpc, _) = delegate
+        .market_increase(store, other_market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let err = rpc
+        .send()
+        .await
+        .expect_err("should throw error when the market is not delegated");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Orders exceeding the max size delta value are rejected.
+    let (rpc, _) = delegate
+        .market_increase(store, market_token, true, 0, true, max_size_delta_value + 1)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let err = rpc
+        .send()
+        .await
+        .expect_err("should throw error when the size exceeds the max size delta value");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // The receiver is always the owner.
+    delegate
+        .market_increase(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .receiver(delegate_address)
+        .build_with_address()
+        .await
+        .expect_err("should throw error when the receiver is not the owner");
+
+    // Orders within the scope are created for the owner.
+    let (rpc, order) = delegate
+        .market_increase(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created an increase position order as a delegate");
+    let header = delegate.order(&order).await?.header;
+    assert_eq!(header.owner, owner_address);
+    assert_eq!(header.receiver, owner_address);
+    assert_eq!(header.creator, delegate_address);
+    let size_before = owner.position(&position).await?.state.size_in_usd;
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .await?;
+    let size_after = owner.position(&position).await?.state.size_in_usd;
+    assert_eq!(size_after, size_before + delta);
+
+    // The delegate can update and close the orders it manages.
+    let price = 50_000 * MARKET_USD_UNIT / 10u128.pow(fbtc.config.decimals as u32);
+    let (rpc, order) = delegate
+        .limit_increase(store, market_token, true, delta, price, true, 0)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a limit order as a delegate");
+    let signature = delegate
+        .update_order_by_delegate(
+            store,
+            &owner_address,
+            market_token,
+            &order,
+            UpdateOrderParams {
+                trigger_price: Some(price / 2),
+                ..Default::default()
+            },
+            UpdateOrderOptions {
+                trailing_distance: None,
+                best_price: None,
+            },
+            None,
+        )
+        .await?
+        .send()
+        .await?;
+    tracing::info!(%order, %signature, "updated the limit order as a delegate");
+    let signature = delegate
+        .close_order(&order)?
+        .by_delegate(true)
+        .build()
+        .await?
+        .send()
+        .await?;
+    tracing::info!(%order, %signature, "closed the limit order as a delegate");
+    assert!(delegate.order(&order).await.is_err());
+
+    // Restrict the delegation to decreasing positions.
+    let signature = owner
+        .update_user_delegation(
+            store,
+            &delegate_address,
+            DelegationScope {
+                markets: vec![*market_token],
+                decrease_only: true,
+                max_size_delta_value: Some(max_size_delta_value),
+                expires_at: None,
+            },
+        )?
+        .send()
+        .await?;
+    tracing::info!(%signature, "restricted the delegation to decrease only");
+
+    let (rpc, _) = delegate
+        .market_increase(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let err = rpc.send().await.expect_err(
+        "should throw error when increasing a position with a decrease-only delegation",
+    );
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Increase orders of the owner are out of the scope of a decrease-only delegation.
+    let (rpc, order) = owner
+        .limit_increase(store, market_token, true, delta, price, true, 0)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a limit order");
+    let err = delegate
+        .update_order_by_delegate(
+            store,
+            &owner_address,
+            market_token,
+            &order,
+            UpdateOrderParams {
+                trigger_price: Some(price / 2),
+                ..Default::default()
+            },
+            UpdateOrderOptions {
+                trailing_distance: None,
+                best_price: None,
+            },
+            None,
+        )
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when updating an order out of the scope");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+    let err = delegate
+        .close_order(&order)?
+        .by_delegate(true)
+        .build()
+        .await?
+        .send()
+        .await
+        .expect_err("should throw error when closing an order out of the scope");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+    let signature = owner.close_order(&order)?.build().await?.send().await?;
+    tracing::info!(%order, %signature, "closed the limit order");
+
+    let (rpc, order) = delegate
+        .market_decrease(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let signature = rpc.send().await?;
+    tracing::info!(%order, %signature, "created a decrease position order as a delegate");
+    let size_before = owner.position(&position).await?.state.size_in_usd;
+    let mut builder = keeper.execute_order(store, oracle, &order, false)?;
+    deployment
+        .execute_with_pyth(&mut builder, None, true, true)
+        .await?;
+    let size_after = owner.position(&position).await?.state.size_in_usd;
+    assert_eq!(size_after, size_before - delta);
+
+    // Expired delegations are rejected.
+    let slot = owner.rpc().get_slot().await?;
+    let now = owner.rpc().get_block_time(slot).await?;
+    let expires_at = now + 3;
+    let signature = owner
+        .update_user_delegation(
+            store,
+            &delegate_address,
+            DelegationScope {
+                markets: vec![],
+                decrease_only: false,
+                max_size_delta_value: None,
+                expires_at: Some(expires_at),
+            },
+        )?
+        .send()
+        .await?;
+    tracing::info!(%signature, %expires_at, "set the delegation to expire");
+    loop {
+        let slot = owner.rpc().get_slot().await?;
+        let now = owner.rpc().get_block_time(slot).await?;
+        if now > expires_at {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs((expires_at - now + 1) as u64)).await;
+    }
+    let (rpc, _) = delegate
+        .market_decrease(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    let err = rpc
+        .send()
+        .await
+        .expect_err("should throw error when the delegation has expired");
+    assert_eq!(
+        gmsol_sdk::Error::from(err).anchor_error_code(),
+        Some(CoreError::PermissionDenied.into())
+    );
+
+    // Revoked delegations can no longer be used.
+    let signature = owner
+        .revoke_user_delegation(store, &delegate_address)?
+        .send()
+        .await?;
+    tracing::info!(%signature, "revoked the delegation");
+    let delegations = owner
+        .user_delegations(store, Some(&owner_address), Some(&delegate_address))
+        .await?;
+    assert!(delegations.is_empty());
+    let (rpc, _) = delegate
+        .market_decrease(store, market_token, true, 0, true, delta)
+        .delegate_of(owner_address)
+        .build_with_address()
+        .await?;
+    rpc.send()
+        .await
+        .expect_err("should throw error when the delegation has been revoked");
+
+    Ok(())
+}